@@ -1,50 +1,137 @@
-use crate::agent::dispatcher::{
-    NativeToolDispatcher, ParsedToolCall, ToolDispatcher, ToolExecutionResult, XmlToolDispatcher,
-};
+use crate::agent::context_builder::{ContextBuilder, MemoryContextBuilder};
+use crate::agent::dispatcher::{self, ToolDispatcher};
+use crate::agent::history::{auto_compact_history, trim_history, ConversationHistory};
+use crate::agent::loop_::{run_tool_call_loop, ToolIterationsExhausted, ToolLoopOptions};
 use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
+use crate::agent::output::Output;
 use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
+use crate::agent::replay::ToolCallReplayStore;
 use crate::config::Config;
+use crate::error::ZeroClawError;
+use crate::memory::categorize::user_message_category;
 use crate::memory::{self, Memory, MemoryCategory};
-use crate::observability::{self, Observer, ObserverEvent};
-use crate::providers::{self, ChatMessage, ChatRequest, ConversationMessage, Provider};
+use crate::observability::{self, Observer};
+use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
 use crate::security::SecurityPolicy;
-use crate::tools::{self, Tool, ToolSpec};
-use crate::util::truncate_with_ellipsis;
+use crate::tools::{self, Tool};
+use crate::util::{refresh_datetime_section, truncate_with_ellipsis, LocalTimezone};
 use anyhow::Result;
+use chrono::Utc;
 use std::io::Write as IoWrite;
 use std::sync::Arc;
-use std::time::Instant;
 
+/// Progress reported while [`Agent::send`] works on a message. The agent
+/// prints nothing itself; embedders subscribe with [`AgentBuilder::on_event`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentEvent {
+    /// Reasoning the model produced before its answer.
+    Thinking(String),
+    /// Prose the model wrote alongside a round of tool calls.
+    Text(String),
+    /// A tool call finished (or was skipped).
+    ToolCall(ToolCallRecord),
+}
+
+/// Callback receiving [`AgentEvent`]s as they happen.
+pub type AgentEventHandler = Arc<dyn Fn(&AgentEvent) + Send + Sync>;
+
+/// One tool call made while answering a message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCallRecord {
+    pub name: String,
+    pub arguments: serde_json::Value,
+    pub output: String,
+    pub success: bool,
+}
+
+/// Estimated usage for one [`Agent::send`] (~4 characters per token).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AgentUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Provider round trips, including the empty-response retry.
+    pub provider_calls: usize,
+}
+
+/// Result of [`Agent::send`]: the final answer plus what it took to get there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentResponse {
+    pub text: String,
+    pub tool_calls: Vec<ToolCallRecord>,
+    pub usage: AgentUsage,
+}
+
+/// Event handler used by the CLI entry points: prose is streamed to stdout
+/// and reasoning goes through the CLI [`Output`].
+pub fn cli_event_handler() -> AgentEventHandler {
+    Arc::new(|event| match event {
+        AgentEvent::Thinking(reasoning) => Output::for_channel("cli", false).thinking(reasoning),
+        AgentEvent::Text(text) => {
+            print!("{text}");
+            let _ = std::io::stdout().flush();
+        }
+        AgentEvent::ToolCall(_) => {}
+    })
+}
+
+/// A conversational agent that can be embedded in other programs.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use zeroclaw::agent::{Agent, AgentEvent};
+/// use zeroclaw::Config;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let config = Config::load_or_init()?;
+/// let mut agent = Agent::from_config(&config)?;
+/// agent.set_event_handler(Arc::new(|event| {
+///     if let AgentEvent::ToolCall(call) = event {
+///         eprintln!("ran {}", call.name);
+///     }
+/// }));
+///
+/// let response = agent.send("What files are in the workspace?").await?;
+/// println!("{}", response.text);
+/// println!("{} tool calls, ~{} output tokens", response.tool_calls.len(), response.usage.output_tokens);
+/// # Ok(())
+/// # }
+/// ```
 pub struct Agent {
     provider: Box<dyn Provider>,
+    provider_name: String,
     tools: Vec<Box<dyn Tool>>,
-    tool_specs: Vec<ToolSpec>,
     memory: Arc<dyn Memory>,
     observer: Arc<dyn Observer>,
     prompt_builder: SystemPromptBuilder,
     tool_dispatcher: Box<dyn ToolDispatcher>,
-    memory_loader: Box<dyn MemoryLoader>,
+    context_builder: Box<dyn ContextBuilder>,
     config: crate::config::AgentConfig,
+    timezone: LocalTimezone,
     model_name: String,
     temperature: f64,
     workspace_dir: std::path::PathBuf,
     identity_config: crate::config::IdentityConfig,
     skills: Vec<crate::skills::Skill>,
     auto_save: bool,
-    history: Vec<ConversationMessage>,
+    infer_memory_categories: bool,
+    history: ConversationHistory,
     classification_config: crate::config::QueryClassificationConfig,
     available_hints: Vec<String>,
+    replay: Option<ToolCallReplayStore>,
+    on_event: Option<AgentEventHandler>,
 }
 
 pub struct AgentBuilder {
     provider: Option<Box<dyn Provider>>,
+    provider_name: Option<String>,
     tools: Option<Vec<Box<dyn Tool>>>,
     memory: Option<Arc<dyn Memory>>,
     observer: Option<Arc<dyn Observer>>,
     prompt_builder: Option<SystemPromptBuilder>,
     tool_dispatcher: Option<Box<dyn ToolDispatcher>>,
     memory_loader: Option<Box<dyn MemoryLoader>>,
+    context_builder: Option<Box<dyn ContextBuilder>>,
     config: Option<crate::config::AgentConfig>,
     model_name: Option<String>,
     temperature: Option<f64>,
@@ -52,20 +139,24 @@ pub struct AgentBuilder {
     identity_config: Option<crate::config::IdentityConfig>,
     skills: Option<Vec<crate::skills::Skill>>,
     auto_save: Option<bool>,
+    infer_memory_categories: Option<bool>,
     classification_config: Option<crate::config::QueryClassificationConfig>,
     available_hints: Option<Vec<String>>,
+    on_event: Option<AgentEventHandler>,
 }
 
 impl AgentBuilder {
     pub fn new() -> Self {
         Self {
             provider: None,
+            provider_name: None,
             tools: None,
             memory: None,
             observer: None,
             prompt_builder: None,
             tool_dispatcher: None,
             memory_loader: None,
+            context_builder: None,
             config: None,
             model_name: None,
             temperature: None,
@@ -73,8 +164,10 @@ impl AgentBuilder {
             identity_config: None,
             skills: None,
             auto_save: None,
+            infer_memory_categories: None,
             classification_config: None,
             available_hints: None,
+            on_event: None,
         }
     }
 
@@ -83,6 +176,12 @@ impl AgentBuilder {
         self
     }
 
+    /// Name reported to the observer for provider calls (`"openrouter"`, ...).
+    pub fn provider_name(mut self, provider_name: String) -> Self {
+        self.provider_name = Some(provider_name);
+        self
+    }
+
     pub fn tools(mut self, tools: Vec<Box<dyn Tool>>) -> Self {
         self.tools = Some(tools);
         self
@@ -108,11 +207,19 @@ impl AgentBuilder {
         self
     }
 
+    /// Memory loader used by the default context builder; ignored when
+    /// [`Self::context_builder`] is set.
     pub fn memory_loader(mut self, memory_loader: Box<dyn MemoryLoader>) -> Self {
         self.memory_loader = Some(memory_loader);
         self
     }
 
+    /// Replace how the context prepended to each user message is built.
+    pub fn context_builder(mut self, context_builder: Box<dyn ContextBuilder>) -> Self {
+        self.context_builder = Some(context_builder);
+        self
+    }
+
     pub fn config(mut self, config: crate::config::AgentConfig) -> Self {
         self.config = Some(config);
         self
@@ -148,6 +255,13 @@ impl AgentBuilder {
         self
     }
 
+    /// File auto-saved preferences and decisions under their own memory
+    /// categories (see [`crate::memory::categorize`]).
+    pub fn infer_memory_categories(mut self, infer: bool) -> Self {
+        self.infer_memory_categories = Some(infer);
+        self
+    }
+
     pub fn classification_config(
         mut self,
         classification_config: crate::config::QueryClassificationConfig,
@@ -161,34 +275,48 @@ impl AgentBuilder {
         self
     }
 
-    pub fn build(self) -> Result<Agent> {
+    /// Receive [`AgentEvent`]s while a message is processed. Without a
+    /// handler the agent is silent.
+    pub fn on_event(mut self, handler: AgentEventHandler) -> Self {
+        self.on_event = Some(handler);
+        self
+    }
+
+    /// Fails with [`ZeroClawError::ConfigError`] when a required part is missing.
+    pub fn build(self) -> Result<Agent, ZeroClawError> {
         let tools = self
             .tools
-            .ok_or_else(|| anyhow::anyhow!("tools are required"))?;
-        let tool_specs = tools.iter().map(|tool| tool.spec()).collect();
+            .ok_or_else(|| missing_part("tools are required"))?;
+        tools::tool_descriptions(&tools).map_err(ZeroClawError::ConfigError)?;
+        let config = self.config.unwrap_or_default();
+        let timezone = LocalTimezone::from_config(config.timezone.as_deref());
 
         Ok(Agent {
             provider: self
                 .provider
-                .ok_or_else(|| anyhow::anyhow!("provider is required"))?,
+                .ok_or_else(|| missing_part("provider is required"))?,
+            provider_name: self.provider_name.unwrap_or_else(|| "custom".into()),
             tools,
-            tool_specs,
             memory: self
                 .memory
-                .ok_or_else(|| anyhow::anyhow!("memory is required"))?,
+                .ok_or_else(|| missing_part("memory is required"))?,
             observer: self
                 .observer
-                .ok_or_else(|| anyhow::anyhow!("observer is required"))?,
+                .ok_or_else(|| missing_part("observer is required"))?,
             prompt_builder: self
                 .prompt_builder
                 .unwrap_or_else(SystemPromptBuilder::with_defaults),
             tool_dispatcher: self
                 .tool_dispatcher
-                .ok_or_else(|| anyhow::anyhow!("tool_dispatcher is required"))?,
-            memory_loader: self
-                .memory_loader
-                .unwrap_or_else(|| Box::new(DefaultMemoryLoader::default())),
-            config: self.config.unwrap_or_default(),
+                .ok_or_else(|| missing_part("tool_dispatcher is required"))?,
+            context_builder: self.context_builder.unwrap_or_else(|| {
+                Box::new(MemoryContextBuilder::new(
+                    self.memory_loader
+                        .unwrap_or_else(|| Box::new(DefaultMemoryLoader::default())),
+                ))
+            }),
+            config,
+            timezone,
             model_name: self
                 .model_name
                 .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into()),
@@ -199,19 +327,36 @@ impl AgentBuilder {
             identity_config: self.identity_config.unwrap_or_default(),
             skills: self.skills.unwrap_or_default(),
             auto_save: self.auto_save.unwrap_or(false),
-            history: Vec::new(),
+            infer_memory_categories: self.infer_memory_categories.unwrap_or(false),
+            history: ConversationHistory::default(),
             classification_config: self.classification_config.unwrap_or_default(),
             available_hints: self.available_hints.unwrap_or_default(),
+            replay: None,
+            on_event: self.on_event,
         })
     }
 }
 
+fn missing_part(message: &'static str) -> ZeroClawError {
+    ZeroClawError::ConfigError(anyhow::anyhow!(message))
+}
+
+/// Classify a failed turn. Besides the iteration limit, everything the tool
+/// loop returns is a failed or empty provider reply.
+fn turn_error(err: anyhow::Error) -> ZeroClawError {
+    if err.is::<ToolIterationsExhausted>() {
+        ZeroClawError::ToolError(err)
+    } else {
+        ZeroClawError::ProviderError(err)
+    }
+}
+
 impl Agent {
     pub fn builder() -> AgentBuilder {
         AgentBuilder::new()
     }
 
-    pub fn history(&self) -> &[ConversationMessage] {
+    pub fn history(&self) -> &[ChatMessage] {
         &self.history
     }
 
@@ -219,22 +364,52 @@ impl Agent {
         self.history.clear();
     }
 
-    pub fn from_config(config: &Config) -> Result<Self> {
+    pub(crate) fn history_mut(&mut self) -> &mut ConversationHistory {
+        &mut self.history
+    }
+
+    pub(crate) fn provider(&self) -> &dyn Provider {
+        self.provider.as_ref()
+    }
+
+    pub(crate) fn tools(&self) -> &[Box<dyn Tool>] {
+        &self.tools
+    }
+
+    pub(crate) fn skills(&self) -> &[crate::skills::Skill] {
+        &self.skills
+    }
+
+    /// Answer matching tool calls from `replay` instead of executing them.
+    pub fn set_replay_store(&mut self, replay: ToolCallReplayStore) {
+        self.replay = Some(replay);
+    }
+
+    /// Replace the event handler set with [`AgentBuilder::on_event`].
+    pub fn set_event_handler(&mut self, handler: AgentEventHandler) {
+        self.on_event = Some(handler);
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, ZeroClawError> {
         let observer: Arc<dyn Observer> =
             Arc::from(observability::create_observer(&config.observability));
-        let runtime: Arc<dyn runtime::RuntimeAdapter> =
-            Arc::from(runtime::create_runtime(&config.runtime)?);
+        let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(
+            runtime::create_runtime(&config.runtime).map_err(ZeroClawError::ConfigError)?,
+        );
         let security = Arc::new(SecurityPolicy::from_config(
             &config.autonomy,
             &config.workspace_dir,
         ));
 
-        let memory: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
+        let (memory, memory_degraded) = memory::create_session_memory(
             &config.memory,
             Some(&config.storage.provider.config),
             &config.workspace_dir,
             config.api_key.as_deref(),
-        )?);
+            LocalTimezone::from_config(config.agent.timezone.as_deref()),
+        )
+        .map_err(ZeroClawError::MemoryError)?;
+        let memory: Arc<dyn Memory> = Arc::from(memory);
 
         let composio_key = if config.composio.enabled {
             config.composio.api_key.as_deref()
@@ -247,7 +422,7 @@ impl Agent {
             None
         };
 
-        let tools = tools::all_tools_with_runtime(
+        let mut tools = tools::all_tools_with_runtime(
             Arc::new(config.clone()),
             &security,
             runtime,
@@ -261,6 +436,11 @@ impl Agent {
             config.api_key.as_deref(),
             config,
         );
+        if memory_degraded {
+            tools.retain(|tool| !tools::is_memory_tool(tool.name()));
+        }
+        let tools =
+            tools::PromptInjectionFilter::wrap_all(tools, config.security.tool_output_injection);
 
         let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
 
@@ -270,28 +450,30 @@ impl Agent {
             .unwrap_or("anthropic/claude-sonnet-4-20250514")
             .to_string();
 
-        let provider: Box<dyn Provider> = providers::create_routed_provider(
+        let provider_options = providers::ProviderRuntimeOptions::default()
+            .with_provider_config(&config.provider)
+            .with_observability_config(&config.observability)
+            .map_err(ZeroClawError::ConfigError)?;
+        let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
             provider_name,
             config.api_key.as_deref(),
             config.api_url.as_deref(),
             &config.reliability,
             &config.model_routes,
             &model_name,
-        )?;
-
-        let dispatcher_choice = config.agent.tool_dispatcher.as_str();
-        let tool_dispatcher: Box<dyn ToolDispatcher> = match dispatcher_choice {
-            "native" => Box::new(NativeToolDispatcher),
-            "xml" => Box::new(XmlToolDispatcher),
-            _ if provider.supports_native_tools() => Box::new(NativeToolDispatcher),
-            _ => Box::new(XmlToolDispatcher),
-        };
+            &provider_options,
+        )
+        .map_err(ZeroClawError::ProviderError)?;
+
+        let tool_dispatcher =
+            dispatcher::from_config(&config.agent.tool_dispatcher, provider.as_ref());
 
         let available_hints: Vec<String> =
             config.model_routes.iter().map(|r| r.hint.clone()).collect();
 
         Agent::builder()
             .provider(provider)
+            .provider_name(provider_name.to_string())
             .tools(tools)
             .memory(memory)
             .observer(observer)
@@ -308,38 +490,15 @@ impl Agent {
             .classification_config(config.query_classification.clone())
             .available_hints(available_hints)
             .identity_config(config.identity.clone())
-            .skills(crate::skills::load_skills(&config.workspace_dir))
+            .skills(crate::skills::load_skills(
+                &config.workspace_dir,
+                &config.skills.dir_paths(),
+            ))
             .auto_save(config.memory.auto_save)
+            .infer_memory_categories(config.memory.infer_categories)
             .build()
     }
 
-    fn trim_history(&mut self) {
-        let max = self.config.max_history_messages;
-        if self.history.len() <= max {
-            return;
-        }
-
-        let mut system_messages = Vec::new();
-        let mut other_messages = Vec::new();
-
-        for msg in self.history.drain(..) {
-            match &msg {
-                ConversationMessage::Chat(chat) if chat.role == "system" => {
-                    system_messages.push(msg);
-                }
-                _ => other_messages.push(msg),
-            }
-        }
-
-        if other_messages.len() > max {
-            let drop_count = other_messages.len() - max;
-            other_messages.drain(0..drop_count);
-        }
-
-        self.history = system_messages;
-        self.history.extend(other_messages);
-    }
-
     fn build_system_prompt(&self) -> Result<String> {
         let instructions = self.tool_dispatcher.prompt_instructions(&self.tools);
         let ctx = PromptContext {
@@ -348,65 +507,13 @@ impl Agent {
             tools: &self.tools,
             skills: &self.skills,
             identity_config: Some(&self.identity_config),
+            agent_config: Some(&self.config),
             dispatcher_instructions: &instructions,
+            timezone: self.timezone,
         };
         self.prompt_builder.build(&ctx)
     }
 
-    async fn execute_tool_call(&self, call: &ParsedToolCall) -> ToolExecutionResult {
-        let start = Instant::now();
-
-        let result = if let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) {
-            match tool.execute(call.arguments.clone()).await {
-                Ok(r) => {
-                    self.observer.record_event(&ObserverEvent::ToolCall {
-                        tool: call.name.clone(),
-                        duration: start.elapsed(),
-                        success: r.success,
-                    });
-                    if r.success {
-                        r.output
-                    } else {
-                        format!("Error: {}", r.error.unwrap_or(r.output))
-                    }
-                }
-                Err(e) => {
-                    self.observer.record_event(&ObserverEvent::ToolCall {
-                        tool: call.name.clone(),
-                        duration: start.elapsed(),
-                        success: false,
-                    });
-                    format!("Error executing {}: {e}", call.name)
-                }
-            }
-        } else {
-            format!("Unknown tool: {}", call.name)
-        };
-
-        ToolExecutionResult {
-            name: call.name.clone(),
-            output: result,
-            success: true,
-            tool_call_id: call.tool_call_id.clone(),
-        }
-    }
-
-    async fn execute_tools(&self, calls: &[ParsedToolCall]) -> Vec<ToolExecutionResult> {
-        if !self.config.parallel_tools {
-            let mut results = Vec::with_capacity(calls.len());
-            for call in calls {
-                results.push(self.execute_tool_call(call).await);
-            }
-            return results;
-        }
-
-        let mut results = Vec::with_capacity(calls.len());
-        for call in calls {
-            results.push(self.execute_tool_call(call).await);
-        }
-        results
-    }
-
     fn classify_model(&self, user_message: &str) -> String {
         if let Some(hint) = super::classifier::classify(&self.classification_config, user_message) {
             if self.available_hints.contains(&hint) {
@@ -417,118 +524,142 @@ impl Agent {
         self.model_name.clone()
     }
 
-    pub async fn turn(&mut self, user_message: &str) -> Result<String> {
+    /// Process one user message and return the final answer text.
+    pub async fn turn(&mut self, user_message: &str) -> Result<String, ZeroClawError> {
+        self.send(user_message).await.map(|response| response.text)
+    }
+
+    /// Process one user message, running tools as the model requests them,
+    /// and return the answer together with the tool calls made and usage.
+    pub async fn send(&mut self, user_message: &str) -> Result<AgentResponse, ZeroClawError> {
         if self.history.is_empty() {
-            let system_prompt = self.build_system_prompt()?;
-            self.history
-                .push(ConversationMessage::Chat(ChatMessage::system(
-                    system_prompt,
-                )));
+            let system_prompt = self
+                .build_system_prompt()
+                .map_err(ZeroClawError::ConfigError)?;
+            self.history.push(ChatMessage::system(system_prompt));
+        } else if let Some(system) = self.history.first_mut() {
+            if system.role == "system" {
+                refresh_datetime_section(&mut system.content, &self.timezone, Utc::now());
+            }
         }
 
         if self.auto_save {
             let _ = self
                 .memory
-                .store("user_msg", user_message, MemoryCategory::Conversation, None)
+                .store(
+                    "user_msg",
+                    user_message,
+                    user_message_category(user_message, self.infer_memory_categories),
+                    None,
+                )
                 .await;
         }
 
-        let context = self
-            .memory_loader
-            .load_context(self.memory.as_ref(), user_message)
+        let enriched = self
+            .context_builder
+            .build(user_message, self.memory.as_ref())
             .await
-            .unwrap_or_default();
-
-        let enriched = if context.is_empty() {
-            user_message.to_string()
-        } else {
-            format!("{context}{user_message}")
-        };
-
-        self.history
-            .push(ConversationMessage::Chat(ChatMessage::user(enriched)));
+            .enrich(user_message);
+        self.history.push(ChatMessage::user(enriched));
 
         let effective_model = self.classify_model(user_message);
-
-        for _ in 0..self.config.max_tool_iterations {
-            let messages = self.tool_dispatcher.to_provider_messages(&self.history);
-            let response = match self
-                .provider
-                .chat(
-                    ChatRequest {
-                        messages: &messages,
-                        tools: if self.tool_dispatcher.should_send_tool_specs() {
-                            Some(&self.tool_specs)
-                        } else {
-                            None
-                        },
-                    },
-                    &effective_model,
-                    self.temperature,
-                )
-                .await
-            {
-                Ok(resp) => resp,
-                Err(err) => return Err(err),
-            };
-
-            let (text, calls) = self.tool_dispatcher.parse_response(&response);
-            if calls.is_empty() {
-                let final_text = if text.is_empty() {
-                    response.text.unwrap_or_default()
-                } else {
-                    text
-                };
-
-                self.history
-                    .push(ConversationMessage::Chat(ChatMessage::assistant(
-                        final_text.clone(),
-                    )));
-                self.trim_history();
-
-                if self.auto_save {
-                    let summary = truncate_with_ellipsis(&final_text, 100);
-                    let _ = self
-                        .memory
-                        .store("assistant_resp", &summary, MemoryCategory::Daily, None)
-                        .await;
-                }
-
-                return Ok(final_text);
-            }
-
-            if !text.is_empty() {
-                self.history
-                    .push(ConversationMessage::Chat(ChatMessage::assistant(
-                        text.clone(),
-                    )));
-                print!("{text}");
-                let _ = std::io::stdout().flush();
-            }
-
-            self.history.push(ConversationMessage::AssistantToolCalls {
-                text: response.text.clone(),
-                tool_calls: response.tool_calls.clone(),
+        let temperature = super::classifier::AutoTemperature::from_config(&self.config)
+            .map_or(self.temperature, |auto| {
+                auto.select(self.temperature, user_message)
             });
+        let mut tool_calls = Vec::new();
+        let mut usage = AgentUsage::default();
+        let provider_name = self.provider_name.clone();
+        let options = ToolLoopOptions {
+            max_tool_iterations: self.config.max_tool_iterations,
+            max_tool_calls_per_turn: self.config.max_tool_calls_per_turn,
+            normalize_output: self.config.normalize_output,
+            early_tool_dispatch: self.config.early_tool_dispatch,
+            tool_retry: tools::ToolRetryPolicy::from_config(&self.config),
+            tool_records: Some(&mut tool_calls),
+            usage: Some(&mut usage),
+            ..ToolLoopOptions::default()
+        };
+
+        let result = self
+            .run_turn(&provider_name, &effective_model, temperature, options)
+            .await;
+        trim_history(&mut self.history, self.config.max_history_messages);
+        let text = result.map_err(turn_error)?;
 
-            let results = self.execute_tools(&calls).await;
-            let formatted = self.tool_dispatcher.format_results(&results);
-            self.history.push(formatted);
-            self.trim_history();
+        if self.auto_save {
+            let summary = truncate_with_ellipsis(&text, 100);
+            let _ = self
+                .memory
+                .store("assistant_resp", &summary, MemoryCategory::Daily, None)
+                .await;
         }
 
-        anyhow::bail!(
-            "Agent exceeded maximum tool iterations ({})",
-            self.config.max_tool_iterations
+        Ok(AgentResponse {
+            text,
+            tool_calls,
+            usage,
+        })
+    }
+
+    /// Run the tool loop on the message at the end of the history. The
+    /// agent adds its dispatcher's tool-call format, replay store and event
+    /// handler to `options`; `zeroclaw agent` passes everything else.
+    pub(crate) async fn run_turn(
+        &mut self,
+        provider_name: &str,
+        model: &str,
+        temperature: f64,
+        options: ToolLoopOptions<'_>,
+    ) -> Result<String> {
+        let options = ToolLoopOptions {
+            prompt_guided_tools: options.prompt_guided_tools
+                || !self.tool_dispatcher.should_send_tool_specs(),
+            replay: options.replay.or(self.replay.as_ref()),
+            on_event: options.on_event.or(self.on_event.as_ref()),
+            ..options
+        };
+        run_tool_call_loop(
+            self.provider.as_ref(),
+            &mut self.history,
+            &self.tools,
+            self.observer.as_ref(),
+            provider_name,
+            model,
+            temperature,
+            options,
         )
+        .await
+    }
+
+    /// Summarize older messages once the history outgrows `max_history`.
+    /// Returns whether anything was compacted.
+    pub(crate) async fn compact_history(
+        &mut self,
+        model: &str,
+        max_history: usize,
+    ) -> Result<bool> {
+        auto_compact_history(
+            &mut self.history,
+            self.provider.as_ref(),
+            model,
+            max_history,
+        )
+        .await
     }
 
-    pub async fn run_single(&mut self, message: &str) -> Result<String> {
+    pub async fn run_single(&mut self, message: &str) -> Result<String, ZeroClawError> {
         self.turn(message).await
     }
 
-    pub async fn run_interactive(&mut self) -> Result<()> {
-        println!("🦀 ZeroClaw Interactive Mode");
+    pub async fn run_interactive(&mut self) -> Result<(), ZeroClawError> {
+        if self.on_event.is_none() {
+            self.on_event = Some(cli_event_handler());
+        }
+        println!(
+            "{}",
+            crate::i18n::tf("repl.banner", &[("name", &self.config.assistant_name())])
+        );
         println!("Type /quit to exit.\n");
 
         let (tx, mut rx) = tokio::sync::mpsc::channel(32);
@@ -554,63 +685,11 @@ impl Agent {
     }
 }
 
-pub async fn run(
-    config: Config,
-    message: Option<String>,
-    provider_override: Option<String>,
-    model_override: Option<String>,
-    temperature: f64,
-) -> Result<()> {
-    let start = Instant::now();
-
-    let mut effective_config = config;
-    if let Some(p) = provider_override {
-        effective_config.default_provider = Some(p);
-    }
-    if let Some(m) = model_override {
-        effective_config.default_model = Some(m);
-    }
-    effective_config.default_temperature = temperature;
-
-    let mut agent = Agent::from_config(&effective_config)?;
-
-    let provider_name = effective_config
-        .default_provider
-        .as_deref()
-        .unwrap_or("openrouter")
-        .to_string();
-    let model_name = effective_config
-        .default_model
-        .as_deref()
-        .unwrap_or("anthropic/claude-sonnet-4-20250514")
-        .to_string();
-
-    agent.observer.record_event(&ObserverEvent::AgentStart {
-        provider: provider_name.clone(),
-        model: model_name.clone(),
-    });
-
-    if let Some(msg) = message {
-        let response = agent.run_single(&msg).await?;
-        println!("{response}");
-    } else {
-        agent.run_interactive().await?;
-    }
-
-    agent.observer.record_event(&ObserverEvent::AgentEnd {
-        provider: provider_name,
-        model: model_name,
-        duration: start.elapsed(),
-        tokens_used: None,
-        cost_usd: None,
-    });
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agent::dispatcher::{NativeToolDispatcher, XmlToolDispatcher};
+    use crate::providers::ChatRequest;
     use async_trait::async_trait;
     use parking_lot::Mutex;
 
@@ -641,6 +720,8 @@ mod tests {
                 return Ok(crate::providers::ChatResponse {
                     text: Some("done".into()),
                     tool_calls: vec![],
+                    reasoning: None,
+                    finish_reason: None,
                 });
             }
             Ok(guard.remove(0))
@@ -668,6 +749,7 @@ mod tests {
                 success: true,
                 output: "tool-out".into(),
                 error: None,
+                artifacts: Vec::new(),
             })
         }
     }
@@ -678,6 +760,8 @@ mod tests {
             responses: Mutex::new(vec![crate::providers::ChatResponse {
                 text: Some("hello".into()),
                 tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
             }]),
         });
 
@@ -715,10 +799,14 @@ mod tests {
                         name: "echo".into(),
                         arguments: "{}".into(),
                     }],
+                    reasoning: None,
+                    finish_reason: None,
                 },
                 crate::providers::ChatResponse {
                     text: Some("done".into()),
                     tool_calls: vec![],
+                    reasoning: None,
+                    finish_reason: None,
                 },
             ]),
         });
@@ -744,9 +832,64 @@ mod tests {
 
         let response = agent.turn("hi").await.unwrap();
         assert_eq!(response, "done");
-        assert!(agent
+        assert!(agent.history().iter().any(|msg| msg.role == "tool"));
+    }
+
+    #[tokio::test]
+    async fn replayed_tool_results_skip_execution() {
+        let provider = Box::new(MockProvider {
+            responses: Mutex::new(vec![crate::providers::ChatResponse {
+                text: Some(String::new()),
+                tool_calls: vec![crate::providers::ToolCall {
+                    id: "tc1".into(),
+                    name: "echo".into(),
+                    arguments: r#"{"text":"hi"}"#.into(),
+                }],
+                reasoning: None,
+                finish_reason: None,
+            }]),
+        });
+
+        let memory_cfg = crate::config::MemoryConfig {
+            backend: "none".into(),
+            ..crate::config::MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None).unwrap(),
+        );
+
+        let mut replay = ToolCallReplayStore::new();
+        replay.record(
+            "echo",
+            serde_json::json!({"$.text": "hi"}),
+            crate::tools::ToolResult {
+                success: true,
+                output: "replayed-out".into(),
+                error: None,
+                artifacts: Vec::new(),
+            },
+        );
+
+        let observer: Arc<dyn Observer> = Arc::from(crate::observability::NoopObserver {});
+        let mut agent = Agent::builder()
+            .provider(provider)
+            .tools(vec![Box::new(MockTool)])
+            .memory(mem)
+            .observer(observer)
+            .tool_dispatcher(Box::new(NativeToolDispatcher))
+            .workspace_dir(std::path::PathBuf::from("/tmp"))
+            .build()
+            .unwrap();
+        agent.set_replay_store(replay);
+
+        agent.turn("hi").await.unwrap();
+        let results: Vec<serde_json::Value> = agent
             .history()
             .iter()
-            .any(|msg| matches!(msg, ConversationMessage::ToolResults(_))));
+            .filter(|msg| msg.role == "tool")
+            .map(|msg| serde_json::from_str(&msg.content).unwrap())
+            .collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["content"], "replayed-out");
     }
 }
@@ -0,0 +1,131 @@
+//! Files and images produced by tools, turned into something the model can
+//! read: inline image markers for vision models, saved files referenced by
+//! path for everyone else.
+
+use crate::tools::{Artifact, ArtifactData};
+use anyhow::Result;
+use std::path::Path;
+use uuid::Uuid;
+
+/// How a tool artifact is surfaced to the model.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ArtifactRendering {
+    /// Inline `[IMAGE:data:...]` marker attached to the next user message.
+    Attachment(String),
+    /// Text note pointing at the artifact's file on disk.
+    Reference(String),
+}
+
+/// Directory artifacts are saved to for a text-only model: `configured`
+/// when set, else a temporary directory created on first use and removed
+/// with `fallback` at the end of the turn.
+pub(crate) fn artifact_dir_for_turn<'d>(
+    configured: Option<&'d Path>,
+    fallback: &'d mut Option<tempfile::TempDir>,
+) -> Result<&'d Path> {
+    if let Some(dir) = configured {
+        return Ok(dir);
+    }
+    if fallback.is_none() {
+        *fallback = Some(
+            tempfile::Builder::new()
+                .prefix("zeroclaw-artifacts-")
+                .tempdir()?,
+        );
+    }
+    Ok(fallback.as_ref().expect("created above").path())
+}
+
+/// Attach images inline for vision-capable providers; otherwise make sure the
+/// artifact exists on disk and reference it by path.
+pub(crate) fn render_artifact(
+    artifact: &Artifact,
+    vision: bool,
+    dir: &Path,
+) -> Result<ArtifactRendering> {
+    use base64::Engine;
+
+    if vision && artifact.is_image() {
+        let encoded = match &artifact.data {
+            ArtifactData::Base64(data) => data.clone(),
+            ArtifactData::Path(path) => {
+                base64::engine::general_purpose::STANDARD.encode(std::fs::read(path)?)
+            }
+        };
+        return Ok(ArtifactRendering::Attachment(format!(
+            "[IMAGE:data:{};base64,{encoded}]",
+            artifact.mime_type
+        )));
+    }
+
+    let path = match &artifact.data {
+        ArtifactData::Path(path) => path.clone(),
+        ArtifactData::Base64(data) => {
+            let bytes = base64::engine::general_purpose::STANDARD.decode(data)?;
+            std::fs::create_dir_all(dir)?;
+            let path = dir.join(format!(
+                "{}.{}",
+                Uuid::new_v4(),
+                artifact_extension(&artifact.mime_type)
+            ));
+            std::fs::write(&path, bytes)?;
+            path
+        }
+    };
+    Ok(ArtifactRendering::Reference(format!(
+        "[Artifact saved: {} ({})]",
+        path.display(),
+        artifact.mime_type
+    )))
+}
+
+/// File extension for a MIME type (`image/png` → `png`, `image/svg+xml` → `svg`).
+fn artifact_extension(mime_type: &str) -> String {
+    let subtype = mime_type.rsplit('/').next().unwrap_or_default();
+    let ext: String = subtype
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect();
+    if ext.is_empty() {
+        "bin".into()
+    } else {
+        ext
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn render_artifact_reads_path_for_vision_and_keeps_path_otherwise() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("shot.png");
+        std::fs::write(&file, b"hello").unwrap();
+        let artifact = Artifact {
+            mime_type: "image/png".into(),
+            data: ArtifactData::Path(file.clone()),
+        };
+
+        assert_eq!(
+            render_artifact(&artifact, true, tmp.path()).unwrap(),
+            ArtifactRendering::Attachment("[IMAGE:data:image/png;base64,aGVsbG8=]".into())
+        );
+        assert_eq!(
+            render_artifact(&artifact, false, tmp.path()).unwrap(),
+            ArtifactRendering::Reference(format!(
+                "[Artifact saved: {} (image/png)]",
+                file.display()
+            ))
+        );
+    }
+
+    #[test]
+    fn artifact_extension_from_mime_type() {
+        assert_eq!(artifact_extension("image/png"), "png");
+        assert_eq!(artifact_extension("image/svg+xml"), "svg");
+        assert_eq!(artifact_extension("application/pdf"), "pdf");
+        assert_eq!(artifact_extension(""), "bin");
+    }
+}
@@ -0,0 +1,592 @@
+//! `zeroclaw batch`: run one prompt template over many inputs.
+//!
+//! Inputs are either a JSONL file (one object per line, `{{field}}`
+//! placeholders are filled from the record) or a file glob (`{{content}}`,
+//! `{{path}}` and `{{name}}` are filled from each file). Every item is answered
+//! by the single-shot agent path from a fresh history; items run concurrently
+//! and share one provider, so retries, key rotation and the optional request
+//! rate limit apply to the batch as a whole.
+//!
+//! Results are appended to the output file as one JSON object per line. Items
+//! whose id is already present there are skipped, so an interrupted batch can
+//! be resumed by running the same command again.
+
+use crate::agent::loop_::SingleShot;
+use crate::agent::turn_summary::{ToolUsage, TurnSummary};
+use crate::config::Config;
+use crate::providers::traits::{ProviderCapabilities, ToolsPayload};
+use crate::providers::{ChatMessage, ChatRequest, ChatResponse, Provider};
+use crate::tools::ToolSpec;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Width of the stderr progress bar, in cells.
+const PROGRESS_BAR_WIDTH: usize = 30;
+
+/// Rough characters-per-token ratio used to estimate usage; providers do not
+/// report token counts to the agent loop.
+const CHARS_PER_TOKEN: u64 = 4;
+
+/// Options for [`run`], mirroring the `zeroclaw batch` flags.
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Prompt template file.
+    pub template: PathBuf,
+    /// JSONL file or file glob.
+    pub input: String,
+    /// Maximum items in flight at once.
+    pub concurrency: usize,
+    /// JSONL results file (appended to).
+    pub output: PathBuf,
+    /// Provider requests allowed per minute across the whole batch; 0 = unlimited.
+    pub requests_per_minute: u32,
+}
+
+/// One unit of work.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchItem {
+    pub id: String,
+    pub source: ItemSource,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemSource {
+    /// A JSONL record; placeholders name its fields.
+    Record(Map<String, Value>),
+    /// A file matched by the input glob; read when the item runs.
+    File(PathBuf),
+}
+
+/// Estimated token usage for one item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchTokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// One line of the results file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub id: String,
+    pub response: Option<String>,
+    /// Tool name → invocation counts.
+    #[serde(default)]
+    pub tool_calls: BTreeMap<String, ToolUsage>,
+    #[serde(default)]
+    pub token_usage: BatchTokenUsage,
+    pub error: Option<String>,
+}
+
+impl BatchItem {
+    /// Render `template` for this item.
+    pub fn render(&self, template: &str) -> Result<String> {
+        match &self.source {
+            ItemSource::Record(fields) => render_template(template, fields),
+            ItemSource::File(path) => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let mut fields = Map::new();
+                fields.insert("content".into(), Value::String(content));
+                fields.insert("path".into(), Value::String(path.display().to_string()));
+                fields.insert(
+                    "name".into(),
+                    Value::String(
+                        path.file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                    ),
+                );
+                render_template(template, &fields)
+            }
+        }
+    }
+}
+
+/// Replace every `{{field}}` with the matching value. Strings are inserted
+/// as-is, other values as JSON; dotted names (`{{user.login}}`) reach into
+/// nested objects. Unknown fields are an error rather than an empty string.
+pub fn render_template(template: &str, fields: &Map<String, Value>) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            anyhow::bail!("Unclosed '{{{{' in template");
+        };
+        let name = after[..end].trim();
+        let mut parts = name.split('.');
+        let value = parts
+            .next()
+            .and_then(|first| fields.get(first))
+            .and_then(|root| parts.try_fold(root, |v, key| v.get(key)))
+            .ok_or_else(|| anyhow::anyhow!("Template field '{name}' is missing from the input"))?;
+        match value {
+            Value::String(s) => out.push_str(s),
+            other => out.push_str(&other.to_string()),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Load batch items from a JSONL file or a file glob.
+///
+/// JSONL records take their id from an `id` field (string or number) and
+/// fall back to the 1-based line number; file items use their path.
+pub fn load_inputs(input: &str) -> Result<Vec<BatchItem>> {
+    let path = Path::new(input);
+    if path.is_file() && path.extension().is_some_and(|ext| ext == "jsonl") {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        return parse_jsonl(&raw).with_context(|| format!("Invalid input {}", path.display()));
+    }
+
+    let mut items = Vec::new();
+    for entry in glob::glob(input).with_context(|| format!("Invalid input glob '{input}'"))? {
+        let file = entry?;
+        if file.is_file() {
+            items.push(BatchItem {
+                id: file.display().to_string(),
+                source: ItemSource::File(file),
+            });
+        }
+    }
+    if items.is_empty() {
+        anyhow::bail!("Input '{input}' matched no files");
+    }
+    Ok(items)
+}
+
+fn parse_jsonl(raw: &str) -> Result<Vec<BatchItem>> {
+    let mut items = Vec::new();
+    let mut seen = HashSet::new();
+    for (index, line) in raw.lines().enumerate() {
+        let line_no = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = match serde_json::from_str::<Value>(line)
+            .with_context(|| format!("Line {line_no} is not valid JSON"))?
+        {
+            Value::Object(fields) => fields,
+            _ => anyhow::bail!("Line {line_no} is not a JSON object"),
+        };
+        let id = match fields.get("id") {
+            Some(Value::String(id)) => id.clone(),
+            Some(Value::Number(id)) => id.to_string(),
+            _ => line_no.to_string(),
+        };
+        if !seen.insert(id.clone()) {
+            anyhow::bail!("Duplicate id '{id}' on line {line_no}");
+        }
+        items.push(BatchItem {
+            id,
+            source: ItemSource::Record(fields),
+        });
+    }
+    Ok(items)
+}
+
+/// Ids already recorded in an existing results file. Unparseable lines (e.g.
+/// a line cut short when a previous run was killed) are ignored.
+pub fn completed_ids(output: &Path) -> Result<HashSet<String>> {
+    let raw = match std::fs::read_to_string(output) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", output.display()));
+        }
+    };
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str::<BatchResult>(line).ok())
+        .map(|result| result.id)
+        .collect())
+}
+
+/// Spaces provider requests evenly across all concurrent items.
+struct RateLimiter {
+    interval: Duration,
+    next_slot: tokio::sync::Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_minute: u32) -> Self {
+        let interval = if requests_per_minute == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(60) / requests_per_minute
+        };
+        Self {
+            interval,
+            next_slot: tokio::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        if self.interval.is_zero() {
+            return;
+        }
+        let wait_until = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(tokio::time::Instant::from_std(wait_until)).await;
+    }
+}
+
+/// Per-item view of the shared provider: waits on the batch rate limiter
+/// before each request and tallies estimated token usage for the item.
+struct BatchProvider<'a> {
+    inner: &'a dyn Provider,
+    limiter: &'a RateLimiter,
+    input_chars: AtomicU64,
+    output_chars: AtomicU64,
+}
+
+impl<'a> BatchProvider<'a> {
+    fn new(inner: &'a dyn Provider, limiter: &'a RateLimiter) -> Self {
+        Self {
+            inner,
+            limiter,
+            input_chars: AtomicU64::new(0),
+            output_chars: AtomicU64::new(0),
+        }
+    }
+
+    async fn before_request(&self, messages: &[ChatMessage]) {
+        self.limiter.acquire().await;
+        let chars: usize = messages.iter().map(|m| m.content.chars().count()).sum();
+        self.input_chars.fetch_add(chars as u64, Ordering::Relaxed);
+    }
+
+    fn after_response(&self, text: &str) {
+        self.output_chars
+            .fetch_add(text.chars().count() as u64, Ordering::Relaxed);
+    }
+
+    fn after_chat_response(&self, response: &ChatResponse) {
+        self.after_response(response.text.as_deref().unwrap_or(""));
+        for call in &response.tool_calls {
+            self.after_response(&call.arguments);
+        }
+    }
+
+    fn usage(&self) -> BatchTokenUsage {
+        BatchTokenUsage {
+            input_tokens: self
+                .input_chars
+                .load(Ordering::Relaxed)
+                .div_ceil(CHARS_PER_TOKEN),
+            output_tokens: self
+                .output_chars
+                .load(Ordering::Relaxed)
+                .div_ceil(CHARS_PER_TOKEN),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for BatchProvider<'_> {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        let mut messages = Vec::new();
+        if let Some(system_prompt) = system_prompt {
+            messages.push(ChatMessage::system(system_prompt));
+        }
+        messages.push(ChatMessage::user(message));
+        self.before_request(&messages).await;
+        let text = self
+            .inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await?;
+        self.after_response(&text);
+        Ok(text)
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        self.before_request(messages).await;
+        let text = self
+            .inner
+            .chat_with_history(messages, model, temperature)
+            .await?;
+        self.after_response(&text);
+        Ok(text)
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        self.before_request(request.messages).await;
+        let response = self.inner.chat(request, model, temperature).await?;
+        self.after_chat_response(&response);
+        Ok(response)
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[Value],
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        self.before_request(messages).await;
+        let response = self
+            .inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await?;
+        self.after_chat_response(&response);
+        Ok(response)
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+}
+
+/// Render one progress line, e.g. `[######......] 12/40 (1 failed)`.
+pub fn render_progress(done: usize, total: usize, failed: usize) -> String {
+    let filled = if total == 0 {
+        PROGRESS_BAR_WIDTH
+    } else {
+        done.min(total) * PROGRESS_BAR_WIDTH / total
+    };
+    let line = format!(
+        "[{}{}] {done}/{total}",
+        "#".repeat(filled),
+        ".".repeat(PROGRESS_BAR_WIDTH - filled)
+    );
+    if failed > 0 {
+        format!("{line} ({failed} failed)")
+    } else {
+        line
+    }
+}
+
+async fn run_item(
+    agent: &SingleShot,
+    provider: &dyn Provider,
+    limiter: &RateLimiter,
+    template: &str,
+    item: BatchItem,
+) -> BatchResult {
+    let metered = BatchProvider::new(provider, limiter);
+    let mut summary = TurnSummary::default();
+    let outcome = match item.render(template) {
+        Ok(prompt) => agent.run_with(&metered, &prompt, Some(&mut summary)).await,
+        Err(e) => Err(e),
+    };
+    let (response, error) = match outcome {
+        Ok(response) => (Some(response), None),
+        Err(e) => (None, Some(format!("{e:#}"))),
+    };
+    BatchResult {
+        id: item.id,
+        response,
+        tool_calls: summary.tools,
+        token_usage: metered.usage(),
+        error,
+    }
+}
+
+/// `zeroclaw batch`: run every pending input through the agent and append
+/// one result line per item to the output file.
+pub async fn run(config: Config, options: BatchOptions) -> Result<()> {
+    let template = std::fs::read_to_string(&options.template)
+        .with_context(|| format!("Failed to read template {}", options.template.display()))?;
+    let items = load_inputs(&options.input)?;
+    let input_count = items.len();
+    let done = completed_ids(&options.output)?;
+    let pending: Vec<BatchItem> = items
+        .into_iter()
+        .filter(|item| !done.contains(&item.id))
+        .collect();
+    let skipped = input_count - pending.len();
+    if pending.is_empty() {
+        eprintln!(
+            "All inputs already have results in {}",
+            options.output.display()
+        );
+        return Ok(());
+    }
+
+    let agent = SingleShot::new(config).await?;
+    let provider = agent.provider();
+    let limiter = RateLimiter::new(options.requests_per_minute);
+    let mut output = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&options.output)
+        .with_context(|| format!("Failed to open {}", options.output.display()))?;
+
+    let total = pending.len();
+    if skipped > 0 {
+        eprintln!(
+            "Skipping {skipped} inputs already in {}",
+            options.output.display()
+        );
+    }
+    let (mut finished, mut failed) = (0, 0);
+    eprint!("\r{}", render_progress(0, total, 0));
+
+    let mut results = futures::stream::iter(pending)
+        .map(|item| run_item(&agent, provider.as_ref(), &limiter, &template, item))
+        .buffer_unordered(options.concurrency.max(1));
+    while let Some(result) = results.next().await {
+        finished += 1;
+        if result.error.is_some() {
+            failed += 1;
+        }
+        writeln!(output, "{}", serde_json::to_string(&result)?)?;
+        output.flush()?;
+        eprint!("\r{}", render_progress(finished, total, failed));
+    }
+    eprintln!();
+    eprintln!(
+        "Wrote {} results to {} ({failed} failed)",
+        finished,
+        options.output.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn render_template_substitutes_fields() {
+        let record = fields(json!({
+            "title": "Crash on start",
+            "number": 42,
+            "user": {"login": "octo"}
+        }));
+        assert_eq!(
+            render_template("#{{number}} {{ title }} by {{user.login}}", &record).unwrap(),
+            "#42 Crash on start by octo"
+        );
+        assert!(render_template("{{missing}}", &record).is_err());
+        assert!(render_template("{{title", &record).is_err());
+        assert_eq!(render_template("no fields", &record).unwrap(), "no fields");
+    }
+
+    #[test]
+    fn parse_jsonl_uses_id_field_or_line_number() {
+        let items = parse_jsonl("{\"id\": \"a\", \"x\": 1}\n\n{\"x\": 2}\n{\"id\": 7}\n").unwrap();
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["a", "3", "7"]);
+
+        assert!(parse_jsonl("[1, 2]").is_err());
+        assert!(parse_jsonl("{\"id\": \"a\"}\n{\"id\": \"a\"}").is_err());
+    }
+
+    #[test]
+    fn load_inputs_reads_file_globs() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("a.md"), "alpha").unwrap();
+        std::fs::write(tmp.path().join("b.md"), "beta").unwrap();
+        std::fs::write(tmp.path().join("c.txt"), "skip").unwrap();
+
+        let pattern = format!("{}/*.md", tmp.path().display());
+        let items = load_inputs(&pattern).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items[1].render("{{name}}: {{content}}").unwrap(),
+            "b.md: beta"
+        );
+
+        let none = format!("{}/*.rs", tmp.path().display());
+        assert!(load_inputs(&none).is_err());
+    }
+
+    #[test]
+    fn completed_ids_skips_truncated_lines() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("results.jsonl");
+        assert!(completed_ids(&path).unwrap().is_empty());
+
+        std::fs::write(
+            &path,
+            "{\"id\":\"a\",\"response\":\"ok\",\"error\":null}\n\
+             {\"id\":\"b\",\"response\":null,\"error\":\"boom\"}\n\
+             {\"id\":\"c\",\"resp",
+        )
+        .unwrap();
+        let ids = completed_ids(&path).unwrap();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains("a") && ids.contains("b"));
+    }
+
+    #[test]
+    fn render_progress_fills_bar_proportionally() {
+        assert_eq!(
+            render_progress(0, 3, 0),
+            format!("[{}] 0/3", ".".repeat(PROGRESS_BAR_WIDTH))
+        );
+        let half = render_progress(5, 10, 1);
+        assert!(half.starts_with(&format!("[{}.", "#".repeat(PROGRESS_BAR_WIDTH / 2))));
+        assert!(half.ends_with("5/10 (1 failed)"));
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_spaces_requests() {
+        let limiter = RateLimiter::new(600);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        let unlimited = RateLimiter::new(0);
+        let start = Instant::now();
+        for _ in 0..100 {
+            unlimited.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
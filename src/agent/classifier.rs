@@ -1,4 +1,4 @@
-use crate::config::schema::QueryClassificationConfig;
+use crate::config::schema::{AgentConfig, QueryClassificationConfig, TemperatureMode};
 
 /// Classify a user message against the configured rules and return the
 /// matching hint string, if any.
@@ -47,6 +47,96 @@ pub fn classify(config: &QueryClassificationConfig, message: &str) -> Option<Str
     None
 }
 
+/// Words that mark a request as precise work (code, math, data).
+const PRECISE_WORDS: &[&str] = &[
+    "algorithm",
+    "calculate",
+    "code",
+    "compile",
+    "compute",
+    "convert",
+    "debug",
+    "equation",
+    "error",
+    "fix",
+    "function",
+    "implement",
+    "json",
+    "math",
+    "parse",
+    "refactor",
+    "regex",
+    "script",
+    "solve",
+    "sql",
+    "test",
+    "typescript",
+    "python",
+    "rust",
+];
+
+/// Words that mark a request as open-ended or creative.
+const CREATIVE_WORDS: &[&str] = &[
+    "brainstorm",
+    "creative",
+    "ideas",
+    "imagine",
+    "invent",
+    "names",
+    "pitch",
+    "poem",
+    "slogan",
+    "story",
+    "tagline",
+    "lyrics",
+    "fiction",
+];
+
+/// Literal snippets that only show up in code-heavy messages.
+const CODE_PATTERNS: &[&str] = &["```", "fn ", "def ", "=>", "();"];
+
+/// Per-message temperature selection for `[agent] temperature = "auto"`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoTemperature {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl AutoTemperature {
+    /// `None` unless auto mode is enabled.
+    pub fn from_config(config: &AgentConfig) -> Option<Self> {
+        (config.temperature == TemperatureMode::Auto).then_some(Self {
+            low: config.temperature_low,
+            high: config.temperature_high,
+        })
+    }
+
+    /// `low` for code and math, `high` for brainstorming and creative writing,
+    /// and `fallback` when the message leans neither way.
+    pub fn select(&self, fallback: f64, message: &str) -> f64 {
+        let lower = message.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        let hits = |list: &[&str]| words.iter().filter(|w| list.contains(w)).count();
+        let precise = hits(PRECISE_WORDS)
+            + CODE_PATTERNS
+                .iter()
+                .filter(|p| message.contains(*p))
+                .count();
+        let creative = hits(CREATIVE_WORDS);
+
+        let temperature = match precise.cmp(&creative) {
+            std::cmp::Ordering::Greater => self.low,
+            std::cmp::Ordering::Less => self.high,
+            std::cmp::Ordering::Equal => fallback,
+        };
+        tracing::debug!(precise, creative, temperature, "Auto-selected temperature");
+        temperature
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,4 +259,41 @@ mod tests {
         );
         assert_eq!(classify(&config, "something completely different"), None);
     }
+
+    fn auto() -> AutoTemperature {
+        AutoTemperature {
+            low: 0.2,
+            high: 1.0,
+        }
+    }
+
+    #[test]
+    fn auto_temperature_is_off_by_default() {
+        assert_eq!(AutoTemperature::from_config(&AgentConfig::default()), None);
+        let config = AgentConfig {
+            temperature: TemperatureMode::Auto,
+            ..AgentConfig::default()
+        };
+        assert_eq!(AutoTemperature::from_config(&config), Some(auto()));
+    }
+
+    #[test]
+    fn code_requests_select_the_low_band() {
+        let t = auto().select(0.7, "Write code to parse this CSV file in Python");
+        assert!((t - 0.2).abs() < f64::EPSILON);
+        let t = auto().select(0.7, "why does ```let x = foo();``` panic?");
+        assert!((t - 0.2).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn brainstorming_selects_the_high_band() {
+        let t = auto().select(0.7, "Brainstorm some names and a tagline for my bakery");
+        assert!((t - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn neutral_messages_keep_the_fallback() {
+        let t = auto().select(0.7, "What's the weather like in Lisbon?");
+        assert!((t - 0.7).abs() < f64::EPSILON);
+    }
 }
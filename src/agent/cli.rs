@@ -0,0 +1,1271 @@
+//! The `zeroclaw agent` command: answer one message, or hold an interactive
+//! session, in a workspace.
+//!
+//! [`run`] starts a [`CliSession`] per workspace. The session's [`Agent`]
+//! owns the provider, tools and history, and every turn goes through
+//! [`Agent::run_turn`], the tool loop behind [`Agent::send`] and the
+//! channels. The session adds what only the terminal needs on top: routing,
+//! planning, checkpoints, the session transcript and post-run hooks. The
+//! interactive loop and its slash commands live in [`super::repl`].
+
+use crate::agent::agent::Agent;
+use crate::agent::classifier::AutoTemperature;
+use crate::agent::context_budget::{self, ContextBudget};
+use crate::agent::dispatcher;
+use crate::agent::hooks;
+use crate::agent::loop_::{
+    build_context, build_hardware_context, build_tool_instructions, resolve_provider_and_model,
+    retain_tools, RoundModels, ToolLoopOptions,
+};
+use crate::agent::output::{self, Interrupted, Output, PolicyDenied};
+use crate::agent::plan::{self, Plan};
+use crate::agent::repl::{self, ReplInput, ReplReader, TurnOverrides};
+use crate::agent::routing::{self, MessageRouter, RouteChoice};
+use crate::agent::session::{self, SessionMode, SessionStore};
+use crate::agent::shutdown;
+use crate::agent::turn_summary::TurnSummary;
+use crate::approval::ApprovalManager;
+use crate::checkpoints::TurnCheckpoints;
+use crate::config::Config;
+use crate::cost::SessionBudget;
+use crate::i18n;
+use crate::memory::categorize::user_message_category;
+use crate::memory::{self, Memory, MemoryCategory};
+use crate::observability::{self, Observer, ObserverEvent};
+use crate::providers::{self, ChatMessage, Provider};
+use crate::runtime;
+use crate::security::SecurityPolicy;
+use crate::term;
+use crate::tools::{self, Tool, ToolRetryPolicy};
+use crate::util::{
+    estimate_tokens, refresh_datetime_section, truncate_with_ellipsis, LocalTimezone,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How a run in one workspace ended.
+#[derive(Debug, PartialEq, Eq)]
+enum RunOutcome {
+    Finished(String),
+    /// `/workspace <name>` was requested in interactive mode.
+    SwitchWorkspace(String),
+}
+
+pub async fn run(
+    config: Config,
+    message: Option<String>,
+    provider_override: Option<String>,
+    model_override: Option<String>,
+    temperature: f64,
+    peripheral_overrides: Vec<String>,
+    session_mode: SessionMode,
+) -> Result<String> {
+    // Shared across workspace switches so only one thread ever reads stdin.
+    let mut input = None;
+    let mut workspace_config = config.clone();
+    let mut session_mode = session_mode;
+
+    loop {
+        let outcome = run_in_workspace(
+            workspace_config,
+            message.as_deref(),
+            provider_override.as_deref(),
+            model_override.as_deref(),
+            temperature,
+            &peripheral_overrides,
+            session_mode,
+            &mut input,
+        )
+        .await?;
+
+        match outcome {
+            RunOutcome::Finished(output) => return Ok(output),
+            RunOutcome::SwitchWorkspace(target) => {
+                // Memory, tools and the sandbox of the previous workspace were
+                // dropped with its run; rebuild everything from the base config.
+                workspace_config = config.clone();
+                workspace_config.apply_workspace(&target)?;
+                session_mode = SessionMode::New;
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_in_workspace(
+    config: Config,
+    message: Option<&str>,
+    provider_override: Option<&str>,
+    model_override: Option<&str>,
+    temperature: f64,
+    peripheral_overrides: &[String],
+    session_mode: SessionMode,
+    input: &mut Option<ReplReader>,
+) -> Result<RunOutcome> {
+    let mut session = CliSession::start(
+        config,
+        provider_override,
+        model_override,
+        temperature,
+        peripheral_overrides,
+        session_mode,
+        message.is_none(),
+    )
+    .await?;
+
+    let next_workspace = match message {
+        Some(message) => {
+            if !session.answer_message(message, input).await? {
+                return Ok(RunOutcome::Finished(String::new()));
+            }
+            None
+        }
+        None => repl::run(&mut session, input).await?,
+    };
+
+    let output = session.finish().await;
+    Ok(match next_workspace {
+        Some(target) => RunOutcome::SwitchWorkspace(target),
+        None => RunOutcome::Finished(output),
+    })
+}
+
+/// One `zeroclaw agent` run in a workspace: the [`Agent`] plus the state
+/// the terminal keeps around its turns.
+pub(super) struct CliSession {
+    pub(super) config: Config,
+    pub(super) agent: Agent,
+    observer: Arc<dyn Observer>,
+    pub(super) mem: Arc<dyn Memory>,
+    pub(super) security: Arc<SecurityPolicy>,
+    runtime: Arc<dyn runtime::RuntimeAdapter>,
+    /// Scratch space for this run; removed when it ends unless --keep-temp.
+    scratch: tools::ScratchDir,
+    _scratch_registration: Option<shutdown::Registration>,
+    /// Task list for multi-step work, saved with the session transcript.
+    pub(super) todos: tools::TodoList,
+    /// Seeds the history, and again after `/clear`.
+    pub(super) system_prompt: String,
+    timezone: LocalTimezone,
+    hardware_rag: Option<crate::rag::HardwareRag>,
+    board_names: Vec<String>,
+    provider_name: String,
+    /// `/model <name>` switches this mid-session.
+    pub(super) model_name: String,
+    /// An explicit model (`--model`, `/model`) always wins over `[routing]`.
+    pub(super) model_pinned: bool,
+    pub(super) temperature: f64,
+    /// Cleared by `/temperature`, which pins the value for the rest of the session.
+    pub(super) auto_temperature: Option<AutoTemperature>,
+    message_router: MessageRouter,
+    approval_manager: ApprovalManager,
+    session_budget: Option<SessionBudget>,
+    pub(super) session_mode: SessionMode,
+    /// Message count of the transcript resumed with `--continue`.
+    pub(super) resumed_messages: Option<usize>,
+    pub(super) session_store: SessionStore,
+    pub(super) session_path: Option<PathBuf>,
+    pub(super) session_id: Option<String>,
+    run_report: hooks::RunReport,
+    final_output: String,
+    start: Instant,
+    pub(super) out: Output,
+    // Last, so observer output is flushed once everything else is dropped.
+    _flush_observer: FlushOnDrop,
+}
+
+/// A turn that got past planning.
+pub(super) struct TurnRun {
+    /// `None` when the turn was cancelled.
+    pub(super) result: Option<Result<String>>,
+    pub(super) summary: TurnSummary,
+    /// Checkpoint taken before the turn first changed a file.
+    pub(super) undo_checkpoint: Option<String>,
+}
+
+impl CliSession {
+    async fn start(
+        config: Config,
+        provider_override: Option<&str>,
+        model_override: Option<&str>,
+        temperature: f64,
+        peripheral_overrides: &[String],
+        session_mode: SessionMode,
+        interactive: bool,
+    ) -> Result<Self> {
+        // ── Wire up agnostic subsystems ──────────────────────────────
+        let observer: Arc<dyn Observer> =
+            Arc::from(observability::create_observer(&config.observability));
+        let flush_observer = FlushOnDrop(observer.clone());
+        let runtime: Arc<dyn runtime::RuntimeAdapter> =
+            Arc::from(runtime::create_runtime(&config.runtime)?);
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+
+        // ── Memory (the brain) ────────────────────────────────────────
+        let timezone = LocalTimezone::from_config(config.agent.timezone.as_deref());
+        let (mem, memory_degraded) = memory::create_session_memory(
+            &config.memory,
+            Some(&config.storage.provider.config),
+            &config.workspace_dir,
+            config.api_key.as_deref(),
+            timezone,
+        )?;
+        let mem: Arc<dyn Memory> = Arc::from(mem);
+        tracing::info!(backend = mem.name(), "Memory initialized");
+
+        if !peripheral_overrides.is_empty() {
+            tracing::info!(
+                peripherals = ?peripheral_overrides,
+                "Peripheral overrides from CLI (config boards take precedence)"
+            );
+        }
+
+        // ── Tools (including memory tools and peripherals) ────────────
+        let scratch = tools::ScratchDir::create(&config.workspace_dir, config.agent.keep_temp)?;
+        let scratch_registration =
+            (!config.agent.keep_temp).then(|| shutdown::register_temp_path(scratch.path()));
+        let todos = tools::TodoList::default();
+        let tools_registry = build_tools(
+            &config,
+            &security,
+            &runtime,
+            &mem,
+            memory_degraded,
+            &scratch,
+            &observer,
+            &todos,
+            interactive,
+        )
+        .await?;
+
+        // ── Resolve provider ─────────────────────────────────────────
+        let (provider_name, model_name) =
+            resolve_provider_and_model(&config, provider_override, model_override);
+        let (provider_name, model_name) = (provider_name.to_string(), model_name.to_string());
+        let message_router = MessageRouter::from_config(&config.routing)?;
+        let mut model_routes = config.model_routes.clone();
+        model_routes.extend(message_router.model_routes(&model_name));
+
+        let provider_options = providers::ProviderRuntimeOptions::default()
+            .with_provider_config(&config.provider)
+            .with_observability_config(&config.observability)?;
+        let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
+            &provider_name,
+            config.api_key.as_deref(),
+            config.api_url.as_deref(),
+            &config.reliability,
+            &model_routes,
+            &model_name,
+            &provider_options,
+        )?;
+
+        observer.record_event(&ObserverEvent::AgentStart {
+            provider: provider_name.clone(),
+            model: model_name.clone(),
+        });
+
+        // ── Hardware RAG (datasheet retrieval when peripherals + datasheet_dir) ──
+        let hardware_rag: Option<crate::rag::HardwareRag> = config
+            .peripherals
+            .datasheet_dir
+            .as_ref()
+            .filter(|d| !d.trim().is_empty())
+            .map(|dir| crate::rag::HardwareRag::load(&config.workspace_dir, dir.trim()))
+            .and_then(Result::ok)
+            .filter(|r: &crate::rag::HardwareRag| !r.is_empty());
+        if let Some(ref rag) = hardware_rag {
+            tracing::info!(chunks = rag.len(), "Hardware RAG loaded");
+        }
+
+        let board_names: Vec<String> = config
+            .peripherals
+            .boards
+            .iter()
+            .map(|b| b.board.clone())
+            .collect();
+
+        // ── Build system prompt from workspace MD files (OpenClaw framework) ──
+        let skills = crate::skills::load_skills(&config.workspace_dir, &config.skills.dir_paths());
+        let tool_descs = tools::tool_descriptions(&tools_registry)?;
+        let bootstrap_max_chars = config.agent.compact_context.then_some(6000);
+        let mut system_prompt = crate::channels::build_system_prompt(
+            &config.workspace_dir,
+            &model_name,
+            &tool_descs,
+            &skills,
+            Some(&config.identity),
+            Some(&config.agent),
+            bootstrap_max_chars,
+            &timezone,
+        );
+
+        // Append structured tool-use instructions with schemas
+        system_prompt.push_str(&build_tool_instructions(&tools_registry));
+
+        let tool_dispatcher =
+            dispatcher::from_config(&config.agent.tool_dispatcher, provider.as_ref());
+        let mut agent = Agent::builder()
+            .provider(provider)
+            .provider_name(provider_name.clone())
+            .tools(tools_registry)
+            .memory(mem.clone())
+            .observer(observer.clone())
+            .tool_dispatcher(tool_dispatcher)
+            .config(config.agent.clone())
+            .model_name(model_name.clone())
+            .temperature(temperature)
+            .workspace_dir(config.workspace_dir.clone())
+            .identity_config(config.identity.clone())
+            .skills(skills)
+            .build()?;
+
+        // ── Session transcript (--continue) ──────────────────────────
+        let session_store = SessionStore::new(&config.workspace_dir);
+        let resumed = if session_mode == SessionMode::Continue {
+            session_store.latest().unwrap_or_else(|e| {
+                tracing::warn!("Failed to load previous session: {e}");
+                None
+            })
+        } else {
+            None
+        };
+        let session_path = match session_mode {
+            SessionMode::Ephemeral => None,
+            SessionMode::New => Some(session_store.new_session_path()),
+            SessionMode::Continue => Some(
+                resumed
+                    .as_ref()
+                    .map_or_else(|| session_store.new_session_path(), |s| s.path.clone()),
+            ),
+        };
+        if let Some(session) = &resumed {
+            todos.set(session.todos.clone());
+        }
+        *agent.history_mut() = session::initial_history(&system_prompt, resumed.as_ref()).into();
+
+        Ok(Self {
+            approval_manager: ApprovalManager::from_config(&config.autonomy).with_policy(&security),
+            session_budget: SessionBudget::from_config(&config.limits, &config.cost),
+            auto_temperature: AutoTemperature::from_config(&config.agent),
+            model_pinned: model_override.is_some(),
+            session_id: session_id_of(session_path.as_ref()),
+            resumed_messages: resumed.map(|session| session.messages.len()),
+            config,
+            agent,
+            observer,
+            mem,
+            security,
+            runtime,
+            scratch,
+            _scratch_registration: scratch_registration,
+            todos,
+            system_prompt,
+            timezone,
+            hardware_rag,
+            board_names,
+            provider_name,
+            model_name,
+            temperature,
+            message_router,
+            session_mode,
+            session_store,
+            session_path,
+            run_report: hooks::RunReport::default(),
+            final_output: String::new(),
+            start: Instant::now(),
+            out: Output::for_channel("cli", false),
+            _flush_observer: flush_observer,
+        })
+    }
+
+    /// Answer `message` and return. Nothing is prompting, so a refused tool
+    /// call fails the run. `false` when the plan was rejected.
+    async fn answer_message(
+        &mut self,
+        message: &str,
+        input: &mut Option<ReplReader>,
+    ) -> Result<bool> {
+        let (context, enriched) = self.enrich(message).await;
+        self.agent.history_mut().push(ChatMessage::user(&enriched));
+
+        let approval_input = if self.config.agent.plan_first && self.config.agent.plan_approval {
+            Some(input.get_or_insert_with(|| {
+                ReplReader::spawn(Vec::new(), repl::history_path(&self.config))
+            }))
+        } else {
+            None
+        };
+        let Some(turn) = self
+            .run_turn(message, &TurnOverrides::default(), approval_input, &context)
+            .await
+        else {
+            self.out.progress(i18n::t("plan.rejected"));
+            return Ok(false);
+        };
+        let Some(result) = turn.result else {
+            return Err(self.interrupted());
+        };
+        let response = result?;
+        self.answer(&response, &turn.summary, false);
+        self.save_session();
+        self.autosave_response(&response).await;
+
+        // Nothing is prompting in single-message mode, so a refused tool call
+        // means the answer may be incomplete: surface it in the exit code.
+        if !turn.summary.policy_denials.is_empty() {
+            return Err(PolicyDenied {
+                tools: turn.summary.policy_denials,
+            }
+            .into());
+        }
+        Ok(true)
+    }
+
+    /// Autosave `user_input` and put its memory and datasheet context in
+    /// front of it. Returns the context and the message to send.
+    pub(super) async fn enrich(&self, user_input: &str) -> (String, String) {
+        if self.config.memory.auto_save {
+            let _ = self
+                .mem
+                .store(
+                    &autosave_memory_key("user_msg"),
+                    user_input,
+                    user_message_category(user_input, self.config.memory.infer_categories),
+                    self.session_id.as_deref(),
+                )
+                .await;
+        }
+
+        let mem_context = build_context(
+            self.mem.as_ref(),
+            user_input,
+            self.config.memory.min_relevance_score,
+            self.config.memory.recall_scope,
+            self.session_id.as_deref(),
+        )
+        .await;
+        let rag_limit = if self.config.agent.compact_context {
+            2
+        } else {
+            5
+        };
+        let hw_context = self
+            .hardware_rag
+            .as_ref()
+            .map(|r| build_hardware_context(r, user_input, &self.board_names, rag_limit))
+            .unwrap_or_default();
+        let context = format!("{mem_context}{hw_context}");
+        let enriched = if context.is_empty() {
+            user_input.to_string()
+        } else {
+            format!("{context}{user_input}")
+        };
+        (context, enriched)
+    }
+
+    /// Run a turn on the user message at the end of the history: route it,
+    /// plan it when `[agent] plan_first` is set, then run the tool loop.
+    /// `None` when the plan was rejected.
+    pub(super) async fn run_turn(
+        &mut self,
+        user_input: &str,
+        overrides: &TurnOverrides,
+        approval_input: Option<&mut ReplReader>,
+        memory_context: &str,
+    ) -> Option<TurnRun> {
+        // Keep the prompt's date/time current across a long session.
+        if let Some(system) = self
+            .agent
+            .history_mut()
+            .first_mut()
+            .filter(|m| m.role == "system")
+        {
+            refresh_datetime_section(&mut system.content, &self.timezone, Utc::now());
+        }
+        report_context_budget(
+            self.agent.history(),
+            self.agent.skills(),
+            &self.config,
+            memory_context,
+        );
+        let turn_temperature = self.auto_temperature.map_or(self.temperature, |auto| {
+            auto.select(self.temperature, user_input)
+        });
+        let route = routing::route_message(
+            &self.message_router,
+            self.model_pinned || !overrides.is_empty(),
+            user_input,
+            "cli",
+            RouteChoice::default_route(
+                &self.provider_name,
+                overrides.model.as_deref().unwrap_or(&self.model_name),
+                overrides.temperature.unwrap_or(turn_temperature),
+            ),
+            self.observer.as_ref(),
+        );
+        let turn_model = MessageRouter::model_for(&route, &self.provider_name);
+        let turn_temperature = route.temperature;
+
+        let mut summary = TurnSummary::default();
+        if self.config.agent.plan_first {
+            let approval_input = approval_input.filter(|_| self.config.agent.plan_approval);
+            match plan_turn(
+                &mut self.agent,
+                &turn_model,
+                turn_temperature,
+                approval_input,
+                &self.out,
+            )
+            .await
+            {
+                PlanOutcome::Approved(plan) => summary.plan = Some(plan),
+                PlanOutcome::Skipped => {}
+                PlanOutcome::Rejected => return None,
+            }
+        }
+
+        let checkpoints = TurnCheckpoints::from_config(&self.config, user_input);
+        let round_models = RoundModels::from_config(&self.config.agent.model_router, &turn_model);
+        let scratch_path = self.scratch.path();
+        let turn = shutdown::begin_turn();
+        let result = turn
+            .run(self.agent.run_turn(
+                &route.provider,
+                &turn_model,
+                turn_temperature,
+                ToolLoopOptions {
+                    silent: false,
+                    approval: Some(&self.approval_manager),
+                    channel_name: "cli",
+                    max_tool_iterations: self.config.agent.max_tool_iterations,
+                    max_tool_calls_per_turn: self.config.agent.max_tool_calls_per_turn,
+                    checkpoints: checkpoints.as_ref(),
+                    summary: Some(&mut summary),
+                    round_models: round_models.as_ref(),
+                    normalize_output: self.config.agent.normalize_output,
+                    early_tool_dispatch: self.config.agent.early_tool_dispatch,
+                    tool_retry: ToolRetryPolicy::from_config(&self.config.agent),
+                    budget: self.session_budget.as_ref(),
+                    artifact_dir: Some(scratch_path.as_path()),
+                    ..ToolLoopOptions::default()
+                },
+            ))
+            .await;
+        drop(turn);
+
+        let mut undo_checkpoint = None;
+        if let Some(cp) = &checkpoints {
+            undo_checkpoint = cp.before_id();
+            summary.file_changes = cp.finish();
+        }
+        Some(TurnRun {
+            result,
+            summary,
+            undo_checkpoint,
+        })
+    }
+
+    /// Show an answered turn and record it. Interactive answers are set off
+    /// by blank lines; a single message's answer follows `--json`.
+    pub(super) fn answer(&mut self, response: &str, summary: &TurnSummary, interactive: bool) {
+        self.final_output = response.to_string();
+        self.run_report.record_turn(summary);
+        output::clear_partial_response();
+        if interactive {
+            term::response(format_args!("\n{response}\n"));
+        } else if output::json_output() {
+            self.out
+                .response(output::json_response(response, &summary.assistant_updates));
+        } else {
+            self.out.response(response);
+        }
+        report_turn_summary(
+            summary,
+            self.observer.as_ref(),
+            &self.out,
+            self.config.agent.turn_summary,
+        );
+        self.observer.record_event(&ObserverEvent::TurnComplete);
+    }
+
+    /// Auto-save the assistant response to the daily log.
+    pub(super) async fn autosave_response(&self, response: &str) {
+        if !self.config.memory.auto_save {
+            return;
+        }
+        let summary = truncate_with_ellipsis(response, 100);
+        let _ = self
+            .mem
+            .store(
+                &autosave_memory_key("assistant_resp"),
+                &summary,
+                MemoryCategory::Daily,
+                self.session_id.as_deref(),
+            )
+            .await;
+    }
+
+    /// Write the transcript and task list, unless the session is not recorded.
+    pub(super) fn save_session(&self) {
+        let Some(path) = &self.session_path else {
+            return;
+        };
+        if let Err(e) = self.session_store.save(path, self.agent.history()) {
+            tracing::warn!("Failed to save session transcript: {e}");
+        }
+        if let Err(e) = self.session_store.save_todos(path, &self.todos.items()) {
+            tracing::warn!("Failed to save session todo list: {e}");
+        }
+    }
+
+    /// Save the session and record the end of a run stopped by SIGINT/SIGTERM.
+    pub(super) fn interrupted(&self) -> anyhow::Error {
+        self.save_session();
+        self.observer.record_event(&ObserverEvent::AgentEnd {
+            provider: self.provider_name.clone(),
+            model: self.model_name.clone(),
+            duration: self.start.elapsed(),
+            tokens_used: None,
+            cost_usd: None,
+            interrupted: true,
+        });
+        Interrupted.into()
+    }
+
+    /// Record the end of the run and run the post-run hooks. Returns the
+    /// last answer.
+    async fn finish(mut self) -> String {
+        let duration = self.start.elapsed();
+        self.observer.record_event(&ObserverEvent::AgentEnd {
+            provider: self.provider_name.clone(),
+            model: self.model_name.clone(),
+            duration,
+            tokens_used: None,
+            cost_usd: None,
+            interrupted: false,
+        });
+
+        if self.config.hooks.post_run_url.is_some() || self.config.hooks.post_run_command.is_some()
+        {
+            self.run_report.session_id = self.session_id.clone();
+            self.run_report.provider = self.provider_name.clone();
+            self.run_report.model = self.model_name.clone();
+            self.run_report.response = self.final_output.clone();
+            self.run_report.duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+            hooks::run_post_run_hooks(
+                &self.config.hooks,
+                &self.security,
+                self.runtime.as_ref(),
+                &self.run_report,
+            )
+            .await;
+        }
+        std::mem::take(&mut self.final_output)
+    }
+}
+
+/// The run's tools: the configured set with shell and file tools pointed at
+/// the scratch directory, the task list and peripheral tools, narrowed to
+/// `[agent] tools` and wrapped against prompt injection and oversized results.
+#[allow(clippy::too_many_arguments)]
+async fn build_tools(
+    config: &Config,
+    security: &Arc<SecurityPolicy>,
+    runtime: &Arc<dyn runtime::RuntimeAdapter>,
+    mem: &Arc<dyn Memory>,
+    memory_degraded: bool,
+    scratch: &tools::ScratchDir,
+    observer: &Arc<dyn Observer>,
+    todos: &tools::TodoList,
+    interactive: bool,
+) -> Result<Vec<Box<dyn Tool>>> {
+    let (composio_key, composio_entity_id) = if config.composio.enabled {
+        (
+            config.composio.api_key.as_deref(),
+            Some(config.composio.entity_id.as_str()),
+        )
+    } else {
+        (None, None)
+    };
+    let mut tools_registry = tools::all_tools_with_runtime(
+        Arc::new(config.clone()),
+        security,
+        runtime.clone(),
+        mem.clone(),
+        composio_key,
+        composio_entity_id,
+        &config.browser,
+        &config.http_request,
+        &config.workspace_dir,
+        &config.agents,
+        config.api_key.as_deref(),
+        config,
+    );
+    if memory_degraded {
+        tools_registry.retain(|tool| !tools::is_memory_tool(tool.name()));
+    }
+
+    let mut shell =
+        tools::ShellTool::new(security.clone(), runtime.clone()).with_scratch_dir(scratch.path());
+
+    // Stream shell output to the terminal (and observer) while commands run.
+    if config.agent.stream_shell_output {
+        let out = Output::for_channel("cli", false);
+        let sink_observer = observer.clone();
+        shell = shell.with_output_sink(Arc::new(move |line: &str| {
+            out.progress(format_args!("  │ {line}"));
+            sink_observer.record_event(&ObserverEvent::ToolOutput {
+                tool: "shell".into(),
+                line: line.to_string(),
+            });
+        }));
+    }
+    let mut shell = Some(shell);
+    for slot in &mut tools_registry {
+        let scratch_dir = scratch.relative_path().to_path_buf();
+        match slot.name() {
+            "shell" => {
+                if let Some(shell) = shell.take() {
+                    *slot = Box::new(shell);
+                }
+            }
+            "file_read" => {
+                *slot = Box::new(
+                    tools::FileReadTool::new(security.clone()).with_scratch_dir(scratch_dir),
+                );
+            }
+            "file_write" => {
+                *slot = Box::new(
+                    tools::FileWriteTool::new(security.clone()).with_scratch_dir(scratch_dir),
+                );
+            }
+            "multi_file_write" => {
+                *slot = Box::new(
+                    tools::MultiFileWriteTool::new(security.clone()).with_scratch_dir(scratch_dir),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    // Changes to the task list are shown as they happen in interactive
+    // mode, or with --verbose.
+    let todo_out = Output::for_channel("cli", false);
+    tools_registry.push(Box::new(
+        tools::TodoTool::new(todos.clone()).with_on_change(Arc::new(move |list: &str| {
+            let block = format!("📋 Tasks:\n{list}");
+            if interactive {
+                todo_out.progress(block);
+            } else {
+                todo_out.verbose(block);
+            }
+        })),
+    ));
+
+    let peripheral_tools: Vec<Box<dyn Tool>> =
+        crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
+    if !peripheral_tools.is_empty() {
+        tracing::info!(count = peripheral_tools.len(), "Peripheral tools added");
+        tools_registry.extend(peripheral_tools);
+    }
+    retain_tools(&mut tools_registry, &config.agent.tools)?;
+    let tools_registry = tools::PromptInjectionFilter::wrap_all(
+        tools_registry,
+        config.security.tool_output_injection,
+    );
+    Ok(tools::OffloadLargeResults::wrap_all(
+        tools_registry,
+        &scratch.path(),
+        config.agent.tool_result_offload_bytes,
+    ))
+}
+
+fn autosave_memory_key(prefix: &str) -> String {
+    format!("{prefix}_{}", Uuid::new_v4())
+}
+
+/// Session id for memory scoping and run reports: the transcript's file stem.
+pub(super) fn session_id_of(session_path: Option<&PathBuf>) -> Option<String> {
+    session_path
+        .and_then(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+}
+
+/// Emit the turn summary to observers and, unless disabled, report it as progress.
+fn report_turn_summary(summary: &TurnSummary, observer: &dyn Observer, out: &Output, print: bool) {
+    if summary.is_empty() {
+        return;
+    }
+    observer.record_event(&summary.to_observer_event());
+    if print {
+        out.progress(format!("\n{}\n", summary.render()));
+    }
+}
+
+/// `--show-context-budget`: print the breakdown of the request about to be
+/// sent on stderr, keeping stdout for the answer.
+fn report_context_budget(
+    history: &[ChatMessage],
+    skills: &[crate::skills::Skill],
+    config: &Config,
+    memory_context: &str,
+) {
+    let Some(format) = context_budget::report_format() else {
+        return;
+    };
+    let budget = ContextBudget::measure(
+        history,
+        skills,
+        &config.workspace_dir,
+        memory_context,
+        config.agent.context_window_tokens,
+    );
+    term::eprintln(budget.render(format));
+}
+
+/// Result of the planning phase for one turn.
+#[derive(Debug)]
+enum PlanOutcome {
+    Approved(Plan),
+    Rejected,
+    /// Planning failed or the task needs only one step; execute directly.
+    Skipped,
+}
+
+/// Planning phase for `[agent].plan_first`: ask for a plan, show it, wait for
+/// approval when `approval_input` is given, then append the plan to the last
+/// user message as the execution brief.
+async fn plan_turn(
+    agent: &mut Agent,
+    model: &str,
+    temperature: f64,
+    approval_input: Option<&mut ReplReader>,
+    out: &Output,
+) -> PlanOutcome {
+    let plan =
+        match plan::generate_plan(agent.provider(), agent.history(), model, temperature).await {
+            Ok(plan) if plan.steps.len() > 1 => plan,
+            Ok(_) => return PlanOutcome::Skipped,
+            Err(e) => {
+                tracing::warn!("Planning failed, executing without a plan: {e}");
+                return PlanOutcome::Skipped;
+            }
+        };
+
+    out.progress(plan.render());
+    if let Some(input) = approval_input {
+        let approved = match input.read(out, i18n::t("plan.confirm"), None).await {
+            ReplInput::Line(answer) => {
+                answer.trim().is_empty() || i18n::is_answer("answer.yes", &answer)
+            }
+            ReplInput::Eof | ReplInput::IdleTimeout => false,
+        };
+        if !approved {
+            return PlanOutcome::Rejected;
+        }
+    }
+
+    if let Some(last) = agent.history_mut().last_mut() {
+        last.content.push_str("\n\n");
+        last.content.push_str(&plan.execution_brief());
+    }
+    PlanOutcome::Approved(plan)
+}
+
+/// The system prompt a run in `config`'s workspace would start with, and,
+/// for `message`, the user message as it would be sent (with its memory
+/// context prepended). Peripheral tools and hardware datasheet context are
+/// left out, since building them talks to connected boards.
+pub async fn render_context(config: &Config, message: Option<&str>) -> Result<String> {
+    let runtime: Arc<dyn runtime::RuntimeAdapter> =
+        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    let timezone = LocalTimezone::from_config(config.agent.timezone.as_deref());
+    let (mem, memory_degraded) = memory::create_session_memory(
+        &config.memory,
+        Some(&config.storage.provider.config),
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        timezone,
+    )?;
+    let mem: Arc<dyn Memory> = Arc::from(mem);
+
+    let (composio_key, composio_entity_id) = if config.composio.enabled {
+        (
+            config.composio.api_key.as_deref(),
+            Some(config.composio.entity_id.as_str()),
+        )
+    } else {
+        (None, None)
+    };
+    let mut tools_registry = tools::all_tools_with_runtime(
+        Arc::new(config.clone()),
+        &security,
+        runtime,
+        mem.clone(),
+        composio_key,
+        composio_entity_id,
+        &config.browser,
+        &config.http_request,
+        &config.workspace_dir,
+        &config.agents,
+        config.api_key.as_deref(),
+        config,
+    );
+    tools_registry.push(Box::new(tools::TodoTool::new(tools::TodoList::default())));
+    if memory_degraded {
+        tools_registry.retain(|tool| !tools::is_memory_tool(tool.name()));
+    }
+    retain_tools(&mut tools_registry, &config.agent.tools)?;
+    let tool_descs = tools::tool_descriptions(&tools_registry)?;
+
+    let (_, model_name) = resolve_provider_and_model(config, None, None);
+    let skills = crate::skills::load_skills(&config.workspace_dir, &config.skills.dir_paths());
+    let bootstrap_max_chars = config.agent.compact_context.then_some(6000);
+    let mut system_prompt = crate::channels::build_system_prompt(
+        &config.workspace_dir,
+        model_name,
+        &tool_descs,
+        &skills,
+        Some(&config.identity),
+        Some(&config.agent),
+        bootstrap_max_chars,
+        &timezone,
+    );
+    system_prompt.push_str(&build_tool_instructions(&tools_registry));
+
+    let Some(message) = message else {
+        return Ok(system_prompt);
+    };
+    let memory_context = build_context(
+        mem.as_ref(),
+        message,
+        config.memory.min_relevance_score,
+        config.memory.recall_scope,
+        None,
+    )
+    .await;
+    Ok(format!(
+        "{system_prompt}\n\n--- user message ---\n{memory_context}{message}"
+    ))
+}
+
+pub async fn handle_context_command(
+    command: crate::ContextCommands,
+    config: &Config,
+) -> Result<()> {
+    match command {
+        crate::ContextCommands::Show {
+            message,
+            count_tokens,
+        } => {
+            let rendered = render_context(config, message.as_deref()).await?;
+            term::response(&rendered);
+            if count_tokens {
+                term::eprintln(format_args!(
+                    "Estimated tokens: {}",
+                    estimate_tokens(rendered.chars().count())
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The message for `zeroclaw agent --file <path>`: the file's content, or
+/// all of stdin for `-`, without trailing whitespace.
+pub fn read_message_file(path: &str) -> Result<String> {
+    let raw = if path == "-" {
+        let mut raw = String::new();
+        std::io::stdin()
+            .read_to_string(&mut raw)
+            .context("Failed to read the message from stdin")?;
+        raw
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the message from {path}"))?
+    };
+    let message = raw.trim_end();
+    if message.trim().is_empty() {
+        anyhow::bail!("The message in {path} is empty");
+    }
+    Ok(message.to_string())
+}
+
+/// Await `agent_run` (a [`run`] call) under an optional `--timeout-secs`
+/// deadline.
+///
+/// On timeout the run is dropped, which flushes its observer and releases
+/// memory and the scratch directory, then any prose the model had written
+/// in the unfinished turn is printed and [`output::TimedOut`] is returned.
+pub async fn run_with_timeout(
+    config: &Config,
+    timeout_secs: Option<u64>,
+    agent_run: impl std::future::Future<Output = Result<String>>,
+) -> Result<String> {
+    let Some(secs) = timeout_secs else {
+        return agent_run.await;
+    };
+    let timeout = Duration::from_secs(secs);
+    match tokio::time::timeout(timeout, agent_run).await {
+        Ok(result) => result,
+        Err(_) => {
+            let partial = output::take_partial_response();
+            if !partial.is_empty() {
+                Output::for_channel("cli", false).response(&partial);
+            }
+            let observer = observability::create_observer(&config.observability);
+            observer.record_event(&ObserverEvent::AgentTimeout { timeout });
+            observer.flush();
+            Err(output::TimedOut { secs }.into())
+        }
+    }
+}
+
+/// Flushes buffered observer output when a run ends, including when the
+/// run is dropped mid-turn by `--timeout-secs`.
+struct FlushOnDrop(Arc<dyn Observer>);
+
+impl Drop for FlushOnDrop {
+    fn drop(&mut self) {
+        self.0.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::dispatcher::XmlToolDispatcher;
+    use crate::memory::SqliteMemory;
+    use crate::providers::ChatRequest;
+    use tempfile::TempDir;
+
+    #[test]
+    fn autosave_memory_key_has_prefix_and_uniqueness() {
+        let key1 = autosave_memory_key("user_msg");
+        let key2 = autosave_memory_key("user_msg");
+
+        assert!(key1.starts_with("user_msg_"));
+        assert!(key2.starts_with("user_msg_"));
+        assert_ne!(key1, key2);
+    }
+
+    #[tokio::test]
+    async fn autosave_memory_keys_preserve_multiple_turns() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+
+        let key1 = autosave_memory_key("user_msg");
+        let key2 = autosave_memory_key("user_msg");
+
+        mem.store(&key1, "I'm Paul", MemoryCategory::Conversation, None)
+            .await
+            .unwrap();
+        mem.store(&key2, "I'm 45", MemoryCategory::Conversation, None)
+            .await
+            .unwrap();
+
+        assert_eq!(mem.count().await.unwrap(), 2);
+
+        let recalled = mem.recall("45", 5, None).await.unwrap();
+        assert!(recalled.iter().any(|entry| entry.content.contains("45")));
+    }
+
+    /// Returns a three-step plan, then works through it one step per round-trip.
+    struct PlanningProvider {
+        calls: std::sync::Mutex<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for PlanningProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("Plan:\n1. Capture the screen\n2. Capture it again\n3. Compare both".into())
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<crate::providers::ChatResponse> {
+            let mut calls = self.calls.lock().unwrap();
+            *calls += 1;
+            let text = match *calls {
+                1 => "Step 1: first capture\n<tool_call>{\"name\":\"snap\",\"arguments\":{}}</tool_call>",
+                2 => "Step 2: second capture\n<tool_call>{\"name\":\"snap\",\"arguments\":{}}</tool_call>",
+                _ => "Step 3: both captures match.",
+            };
+            Ok(crate::providers::ChatResponse {
+                text: Some(text.into()),
+                tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
+            })
+        }
+    }
+
+    struct SnapTool;
+
+    #[async_trait::async_trait]
+    impl Tool for SnapTool {
+        fn name(&self) -> &str {
+            "snap"
+        }
+
+        fn description(&self) -> &str {
+            "Captures the screen"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<crate::tools::ToolResult> {
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: "captured".into(),
+                error: None,
+                artifacts: Vec::new(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_steps_are_parsed_and_tracked_through_execution() {
+        let memory_cfg = crate::config::MemoryConfig {
+            backend: "none".into(),
+            ..crate::config::MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> = Arc::from(
+            memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None).unwrap(),
+        );
+        let mut agent = Agent::builder()
+            .provider(Box::new(PlanningProvider {
+                calls: std::sync::Mutex::new(0),
+            }))
+            .tools(vec![Box::new(SnapTool)])
+            .memory(mem)
+            .observer(Arc::new(crate::observability::NoopObserver {}))
+            .tool_dispatcher(Box::new(XmlToolDispatcher))
+            .workspace_dir(std::path::PathBuf::from("/tmp"))
+            .build()
+            .unwrap();
+        agent
+            .history_mut()
+            .extend([ChatMessage::system("sys"), ChatMessage::user("compare")]);
+        let out = Output::new(crate::agent::output::Verbosity::Quiet);
+
+        let PlanOutcome::Approved(plan) =
+            plan_turn(&mut agent, "test-model", 0.0, None, &out).await
+        else {
+            panic!("expected an approved plan");
+        };
+        assert_eq!(plan.steps.len(), 3);
+        assert_eq!(plan.steps[0].description, "Capture the screen");
+        assert!(agent.history()[1].content.contains("3. Compare both"));
+
+        let mut summary = TurnSummary {
+            plan: Some(plan),
+            ..TurnSummary::default()
+        };
+        let response = agent
+            .run_turn(
+                "test",
+                "test-model",
+                0.0,
+                ToolLoopOptions {
+                    max_tool_iterations: 5,
+                    summary: Some(&mut summary),
+                    ..ToolLoopOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response, "Step 3: both captures match.");
+        let plan = summary.plan.as_ref().unwrap();
+        assert!(plan
+            .steps
+            .iter()
+            .all(|s| s.status == crate::agent::plan::StepStatus::Done));
+        assert_eq!(summary.tool_calls(), 2);
+        assert!(summary.render().contains("Plan: 3/3 steps done"));
+    }
+
+    #[tokio::test]
+    async fn render_context_shows_prompt_tools_and_enriched_message() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        config.memory.backend = "markdown".into();
+
+        let prompt_only = render_context(&config, None).await.unwrap();
+        assert!(prompt_only.contains("## Tool Use Protocol"));
+        assert!(prompt_only.contains("**file_read**"));
+        assert!(!prompt_only.contains("--- user message ---"));
+
+        let with_message = render_context(&config, Some("what changed?"))
+            .await
+            .unwrap();
+        assert!(with_message.starts_with(&prompt_only));
+        assert!(with_message.ends_with("--- user message ---\nwhat changed?"));
+    }
+
+    #[tokio::test]
+    async fn message_file_content_becomes_the_user_message() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        config.memory.backend = "markdown".into();
+        let file = tmp.path().join("prompt.md");
+        std::fs::write(
+            &file,
+            "Review this diff:\n\n- src/main.rs\n- src/lib.rs\n\n",
+        )
+        .unwrap();
+
+        let message = read_message_file(file.to_str().unwrap()).unwrap();
+        assert_eq!(message, "Review this diff:\n\n- src/main.rs\n- src/lib.rs");
+        let rendered = render_context(&config, Some(&message)).await.unwrap();
+        assert!(rendered.ends_with(&format!("--- user message ---\n{message}")));
+
+        std::fs::write(&file, "\n  \n").unwrap();
+        assert!(read_message_file(file.to_str().unwrap()).is_err());
+        assert!(read_message_file(tmp.path().join("missing.md").to_str().unwrap()).is_err());
+    }
+
+    #[tokio::test]
+    async fn run_with_timeout_cancels_a_stuck_run() {
+        let config = Config::default();
+        let err = run_with_timeout(&config, Some(0), std::future::pending())
+            .await
+            .unwrap_err();
+        assert_eq!(output::exit_code(&err), output::EXIT_TIMEOUT);
+
+        let done = run_with_timeout(&config, Some(60), async { Ok("done".to_string()) })
+            .await
+            .unwrap();
+        assert_eq!(done, "done");
+    }
+}
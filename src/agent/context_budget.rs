@@ -0,0 +1,336 @@
+//! Estimated token breakdown of the next provider request (`/context`,
+//! `--show-context-budget`).
+//!
+//! Every figure is measured on the strings that are actually sent: the system
+//! prompt in the history, each skill's entry as rendered into it, the memory
+//! block prepended to the latest user message, and the history messages
+//! themselves. Counts use the ~4 characters per token estimate.
+
+use crate::providers::ChatMessage;
+use crate::skills::Skill;
+use crate::util::estimate_tokens;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How `--show-context-budget` prints the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BudgetFormat {
+    Text,
+    Json,
+}
+
+static REPORT_FORMAT: AtomicU8 = AtomicU8::new(0);
+
+/// Print a budget report before each turn's request (`--show-context-budget`).
+pub fn set_report_format(format: Option<BudgetFormat>) {
+    let value = match format {
+        None => 0,
+        Some(BudgetFormat::Text) => 1,
+        Some(BudgetFormat::Json) => 2,
+    };
+    REPORT_FORMAT.store(value, Ordering::Relaxed);
+}
+
+pub fn report_format() -> Option<BudgetFormat> {
+    match REPORT_FORMAT.load(Ordering::Relaxed) {
+        1 => Some(BudgetFormat::Text),
+        2 => Some(BudgetFormat::Json),
+        _ => None,
+    }
+}
+
+/// What a line of the report accounts for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetKind {
+    /// The system prompt minus the skill entries
+    SystemPrompt,
+    Skill,
+    Memory,
+    /// One user turn with the replies and tool results that followed it
+    History,
+    /// Tool results the model has not answered yet
+    PendingToolResults,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BudgetLine {
+    pub kind: BudgetKind,
+    pub label: String,
+    pub tokens: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContextBudget {
+    pub lines: Vec<BudgetLine>,
+    pub total_tokens: u64,
+    /// `[agent] context_window_tokens`, when configured
+    pub context_limit: Option<u64>,
+    /// Tokens left before the limit; negative when already over it
+    pub headroom: Option<i64>,
+}
+
+fn is_tool_result(message: &ChatMessage) -> bool {
+    message.role == "tool"
+        || (message.role == "user" && message.content.starts_with("[Tool results]"))
+}
+
+fn tokens(text: &str) -> u64 {
+    estimate_tokens(text.chars().count())
+}
+
+impl ContextBudget {
+    /// Break down `history` as it would be sent next. `memory_context` is the
+    /// block that was prepended to the latest user message (empty if none).
+    pub fn measure(
+        history: &[ChatMessage],
+        skills: &[Skill],
+        workspace_dir: &Path,
+        memory_context: &str,
+        context_limit: Option<u64>,
+    ) -> Self {
+        let mut lines = Vec::new();
+        let (system, rest) = match history.split_first() {
+            Some((first, rest)) if first.role == "system" => (Some(first), rest),
+            _ => (None, history),
+        };
+
+        if let Some(system) = system {
+            let mut skill_lines = Vec::new();
+            for skill in skills {
+                let entry = crate::channels::skill_prompt_entry(skill, workspace_dir);
+                if system.content.contains(&entry) {
+                    skill_lines.push(BudgetLine {
+                        kind: BudgetKind::Skill,
+                        label: skill.name.clone(),
+                        tokens: tokens(&entry),
+                    });
+                }
+            }
+            let skill_tokens: u64 = skill_lines.iter().map(|line| line.tokens).sum();
+            lines.push(BudgetLine {
+                kind: BudgetKind::SystemPrompt,
+                label: "system prompt".into(),
+                tokens: tokens(&system.content).saturating_sub(skill_tokens),
+            });
+            lines.extend(skill_lines);
+        }
+
+        // Tool results after the last assistant message are still pending.
+        let pending_from = rest
+            .iter()
+            .rposition(|message| message.role == "assistant")
+            .map_or(0, |i| i + 1);
+        let pending_from = rest[pending_from..]
+            .iter()
+            .position(is_tool_result)
+            .map_or(rest.len(), |i| pending_from + i);
+        let latest_user = rest[..pending_from]
+            .iter()
+            .rposition(|message| message.role == "user" && !is_tool_result(message));
+
+        let mut memory_tokens = 0;
+        let mut turn = 0;
+        for (i, message) in rest[..pending_from].iter().enumerate() {
+            let mut message_tokens = tokens(&message.content);
+            if Some(i) == latest_user
+                && !memory_context.is_empty()
+                && message.content.starts_with(memory_context)
+            {
+                memory_tokens = tokens(memory_context);
+                message_tokens = tokens(&message.content[memory_context.len()..]);
+            }
+            let starts_turn = message.role == "user" && !is_tool_result(message);
+            match lines.last_mut() {
+                Some(line) if line.kind == BudgetKind::History && !starts_turn => {
+                    line.tokens += message_tokens;
+                }
+                _ => {
+                    turn += 1;
+                    lines.push(BudgetLine {
+                        kind: BudgetKind::History,
+                        label: format!("turn {turn}"),
+                        tokens: message_tokens,
+                    });
+                }
+            }
+        }
+        if memory_tokens > 0 {
+            lines.push(BudgetLine {
+                kind: BudgetKind::Memory,
+                label: "memory context".into(),
+                tokens: memory_tokens,
+            });
+        }
+
+        let pending: u64 = rest[pending_from..]
+            .iter()
+            .map(|message| tokens(&message.content))
+            .sum();
+        if pending_from < rest.len() {
+            lines.push(BudgetLine {
+                kind: BudgetKind::PendingToolResults,
+                label: "pending tool results".into(),
+                tokens: pending,
+            });
+        }
+
+        let total_tokens = lines.iter().map(|line| line.tokens).sum();
+        let headroom = context_limit.map(|limit| {
+            i64::try_from(limit)
+                .unwrap_or(i64::MAX)
+                .saturating_sub(i64::try_from(total_tokens).unwrap_or(i64::MAX))
+        });
+        Self {
+            lines,
+            total_tokens,
+            context_limit,
+            headroom,
+        }
+    }
+
+    pub fn render(&self, format: BudgetFormat) -> String {
+        match format {
+            BudgetFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+            BudgetFormat::Text => self.render_text(),
+        }
+    }
+
+    fn render_text(&self) -> String {
+        let mut out = String::from("Context budget (estimated tokens):\n");
+        for line in &self.lines {
+            let label = match line.kind {
+                BudgetKind::Skill => format!("  skill {}", line.label),
+                _ => line.label.clone(),
+            };
+            out.push_str(&format!("  {label:<28} {:>8}\n", line.tokens));
+        }
+        out.push_str(&format!("  {:<28} {:>8}\n", "total", self.total_tokens));
+        match (self.context_limit, self.headroom) {
+            (Some(limit), Some(headroom)) => {
+                out.push_str(&format!("  {:<28} {limit:>8}\n", "context limit"));
+                out.push_str(&format!("  {:<28} {headroom:>8}\n", "headroom"));
+            }
+            _ => out
+                .push_str("  (set [agent] context_window_tokens to see the limit and headroom)\n"),
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(name: &str) -> Skill {
+        Skill {
+            name: name.into(),
+            description: "does things".into(),
+            version: "1.0.0".into(),
+            author: None,
+            tags: Vec::new(),
+            tools: Vec::new(),
+            prompts: Vec::new(),
+            location: None,
+            title: None,
+            models: Vec::new(),
+            priority: 0.0,
+        }
+    }
+
+    fn kinds(budget: &ContextBudget) -> Vec<(BudgetKind, &str)> {
+        budget
+            .lines
+            .iter()
+            .map(|line| (line.kind, line.label.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn breakdown_sums_to_the_estimate_of_the_sent_strings() {
+        let ws = Path::new("/ws");
+        let skills = [skill("deploy"), skill("review")];
+        let entries: String = skills
+            .iter()
+            .map(|s| crate::channels::skill_prompt_entry(s, ws))
+            .collect();
+        let system = format!("## Tools\n\n<available_skills>\n{entries}</available_skills>\n");
+        let memory = "[Memory context]\n- likes tea\n\n";
+        let history = vec![
+            ChatMessage::system(&system),
+            ChatMessage::user("first question"),
+            ChatMessage::assistant("<tool_call>{}</tool_call>"),
+            ChatMessage::user("[Tool results]\nok"),
+            ChatMessage::assistant("first answer"),
+            ChatMessage::user(format!("{memory}second question")),
+        ];
+
+        let budget = ContextBudget::measure(&history, &skills, ws, memory, Some(1000));
+        assert_eq!(
+            kinds(&budget),
+            [
+                (BudgetKind::SystemPrompt, "system prompt"),
+                (BudgetKind::Skill, "deploy"),
+                (BudgetKind::Skill, "review"),
+                (BudgetKind::History, "turn 1"),
+                (BudgetKind::History, "turn 2"),
+                (BudgetKind::Memory, "memory context"),
+            ]
+        );
+
+        let t = |s: &str| estimate_tokens(s.chars().count());
+        let skill_tokens = t(&crate::channels::skill_prompt_entry(&skills[0], ws));
+        assert_eq!(budget.lines[1].tokens, skill_tokens);
+        assert_eq!(budget.lines[0].tokens, t(&system) - 2 * skill_tokens);
+        assert_eq!(
+            budget.lines[3].tokens,
+            t("first question")
+                + t("<tool_call>{}</tool_call>")
+                + t("[Tool results]\nok")
+                + t("first answer")
+        );
+        assert_eq!(budget.lines[4].tokens, t("second question"));
+        assert_eq!(budget.lines[5].tokens, t(memory));
+
+        let sum: u64 = budget.lines.iter().map(|line| line.tokens).sum();
+        assert_eq!(budget.total_tokens, sum);
+        assert_eq!(budget.headroom, Some(1000 - i64::try_from(sum).unwrap()));
+    }
+
+    #[test]
+    fn unanswered_tool_results_are_pending() {
+        let results = ["{\"content\":\"12345678\"}", "{\"content\":\"x\"}"];
+        let history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("do it"),
+            ChatMessage::assistant("calling"),
+            ChatMessage::tool(results[0]),
+            ChatMessage::tool(results[1]),
+        ];
+        let budget = ContextBudget::measure(&history, &[], Path::new("/ws"), "", None);
+        let pending = budget.lines.last().unwrap();
+        assert_eq!(pending.kind, BudgetKind::PendingToolResults);
+        assert_eq!(
+            pending.tokens,
+            estimate_tokens(results[0].len()) + estimate_tokens(results[1].len()),
+            "both unanswered results count"
+        );
+        assert_eq!(budget.headroom, None);
+    }
+
+    #[test]
+    fn over_the_limit_has_negative_headroom_and_json_form() {
+        let history = vec![ChatMessage::system("x".repeat(400))];
+        let budget = ContextBudget::measure(&history, &[], Path::new("/ws"), "", Some(50));
+        assert_eq!(budget.total_tokens, 100);
+        assert_eq!(budget.headroom, Some(-50));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&budget.render(BudgetFormat::Json)).unwrap();
+        assert_eq!(json["lines"][0]["kind"], "system_prompt");
+        assert_eq!(json["total_tokens"], 100);
+        assert_eq!(json["headroom"], -50);
+        assert!(budget.render(BudgetFormat::Text).contains("headroom"));
+    }
+}
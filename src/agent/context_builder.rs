@@ -0,0 +1,72 @@
+//! Per-message context assembly for [`Agent`](crate::agent::Agent).
+//!
+//! Before each user message is sent, the agent asks its [`ContextBuilder`]
+//! for a block to prepend to it. The default, [`MemoryContextBuilder`],
+//! recalls relevant memories through a [`MemoryLoader`]; embedders plug in
+//! their own retrieval (a RAG pipeline, a search index) with
+//! [`AgentBuilder::context_builder`](crate::agent::AgentBuilder::context_builder).
+
+use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
+use crate::memory::Memory;
+use async_trait::async_trait;
+
+/// Context assembled for one user message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuiltContext {
+    /// Text prepended to the user message; empty adds nothing.
+    pub context: String,
+}
+
+impl BuiltContext {
+    pub fn new(context: impl Into<String>) -> Self {
+        Self {
+            context: context.into(),
+        }
+    }
+
+    /// The user message as sent to the provider.
+    pub fn enrich(&self, user_msg: &str) -> String {
+        if self.context.is_empty() {
+            user_msg.to_string()
+        } else {
+            format!("{}{user_msg}", self.context)
+        }
+    }
+}
+
+#[async_trait]
+pub trait ContextBuilder: Send + Sync {
+    /// Build the context for `user_msg`. Retrieval failures should degrade
+    /// to an empty context rather than fail the turn.
+    async fn build(&self, user_msg: &str, mem: &dyn Memory) -> BuiltContext;
+}
+
+/// The default builder: memories recalled by a [`MemoryLoader`].
+pub struct MemoryContextBuilder {
+    loader: Box<dyn MemoryLoader>,
+}
+
+impl MemoryContextBuilder {
+    pub fn new(loader: Box<dyn MemoryLoader>) -> Self {
+        Self { loader }
+    }
+}
+
+impl Default for MemoryContextBuilder {
+    fn default() -> Self {
+        Self::new(Box::new(DefaultMemoryLoader::default()))
+    }
+}
+
+#[async_trait]
+impl ContextBuilder for MemoryContextBuilder {
+    async fn build(&self, user_msg: &str, mem: &dyn Memory) -> BuiltContext {
+        match self.loader.load_context(mem, user_msg).await {
+            Ok(context) => BuiltContext::new(context),
+            Err(e) => {
+                tracing::warn!("Memory context unavailable: {e}");
+                BuiltContext::default()
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! Conversation history of an interactive session.
+//!
+//! [`ConversationHistory`] wraps the `Vec<ChatMessage>` the REPL keeps across
+//! turns and derefs to it, so it is passed straight to
+//! [`run_tool_call_loop`](super::loop_::run_tool_call_loop).
+//! [`ConversationHistory::fork`] makes an independent copy: `/retry` forks
+//! the history before dropping the last response and goes back to the fork
+//! if the regeneration fails or is cancelled.
+//!
+//! Long sessions are kept in bounds by [`auto_compact_history`], which
+//! summarizes older messages with the model, and [`trim_history`], the hard
+//! cap applied after it.
+
+use crate::providers::{ChatMessage, Provider};
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use std::fmt::Write;
+use std::ops::{Deref, DerefMut};
+
+/// Default trigger for auto-compaction when non-system message count exceeds this threshold.
+/// Prefer passing the config-driven value via `run_tool_call_loop`; this constant is only
+/// used when callers omit the parameter.
+const DEFAULT_MAX_HISTORY_MESSAGES: usize = 50;
+
+/// Keep this many most-recent non-system messages after compaction.
+const COMPACTION_KEEP_RECENT_MESSAGES: usize = 20;
+
+/// Safety cap for compaction source transcript passed to the summarizer.
+const COMPACTION_MAX_SOURCE_CHARS: usize = 12_000;
+
+/// Max characters retained in stored compaction summary.
+const COMPACTION_MAX_SUMMARY_CHARS: usize = 2_000;
+
+/// Messages of one conversation, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct ConversationHistory(Vec<ChatMessage>);
+
+impl ConversationHistory {
+    pub fn new(messages: Vec<ChatMessage>) -> Self {
+        Self(messages)
+    }
+
+    /// An independent copy of the history. Messages pushed to or edited in
+    /// either copy never show up in the other.
+    #[must_use]
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    pub fn into_messages(self) -> Vec<ChatMessage> {
+        self.0
+    }
+}
+
+impl From<Vec<ChatMessage>> for ConversationHistory {
+    fn from(messages: Vec<ChatMessage>) -> Self {
+        Self(messages)
+    }
+}
+
+impl Deref for ConversationHistory {
+    type Target = Vec<ChatMessage>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ConversationHistory {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Trim conversation history to prevent unbounded growth.
+/// Preserves the system prompt (first message if role=system) and the most recent messages.
+pub(crate) fn trim_history(history: &mut Vec<ChatMessage>, max_history: usize) {
+    // Nothing to trim if within limit
+    let has_system = history.first().map_or(false, |m| m.role == "system");
+    let non_system_count = if has_system {
+        history.len() - 1
+    } else {
+        history.len()
+    };
+
+    if non_system_count <= max_history {
+        return;
+    }
+
+    let start = if has_system { 1 } else { 0 };
+    let to_remove = non_system_count - max_history;
+    history.drain(start..start + to_remove);
+}
+
+fn build_compaction_transcript(messages: &[ChatMessage]) -> String {
+    let mut transcript = String::new();
+    for msg in messages {
+        let role = msg.role.to_uppercase();
+        let _ = writeln!(transcript, "{role}: {}", msg.content.trim());
+    }
+
+    if transcript.chars().count() > COMPACTION_MAX_SOURCE_CHARS {
+        truncate_with_ellipsis(&transcript, COMPACTION_MAX_SOURCE_CHARS)
+    } else {
+        transcript
+    }
+}
+
+fn apply_compaction_summary(
+    history: &mut Vec<ChatMessage>,
+    start: usize,
+    compact_end: usize,
+    summary: &str,
+) {
+    let summary_msg = ChatMessage::assistant(format!("[Compaction summary]\n{}", summary.trim()));
+    history.splice(start..compact_end, std::iter::once(summary_msg));
+}
+
+pub(crate) async fn auto_compact_history(
+    history: &mut Vec<ChatMessage>,
+    provider: &dyn Provider,
+    model: &str,
+    max_history: usize,
+) -> Result<bool> {
+    let has_system = history.first().map_or(false, |m| m.role == "system");
+    let non_system_count = if has_system {
+        history.len().saturating_sub(1)
+    } else {
+        history.len()
+    };
+
+    if non_system_count <= max_history {
+        return Ok(false);
+    }
+
+    let keep_recent = COMPACTION_KEEP_RECENT_MESSAGES.min(non_system_count);
+    compact_history(history, provider, model, keep_recent).await
+}
+
+/// Summarize everything but the system prompt and the `keep_recent` newest
+/// messages into a single compaction summary.
+pub(crate) async fn compact_history(
+    history: &mut Vec<ChatMessage>,
+    provider: &dyn Provider,
+    model: &str,
+    keep_recent: usize,
+) -> Result<bool> {
+    let has_system = history.first().map_or(false, |m| m.role == "system");
+    let start = if has_system { 1 } else { 0 };
+    let mut compact_end = history.len().saturating_sub(keep_recent).max(start);
+    // Tool results must stay next to the assistant message that called them.
+    while compact_end > start && compact_end < history.len() && history[compact_end].role == "tool"
+    {
+        compact_end -= 1;
+    }
+    if compact_end == start {
+        return Ok(false);
+    }
+
+    let to_compact: Vec<ChatMessage> = history[start..compact_end].to_vec();
+    let transcript = build_compaction_transcript(&to_compact);
+
+    let summarizer_system = "You are a conversation compaction engine. Summarize older chat history into concise context for future turns. Preserve: user preferences, commitments, decisions, unresolved tasks, key facts. Omit: filler, repeated chit-chat, verbose tool logs. Output plain text bullet points only.";
+
+    let summarizer_user = format!(
+        "Summarize the following conversation history for context preservation. Keep it short (max 12 bullet points).\n\n{}",
+        transcript
+    );
+
+    let summary_raw = provider
+        .chat_with_system(Some(summarizer_system), &summarizer_user, model, 0.2)
+        .await
+        .unwrap_or_else(|_| {
+            // Fallback to deterministic local truncation when summarization fails.
+            truncate_with_ellipsis(&transcript, COMPACTION_MAX_SUMMARY_CHARS)
+        });
+
+    let summary = truncate_with_ellipsis(&summary_raw, COMPACTION_MAX_SUMMARY_CHARS);
+    apply_compaction_summary(history, start, compact_end, &summary);
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(history: &ConversationHistory) -> Vec<&str> {
+        history.iter().map(|m| m.content.as_str()).collect()
+    }
+
+    #[test]
+    fn forks_diverge_after_appending() {
+        let mut original = ConversationHistory::new(vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("plan the release"),
+        ]);
+        let mut cautious = original.fork();
+        let mut fast = original.fork();
+
+        cautious.push(ChatMessage::assistant("Run the full test suite first."));
+        fast.push(ChatMessage::assistant("Tag it now."));
+        fast.push(ChatMessage::user("Go ahead."));
+
+        assert_eq!(contents(&original), ["sys", "plan the release"]);
+        assert_eq!(
+            contents(&cautious),
+            ["sys", "plan the release", "Run the full test suite first."]
+        );
+        assert_eq!(
+            contents(&fast),
+            ["sys", "plan the release", "Tag it now.", "Go ahead."]
+        );
+
+        original.push(ChatMessage::assistant("Picked the cautious plan."));
+        assert_eq!(cautious.len(), 3);
+        assert_eq!(fast.len(), 4);
+    }
+
+    #[test]
+    fn editing_or_trimming_a_fork_leaves_the_original_alone() {
+        let original: ConversationHistory = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("first"),
+            ChatMessage::assistant("second"),
+        ]
+        .into();
+        let mut fork = original.fork();
+
+        fork[1].content = "rewritten".into();
+        fork.truncate(2);
+
+        assert_eq!(contents(&fork), ["sys", "rewritten"]);
+        assert_eq!(contents(&original), ["sys", "first", "second"]);
+        assert_eq!(fork.into_messages().len(), 2);
+    }
+
+    const _: () = {
+        assert!(DEFAULT_MAX_HISTORY_MESSAGES > 0);
+        assert!(DEFAULT_MAX_HISTORY_MESSAGES <= 1000);
+    };
+
+    #[test]
+    fn trim_history_preserves_system_prompt() {
+        let mut history = vec![ChatMessage::system("system prompt")];
+        for i in 0..DEFAULT_MAX_HISTORY_MESSAGES + 20 {
+            history.push(ChatMessage::user(format!("msg {i}")));
+        }
+        let original_len = history.len();
+        assert!(original_len > DEFAULT_MAX_HISTORY_MESSAGES + 1);
+
+        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
+
+        // System prompt preserved
+        assert_eq!(history[0].role, "system");
+        assert_eq!(history[0].content, "system prompt");
+        // Trimmed to limit
+        assert_eq!(history.len(), DEFAULT_MAX_HISTORY_MESSAGES + 1); // +1 for system
+                                                                     // Most recent messages preserved
+        let last = &history[history.len() - 1];
+        assert_eq!(
+            last.content,
+            format!("msg {}", DEFAULT_MAX_HISTORY_MESSAGES + 19)
+        );
+    }
+
+    #[test]
+    fn trim_history_noop_when_within_limit() {
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("hello"),
+            ChatMessage::assistant("hi"),
+        ];
+        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn build_compaction_transcript_formats_roles() {
+        let messages = vec![
+            ChatMessage::user("I like dark mode"),
+            ChatMessage::assistant("Got it"),
+        ];
+        let transcript = build_compaction_transcript(&messages);
+        assert!(transcript.contains("USER: I like dark mode"));
+        assert!(transcript.contains("ASSISTANT: Got it"));
+    }
+
+    #[test]
+    fn apply_compaction_summary_replaces_old_segment() {
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("old 1"),
+            ChatMessage::assistant("old 2"),
+            ChatMessage::user("recent 1"),
+            ChatMessage::assistant("recent 2"),
+        ];
+
+        apply_compaction_summary(&mut history, 1, 3, "- user prefers concise replies");
+
+        assert_eq!(history.len(), 4);
+        assert!(history[1].content.contains("Compaction summary"));
+        assert!(history[2].content.contains("recent 1"));
+        assert!(history[3].content.contains("recent 2"));
+    }
+
+    #[test]
+    fn trim_history_with_no_system_prompt() {
+        // Recovery: History without system prompt should trim correctly
+        let mut history = vec![];
+        for i in 0..DEFAULT_MAX_HISTORY_MESSAGES + 20 {
+            history.push(ChatMessage::user(format!("msg {i}")));
+        }
+        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
+        assert_eq!(history.len(), DEFAULT_MAX_HISTORY_MESSAGES);
+    }
+
+    #[test]
+    fn trim_history_preserves_role_ordering() {
+        // Recovery: After trimming, role ordering should remain consistent
+        let mut history = vec![ChatMessage::system("system")];
+        for i in 0..DEFAULT_MAX_HISTORY_MESSAGES + 10 {
+            history.push(ChatMessage::user(format!("user {i}")));
+            history.push(ChatMessage::assistant(format!("assistant {i}")));
+        }
+        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
+        assert_eq!(history[0].role, "system");
+        assert_eq!(history[history.len() - 1].role, "assistant");
+    }
+
+    #[test]
+    fn trim_history_with_only_system_prompt() {
+        // Recovery: Only system prompt should not be trimmed
+        let mut history = vec![ChatMessage::system("system prompt")];
+        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
+        assert_eq!(history.len(), 1);
+    }
+}
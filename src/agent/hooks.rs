@@ -0,0 +1,324 @@
+//! Post-run hook: report a finished `zeroclaw agent` run to a webhook and/or
+//! a shell command, configured under `[hooks]`.
+//!
+//! Both receive the same JSON report: the POST body for `post_run_url`, and
+//! the `ZEROCLAW_RUN_REPORT` environment variable for `post_run_command`.
+//! Failed deliveries are retried with exponential backoff; a hook that still
+//! fails is logged and never changes the run's result.
+
+use crate::agent::turn_summary::{ToolUsage, TurnSummary};
+use crate::config::HooksConfig;
+use crate::runtime::RuntimeAdapter;
+use crate::security::SecurityPolicy;
+use crate::tools::shell::safe_env_vars;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Duration;
+
+/// Environment variable carrying the report for `post_run_command`.
+const REPORT_ENV_VAR: &str = "ZEROCLAW_RUN_REPORT";
+
+/// What a finished run reports to its hooks.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunReport {
+    /// Session transcript id (file stem), when the run saved one
+    pub session_id: Option<String>,
+    pub provider: String,
+    pub model: String,
+    /// Final response of the last turn
+    pub response: String,
+    /// Tool name → invocation counts across every turn of the run
+    pub tool_calls: BTreeMap<String, ToolUsage>,
+    pub usage: RunUsage,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunUsage {
+    pub provider_round_trips: usize,
+    pub tokens_used: Option<u64>,
+    pub cost_usd: Option<f64>,
+}
+
+impl RunReport {
+    /// Add one turn's tool calls and provider round-trips.
+    pub fn record_turn(&mut self, summary: &TurnSummary) {
+        for (name, usage) in &summary.tools {
+            let total = self.tool_calls.entry(name.clone()).or_default();
+            total.calls += usage.calls;
+            total.failures += usage.failures;
+        }
+        self.usage.provider_round_trips += summary.provider_round_trips;
+    }
+}
+
+/// Deliver `report` to every configured hook.
+pub async fn run_post_run_hooks(
+    config: &HooksConfig,
+    security: &SecurityPolicy,
+    runtime: &dyn RuntimeAdapter,
+    report: &RunReport,
+) {
+    let payload = match serde_json::to_string(report) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!("Failed to serialize run report: {e}");
+            return;
+        }
+    };
+    let backoff = Duration::from_millis(config.retry_backoff_ms);
+    let timeout = Duration::from_secs(config.timeout_secs.max(1));
+
+    if let Some(url) = config.post_run_url.as_deref() {
+        let client = crate::config::build_runtime_proxy_client_with_timeouts(
+            "hook.post_run",
+            config.timeout_secs.max(1),
+            10,
+        );
+        let result = with_retries(config.max_retries, backoff, || {
+            post_report(&client, url, &payload)
+        })
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Post-run webhook failed: {e:#}");
+        }
+    }
+
+    if let Some(command) = config.post_run_command.as_deref() {
+        // The operator configured this command, so it counts as approved, but
+        // the allowlist and high-risk blocking still apply.
+        if let Err(reason) = security.validate_command_execution(command, true) {
+            tracing::warn!("Post-run command refused: {reason}");
+            return;
+        }
+        let result = with_retries(config.max_retries, backoff, || {
+            run_command(security, runtime, command, &payload, timeout)
+        })
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Post-run command failed: {e:#}");
+        }
+    }
+}
+
+/// Call `attempt` until it succeeds, at most `1 + max_retries` times,
+/// doubling the delay between attempts.
+async fn with_retries<F, Fut>(max_retries: u32, backoff: Duration, mut attempt: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut delay = backoff;
+    let mut tries = 0;
+    loop {
+        match attempt().await {
+            Ok(()) => return Ok(()),
+            Err(e) if tries >= max_retries => return Err(e),
+            Err(e) => {
+                tries += 1;
+                tracing::debug!("Post-run hook attempt {tries} failed, retrying: {e:#}");
+                tokio::time::sleep(delay).await;
+                delay = delay.saturating_mul(2);
+            }
+        }
+    }
+}
+
+async fn post_report(client: &reqwest::Client, url: &str, payload: &str) -> Result<()> {
+    let response = client
+        .post(url)
+        .header("content-type", "application/json")
+        .body(payload.to_string())
+        .send()
+        .await
+        .with_context(|| format!("POST {url}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("POST {url} returned {status}");
+    }
+    Ok(())
+}
+
+async fn run_command(
+    security: &SecurityPolicy,
+    runtime: &dyn RuntimeAdapter,
+    command: &str,
+    payload: &str,
+    timeout: Duration,
+) -> Result<()> {
+    let mut cmd = runtime.build_shell_command(command, &security.workspace_dir)?;
+    cmd.env_clear();
+    cmd.kill_on_drop(true);
+    for var in safe_env_vars() {
+        if let Ok(val) = std::env::var(var) {
+            cmd.env(var, val);
+        }
+    }
+    cmd.env(REPORT_ENV_VAR, payload);
+    let output = tokio::time::timeout(timeout, cmd.output())
+        .await
+        .map_err(|_| anyhow::anyhow!("timed out after {}s", timeout.as_secs()))??;
+    if !output.status.success() {
+        anyhow::bail!(
+            "exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::sync::mpsc;
+
+    /// Answer one request per status in `statuses`, forwarding each request body.
+    async fn serve(statuses: Vec<&'static str>) -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let body = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some(head_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..head_end]
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                            })
+                            .unwrap_or(0);
+                        if request.len() >= head_end + 4 + content_length || n == 0 {
+                            break text[head_end + 4..].to_string();
+                        }
+                    }
+                };
+                tx.send(body).unwrap();
+                let response =
+                    format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (format!("http://{addr}/hook"), rx)
+    }
+
+    fn report() -> RunReport {
+        let mut summary = TurnSummary::default();
+        summary.record_tool("shell", &serde_json::json!({"command": "ls"}), true);
+        summary.record_tool("shell", &serde_json::json!({"command": "false"}), false);
+        summary.record_round_trip();
+        summary.record_round_trip();
+        let mut report = RunReport {
+            session_id: Some("2026-01-01-120000-agent-abcd1234".into()),
+            provider: "openrouter".into(),
+            model: "test-model".into(),
+            response: "All done.".into(),
+            duration_ms: 1500,
+            ..RunReport::default()
+        };
+        report.record_turn(&summary);
+        report
+    }
+
+    fn hooks(url: &str) -> HooksConfig {
+        HooksConfig {
+            post_run_url: Some(url.into()),
+            retry_backoff_ms: 1,
+            ..HooksConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn completed_run_posts_report_payload() {
+        let (url, mut bodies) = serve(vec!["200 OK"]).await;
+        let security = SecurityPolicy::default();
+        run_post_run_hooks(&hooks(&url), &security, &NativeRuntime::new(), &report()).await;
+
+        let body: serde_json::Value = serde_json::from_str(&bodies.recv().await.unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "session_id": "2026-01-01-120000-agent-abcd1234",
+                "provider": "openrouter",
+                "model": "test-model",
+                "response": "All done.",
+                "tool_calls": { "shell": { "calls": 2, "failures": 1 } },
+                "usage": {
+                    "provider_round_trips": 2,
+                    "tokens_used": null,
+                    "cost_usd": null
+                },
+                "duration_ms": 1500
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_post_is_retried() {
+        let (url, mut bodies) = serve(vec!["503 Service Unavailable", "200 OK"]).await;
+        let security = SecurityPolicy::default();
+        run_post_run_hooks(&hooks(&url), &security, &NativeRuntime::new(), &report()).await;
+
+        let first = bodies.recv().await.unwrap();
+        let second = bodies.recv().await.unwrap();
+        assert_eq!(first, second, "the retry resends the same report");
+    }
+
+    #[tokio::test]
+    async fn retries_stop_after_max_retries() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let result = with_retries(2, Duration::from_millis(1), || {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async { anyhow::bail!("down") }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn command_hook_receives_report_in_env() {
+        let tmp = tempfile::tempdir().unwrap();
+        let security = SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        };
+        let payload = serde_json::to_string(&report()).unwrap();
+        run_command(
+            &security,
+            &NativeRuntime::new(),
+            "test -n \"$ZEROCLAW_RUN_REPORT\"",
+            &payload,
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn command_hook_outside_the_allowlist_is_not_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let security = SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        };
+        let config = HooksConfig {
+            post_run_command: Some("touch hooked".into()),
+            ..HooksConfig::default()
+        };
+        run_post_run_hooks(&config, &security, &NativeRuntime::new(), &report()).await;
+        assert!(!tmp.path().join("hooked").exists());
+    }
+}
@@ -1,19 +1,37 @@
+use crate::agent::agent::{AgentEvent, AgentEventHandler, AgentUsage, ToolCallRecord};
+use crate::agent::artifacts::{artifact_dir_for_turn, render_artifact, ArtifactRendering};
+use crate::agent::classifier::AutoTemperature;
+use crate::agent::history::compact_history;
+use crate::agent::output::{self, Output};
+use crate::agent::parser::{
+    build_native_assistant_history, is_tool_call_fence, malformed_tool_call_error,
+    malformed_tool_call_feedback, parse_structured_tool_calls, parse_tool_calls, ParsedToolCall,
+};
+use crate::agent::replay::ToolCallReplayStore;
+use crate::agent::turn_summary::TurnSummary;
 use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
-use crate::config::Config;
-use crate::memory::{self, Memory, MemoryCategory};
+use crate::channels::traits::StreamEvent;
+use crate::checkpoints::TurnCheckpoints;
+use crate::config::{Config, RecallScope};
+use crate::cost::{BudgetExhausted, SessionBudget};
+use crate::i18n;
+use crate::memory::{self, Memory};
 use crate::observability::{self, Observer, ObserverEvent};
-use crate::providers::{self, ChatMessage, ChatRequest, Provider, ToolCall};
+use crate::providers::traits::{ProviderCapabilities, StreamOptions};
+use crate::providers::{self, ChatMessage, ChatRequest, FinishReason, Provider};
 use crate::runtime;
-use crate::security::SecurityPolicy;
-use crate::tools::{self, Tool};
-use crate::util::truncate_with_ellipsis;
+use crate::security::{PolicyViolation, SecurityPolicy};
+use crate::tools::{self, Tool, ToolRetryPolicy};
+use crate::util::{estimate_tokens, refresh_datetime_section, LocalTimezone};
 use anyhow::Result;
+use chrono::Utc;
+use futures_util::StreamExt;
 use regex::{Regex, RegexSet};
 use std::fmt::Write;
-use std::io::Write as _;
+use std::io::IsTerminal;
+use std::path::Path;
 use std::sync::{Arc, LazyLock};
-use std::time::Instant;
-use uuid::Uuid;
+use std::time::{Duration, Instant};
 
 /// Minimum characters per chunk when relaying LLM text to a streaming draft.
 const STREAM_CHUNK_MIN_CHARS: usize = 80;
@@ -42,7 +60,7 @@ static SENSITIVE_KV_REGEX: LazyLock<Regex> = LazyLock::new(|| {
 /// Scrub credentials from tool output to prevent accidental exfiltration.
 /// Replaces known credential patterns with a redacted placeholder while preserving
 /// a small prefix for context.
-fn scrub_credentials(input: &str) -> String {
+pub(crate) fn scrub_credentials(input: &str) -> String {
     SENSITIVE_KV_REGEX
         .replace_all(input, |caps: &regex::Captures| {
             let full_match = &caps[0];
@@ -76,20 +94,6 @@ fn scrub_credentials(input: &str) -> String {
         .to_string()
 }
 
-/// Default trigger for auto-compaction when non-system message count exceeds this threshold.
-/// Prefer passing the config-driven value via `run_tool_call_loop`; this constant is only
-/// used when callers omit the parameter.
-const DEFAULT_MAX_HISTORY_MESSAGES: usize = 50;
-
-/// Keep this many most-recent non-system messages after compaction.
-const COMPACTION_KEEP_RECENT_MESSAGES: usize = 20;
-
-/// Safety cap for compaction source transcript passed to the summarizer.
-const COMPACTION_MAX_SOURCE_CHARS: usize = 12_000;
-
-/// Max characters retained in stored compaction summary.
-const COMPACTION_MAX_SUMMARY_CHARS: usize = 2_000;
-
 /// Convert a tool registry to OpenAI function-calling format for native tool support.
 fn tools_to_openai_format(tools_registry: &[Box<dyn Tool>]) -> Vec<serde_json::Value> {
     tools_registry
@@ -107,111 +111,21 @@ fn tools_to_openai_format(tools_registry: &[Box<dyn Tool>]) -> Vec<serde_json::V
         .collect()
 }
 
-fn autosave_memory_key(prefix: &str) -> String {
-    format!("{prefix}_{}", Uuid::new_v4())
-}
-
-/// Trim conversation history to prevent unbounded growth.
-/// Preserves the system prompt (first message if role=system) and the most recent messages.
-fn trim_history(history: &mut Vec<ChatMessage>, max_history: usize) {
-    // Nothing to trim if within limit
-    let has_system = history.first().map_or(false, |m| m.role == "system");
-    let non_system_count = if has_system {
-        history.len() - 1
-    } else {
-        history.len()
-    };
-
-    if non_system_count <= max_history {
-        return;
-    }
-
-    let start = if has_system { 1 } else { 0 };
-    let to_remove = non_system_count - max_history;
-    history.drain(start..start + to_remove);
-}
-
-fn build_compaction_transcript(messages: &[ChatMessage]) -> String {
-    let mut transcript = String::new();
-    for msg in messages {
-        let role = msg.role.to_uppercase();
-        let _ = writeln!(transcript, "{role}: {}", msg.content.trim());
-    }
-
-    if transcript.chars().count() > COMPACTION_MAX_SOURCE_CHARS {
-        truncate_with_ellipsis(&transcript, COMPACTION_MAX_SOURCE_CHARS)
-    } else {
-        transcript
-    }
-}
-
-fn apply_compaction_summary(
-    history: &mut Vec<ChatMessage>,
-    start: usize,
-    compact_end: usize,
-    summary: &str,
-) {
-    let summary_msg = ChatMessage::assistant(format!("[Compaction summary]\n{}", summary.trim()));
-    history.splice(start..compact_end, std::iter::once(summary_msg));
-}
-
-async fn auto_compact_history(
-    history: &mut Vec<ChatMessage>,
-    provider: &dyn Provider,
-    model: &str,
-    max_history: usize,
-) -> Result<bool> {
-    let has_system = history.first().map_or(false, |m| m.role == "system");
-    let non_system_count = if has_system {
-        history.len().saturating_sub(1)
-    } else {
-        history.len()
-    };
-
-    if non_system_count <= max_history {
-        return Ok(false);
-    }
-
-    let start = if has_system { 1 } else { 0 };
-    let keep_recent = COMPACTION_KEEP_RECENT_MESSAGES.min(non_system_count);
-    let compact_count = non_system_count.saturating_sub(keep_recent);
-    if compact_count == 0 {
-        return Ok(false);
-    }
-
-    let compact_end = start + compact_count;
-    let to_compact: Vec<ChatMessage> = history[start..compact_end].to_vec();
-    let transcript = build_compaction_transcript(&to_compact);
-
-    let summarizer_system = "You are a conversation compaction engine. Summarize older chat history into concise context for future turns. Preserve: user preferences, commitments, decisions, unresolved tasks, key facts. Omit: filler, repeated chit-chat, verbose tool logs. Output plain text bullet points only.";
-
-    let summarizer_user = format!(
-        "Summarize the following conversation history for context preservation. Keep it short (max 12 bullet points).\n\n{}",
-        transcript
-    );
-
-    let summary_raw = provider
-        .chat_with_system(Some(summarizer_system), &summarizer_user, model, 0.2)
-        .await
-        .unwrap_or_else(|_| {
-            // Fallback to deterministic local truncation when summarization fails.
-            truncate_with_ellipsis(&transcript, COMPACTION_MAX_SUMMARY_CHARS)
-        });
-
-    let summary = truncate_with_ellipsis(&summary_raw, COMPACTION_MAX_SUMMARY_CHARS);
-    apply_compaction_summary(history, start, compact_end, &summary);
-
-    Ok(true)
-}
-
 /// Build context preamble by searching memory for relevant entries.
 /// Entries with a hybrid score below `min_relevance_score` are dropped to
 /// prevent unrelated memories from bleeding into the conversation.
-async fn build_context(mem: &dyn Memory, user_msg: &str, min_relevance_score: f64) -> String {
+/// `scope` limits recall to `session_id` when one is known.
+pub(crate) async fn build_context(
+    mem: &dyn Memory,
+    user_msg: &str,
+    min_relevance_score: f64,
+    scope: RecallScope,
+    session_id: Option<&str>,
+) -> String {
     let mut context = String::new();
 
     // Pull relevant memories for this message
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
+    if let Ok(entries) = memory::recall_scoped(mem, user_msg, 5, scope, session_id).await {
         let relevant: Vec<_> = entries
             .iter()
             .filter(|e| match e.score {
@@ -234,7 +148,7 @@ async fn build_context(mem: &dyn Memory, user_msg: &str, min_relevance_score: f6
 
 /// Build hardware datasheet context from RAG when peripherals are enabled.
 /// Includes pin-alias lookup (e.g. "red_led" → 13) when query matches, plus retrieved chunks.
-fn build_hardware_context(
+pub(crate) fn build_hardware_context(
     rag: &crate::rag::HardwareRag,
     user_msg: &str,
     boards: &[String],
@@ -277,547 +191,355 @@ fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn Tool>
     tools.iter().find(|t| t.name() == name).map(|t| t.as_ref())
 }
 
-fn parse_arguments_value(raw: Option<&serde_json::Value>) -> serde_json::Value {
-    match raw {
-        Some(serde_json::Value::String(s)) => serde_json::from_str::<serde_json::Value>(s)
-            .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new())),
-        Some(value) => value.clone(),
-        None => serde_json::Value::Object(serde_json::Map::new()),
-    }
-}
-
-fn parse_tool_call_value(value: &serde_json::Value) -> Option<ParsedToolCall> {
-    if let Some(function) = value.get("function") {
-        let name = function
-            .get("name")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        if !name.is_empty() {
-            let arguments = parse_arguments_value(function.get("arguments"));
-            return Some(ParsedToolCall { name, arguments });
-        }
-    }
-
-    let name = value
-        .get("name")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .trim()
-        .to_string();
-
-    if name.is_empty() {
-        return None;
-    }
-
-    let arguments = parse_arguments_value(value.get("arguments"));
-    Some(ParsedToolCall { name, arguments })
-}
-
-fn parse_tool_calls_from_json_value(value: &serde_json::Value) -> Vec<ParsedToolCall> {
-    let mut calls = Vec::new();
-
-    if let Some(tool_calls) = value.get("tool_calls").and_then(|v| v.as_array()) {
-        for call in tool_calls {
-            if let Some(parsed) = parse_tool_call_value(call) {
-                calls.push(parsed);
-            }
-        }
-
-        if !calls.is_empty() {
-            return calls;
-        }
-    }
-
-    if let Some(array) = value.as_array() {
-        for item in array {
-            if let Some(parsed) = parse_tool_call_value(item) {
-                calls.push(parsed);
-            }
-        }
-        return calls;
-    }
+/// Sent once after the model returns an empty (or whitespace-only) answer.
+pub(crate) const EMPTY_RESPONSE_NUDGE: &str =
+    "Your previous response was empty. Please answer the user's request.";
 
-    if let Some(parsed) = parse_tool_call_value(value) {
-        calls.push(parsed);
+/// Error for a model that answered with nothing even after the nudge.
+pub(crate) fn empty_response_error(finish_reason: Option<&FinishReason>) -> anyhow::Error {
+    const EMPTY: &str = "The model returned an empty response twice";
+    match finish_reason {
+        Some(FinishReason::Length) => anyhow::anyhow!(
+            "{EMPTY} (finish reason: length): it ran out of output tokens before answering"
+        ),
+        Some(FinishReason::ContentFilter) => anyhow::anyhow!(
+            "{EMPTY} (finish reason: content_filter): the provider's content filter blocked the answer"
+        ),
+        Some(reason) => anyhow::anyhow!("{EMPTY} (finish reason: {reason})"),
+        None => anyhow::anyhow!(EMPTY),
     }
-
-    calls
-}
-
-const TOOL_CALL_OPEN_TAGS: [&str; 4] = ["<tool_call>", "<toolcall>", "<tool-call>", "<invoke>"];
-
-fn find_first_tag<'a>(haystack: &str, tags: &'a [&'a str]) -> Option<(usize, &'a str)> {
-    tags.iter()
-        .filter_map(|tag| haystack.find(tag).map(|idx| (idx, *tag)))
-        .min_by_key(|(idx, _)| *idx)
 }
 
-fn matching_tool_call_close_tag(open_tag: &str) -> Option<&'static str> {
-    match open_tag {
-        "<tool_call>" => Some("</tool_call>"),
-        "<toolcall>" => Some("</toolcall>"),
-        "<tool-call>" => Some("</tool-call>"),
-        "<invoke>" => Some("</invoke>"),
-        _ => None,
-    }
+/// Models for one turn under `[agent.model_router]`: tool-calling rounds use
+/// `tool_rounds`, and the final answer comes from `final_answer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RoundModels {
+    pub(crate) tool_rounds: String,
+    pub(crate) final_answer: String,
 }
 
-fn extract_first_json_value_with_end(input: &str) -> Option<(serde_json::Value, usize)> {
-    let trimmed = input.trim_start();
-    let trim_offset = input.len().saturating_sub(trimmed.len());
-
-    for (byte_idx, ch) in trimmed.char_indices() {
-        if ch != '{' && ch != '[' {
-            continue;
-        }
-
-        let slice = &trimmed[byte_idx..];
-        let mut stream = serde_json::Deserializer::from_str(slice).into_iter::<serde_json::Value>();
-        if let Some(Ok(value)) = stream.next() {
-            let consumed = stream.byte_offset();
-            if consumed > 0 {
-                return Some((value, trim_offset + byte_idx + consumed));
-            }
+impl RoundModels {
+    /// Resolve the router config against the active model. `None` when the
+    /// router is unset or both roles resolve to the same model.
+    pub(crate) fn from_config(
+        router: &crate::config::ModelRouterConfig,
+        active_model: &str,
+    ) -> Option<Self> {
+        if router.planner.is_none() && router.executor.is_none() {
+            return None;
         }
+        let planner = router.planner.as_deref().unwrap_or(active_model);
+        let executor = router.executor.as_deref().unwrap_or(active_model);
+        let (tool_rounds, final_answer) = match router.final_answer {
+            crate::config::RouterRole::Planner => (executor, planner),
+            crate::config::RouterRole::Executor => (planner, executor),
+        };
+        (tool_rounds != final_answer).then(|| Self {
+            tool_rounds: tool_rounds.to_string(),
+            final_answer: final_answer.to_string(),
+        })
     }
-
-    None
 }
 
-fn strip_leading_close_tags(mut input: &str) -> &str {
-    loop {
-        let trimmed = input.trim_start();
-        if !trimmed.starts_with("</") {
-            return trimmed;
-        }
-
-        let Some(close_end) = trimmed.find('>') else {
-            return "";
-        };
-        input = &trimmed[close_end + 1..];
-    }
+/// How each round's request is sent, chosen once per turn from the
+/// provider's declared capabilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RoundMode {
+    /// Pass tool specs through the provider's native tool API; otherwise
+    /// tool calls are parsed out of the response text.
+    native_tools: bool,
+    /// Stream the response to the delta sink as it arrives. Only tool-less
+    /// turns stream: streamed text carries no native tool calls, and
+    /// prompt-guided tool markup must not reach a channel draft.
+    stream: bool,
+    /// Stream prompt-guided rounds (without relaying the text) and start
+    /// each tool call as soon as its block is complete
+    /// (`[agent] early_tool_dispatch`).
+    early_tools: bool,
 }
 
-/// Extract JSON values from a string.
-///
-/// # Security Warning
-///
-/// This function extracts ANY JSON objects/arrays from the input. It MUST only
-/// be used on content that is already trusted to be from the LLM, such as
-/// content inside `<invoke>` tags where the LLM has explicitly indicated intent
-/// to make a tool call. Do NOT use this on raw user input or content that
-/// could contain prompt injection payloads.
-fn extract_json_values(input: &str) -> Vec<serde_json::Value> {
-    let mut values = Vec::new();
-    let trimmed = input.trim();
-    if trimmed.is_empty() {
-        return values;
-    }
-
-    if let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) {
-        values.push(value);
-        return values;
-    }
-
-    let char_positions: Vec<(usize, char)> = trimmed.char_indices().collect();
-    let mut idx = 0;
-    while idx < char_positions.len() {
-        let (byte_idx, ch) = char_positions[idx];
-        if ch == '{' || ch == '[' {
-            let slice = &trimmed[byte_idx..];
-            let mut stream =
-                serde_json::Deserializer::from_str(slice).into_iter::<serde_json::Value>();
-            if let Some(Ok(value)) = stream.next() {
-                let consumed = stream.byte_offset();
-                if consumed > 0 {
-                    values.push(value);
-                    let next_byte = byte_idx + consumed;
-                    while idx < char_positions.len() && char_positions[idx].0 < next_byte {
-                        idx += 1;
-                    }
-                    continue;
-                }
-            }
+impl RoundMode {
+    fn select(
+        caps: &ProviderCapabilities,
+        has_tools: bool,
+        has_sink: bool,
+        early_tool_dispatch: bool,
+    ) -> Self {
+        let native_tools = caps.native_tool_calling && has_tools;
+        Self {
+            native_tools,
+            stream: caps.streaming && has_sink && !has_tools,
+            early_tools: early_tool_dispatch && caps.streaming && has_tools && !native_tools,
         }
-        idx += 1;
     }
-
-    values
 }
 
-/// Find the end position of a JSON object by tracking balanced braces.
-fn find_json_end(input: &str) -> Option<usize> {
-    let trimmed = input.trim_start();
-    let offset = input.len() - trimmed.len();
-
-    if !trimmed.starts_with('{') {
-        return None;
-    }
-
-    let mut depth = 0;
-    let mut in_string = false;
-    let mut escape_next = false;
-
-    for (i, ch) in trimmed.char_indices() {
-        if escape_next {
-            escape_next = false;
-            continue;
-        }
-
-        match ch {
-            '\\' if in_string => escape_next = true,
-            '"' => in_string = !in_string,
-            '{' if !in_string => depth += 1,
-            '}' if !in_string => {
-                depth -= 1;
-                if depth == 0 {
-                    return Some(offset + i + ch.len_utf8());
-                }
+/// Stream one round through `stream_chat_with_history`, relaying text to
+/// `tx` as it arrives, and assemble the full response.
+async fn stream_round(
+    provider: &dyn Provider,
+    history: &[ChatMessage],
+    model: &str,
+    temperature: f64,
+    tx: &tokio::sync::mpsc::Sender<StreamEvent>,
+) -> Result<providers::ChatResponse> {
+    let mut stream =
+        provider.stream_chat_with_history(history, model, temperature, StreamOptions::new(true));
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if chunk.is_final {
+            // Error chunks are final chunks that carry the message as text.
+            if !chunk.delta.is_empty() {
+                anyhow::bail!("{}", chunk.delta);
             }
-            _ => {}
+            tool_calls = chunk.tool_calls;
+            break;
         }
-    }
-
-    None
-}
-
-/// Parse GLM-style tool calls from response text.
-/// GLM uses proprietary formats like:
-/// - `browser_open/url>https://example.com`
-/// - `shell/command>ls -la`
-/// - `http_request/url>https://api.example.com`
-fn map_glm_tool_alias(tool_name: &str) -> &str {
-    match tool_name {
-        "browser_open" | "browser" | "web_search" | "shell" | "bash" => "shell",
-        "http_request" | "http" => "http_request",
-        _ => tool_name,
-    }
-}
-
-fn build_curl_command(url: &str) -> Option<String> {
-    if !(url.starts_with("http://") || url.starts_with("https://")) {
-        return None;
-    }
-
-    if url.chars().any(char::is_whitespace) {
-        return None;
-    }
-
-    let escaped = url.replace('\'', r#"'\\''"#);
-    Some(format!("curl -s '{}'", escaped))
+        text.push_str(&chunk.delta);
+        // A dropped receiver only stops the relay, not the round.
+        let _ = tx.send(StreamEvent::Text(chunk.delta)).await;
+    }
+    Ok(providers::ChatResponse {
+        text: Some(text),
+        tool_calls,
+        reasoning: None,
+        finish_reason: None,
+    })
 }
 
-fn parse_glm_style_tool_calls(text: &str) -> Vec<(String, serde_json::Value, Option<String>)> {
-    let mut calls = Vec::new();
+/// Closing tags of the tool-call blocks [`EarlyToolCalls`] watches for.
+const TOOL_CALL_CLOSE_TAGS: [&str; 4] =
+    ["</tool_call>", "</toolcall>", "</tool-call>", "</invoke>"];
 
+/// Whether `text` ends inside an open fence of quoted code (```rust, ...),
+/// where a tool-call tag would be shown rather than called.
+fn ends_inside_quoted_fence(text: &str) -> bool {
+    let mut open_quoted = None;
     for line in text.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-
-        // Format: tool_name/param>value or tool_name/{json}
-        if let Some(pos) = line.find('/') {
-            let tool_part = &line[..pos];
-            let rest = &line[pos + 1..];
-
-            if tool_part.chars().all(|c| c.is_alphanumeric() || c == '_') {
-                let tool_name = map_glm_tool_alias(tool_part);
-
-                if let Some(gt_pos) = rest.find('>') {
-                    let param_name = rest[..gt_pos].trim();
-                    let value = rest[gt_pos + 1..].trim();
-
-                    let arguments = match tool_name {
-                        "shell" => {
-                            if param_name == "url" {
-                                let Some(command) = build_curl_command(value) else {
-                                    continue;
-                                };
-                                serde_json::json!({"command": command})
-                            } else if value.starts_with("http://") || value.starts_with("https://")
-                            {
-                                if let Some(command) = build_curl_command(value) {
-                                    serde_json::json!({"command": command})
-                                } else {
-                                    serde_json::json!({"command": value})
-                                }
-                            } else {
-                                serde_json::json!({"command": value})
-                            }
-                        }
-                        "http_request" => {
-                            serde_json::json!({"url": value, "method": "GET"})
-                        }
-                        _ => serde_json::json!({param_name: value}),
-                    };
-
-                    calls.push((tool_name.to_string(), arguments, Some(line.to_string())));
-                    continue;
-                }
-
-                if rest.starts_with('{') {
-                    if let Ok(json_args) = serde_json::from_str::<serde_json::Value>(rest) {
-                        calls.push((tool_name.to_string(), json_args, Some(line.to_string())));
-                    }
-                }
-            }
-        }
-
-        // Plain URL
-        if let Some(command) = build_curl_command(line) {
-            calls.push((
-                "shell".to_string(),
-                serde_json::json!({"command": command}),
-                Some(line.to_string()),
-            ));
+        if let Some(info) = line.trim_start().strip_prefix("```") {
+            open_quoted = match open_quoted {
+                None => Some(!is_tool_call_fence(info.trim())),
+                Some(_) if info.trim().is_empty() => None,
+                Some(quoted) => Some(quoted),
+            };
         }
     }
+    open_quoted == Some(true)
+}
 
-    calls
+/// Finds tool calls whose blocks are complete while the response is still
+/// streaming. Only closed `<tool_call>`-style tags outside quoted code count,
+/// and the text up to the last one is parsed with [`parse_tool_calls`], so
+/// the calls found are the leading calls of the finished response.
+#[derive(Debug, Default)]
+struct EarlyToolCalls {
+    buffer: String,
+    /// End of the last closing tag already parsed.
+    scanned: usize,
+    /// Calls returned so far.
+    found: usize,
 }
 
-/// Parse tool calls from an LLM response that uses XML-style function calling.
-///
-/// Expected format (common with system-prompt-guided tool use):
-/// ```text
-/// <tool_call>
-/// {"name": "shell", "arguments": {"command": "ls"}}
-/// </tool_call>
-/// ```
-///
-/// Also accepts common tag variants (`<toolcall>`, `<tool-call>`) for model
-/// compatibility.
-///
-/// Also supports JSON with `tool_calls` array from OpenAI-format responses.
-fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
-    let mut text_parts = Vec::new();
-    let mut calls = Vec::new();
-    let mut remaining = response;
-
-    // First, try to parse as OpenAI-style JSON response with tool_calls array
-    // This handles providers like Minimax that return tool_calls in native JSON format
-    if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(response.trim()) {
-        calls = parse_tool_calls_from_json_value(&json_value);
-        if !calls.is_empty() {
-            // If we found tool_calls, extract any content field as text
-            if let Some(content) = json_value.get("content").and_then(|v| v.as_str()) {
-                if !content.trim().is_empty() {
-                    text_parts.push(content.trim().to_string());
-                }
-            }
-            return (text_parts.join("\n"), calls);
+impl EarlyToolCalls {
+    /// Add `delta` and return the calls completed by it, with the index
+    /// each will have in the finished response.
+    fn push(&mut self, delta: &str) -> Vec<(usize, ParsedToolCall)> {
+        self.buffer.push_str(delta);
+        let Some(end) = TOOL_CALL_CLOSE_TAGS
+            .iter()
+            .filter_map(|tag| self.buffer.rfind(tag).map(|idx| idx + tag.len()))
+            .max()
+        else {
+            return Vec::new();
+        };
+        if end <= self.scanned || ends_inside_quoted_fence(&self.buffer[..end]) {
+            return Vec::new();
         }
+        self.scanned = end;
+        let (_, calls) = parse_tool_calls(&self.buffer[..end]);
+        let first = self.found;
+        self.found = self.found.max(calls.len());
+        calls.into_iter().enumerate().skip(first).collect()
     }
+}
 
-    // Fall back to XML-style tool-call tag parsing.
-    while let Some((start, open_tag)) = find_first_tag(remaining, &TOOL_CALL_OPEN_TAGS) {
-        // Everything before the tag is text
-        let before = &remaining[..start];
-        if !before.trim().is_empty() {
-            text_parts.push(before.trim().to_string());
-        }
-
-        let Some(close_tag) = matching_tool_call_close_tag(open_tag) else {
-            break;
-        };
+/// A tool call started while its response was still streaming.
+struct EarlyExecution {
+    name: String,
+    arguments: serde_json::Value,
+    result: Result<crate::tools::ToolResult>,
+    duration: Duration,
+}
 
-        let after_open = &remaining[start + open_tag.len()..];
-        if let Some(close_idx) = after_open.find(close_tag) {
-            let inner = &after_open[..close_idx];
-            let mut parsed_any = false;
-            let json_values = extract_json_values(inner);
-            for value in json_values {
-                let parsed_calls = parse_tool_calls_from_json_value(&value);
-                if !parsed_calls.is_empty() {
-                    parsed_any = true;
-                    calls.extend(parsed_calls);
+/// Stream one prompt-guided round and run each tool call as soon as its
+/// block is complete, one at a time and in order, while the rest of the
+/// response is read. Dispatch stops at the first call `dispatchable`
+/// refuses (one that needs approval, say); it and later calls are left to
+/// the normal tool loop. Returns the full response and the early results by
+/// call index.
+async fn stream_round_with_early_tools<'a>(
+    provider: &dyn Provider,
+    history: &[ChatMessage],
+    model: &str,
+    temperature: f64,
+    tools_registry: &'a [Box<dyn Tool>],
+    checkpoints: Option<&TurnCheckpoints>,
+    tool_retry: ToolRetryPolicy,
+    dispatchable: impl Fn(usize, &str) -> bool,
+) -> Result<(
+    providers::ChatResponse,
+    std::collections::HashMap<usize, EarlyExecution>,
+)> {
+    let (call_tx, mut call_rx) =
+        tokio::sync::mpsc::unbounded_channel::<(usize, ParsedToolCall, &'a dyn Tool)>();
+
+    let read = async move {
+        let mut stream = provider.stream_chat_with_history(
+            history,
+            model,
+            temperature,
+            StreamOptions::new(true),
+        );
+        let mut detector = EarlyToolCalls::default();
+        let mut dispatching = true;
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            if chunk.is_final {
+                if !chunk.delta.is_empty() {
+                    anyhow::bail!("{}", chunk.delta);
                 }
+                tool_calls = chunk.tool_calls;
+                break;
             }
-
-            if !parsed_any {
-                tracing::warn!("Malformed <tool_call> JSON: expected tool-call object in tag body");
-            }
-
-            remaining = &after_open[close_idx + close_tag.len()..];
-        } else {
-            if let Some(json_end) = find_json_end(after_open) {
-                if let Ok(value) =
-                    serde_json::from_str::<serde_json::Value>(&after_open[..json_end])
-                {
-                    let parsed_calls = parse_tool_calls_from_json_value(&value);
-                    if !parsed_calls.is_empty() {
-                        calls.extend(parsed_calls);
-                        remaining = strip_leading_close_tags(&after_open[json_end..]);
-                        continue;
+            text.push_str(&chunk.delta);
+            for (index, call) in detector.push(&chunk.delta) {
+                let tool = find_tool(tools_registry, &call.name);
+                dispatching = dispatching && dispatchable(index, &call.name);
+                match tool.filter(|_| dispatching) {
+                    Some(tool) => {
+                        tracing::debug!(
+                            tool = call.name.as_str(),
+                            index,
+                            "Dispatching tool call before the stream finished"
+                        );
+                        let _ = call_tx.send((index, call, tool));
                     }
+                    None => dispatching = false,
                 }
             }
-
-            if let Some((value, consumed_end)) = extract_first_json_value_with_end(after_open) {
-                let parsed_calls = parse_tool_calls_from_json_value(&value);
-                if !parsed_calls.is_empty() {
-                    calls.extend(parsed_calls);
-                    remaining = strip_leading_close_tags(&after_open[consumed_end..]);
-                    continue;
-                }
-            }
-
-            remaining = &remaining[start..];
-            break;
-        }
-    }
-
-    // If XML tags found nothing, try markdown code blocks with tool_call language.
-    // Models behind OpenRouter sometimes output ```tool_call ... ``` or hybrid
-    // ```tool_call ... </tool_call> instead of structured API calls or XML tags.
-    if calls.is_empty() {
-        static MD_TOOL_CALL_RE: LazyLock<Regex> = LazyLock::new(|| {
-            Regex::new(
-                r"(?s)```(?:tool[_-]?call|invoke)\s*\n(.*?)(?:```|</tool[_-]?call>|</toolcall>|</invoke>)",
-            )
-            .unwrap()
-        });
-        let mut md_text_parts: Vec<String> = Vec::new();
-        let mut last_end = 0;
-
-        for cap in MD_TOOL_CALL_RE.captures_iter(response) {
-            let full_match = cap.get(0).unwrap();
-            let before = &response[last_end..full_match.start()];
-            if !before.trim().is_empty() {
-                md_text_parts.push(before.trim().to_string());
-            }
-            let inner = &cap[1];
-            let json_values = extract_json_values(inner);
-            for value in json_values {
-                let parsed_calls = parse_tool_calls_from_json_value(&value);
-                calls.extend(parsed_calls);
-            }
-            last_end = full_match.end();
-        }
-
-        if !calls.is_empty() {
-            let after = &response[last_end..];
-            if !after.trim().is_empty() {
-                md_text_parts.push(after.trim().to_string());
-            }
-            text_parts = md_text_parts;
-            remaining = "";
         }
-    }
+        // Closing the channel lets the runner finish.
+        drop(call_tx);
+        Ok(providers::ChatResponse {
+            text: Some(text),
+            tool_calls,
+            reasoning: None,
+            finish_reason: None,
+        })
+    };
 
-    // GLM-style tool calls (browser_open/url>https://..., shell/command>ls, etc.)
-    if calls.is_empty() {
-        let glm_calls = parse_glm_style_tool_calls(remaining);
-        if !glm_calls.is_empty() {
-            let mut cleaned_text = remaining.to_string();
-            for (name, args, raw) in &glm_calls {
-                calls.push(ParsedToolCall {
-                    name: name.clone(),
-                    arguments: args.clone(),
-                });
-                if let Some(r) = raw {
-                    cleaned_text = cleaned_text.replace(r, "");
-                }
-            }
-            if !cleaned_text.trim().is_empty() {
-                text_parts.push(cleaned_text.trim().to_string());
+    let run = async move {
+        let mut done = std::collections::HashMap::new();
+        while let Some((index, call, tool)) = call_rx.recv().await {
+            if let Some(cp) = checkpoints {
+                cp.before_tool(tool);
             }
-            remaining = "";
+            let start = Instant::now();
+            let result = tools::execute_with_retry(tool, &call.arguments, tool_retry).await;
+            done.insert(
+                index,
+                EarlyExecution {
+                    name: call.name,
+                    arguments: call.arguments,
+                    result,
+                    duration: start.elapsed(),
+                },
+            );
         }
-    }
-
-    // SECURITY: We do NOT fall back to extracting arbitrary JSON from the response
-    // here. That would enable prompt injection attacks where malicious content
-    // (e.g., in emails, files, or web pages) could include JSON that mimics a
-    // tool call. Tool calls MUST be explicitly wrapped in either:
-    // 1. OpenAI-style JSON with a "tool_calls" array
-    // 2. ZeroClaw tool-call tags (<tool_call>, <toolcall>, <tool-call>)
-    // 3. Markdown code blocks with tool_call/toolcall/tool-call language
-    // 4. Explicit GLM line-based call formats (e.g. `shell/command>...`)
-    // This ensures only the LLM's intentional tool calls are executed.
-
-    // Remaining text after last tool call
-    if !remaining.trim().is_empty() {
-        text_parts.push(remaining.trim().to_string());
-    }
-
-    (text_parts.join("\n"), calls)
-}
-
-fn parse_structured_tool_calls(tool_calls: &[ToolCall]) -> Vec<ParsedToolCall> {
-    tool_calls
-        .iter()
-        .map(|call| ParsedToolCall {
-            name: call.name.clone(),
-            arguments: serde_json::from_str::<serde_json::Value>(&call.arguments)
-                .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new())),
-        })
-        .collect()
-}
-
-/// Build assistant history entry in JSON format for native tool-call APIs.
-/// `convert_messages` in the OpenRouter provider parses this JSON to reconstruct
-/// the proper `NativeMessage` with structured `tool_calls`.
-fn build_native_assistant_history(text: &str, tool_calls: &[ToolCall]) -> String {
-    let calls_json: Vec<serde_json::Value> = tool_calls
-        .iter()
-        .map(|tc| {
-            serde_json::json!({
-                "id": tc.id,
-                "name": tc.name,
-                "arguments": tc.arguments,
-            })
-        })
-        .collect();
-
-    let content = if text.trim().is_empty() {
-        serde_json::Value::Null
-    } else {
-        serde_json::Value::String(text.trim().to_string())
+        done
     };
 
-    serde_json::json!({
-        "content": content,
-        "tool_calls": calls_json,
-    })
-    .to_string()
+    let (response, early) = tokio::join!(read, run);
+    Ok((response?, early))
 }
 
-fn build_assistant_history_with_tool_calls(text: &str, tool_calls: &[ToolCall]) -> String {
-    let mut parts = Vec::new();
-
-    if !text.trim().is_empty() {
-        parts.push(text.trim().to_string());
-    }
-
-    for call in tool_calls {
-        let arguments = serde_json::from_str::<serde_json::Value>(&call.arguments)
-            .unwrap_or_else(|_| serde_json::Value::String(call.arguments.clone()));
-        let payload = serde_json::json!({
-            "id": call.id,
-            "name": call.name,
-            "arguments": arguments,
-        });
-        parts.push(format!("<tool_call>\n{payload}\n</tool_call>"));
-    }
-
-    parts.join("\n")
+/// Optional settings for one [`run_tool_call_loop`] turn. The default is a
+/// silent, non-interactive turn with the default iteration limit, no cap on
+/// tool calls per reply, output normalization on, and no approval,
+/// streaming, checkpoints, summary, model routing or budget.
+pub(crate) struct ToolLoopOptions<'a> {
+    /// Suppress progress output on the terminal.
+    pub silent: bool,
+    /// Ask before running supervised tools.
+    pub approval: Option<&'a ApprovalManager>,
+    /// Channel the turn runs on; `"cli"` prints progress and prompts for
+    /// approval on the terminal.
+    pub channel_name: &'a str,
+    /// Most provider calls in the turn; `0` uses the default.
+    pub max_tool_iterations: usize,
+    /// Most tool calls run from one reply; `0` means no limit.
+    pub max_tool_calls_per_turn: usize,
+    /// Receives streamed text and progress while the turn runs.
+    pub on_delta: Option<tokio::sync::mpsc::Sender<StreamEvent>>,
+    pub checkpoints: Option<&'a TurnCheckpoints>,
+    pub summary: Option<&'a mut TurnSummary>,
+    pub round_models: Option<&'a RoundModels>,
+    pub normalize_output: bool,
+    pub early_tool_dispatch: bool,
+    pub budget: Option<&'a SessionBudget>,
+    /// Retries for transient tool failures (`[agent] tool_max_retries`).
+    pub tool_retry: ToolRetryPolicy,
+    /// Where artifacts are saved for a text-only model, normally the run's
+    /// scratch directory. `None` uses a temporary directory removed when
+    /// the turn ends.
+    pub artifact_dir: Option<&'a Path>,
+    /// Parse tool calls out of the response text even when the provider has
+    /// a native tool API (`[agent] tool_dispatcher = "xml"`).
+    pub prompt_guided_tools: bool,
+    /// Answers matching tool calls instead of running the tool.
+    pub replay: Option<&'a ToolCallReplayStore>,
+    /// Receives reasoning, prose written alongside tool calls and each
+    /// finished tool call.
+    pub on_event: Option<&'a AgentEventHandler>,
+    /// Collects every tool call made, including skipped and denied ones.
+    pub tool_records: Option<&'a mut Vec<ToolCallRecord>>,
+    /// Adds the turn's provider calls and estimated tokens.
+    pub usage: Option<&'a mut AgentUsage>,
 }
 
-#[derive(Debug)]
-struct ParsedToolCall {
-    name: String,
-    arguments: serde_json::Value,
+impl Default for ToolLoopOptions<'_> {
+    fn default() -> Self {
+        Self {
+            silent: true,
+            approval: None,
+            channel_name: "",
+            max_tool_iterations: 0,
+            max_tool_calls_per_turn: 0,
+            on_delta: None,
+            checkpoints: None,
+            summary: None,
+            round_models: None,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            budget: None,
+            tool_retry: ToolRetryPolicy::default(),
+            artifact_dir: None,
+            prompt_guided_tools: false,
+            replay: None,
+            on_event: None,
+            tool_records: None,
+            usage: None,
+        }
+    }
 }
 
 /// Execute a single turn of the agent loop: send messages, parse tool calls,
 /// execute tools, and loop until the LLM produces a final text response.
-/// When `silent` is true, suppresses stdout (for channel use).
-#[allow(clippy::too_many_arguments)]
-pub(crate) async fn agent_turn(
+pub(crate) async fn run_tool_call_loop(
     provider: &dyn Provider,
     history: &mut Vec<ChatMessage>,
     tools_registry: &[Box<dyn Tool>],
@@ -825,43 +547,29 @@ pub(crate) async fn agent_turn(
     provider_name: &str,
     model: &str,
     temperature: f64,
-    silent: bool,
-    max_tool_iterations: usize,
+    options: ToolLoopOptions<'_>,
 ) -> Result<String> {
-    run_tool_call_loop(
-        provider,
-        history,
-        tools_registry,
-        observer,
-        provider_name,
-        model,
-        temperature,
+    let ToolLoopOptions {
         silent,
-        None,
-        "channel",
+        approval,
+        channel_name,
         max_tool_iterations,
-        None,
-    )
-    .await
-}
-
-/// Execute a single turn of the agent loop: send messages, parse tool calls,
-/// execute tools, and loop until the LLM produces a final text response.
-#[allow(clippy::too_many_arguments)]
-pub(crate) async fn run_tool_call_loop(
-    provider: &dyn Provider,
-    history: &mut Vec<ChatMessage>,
-    tools_registry: &[Box<dyn Tool>],
-    observer: &dyn Observer,
-    provider_name: &str,
-    model: &str,
-    temperature: f64,
-    silent: bool,
-    approval: Option<&ApprovalManager>,
-    channel_name: &str,
-    max_tool_iterations: usize,
-    on_delta: Option<tokio::sync::mpsc::Sender<String>>,
-) -> Result<String> {
+        max_tool_calls_per_turn,
+        on_delta,
+        checkpoints,
+        mut summary,
+        round_models,
+        normalize_output,
+        early_tool_dispatch,
+        budget,
+        tool_retry,
+        artifact_dir,
+        prompt_guided_tools,
+        replay,
+        on_event,
+        mut tool_records,
+        mut usage,
+    } = options;
     let max_iterations = if max_tool_iterations == 0 {
         DEFAULT_MAX_TOOL_ITERATIONS
     } else {
@@ -870,45 +578,185 @@ pub(crate) async fn run_tool_call_loop(
 
     let tool_specs: Vec<crate::tools::ToolSpec> =
         tools_registry.iter().map(|tool| tool.spec()).collect();
-    let use_native_tools = provider.supports_native_tools() && !tool_specs.is_empty();
+    let mut capabilities = provider.capabilities();
+    if prompt_guided_tools {
+        capabilities.native_tool_calling = false;
+    }
+    let mode = RoundMode::select(
+        &capabilities,
+        !tool_specs.is_empty(),
+        on_delta.is_some(),
+        early_tool_dispatch && replay.is_none(),
+    );
+    let emit = |event: AgentEvent| {
+        if let Some(handler) = on_event {
+            handler(&event);
+        }
+    };
+    let use_vision = capabilities.vision;
+    let mut turn_artifacts: Option<tempfile::TempDir> = None;
+    let out = Output::for_channel(channel_name, silent);
+    let mut empty_response_retried = false;
+    let mut malformed_tool_call_retried = false;
+    let mut final_round = false;
+    let mut context_compacted = false;
+    // Latest text the model wrote alongside tool calls, returned if the
+    // session budget runs out before a final answer.
+    let mut partial_answer = String::new();
 
     for _iteration in 0..max_iterations {
+        let model = match round_models {
+            Some(models) if final_round => models.final_answer.as_str(),
+            Some(models) => models.tool_rounds.as_str(),
+            None => model,
+        };
         observer.record_event(&ObserverEvent::LlmRequest {
             provider: provider_name.to_string(),
             model: model.to_string(),
             messages_count: history.len(),
         });
 
+        let input_chars: usize = history.iter().map(|m| m.content.chars().count()).sum();
+        if let Some(budget) = budget {
+            if let Err(exhausted) =
+                check_session_budget(budget, model, input_chars, channel_name, observer, &out)
+            {
+                let notice = i18n::tf("budget.exhausted", &[("spent", &exhausted)]);
+                tracing::warn!(%exhausted, "Session budget exhausted; stopping the tool loop");
+                let answer = if partial_answer.is_empty() {
+                    notice
+                } else {
+                    format!("{partial_answer}\n\n{notice}")
+                };
+                history.push(ChatMessage::assistant(answer.clone()));
+                return Ok(answer);
+            }
+        }
         let llm_started_at = Instant::now();
+        if let Some(summary) = summary.as_deref_mut() {
+            summary.record_round_trip();
+        }
+        if let Some(usage) = usage.as_deref_mut() {
+            usage.provider_calls += 1;
+            usage.input_tokens += estimate_tokens(input_chars);
+        }
 
         // Unified path via Provider::chat so provider-specific native tool logic
         // (OpenAI/Anthropic/OpenRouter/compatible adapters) is honored.
-        let request_tools = if use_native_tools {
+        let request_tools = if mode.native_tools {
             Some(tool_specs.as_slice())
         } else {
             None
         };
+        out.debug(format!(
+            "→ {provider_name}/{model}: {} message(s), {} tool spec(s), temperature {temperature}",
+            history.len(),
+            request_tools.map_or(0, <[_]>::len)
+        ));
 
-        let (response_text, parsed_text, tool_calls, assistant_history_content, native_tool_calls) =
-            match provider
-                .chat(
-                    ChatRequest {
-                        messages: history,
-                        tools: request_tools,
-                    },
+        let mut early_results = std::collections::HashMap::new();
+        let chat_result = match on_delta.as_ref().filter(|_| mode.stream) {
+            Some(tx) => stream_round(provider, history, model, temperature, tx).await,
+            None if mode.early_tools => {
+                // Calls that would be skipped or need a prompt wait for the
+                // full response, like everything after them.
+                let dispatchable = |index: usize, name: &str| {
+                    (max_tool_calls_per_turn == 0 || index < max_tool_calls_per_turn)
+                        && approval.is_none_or(|mgr| !mgr.needs_approval(name))
+                };
+                stream_round_with_early_tools(
+                    provider,
+                    history,
                     model,
                     temperature,
+                    tools_registry,
+                    checkpoints,
+                    tool_retry,
+                    dispatchable,
                 )
                 .await
-            {
+                .map(|(response, early)| {
+                    early_results = early;
+                    response
+                })
+            }
+            None => {
+                provider
+                    .chat(
+                        ChatRequest {
+                            messages: history,
+                            tools: request_tools,
+                        },
+                        model,
+                        temperature,
+                    )
+                    .await
+            }
+        };
+
+        if let Some(snapshot) = crate::providers::rate_limit::take_latest() {
+            observer.record_event(&ObserverEvent::RateLimit {
+                provider: snapshot.provider,
+                remaining_requests: snapshot.status.remaining_requests,
+                limit_requests: snapshot.status.limit_requests,
+                remaining_tokens: snapshot.status.remaining_tokens,
+                limit_tokens: snapshot.status.limit_tokens,
+            });
+        }
+        for transition in crate::providers::circuit_breaker::take_transitions() {
+            observer.record_event(&ObserverEvent::CircuitBreaker {
+                provider: transition.provider,
+                state: transition.state.as_str().to_string(),
+            });
+        }
+
+        let finish_reason;
+        let (response_text, parsed_text, tool_calls, assistant_history_content, native_tool_calls) =
+            match chat_result {
                 Ok(resp) => {
+                    let output_chars = resp.text_or_empty().chars().count()
+                        + resp
+                            .tool_calls
+                            .iter()
+                            .map(|call| call.arguments.chars().count())
+                            .sum::<usize>();
                     observer.record_event(&ObserverEvent::LlmResponse {
                         provider: provider_name.to_string(),
                         model: model.to_string(),
                         duration: llm_started_at.elapsed(),
                         success: true,
                         error_message: None,
+                        input_tokens: Some(estimate_tokens(input_chars)),
+                        output_tokens: Some(estimate_tokens(output_chars)),
                     });
+                    if let Some(budget) = budget {
+                        budget.record(
+                            model,
+                            estimate_tokens(input_chars),
+                            estimate_tokens(output_chars),
+                        );
+                    }
+                    if let Some(usage) = usage.as_deref_mut() {
+                        usage.output_tokens += estimate_tokens(output_chars);
+                    }
+
+                    // Reasoning is shown on request but never parsed for tool
+                    // calls, kept in history or returned as the response.
+                    let mut resp = resp.with_reasoning_separated();
+                    if normalize_output {
+                        resp = resp.with_normalized_text();
+                    }
+                    if let Some(reasoning) = resp.reasoning.as_deref() {
+                        out.thinking(reasoning);
+                        emit(AgentEvent::Thinking(reasoning.to_string()));
+                    }
+
+                    out.debug(format!(
+                        "← {}ms: {} char(s), {} native tool call(s)",
+                        llm_started_at.elapsed().as_millis(),
+                        resp.text_or_empty().chars().count(),
+                        resp.tool_calls.len()
+                    ));
 
                     let response_text = resp.text_or_empty().to_string();
                     let mut calls = parse_structured_tool_calls(&resp.tool_calls);
@@ -930,6 +778,7 @@ pub(crate) async fn run_tool_call_loop(
                         build_native_assistant_history(&response_text, &resp.tool_calls)
                     };
 
+                    finish_reason = resp.finish_reason;
                     let native_calls = resp.tool_calls;
                     (
                         response_text,
@@ -946,8 +795,24 @@ pub(crate) async fn run_tool_call_loop(
                         duration: llm_started_at.elapsed(),
                         success: false,
                         error_message: Some(crate::providers::sanitize_api_error(&e.to_string())),
+                        input_tokens: None,
+                        output_tokens: None,
                     });
-                    return Err(e);
+                    // The history outgrew the model's context window: summarize
+                    // the older half and retry once with the shorter history.
+                    if !context_compacted && providers::is_context_length_error(&e) {
+                        context_compacted = true;
+                        let keep_recent = (history.len() / 2).max(1);
+                        if compact_history(history, provider, model, keep_recent).await? {
+                            tracing::warn!(
+                                messages = history.len(),
+                                "Context length exceeded; compacted history and retrying"
+                            );
+                            out.progress(i18n::t("context.compacted"));
+                            continue;
+                        }
+                    }
+                    return Err(providers::error::with_user_hint(e, provider_name, model));
                 }
             };
 
@@ -957,42 +822,129 @@ pub(crate) async fn run_tool_call_loop(
             parsed_text
         };
 
+        // A tool call the model meant to make but wrote as broken JSON is
+        // not an answer: say what was wrong and let it try once more.
+        if tool_calls.is_empty() && native_tool_calls.is_empty() && !malformed_tool_call_retried {
+            if let Some(detail) = malformed_tool_call_error(&response_text) {
+                malformed_tool_call_retried = true;
+                tracing::warn!(error = %detail, "Malformed tool call JSON; asking the model to resend");
+                history.push(ChatMessage::assistant(assistant_history_content));
+                history.push(ChatMessage::user(malformed_tool_call_feedback(&detail)));
+                continue;
+            }
+        }
+
+        // Under a model router, the tool-round model stopping is the cue for
+        // the final-answer model to answer from the same history.
+        if tool_calls.is_empty() && round_models.is_some() && !final_round {
+            final_round = true;
+            continue;
+        }
+
+        // An empty answer is never final: nudge once, then fail with the
+        // provider's finish reason so truncation and refusals are visible.
+        if tool_calls.is_empty() && display_text.trim().is_empty() {
+            if empty_response_retried {
+                return Err(empty_response_error(finish_reason.as_ref()));
+            }
+            empty_response_retried = true;
+            tracing::warn!(
+                finish_reason = ?finish_reason,
+                "Provider returned an empty response; retrying with a nudge"
+            );
+            history.push(ChatMessage::user(EMPTY_RESPONSE_NUDGE));
+            continue;
+        }
+
+        if let Some(plan) = summary.as_deref_mut().and_then(|s| s.plan.as_mut()) {
+            if let Some(step) = plan.observe(&display_text) {
+                out.progress(plan.step_line(step));
+            }
+            if tool_calls.is_empty() {
+                plan.finish();
+            }
+        }
+
         if tool_calls.is_empty() {
             // No tool calls — this is the final response.
-            // If a streaming sender is provided, relay the text in small chunks
-            // so the channel can progressively update the draft message.
-            if let Some(ref tx) = on_delta {
+            // If a streaming sender is provided and the text was not already
+            // streamed, relay it in small chunks so the channel can
+            // progressively update the draft message.
+            if let Some(tx) = on_delta.as_ref().filter(|_| !mode.stream) {
                 // Split on whitespace boundaries, accumulating chunks of at least
                 // STREAM_CHUNK_MIN_CHARS characters for progressive draft updates.
                 let mut chunk = String::new();
                 for word in display_text.split_inclusive(char::is_whitespace) {
                     chunk.push_str(word);
                     if chunk.len() >= STREAM_CHUNK_MIN_CHARS
-                        && tx.send(std::mem::take(&mut chunk)).await.is_err()
+                        && tx
+                            .send(StreamEvent::Text(std::mem::take(&mut chunk)))
+                            .await
+                            .is_err()
                     {
                         break; // receiver dropped
                     }
                 }
                 if !chunk.is_empty() {
-                    let _ = tx.send(chunk).await;
+                    let _ = tx.send(StreamEvent::Text(chunk)).await;
                 }
             }
             history.push(ChatMessage::assistant(response_text.clone()));
             return Ok(display_text);
         }
-
-        // Print any text the LLM produced alongside tool calls (unless silent)
-        if !silent && !display_text.is_empty() {
-            print!("{display_text}");
-            let _ = std::io::stdout().flush();
+        final_round = false;
+
+        // Prose written alongside tool calls ("I'll check the file first") is
+        // an intermediate update: show it before the calls run. It stays in
+        // history as part of the assistant message below.
+        let update = display_text.trim();
+        if !update.is_empty() {
+            partial_answer = update.to_string();
+            let tool_names: Vec<String> = tool_calls.iter().map(|call| call.name.clone()).collect();
+            out.rationale(&tool_names.join(", "), update);
+            tracing::debug!(tools = ?tool_names, rationale = update, "Tool selection rationale");
+            out.progress(update);
+            if channel_name == "cli" {
+                output::record_partial_response(update);
+            }
+            if let Some(summary) = summary.as_deref_mut() {
+                summary.record_update(update);
+                summary.record_rationale(tool_names, update);
+            }
+            if let Some(ref tx) = on_delta {
+                let _ = tx.send(StreamEvent::Text(format!("{update}\n\n"))).await;
+            }
+            emit(AgentEvent::Text(update.to_string()));
         }
+        out.progress(i18n::tf("tool.executing", &[("count", &tool_calls.len())]));
 
         // Execute each tool call and build results.
         // `individual_results` tracks per-call output so that native-mode history
         // can emit one `role: tool` message per tool call with the correct ID.
         let mut tool_results = String::new();
         let mut individual_results: Vec<String> = Vec::new();
-        for call in &tool_calls {
+        let mut image_attachments: Vec<String> = Vec::new();
+        if max_tool_calls_per_turn > 0 && tool_calls.len() > max_tool_calls_per_turn {
+            tracing::warn!(
+                requested = tool_calls.len(),
+                limit = max_tool_calls_per_turn,
+                "Too many tool calls in one response; skipping the rest"
+            );
+        }
+        for (index, call) in tool_calls.iter().enumerate() {
+            // Calls past the per-turn cap are answered without running.
+            if max_tool_calls_per_turn > 0 && index >= max_tool_calls_per_turn {
+                let skipped = skipped_tool_call_notice(max_tool_calls_per_turn);
+                record_tool_call(call, &skipped, false, on_event, &mut tool_records);
+                individual_results.push(skipped.clone());
+                let _ = writeln!(
+                    tool_results,
+                    "<tool_result name=\"{}\">\n{skipped}\n</tool_result>",
+                    call.name
+                );
+                continue;
+            }
+
             // ── Approval hook ────────────────────────────────
             if let Some(mgr) = approval {
                 if mgr.needs_approval(&call.name) {
@@ -1011,7 +963,12 @@ pub(crate) async fn run_tool_call_loop(
                     mgr.record_decision(&call.name, &call.arguments, decision, channel_name);
 
                     if decision == ApprovalResponse::No {
+                        if let Some(summary) = summary.as_deref_mut() {
+                            summary.record_tool(&call.name, &call.arguments, false);
+                            summary.record_policy_denial(&call.name);
+                        }
                         let denied = "Denied by user.".to_string();
+                        record_tool_call(call, &denied, false, on_event, &mut tool_records);
                         individual_results.push(denied.clone());
                         let _ = writeln!(
                             tool_results,
@@ -1023,18 +980,69 @@ pub(crate) async fn run_tool_call_loop(
                 }
             }
 
+            // A call already run while the response streamed is only used
+            // if the finished response still has it at this position.
+            let early = early_results
+                .remove(&index)
+                .filter(|early| early.name == call.name && early.arguments == call.arguments);
+            let replayed = replay.and_then(|store| store.lookup(&call.name, &call.arguments));
+
+            if let Some(cp) = checkpoints.filter(|_| early.is_none() && replayed.is_none()) {
+                if let Some(tool) = find_tool(tools_registry, &call.name) {
+                    cp.before_tool(tool);
+                }
+            }
+
             observer.record_event(&ObserverEvent::ToolCallStart {
                 tool: call.name.clone(),
             });
+            let scrubbed_args = scrub_credentials(&call.arguments.to_string());
+            tracing::debug!(
+                tool = call.name.as_str(),
+                source = ?call.source,
+                "Running parsed tool call"
+            );
+            out.verbose(format!("  → {} {scrubbed_args}", call.name));
+            if let Some(ref tx) = on_delta {
+                let _ = tx
+                    .send(StreamEvent::ToolCall {
+                        name: call.name.clone(),
+                        arguments: serde_json::from_str(&scrubbed_args)
+                            .unwrap_or(serde_json::Value::Null),
+                    })
+                    .await;
+            }
             let start = Instant::now();
-            let result = if let Some(tool) = find_tool(tools_registry, &call.name) {
-                match tool.execute(call.arguments.clone()).await {
-                    Ok(r) => {
+            let mut artifacts = Vec::new();
+            let mut succeeded = false;
+            let mut denied = false;
+            let mut duration = Duration::ZERO;
+            let mut result = if let Some(tool) = find_tool(tools_registry, &call.name) {
+                let outcome = match (replayed, early) {
+                    (Some(replayed), _) => {
+                        tracing::debug!(tool = call.name.as_str(), "Using replayed tool result");
+                        Ok(replayed)
+                    }
+                    (None, Some(early)) => {
+                        duration = early.duration;
+                        early.result
+                    }
+                    (None, None) => {
+                        let outcome =
+                            tools::execute_with_retry(tool, &call.arguments, tool_retry).await;
+                        duration = start.elapsed();
+                        outcome
+                    }
+                };
+                match outcome {
+                    Ok(mut r) => {
                         observer.record_event(&ObserverEvent::ToolCall {
                             tool: call.name.clone(),
-                            duration: start.elapsed(),
+                            duration,
                             success: r.success,
                         });
+                        succeeded = r.success;
+                        artifacts = std::mem::take(&mut r.artifacts);
                         if r.success {
                             scrub_credentials(&r.output)
                         } else {
@@ -1044,16 +1052,52 @@ pub(crate) async fn run_tool_call_loop(
                     Err(e) => {
                         observer.record_event(&ObserverEvent::ToolCall {
                             tool: call.name.clone(),
-                            duration: start.elapsed(),
+                            duration,
                             success: false,
                         });
+                        denied = is_policy_denial(&e);
                         format!("Error executing {}: {e}", call.name)
                     }
                 }
             } else {
                 format!("Unknown tool: {}", call.name)
             };
+            out.verbose(format!(
+                "  ← {} {} in {}ms",
+                call.name,
+                if succeeded { "ok" } else { "failed" },
+                duration.as_millis()
+            ));
+            if let Some(ref tx) = on_delta {
+                let _ = tx
+                    .send(StreamEvent::ToolResult {
+                        name: call.name.clone(),
+                        success: succeeded,
+                    })
+                    .await;
+            }
+            if let Some(summary) = summary.as_deref_mut() {
+                summary.record_tool(&call.name, &call.arguments, succeeded);
+                if denied {
+                    summary.record_policy_denial(&call.name);
+                }
+            }
+
+            for artifact in &artifacts {
+                let rendered = artifact_dir_for_turn(artifact_dir, &mut turn_artifacts)
+                    .and_then(|dir| render_artifact(artifact, use_vision, dir));
+                match rendered {
+                    Ok(ArtifactRendering::Attachment(marker)) => image_attachments.push(marker),
+                    Ok(ArtifactRendering::Reference(note)) => {
+                        let _ = write!(result, "\n{note}");
+                    }
+                    Err(e) => {
+                        let _ = write!(result, "\n[Artifact unavailable: {e}]");
+                    }
+                }
+            }
 
+            record_tool_call(call, &result, succeeded, on_event, &mut tool_records);
             individual_results.push(result.clone());
             let _ = writeln!(
                 tool_results,
@@ -1062,6 +1106,13 @@ pub(crate) async fn run_tool_call_loop(
             );
         }
 
+        for early in early_results.values() {
+            tracing::warn!(
+                tool = early.name.as_str(),
+                "Tool call run while streaming is not in the finished response; result discarded"
+            );
+        }
+
         // Add assistant message with tool calls + tool results to history.
         // Native mode: use JSON-structured messages so convert_messages() can
         // reconstruct proper OpenAI-format tool_calls and tool result messages.
@@ -1078,9 +1129,117 @@ pub(crate) async fn run_tool_call_loop(
                 history.push(ChatMessage::tool(tool_msg.to_string()));
             }
         }
+        if !image_attachments.is_empty() {
+            history.push(ChatMessage::user(format!(
+                "[Tool artifacts]\n{}",
+                image_attachments.join("\n")
+            )));
+        }
+    }
+
+    Err(ToolIterationsExhausted(max_iterations).into())
+}
+
+/// The model was still calling tools when the turn ran out of iterations.
+#[derive(Debug, thiserror::Error)]
+#[error("Agent exceeded maximum tool iterations ({0})")]
+pub(crate) struct ToolIterationsExhausted(pub(crate) usize);
+
+/// Report a finished (or skipped) tool call to the turn's event handler and
+/// tool call records.
+fn record_tool_call(
+    call: &ParsedToolCall,
+    output: &str,
+    success: bool,
+    on_event: Option<&AgentEventHandler>,
+    records: &mut Option<&mut Vec<ToolCallRecord>>,
+) {
+    if on_event.is_none() && records.is_none() {
+        return;
+    }
+    let record = ToolCallRecord {
+        name: call.name.clone(),
+        arguments: call.arguments.clone(),
+        output: output.to_string(),
+        success,
+    };
+    if let Some(handler) = on_event {
+        handler(&AgentEvent::ToolCall(record.clone()));
+    }
+    if let Some(records) = records {
+        records.push(record);
+    }
+}
+
+/// Result fed back for a tool call beyond `[agent] max_tool_calls_per_turn`.
+pub(crate) fn skipped_tool_call_notice(limit: usize) -> String {
+    format!(
+        "Skipped: at most {limit} tool calls run per response. Request this call again in your next response if it is still needed."
+    )
+}
+
+/// Check the session budget before a provider call sending `input_chars` of
+/// history. On the CLI the user may raise an exhausted budget for the rest
+/// of the session; otherwise the exhaustion is returned.
+fn check_session_budget(
+    budget: &SessionBudget,
+    model: &str,
+    input_chars: usize,
+    channel_name: &str,
+    observer: &dyn Observer,
+    out: &Output,
+) -> std::result::Result<(), BudgetExhausted> {
+    let input_tokens = estimate_tokens(input_chars);
+    while let Err(exhausted) = budget.check(model, input_tokens) {
+        observer.record_event(&ObserverEvent::BudgetExhausted {
+            tokens_used: exhausted.tokens_used,
+            max_tokens: exhausted.max_tokens,
+            cost_usd: exhausted.cost_usd,
+            max_cost_usd: exhausted.max_cost_usd,
+        });
+        if channel_name != "cli" || !std::io::stdin().is_terminal() {
+            return Err(exhausted);
+        }
+        out.prompt(format!(
+            "\n{}",
+            i18n::tf("budget.raise", &[("spent", &exhausted)])
+        ));
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_err()
+            || !i18n::is_answer("answer.yes", &answer)
+        {
+            return Err(exhausted);
+        }
+        budget.raise();
     }
+    Ok(())
+}
+
+/// Whether a tool failed because the security policy denied the call, as
+/// opposed to a runtime error or a rate limit.
+fn is_policy_denial(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<PolicyViolation>()
+        .is_some_and(PolicyViolation::is_denial)
+}
 
-    anyhow::bail!("Agent exceeded maximum tool iterations ({max_iterations})")
+/// Drop every tool not named in `allowed`; an empty list keeps them all.
+/// Unknown names are an error so a typo doesn't silently remove a tool.
+pub(crate) fn retain_tools(
+    tools_registry: &mut Vec<Box<dyn Tool>>,
+    allowed: &[String],
+) -> Result<()> {
+    if allowed.is_empty() {
+        return Ok(());
+    }
+    if let Some(unknown) = allowed
+        .iter()
+        .find(|name| !tools_registry.iter().any(|t| t.name() == name.as_str()))
+    {
+        anyhow::bail!("Unknown tool '{unknown}'");
+    }
+    tools_registry.retain(|t| allowed.iter().any(|name| name == t.name()));
+    Ok(())
 }
 
 /// Build the tool instruction block for the system prompt so the LLM knows
@@ -1113,643 +1272,260 @@ pub(crate) fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> Strin
     instructions
 }
 
-#[allow(clippy::too_many_lines)]
-pub async fn run(
-    config: Config,
-    message: Option<String>,
-    provider_override: Option<String>,
-    model_override: Option<String>,
-    temperature: f64,
-    peripheral_overrides: Vec<String>,
-) -> Result<String> {
-    // ── Wire up agnostic subsystems ──────────────────────────────
-    let base_observer = observability::create_observer(&config.observability);
-    let observer: Arc<dyn Observer> = Arc::from(base_observer);
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
-
-    // ── Memory (the brain) ────────────────────────────────────────
-    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
-        &config.memory,
-        Some(&config.storage.provider.config),
-        &config.workspace_dir,
-        config.api_key.as_deref(),
-    )?);
-    tracing::info!(backend = mem.name(), "Memory initialized");
-
-    // ── Peripherals (merge peripheral tools into registry) ─
-    if !peripheral_overrides.is_empty() {
-        tracing::info!(
-            peripherals = ?peripheral_overrides,
-            "Peripheral overrides from CLI (config boards take precedence)"
-        );
-    }
-
-    // ── Tools (including memory tools and peripherals) ────────────
-    let (composio_key, composio_entity_id) = if config.composio.enabled {
-        (
-            config.composio.api_key.as_deref(),
-            Some(config.composio.entity_id.as_str()),
-        )
-    } else {
-        (None, None)
-    };
-    let mut tools_registry = tools::all_tools_with_runtime(
-        Arc::new(config.clone()),
-        &security,
-        runtime,
-        mem.clone(),
-        composio_key,
-        composio_entity_id,
-        &config.browser,
-        &config.http_request,
-        &config.workspace_dir,
-        &config.agents,
-        config.api_key.as_deref(),
-        &config,
-    );
-
-    let peripheral_tools: Vec<Box<dyn Tool>> =
-        crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
-    if !peripheral_tools.is_empty() {
-        tracing::info!(count = peripheral_tools.len(), "Peripheral tools added");
-        tools_registry.extend(peripheral_tools);
-    }
-
-    // ── Resolve provider ─────────────────────────────────────────
+/// Provider and model for a run: CLI flags, then the config (user config
+/// merged with the workspace's `.zeroclaw.toml`), then built-in defaults.
+pub(crate) fn resolve_provider_and_model<'a>(
+    config: &'a Config,
+    provider_override: Option<&'a str>,
+    model_override: Option<&'a str>,
+) -> (&'a str, &'a str) {
     let provider_name = provider_override
-        .as_deref()
         .or(config.default_provider.as_deref())
         .unwrap_or("openrouter");
-
     let model_name = model_override
-        .as_deref()
         .or(config.default_model.as_deref())
         .unwrap_or("anthropic/claude-sonnet-4");
+    (provider_name, model_name)
+}
 
-    let provider: Box<dyn Provider> = providers::create_routed_provider(
-        provider_name,
-        config.api_key.as_deref(),
-        config.api_url.as_deref(),
-        &config.reliability,
-        &config.model_routes,
-        model_name,
-    )?;
-
-    observer.record_event(&ObserverEvent::AgentStart {
-        provider: provider_name.to_string(),
-        model: model_name.to_string(),
-    });
-
-    // ── Hardware RAG (datasheet retrieval when peripherals + datasheet_dir) ──
-    let hardware_rag: Option<crate::rag::HardwareRag> = config
-        .peripherals
-        .datasheet_dir
-        .as_ref()
-        .filter(|d| !d.trim().is_empty())
-        .map(|dir| crate::rag::HardwareRag::load(&config.workspace_dir, dir.trim()))
-        .and_then(Result::ok)
-        .filter(|r: &crate::rag::HardwareRag| !r.is_empty());
-    if let Some(ref rag) = hardware_rag {
-        tracing::info!(chunks = rag.len(), "Hardware RAG loaded");
-    }
-
-    let board_names: Vec<String> = config
-        .peripherals
-        .boards
-        .iter()
-        .map(|b| b.board.clone())
-        .collect();
-
-    // ── Build system prompt from workspace MD files (OpenClaw framework) ──
-    let skills = crate::skills::load_skills(&config.workspace_dir);
-    let mut tool_descs: Vec<(&str, &str)> = vec![
-        (
-            "shell",
-            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
-        ),
-        (
-            "file_read",
-            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
-        ),
-        (
-            "file_write",
-            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
-        ),
-        (
-            "memory_store",
-            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
-        ),
-        (
-            "memory_recall",
-            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
-        ),
-        (
-            "memory_forget",
-            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
-        ),
-    ];
-    tool_descs.push((
-        "cron_add",
-        "Create a cron job. Supports schedule kinds: cron, at, every; and job types: shell or agent.",
-    ));
-    tool_descs.push((
-        "cron_list",
-        "List all cron jobs with schedule, status, and metadata.",
-    ));
-    tool_descs.push(("cron_remove", "Remove a cron job by job_id."));
-    tool_descs.push((
-        "cron_update",
-        "Patch a cron job (schedule, enabled, command/prompt, model, delivery, session_target).",
-    ));
-    tool_descs.push((
-        "cron_run",
-        "Force-run a cron job immediately and record a run history entry.",
-    ));
-    tool_descs.push(("cron_runs", "Show recent run history for a cron job."));
-    tool_descs.push((
-        "screenshot",
-        "Capture a screenshot of the current screen. Returns file path and base64-encoded PNG. Use when: visual verification, UI inspection, debugging displays.",
-    ));
-    tool_descs.push((
-        "image_info",
-        "Read image file metadata (format, dimensions, size) and optionally base64-encode it. Use when: inspecting images, preparing visual data for analysis.",
-    ));
-    if config.browser.enabled {
-        tool_descs.push((
-            "browser_open",
-            "Open approved HTTPS URLs in Brave Browser (allowlist-only, no scraping)",
-        ));
-    }
-    if config.composio.enabled {
-        tool_descs.push((
-            "composio",
-            "Execute actions on 1000+ apps via Composio (Gmail, Notion, GitHub, Slack, etc.). Use action='list' to discover, 'execute' to run (optionally with connected_account_id), 'connect' to OAuth.",
-        ));
-    }
-    tool_descs.push((
-        "schedule",
-        "Manage scheduled tasks (create/list/get/cancel/pause/resume). Supports recurring cron and one-shot delays.",
-    ));
-    if !config.agents.is_empty() {
-        tool_descs.push((
-            "delegate",
-            "Delegate a sub-task to a specialized agent. Use when: task needs different model/capability, or to parallelize work.",
-        ));
-    }
-    if config.peripherals.enabled && !config.peripherals.boards.is_empty() {
-        tool_descs.push((
-            "gpio_read",
-            "Read GPIO pin value (0 or 1) on connected hardware (STM32, Arduino). Use when: checking sensor/button state, LED status.",
-        ));
-        tool_descs.push((
-            "gpio_write",
-            "Set GPIO pin high (1) or low (0) on connected hardware. Use when: turning LED on/off, controlling actuators.",
-        ));
-        tool_descs.push((
-            "arduino_upload",
-            "Upload agent-generated Arduino sketch. Use when: user asks for 'make a heart', 'blink pattern', or custom LED behavior on Arduino. You write the full .ino code; ZeroClaw compiles and uploads it. Pin 13 = built-in LED on Uno.",
-        ));
-        tool_descs.push((
-            "hardware_memory_map",
-            "Return flash and RAM address ranges for connected hardware. Use when: user asks for 'upper and lower memory addresses', 'memory map', or 'readable addresses'.",
-        ));
-        tool_descs.push((
-            "hardware_board_info",
-            "Return full board info (chip, architecture, memory map) for connected hardware. Use when: user asks for 'board info', 'what board do I have', 'connected hardware', 'chip info', or 'what hardware'.",
-        ));
-        tool_descs.push((
-            "hardware_memory_read",
-            "Read actual memory/register values from Nucleo via USB. Use when: user asks to 'read register values', 'read memory', 'dump lower memory 0-126', 'give address and value'. Params: address (hex, default 0x20000000), length (bytes, default 128).",
-        ));
-        tool_descs.push((
-            "hardware_capabilities",
-            "Query connected hardware for reported GPIO pins and LED pin. Use when: user asks what pins are available.",
-        ));
+/// Process a single message through the full agent (with tools, peripherals, memory).
+/// Used by channels (Telegram, Discord, etc.) to enable hardware and tool use.
+pub async fn process_message(config: Config, message: &str) -> Result<String> {
+    SingleShot::new(config).await?.run(message).await
+}
+
+/// Everything the single-shot agent path sets up before it can answer a
+/// message. Built once and reused for many independent messages by
+/// `zeroclaw batch`; each message still starts from a fresh history.
+pub(crate) struct SingleShot {
+    config: Config,
+    observer: Arc<dyn Observer>,
+    mem: Arc<dyn Memory>,
+    tools_registry: Vec<Box<dyn Tool>>,
+    provider: Arc<dyn Provider>,
+    provider_name: String,
+    model_name: String,
+    system_prompt: String,
+    timezone: LocalTimezone,
+    hardware_rag: Option<crate::rag::HardwareRag>,
+    board_names: Vec<String>,
+}
+
+impl SingleShot {
+    pub(crate) async fn new(config: Config) -> Result<Self> {
+        Self::with_tools(config, &[]).await
     }
-    let bootstrap_max_chars = if config.agent.compact_context {
-        Some(6000)
-    } else {
-        None
-    };
-    let mut system_prompt = crate::channels::build_system_prompt(
-        &config.workspace_dir,
-        model_name,
-        &tool_descs,
-        &skills,
-        Some(&config.identity),
-        bootstrap_max_chars,
-    );
 
-    // Append structured tool-use instructions with schemas
-    system_prompt.push_str(&build_tool_instructions(&tools_registry));
+    /// Like [`SingleShot::new`], but only the tools named in `allowed` are
+    /// registered and described to the model; an empty list keeps them all.
+    pub(crate) async fn with_tools(config: Config, allowed: &[String]) -> Result<Self> {
+        let observer: Arc<dyn Observer> =
+            Arc::from(observability::create_observer(&config.observability));
+        let runtime: Arc<dyn runtime::RuntimeAdapter> =
+            Arc::from(runtime::create_runtime(&config.runtime)?);
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+        let timezone = LocalTimezone::from_config(config.agent.timezone.as_deref());
+        let (mem, memory_degraded) = memory::create_session_memory(
+            &config.memory,
+            Some(&config.storage.provider.config),
+            &config.workspace_dir,
+            config.api_key.as_deref(),
+            timezone,
+        )?;
+        let mem: Arc<dyn Memory> = Arc::from(mem);
+
+        let (composio_key, composio_entity_id) = if config.composio.enabled {
+            (
+                config.composio.api_key.as_deref(),
+                Some(config.composio.entity_id.as_str()),
+            )
+        } else {
+            (None, None)
+        };
+        let mut tools_registry = tools::all_tools_with_runtime(
+            Arc::new(config.clone()),
+            &security,
+            runtime,
+            mem.clone(),
+            composio_key,
+            composio_entity_id,
+            &config.browser,
+            &config.http_request,
+            &config.workspace_dir,
+            &config.agents,
+            config.api_key.as_deref(),
+            &config,
+        );
+        let peripheral_tools: Vec<Box<dyn Tool>> =
+            crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
+        tools_registry.extend(peripheral_tools);
+        if memory_degraded {
+            tools_registry.retain(|tool| !tools::is_memory_tool(tool.name()));
+        }
+        retain_tools(&mut tools_registry, allowed)?;
+        let tools_registry = tools::PromptInjectionFilter::wrap_all(
+            tools_registry,
+            config.security.tool_output_injection,
+        );
 
-    // ── Approval manager (supervised mode) ───────────────────────
-    let approval_manager = ApprovalManager::from_config(&config.autonomy);
+        let provider_name = config
+            .default_provider
+            .clone()
+            .unwrap_or_else(|| "openrouter".into());
+        let model_name = config
+            .default_model
+            .clone()
+            .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
+        let provider_options = providers::ProviderRuntimeOptions::default()
+            .with_provider_config(&config.provider)
+            .with_observability_config(&config.observability)?;
+        let provider: Arc<dyn Provider> =
+            Arc::from(providers::create_routed_provider_with_options(
+                &provider_name,
+                config.api_key.as_deref(),
+                config.api_url.as_deref(),
+                &config.reliability,
+                &config.model_routes,
+                &model_name,
+                &provider_options,
+            )?);
+
+        let hardware_rag: Option<crate::rag::HardwareRag> = config
+            .peripherals
+            .datasheet_dir
+            .as_ref()
+            .filter(|d| !d.trim().is_empty())
+            .map(|dir| crate::rag::HardwareRag::load(&config.workspace_dir, dir.trim()))
+            .and_then(Result::ok)
+            .filter(|r: &crate::rag::HardwareRag| !r.is_empty());
+        let board_names: Vec<String> = config
+            .peripherals
+            .boards
+            .iter()
+            .map(|b| b.board.clone())
+            .collect();
 
-    // ── Execute ──────────────────────────────────────────────────
-    let start = Instant::now();
+        let skills = crate::skills::load_skills(&config.workspace_dir, &config.skills.dir_paths());
+        let tool_descs = tools::tool_descriptions(&tools_registry)?;
+        let bootstrap_max_chars = if config.agent.compact_context {
+            Some(6000)
+        } else {
+            None
+        };
+        let mut system_prompt = crate::channels::build_system_prompt(
+            &config.workspace_dir,
+            &model_name,
+            &tool_descs,
+            &skills,
+            Some(&config.identity),
+            Some(&config.agent),
+            bootstrap_max_chars,
+            &timezone,
+        );
+        system_prompt.push_str(&build_tool_instructions(&tools_registry));
+
+        Ok(Self {
+            config,
+            observer,
+            mem,
+            tools_registry,
+            provider,
+            provider_name,
+            model_name,
+            system_prompt,
+            timezone,
+            hardware_rag,
+            board_names,
+        })
+    }
 
-    let mut final_output = String::new();
+    /// The provider built from config; share it to keep one rate limiter and
+    /// key-rotation state across concurrent runs.
+    pub(crate) fn provider(&self) -> Arc<dyn Provider> {
+        Arc::clone(&self.provider)
+    }
 
-    if let Some(msg) = message {
-        // Auto-save user message to memory
-        if config.memory.auto_save {
-            let user_key = autosave_memory_key("user_msg");
-            let _ = mem
-                .store(&user_key, &msg, MemoryCategory::Conversation, None)
-                .await;
-        }
+    pub(crate) async fn run(&self, message: &str) -> Result<String> {
+        self.run_with(self.provider.as_ref(), message, None).await
+    }
 
-        // Inject memory + hardware RAG context into user message
-        let mem_context =
-            build_context(mem.as_ref(), &msg, config.memory.min_relevance_score).await;
+    /// Answer `message` from a fresh history using `provider`, recording tool
+    /// use into `summary` when given.
+    pub(crate) async fn run_with(
+        &self,
+        provider: &dyn Provider,
+        message: &str,
+        summary: Option<&mut TurnSummary>,
+    ) -> Result<String> {
+        let config = &self.config;
+        let mem_context = build_context(
+            self.mem.as_ref(),
+            message,
+            config.memory.min_relevance_score,
+            config.memory.recall_scope,
+            None,
+        )
+        .await;
         let rag_limit = if config.agent.compact_context { 2 } else { 5 };
-        let hw_context = hardware_rag
+        let hw_context = self
+            .hardware_rag
             .as_ref()
-            .map(|r| build_hardware_context(r, &msg, &board_names, rag_limit))
+            .map(|r| build_hardware_context(r, message, &self.board_names, rag_limit))
             .unwrap_or_default();
         let context = format!("{mem_context}{hw_context}");
         let enriched = if context.is_empty() {
-            msg.clone()
+            message.to_string()
         } else {
-            format!("{context}{msg}")
+            format!("{context}{message}")
         };
 
+        let mut system_prompt = self.system_prompt.clone();
+        refresh_datetime_section(&mut system_prompt, &self.timezone, Utc::now());
         let mut history = vec![
-            ChatMessage::system(&system_prompt),
+            ChatMessage::system(system_prompt),
             ChatMessage::user(&enriched),
         ];
 
-        let response = run_tool_call_loop(
-            provider.as_ref(),
+        let temperature = AutoTemperature::from_config(&config.agent)
+            .map_or(config.default_temperature, |auto| {
+                auto.select(config.default_temperature, message)
+            });
+        let round_models = RoundModels::from_config(&config.agent.model_router, &self.model_name);
+        // Every message starts a fresh history, so it is its own session.
+        let budget = SessionBudget::from_config(&config.limits, &config.cost);
+        run_tool_call_loop(
+            provider,
             &mut history,
-            &tools_registry,
-            observer.as_ref(),
-            provider_name,
-            model_name,
+            &self.tools_registry,
+            self.observer.as_ref(),
+            &self.provider_name,
+            &self.model_name,
             temperature,
-            false,
-            Some(&approval_manager),
-            "cli",
-            config.agent.max_tool_iterations,
-            None,
-        )
-        .await?;
-        final_output = response.clone();
-        println!("{response}");
-        observer.record_event(&ObserverEvent::TurnComplete);
-
-        // Auto-save assistant response to daily log
-        if config.memory.auto_save {
-            let summary = truncate_with_ellipsis(&response, 100);
-            let response_key = autosave_memory_key("assistant_resp");
-            let _ = mem
-                .store(&response_key, &summary, MemoryCategory::Daily, None)
-                .await;
-        }
-    } else {
-        println!("🦀 ZeroClaw Interactive Mode");
-        println!("Type /help for commands.\n");
-        let cli = crate::channels::CliChannel::new();
-
-        // Persistent conversation history across turns
-        let mut history = vec![ChatMessage::system(&system_prompt)];
-
-        loop {
-            print!("> ");
-            let _ = std::io::stdout().flush();
-
-            let mut input = String::new();
-            match std::io::stdin().read_line(&mut input) {
-                Ok(0) => break,
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("\nError reading input: {e}\n");
-                    break;
-                }
-            }
-
-            let user_input = input.trim().to_string();
-            if user_input.is_empty() {
-                continue;
-            }
-            match user_input.as_str() {
-                "/quit" | "/exit" => break,
-                "/help" => {
-                    println!("Available commands:");
-                    println!("  /help        Show this help message");
-                    println!("  /clear /new  Clear conversation history");
-                    println!("  /quit /exit  Exit interactive mode\n");
-                    continue;
-                }
-                "/clear" | "/new" => {
-                    println!(
-                        "This will clear the current conversation and delete all session memory."
-                    );
-                    println!("Core memories (long-term facts/preferences) will be preserved.");
-                    print!("Continue? [y/N] ");
-                    let _ = std::io::stdout().flush();
-
-                    let mut confirm = String::new();
-                    if std::io::stdin().read_line(&mut confirm).is_err() {
-                        continue;
-                    }
-                    if !matches!(confirm.trim().to_lowercase().as_str(), "y" | "yes") {
-                        println!("Cancelled.\n");
-                        continue;
-                    }
-
-                    history.clear();
-                    history.push(ChatMessage::system(&system_prompt));
-                    // Clear conversation and daily memory
-                    let mut cleared = 0;
-                    for category in [MemoryCategory::Conversation, MemoryCategory::Daily] {
-                        let entries = mem.list(Some(&category), None).await.unwrap_or_default();
-                        for entry in entries {
-                            if mem.forget(&entry.key).await.unwrap_or(false) {
-                                cleared += 1;
-                            }
-                        }
-                    }
-                    if cleared > 0 {
-                        println!("Conversation cleared ({cleared} memory entries removed).\n");
-                    } else {
-                        println!("Conversation cleared.\n");
-                    }
-                    continue;
-                }
-                _ => {}
-            }
-
-            // Auto-save conversation turns
-            if config.memory.auto_save {
-                let user_key = autosave_memory_key("user_msg");
-                let _ = mem
-                    .store(&user_key, &user_input, MemoryCategory::Conversation, None)
-                    .await;
-            }
-
-            // Inject memory + hardware RAG context into user message
-            let mem_context =
-                build_context(mem.as_ref(), &user_input, config.memory.min_relevance_score).await;
-            let rag_limit = if config.agent.compact_context { 2 } else { 5 };
-            let hw_context = hardware_rag
-                .as_ref()
-                .map(|r| build_hardware_context(r, &user_input, &board_names, rag_limit))
-                .unwrap_or_default();
-            let context = format!("{mem_context}{hw_context}");
-            let enriched = if context.is_empty() {
-                user_input.clone()
-            } else {
-                format!("{context}{user_input}")
-            };
-
-            history.push(ChatMessage::user(&enriched));
-
-            let response = match run_tool_call_loop(
-                provider.as_ref(),
-                &mut history,
-                &tools_registry,
-                observer.as_ref(),
-                provider_name,
-                model_name,
-                temperature,
-                false,
-                Some(&approval_manager),
-                "cli",
-                config.agent.max_tool_iterations,
-                None,
-            )
-            .await
-            {
-                Ok(resp) => resp,
-                Err(e) => {
-                    eprintln!("\nError: {e}\n");
-                    continue;
-                }
-            };
-            final_output = response.clone();
-            if let Err(e) = crate::channels::Channel::send(
-                &cli,
-                &crate::channels::traits::SendMessage::new(format!("\n{response}\n"), "user"),
-            )
-            .await
-            {
-                eprintln!("\nError sending CLI response: {e}\n");
-            }
-            observer.record_event(&ObserverEvent::TurnComplete);
-
-            // Auto-compaction before hard trimming to preserve long-context signal.
-            if let Ok(compacted) = auto_compact_history(
-                &mut history,
-                provider.as_ref(),
-                model_name,
-                config.agent.max_history_messages,
-            )
-            .await
-            {
-                if compacted {
-                    println!("🧹 Auto-compaction complete");
-                }
-            }
-
-            // Hard cap as a safety net.
-            trim_history(&mut history, config.agent.max_history_messages);
-
-            if config.memory.auto_save {
-                let summary = truncate_with_ellipsis(&response, 100);
-                let response_key = autosave_memory_key("assistant_resp");
-                let _ = mem
-                    .store(&response_key, &summary, MemoryCategory::Daily, None)
-                    .await;
-            }
-        }
-    }
-
-    let duration = start.elapsed();
-    observer.record_event(&ObserverEvent::AgentEnd {
-        provider: provider_name.to_string(),
-        model: model_name.to_string(),
-        duration,
-        tokens_used: None,
-        cost_usd: None,
-    });
-
-    Ok(final_output)
-}
-
-/// Process a single message through the full agent (with tools, peripherals, memory).
-/// Used by channels (Telegram, Discord, etc.) to enable hardware and tool use.
-pub async fn process_message(config: Config, message: &str) -> Result<String> {
-    let observer: Arc<dyn Observer> =
-        Arc::from(observability::create_observer(&config.observability));
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
-    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
-        &config.memory,
-        Some(&config.storage.provider.config),
-        &config.workspace_dir,
-        config.api_key.as_deref(),
-    )?);
-
-    let (composio_key, composio_entity_id) = if config.composio.enabled {
-        (
-            config.composio.api_key.as_deref(),
-            Some(config.composio.entity_id.as_str()),
+            ToolLoopOptions {
+                channel_name: "channel",
+                max_tool_iterations: config.agent.max_tool_iterations,
+                max_tool_calls_per_turn: config.agent.max_tool_calls_per_turn,
+                summary,
+                round_models: round_models.as_ref(),
+                normalize_output: config.agent.normalize_output,
+                early_tool_dispatch: config.agent.early_tool_dispatch,
+                tool_retry: ToolRetryPolicy::from_config(&config.agent),
+                budget: budget.as_ref(),
+                ..ToolLoopOptions::default()
+            },
         )
-    } else {
-        (None, None)
-    };
-    let mut tools_registry = tools::all_tools_with_runtime(
-        Arc::new(config.clone()),
-        &security,
-        runtime,
-        mem.clone(),
-        composio_key,
-        composio_entity_id,
-        &config.browser,
-        &config.http_request,
-        &config.workspace_dir,
-        &config.agents,
-        config.api_key.as_deref(),
-        &config,
-    );
-    let peripheral_tools: Vec<Box<dyn Tool>> =
-        crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
-    tools_registry.extend(peripheral_tools);
-
-    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
-    let model_name = config
-        .default_model
-        .clone()
-        .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
-    let provider: Box<dyn Provider> = providers::create_routed_provider(
-        provider_name,
-        config.api_key.as_deref(),
-        config.api_url.as_deref(),
-        &config.reliability,
-        &config.model_routes,
-        &model_name,
-    )?;
-
-    let hardware_rag: Option<crate::rag::HardwareRag> = config
-        .peripherals
-        .datasheet_dir
-        .as_ref()
-        .filter(|d| !d.trim().is_empty())
-        .map(|dir| crate::rag::HardwareRag::load(&config.workspace_dir, dir.trim()))
-        .and_then(Result::ok)
-        .filter(|r: &crate::rag::HardwareRag| !r.is_empty());
-    let board_names: Vec<String> = config
-        .peripherals
-        .boards
-        .iter()
-        .map(|b| b.board.clone())
-        .collect();
-
-    let skills = crate::skills::load_skills(&config.workspace_dir);
-    let mut tool_descs: Vec<(&str, &str)> = vec![
-        ("shell", "Execute terminal commands."),
-        ("file_read", "Read file contents."),
-        ("file_write", "Write file contents."),
-        ("memory_store", "Save to memory."),
-        ("memory_recall", "Search memory."),
-        ("memory_forget", "Delete a memory entry."),
-        ("screenshot", "Capture a screenshot."),
-        ("image_info", "Read image metadata."),
-    ];
-    if config.browser.enabled {
-        tool_descs.push(("browser_open", "Open approved URLs in browser."));
-    }
-    if config.composio.enabled {
-        tool_descs.push(("composio", "Execute actions on 1000+ apps via Composio."));
-    }
-    if config.peripherals.enabled && !config.peripherals.boards.is_empty() {
-        tool_descs.push(("gpio_read", "Read GPIO pin value on connected hardware."));
-        tool_descs.push((
-            "gpio_write",
-            "Set GPIO pin high or low on connected hardware.",
-        ));
-        tool_descs.push((
-            "arduino_upload",
-            "Upload Arduino sketch. Use for 'make a heart', custom patterns. You write full .ino code; ZeroClaw uploads it.",
-        ));
-        tool_descs.push((
-            "hardware_memory_map",
-            "Return flash and RAM address ranges. Use when user asks for memory addresses or memory map.",
-        ));
-        tool_descs.push((
-            "hardware_board_info",
-            "Return full board info (chip, architecture, memory map). Use when user asks for board info, what board, connected hardware, or chip info.",
-        ));
-        tool_descs.push((
-            "hardware_memory_read",
-            "Read actual memory/register values from Nucleo. Use when user asks to read registers, read memory, dump lower memory 0-126, or give address and value.",
-        ));
-        tool_descs.push((
-            "hardware_capabilities",
-            "Query connected hardware for reported GPIO pins and LED pin. Use when user asks what pins are available.",
-        ));
+        .await
     }
-    let bootstrap_max_chars = if config.agent.compact_context {
-        Some(6000)
-    } else {
-        None
-    };
-    let mut system_prompt = crate::channels::build_system_prompt(
-        &config.workspace_dir,
-        &model_name,
-        &tool_descs,
-        &skills,
-        Some(&config.identity),
-        bootstrap_max_chars,
-    );
-    system_prompt.push_str(&build_tool_instructions(&tools_registry));
-
-    let mem_context = build_context(mem.as_ref(), message, config.memory.min_relevance_score).await;
-    let rag_limit = if config.agent.compact_context { 2 } else { 5 };
-    let hw_context = hardware_rag
-        .as_ref()
-        .map(|r| build_hardware_context(r, message, &board_names, rag_limit))
-        .unwrap_or_default();
-    let context = format!("{mem_context}{hw_context}");
-    let enriched = if context.is_empty() {
-        message.to_string()
-    } else {
-        format!("{context}{message}")
-    };
-
-    let mut history = vec![
-        ChatMessage::system(&system_prompt),
-        ChatMessage::user(&enriched),
-    ];
-
-    agent_turn(
-        provider.as_ref(),
-        &mut history,
-        &tools_registry,
-        observer.as_ref(),
-        provider_name,
-        &model_name,
-        config.default_temperature,
-        true,
-        config.agent.max_tool_iterations,
-    )
-    .await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::{Artifact, ArtifactData};
+    use std::path::PathBuf;
 
     #[test]
     fn test_scrub_credentials() {
@@ -1769,746 +1545,1618 @@ mod tests {
         assert!(scrubbed.contains("\"api_key\": \"sk-1*[REDACTED]\""));
         assert!(scrubbed.contains("public"));
     }
-    use crate::memory::{Memory, MemoryCategory, SqliteMemory};
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use tempfile::TempDir;
 
     #[test]
-    fn parse_tool_calls_extracts_single_call() {
-        let response = r#"Let me check that.
-<tool_call>
-{"name": "shell", "arguments": {"command": "ls -la"}}
-</tool_call>"#;
-
-        let (text, calls) = parse_tool_calls(response);
-        assert_eq!(text, "Let me check that.");
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "ls -la"
-        );
-    }
-
-    #[test]
-    fn parse_tool_calls_extracts_multiple_calls() {
-        let response = r#"<tool_call>
-{"name": "file_read", "arguments": {"path": "a.txt"}}
-</tool_call>
-<tool_call>
-{"name": "file_read", "arguments": {"path": "b.txt"}}
-</tool_call>"#;
-
-        let (_, calls) = parse_tool_calls(response);
-        assert_eq!(calls.len(), 2);
-        assert_eq!(calls[0].name, "file_read");
-        assert_eq!(calls[1].name, "file_read");
-    }
+    fn build_tool_instructions_includes_all_tools() {
+        use crate::security::SecurityPolicy;
+        let security = Arc::new(SecurityPolicy::from_config(
+            &crate::config::AutonomyConfig::default(),
+            std::path::Path::new("/tmp"),
+        ));
+        let tools = tools::default_tools(security);
+        let instructions = build_tool_instructions(&tools);
 
-    #[test]
-    fn parse_tool_calls_returns_text_only_when_no_calls() {
-        let response = "Just a normal response with no tools.";
-        let (text, calls) = parse_tool_calls(response);
-        assert_eq!(text, "Just a normal response with no tools.");
-        assert!(calls.is_empty());
+        assert!(instructions.contains("## Tool Use Protocol"));
+        assert!(instructions.contains("<tool_call>"));
+        assert!(instructions.contains("shell"));
+        assert!(instructions.contains("file_read"));
+        assert!(instructions.contains("file_write"));
     }
 
     #[test]
-    fn parse_tool_calls_handles_malformed_json() {
-        let response = r#"<tool_call>
-not valid json
-</tool_call>
-Some text after."#;
+    fn tools_to_openai_format_produces_valid_schema() {
+        use crate::security::SecurityPolicy;
+        let security = Arc::new(SecurityPolicy::from_config(
+            &crate::config::AutonomyConfig::default(),
+            std::path::Path::new("/tmp"),
+        ));
+        let tools = tools::default_tools(security);
+        let formatted = tools_to_openai_format(&tools);
 
-        let (text, calls) = parse_tool_calls(response);
-        assert!(calls.is_empty());
-        assert!(text.contains("Some text after."));
+        assert!(!formatted.is_empty());
+        for tool_json in &formatted {
+            assert_eq!(tool_json["type"], "function");
+            assert!(tool_json["function"]["name"].is_string());
+            assert!(tool_json["function"]["description"].is_string());
+            assert!(!tool_json["function"]["name"].as_str().unwrap().is_empty());
+        }
+        // Verify known tools are present
+        let names: Vec<&str> = formatted
+            .iter()
+            .filter_map(|t| t["function"]["name"].as_str())
+            .collect();
+        assert!(names.contains(&"shell"));
+        assert!(names.contains(&"file_read"));
     }
 
+    // ═══════════════════════════════════════════════════════════════════════
+    // Recovery Tests - Constants Validation
+    // ═══════════════════════════════════════════════════════════════════════
+
+    const _: () = {
+        assert!(DEFAULT_MAX_TOOL_ITERATIONS > 0);
+        assert!(DEFAULT_MAX_TOOL_ITERATIONS <= 100);
+    };
+
     #[test]
-    fn parse_tool_calls_text_before_and_after() {
-        let response = r#"Before text.
-<tool_call>
-{"name": "shell", "arguments": {"command": "echo hi"}}
-</tool_call>
-After text."#;
-
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.contains("Before text."));
-        assert!(text.contains("After text."));
-        assert_eq!(calls.len(), 1);
+    fn constants_bounds_are_compile_time_checked() {
+        // Bounds are enforced by the const assertions above.
     }
 
     #[test]
-    fn parse_tool_calls_handles_openai_format() {
-        // OpenAI-style response with tool_calls array
-        let response = r#"{"content": "Let me check that for you.", "tool_calls": [{"type": "function", "function": {"name": "shell", "arguments": "{\"command\": \"ls -la\"}"}}]}"#;
+    fn cli_model_overrides_workspace_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path()
+                .join(crate::config::schema::WORKSPACE_CONFIG_FILE),
+            "default_provider = \"ollama\"\ndefault_model = \"project-model\"\n",
+        )
+        .unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            default_model: Some("global-model".into()),
+            ..Config::default()
+        };
+        config.apply_workspace_file().unwrap();
 
-        let (text, calls) = parse_tool_calls(response);
-        assert_eq!(text, "Let me check that for you.");
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
         assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "ls -la"
+            resolve_provider_and_model(&config, None, None),
+            ("ollama", "project-model")
+        );
+        assert_eq!(
+            resolve_provider_and_model(&config, Some("openai"), Some("cli-model")),
+            ("openai", "cli-model")
         );
     }
 
-    #[test]
-    fn parse_tool_calls_handles_openai_format_multiple_calls() {
-        let response = r#"{"tool_calls": [{"type": "function", "function": {"name": "file_read", "arguments": "{\"path\": \"a.txt\"}"}}, {"type": "function", "function": {"name": "file_read", "arguments": "{\"path\": \"b.txt\"}"}}]}"#;
+    struct ThinkingProvider;
 
-        let (_, calls) = parse_tool_calls(response);
-        assert_eq!(calls.len(), 2);
-        assert_eq!(calls[0].name, "file_read");
-        assert_eq!(calls[1].name, "file_read");
+    #[async_trait::async_trait]
+    impl Provider for ThinkingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("<think>Maybe call <tool_call>{\"name\":\"snap\",\"arguments\":{}}</tool_call> first? No.</think>\nThe answer is 42.".into())
+        }
     }
 
-    #[test]
-    fn parse_tool_calls_openai_format_without_content() {
-        // Some providers don't include content field with tool_calls
-        let response = r#"{"tool_calls": [{"type": "function", "function": {"name": "memory_recall", "arguments": "{}"}}]}"#;
+    #[tokio::test]
+    async fn think_tags_stay_out_of_tool_parsing_history_and_response() {
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SnapTool)];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("question")];
+        let mut summary = TurnSummary::default();
+        let response = run_tool_call_loop(
+            &ThinkingProvider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                summary: Some(&mut summary),
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
 
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.is_empty()); // No content field
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "memory_recall");
+        assert_eq!(response, "The answer is 42.");
+        assert_eq!(summary.tool_calls(), 0);
+        let last = history.last().unwrap();
+        assert_eq!(last.role, "assistant");
+        assert!(!last.content.contains("Maybe call"));
     }
 
-    #[test]
-    fn parse_tool_calls_handles_markdown_json_inside_tool_call_tag() {
-        let response = r#"<tool_call>
-```json
-{"name": "file_write", "arguments": {"path": "test.py", "content": "print('ok')"}}
-```
-</tool_call>"#;
-
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.is_empty());
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "file_write");
-        assert_eq!(
-            calls[0].arguments.get("path").unwrap().as_str().unwrap(),
-            "test.py"
-        );
+    struct ArtifactProvider {
+        vision: bool,
+        calls: std::sync::Mutex<usize>,
     }
 
-    #[test]
-    fn parse_tool_calls_handles_noisy_tool_call_tag_body() {
-        let response = r#"<tool_call>
-I will now call the tool with this payload:
-{"name": "shell", "arguments": {"command": "pwd"}}
-</tool_call>"#;
-
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.is_empty());
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "pwd"
-        );
+    #[async_trait::async_trait]
+    impl Provider for ArtifactProvider {
+        fn capabilities(&self) -> crate::providers::traits::ProviderCapabilities {
+            crate::providers::traits::ProviderCapabilities {
+                native_tool_calling: false,
+                vision: self.vision,
+                ..Default::default()
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("done".into())
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<crate::providers::ChatResponse> {
+            let mut calls = self.calls.lock().unwrap();
+            *calls += 1;
+            let text = if *calls == 1 {
+                "<tool_call>{\"name\":\"snap\",\"arguments\":{}}</tool_call>"
+            } else {
+                "done"
+            };
+            Ok(crate::providers::ChatResponse {
+                text: Some(text.into()),
+                tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
+            })
+        }
     }
 
-    #[test]
-    fn parse_tool_calls_handles_markdown_tool_call_fence() {
-        let response = r#"I'll check that.
-```tool_call
-{"name": "shell", "arguments": {"command": "pwd"}}
-```
-Done."#;
-
-        let (text, calls) = parse_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "pwd"
-        );
-        assert!(text.contains("I'll check that."));
-        assert!(text.contains("Done."));
-        assert!(!text.contains("```tool_call"));
+    struct SnapTool;
+
+    #[async_trait::async_trait]
+    impl Tool for SnapTool {
+        fn name(&self) -> &str {
+            "snap"
+        }
+
+        fn description(&self) -> &str {
+            "Returns a tiny image artifact"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<crate::tools::ToolResult> {
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: "captured".into(),
+                error: None,
+                artifacts: vec![Artifact {
+                    mime_type: "image/png".into(),
+                    data: ArtifactData::Base64("aGVsbG8=".into()),
+                }],
+            })
+        }
     }
 
     #[test]
-    fn parse_tool_calls_handles_markdown_tool_call_hybrid_close_tag() {
-        let response = r#"Preface
-```tool-call
-{"name": "shell", "arguments": {"command": "date"}}
-</tool_call>
-Tail"#;
-
-        let (text, calls) = parse_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "date"
-        );
-        assert!(text.contains("Preface"));
-        assert!(text.contains("Tail"));
-        assert!(!text.contains("```tool-call"));
+    fn retain_tools_keeps_only_allowed_and_rejects_unknown_names() {
+        let security = Arc::new(SecurityPolicy::default());
+        let mut registry = tools::default_tools(security.clone());
+        let all = registry.len();
+        retain_tools(&mut registry, &[]).unwrap();
+        assert_eq!(registry.len(), all);
+
+        retain_tools(&mut registry, &["file_read".into(), "shell".into()]).unwrap();
+        let names: Vec<&str> = registry.iter().map(|t| t.name()).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"shell") && names.contains(&"file_read"));
+
+        let mut registry = tools::default_tools(security);
+        let err = retain_tools(&mut registry, &["shel".into()]).unwrap_err();
+        assert!(err.to_string().contains("Unknown tool 'shel'"));
+    }
+
+    async fn run_snap_turn(vision: bool, artifact_dir: Option<&Path>) -> Vec<ChatMessage> {
+        let provider = ArtifactProvider {
+            vision,
+            calls: std::sync::Mutex::new(0),
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SnapTool)];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("snap it")];
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                artifact_dir,
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(response, "done");
+        history
     }
 
-    #[test]
-    fn parse_tool_calls_handles_markdown_invoke_fence() {
-        let response = r#"Checking.
-```invoke
-{"name": "shell", "arguments": {"command": "date"}}
-```
-Done."#;
-
-        let (text, calls) = parse_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "date"
-        );
-        assert!(text.contains("Checking."));
-        assert!(text.contains("Done."));
+    #[tokio::test]
+    async fn turn_summary_counts_round_trips_and_tools() {
+        let provider = ArtifactProvider {
+            vision: true,
+            calls: std::sync::Mutex::new(0),
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SnapTool)];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("snap it")];
+        let mut summary = TurnSummary::default();
+        run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                summary: Some(&mut summary),
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(summary.provider_round_trips, 2);
+        assert_eq!(summary.tool_calls(), 1);
+        assert_eq!(summary.tool_failures(), 0);
+        assert_eq!(summary.tools["snap"].calls, 1);
+        assert!(summary.shell_commands.is_empty());
+        assert!(summary.render().contains("Tools: snap ×1"));
+    }
+
+    /// Rejects requests with more than `limit` messages the way OpenAI
+    /// reports an overlong prompt; records the size of every request.
+    struct ContextLimitedProvider {
+        limit: usize,
+        error: &'static str,
+        seen: std::sync::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ContextLimitedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("- the user has been asking about the build".into())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<crate::providers::ChatResponse> {
+            self.seen.lock().unwrap().push(request.messages.len());
+            if request.messages.len() > self.limit {
+                anyhow::bail!("{}", self.error);
+            }
+            Ok(crate::providers::ChatResponse {
+                text: Some("short enough".into()),
+                tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
+            })
+        }
     }
 
-    #[test]
-    fn parse_tool_calls_handles_toolcall_tag_alias() {
-        let response = r#"<toolcall>
-{"name": "shell", "arguments": {"command": "date"}}
-</toolcall>"#;
+    fn long_history() -> Vec<ChatMessage> {
+        let mut history = vec![ChatMessage::system("sys")];
+        for i in 0..5 {
+            history.push(ChatMessage::user(format!("question {i}")));
+            history.push(ChatMessage::assistant(format!("answer {i}")));
+        }
+        history.push(ChatMessage::user("and now?"));
+        history
+    }
 
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.is_empty());
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "date"
-        );
+    async fn run_context_limited(provider: &ContextLimitedProvider) -> Result<String> {
+        let mut history = long_history();
+        run_tool_call_loop(
+            provider,
+            &mut history,
+            &[],
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
     }
 
-    #[test]
-    fn parse_tool_calls_handles_tool_dash_call_tag_alias() {
-        let response = r#"<tool-call>
-{"name": "shell", "arguments": {"command": "whoami"}}
-</tool-call>"#;
+    #[tokio::test]
+    async fn context_length_error_compacts_history_and_retries_once() {
+        let provider = ContextLimitedProvider {
+            limit: 8,
+            error: "OpenAI API error (400 Bad Request): This model's maximum context length is 8192 tokens",
+            seen: std::sync::Mutex::new(Vec::new()),
+        };
+        let mut history = long_history();
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &[],
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
 
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.is_empty());
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "whoami"
-        );
+        assert_eq!(response, "short enough");
+        assert_eq!(*provider.seen.lock().unwrap(), [12, 8]);
+        assert_eq!(history[0].content, "sys");
+        assert!(history[1].content.starts_with("[Compaction summary]"));
+        assert!(history.iter().any(|m| m.content == "and now?"));
     }
 
-    #[test]
-    fn parse_tool_calls_handles_invoke_tag_alias() {
-        let response = r#"<invoke>
-{"name": "shell", "arguments": {"command": "uptime"}}
-</invoke>"#;
+    #[tokio::test]
+    async fn context_length_retry_happens_only_once() {
+        let provider = ContextLimitedProvider {
+            limit: 2,
+            error: "Anthropic API error (400): prompt is too long: 210000 tokens > 200000 maximum",
+            seen: std::sync::Mutex::new(Vec::new()),
+        };
+        let err = run_context_limited(&provider).await.unwrap_err();
+        assert!(err.to_string().contains("prompt is too long"));
+        assert_eq!(provider.seen.lock().unwrap().len(), 2);
+    }
 
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.is_empty());
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "uptime"
-        );
+    #[tokio::test]
+    async fn other_bad_requests_are_not_retried_with_compaction() {
+        let provider = ContextLimitedProvider {
+            limit: 0,
+            error: "OpenAI API error (400 Bad Request): invalid value for 'temperature'",
+            seen: std::sync::Mutex::new(Vec::new()),
+        };
+        assert!(run_context_limited(&provider).await.is_err());
+        assert_eq!(*provider.seen.lock().unwrap(), [12]);
     }
 
-    #[test]
-    fn parse_tool_calls_recovers_unclosed_tool_call_with_json() {
-        let response = r#"I will call the tool now.
-<tool_call>
-{"name": "shell", "arguments": {"command": "uptime -p"}}"#;
+    /// Calls `snap` once, then answers; records the model of every call.
+    struct ModelRecordingProvider {
+        models: std::sync::Mutex<Vec<String>>,
+    }
 
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.contains("I will call the tool now."));
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "uptime -p"
-        );
+    #[async_trait::async_trait]
+    impl Provider for ModelRecordingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("done".into())
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            model: &str,
+            _temperature: f64,
+        ) -> Result<crate::providers::ChatResponse> {
+            let mut models = self.models.lock().unwrap();
+            models.push(model.to_string());
+            let text = if models.len() == 1 {
+                "<tool_call>{\"name\":\"snap\",\"arguments\":{}}</tool_call>".to_string()
+            } else {
+                format!("answer from {model}")
+            };
+            Ok(crate::providers::ChatResponse {
+                text: Some(text),
+                tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
+            })
+        }
     }
 
-    #[test]
-    fn parse_tool_calls_recovers_mismatched_close_tag() {
-        let response = r#"<tool_call>
-{"name": "shell", "arguments": {"command": "uptime"}}
-</arg_value>"#;
+    #[tokio::test]
+    async fn model_router_uses_executor_for_tool_rounds_and_planner_for_answer() {
+        let router = crate::config::ModelRouterConfig {
+            planner: Some("strong-model".into()),
+            executor: Some("cheap-model".into()),
+            ..crate::config::ModelRouterConfig::default()
+        };
+        let round_models = RoundModels::from_config(&router, "test-model").unwrap();
+        let provider = ModelRecordingProvider {
+            models: std::sync::Mutex::new(Vec::new()),
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SnapTool)];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("snap it")];
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                round_models: Some(&round_models),
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
 
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.is_empty());
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
         assert_eq!(
-            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
-            "uptime"
+            *provider.models.lock().unwrap(),
+            ["cheap-model", "cheap-model", "strong-model"]
         );
+        assert_eq!(response, "answer from strong-model");
+        // The executor's would-be answer is discarded, not kept in history.
+        assert!(!history
+            .iter()
+            .any(|m| m.content.contains("answer from cheap-model")));
     }
 
     #[test]
-    fn parse_tool_calls_recovers_cross_alias_closing_tags() {
-        let response = r#"<toolcall>
-{"name": "shell", "arguments": {"command": "date"}}
-</tool_call>"#;
+    fn model_router_roles_resolve_against_active_model() {
+        use crate::config::{ModelRouterConfig, RouterRole};
 
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.is_empty());
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-    }
+        assert_eq!(
+            RoundModels::from_config(&ModelRouterConfig::default(), "m"),
+            None
+        );
 
-    #[test]
-    fn parse_tool_calls_rejects_raw_tool_json_without_tags() {
-        // SECURITY: Raw JSON without explicit wrappers should NOT be parsed
-        // This prevents prompt injection attacks where malicious content
-        // could include JSON that mimics a tool call.
-        let response = r#"Sure, creating the file now.
-{"name": "file_write", "arguments": {"path": "hello.py", "content": "print('hello')"}}"#;
-
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.contains("Sure, creating the file now."));
+        let reversed = ModelRouterConfig {
+            planner: Some("hint:reasoning".into()),
+            executor: None,
+            final_answer: RouterRole::Executor,
+        };
         assert_eq!(
-            calls.len(),
-            0,
-            "Raw JSON without wrappers should not be parsed"
+            RoundModels::from_config(&reversed, "active"),
+            Some(RoundModels {
+                tool_rounds: "hint:reasoning".into(),
+                final_answer: "active".into(),
+            })
         );
-    }
 
-    #[test]
-    fn build_tool_instructions_includes_all_tools() {
-        use crate::security::SecurityPolicy;
-        let security = Arc::new(SecurityPolicy::from_config(
-            &crate::config::AutonomyConfig::default(),
-            std::path::Path::new("/tmp"),
-        ));
-        let tools = tools::default_tools(security);
-        let instructions = build_tool_instructions(&tools);
+        let same = ModelRouterConfig {
+            planner: Some("m".into()),
+            executor: Some("m".into()),
+            ..ModelRouterConfig::default()
+        };
+        assert_eq!(RoundModels::from_config(&same, "other"), None);
+    }
 
-        assert!(instructions.contains("## Tool Use Protocol"));
-        assert!(instructions.contains("<tool_call>"));
-        assert!(instructions.contains("shell"));
-        assert!(instructions.contains("file_read"));
-        assert!(instructions.contains("file_write"));
+    /// Narrates, calls `snap`, then answers.
+    struct NarratingProvider {
+        calls: std::sync::Mutex<usize>,
     }
 
-    #[test]
-    fn tools_to_openai_format_produces_valid_schema() {
-        use crate::security::SecurityPolicy;
-        let security = Arc::new(SecurityPolicy::from_config(
-            &crate::config::AutonomyConfig::default(),
-            std::path::Path::new("/tmp"),
-        ));
-        let tools = tools::default_tools(security);
-        let formatted = tools_to_openai_format(&tools);
+    #[async_trait::async_trait]
+    impl Provider for NarratingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("done".into())
+        }
 
-        assert!(!formatted.is_empty());
-        for tool_json in &formatted {
-            assert_eq!(tool_json["type"], "function");
-            assert!(tool_json["function"]["name"].is_string());
-            assert!(tool_json["function"]["description"].is_string());
-            assert!(!tool_json["function"]["name"].as_str().unwrap().is_empty());
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<crate::providers::ChatResponse> {
+            let mut calls = self.calls.lock().unwrap();
+            *calls += 1;
+            let text = if *calls == 1 {
+                "I'll take a snapshot first.\n```tool_call\n{\"name\":\"snap\",\"arguments\":{}}\n```"
+            } else {
+                "The snapshot looks fine."
+            };
+            Ok(crate::providers::ChatResponse {
+                text: Some(text.into()),
+                tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
+            })
         }
-        // Verify known tools are present
-        let names: Vec<&str> = formatted
-            .iter()
-            .filter_map(|t| t["function"]["name"].as_str())
-            .collect();
-        assert!(names.contains(&"shell"));
-        assert!(names.contains(&"file_read"));
     }
 
-    #[test]
-    fn trim_history_preserves_system_prompt() {
-        let mut history = vec![ChatMessage::system("system prompt")];
-        for i in 0..DEFAULT_MAX_HISTORY_MESSAGES + 20 {
-            history.push(ChatMessage::user(format!("msg {i}")));
-        }
-        let original_len = history.len();
-        assert!(original_len > DEFAULT_MAX_HISTORY_MESSAGES + 1);
-
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-
-        // System prompt preserved
-        assert_eq!(history[0].role, "system");
-        assert_eq!(history[0].content, "system prompt");
-        // Trimmed to limit
-        assert_eq!(history.len(), DEFAULT_MAX_HISTORY_MESSAGES + 1); // +1 for system
-                                                                     // Most recent messages preserved
-        let last = &history[history.len() - 1];
+    #[tokio::test]
+    async fn prose_beside_tool_calls_is_reported_streamed_and_kept_in_history() {
+        let provider = NarratingProvider {
+            calls: std::sync::Mutex::new(0),
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SnapTool)];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("snap it")];
+        let mut summary = TurnSummary::default();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                on_delta: Some(tx),
+                summary: Some(&mut summary),
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response, "The snapshot looks fine.");
+        assert_eq!(summary.assistant_updates, ["I'll take a snapshot first."]);
+        assert_eq!(summary.tools["snap"].calls, 1);
+        assert_eq!(
+            summary.tool_rationales,
+            [crate::agent::turn_summary::ToolRationale {
+                tools: vec!["snap".into()],
+                rationale: "I'll take a snapshot first.".into(),
+            }]
+        );
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            StreamEvent::Text("I'll take a snapshot first.\n\n".into())
+        );
         assert_eq!(
-            last.content,
-            format!("msg {}", DEFAULT_MAX_HISTORY_MESSAGES + 19)
+            rx.recv().await.unwrap(),
+            StreamEvent::ToolCall {
+                name: "snap".into(),
+                arguments: serde_json::json!({}),
+            }
         );
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            StreamEvent::ToolResult {
+                name: "snap".into(),
+                success: true,
+            }
+        );
+        assert!(history
+            .iter()
+            .any(|m| m.role == "assistant" && m.content.contains("I'll take a snapshot first.")));
     }
 
-    #[test]
-    fn trim_history_noop_when_within_limit() {
-        let mut history = vec![
-            ChatMessage::system("sys"),
-            ChatMessage::user("hello"),
-            ChatMessage::assistant("hi"),
-        ];
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-        assert_eq!(history.len(), 3);
+    /// Answers through `chat` or, when it declares streaming, through
+    /// `stream_chat_with_history`; records which path was taken.
+    struct CapabilityProvider {
+        streaming: bool,
+        chat_calls: AtomicUsize,
+        stream_calls: AtomicUsize,
     }
 
-    #[test]
-    fn build_compaction_transcript_formats_roles() {
-        let messages = vec![
-            ChatMessage::user("I like dark mode"),
-            ChatMessage::assistant("Got it"),
-        ];
-        let transcript = build_compaction_transcript(&messages);
-        assert!(transcript.contains("USER: I like dark mode"));
-        assert!(transcript.contains("ASSISTANT: Got it"));
+    impl CapabilityProvider {
+        fn new(streaming: bool) -> Self {
+            Self {
+                streaming,
+                chat_calls: AtomicUsize::new(0),
+                stream_calls: AtomicUsize::new(0),
+            }
+        }
     }
 
-    #[test]
-    fn apply_compaction_summary_replaces_old_segment() {
-        let mut history = vec![
-            ChatMessage::system("sys"),
-            ChatMessage::user("old 1"),
-            ChatMessage::assistant("old 2"),
-            ChatMessage::user("recent 1"),
-            ChatMessage::assistant("recent 2"),
-        ];
+    #[async_trait::async_trait]
+    impl Provider for CapabilityProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                streaming: self.streaming,
+                ..ProviderCapabilities::default()
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("buffered answer".into())
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<crate::providers::ChatResponse> {
+            self.chat_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(crate::providers::ChatResponse {
+                text: Some("buffered answer".into()),
+                tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
+            })
+        }
 
-        apply_compaction_summary(&mut history, 1, 3, "- user prefers concise replies");
+        fn stream_chat_with_history(
+            &self,
+            _messages: &[ChatMessage],
+            _model: &str,
+            _temperature: f64,
+            _options: StreamOptions,
+        ) -> futures_util::stream::BoxStream<
+            'static,
+            crate::providers::traits::StreamResult<crate::providers::traits::StreamChunk>,
+        > {
+            use crate::providers::traits::StreamChunk;
+            self.stream_calls.fetch_add(1, Ordering::SeqCst);
+            futures_util::stream::iter(vec![
+                Ok(StreamChunk::delta("streamed ")),
+                Ok(StreamChunk::delta("answer")),
+                Ok(StreamChunk::final_chunk()),
+            ])
+            .boxed()
+        }
+    }
 
-        assert_eq!(history.len(), 4);
-        assert!(history[1].content.contains("Compaction summary"));
-        assert!(history[2].content.contains("recent 1"));
-        assert!(history[3].content.contains("recent 2"));
+    async fn run_capability_turn(
+        provider: &CapabilityProvider,
+        tools: &[Box<dyn Tool>],
+    ) -> (String, Vec<StreamEvent>) {
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+        let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+        let response = run_tool_call_loop(
+            provider,
+            &mut history,
+            tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                on_delta: Some(tx),
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        (response, events)
     }
 
     #[test]
-    fn autosave_memory_key_has_prefix_and_uniqueness() {
-        let key1 = autosave_memory_key("user_msg");
-        let key2 = autosave_memory_key("user_msg");
-
-        assert!(key1.starts_with("user_msg_"));
-        assert!(key2.starts_with("user_msg_"));
-        assert_ne!(key1, key2);
+    fn round_mode_follows_declared_capabilities() {
+        let native = ProviderCapabilities {
+            native_tool_calling: true,
+            streaming: true,
+            ..ProviderCapabilities::default()
+        };
+        assert_eq!(
+            RoundMode::select(&native, true, true, true),
+            RoundMode {
+                native_tools: true,
+                stream: false,
+                early_tools: false,
+            }
+        );
+        assert_eq!(
+            RoundMode::select(&native, false, true, false),
+            RoundMode {
+                native_tools: false,
+                stream: true,
+                early_tools: false,
+            }
+        );
+        assert!(!RoundMode::select(&native, false, false, false).stream);
+        assert_eq!(
+            RoundMode::select(&ProviderCapabilities::default(), true, true, true),
+            RoundMode {
+                native_tools: false,
+                stream: false,
+                early_tools: false,
+            }
+        );
+        let prompt_guided = ProviderCapabilities {
+            streaming: true,
+            ..ProviderCapabilities::default()
+        };
+        assert!(RoundMode::select(&prompt_guided, true, false, true).early_tools);
+        assert!(!RoundMode::select(&prompt_guided, true, false, false).early_tools);
+        assert!(!RoundMode::select(&prompt_guided, false, false, true).early_tools);
     }
 
     #[tokio::test]
-    async fn autosave_memory_keys_preserve_multiple_turns() {
-        let tmp = TempDir::new().unwrap();
-        let mem = SqliteMemory::new(tmp.path()).unwrap();
+    async fn streaming_capability_streams_tool_less_turns() {
+        let provider = CapabilityProvider::new(true);
+        let (response, events) = run_capability_turn(&provider, &[]).await;
 
-        let key1 = autosave_memory_key("user_msg");
-        let key2 = autosave_memory_key("user_msg");
-
-        mem.store(&key1, "I'm Paul", MemoryCategory::Conversation, None)
-            .await
-            .unwrap();
-        mem.store(&key2, "I'm 45", MemoryCategory::Conversation, None)
-            .await
-            .unwrap();
+        assert_eq!(response, "streamed answer");
+        assert_eq!(provider.stream_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(provider.chat_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            events,
+            [
+                StreamEvent::Text("streamed ".into()),
+                StreamEvent::Text("answer".into()),
+            ]
+        );
+    }
 
-        assert_eq!(mem.count().await.unwrap(), 2);
+    #[tokio::test]
+    async fn buffered_path_without_streaming_capability_or_with_tools() {
+        let provider = CapabilityProvider::new(false);
+        let (response, _) = run_capability_turn(&provider, &[]).await;
+        assert_eq!(response, "buffered answer");
+        assert_eq!(provider.stream_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(provider.chat_calls.load(Ordering::SeqCst), 1);
 
-        let recalled = mem.recall("45", 5, None).await.unwrap();
-        assert!(recalled.iter().any(|entry| entry.content.contains("45")));
+        let provider = CapabilityProvider::new(true);
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SnapTool)];
+        let (response, _) = run_capability_turn(&provider, &tools).await;
+        assert_eq!(response, "buffered answer");
+        assert_eq!(provider.stream_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(provider.chat_calls.load(Ordering::SeqCst), 1);
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // Recovery Tests - Tool Call Parsing Edge Cases
-    // ═══════════════════════════════════════════════════════════════════════
-
     #[test]
-    fn parse_tool_calls_handles_empty_tool_result() {
-        // Recovery: Empty tool_result tag should be handled gracefully
-        let response = r#"I'll run that command.
-<tool_result name="shell">
+    fn early_tool_calls_are_reported_once_their_block_closes() {
+        let mut detector = EarlyToolCalls::default();
+        assert!(detector
+            .push("Checking. <tool_call>{\"name\":\"a\",")
+            .is_empty());
+        assert!(detector.push("\"arguments\":{}}</tool_").is_empty());
+        let calls = detector.push("call> then ");
+        assert_eq!(calls.len(), 1);
+        assert_eq!((calls[0].0, calls[0].1.name.as_str()), (0, "a"));
+        assert!(detector.push("more prose").is_empty());
 
-</tool_result>
-Done."#;
-        let (text, calls) = parse_tool_calls(response);
-        assert!(text.contains("Done."));
-        assert!(calls.is_empty());
+        let calls = detector.push(
+            "<tool_call>{\"name\":\"b\",\"arguments\":{}}</tool_call>\
+             <tool_call>{\"name\":\"c\",\"arguments\":{}}</tool_call>",
+        );
+        let names: Vec<_> = calls
+            .iter()
+            .map(|(index, call)| (*index, call.name.as_str()))
+            .collect();
+        assert_eq!(names, [(1, "b"), (2, "c")]);
     }
 
     #[test]
-    fn parse_arguments_value_handles_null() {
-        // Recovery: null arguments are returned as-is (Value::Null)
-        let value = serde_json::json!(null);
-        let result = parse_arguments_value(Some(&value));
-        assert!(result.is_null());
-    }
+    fn early_tool_calls_ignore_blocks_inside_quoted_fences() {
+        let mut detector = EarlyToolCalls::default();
+        assert!(detector
+            .push("Example:\n```rust\n<tool_call>{\"name\":\"a\",\"arguments\":{}}</tool_call>")
+            .is_empty());
+        assert!(detector.push("\n```\n").is_empty());
+    }
+
+    /// Streams a prompt-guided tool call, then reports whether the tool ran
+    /// before the rest of the response was sent.
+    struct EarlyDispatchProvider {
+        ran: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for EarlyDispatchProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                streaming: true,
+                ..ProviderCapabilities::default()
+            }
+        }
 
-    #[test]
-    fn parse_tool_calls_handles_empty_tool_calls_array() {
-        // Recovery: Empty tool_calls array returns original response (no tool parsing)
-        let response = r#"{"content": "Hello", "tool_calls": []}"#;
-        let (text, calls) = parse_tool_calls(response);
-        // When tool_calls is empty, the entire JSON is returned as text
-        assert!(text.contains("Hello"));
-        assert!(calls.is_empty());
-    }
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            anyhow::bail!("early dispatch should stream")
+        }
 
-    #[test]
-    fn parse_tool_calls_handles_whitespace_only_name() {
-        // Recovery: Whitespace-only tool name should return None
-        let value = serde_json::json!({"function": {"name": "   ", "arguments": {}}});
-        let result = parse_tool_call_value(&value);
-        assert!(result.is_none());
+        fn stream_chat_with_history(
+            &self,
+            messages: &[ChatMessage],
+            _model: &str,
+            _temperature: f64,
+            _options: StreamOptions,
+        ) -> futures_util::stream::BoxStream<
+            'static,
+            crate::providers::traits::StreamResult<crate::providers::traits::StreamChunk>,
+        > {
+            use crate::providers::traits::StreamChunk;
+            use futures_util::stream;
+            if messages
+                .iter()
+                .any(|m| m.content.starts_with("[Tool results]"))
+            {
+                return stream::iter(vec![
+                    Ok(StreamChunk::delta("done")),
+                    Ok(StreamChunk::final_chunk()),
+                ])
+                .boxed();
+            }
+            let ran = self.ran.clone();
+            stream::iter(vec![Ok(StreamChunk::delta(
+                "<tool_call>{\"name\":\"probe\",\"arguments\":{}}</tool_call>",
+            ))])
+            .chain(stream::once(async move {
+                for _ in 0..1000 {
+                    if ran.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    tokio::task::yield_now().await;
+                }
+                Ok(StreamChunk::delta(format!(
+                    " ran mid-stream: {}",
+                    ran.load(Ordering::SeqCst)
+                )))
+            }))
+            .chain(stream::iter(vec![Ok(StreamChunk::final_chunk())]))
+            .boxed()
+        }
     }
 
-    #[test]
-    fn parse_tool_calls_handles_empty_string_arguments() {
-        // Recovery: Empty string arguments should be handled
-        let value = serde_json::json!({"name": "test", "arguments": ""});
-        let result = parse_tool_call_value(&value);
-        assert!(result.is_some());
-        assert_eq!(result.unwrap().name, "test");
+    struct ProbeTool {
+        ran: Arc<std::sync::atomic::AtomicBool>,
+        calls: Arc<AtomicUsize>,
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // Recovery Tests - History Management
-    // ═══════════════════════════════════════════════════════════════════════
+    #[async_trait::async_trait]
+    impl Tool for ProbeTool {
+        fn name(&self) -> &str {
+            "probe"
+        }
 
-    #[test]
-    fn trim_history_with_no_system_prompt() {
-        // Recovery: History without system prompt should trim correctly
-        let mut history = vec![];
-        for i in 0..DEFAULT_MAX_HISTORY_MESSAGES + 20 {
-            history.push(ChatMessage::user(format!("msg {i}")));
+        fn description(&self) -> &str {
+            "Records that it ran"
         }
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-        assert_eq!(history.len(), DEFAULT_MAX_HISTORY_MESSAGES);
-    }
 
-    #[test]
-    fn trim_history_preserves_role_ordering() {
-        // Recovery: After trimming, role ordering should remain consistent
-        let mut history = vec![ChatMessage::system("system")];
-        for i in 0..DEFAULT_MAX_HISTORY_MESSAGES + 10 {
-            history.push(ChatMessage::user(format!("user {i}")));
-            history.push(ChatMessage::assistant(format!("assistant {i}")));
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<crate::tools::ToolResult> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.ran.store(true, Ordering::SeqCst);
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: "probed".into(),
+                error: None,
+                artifacts: Vec::new(),
+            })
         }
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-        assert_eq!(history[0].role, "system");
-        assert_eq!(history[history.len() - 1].role, "assistant");
     }
 
-    #[test]
-    fn trim_history_with_only_system_prompt() {
-        // Recovery: Only system prompt should not be trimmed
-        let mut history = vec![ChatMessage::system("system prompt")];
-        trim_history(&mut history, DEFAULT_MAX_HISTORY_MESSAGES);
-        assert_eq!(history.len(), 1);
+    #[tokio::test]
+    async fn early_tool_dispatch_runs_calls_before_the_stream_ends() {
+        let ran = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = EarlyDispatchProvider { ran: ran.clone() };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(ProbeTool {
+            ran,
+            calls: calls.clone(),
+        })];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("probe")];
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                early_tool_dispatch: true,
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response, "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(history
+            .iter()
+            .any(|m| m.role == "assistant" && m.content.contains("ran mid-stream: true")));
+        let results = history
+            .iter()
+            .find(|m| m.content.starts_with("[Tool results]"))
+            .unwrap();
+        assert!(results.content.contains("probed"));
+    }
+
+    /// Requests three `count` calls in one response, then answers.
+    struct FanOutProvider {
+        calls: std::sync::Mutex<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for FanOutProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            let mut calls = self.calls.lock().unwrap();
+            *calls += 1;
+            Ok(if *calls == 1 {
+                "<tool_call>{\"name\":\"count\",\"arguments\":{}}</tool_call>".repeat(3)
+            } else {
+                "Counted.".into()
+            })
+        }
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // Recovery Tests - Arguments Parsing
-    // ═══════════════════════════════════════════════════════════════════════
+    struct CountTool(Arc<std::sync::atomic::AtomicUsize>);
 
-    #[test]
-    fn parse_arguments_value_handles_invalid_json_string() {
-        // Recovery: Invalid JSON string should return empty object
-        let value = serde_json::Value::String("not valid json".to_string());
-        let result = parse_arguments_value(Some(&value));
-        assert!(result.is_object());
-        assert!(result.as_object().unwrap().is_empty());
+    #[async_trait::async_trait]
+    impl Tool for CountTool {
+        fn name(&self) -> &str {
+            "count"
+        }
+
+        fn description(&self) -> &str {
+            "Counts its calls"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<crate::tools::ToolResult> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: "counted".into(),
+                error: None,
+                artifacts: Vec::new(),
+            })
+        }
     }
 
-    #[test]
-    fn parse_arguments_value_handles_none() {
-        // Recovery: None arguments should return empty object
-        let result = parse_arguments_value(None);
-        assert!(result.is_object());
-        assert!(result.as_object().unwrap().is_empty());
+    #[tokio::test]
+    async fn tool_calls_beyond_the_per_turn_cap_are_skipped_and_reported() {
+        let provider = FanOutProvider {
+            calls: std::sync::Mutex::new(0),
+        };
+        let executed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(CountTool(executed.clone()))];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("count")];
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                max_tool_calls_per_turn: 2,
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response, "Counted.");
+        assert_eq!(executed.load(std::sync::atomic::Ordering::SeqCst), 2);
+        let results = history
+            .iter()
+            .find(|m| m.role == "user" && m.content.starts_with("[Tool results]"))
+            .unwrap();
+        assert_eq!(results.content.matches("counted").count(), 2);
+        assert_eq!(
+            results
+                .content
+                .matches(&skipped_tool_call_notice(2))
+                .count(),
+            1
+        );
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // Recovery Tests - JSON Extraction
-    // ═══════════════════════════════════════════════════════════════════════
+    /// `count` tool whose first call fails with a transient error.
+    struct FlakyCountTool(Arc<std::sync::atomic::AtomicUsize>);
 
-    #[test]
-    fn extract_json_values_handles_empty_string() {
-        // Recovery: Empty input should return empty vec
-        let result = extract_json_values("");
-        assert!(result.is_empty());
+    #[async_trait::async_trait]
+    impl Tool for FlakyCountTool {
+        fn name(&self) -> &str {
+            "count"
+        }
+
+        fn description(&self) -> &str {
+            "Counts its calls, failing the first"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        fn is_retryable(&self, _args: &serde_json::Value, error: &str) -> bool {
+            error.contains("connection reset")
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<crate::tools::ToolResult> {
+            if self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                anyhow::bail!("connection reset by peer");
+            }
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: "counted".into(),
+                error: None,
+                artifacts: Vec::new(),
+            })
+        }
     }
 
-    #[test]
-    fn extract_json_values_handles_whitespace_only() {
-        // Recovery: Whitespace only should return empty vec
-        let result = extract_json_values("   \n\t  ");
-        assert!(result.is_empty());
+    async fn run_flaky_count(tool_retry: ToolRetryPolicy) -> (usize, String) {
+        let provider = FanOutProvider {
+            calls: std::sync::Mutex::new(0),
+        };
+        let executed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(FlakyCountTool(executed.clone()))];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("count")];
+        run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                max_tool_calls_per_turn: 1,
+                tool_retry,
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+        let results = history
+            .iter()
+            .find(|m| m.role == "user" && m.content.starts_with("[Tool results]"))
+            .unwrap()
+            .content
+            .clone();
+        (executed.load(std::sync::atomic::Ordering::SeqCst), results)
     }
 
-    #[test]
-    fn extract_json_values_handles_multiple_objects() {
-        // Recovery: Multiple JSON objects should all be extracted
-        let input = r#"{"a": 1}{"b": 2}{"c": 3}"#;
-        let result = extract_json_values(input);
-        assert_eq!(result.len(), 3);
+    #[tokio::test]
+    async fn loop_retries_transient_tool_failures() {
+        let (executed, results) = run_flaky_count(ToolRetryPolicy {
+            max_retries: 2,
+            backoff_ms: 1,
+        })
+        .await;
+        assert_eq!(executed, 2);
+        assert!(results.contains("counted"), "{results}");
+        assert!(!results.contains("connection reset"), "{results}");
+
+        let (executed, results) = run_flaky_count(ToolRetryPolicy::default()).await;
+        assert_eq!(executed, 1);
+        assert!(results.contains("connection reset"), "{results}");
+    }
+
+    /// Sends a broken tool call, then (after feedback) a valid one, then answers.
+    struct MalformedThenValidProvider {
+        requests: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for MalformedThenValidProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("done".into())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<crate::providers::ChatResponse> {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push(request.messages.last().unwrap().content.clone());
+            let text = match requests.len() {
+                1 => "```tool_call\n{\"name\": \"snap\", \"arguments\": {\n```",
+                2 => "```tool_call\n{\"name\": \"snap\", \"arguments\": {}}\n```",
+                _ => "Snapshot taken.",
+            };
+            Ok(crate::providers::ChatResponse {
+                text: Some(text.into()),
+                tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
+            })
+        }
     }
 
-    #[test]
-    fn extract_json_values_handles_arrays() {
-        // Recovery: JSON arrays should be extracted
-        let input = r#"[1, 2, 3]{"key": "value"}"#;
-        let result = extract_json_values(input);
-        assert_eq!(result.len(), 2);
+    #[tokio::test]
+    async fn malformed_tool_call_gets_one_corrective_retry() {
+        let provider = MalformedThenValidProvider {
+            requests: std::sync::Mutex::new(Vec::new()),
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SnapTool)];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("snap it")];
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response, "Snapshot taken.");
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(
+            requests[1].starts_with("Your tool_calls block was invalid JSON: "),
+            "{}",
+            requests[1]
+        );
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // Recovery Tests - Constants Validation
-    // ═══════════════════════════════════════════════════════════════════════
+    struct CrlfProvider {
+        calls: std::sync::Mutex<usize>,
+    }
 
-    const _: () = {
-        assert!(DEFAULT_MAX_TOOL_ITERATIONS > 0);
-        assert!(DEFAULT_MAX_TOOL_ITERATIONS <= 100);
-        assert!(DEFAULT_MAX_HISTORY_MESSAGES > 0);
-        assert!(DEFAULT_MAX_HISTORY_MESSAGES <= 1000);
-    };
+    #[async_trait::async_trait]
+    impl Provider for CrlfProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("done".into())
+        }
 
-    #[test]
-    fn constants_bounds_are_compile_time_checked() {
-        // Bounds are enforced by the const assertions above.
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<crate::providers::ChatResponse> {
+            let mut calls = self.calls.lock().unwrap();
+            *calls += 1;
+            let text = if *calls == 1 {
+                "Taking a snapshot.\r\n\r\n\r\n```tool_call\r\n{\"name\": \"snap\", \"arguments\": {}}\r\n```\r\n"
+            } else {
+                "Snapshot\r\ntaken.\r\n\r\n\r\n\r\nNothing else to do."
+            };
+            Ok(crate::providers::ChatResponse {
+                text: Some(text.into()),
+                tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
+            })
+        }
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // Recovery Tests - Tool Call Value Parsing
-    // ═══════════════════════════════════════════════════════════════════════
+    #[tokio::test]
+    async fn crlf_output_is_normalized_before_parsing_and_printing() {
+        let provider = CrlfProvider {
+            calls: std::sync::Mutex::new(0),
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SnapTool)];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("snap it")];
+        let mut summary = TurnSummary::default();
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                summary: Some(&mut summary),
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
 
-    #[test]
-    fn parse_tool_call_value_handles_missing_name_field() {
-        // Recovery: Missing name field should return None
-        let value = serde_json::json!({"function": {"arguments": {}}});
-        let result = parse_tool_call_value(&value);
-        assert!(result.is_none());
+        assert_eq!(response, "Snapshot\ntaken.\n\nNothing else to do.");
+        assert_eq!(summary.tool_calls(), 1);
+        assert!(history.iter().all(|m| !m.content.contains('\r')));
     }
 
-    #[test]
-    fn parse_tool_call_value_handles_top_level_name() {
-        // Recovery: Tool call with name at top level (non-OpenAI format)
-        let value = serde_json::json!({"name": "test_tool", "arguments": {}});
-        let result = parse_tool_call_value(&value);
-        assert!(result.is_some());
-        assert_eq!(result.unwrap().name, "test_tool");
-    }
+    #[tokio::test]
+    async fn valid_prose_is_answered_without_corrective_feedback() {
+        let provider = NarratingProvider {
+            calls: std::sync::Mutex::new(1),
+        };
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &[],
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                max_tool_iterations: 5,
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
 
-    #[test]
-    fn parse_tool_calls_from_json_value_handles_empty_array() {
-        // Recovery: Empty tool_calls array should return empty vec
-        let value = serde_json::json!({"tool_calls": []});
-        let result = parse_tool_calls_from_json_value(&value);
-        assert!(result.is_empty());
+        assert_eq!(response, "The snapshot looks fine.");
+        assert!(!history.iter().any(|m| m.content.contains("invalid JSON")));
     }
 
-    #[test]
-    fn parse_tool_calls_from_json_value_handles_missing_tool_calls() {
-        // Recovery: Missing tool_calls field should fall through
-        let value = serde_json::json!({"name": "test", "arguments": {}});
-        let result = parse_tool_calls_from_json_value(&value);
-        assert_eq!(result.len(), 1);
+    #[tokio::test]
+    async fn image_artifact_is_base64_attached_for_vision_model() {
+        let history = run_snap_turn(true, None).await;
+        let attachment = history
+            .iter()
+            .find(|m| m.role == "user" && m.content.starts_with("[Tool artifacts]"))
+            .expect("artifact attachment message");
+        assert!(attachment
+            .content
+            .contains("[IMAGE:data:image/png;base64,aGVsbG8=]"));
+        assert!(!history
+            .iter()
+            .any(|m| m.content.contains("[Artifact saved:")));
     }
 
-    #[test]
-    fn parse_tool_calls_from_json_value_handles_top_level_array() {
-        // Recovery: Top-level array of tool calls
-        let value = serde_json::json!([
-            {"name": "tool_a", "arguments": {}},
-            {"name": "tool_b", "arguments": {}}
-        ]);
-        let result = parse_tool_calls_from_json_value(&value);
-        assert_eq!(result.len(), 2);
+    #[tokio::test]
+    async fn image_artifact_is_saved_and_referenced_for_text_only_model() {
+        /// Path of the saved artifact named in the tool results.
+        fn saved_path(history: &[ChatMessage]) -> PathBuf {
+            let results = history
+                .iter()
+                .find(|m| m.role == "user" && m.content.starts_with("[Tool results]"))
+                .expect("tool results message");
+            let start =
+                results.content.find("[Artifact saved: ").unwrap() + "[Artifact saved: ".len();
+            let end = results.content[start..].find(" (image/png)]").unwrap() + start;
+            PathBuf::from(&results.content[start..end])
+        }
+
+        let scratch = TempDir::new().unwrap();
+        let history = run_snap_turn(false, Some(scratch.path())).await;
+        assert!(!history
+            .iter()
+            .any(|m| m.content.starts_with("[Tool artifacts]")));
+        let path = saved_path(&history);
+        assert_eq!(path.parent(), Some(scratch.path()));
+        assert_eq!(path.extension().unwrap(), "png");
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+
+        // Without a scratch directory the file only lasts for the turn.
+        let history = run_snap_turn(false, None).await;
+        assert!(!saved_path(&history).exists());
+    }
+
+    /// Answer one Anthropic Messages request per reply in `replies`,
+    /// forwarding each request body.
+    async fn serve_anthropic(
+        replies: Vec<&'static str>,
+    ) -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for reply in replies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let body = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some(head_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..head_end]
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                            })
+                            .unwrap_or(0);
+                        if request.len() >= head_end + 4 + content_length || n == 0 {
+                            break text[head_end + 4..].to_string();
+                        }
+                    }
+                };
+                tx.send(body).unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{reply}",
+                    reply.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (format!("http://{addr}"), rx)
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // GLM-Style Tool Call Parsing
-    // ═══════════════════════════════════════════════════════════════════════
+    #[tokio::test]
+    async fn image_artifact_reaches_anthropic_as_an_image_block() {
+        let (url, mut bodies) = serve_anthropic(vec![
+            r#"{"content":[{"type":"tool_use","id":"toolu_1","name":"snap","input":{}}],"stop_reason":"tool_use"}"#,
+            r#"{"content":[{"type":"text","text":"done"}],"stop_reason":"end_turn"}"#,
+        ])
+        .await;
+        let provider = crate::providers::anthropic::AnthropicProvider::with_base_url(
+            Some("test-key"),
+            Some(&url),
+        );
+        assert!(provider.supports_vision());
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SnapTool)];
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("snap it")];
 
-    #[test]
-    fn parse_glm_style_browser_open_url() {
-        let response = "browser_open/url>https://example.com";
-        let calls = parse_glm_style_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].0, "shell");
-        assert!(calls[0].1["command"].as_str().unwrap().contains("curl"));
-        assert!(calls[0].1["command"]
-            .as_str()
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "anthropic",
+            "claude-test",
+            0.0,
+            ToolLoopOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response, "done");
+
+        bodies.recv().await.unwrap();
+        let second: serde_json::Value =
+            serde_json::from_str(&bodies.recv().await.unwrap()).unwrap();
+        let blocks: Vec<&serde_json::Value> = second["messages"]
+            .as_array()
             .unwrap()
-            .contains("example.com"));
+            .iter()
+            .flat_map(|m| m["content"].as_array().unwrap())
+            .collect();
+        assert!(blocks.contains(&&serde_json::json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": "image/png", "data": "aGVsbG8="}
+        })));
+        assert!(!blocks
+            .iter()
+            .any(|b| b["text"].as_str().is_some_and(|t| t.contains("[IMAGE:"))));
     }
 
     #[test]
-    fn parse_glm_style_shell_command() {
-        let response = "shell/command>ls -la";
-        let calls = parse_glm_style_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].0, "shell");
-        assert_eq!(calls[0].1["command"], "ls -la");
-    }
+    fn policy_denials_are_distinguished_from_runtime_errors() {
+        assert!(is_policy_denial(&PolicyViolation::ReadOnly.into()));
+        assert!(is_policy_denial(
+            &PolicyViolation::CommandNotAllowed("rm -rf /".into()).into()
+        ));
+        assert!(is_policy_denial(
+            &PolicyViolation::PathNotAllowed("/etc/passwd".into()).into()
+        ));
+        assert!(!is_policy_denial(&PolicyViolation::RateLimited.into()));
+        assert!(!is_policy_denial(&anyhow::anyhow!(
+            "Rate limit exceeded: too many actions in the last hour"
+        )));
+        assert!(!is_policy_denial(&anyhow::anyhow!(
+            "Path not allowed by security policy: /etc/passwd"
+        )));
+    }
+
+    /// Replays a fixed script of responses, repeating the last one forever.
+    struct ReplayProvider {
+        script: Vec<&'static str>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ReplayProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("done".into())
+        }
 
-    #[test]
-    fn parse_glm_style_http_request() {
-        let response = "http_request/url>https://api.example.com/data";
-        let calls = parse_glm_style_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].0, "http_request");
-        assert_eq!(calls[0].1["url"], "https://api.example.com/data");
-        assert_eq!(calls[0].1["method"], "GET");
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<crate::providers::ChatResponse> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let text = self.script[call.min(self.script.len() - 1)];
+            Ok(crate::providers::ChatResponse {
+                text: Some(text.into()),
+                tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
+            })
+        }
     }
 
-    #[test]
-    fn parse_glm_style_plain_url() {
-        let response = "https://example.com/api";
-        let calls = parse_glm_style_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].0, "shell");
-        assert!(calls[0].1["command"].as_str().unwrap().contains("curl"));
+    #[derive(Default)]
+    struct BudgetObserver {
+        exhausted: std::sync::Mutex<Vec<(u64, Option<u64>)>>,
     }
 
-    #[test]
-    fn parse_glm_style_json_args() {
-        let response = r#"shell/{"command": "echo hello"}"#;
-        let calls = parse_glm_style_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].0, "shell");
-        assert_eq!(calls[0].1["command"], "echo hello");
-    }
+    impl Observer for BudgetObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            if let ObserverEvent::BudgetExhausted {
+                tokens_used,
+                max_tokens,
+                ..
+            } = event
+            {
+                self.exhausted
+                    .lock()
+                    .unwrap()
+                    .push((*tokens_used, *max_tokens));
+            }
+        }
 
-    #[test]
-    fn parse_glm_style_multiple_calls() {
-        let response = r#"shell/command>ls
-browser_open/url>https://example.com"#;
-        let calls = parse_glm_style_tool_calls(response);
-        assert_eq!(calls.len(), 2);
-    }
+        fn record_metric(&self, _metric: &crate::observability::traits::ObserverMetric) {}
 
-    #[test]
-    fn parse_glm_style_tool_call_integration() {
-        // Integration test: GLM format should be parsed in parse_tool_calls
-        let response = "Checking...\nbrowser_open/url>https://example.com\nDone";
-        let (text, calls) = parse_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert!(text.contains("Checking"));
-        assert!(text.contains("Done"));
+        fn name(&self) -> &str {
+            "budget"
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
     }
 
-    #[test]
-    fn parse_glm_style_rejects_non_http_url_param() {
-        let response = "browser_open/url>javascript:alert(1)";
-        let calls = parse_glm_style_tool_calls(response);
-        assert!(calls.is_empty());
+    #[tokio::test]
+    async fn session_budget_stops_a_runaway_tool_loop() {
+        let provider = ReplayProvider {
+            script: vec![
+                "Probing once more.\n<tool_call>\n{\"name\": \"probe\", \"arguments\": {}}\n</tool_call>",
+            ],
+            calls: AtomicUsize::new(0),
+        };
+        let calls = Arc::new(AtomicUsize::new(0));
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(ProbeTool {
+            ran: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            calls: Arc::clone(&calls),
+        })];
+        let budget = SessionBudget::from_config(
+            &crate::config::LimitsConfig {
+                max_tokens_per_session: 200,
+                max_cost_per_session: 0.0,
+            },
+            &crate::config::CostConfig::default(),
+        )
+        .unwrap();
+        let observer = BudgetObserver::default();
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("probe it")];
+
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &observer,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                channel_name: "channel",
+                max_tool_iterations: 50,
+                budget: Some(&budget),
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let rounds = provider.calls.load(Ordering::SeqCst);
+        assert!((2..10).contains(&rounds), "{rounds} provider calls");
+        assert_eq!(calls.load(Ordering::SeqCst), rounds);
+        assert!(response.starts_with("Probing once more."), "{response}");
+        assert!(response.contains("Session budget exhausted"), "{response}");
+        assert_eq!(history.last().unwrap().content, response);
+
+        let exhausted = observer.exhausted.lock().unwrap();
+        assert_eq!(exhausted.len(), 1);
+        let (tokens_used, max_tokens) = exhausted[0];
+        assert_eq!(max_tokens, Some(200));
+        assert!(tokens_used > 0);
     }
 
-    #[test]
-    fn parse_tool_calls_handles_unclosed_tool_call_tag() {
-        let response = "<tool_call>{\"name\":\"shell\",\"arguments\":{\"command\":\"pwd\"}}\nDone";
-        let (text, calls) = parse_tool_calls(response);
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0].name, "shell");
-        assert_eq!(calls[0].arguments["command"], "pwd");
-        assert_eq!(text, "Done");
+    #[tokio::test]
+    async fn a_budget_too_small_for_the_first_call_makes_no_calls() {
+        let provider = ReplayProvider {
+            script: vec!["never sent"],
+            calls: AtomicUsize::new(0),
+        };
+        let budget = SessionBudget::from_config(
+            &crate::config::LimitsConfig {
+                max_tokens_per_session: 1,
+                max_cost_per_session: 0.0,
+            },
+            &crate::config::CostConfig::default(),
+        )
+        .unwrap();
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("hello there")];
+
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &[],
+            &crate::observability::NoopObserver,
+            "test",
+            "test-model",
+            0.0,
+            ToolLoopOptions {
+                channel_name: "channel",
+                max_tool_iterations: 5,
+                budget: Some(&budget),
+                ..ToolLoopOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+        assert!(response.starts_with("⛔ Session budget exhausted (~0 of 1 tokens)"));
     }
 }
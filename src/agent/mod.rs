@@ -1,15 +1,41 @@
 #[allow(clippy::module_inception)]
 pub mod agent;
+pub mod artifacts;
+pub mod batch;
 pub mod classifier;
+pub mod cli;
+pub mod context_budget;
+pub mod context_builder;
 pub mod dispatcher;
+pub mod history;
+pub mod hooks;
 pub mod loop_;
 pub mod memory_loader;
+pub mod output;
+pub mod parser;
+pub mod plan;
 pub mod prompt;
+pub mod repl;
+pub mod replay;
+pub mod routing;
+pub mod session;
+pub mod shutdown;
+pub mod task;
+pub mod turn_summary;
+pub mod watch;
 
 #[cfg(test)]
 mod tests;
 
 #[allow(unused_imports)]
-pub use agent::{Agent, AgentBuilder};
+pub use agent::{
+    Agent, AgentBuilder, AgentEvent, AgentEventHandler, AgentResponse, AgentUsage, ToolCallRecord,
+};
 #[allow(unused_imports)]
-pub use loop_::{process_message, run};
+pub use cli::{run, run_with_timeout};
+#[allow(unused_imports)]
+pub use context_builder::{BuiltContext, ContextBuilder};
+#[allow(unused_imports)]
+pub use history::ConversationHistory;
+#[allow(unused_imports)]
+pub use loop_::process_message;
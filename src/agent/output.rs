@@ -0,0 +1,369 @@
+//! Terminal output and exit codes for `zeroclaw agent`.
+//!
+//! Only the model's final answer, and output the user explicitly asked for
+//! such as interactive `/help`, goes to stdout so it can be piped into other
+//! commands; everything else (progress, tool activity, interactive UI) goes to
+//! stderr, is filtered by the process-wide [`Verbosity`] set from
+//! `--quiet` / `--verbose` and is styled by [`crate::term`]. Model reasoning
+//! is never part of the response; with `--show-thinking` it is printed
+//! dimmed to stderr. With `--output json`, a single-message run prints one
+//! JSON object instead of plain text.
+//!
+//! Exit codes: `0` success, `1` error (including a provider that failed after
+//! retries), `2` invalid arguments, `3` a tool call was denied by security
+//! policy or approval in single-message mode, `124` the run exceeded
+//! `--timeout-secs`, `130` the run was stopped by SIGINT/SIGTERM.
+
+use crate::term::{self, Stream};
+use std::fmt::Display;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Mutex;
+
+/// How much the CLI reports besides the final response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Final response only (`--quiet`).
+    Quiet = 0,
+    /// Progress on stderr.
+    #[default]
+    Normal = 1,
+    /// Also tool arguments and timing (`-v`).
+    Verbose = 2,
+    /// Also provider request metadata (`-vv`).
+    Debug = 3,
+}
+
+impl Verbosity {
+    /// Map `--quiet` and the number of `-v` flags to a level.
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Self::Quiet,
+            (false, 0) => Self::Normal,
+            (false, 1) => Self::Verbose,
+            (false, _) => Self::Debug,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Quiet,
+            1 => Self::Normal,
+            2 => Self::Verbose,
+            _ => Self::Debug,
+        }
+    }
+}
+
+/// Exit code for a run where a tool call was denied by policy.
+pub const EXIT_POLICY_DENIED: i32 = 3;
+
+/// Returned by single-message runs in which the model's tool calls were refused.
+#[derive(Debug, thiserror::Error)]
+#[error("Tool call(s) denied by security policy or approval: {}", tools.join(", "))]
+pub struct PolicyDenied {
+    pub tools: Vec<String>,
+}
+
+/// Exit code for a run cancelled by `--timeout-secs` (as with `timeout(1)`).
+pub const EXIT_TIMEOUT: i32 = 124;
+
+/// Returned when a run is cancelled by `--timeout-secs`.
+#[derive(Debug, thiserror::Error)]
+#[error("Agent run timed out after {secs}s")]
+pub struct TimedOut {
+    pub secs: u64,
+}
+
+/// Exit code for a run stopped by SIGINT/SIGTERM (128 + SIGINT, as shells report).
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// Returned when a run is stopped by SIGINT/SIGTERM.
+#[derive(Debug, thiserror::Error)]
+#[error("Agent run interrupted")]
+pub struct Interrupted;
+
+/// Process exit code for a failed run.
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    if error.downcast_ref::<PolicyDenied>().is_some() {
+        EXIT_POLICY_DENIED
+    } else if error.downcast_ref::<TimedOut>().is_some() {
+        EXIT_TIMEOUT
+    } else if error.downcast_ref::<Interrupted>().is_some() {
+        EXIT_INTERRUPTED
+    } else {
+        1
+    }
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+pub fn set_verbosity(level: Verbosity) {
+    VERBOSITY.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn verbosity() -> Verbosity {
+    Verbosity::from_u8(VERBOSITY.load(Ordering::Relaxed))
+}
+
+static SHOW_THINKING: AtomicBool = AtomicBool::new(false);
+
+/// Print model reasoning on stderr (`--show-thinking`).
+pub fn set_show_thinking(show: bool) {
+    SHOW_THINKING.store(show, Ordering::Relaxed);
+}
+
+pub fn show_thinking() -> bool {
+    SHOW_THINKING.load(Ordering::Relaxed)
+}
+
+static EXPLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Print the prose behind each round of tool calls on stderr (`--explain`).
+pub fn set_explain(explain: bool) {
+    EXPLAIN.store(explain, Ordering::Relaxed);
+}
+
+pub fn explain() -> bool {
+    EXPLAIN.load(Ordering::Relaxed)
+}
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Print single-message results as JSON (`--output json`).
+pub fn set_json_output(json: bool) {
+    JSON_OUTPUT.store(json, Ordering::Relaxed);
+}
+
+pub fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+static PARTIAL_RESPONSE: Mutex<String> = Mutex::new(String::new());
+
+/// Remember prose the model wrote in the current turn, so a run cancelled
+/// by `--timeout-secs` can still print what it had.
+pub fn record_partial_response(text: &str) {
+    if let Ok(mut partial) = PARTIAL_RESPONSE.lock() {
+        if !partial.is_empty() {
+            partial.push_str("\n\n");
+        }
+        partial.push_str(text);
+    }
+}
+
+/// Forget the partial response once its turn has produced an answer.
+pub fn clear_partial_response() {
+    if let Ok(mut partial) = PARTIAL_RESPONSE.lock() {
+        partial.clear();
+    }
+}
+
+/// Take the partial response of the unfinished turn, if any.
+pub fn take_partial_response() -> String {
+    PARTIAL_RESPONSE
+        .lock()
+        .map(|mut partial| std::mem::take(&mut *partial))
+        .unwrap_or_default()
+}
+
+/// `--output json` result: the answer plus the prose the model wrote
+/// alongside its tool calls, in order.
+pub fn json_response(response: &str, assistant_updates: &[String]) -> serde_json::Value {
+    serde_json::json!({
+        "response": response,
+        "assistant_updates": assistant_updates,
+    })
+}
+
+/// Output sink for one agent run.
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    level: Verbosity,
+    show_thinking: bool,
+    explain: bool,
+}
+
+impl Output {
+    pub fn new(level: Verbosity) -> Self {
+        Self {
+            level,
+            show_thinking: false,
+            explain: false,
+        }
+    }
+
+    /// Output for a run on `channel_name`. Only the CLI reports progress on the
+    /// terminal; other channels (and `silent` runs) stay quiet.
+    pub fn for_channel(channel_name: &str, silent: bool) -> Self {
+        if silent || channel_name != "cli" {
+            Self::new(Verbosity::Quiet)
+        } else {
+            Self {
+                level: verbosity(),
+                show_thinking: show_thinking(),
+                explain: explain(),
+            }
+        }
+    }
+
+    pub fn shows_thinking(&self) -> bool {
+        self.show_thinking
+    }
+
+    pub fn explains(&self) -> bool {
+        self.explain
+    }
+
+    pub fn level(&self) -> Verbosity {
+        self.level
+    }
+
+    /// The final response or requested command output, always on stdout.
+    pub fn response(&self, text: impl Display) {
+        term::response(text);
+    }
+
+    /// Progress and interactive UI (default level).
+    pub fn progress(&self, text: impl Display) {
+        if self.level >= Verbosity::Normal {
+            term::eprintln(text);
+        }
+    }
+
+    /// Tool arguments and timing (`-v`).
+    pub fn verbose(&self, text: impl Display) {
+        if self.level >= Verbosity::Verbose {
+            term::eprintln(text);
+        }
+    }
+
+    /// Provider request metadata (`-vv`).
+    pub fn debug(&self, text: impl Display) {
+        if self.level >= Verbosity::Debug {
+            term::eprintln(text);
+        }
+    }
+
+    /// Model reasoning, dimmed (`--show-thinking` only).
+    pub fn thinking(&self, text: impl Display) {
+        if self.show_thinking {
+            term::eprintln(term::dim(format_args!("💭 {text}"), Stream::Stderr));
+        }
+    }
+
+    /// Why the model chose `tools`, dimmed (`--explain` only).
+    pub fn rationale(&self, tools: &str, rationale: &str) {
+        if self.explain {
+            let line = crate::i18n::tf(
+                "explain.rationale",
+                &[("tools", &tools), ("rationale", &rationale)],
+            );
+            term::eprintln(term::dim(line, Stream::Stderr));
+        }
+    }
+
+    /// Input prompt without a trailing newline; shown even with `--quiet`.
+    pub fn prompt(&self, text: impl Display) {
+        term::eprint(text);
+        let _ = std::io::stderr().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_map_to_levels() {
+        assert_eq!(Verbosity::from_flags(false, 0), Verbosity::Normal);
+        assert_eq!(Verbosity::from_flags(false, 1), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(false, 2), Verbosity::Debug);
+        assert_eq!(Verbosity::from_flags(false, 5), Verbosity::Debug);
+        assert_eq!(Verbosity::from_flags(true, 2), Verbosity::Quiet);
+    }
+
+    #[test]
+    fn non_cli_channels_are_quiet() {
+        assert_eq!(
+            Output::for_channel("telegram", false).level(),
+            Verbosity::Quiet
+        );
+        assert_eq!(Output::for_channel("cli", true).level(), Verbosity::Quiet);
+    }
+
+    #[test]
+    fn thinking_is_cli_only_and_opt_in() {
+        assert!(!Output::new(Verbosity::Debug).shows_thinking());
+        set_show_thinking(true);
+        assert!(Output::for_channel("cli", false).shows_thinking());
+        assert!(!Output::for_channel("cli", true).shows_thinking());
+        assert!(!Output::for_channel("slack", false).shows_thinking());
+        set_show_thinking(false);
+    }
+
+    #[test]
+    fn explain_is_cli_only_and_opt_in() {
+        assert!(!Output::new(Verbosity::Debug).explains());
+        set_explain(true);
+        assert!(Output::for_channel("cli", false).explains());
+        assert!(!Output::for_channel("discord", false).explains());
+        set_explain(false);
+        assert!(!Output::for_channel("cli", false).explains());
+    }
+
+    #[test]
+    fn json_response_lists_intermediate_updates() {
+        let value = json_response("Done.", &["I'll check the file first.".to_string()]);
+        assert_eq!(value["response"], "Done.");
+        assert_eq!(value["assistant_updates"][0], "I'll check the file first.");
+        assert_eq!(
+            json_response("Done.", &[])["assistant_updates"],
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn policy_denial_maps_to_dedicated_exit_code() {
+        let denied = anyhow::Error::new(PolicyDenied {
+            tools: vec!["shell".into()],
+        });
+        assert_eq!(exit_code(&denied), EXIT_POLICY_DENIED);
+        assert!(denied.to_string().contains("shell"));
+        assert_eq!(exit_code(&anyhow::anyhow!("provider error")), 1);
+    }
+
+    #[test]
+    fn timeout_maps_to_exit_code_124() {
+        let timed_out = anyhow::Error::new(TimedOut { secs: 30 });
+        assert_eq!(exit_code(&timed_out), EXIT_TIMEOUT);
+        assert_eq!(timed_out.to_string(), "Agent run timed out after 30s");
+    }
+
+    #[test]
+    fn interruption_maps_to_exit_code_130() {
+        assert_eq!(exit_code(&Interrupted.into()), EXIT_INTERRUPTED);
+    }
+
+    #[test]
+    fn partial_response_collects_updates_until_taken() {
+        clear_partial_response();
+        record_partial_response("Reading the config.");
+        record_partial_response("Now checking the tests.");
+        assert_eq!(
+            take_partial_response(),
+            "Reading the config.\n\nNow checking the tests."
+        );
+        assert!(take_partial_response().is_empty());
+    }
+
+    #[test]
+    fn levels_are_ordered() {
+        assert!(Verbosity::Quiet < Verbosity::Normal);
+        assert!(Verbosity::Verbose < Verbosity::Debug);
+        assert_eq!(
+            Verbosity::from_u8(Verbosity::Verbose as u8),
+            Verbosity::Verbose
+        );
+    }
+}
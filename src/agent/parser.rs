@@ -0,0 +1,1691 @@
+//! Tool calls in model responses.
+//!
+//! Providers with a native tool API return structured calls; everything else
+//! writes them into the text. [`parse_tool_calls`] recognizes `<tool_call>`
+//! tags and their aliases, tool-call code fences, bare tool-call JSON and
+//! GLM-style `tool/param>value` lines, while leaving tool-call syntax inside
+//! quoted code alone. Each [`ParsedToolCall`] records the [`ParseSource`]
+//! that matched it.
+
+use crate::providers::ToolCall;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Nested string layers unwrapped when a model JSON-encodes its arguments
+/// more than once.
+const MAX_ARGUMENT_ENCODING_DEPTH: usize = 3;
+
+/// Decode tool-call arguments that arrive as a JSON string, e.g.
+/// `"{\"command\": \"ls\"}"`, including strings encoded more than once.
+/// Anything that doesn't decode to a JSON value becomes `{}`.
+fn decode_arguments_string(raw: &str) -> serde_json::Value {
+    let mut decoded = match serde_json::from_str::<serde_json::Value>(raw.trim()) {
+        Ok(value) => value,
+        Err(_) => return serde_json::Value::Object(serde_json::Map::new()),
+    };
+    for _ in 1..MAX_ARGUMENT_ENCODING_DEPTH {
+        let serde_json::Value::String(inner) = &decoded else {
+            break;
+        };
+        match serde_json::from_str::<serde_json::Value>(inner.trim()) {
+            Ok(value) => decoded = value,
+            Err(_) => break,
+        }
+    }
+    if decoded.is_string() {
+        serde_json::Value::Object(serde_json::Map::new())
+    } else {
+        decoded
+    }
+}
+
+fn parse_arguments_value(raw: Option<&serde_json::Value>) -> serde_json::Value {
+    match raw {
+        Some(serde_json::Value::String(s)) => decode_arguments_string(s),
+        Some(value) => value.clone(),
+        None => serde_json::Value::Object(serde_json::Map::new()),
+    }
+}
+
+fn parse_tool_call_value(value: &serde_json::Value, source: ParseSource) -> Option<ParsedToolCall> {
+    if let Some(function) = value.get("function") {
+        let name = function
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        if !name.is_empty() {
+            let arguments = parse_arguments_value(function.get("arguments"));
+            return Some(ParsedToolCall {
+                name,
+                arguments,
+                source,
+            });
+        }
+    }
+
+    let name = value
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim()
+        .to_string();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let arguments = parse_arguments_value(value.get("arguments"));
+    Some(ParsedToolCall {
+        name,
+        arguments,
+        source,
+    })
+}
+
+/// Keys some models use for the tool name instead of `name`.
+const TOOL_NAME_KEY_VARIANTS: [&str; 3] = ["tool_name", "action", "tool"];
+/// Keys that may hold the arguments next to a tool-name key variant.
+const TOOL_ARGUMENT_KEY_VARIANTS: [&str; 5] =
+    ["arguments", "args", "parameters", "action_input", "input"];
+
+fn is_tool_name_like(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Tool-call shapes that only count inside an explicit tool-call wrapper:
+/// `{"tool_name"|"action"|"tool": "shell", ...}` with the arguments under an
+/// argument key or as the remaining keys, and `{"shell": {"command": "ls"}}`
+/// keyed by the tool name.
+fn parse_loose_tool_call_value(
+    value: &serde_json::Value,
+    source: ParseSource,
+) -> Option<ParsedToolCall> {
+    let object = value.as_object()?;
+
+    for key in TOOL_NAME_KEY_VARIANTS {
+        let Some(name) = object.get(key).and_then(|v| v.as_str()).map(str::trim) else {
+            continue;
+        };
+        if !is_tool_name_like(name) {
+            continue;
+        }
+        let arguments = match TOOL_ARGUMENT_KEY_VARIANTS
+            .iter()
+            .find_map(|arg_key| object.get(*arg_key))
+        {
+            Some(raw) => parse_arguments_value(Some(raw)),
+            None => serde_json::Value::Object(
+                object
+                    .iter()
+                    .filter(|(k, _)| k.as_str() != key)
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            ),
+        };
+        return Some(ParsedToolCall {
+            name: name.to_string(),
+            arguments,
+            source,
+        });
+    }
+
+    if object.len() == 1 {
+        let (name, arguments) = object.iter().next()?;
+        let reserved = name == "function" || TOOL_ARGUMENT_KEY_VARIANTS.contains(&name.as_str());
+        if is_tool_name_like(name) && !reserved && arguments.is_object() {
+            return Some(ParsedToolCall {
+                name: name.clone(),
+                arguments: arguments.clone(),
+                source,
+            });
+        }
+    }
+
+    None
+}
+
+/// Tool calls inside `<tool_call>` tags or a tool_call code block: the
+/// standard shapes first, then [`parse_loose_tool_call_value`].
+fn parse_wrapped_tool_calls_from_json_value(
+    value: &serde_json::Value,
+    source: ParseSource,
+) -> Vec<ParsedToolCall> {
+    let calls = parse_tool_calls_from_json_value(value, source);
+    if !calls.is_empty() {
+        return calls;
+    }
+    match value.as_array() {
+        Some(items) => items
+            .iter()
+            .filter_map(|item| parse_loose_tool_call_value(item, source))
+            .collect(),
+        None => parse_loose_tool_call_value(value, source)
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn parse_tool_calls_from_json_value(
+    value: &serde_json::Value,
+    source: ParseSource,
+) -> Vec<ParsedToolCall> {
+    let mut calls = Vec::new();
+
+    if let Some(tool_calls) = value.get("tool_calls").and_then(|v| v.as_array()) {
+        for call in tool_calls {
+            if let Some(parsed) = parse_tool_call_value(call, source) {
+                calls.push(parsed);
+            }
+        }
+
+        if !calls.is_empty() {
+            return calls;
+        }
+    }
+
+    if let Some(array) = value.as_array() {
+        for item in array {
+            if let Some(parsed) = parse_tool_call_value(item, source) {
+                calls.push(parsed);
+            }
+        }
+        return calls;
+    }
+
+    if let Some(parsed) = parse_tool_call_value(value, source) {
+        calls.push(parsed);
+    }
+
+    calls
+}
+
+const TOOL_CALL_OPEN_TAGS: [&str; 4] = ["<tool_call>", "<toolcall>", "<tool-call>", "<invoke>"];
+
+fn find_first_tag<'a>(haystack: &str, tags: &'a [&'a str]) -> Option<(usize, &'a str)> {
+    tags.iter()
+        .filter_map(|tag| haystack.find(tag).map(|idx| (idx, *tag)))
+        .min_by_key(|(idx, _)| *idx)
+}
+
+/// Fence languages that may wrap a tool call rather than quote code. A bare
+/// fence counts too: the tool instructions show the format inside one.
+pub(crate) fn is_tool_call_fence(info: &str) -> bool {
+    matches!(
+        info.to_ascii_lowercase().as_str(),
+        "" | "xml" | "json" | "tool_call" | "toolcall" | "tool-call" | "invoke"
+    )
+}
+
+/// Byte ranges of closed fenced code blocks whose language marks them as
+/// quoted code (```rust, ```python, ...). Tool-call tags inside them are part
+/// of what the model is showing, not calls to run.
+fn quoted_code_ranges(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut open: Option<(usize, bool)> = None;
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        if let Some(info) = line.trim_start().strip_prefix("```") {
+            match open {
+                None => open = Some((line_start, !is_tool_call_fence(info.trim()))),
+                Some((start, quoted)) if info.trim().is_empty() => {
+                    if quoted {
+                        ranges.push((start, line_end));
+                    }
+                    open = None;
+                }
+                Some(_) => {}
+            }
+        }
+        line_start = line_end;
+    }
+    ranges
+}
+
+/// [`find_first_tag`] that skips tags inside `quoted` ranges. `offset` is the
+/// position of `haystack` within the text the ranges were computed for.
+fn find_unquoted_tag<'a>(
+    haystack: &str,
+    offset: usize,
+    quoted: &[(usize, usize)],
+    tags: &'a [&'a str],
+) -> Option<(usize, &'a str)> {
+    let mut from = 0;
+    loop {
+        let (idx, tag) = find_first_tag(&haystack[from..], tags)?;
+        let absolute = offset + from + idx;
+        match quoted
+            .iter()
+            .find(|(start, end)| *start <= absolute && absolute < *end)
+        {
+            Some((_, end)) => from = end - offset,
+            None => return Some((from + idx, tag)),
+        }
+    }
+}
+
+/// Drop the opening line of a fence that only wrapped a tool call, so the
+/// prose shown to the user doesn't end in a dangling ```` ``` ````.
+fn strip_wrapping_fence_open(before: &str) -> &str {
+    let trimmed = before.trim_end();
+    let line_start = trimmed.rfind('\n').map_or(0, |i| i + 1);
+    match trimmed[line_start..].trim_start().strip_prefix("```") {
+        Some(info) if is_tool_call_fence(info.trim()) => &trimmed[..line_start],
+        _ => before,
+    }
+}
+
+/// Counterpart of [`strip_wrapping_fence_open`] for the closing fence.
+fn strip_wrapping_fence_close(after: &str) -> &str {
+    let trimmed = after.trim_start();
+    match trimmed.strip_prefix("```") {
+        Some(rest) if rest.starts_with('\n') || rest.trim().is_empty() => rest,
+        _ => after,
+    }
+}
+
+fn matching_tool_call_close_tag(open_tag: &str) -> Option<&'static str> {
+    match open_tag {
+        "<tool_call>" => Some("</tool_call>"),
+        "<toolcall>" => Some("</toolcall>"),
+        "<tool-call>" => Some("</tool-call>"),
+        "<invoke>" => Some("</invoke>"),
+        _ => None,
+    }
+}
+
+fn extract_first_json_value_with_end(input: &str) -> Option<(serde_json::Value, usize)> {
+    let trimmed = input.trim_start();
+    let trim_offset = input.len().saturating_sub(trimmed.len());
+
+    for (byte_idx, ch) in trimmed.char_indices() {
+        if ch != '{' && ch != '[' {
+            continue;
+        }
+
+        let slice = &trimmed[byte_idx..];
+        let mut stream = serde_json::Deserializer::from_str(slice).into_iter::<serde_json::Value>();
+        if let Some(Ok(value)) = stream.next() {
+            let consumed = stream.byte_offset();
+            if consumed > 0 {
+                return Some((value, trim_offset + byte_idx + consumed));
+            }
+        }
+    }
+
+    None
+}
+
+/// Find a balanced, tool-call-shaped JSON object/array that ends the
+/// response (or is all of it), returning the prose before it and the value.
+///
+/// Values are taken whole: an array of calls is not split into elements, and
+/// an object nested inside other JSON is never picked out. JSON followed by
+/// more prose, or inside quoted code fences or `>` blockquotes, is what the
+/// model is showing, not a call.
+fn extract_trailing_tool_json(input: &str) -> Option<(&str, serde_json::Value)> {
+    let quoted = quoted_code_ranges(input);
+    let mut from = 0;
+    while let Some(offset) = input[from..].find(['{', '[']) {
+        let byte_idx = from + offset;
+        if let Some((_, end)) = quoted
+            .iter()
+            .find(|(start, end)| *start <= byte_idx && byte_idx < *end)
+        {
+            from = *end;
+            continue;
+        }
+
+        let slice = &input[byte_idx..];
+        let mut stream = serde_json::Deserializer::from_str(slice).into_iter::<serde_json::Value>();
+        match stream.next() {
+            Some(Ok(value)) => {
+                let end = byte_idx + stream.byte_offset();
+                let line_start = input[..byte_idx].rfind('\n').map_or(0, |i| i + 1);
+                let in_blockquote = input[line_start..byte_idx].trim_start().starts_with('>');
+                if !in_blockquote && input[end..].trim().is_empty() && is_tool_call_shaped(&value) {
+                    return Some((&input[..byte_idx], value));
+                }
+                from = end;
+            }
+            _ => from = byte_idx + 1,
+        }
+    }
+
+    None
+}
+
+/// Strict tool-call shapes accepted outside of explicit wrappers: a
+/// `tool_calls` array, `{"function": {"name": ...}}`, or `{"name": ...,
+/// "arguments": ...}`. A bare `{"name": "Bob"}` is just data.
+fn is_tool_call_shaped(value: &serde_json::Value) -> bool {
+    if let Some(calls) = value.get("tool_calls").and_then(|v| v.as_array()) {
+        return !calls.is_empty();
+    }
+    if let Some(items) = value.as_array() {
+        return !items.is_empty() && items.iter().all(is_tool_call_shaped);
+    }
+    let has_name = |v: &serde_json::Value| {
+        v.get("name")
+            .and_then(|n| n.as_str())
+            .is_some_and(|n| !n.trim().is_empty())
+    };
+    if value.get("function").is_some_and(has_name) {
+        return true;
+    }
+    has_name(value) && value.get("arguments").is_some()
+}
+
+fn strip_leading_close_tags(mut input: &str) -> &str {
+    loop {
+        let trimmed = input.trim_start();
+        if !trimmed.starts_with("</") {
+            return trimmed;
+        }
+
+        let Some(close_end) = trimmed.find('>') else {
+            return "";
+        };
+        input = &trimmed[close_end + 1..];
+    }
+}
+
+/// Extract JSON values from a string.
+///
+/// # Security Warning
+///
+/// This function extracts ANY JSON objects/arrays from the input. It MUST only
+/// be used on content that is already trusted to be from the LLM, such as
+/// content inside `<invoke>` tags where the LLM has explicitly indicated intent
+/// to make a tool call. Do NOT use this on raw user input or content that
+/// could contain prompt injection payloads.
+fn extract_json_values(input: &str) -> Vec<serde_json::Value> {
+    let mut values = Vec::new();
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return values;
+    }
+
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) {
+        values.push(value);
+        return values;
+    }
+
+    let char_positions: Vec<(usize, char)> = trimmed.char_indices().collect();
+    let mut idx = 0;
+    while idx < char_positions.len() {
+        let (byte_idx, ch) = char_positions[idx];
+        if ch == '{' || ch == '[' {
+            let slice = &trimmed[byte_idx..];
+            let mut stream =
+                serde_json::Deserializer::from_str(slice).into_iter::<serde_json::Value>();
+            if let Some(Ok(value)) = stream.next() {
+                let consumed = stream.byte_offset();
+                if consumed > 0 {
+                    values.push(value);
+                    let next_byte = byte_idx + consumed;
+                    while idx < char_positions.len() && char_positions[idx].0 < next_byte {
+                        idx += 1;
+                    }
+                    continue;
+                }
+            }
+        }
+        idx += 1;
+    }
+
+    values
+}
+
+/// Find the end position of a JSON object by tracking balanced braces.
+fn find_json_end(input: &str) -> Option<usize> {
+    let trimmed = input.trim_start();
+    let offset = input.len() - trimmed.len();
+
+    if !trimmed.starts_with('{') {
+        return None;
+    }
+
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escape_next = false;
+
+    for (i, ch) in trimmed.char_indices() {
+        if escape_next {
+            escape_next = false;
+            continue;
+        }
+
+        match ch {
+            '\\' if in_string => escape_next = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(offset + i + ch.len_utf8());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Parse GLM-style tool calls from response text.
+/// GLM uses proprietary formats like:
+/// - `browser_open/url>https://example.com`
+/// - `shell/command>ls -la`
+/// - `http_request/url>https://api.example.com`
+fn map_glm_tool_alias(tool_name: &str) -> &str {
+    match tool_name {
+        "browser_open" | "browser" | "web_search" | "shell" | "bash" => "shell",
+        "http_request" | "http" => "http_request",
+        _ => tool_name,
+    }
+}
+
+fn build_curl_command(url: &str) -> Option<String> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return None;
+    }
+
+    if url.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    let escaped = url.replace('\'', r#"'\\''"#);
+    Some(format!("curl -s '{}'", escaped))
+}
+
+fn parse_glm_style_tool_calls(text: &str) -> Vec<(String, serde_json::Value, Option<String>)> {
+    let mut calls = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // Format: tool_name/param>value or tool_name/{json}
+        if let Some(pos) = line.find('/') {
+            let tool_part = &line[..pos];
+            let rest = &line[pos + 1..];
+
+            if tool_part.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                let tool_name = map_glm_tool_alias(tool_part);
+
+                if let Some(gt_pos) = rest.find('>') {
+                    let param_name = rest[..gt_pos].trim();
+                    let value = rest[gt_pos + 1..].trim();
+
+                    let arguments = match tool_name {
+                        "shell" => {
+                            if param_name == "url" {
+                                let Some(command) = build_curl_command(value) else {
+                                    continue;
+                                };
+                                serde_json::json!({"command": command})
+                            } else if value.starts_with("http://") || value.starts_with("https://")
+                            {
+                                if let Some(command) = build_curl_command(value) {
+                                    serde_json::json!({"command": command})
+                                } else {
+                                    serde_json::json!({"command": value})
+                                }
+                            } else {
+                                serde_json::json!({"command": value})
+                            }
+                        }
+                        "http_request" => {
+                            serde_json::json!({"url": value, "method": "GET"})
+                        }
+                        _ => serde_json::json!({param_name: value}),
+                    };
+
+                    calls.push((tool_name.to_string(), arguments, Some(line.to_string())));
+                    continue;
+                }
+
+                if rest.starts_with('{') {
+                    if let Ok(json_args) = serde_json::from_str::<serde_json::Value>(rest) {
+                        calls.push((tool_name.to_string(), json_args, Some(line.to_string())));
+                    }
+                }
+            }
+        }
+
+        // Plain URL
+        if let Some(command) = build_curl_command(line) {
+            calls.push((
+                "shell".to_string(),
+                serde_json::json!({"command": command}),
+                Some(line.to_string()),
+            ));
+        }
+    }
+
+    calls
+}
+
+/// Parse tool calls from an LLM response that uses XML-style function calling.
+///
+/// Expected format (common with system-prompt-guided tool use):
+/// ```text
+/// <tool_call>
+/// {"name": "shell", "arguments": {"command": "ls"}}
+/// </tool_call>
+/// ```
+///
+/// Also accepts common tag variants (`<toolcall>`, `<tool-call>`) for model
+/// compatibility.
+///
+/// Also supports JSON with `tool_calls` array from OpenAI-format responses.
+pub(crate) fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
+    let mut text_parts = Vec::new();
+    let mut calls = Vec::new();
+    let mut remaining = response;
+
+    // First, try to parse as OpenAI-style JSON response with tool_calls array
+    // This handles providers like Minimax that return tool_calls in native JSON format
+    if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(response.trim()) {
+        calls = parse_tool_calls_from_json_value(&json_value, ParseSource::DirectJson);
+        if !calls.is_empty() {
+            // If we found tool_calls, extract any content field as text
+            if let Some(content) = json_value.get("content").and_then(|v| v.as_str()) {
+                if !content.trim().is_empty() {
+                    text_parts.push(content.trim().to_string());
+                }
+            }
+            return (text_parts.join("\n"), calls);
+        }
+    }
+
+    // Fall back to XML-style tool-call tag parsing.
+    let quoted = quoted_code_ranges(response);
+    while let Some((start, open_tag)) = find_unquoted_tag(
+        remaining,
+        response.len() - remaining.len(),
+        &quoted,
+        &TOOL_CALL_OPEN_TAGS,
+    ) {
+        // Everything before the tag is text
+        let before = strip_wrapping_fence_open(&remaining[..start]);
+        if !before.trim().is_empty() {
+            text_parts.push(before.trim().to_string());
+        }
+
+        let Some(close_tag) = matching_tool_call_close_tag(open_tag) else {
+            break;
+        };
+
+        let after_open = &remaining[start + open_tag.len()..];
+        if let Some(close_idx) = after_open.find(close_tag) {
+            let inner = &after_open[..close_idx];
+            let mut parsed_any = false;
+            let json_values = extract_json_values(inner);
+            for value in json_values {
+                let parsed_calls =
+                    parse_wrapped_tool_calls_from_json_value(&value, ParseSource::Xml);
+                if !parsed_calls.is_empty() {
+                    parsed_any = true;
+                    calls.extend(parsed_calls);
+                }
+            }
+
+            if !parsed_any {
+                tracing::warn!("Malformed <tool_call> JSON: expected tool-call object in tag body");
+            }
+
+            remaining = strip_wrapping_fence_close(&after_open[close_idx + close_tag.len()..]);
+        } else {
+            if let Some(json_end) = find_json_end(after_open) {
+                if let Ok(value) =
+                    serde_json::from_str::<serde_json::Value>(&after_open[..json_end])
+                {
+                    let parsed_calls =
+                        parse_wrapped_tool_calls_from_json_value(&value, ParseSource::Xml);
+                    if !parsed_calls.is_empty() {
+                        calls.extend(parsed_calls);
+                        remaining = strip_leading_close_tags(&after_open[json_end..]);
+                        continue;
+                    }
+                }
+            }
+
+            if let Some((value, consumed_end)) = extract_first_json_value_with_end(after_open) {
+                let parsed_calls =
+                    parse_wrapped_tool_calls_from_json_value(&value, ParseSource::Xml);
+                if !parsed_calls.is_empty() {
+                    calls.extend(parsed_calls);
+                    remaining = strip_leading_close_tags(&after_open[consumed_end..]);
+                    continue;
+                }
+            }
+
+            remaining = &remaining[start..];
+            break;
+        }
+    }
+
+    // If XML tags found nothing, try markdown code blocks with tool_call language.
+    // Models behind OpenRouter sometimes output ```tool_call ... ``` or hybrid
+    // ```tool_call ... </tool_call> instead of structured API calls or XML tags.
+    if calls.is_empty() {
+        static MD_TOOL_CALL_RE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(
+                r"(?s)```(?:tool[_-]?call|invoke)\s*\n(.*?)(?:```|</tool[_-]?call>|</toolcall>|</invoke>)",
+            )
+            .unwrap()
+        });
+        let mut md_text_parts: Vec<String> = Vec::new();
+        let mut last_end = 0;
+
+        for cap in MD_TOOL_CALL_RE.captures_iter(response) {
+            let full_match = cap.get(0).unwrap();
+            let before = &response[last_end..full_match.start()];
+            if !before.trim().is_empty() {
+                md_text_parts.push(before.trim().to_string());
+            }
+            let inner = &cap[1];
+            let json_values = extract_json_values(inner);
+            for value in json_values {
+                let parsed_calls =
+                    parse_wrapped_tool_calls_from_json_value(&value, ParseSource::ToolCallsFence);
+                calls.extend(parsed_calls);
+            }
+            last_end = full_match.end();
+        }
+
+        if !calls.is_empty() {
+            let after = &response[last_end..];
+            if !after.trim().is_empty() {
+                md_text_parts.push(after.trim().to_string());
+            }
+            text_parts = md_text_parts;
+            remaining = "";
+        }
+    }
+
+    // GLM-style tool calls (browser_open/url>https://..., shell/command>ls, etc.)
+    if calls.is_empty() {
+        let glm_calls = parse_glm_style_tool_calls(remaining);
+        if !glm_calls.is_empty() {
+            let mut cleaned_text = remaining.to_string();
+            for (name, args, raw) in &glm_calls {
+                calls.push(ParsedToolCall {
+                    name: name.clone(),
+                    arguments: args.clone(),
+                    source: ParseSource::Bracketed,
+                });
+                if let Some(r) = raw {
+                    cleaned_text = cleaned_text.replace(r, "");
+                }
+            }
+            if !cleaned_text.trim().is_empty() {
+                text_parts.push(cleaned_text.trim().to_string());
+            }
+            remaining = "";
+        }
+    }
+
+    // Prose followed by a bare tool call ("Sure, here: {\"name\": ...}").
+    if calls.is_empty() {
+        if let Some((before, value)) = extract_trailing_tool_json(response) {
+            calls = parse_tool_calls_from_json_value(&value, ParseSource::JsonFence);
+            if !calls.is_empty() {
+                text_parts.clear();
+                if !before.trim().is_empty() {
+                    text_parts.push(before.trim().to_string());
+                }
+                remaining = "";
+            }
+        }
+    }
+
+    // SECURITY: We do NOT fall back to extracting arbitrary JSON from the response
+    // here. That would enable prompt injection attacks where malicious content
+    // (e.g., in emails, files, or web pages) could include JSON that mimics a
+    // tool call. Tool calls MUST be either:
+    // 1. OpenAI-style JSON with a "tool_calls" array
+    // 2. ZeroClaw tool-call tags (<tool_call>, <toolcall>, <tool-call>)
+    // 3. Markdown code blocks with tool_call/toolcall/tool-call language
+    // 4. Explicit GLM line-based call formats (e.g. `shell/command>...`)
+    // 5. A strictly tool-call-shaped JSON value that ends the response; JSON
+    //    quoted mid-answer, in code fences or blockquotes, or nested in other
+    //    JSON is never executed
+    // This ensures only the LLM's intentional tool calls are executed.
+
+    // Remaining text after last tool call
+    if !remaining.trim().is_empty() {
+        text_parts.push(remaining.trim().to_string());
+    }
+
+    (text_parts.join("\n"), calls)
+}
+
+/// When a response that yielded no tool calls still contains an explicit
+/// tool-call wrapper (`<tool_call>` tags, a ```` ```tool_call ```` fence, or a
+/// ```` ```json ```` fence with a `tool_calls` key) whose body is not valid
+/// JSON, describe the parse error so the model can correct itself.
+pub(crate) fn malformed_tool_call_error(response: &str) -> Option<String> {
+    static TOOL_CALL_FENCE_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?s)```(tool[_-]?call|invoke|json)[ \t]*\n(.*?)(?:```|</tool[_-]?call>|</toolcall>|</invoke>|\z)")
+            .unwrap()
+    });
+    static TOOL_CALL_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?s)<(tool_call|toolcall|tool-call|invoke)>(.*?)(?:</(?:tool_call|toolcall|tool-call|invoke)>|\z)")
+            .unwrap()
+    });
+
+    let quoted = quoted_code_ranges(response);
+    let bodies = TOOL_CALL_FENCE_RE
+        .captures_iter(response)
+        .filter(|cap| &cap[1] != "json" || cap[2].contains("\"tool_calls\""))
+        .chain(TOOL_CALL_TAG_RE.captures_iter(response).filter(|cap| {
+            let start = cap.get(0).map_or(0, |m| m.start());
+            !quoted.iter().any(|(s, e)| *s <= start && start < *e)
+        }))
+        .map(|cap| cap[2].trim().to_string());
+
+    for body in bodies {
+        if body.is_empty() {
+            continue;
+        }
+        if let Err(e) = serde_json::from_str::<serde_json::Value>(&body) {
+            return Some(e.to_string());
+        }
+    }
+    None
+}
+
+/// Feedback for one corrective retry after [`malformed_tool_call_error`].
+pub(crate) fn malformed_tool_call_feedback(detail: &str) -> String {
+    format!(
+        "Your tool_calls block was invalid JSON: {detail}. \
+         Resend the tool call as valid JSON, or answer without calling a tool."
+    )
+}
+
+pub(crate) fn parse_structured_tool_calls(tool_calls: &[ToolCall]) -> Vec<ParsedToolCall> {
+    tool_calls
+        .iter()
+        .map(|call| ParsedToolCall {
+            name: call.name.clone(),
+            arguments: decode_arguments_string(&call.arguments),
+            source: ParseSource::Native,
+        })
+        .collect()
+}
+
+/// Build assistant history entry in JSON format for native tool-call APIs.
+/// `convert_messages` in the OpenRouter provider parses this JSON to reconstruct
+/// the proper `NativeMessage` with structured `tool_calls`.
+pub(crate) fn build_native_assistant_history(text: &str, tool_calls: &[ToolCall]) -> String {
+    let calls_json: Vec<serde_json::Value> = tool_calls
+        .iter()
+        .map(|tc| {
+            serde_json::json!({
+                "id": tc.id,
+                "name": tc.name,
+                "arguments": tc.arguments,
+            })
+        })
+        .collect();
+
+    let content = if text.trim().is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::Value::String(text.trim().to_string())
+    };
+
+    serde_json::json!({
+        "content": content,
+        "tool_calls": calls_json,
+    })
+    .to_string()
+}
+
+pub(crate) fn build_assistant_history_with_tool_calls(
+    text: &str,
+    tool_calls: &[ToolCall],
+) -> String {
+    let mut parts = Vec::new();
+
+    if !text.trim().is_empty() {
+        parts.push(text.trim().to_string());
+    }
+
+    for call in tool_calls {
+        let arguments = serde_json::from_str::<serde_json::Value>(&call.arguments)
+            .unwrap_or_else(|_| serde_json::Value::String(call.arguments.clone()));
+        let payload = serde_json::json!({
+            "id": call.id,
+            "name": call.name,
+            "arguments": arguments,
+        });
+        parts.push(format!("<tool_call>\n{payload}\n</tool_call>"));
+    }
+
+    parts.join("\n")
+}
+
+/// Which path produced a [`ParsedToolCall`], logged with every call so a bad
+/// parse can be traced back to the heuristic that matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ParseSource {
+    /// Structured `tool_calls` returned by the provider API.
+    Native,
+    /// A ```` ```tool_call ```` or ```` ```invoke ```` code block.
+    ToolCallsFence,
+    /// Tool-call JSON in the prose, usually inside a ```` ```json ```` fence.
+    JsonFence,
+    /// The whole response is tool-call JSON.
+    DirectJson,
+    /// GLM line-based calls such as `shell/command>ls`.
+    Bracketed,
+    /// `<tool_call>` tags and their aliases.
+    Xml,
+}
+
+#[derive(Debug)]
+pub(crate) struct ParsedToolCall {
+    pub(crate) name: String,
+    pub(crate) arguments: serde_json::Value,
+    pub(crate) source: ParseSource,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tool_calls_extracts_single_call() {
+        let response = r#"Let me check that.
+<tool_call>
+{"name": "shell", "arguments": {"command": "ls -la"}}
+</tool_call>"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(text, "Let me check that.");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "ls -la"
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_extracts_multiple_calls() {
+        let response = r#"<tool_call>
+{"name": "file_read", "arguments": {"path": "a.txt"}}
+</tool_call>
+<tool_call>
+{"name": "file_read", "arguments": {"path": "b.txt"}}
+</tool_call>"#;
+
+        let (_, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "file_read");
+        assert_eq!(calls[1].name, "file_read");
+    }
+
+    #[test]
+    fn parse_tool_calls_returns_text_only_when_no_calls() {
+        let response = "Just a normal response with no tools.";
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(text, "Just a normal response with no tools.");
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_malformed_json() {
+        let response = r#"<tool_call>
+not valid json
+</tool_call>
+Some text after."#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(calls.is_empty());
+        assert!(text.contains("Some text after."));
+    }
+
+    #[test]
+    fn parse_tool_calls_text_before_and_after() {
+        let response = r#"Before text.
+<tool_call>
+{"name": "shell", "arguments": {"command": "echo hi"}}
+</tool_call>
+After text."#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.contains("Before text."));
+        assert!(text.contains("After text."));
+        assert_eq!(calls.len(), 1);
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_openai_format() {
+        // OpenAI-style response with tool_calls array
+        let response = r#"{"content": "Let me check that for you.", "tool_calls": [{"type": "function", "function": {"name": "shell", "arguments": "{\"command\": \"ls -la\"}"}}]}"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(text, "Let me check that for you.");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "ls -la"
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_double_encoded_arguments() {
+        // OpenAI format: arguments JSON-encoded twice
+        let openai = r#"{"tool_calls": [{"type": "function", "function": {"name": "shell", "arguments": "\"{\\\"command\\\": \\\"ls\\\"}\""}}]}"#;
+        let (_, calls) = parse_tool_calls(openai);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].arguments, serde_json::json!({"command": "ls"}));
+
+        // Simple format: arguments as a JSON string inside the tag body
+        let simple = r#"<tool_call>
+{"name": "shell", "arguments": "{\"command\": \"ls\"}"}
+</tool_call>"#;
+        let (_, calls) = parse_tool_calls(simple);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].arguments, serde_json::json!({"command": "ls"}));
+
+        // Simple format, double-encoded
+        let simple_double = r#"<tool_call>
+{"name": "file_read", "arguments": "\"{\\\"path\\\": \\\"a.txt\\\"}\""}
+</tool_call>"#;
+        let (_, calls) = parse_tool_calls(simple_double);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].arguments, serde_json::json!({"path": "a.txt"}));
+    }
+
+    #[test]
+    fn decode_arguments_string_falls_back_to_empty_object() {
+        assert_eq!(decode_arguments_string("not json"), serde_json::json!({}));
+        assert_eq!(
+            decode_arguments_string("\"just text\""),
+            serde_json::json!({})
+        );
+        assert_eq!(
+            decode_arguments_string(" {\"a\": 1} "),
+            serde_json::json!({"a": 1})
+        );
+    }
+
+    #[test]
+    fn parse_structured_tool_calls_decodes_double_encoded_arguments() {
+        let calls = parse_structured_tool_calls(&[ToolCall {
+            id: "call_1".into(),
+            name: "shell".into(),
+            arguments: r#""{\"command\": \"pwd\"}""#.into(),
+        }]);
+        assert_eq!(calls[0].arguments, serde_json::json!({"command": "pwd"}));
+    }
+
+    #[test]
+    fn parse_tool_calls_records_which_parser_matched() {
+        let call = r#"{"name": "shell", "arguments": {"command": "ls"}}"#;
+        let cases = [
+            (
+                format!("```tool_call\n{call}\n```"),
+                ParseSource::ToolCallsFence,
+            ),
+            (
+                format!("Listing.\n```json\n{call}\n```"),
+                ParseSource::JsonFence,
+            ),
+            (call.to_string(), ParseSource::DirectJson),
+            ("shell/command>ls".to_string(), ParseSource::Bracketed),
+            (format!("<tool_call>{call}</tool_call>"), ParseSource::Xml),
+        ];
+        for (response, source) in cases {
+            let (_, calls) = parse_tool_calls(&response);
+            assert_eq!(calls.len(), 1, "{response}");
+            assert_eq!(calls[0].name, "shell");
+            assert_eq!(calls[0].source, source, "{response}");
+        }
+
+        let native = parse_structured_tool_calls(&[ToolCall {
+            id: "call_1".into(),
+            name: "shell".into(),
+            arguments: r#"{"command": "ls"}"#.into(),
+        }]);
+        assert_eq!(native[0].source, ParseSource::Native);
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_openai_format_multiple_calls() {
+        let response = r#"{"tool_calls": [{"type": "function", "function": {"name": "file_read", "arguments": "{\"path\": \"a.txt\"}"}}, {"type": "function", "function": {"name": "file_read", "arguments": "{\"path\": \"b.txt\"}"}}]}"#;
+
+        let (_, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "file_read");
+        assert_eq!(calls[1].name, "file_read");
+    }
+
+    #[test]
+    fn parse_tool_calls_openai_format_without_content() {
+        // Some providers don't include content field with tool_calls
+        let response = r#"{"tool_calls": [{"type": "function", "function": {"name": "memory_recall", "arguments": "{}"}}]}"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.is_empty()); // No content field
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "memory_recall");
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_markdown_json_inside_tool_call_tag() {
+        let response = r#"<tool_call>
+```json
+{"name": "file_write", "arguments": {"path": "test.py", "content": "print('ok')"}}
+```
+</tool_call>"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.is_empty());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "file_write");
+        assert_eq!(
+            calls[0].arguments.get("path").unwrap().as_str().unwrap(),
+            "test.py"
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_noisy_tool_call_tag_body() {
+        let response = r#"<tool_call>
+I will now call the tool with this payload:
+{"name": "shell", "arguments": {"command": "pwd"}}
+</tool_call>"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.is_empty());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "pwd"
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_markdown_tool_call_fence() {
+        let response = r#"I'll check that.
+```tool_call
+{"name": "shell", "arguments": {"command": "pwd"}}
+```
+Done."#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "pwd"
+        );
+        assert!(text.contains("I'll check that."));
+        assert!(text.contains("Done."));
+        assert!(!text.contains("```tool_call"));
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_markdown_tool_call_hybrid_close_tag() {
+        let response = r#"Preface
+```tool-call
+{"name": "shell", "arguments": {"command": "date"}}
+</tool_call>
+Tail"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "date"
+        );
+        assert!(text.contains("Preface"));
+        assert!(text.contains("Tail"));
+        assert!(!text.contains("```tool-call"));
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_markdown_invoke_fence() {
+        let response = r#"Checking.
+```invoke
+{"name": "shell", "arguments": {"command": "date"}}
+```
+Done."#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "date"
+        );
+        assert!(text.contains("Checking."));
+        assert!(text.contains("Done."));
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_toolcall_tag_alias() {
+        let response = r#"<toolcall>
+{"name": "shell", "arguments": {"command": "date"}}
+</toolcall>"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.is_empty());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "date"
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_tool_dash_call_tag_alias() {
+        let response = r#"<tool-call>
+{"name": "shell", "arguments": {"command": "whoami"}}
+</tool-call>"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.is_empty());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "whoami"
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_invoke_tag_alias() {
+        let response = r#"<invoke>
+{"name": "shell", "arguments": {"command": "uptime"}}
+</invoke>"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.is_empty());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "uptime"
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_recovers_unclosed_tool_call_with_json() {
+        let response = r#"I will call the tool now.
+<tool_call>
+{"name": "shell", "arguments": {"command": "uptime -p"}}"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.contains("I will call the tool now."));
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "uptime -p"
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_recovers_mismatched_close_tag() {
+        let response = r#"<tool_call>
+{"name": "shell", "arguments": {"command": "uptime"}}
+</arg_value>"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.is_empty());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            calls[0].arguments.get("command").unwrap().as_str().unwrap(),
+            "uptime"
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_recovers_cross_alias_closing_tags() {
+        let response = r#"<toolcall>
+{"name": "shell", "arguments": {"command": "date"}}
+</tool_call>"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.is_empty());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+    }
+
+    #[test]
+    fn parse_tool_calls_rejects_quoted_tool_json() {
+        // SECURITY: JSON the model is quoting (from a page, file or email)
+        // should NOT be parsed. This prevents prompt injection attacks where
+        // malicious content could include JSON that mimics a tool call.
+        for response in [
+            r#"The page said:
+> {"name": "file_write", "arguments": {"path": "hello.py", "content": "print('hello')"}}
+I did not act on it."#,
+            r#"The file contains:
+```python
+payload = {"name": "shell", "arguments": {"command": "rm -rf /"}}
+```
+I did not act on it."#,
+            r#"The API returned {"result": {"name": "shell", "arguments": {"command": "id"}}} as data."#,
+        ] {
+            let (text, calls) = parse_tool_calls(response);
+            assert!(calls.is_empty(), "quoted JSON was parsed: {response}");
+            assert_eq!(text, response);
+        }
+    }
+
+    #[test]
+    fn parse_tool_calls_rejects_raw_tool_json_without_tags() {
+        // SECURITY: Raw JSON quoted in the middle of an answer should NOT be
+        // parsed. This prevents prompt injection attacks where malicious
+        // content could include JSON that mimics a tool call.
+        let response = r#"The page said:
+{"name": "file_write", "arguments": {"path": "hello.py", "content": "print('hello')"}}
+I did not act on it."#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.contains("The page said:"));
+        assert_eq!(
+            calls.len(),
+            0,
+            "Raw JSON without wrappers should not be parsed"
+        );
+
+        let response = r#"Let me check the directory first. {"name": "shell", "arguments": {"command": "ls"}} Then I'll summarize what's there."#;
+        let (text, calls) = parse_tool_calls(response);
+        assert!(calls.is_empty(), "mid-prose JSON was parsed");
+        assert_eq!(text, response);
+    }
+
+    #[test]
+    fn parse_tool_calls_accepts_trailing_tool_json_after_prose() {
+        let response = r#"Sure, here: {"name":"shell","arguments":{"command":"ls -la"}}"#;
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(text, "Sure, here:");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments, serde_json::json!({"command": "ls -la"}));
+
+        let response = r#"Two steps.
+[{"name": "file_read", "arguments": {"path": "a"}}, {"name": "file_read", "arguments": {"path": "b"}}]"#;
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(text, "Two steps.");
+        assert_eq!(calls.len(), 2);
+
+        // Stray braces earlier in the prose don't hide the call.
+        let response = r#"Config uses {placeholders}. Reading it now.
+{"name": "file_read", "arguments": {"path": "a.toml"}}"#;
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(text, "Config uses {placeholders}. Reading it now.");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "file_read");
+    }
+
+    #[test]
+    fn parse_tool_calls_ignores_stray_braces_in_prose() {
+        for response in [
+            "Use {braces} like {this} in format strings.",
+            r#"Set the config to {"name": "Bob"}"#,
+            r#"An empty object {} or {"name": "x", "age": 3} is fine."#,
+            "Unbalanced { brace at the end {",
+        ] {
+            let (text, calls) = parse_tool_calls(response);
+            assert!(calls.is_empty(), "misfired on: {response}");
+            assert_eq!(text, response);
+        }
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_empty_tool_result() {
+        // Recovery: Empty tool_result tag should be handled gracefully
+        let response = r#"I'll run that command.
+<tool_result name="shell">
+
+</tool_result>
+Done."#;
+        let (text, calls) = parse_tool_calls(response);
+        assert!(text.contains("Done."));
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn parse_arguments_value_handles_null() {
+        // Recovery: null arguments are returned as-is (Value::Null)
+        let value = serde_json::json!(null);
+        let result = parse_arguments_value(Some(&value));
+        assert!(result.is_null());
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_empty_tool_calls_array() {
+        // Recovery: Empty tool_calls array returns original response (no tool parsing)
+        let response = r#"{"content": "Hello", "tool_calls": []}"#;
+        let (text, calls) = parse_tool_calls(response);
+        // When tool_calls is empty, the entire JSON is returned as text
+        assert!(text.contains("Hello"));
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_whitespace_only_name() {
+        // Recovery: Whitespace-only tool name should return None
+        let value = serde_json::json!({"function": {"name": "   ", "arguments": {}}});
+        let result = parse_tool_call_value(&value, ParseSource::DirectJson);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_empty_string_arguments() {
+        // Recovery: Empty string arguments should be handled
+        let value = serde_json::json!({"name": "test", "arguments": ""});
+        let result = parse_tool_call_value(&value, ParseSource::DirectJson);
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().name, "test");
+    }
+
+    #[test]
+    fn parse_arguments_value_handles_invalid_json_string() {
+        // Recovery: Invalid JSON string should return empty object
+        let value = serde_json::Value::String("not valid json".to_string());
+        let result = parse_arguments_value(Some(&value));
+        assert!(result.is_object());
+        assert!(result.as_object().unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_arguments_value_handles_none() {
+        // Recovery: None arguments should return empty object
+        let result = parse_arguments_value(None);
+        assert!(result.is_object());
+        assert!(result.as_object().unwrap().is_empty());
+    }
+
+    #[test]
+    fn extract_json_values_handles_empty_string() {
+        // Recovery: Empty input should return empty vec
+        let result = extract_json_values("");
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn extract_json_values_handles_whitespace_only() {
+        // Recovery: Whitespace only should return empty vec
+        let result = extract_json_values("   \n\t  ");
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn extract_json_values_handles_multiple_objects() {
+        // Recovery: Multiple JSON objects should all be extracted
+        let input = r#"{"a": 1}{"b": 2}{"c": 3}"#;
+        let result = extract_json_values(input);
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn extract_json_values_handles_arrays() {
+        // Recovery: JSON arrays should be extracted
+        let input = r#"[1, 2, 3]{"key": "value"}"#;
+        let result = extract_json_values(input);
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn parse_tool_call_value_handles_missing_name_field() {
+        // Recovery: Missing name field should return None
+        let value = serde_json::json!({"function": {"arguments": {}}});
+        let result = parse_tool_call_value(&value, ParseSource::DirectJson);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn parse_tool_call_value_handles_top_level_name() {
+        // Recovery: Tool call with name at top level (non-OpenAI format)
+        let value = serde_json::json!({"name": "test_tool", "arguments": {}});
+        let result = parse_tool_call_value(&value, ParseSource::DirectJson);
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().name, "test_tool");
+    }
+
+    #[test]
+    fn parse_tool_calls_from_json_value_handles_empty_array() {
+        // Recovery: Empty tool_calls array should return empty vec
+        let value = serde_json::json!({"tool_calls": []});
+        let result = parse_tool_calls_from_json_value(&value, ParseSource::DirectJson);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn parse_tool_calls_from_json_value_handles_missing_tool_calls() {
+        // Recovery: Missing tool_calls field should fall through
+        let value = serde_json::json!({"name": "test", "arguments": {}});
+        let result = parse_tool_calls_from_json_value(&value, ParseSource::DirectJson);
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn parse_tool_calls_accepts_tool_name_key_with_inline_arguments() {
+        let response = r#"<tool_call>{"tool_name": "shell", "command": "ls"}</tool_call>"#;
+        let (_, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments, serde_json::json!({"command": "ls"}));
+    }
+
+    #[test]
+    fn parse_tool_calls_accepts_action_key_with_action_input() {
+        let response = r#"<tool_call>{"action": "file_read", "action_input": "{\"path\": \"a.txt\"}"}</tool_call>"#;
+        let (_, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "file_read");
+        assert_eq!(calls[0].arguments, serde_json::json!({"path": "a.txt"}));
+
+        let response =
+            "```tool_call\n{\"tool\": \"memory_recall\", \"parameters\": {\"query\": \"x\"}}\n```";
+        let (_, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "memory_recall");
+        assert_eq!(calls[0].arguments, serde_json::json!({"query": "x"}));
+    }
+
+    #[test]
+    fn parse_tool_calls_accepts_object_keyed_by_tool_name() {
+        let response = r#"Listing. <tool_call>[{"shell": {"command": "ls"}}, {"file_read": {"path": "b"}}]</tool_call>"#;
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(text, "Listing.");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments, serde_json::json!({"command": "ls"}));
+        assert_eq!(calls[1].name, "file_read");
+    }
+
+    #[test]
+    fn loose_tool_call_shapes_require_a_wrapper() {
+        // A plain JSON answer is not mistaken for a tool call.
+        let (_, calls) = parse_tool_calls(r#"{"shell": {"command": "ls"}}"#);
+        assert!(calls.is_empty());
+        let (_, calls) = parse_tool_calls(r#"{"action": "deploy", "target": "prod"}"#);
+        assert!(calls.is_empty());
+
+        // Standard keys are never read as a tool name.
+        assert!(parse_loose_tool_call_value(
+            &serde_json::json!({"function": {}}),
+            ParseSource::Xml
+        )
+        .is_none());
+        assert!(parse_loose_tool_call_value(
+            &serde_json::json!({"tool_name": "rm -rf /"}),
+            ParseSource::Xml
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn parse_tool_calls_from_json_value_handles_top_level_array() {
+        // Recovery: Top-level array of tool calls
+        let value = serde_json::json!([
+            {"name": "tool_a", "arguments": {}},
+            {"name": "tool_b", "arguments": {}}
+        ]);
+        let result = parse_tool_calls_from_json_value(&value, ParseSource::DirectJson);
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn parse_glm_style_browser_open_url() {
+        let response = "browser_open/url>https://example.com";
+        let calls = parse_glm_style_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "shell");
+        assert!(calls[0].1["command"].as_str().unwrap().contains("curl"));
+        assert!(calls[0].1["command"]
+            .as_str()
+            .unwrap()
+            .contains("example.com"));
+    }
+
+    #[test]
+    fn parse_glm_style_shell_command() {
+        let response = "shell/command>ls -la";
+        let calls = parse_glm_style_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "shell");
+        assert_eq!(calls[0].1["command"], "ls -la");
+    }
+
+    #[test]
+    fn parse_glm_style_http_request() {
+        let response = "http_request/url>https://api.example.com/data";
+        let calls = parse_glm_style_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "http_request");
+        assert_eq!(calls[0].1["url"], "https://api.example.com/data");
+        assert_eq!(calls[0].1["method"], "GET");
+    }
+
+    #[test]
+    fn parse_glm_style_plain_url() {
+        let response = "https://example.com/api";
+        let calls = parse_glm_style_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "shell");
+        assert!(calls[0].1["command"].as_str().unwrap().contains("curl"));
+    }
+
+    #[test]
+    fn parse_glm_style_json_args() {
+        let response = r#"shell/{"command": "echo hello"}"#;
+        let calls = parse_glm_style_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "shell");
+        assert_eq!(calls[0].1["command"], "echo hello");
+    }
+
+    #[test]
+    fn parse_glm_style_multiple_calls() {
+        let response = r#"shell/command>ls
+browser_open/url>https://example.com"#;
+        let calls = parse_glm_style_tool_calls(response);
+        assert_eq!(calls.len(), 2);
+    }
+
+    #[test]
+    fn parse_glm_style_tool_call_integration() {
+        // Integration test: GLM format should be parsed in parse_tool_calls
+        let response = "Checking...\nbrowser_open/url>https://example.com\nDone";
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert!(text.contains("Checking"));
+        assert!(text.contains("Done"));
+    }
+
+    #[test]
+    fn parse_glm_style_rejects_non_http_url_param() {
+        let response = "browser_open/url>javascript:alert(1)";
+        let calls = parse_glm_style_tool_calls(response);
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn parse_tool_calls_ignores_tags_in_quoted_code_blocks() {
+        let response = "The format looks like this:\n```python\nprint('<tool_call>{\"name\":\"shell\",\"arguments\":{}}</tool_call>')\n```\nThat's all.";
+        let (text, calls) = parse_tool_calls(response);
+        assert!(calls.is_empty());
+        assert_eq!(text, response);
+    }
+
+    #[test]
+    fn parse_tool_calls_keeps_quoted_code_beside_a_real_call() {
+        let response = "Current code:\n```rust\nfn main() {}\n```\nI'll run the tests.\n<tool_call>{\"name\":\"shell\",\"arguments\":{\"command\":\"cargo test\"}}</tool_call>";
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].arguments["command"], "cargo test");
+        assert_eq!(
+            text,
+            "Current code:\n```rust\nfn main() {}\n```\nI'll run the tests."
+        );
+    }
+
+    #[test]
+    fn parse_tool_calls_strips_fence_that_wraps_a_call() {
+        let response = "I'll check the file first.\n```\n<tool_call>\n{\"name\":\"file_read\",\"arguments\":{\"path\":\"a.txt\"}}\n</tool_call>\n```";
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "file_read");
+        assert_eq!(text, "I'll check the file first.");
+    }
+
+    #[test]
+    fn parse_tool_calls_handles_unclosed_tool_call_tag() {
+        let response = "<tool_call>{\"name\":\"shell\",\"arguments\":{\"command\":\"pwd\"}}\nDone";
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments["command"], "pwd");
+        assert_eq!(text, "Done");
+    }
+
+    #[test]
+    fn malformed_tool_call_error_flags_broken_wrappers_only() {
+        let detail = malformed_tool_call_error(
+            "```tool_call\n{\"name\": \"shell\", \"arguments\": {\"command\": \"ls\"}\n```",
+        )
+        .expect("broken fenced tool call");
+        assert!(detail.contains("EOF"), "{detail}");
+        assert!(malformed_tool_call_error(
+            "```json\n{\"tool_calls\": [{\"name\": \"shell\", arguments: {}}]}\n```"
+        )
+        .is_some());
+        assert!(malformed_tool_call_error("<tool_call>{name: shell}</tool_call>").is_some());
+
+        for prose in [
+            "Everything looks fine.",
+            "Use `{braces}` like {this}.",
+            "```json\n{\"port\": 8080,}\n```",
+            "```rust\nlet x = \"<tool_call>{oops</tool_call>\";\n```",
+        ] {
+            assert_eq!(malformed_tool_call_error(prose), None, "{prose}");
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Plan-then-execute.
+//!
+//! With `[agent].plan_first` (or `zeroclaw agent --plan`) the model is first
+//! asked for a numbered step list without tools. The parsed [`Plan`] is shown
+//! (and on the CLI optionally approved), then handed to the model as the
+//! execution brief. While executing, the model opens each step with a
+//! `Step N:` marker, which [`Plan::observe`] uses to track progress.
+
+use crate::providers::{ChatMessage, Provider};
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Appended to the conversation to request a plan instead of an answer.
+const PLANNING_INSTRUCTION: &str = "Before doing anything, write a short plan for the request above as a numbered list (`1. ...`), one concrete action per line, at most 10 steps. Do not call any tools and do not carry out the steps yet. If the request needs no more than one action, reply with a single step.";
+
+/// Plans are capped at this many steps; anything beyond is dropped.
+const MAX_PLAN_STEPS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    InProgress,
+    Done,
+}
+
+/// One step of a plan, numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlanStep {
+    pub number: usize,
+    pub description: String,
+    pub status: StepStatus,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Plan {
+    pub steps: Vec<PlanStep>,
+}
+
+/// Strip a `1.`, `1)`, `Step 1:` or `- ` prefix, returning the step text.
+fn strip_step_prefix(line: &str) -> Option<&str> {
+    let line = line.trim().trim_start_matches("**");
+    let rest = line
+        .strip_prefix("Step ")
+        .or_else(|| line.strip_prefix("step "))
+        .unwrap_or(line);
+
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        let after = &rest[digits..];
+        let after = after
+            .strip_prefix('.')
+            .or_else(|| after.strip_prefix(')'))
+            .or_else(|| after.strip_prefix(':'))?;
+        return Some(after.trim().trim_start_matches("**").trim());
+    }
+
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .map(str::trim)
+}
+
+/// Step number of a `Step N` marker at the start of `line`.
+fn step_marker(line: &str) -> Option<usize> {
+    let line = line.trim().trim_start_matches(['*', '#', '[', ' ']);
+    let rest = line
+        .strip_prefix("Step ")
+        .or_else(|| line.strip_prefix("step "))
+        .or_else(|| line.strip_prefix("STEP "))?;
+    let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+impl Plan {
+    /// Parse a model-written step list. Numbered lines win over bullets;
+    /// surrounding prose is ignored.
+    pub fn parse(text: &str) -> Self {
+        let numbered: Vec<&str> = text
+            .lines()
+            .filter(|line| {
+                line.trim_start()
+                    .trim_start_matches("**")
+                    .trim_start_matches("Step ")
+                    .starts_with(|c: char| c.is_ascii_digit())
+            })
+            .filter_map(strip_step_prefix)
+            .collect();
+        let descriptions = if numbered.is_empty() {
+            text.lines().filter_map(strip_step_prefix).collect()
+        } else {
+            numbered
+        };
+
+        let steps = descriptions
+            .into_iter()
+            .filter(|d| !d.is_empty())
+            .take(MAX_PLAN_STEPS)
+            .enumerate()
+            .map(|(i, description)| PlanStep {
+                number: i + 1,
+                description: description.to_string(),
+                status: StepStatus::Pending,
+            })
+            .collect();
+        Self { steps }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn completed(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|s| s.status == StepStatus::Done)
+            .count()
+    }
+
+    /// Update step status from `Step N` markers in model output. Earlier steps
+    /// are marked done and step N in progress. Returns the newly started step.
+    pub fn observe(&mut self, text: &str) -> Option<usize> {
+        let latest = text
+            .lines()
+            .filter_map(step_marker)
+            .filter(|n| (1..=self.steps.len()).contains(n))
+            .max()?;
+
+        let previous = self
+            .steps
+            .iter()
+            .find(|s| s.status == StepStatus::InProgress)
+            .map(|s| s.number);
+        for step in &mut self.steps {
+            if step.number < latest {
+                step.status = StepStatus::Done;
+            } else if step.number == latest && step.status == StepStatus::Pending {
+                step.status = StepStatus::InProgress;
+            }
+        }
+        (previous != Some(latest) && self.steps[latest - 1].status == StepStatus::InProgress)
+            .then_some(latest)
+    }
+
+    /// The model gave its final answer: the step in progress is done.
+    pub fn finish(&mut self) {
+        for step in &mut self.steps {
+            if step.status == StepStatus::InProgress {
+                step.status = StepStatus::Done;
+            }
+        }
+    }
+
+    /// Progress line for step `number`.
+    pub fn step_line(&self, number: usize) -> String {
+        let step = &self.steps[number - 1];
+        format!(
+            "▶ Step {}/{}: {}",
+            step.number,
+            self.steps.len(),
+            step.description
+        )
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("📋 Plan:\n");
+        for step in &self.steps {
+            let marker = match step.status {
+                StepStatus::Pending => ' ',
+                StepStatus::InProgress => '→',
+                StepStatus::Done => '✓',
+            };
+            let _ = writeln!(out, "  [{marker}] {}. {}", step.number, step.description);
+        }
+        out
+    }
+
+    /// Message that hands the approved plan to the model for execution.
+    pub fn execution_brief(&self) -> String {
+        let mut brief = String::from("Carry out this plan:\n");
+        for step in &self.steps {
+            let _ = writeln!(brief, "{}. {}", step.number, step.description);
+        }
+        brief.push_str(
+            "\nBegin the message in which you start each step with `Step N:` (e.g. `Step 1:`), then continue with the tool calls for that step.",
+        );
+        brief
+    }
+}
+
+/// Ask the model for a plan for the conversation so far, without tools.
+pub async fn generate_plan(
+    provider: &dyn Provider,
+    history: &[ChatMessage],
+    model: &str,
+    temperature: f64,
+) -> Result<Plan> {
+    let mut messages = history.to_vec();
+    messages.push(ChatMessage::user(PLANNING_INSTRUCTION));
+    let reply = provider
+        .chat_with_history(&messages, model, temperature)
+        .await?;
+    Ok(Plan::parse(&reply))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbered_steps_and_ignores_prose() {
+        let plan = Plan::parse(
+            "Here's my plan:\n1. Read Cargo.toml\n2) Run `cargo test`\n**3.** Summarize failures\nLet me know!",
+        );
+        let descriptions: Vec<&str> = plan.steps.iter().map(|s| s.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            vec!["Read Cargo.toml", "Run `cargo test`", "Summarize failures"]
+        );
+        assert!(plan.steps.iter().all(|s| s.status == StepStatus::Pending));
+        assert_eq!(plan.steps[2].number, 3);
+    }
+
+    #[test]
+    fn falls_back_to_bullets_and_step_prefixes() {
+        assert_eq!(Plan::parse("- fetch\n- parse").steps.len(), 2);
+        let plan = Plan::parse("Step 1: fetch\nStep 2: parse");
+        assert_eq!(plan.steps[1].description, "parse");
+        assert!(Plan::parse("Sure, done.").is_empty());
+    }
+
+    #[test]
+    fn observe_tracks_step_markers() {
+        let mut plan = Plan::parse("1. a\n2. b\n3. c");
+        assert_eq!(plan.observe("Step 1: starting"), Some(1));
+        assert_eq!(plan.observe("still on it"), None);
+        assert_eq!(plan.observe("Step 1: again"), None);
+        assert_eq!(plan.observe("**Step 3:** skipping ahead"), Some(3));
+        assert_eq!(plan.steps[1].status, StepStatus::Done);
+        assert_eq!(plan.observe("Step 9: out of range"), None);
+        plan.finish();
+        assert_eq!(plan.completed(), 3);
+        assert!(plan.render().contains("[✓] 3. c"));
+    }
+
+    #[test]
+    fn execution_brief_lists_steps_and_marker_convention() {
+        let plan = Plan::parse("1. a\n2. b");
+        let brief = plan.execution_brief();
+        assert!(brief.contains("1. a\n2. b"));
+        assert!(brief.contains("`Step N:`"));
+        assert_eq!(plan.step_line(2), "▶ Step 2/2: b");
+    }
+}
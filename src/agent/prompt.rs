@@ -1,9 +1,10 @@
-use crate::config::IdentityConfig;
+use crate::config::{AgentConfig, IdentityConfig};
 use crate::identity;
 use crate::skills::Skill;
 use crate::tools::Tool;
+use crate::util::{datetime_section, LocalTimezone};
 use anyhow::Result;
-use chrono::Local;
+use chrono::Utc;
 use std::fmt::Write;
 use std::path::Path;
 
@@ -15,7 +16,9 @@ pub struct PromptContext<'a> {
     pub tools: &'a [Box<dyn Tool>],
     pub skills: &'a [Skill],
     pub identity_config: Option<&'a IdentityConfig>,
+    pub agent_config: Option<&'a AgentConfig>,
     pub dispatcher_instructions: &'a str,
+    pub timezone: LocalTimezone,
 }
 
 pub trait PromptSection: Send + Sync {
@@ -32,6 +35,7 @@ impl SystemPromptBuilder {
     pub fn with_defaults() -> Self {
         Self {
             sections: vec![
+                Box::new(PersonaSection),
                 Box::new(IdentitySection),
                 Box::new(ToolsSection),
                 Box::new(SafetySection),
@@ -39,6 +43,7 @@ impl SystemPromptBuilder {
                 Box::new(WorkspaceSection),
                 Box::new(DateTimeSection),
                 Box::new(RuntimeSection),
+                Box::new(LanguageSection),
             ],
         }
     }
@@ -62,6 +67,7 @@ impl SystemPromptBuilder {
     }
 }
 
+pub struct PersonaSection;
 pub struct IdentitySection;
 pub struct ToolsSection;
 pub struct SafetySection;
@@ -69,6 +75,20 @@ pub struct SkillsSection;
 pub struct WorkspaceSection;
 pub struct RuntimeSection;
 pub struct DateTimeSection;
+pub struct LanguageSection;
+
+impl PromptSection for PersonaSection {
+    fn name(&self) -> &str {
+        "persona"
+    }
+
+    fn build(&self, ctx: &PromptContext<'_>) -> Result<String> {
+        Ok(ctx
+            .agent_config
+            .map(identity::persona_section)
+            .unwrap_or_default())
+    }
+}
 
 impl PromptSection for IdentitySection {
     fn name(&self) -> &str {
@@ -149,12 +169,13 @@ impl PromptSection for SkillsSection {
     }
 
     fn build(&self, ctx: &PromptContext<'_>) -> Result<String> {
-        if ctx.skills.is_empty() {
+        let skills = crate::skills::skills_for_model(ctx.skills, ctx.model_name);
+        if skills.is_empty() {
             return Ok(String::new());
         }
 
         let mut prompt = String::from("## Available Skills\n\n<available_skills>\n");
-        for skill in ctx.skills {
+        for skill in skills {
             let location = skill.location.clone().unwrap_or_else(|| {
                 ctx.workspace_dir
                     .join("skills")
@@ -208,12 +229,18 @@ impl PromptSection for DateTimeSection {
         "datetime"
     }
 
+    fn build(&self, ctx: &PromptContext<'_>) -> Result<String> {
+        Ok(datetime_section(&ctx.timezone, Utc::now()))
+    }
+}
+
+impl PromptSection for LanguageSection {
+    fn name(&self) -> &str {
+        "language"
+    }
+
     fn build(&self, _ctx: &PromptContext<'_>) -> Result<String> {
-        let now = Local::now();
-        Ok(format!(
-            "## Current Date & Time\n\nTimezone: {}",
-            now.format("%Z")
-        ))
+        Ok(crate::i18n::language_section())
     }
 }
 
@@ -281,6 +308,7 @@ mod tests {
                 success: true,
                 output: "ok".into(),
                 error: None,
+                artifacts: Vec::new(),
             })
         }
     }
@@ -294,9 +322,12 @@ mod tests {
             tools: &tools,
             skills: &[],
             identity_config: None,
+            agent_config: None,
             dispatcher_instructions: "instr",
+            timezone: LocalTimezone::System,
         };
         let prompt = SystemPromptBuilder::with_defaults().build(&ctx).unwrap();
+        assert!(!prompt.contains("## Persona"));
         assert!(prompt.contains("## Tools"));
         assert!(prompt.contains("test_tool"));
         assert!(prompt.contains("instr"));
@@ -0,0 +1,861 @@
+//! Interactive `zeroclaw agent` sessions.
+//!
+//! [`run`] reads lines from a [`ReplReader`], handles slash commands in
+//! place and turns everything else into a turn of the [`CliSession`]. Each
+//! command has its own handler on [`Repl`].
+
+use crate::agent::cli::{session_id_of, CliSession};
+use crate::agent::context_budget::{BudgetFormat, ContextBudget};
+use crate::agent::history::{trim_history, ConversationHistory};
+use crate::agent::output::{self, Output};
+use crate::agent::parser::parse_tool_calls;
+use crate::agent::session::{self, SessionMode};
+use crate::agent::shutdown;
+use crate::config::Config;
+use crate::i18n;
+use crate::memory::MemoryCategory;
+use crate::providers::ChatMessage;
+use crate::security::ConfirmationMode;
+use crate::term;
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Printed by the interactive `/help` command.
+const INTERACTIVE_HELP: &str = "\
+Available commands:
+  /help        Show this help message
+  /clear /new  Clear conversation history
+  /memory      List recent memory entries
+  /model <name>  Switch model (bare /model shows the current one)
+  /temperature <0.0-2.0>  Change sampling temperature
+  /retry [model] [temperature]  Regenerate the last response
+  /prompt <name> [name=value ...]  Send a prompt template (bare /prompt lists them)
+  /approval <tool> <always|when_destructive|never>  Change when a tool asks for confirmation
+  /fork <name>  Continue in a copy of this session, keeping the original
+  /skills      List loaded skills
+  /context [json]  Estimated token breakdown of the next request
+  /workspace <name>  Switch workspace (reloads memory and tools)
+  /quit /exit  Exit interactive mode
+Tab completes commands and tool names; Up/Down browse input history.
+";
+
+/// Entries shown by the interactive `/memory` command.
+const MEMORY_LIMIT: usize = 10;
+
+/// Result of waiting for the next line of interactive input.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum ReplInput {
+    Line(String),
+    /// stdin closed or failed.
+    Eof,
+    /// No input arrived within the configured idle timeout.
+    IdleTimeout,
+}
+
+/// Interactive input read on a dedicated thread so the REPL can wait on it
+/// with a timeout. The thread reads one line per prompt it is sent, exits
+/// once either side is dropped, and never keeps the process alive on exit.
+pub(super) struct ReplReader {
+    prompts: std::sync::mpsc::Sender<String>,
+    lines: tokio::sync::mpsc::Receiver<String>,
+    /// The line editor draws the prompt itself.
+    draws_prompt: bool,
+}
+
+impl ReplReader {
+    pub(super) fn spawn(tool_names: Vec<String>, history_path: Option<PathBuf>) -> Self {
+        let (prompt_tx, prompt_rx) = std::sync::mpsc::channel::<String>();
+        let (line_tx, line_rx) = tokio::sync::mpsc::channel(1);
+        let mut editor = crate::channels::cli::LineEditor::new(tool_names, history_path);
+        let draws_prompt = editor.is_interactive();
+        std::thread::spawn(move || {
+            for prompt in prompt_rx {
+                match editor.read_line(&prompt) {
+                    Ok(Some(line)) => {
+                        if line_tx.blocking_send(line).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        term::eprintln(format_args!("\nError reading input: {e}\n"));
+                        break;
+                    }
+                }
+            }
+        });
+        Self {
+            prompts: prompt_tx,
+            lines: line_rx,
+            draws_prompt,
+        }
+    }
+
+    /// Show `prompt` and wait for the answer, giving up after `idle_timeout`.
+    pub(super) async fn read(
+        &mut self,
+        out: &Output,
+        prompt: &str,
+        idle_timeout: Option<Duration>,
+    ) -> ReplInput {
+        if !self.draws_prompt {
+            out.prompt(prompt);
+        }
+        if self.prompts.send(prompt.to_string()).is_err() {
+            return ReplInput::Eof;
+        }
+        next_input(&mut self.lines, idle_timeout).await
+    }
+}
+
+/// Input history for the interactive agent, kept next to the config file.
+pub(super) fn history_path(config: &Config) -> Option<PathBuf> {
+    config
+        .config_path
+        .parent()
+        .map(|dir| dir.join("history.txt"))
+}
+
+/// Wait for the next input line, giving up after `idle_timeout` if set.
+async fn next_input(
+    rx: &mut tokio::sync::mpsc::Receiver<String>,
+    idle_timeout: Option<Duration>,
+) -> ReplInput {
+    let line = match idle_timeout {
+        Some(limit) => match tokio::time::timeout(limit, rx.recv()).await {
+            Ok(line) => line,
+            Err(_) => return ReplInput::IdleTimeout,
+        },
+        None => rx.recv().await,
+    };
+    line.map_or(ReplInput::Eof, ReplInput::Line)
+}
+
+/// Model and temperature for one turn only: the regeneration of
+/// `/retry [model] [temperature]`, or a `/prompt` template's overrides.
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct TurnOverrides {
+    pub(super) model: Option<String>,
+    pub(super) temperature: Option<f64>,
+}
+
+impl TurnOverrides {
+    fn parse(args: &str) -> Result<Self> {
+        let mut overrides = Self::default();
+        for arg in args.split_whitespace() {
+            match arg.parse::<f64>() {
+                Ok(t) if (0.0..=2.0).contains(&t) => overrides.temperature = Some(t),
+                Ok(_) => anyhow::bail!("temperature must be a number between 0.0 and 2.0"),
+                Err(_) if overrides.model.is_none() => overrides.model = Some(arg.to_string()),
+                Err(_) => anyhow::bail!("usage: /retry [model] [temperature]"),
+            }
+        }
+        Ok(overrides)
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.model.is_none() && self.temperature.is_none()
+    }
+}
+
+/// Names of the tools called in `discarded` history, in call order.
+fn discarded_tool_calls(discarded: &[ChatMessage]) -> Vec<String> {
+    discarded
+        .iter()
+        .filter(|m| m.role == "assistant")
+        .flat_map(|m| parse_tool_calls(&m.content).1)
+        .map(|call| call.name)
+        .collect()
+}
+
+/// The last interactive turn, for `/retry`.
+struct LastTurn {
+    /// What the user typed; routing and auto-temperature look at this.
+    user_input: String,
+    /// The message as sent, memory context included.
+    enriched: String,
+    /// Pre-mutation checkpoint of the turn's first run that changed files;
+    /// restoring it undoes every attempt.
+    undo_checkpoint: Option<String>,
+}
+
+/// What to do after a line of input.
+enum Step {
+    /// Wait for the next line.
+    Read,
+    /// Run a turn.
+    Turn(TurnRequest),
+    /// End the session.
+    Quit,
+}
+
+/// A turn asked for by a line of input.
+enum TurnRequest {
+    /// A message typed by the user or rendered from a `/prompt` template.
+    Message(String, TurnOverrides),
+    /// `/retry`: run the last turn again on the user message already in the
+    /// history. `original` is the history from before the last response was
+    /// dropped, restored if the regeneration fails or is cancelled.
+    Retry {
+        user_input: String,
+        overrides: TurnOverrides,
+        original: ConversationHistory,
+    },
+}
+
+/// An interactive session in one workspace.
+struct Repl<'a> {
+    session: &'a mut CliSession,
+    input: &'a mut ReplReader,
+    idle_timeout: Option<Duration>,
+    /// Memory block sent with the latest user message, for `/context`.
+    last_context: String,
+    last_turn: Option<LastTurn>,
+    /// Set by `/workspace <name>`.
+    next_workspace: Option<String>,
+}
+
+/// Read and answer lines until the user quits, stdin closes or the session
+/// idles out. Returns the workspace `/workspace` switched to, if any.
+pub(super) async fn run(
+    session: &mut CliSession,
+    input: &mut Option<ReplReader>,
+) -> Result<Option<String>> {
+    let out = session.out;
+    out.progress(i18n::tf(
+        "repl.banner",
+        &[("name", &session.config.agent.assistant_name())],
+    ));
+    out.progress(format!("{}\n", i18n::t("repl.help_hint")));
+    if session.session_mode == SessionMode::Continue {
+        match session.resumed_messages {
+            Some(count) => out.progress(format!("Resuming previous session ({count} messages).\n")),
+            None => out.progress("No previous session found; starting fresh.\n"),
+        }
+    }
+
+    let idle_timeout = match session.config.agent.idle_timeout_mins {
+        0 => None,
+        mins => Some(Duration::from_secs(mins.saturating_mul(60))),
+    };
+    let input = input.get_or_insert_with(|| {
+        let tool_names = session
+            .agent
+            .tools()
+            .iter()
+            .map(|t| t.name().to_string())
+            .collect();
+        ReplReader::spawn(tool_names, history_path(&session.config))
+    });
+    let mut repl = Repl {
+        session,
+        input,
+        idle_timeout,
+        last_context: String::new(),
+        last_turn: None,
+        next_workspace: None,
+    };
+
+    loop {
+        let read = tokio::select! {
+            read = repl.input.read(&out, "> ", idle_timeout) => read,
+            () = shutdown::shutdown_requested() => return Err(repl.session.interrupted()),
+        };
+        let line = match read {
+            ReplInput::Line(line) => line,
+            ReplInput::Eof => break,
+            ReplInput::IdleTimeout => {
+                out.progress(format!(
+                    "\n\nNo input for {} minute(s); ending session.",
+                    repl.session.config.agent.idle_timeout_mins
+                ));
+                repl.session.save_session();
+                break;
+            }
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match repl.command(line).await {
+            Step::Read => {}
+            Step::Turn(request) => repl.turn(request).await?,
+            Step::Quit => break,
+        }
+    }
+    Ok(repl.next_workspace)
+}
+
+impl Repl<'_> {
+    /// Run a slash command, or ask for a turn on anything else.
+    async fn command(&mut self, line: &str) -> Step {
+        let (name, args) = match line.split_once(' ') {
+            Some((name, args)) => (name, args.trim()),
+            None => (line, ""),
+        };
+        match (name, args) {
+            ("/quit" | "/exit", "") => Step::Quit,
+            ("/help", "") => {
+                self.session.out.response(INTERACTIVE_HELP);
+                Step::Read
+            }
+            ("/workspace", target) => self.workspace(target),
+            ("/memory", "") => {
+                self.memory().await;
+                Step::Read
+            }
+            ("/model", target) => {
+                self.model(target);
+                Step::Read
+            }
+            ("/temperature", value) => {
+                self.temperature(value);
+                Step::Read
+            }
+            ("/prompt", args) => self.prompt(args),
+            ("/approval", args) => {
+                self.approval(args).await;
+                Step::Read
+            }
+            ("/retry", args) => self.retry(args),
+            ("/fork", name) => {
+                self.fork(name);
+                Step::Read
+            }
+            ("/skills", "") => {
+                self.skills();
+                Step::Read
+            }
+            ("/context", format @ ("" | "json")) => {
+                self.context(format == "json");
+                Step::Read
+            }
+            ("/clear" | "/new", "") => {
+                self.clear().await;
+                Step::Read
+            }
+            _ => Step::Turn(TurnRequest::Message(
+                line.to_string(),
+                TurnOverrides::default(),
+            )),
+        }
+    }
+
+    /// `/workspace [name]`: show the current workspace, or end the session
+    /// to continue in another one.
+    fn workspace(&mut self, target: &str) -> Step {
+        let session = &*self.session;
+        if target.is_empty() {
+            session.out.progress(format!(
+                "Current workspace: {} ({})\n",
+                session.config.active_workspace_name().unwrap_or("unnamed"),
+                session.config.workspace_dir.display()
+            ));
+            return Step::Read;
+        }
+        if let Err(e) = session.config.clone().apply_workspace(target) {
+            term::eprintln(format_args!("\nError: {e}\n"));
+            return Step::Read;
+        }
+        session.save_session();
+        session
+            .out
+            .progress(format!("Switching to workspace '{target}'...\n"));
+        self.next_workspace = Some(target.to_string());
+        Step::Quit
+    }
+
+    /// `/memory`: the most recent memory entries.
+    async fn memory(&self) {
+        let out = self.session.out;
+        let mut entries = self.session.mem.list(None, None).await.unwrap_or_default();
+        if entries.is_empty() {
+            out.progress("No memory entries.\n");
+            return;
+        }
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        out.progress(format!(
+            "Recent memory ({} of {}):",
+            entries.len().min(MEMORY_LIMIT),
+            entries.len()
+        ));
+        for entry in entries.iter().take(MEMORY_LIMIT) {
+            out.progress(format!(
+                "  [{}] {}: {}",
+                entry.category,
+                entry.key,
+                truncate_with_ellipsis(&entry.content, 80)
+            ));
+        }
+        out.progress("");
+    }
+
+    /// `/model [name]`: show the model, or switch to `target` for the rest
+    /// of the session.
+    fn model(&mut self, target: &str) {
+        let session = &mut *self.session;
+        if target.is_empty() {
+            session
+                .out
+                .progress(format!("Current model: {}\n", session.model_name));
+        } else {
+            session.model_name = target.to_string();
+            session.model_pinned = true;
+            session
+                .out
+                .progress(format!("Model switched to {}\n", session.model_name));
+        }
+    }
+
+    /// `/temperature [value]`: show the temperature, or pin it for the rest
+    /// of the session.
+    fn temperature(&mut self, value: &str) {
+        let session = &mut *self.session;
+        if value.is_empty() {
+            session
+                .out
+                .progress(format!("Current temperature: {}\n", session.temperature));
+            return;
+        }
+        match value.parse::<f64>() {
+            Ok(t) if (0.0..=2.0).contains(&t) => {
+                session.temperature = t;
+                session.auto_temperature = None;
+                session.out.progress(format!("Temperature set to {t}\n"));
+            }
+            _ => term::eprintln("\nError: temperature must be a number between 0.0 and 2.0\n"),
+        }
+    }
+
+    /// `/prompt [name] [name=value ...]`: list the templates, or send one.
+    fn prompt(&self, args: &str) -> Step {
+        let out = self.session.out;
+        let args: Vec<&str> = args.split_whitespace().collect();
+        let dirs = crate::prompts::prompt_dirs(&self.session.config);
+        let Some((name, vars)) = args.split_first() else {
+            let names: Vec<String> = crate::prompts::list_prompts(&dirs)
+                .into_iter()
+                .map(|p| p.name)
+                .collect();
+            if names.is_empty() {
+                out.progress("No prompt templates found.\n");
+            } else {
+                out.progress(format!("Prompt templates: {}\n", names.join(", ")));
+            }
+            return Step::Read;
+        };
+        let rendered = crate::prompts::find_prompt(&dirs, name).and_then(|template| {
+            let text = template.render(&crate::prompts::parse_vars(vars)?)?;
+            let overrides = TurnOverrides {
+                model: template.model,
+                temperature: template.temperature,
+            };
+            Ok((text, overrides))
+        });
+        match rendered {
+            Ok((text, overrides)) => Step::Turn(TurnRequest::Message(text, overrides)),
+            Err(e) => {
+                term::eprintln(format_args!("\nError: {e:#}\n"));
+                Step::Read
+            }
+        }
+    }
+
+    /// `/approval [tool mode]`: list the per-tool approval modes, or change
+    /// one.
+    async fn approval(&self, args: &str) {
+        let out = self.session.out;
+        let security = &self.session.security;
+        let args: Vec<&str> = args.split_whitespace().collect();
+        match args.as_slice() {
+            [] => {
+                let mut modes: Vec<(String, ConfirmationMode)> = security
+                    .require_tool_approval
+                    .read()
+                    .iter()
+                    .map(|(tool, mode)| (tool.clone(), *mode))
+                    .collect();
+                if modes.is_empty() {
+                    out.progress("No per-tool approval modes set.\n");
+                    return;
+                }
+                modes.sort_by(|a, b| a.0.cmp(&b.0));
+                for (tool, mode) in modes {
+                    out.progress(format!("  {tool}: {mode}"));
+                }
+                out.progress("");
+            }
+            [tool, mode] => {
+                if !self.session.agent.tools().iter().any(|t| t.name() == *tool) {
+                    term::eprintln(format_args!("\nError: unknown tool '{tool}'\n"));
+                    return;
+                }
+                let Some(mode) = ConfirmationMode::parse(mode) else {
+                    term::eprintln(
+                        "\nError: approval mode must be always, when_destructive or never\n",
+                    );
+                    return;
+                };
+                security.set_approval_mode(tool, mode).await;
+                out.progress(format!("Approval for {tool} set to {mode}\n"));
+            }
+            _ => out.progress("Usage: /approval <tool> <always|when_destructive|never>\n"),
+        }
+    }
+
+    /// `/retry [model] [temperature]`: drop the last response and ask for a
+    /// new one.
+    fn retry(&mut self, args: &str) -> Step {
+        let out = self.session.out;
+        let overrides = match TurnOverrides::parse(args) {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                term::eprintln(format_args!("\nError: {e}\n"));
+                return Step::Read;
+            }
+        };
+        let Some(turn) = &self.last_turn else {
+            out.progress("Nothing to retry yet.\n");
+            return Step::Read;
+        };
+        let history = self.session.agent.history_mut();
+        let original = history.fork();
+        let Some(discarded) = session::truncate_last_turn(history, &turn.enriched) else {
+            out.progress(
+                "The last turn is no longer in the history (it was compacted); nothing to retry.\n",
+            );
+            return Step::Read;
+        };
+        // Side effects of the discarded turn stay; say how to undo them.
+        let tools_run = discarded_tool_calls(&discarded);
+        if !tools_run.is_empty() {
+            out.progress(format!(
+                "Note: the discarded response ran {}; its effects were not reverted.",
+                tools_run.join(", ")
+            ));
+            if let Some(id) = &turn.undo_checkpoint {
+                out.progress(format!(
+                    "Undo its file changes with `zeroclaw checkpoint restore {id}`."
+                ));
+            }
+        }
+        out.progress("Regenerating the last response...\n");
+        Step::Turn(TurnRequest::Retry {
+            user_input: turn.user_input.clone(),
+            overrides,
+            original,
+        })
+    }
+
+    /// `/fork <name>`: continue in a copy of the session transcript.
+    fn fork(&mut self, name: &str) {
+        let session = &mut *self.session;
+        if name.is_empty() {
+            session.out.progress("Usage: /fork <name>\n");
+            return;
+        }
+        let Some(current) = session.session_path.clone() else {
+            session
+                .out
+                .progress("This session is not recorded; nothing to fork.\n");
+            return;
+        };
+        session.save_session();
+        match session.session_store.fork(&current, name) {
+            Ok(path) => {
+                session.out.progress(format!(
+                    "Forked to {}; the original stays at {}.\n",
+                    path.display(),
+                    current.display()
+                ));
+                session.session_id = session_id_of(Some(&path));
+                session.session_path = Some(path);
+            }
+            Err(e) => term::eprintln(format_args!("\nError: {e}\n")),
+        }
+    }
+
+    /// `/skills`: the loaded skills.
+    fn skills(&self) {
+        let out = self.session.out;
+        let skills = self.session.agent.skills();
+        if skills.is_empty() {
+            out.progress(format!(
+                "No skills loaded (add them under {}).\n",
+                self.session.config.workspace_dir.join("skills").display()
+            ));
+            return;
+        }
+        out.progress(format!("Loaded skills ({}):", skills.len()));
+        for skill in skills {
+            out.progress(format!(
+                "  {} v{}: {}",
+                skill.name, skill.version, skill.description
+            ));
+        }
+        out.progress("");
+    }
+
+    /// `/context [json]`: estimated token breakdown of the next request.
+    fn context(&self, json: bool) {
+        let format = if json {
+            BudgetFormat::Json
+        } else {
+            BudgetFormat::Text
+        };
+        let session = &*self.session;
+        let budget = ContextBudget::measure(
+            session.agent.history(),
+            session.agent.skills(),
+            &session.config.workspace_dir,
+            &self.last_context,
+            session.config.agent.context_window_tokens,
+        );
+        session.out.progress(format!("{}\n", budget.render(format)));
+    }
+
+    /// `/clear`, `/new`: after confirmation, start over with an empty
+    /// history and task list and forget conversation and daily memory.
+    async fn clear(&mut self) {
+        let out = self.session.out;
+        out.progress(i18n::t("repl.clear_warning"));
+        out.progress(i18n::t("repl.clear_keeps_core"));
+        let ReplInput::Line(confirm) = self
+            .input
+            .read(&out, i18n::t("repl.confirm"), self.idle_timeout)
+            .await
+        else {
+            out.progress(format!("{}\n", i18n::t("repl.cancelled")));
+            return;
+        };
+        if !i18n::is_answer("answer.yes", &confirm) {
+            out.progress(format!("{}\n", i18n::t("repl.cancelled")));
+            return;
+        }
+
+        let session = &mut *self.session;
+        session.agent.clear_history();
+        session
+            .agent
+            .history_mut()
+            .push(ChatMessage::system(&session.system_prompt));
+        session.todos.clear();
+        self.last_turn = None;
+        if session.session_path.is_some() {
+            session.session_path = Some(session.session_store.new_session_path());
+            session.session_id = session_id_of(session.session_path.as_ref());
+        }
+        // Clear conversation and daily memory
+        let mut cleared = 0;
+        for category in [MemoryCategory::Conversation, MemoryCategory::Daily] {
+            let entries = session
+                .mem
+                .list(Some(&category), None)
+                .await
+                .unwrap_or_default();
+            for entry in entries {
+                if session.mem.forget(&entry.key).await.unwrap_or(false) {
+                    cleared += 1;
+                }
+            }
+        }
+        if cleared > 0 {
+            out.progress(format!(
+                "{}\n",
+                i18n::tf("repl.cleared_entries", &[("count", &cleared)])
+            ));
+        } else {
+            out.progress(format!("{}\n", i18n::t("repl.cleared")));
+        }
+    }
+
+    /// Run a turn and show its answer. Fails only when a shutdown
+    /// interrupted it.
+    async fn turn(&mut self, request: TurnRequest) -> Result<()> {
+        let out = self.session.out;
+        let (user_input, overrides, original) = match request {
+            TurnRequest::Message(text, overrides) => {
+                let (context, enriched) = self.session.enrich(&text).await;
+                self.session
+                    .agent
+                    .history_mut()
+                    .push(ChatMessage::user(&enriched));
+                self.last_context = context;
+                self.last_turn = Some(LastTurn {
+                    user_input: text.clone(),
+                    enriched,
+                    undo_checkpoint: None,
+                });
+                (text, overrides, None)
+            }
+            TurnRequest::Retry {
+                user_input,
+                overrides,
+                original,
+            } => (user_input, overrides, Some(original)),
+        };
+
+        let Some(run) = self
+            .session
+            .run_turn(
+                &user_input,
+                &overrides,
+                Some(&mut *self.input),
+                &self.last_context,
+            )
+            .await
+        else {
+            self.session.agent.history_mut().pop();
+            out.progress(format!("{}\n", i18n::t("plan.rejected")));
+            return Ok(());
+        };
+        if let Some(turn) = self
+            .last_turn
+            .as_mut()
+            .filter(|t| t.undo_checkpoint.is_none())
+        {
+            turn.undo_checkpoint = run.undo_checkpoint;
+        }
+        let failed = !matches!(run.result, Some(Ok(_)));
+        if let Some(original) = original.filter(|_| failed) {
+            *self.session.agent.history_mut() = original;
+            out.progress("Kept the previous response.");
+        }
+        let Some(result) = run.result else {
+            // The history keeps whatever the turn completed before it
+            // was cancelled.
+            if shutdown::is_shutting_down() {
+                return Err(self.session.interrupted());
+            }
+            self.session.save_session();
+            output::clear_partial_response();
+            out.progress(format!("\n{}\n", i18n::t("turn.cancelled")));
+            return Ok(());
+        };
+        let response = match result {
+            Ok(response) => response,
+            Err(e) => {
+                term::eprintln(format_args!("\nError: {e}\n"));
+                return Ok(());
+            }
+        };
+        self.session.answer(&response, &run.summary, true);
+
+        // Auto-compaction before hard trimming to preserve long-context signal.
+        let max_history = self.session.config.agent.max_history_messages;
+        if let Ok(compacted) = self
+            .session
+            .agent
+            .compact_history(&self.session.model_name, max_history)
+            .await
+        {
+            if compacted {
+                out.progress("🧹 Auto-compaction complete");
+            }
+        }
+
+        // Hard cap as a safety net.
+        trim_history(self.session.agent.history_mut(), max_history);
+        self.session.save_session();
+        self.session.autosave_response(&response).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::parser::{
+        build_assistant_history_with_tool_calls, build_native_assistant_history,
+    };
+    use crate::providers::ToolCall;
+
+    #[test]
+    fn retry_overrides_parse_model_and_temperature() {
+        assert!(TurnOverrides::parse("").unwrap().is_empty());
+        assert_eq!(
+            TurnOverrides::parse(" gpt-4o 1.2 ").unwrap(),
+            TurnOverrides {
+                model: Some("gpt-4o".into()),
+                temperature: Some(1.2),
+            }
+        );
+        assert_eq!(TurnOverrides::parse("0").unwrap().temperature, Some(0.0));
+        assert!(TurnOverrides::parse("3.5").is_err());
+        assert!(TurnOverrides::parse("one two").is_err());
+    }
+
+    #[test]
+    fn retry_lists_tools_run_by_the_discarded_multi_tool_turn() {
+        let calls = [
+            ToolCall {
+                id: "1".into(),
+                name: "shell".into(),
+                arguments: "{}".into(),
+            },
+            ToolCall {
+                id: "2".into(),
+                name: "file_write".into(),
+                arguments: "{}".into(),
+            },
+        ];
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("fix the build"),
+            ChatMessage::assistant(build_assistant_history_with_tool_calls(
+                "Checking first.",
+                &calls[..1],
+            )),
+            ChatMessage::user(
+                "[Tool results]\n<tool_result name=\"shell\">\nerror\n</tool_result>",
+            ),
+            ChatMessage::assistant(build_native_assistant_history("", &calls)),
+            ChatMessage::tool(r#"{"tool_call_id": "1", "content": "ok"}"#),
+            ChatMessage::tool(r#"{"tool_call_id": "2", "content": "ok"}"#),
+            ChatMessage::assistant("Fixed."),
+        ];
+
+        let discarded = session::truncate_last_turn(&mut history, "fix the build").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            discarded_tool_calls(&discarded),
+            ["shell", "shell", "file_write"]
+        );
+        assert!(discarded_tool_calls(&[ChatMessage::assistant("Fixed.")]).is_empty());
+    }
+
+    #[test]
+    fn interactive_help_lists_every_slash_command() {
+        for command in crate::channels::cli::SLASH_COMMANDS {
+            assert!(INTERACTIVE_HELP.contains(command), "{command}");
+        }
+    }
+
+    #[tokio::test]
+    async fn repl_input_times_out_when_idle() {
+        // Keep the sender alive so the channel stays open with no input.
+        let (_tx, mut rx) = tokio::sync::mpsc::channel::<String>(1);
+        let outcome = tokio::time::timeout(
+            Duration::from_secs(5),
+            next_input(&mut rx, Some(Duration::from_millis(50))),
+        )
+        .await
+        .expect("idle timeout should fire instead of hanging");
+        assert_eq!(outcome, ReplInput::IdleTimeout);
+    }
+
+    #[tokio::test]
+    async fn repl_input_returns_lines_and_eof() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(1);
+        tx.send("hello\n".into()).await.unwrap();
+        assert_eq!(
+            next_input(&mut rx, Some(Duration::from_secs(5))).await,
+            ReplInput::Line("hello\n".into())
+        );
+        drop(tx);
+        assert_eq!(next_input(&mut rx, None).await, ReplInput::Eof);
+    }
+}
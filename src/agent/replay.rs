@@ -0,0 +1,276 @@
+//! Pre-recorded tool results.
+//!
+//! A [`ToolCallReplayStore`] holds `(tool_name, args_pattern, result)` records.
+//! When the agent is about to run a tool, the first record whose name matches
+//! and whose pattern matches the call arguments supplies the result instead,
+//! and the tool is never executed; calls without a match run normally. Used
+//! by tests and by `zeroclaw replay` for reproducible demos.
+//!
+//! An `args_pattern` is one of:
+//! - `null` or `{}`: matches any arguments;
+//! - a JSONPath string such as `"$.path"`: matches when the path exists;
+//! - an object mapping JSONPath expressions to expected values, e.g.
+//!   `{"$.command": "cargo test"}`: matches when every path holds its value.
+//!
+//! Supported JSONPath syntax is `$`, `.key`, `['key']` and `[index]`.
+
+use crate::agent::Agent;
+use crate::config::Config;
+use crate::tools::ToolResult;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallReplayStore {
+    pub records: Vec<(String, Value, ToolResult)>,
+}
+
+/// One record in a replay file.
+#[derive(Debug, Deserialize)]
+struct ReplayRecord {
+    tool: String,
+    #[serde(default)]
+    args: Value,
+    result: ToolResult,
+}
+
+/// One step of a parsed JSONPath.
+#[derive(Debug, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn parse_json_path(path: &str) -> Result<Vec<PathSegment>> {
+    let rest = path
+        .trim()
+        .strip_prefix('$')
+        .ok_or_else(|| anyhow::anyhow!("JSONPath '{path}' must start with '$'"))?;
+    let mut segments = Vec::new();
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                let mut key = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next == '.' || next == '[' {
+                        break;
+                    }
+                    key.push(next);
+                    chars.next();
+                }
+                if key.is_empty() {
+                    anyhow::bail!("Empty key in JSONPath '{path}'");
+                }
+                segments.push(PathSegment::Key(key));
+            }
+            '[' => {
+                let mut inner = String::new();
+                for next in chars.by_ref() {
+                    if next == ']' {
+                        break;
+                    }
+                    inner.push(next);
+                }
+                let inner = inner.trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                if let Some(key) = quoted {
+                    segments.push(PathSegment::Key(key.to_string()));
+                } else {
+                    let index = inner
+                        .parse()
+                        .with_context(|| format!("Invalid index '{inner}' in JSONPath '{path}'"))?;
+                    segments.push(PathSegment::Index(index));
+                }
+            }
+            other => anyhow::bail!("Unexpected '{other}' in JSONPath '{path}'"),
+        }
+    }
+    Ok(segments)
+}
+
+/// Resolve a JSONPath against `value`. Invalid paths resolve to nothing.
+fn select<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    parse_json_path(path)
+        .ok()?
+        .iter()
+        .try_fold(value, |current, segment| match segment {
+            PathSegment::Key(key) => current.get(key),
+            PathSegment::Index(index) => current.get(index),
+        })
+}
+
+fn pattern_matches(pattern: &Value, args: &Value) -> bool {
+    match pattern {
+        Value::Null => true,
+        Value::String(path) => select(args, path).is_some(),
+        Value::Object(expected) => expected
+            .iter()
+            .all(|(path, want)| select(args, path) == Some(want)),
+        _ => false,
+    }
+}
+
+impl ToolCallReplayStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a record; earlier records take precedence.
+    pub fn record(&mut self, tool: impl Into<String>, args_pattern: Value, result: ToolResult) {
+        self.records.push((tool.into(), args_pattern, result));
+    }
+
+    /// Load records from a JSON file: an array of
+    /// `{"tool": ..., "args": <pattern>, "result": {"success", "output", "error"}}`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read replay file {}", path.display()))?;
+        let records: Vec<ReplayRecord> = serde_json::from_str(&raw)
+            .with_context(|| format!("Invalid replay file {}", path.display()))?;
+
+        let mut store = Self::new();
+        for record in records {
+            validate_pattern(&record.args)
+                .with_context(|| format!("Invalid args pattern for '{}'", record.tool))?;
+            store.record(record.tool, record.args, record.result);
+        }
+        Ok(store)
+    }
+
+    /// Pre-recorded result for a call to `tool` with `args`, if any.
+    pub fn lookup(&self, tool: &str, args: &Value) -> Option<ToolResult> {
+        self.records
+            .iter()
+            .find(|(name, pattern, _)| name == tool && pattern_matches(pattern, args))
+            .map(|(_, _, result)| result.clone())
+    }
+}
+
+/// `zeroclaw replay`: answer one message with tool calls served from `file`.
+/// Calls without a matching record run the real tool.
+pub async fn run(config: &Config, file: &Path, message: &str) -> Result<()> {
+    let store = ToolCallReplayStore::load(file)?;
+    tracing::info!(records = store.records.len(), "Loaded replay records");
+
+    let mut agent = Agent::from_config(config)?;
+    agent.set_replay_store(store);
+    agent.set_event_handler(crate::agent::agent::cli_event_handler());
+    let response = agent.run_single(message).await?;
+    println!("{response}");
+    Ok(())
+}
+
+fn validate_pattern(pattern: &Value) -> Result<()> {
+    match pattern {
+        Value::Null => Ok(()),
+        Value::String(path) => parse_json_path(path).map(|_| ()),
+        Value::Object(expected) => expected
+            .keys()
+            .try_for_each(|path| parse_json_path(path).map(|_| ())),
+        other => anyhow::bail!("Expected null, a JSONPath string or an object, got {other}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ok(output: &str) -> ToolResult {
+        ToolResult {
+            success: true,
+            output: output.into(),
+            error: None,
+            artifacts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_json_path_segments() {
+        assert_eq!(
+            parse_json_path("$.files[1]['name']").unwrap(),
+            vec![
+                PathSegment::Key("files".into()),
+                PathSegment::Index(1),
+                PathSegment::Key("name".into()),
+            ]
+        );
+        assert!(parse_json_path("$").unwrap().is_empty());
+        assert!(parse_json_path("files").is_err());
+        assert!(parse_json_path("$.a[x]").is_err());
+    }
+
+    #[test]
+    fn lookup_matches_by_tool_name_and_path_values() {
+        let mut store = ToolCallReplayStore::new();
+        store.record("shell", json!({"$.command": "cargo test"}), ok("test ok"));
+        store.record("shell", Value::Null, ok("any shell"));
+        store.record("file_read", json!("$.path"), ok("contents"));
+
+        assert_eq!(
+            store
+                .lookup("shell", &json!({"command": "cargo test"}))
+                .unwrap()
+                .output,
+            "test ok"
+        );
+        assert_eq!(
+            store
+                .lookup("shell", &json!({"command": "ls"}))
+                .unwrap()
+                .output,
+            "any shell"
+        );
+        assert_eq!(
+            store
+                .lookup("file_read", &json!({"path": "a.txt"}))
+                .unwrap()
+                .output,
+            "contents"
+        );
+        assert!(store.lookup("file_read", &json!({})).is_none());
+        assert!(store.lookup("file_write", &json!({"path": "a"})).is_none());
+    }
+
+    #[test]
+    fn nested_paths_and_indices_match() {
+        let args = json!({"edits": [{"path": "a.rs"}, {"path": "b.rs"}]});
+        assert!(pattern_matches(&json!({"$.edits[1].path": "b.rs"}), &args));
+        assert!(!pattern_matches(&json!({"$.edits[0].path": "b.rs"}), &args));
+        assert!(pattern_matches(&json!({}), &args));
+        assert!(!pattern_matches(&json!(42), &args));
+    }
+
+    #[test]
+    fn load_reads_records_and_rejects_bad_patterns() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("replay.json");
+        std::fs::write(
+            &path,
+            r#"[{"tool": "shell", "args": {"$.command": "date"}, "result": {"success": true, "output": "Mon", "error": null}}]"#,
+        )
+        .unwrap();
+        let store = ToolCallReplayStore::load(&path).unwrap();
+        assert_eq!(store.records.len(), 1);
+        assert_eq!(
+            store
+                .lookup("shell", &json!({"command": "date"}))
+                .unwrap()
+                .output,
+            "Mon"
+        );
+
+        std::fs::write(
+            &path,
+            r#"[{"tool": "shell", "args": "command", "result": {"success": true, "output": "", "error": null}}]"#,
+        )
+        .unwrap();
+        assert!(ToolCallReplayStore::load(&path).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! Per-message routing: pick a provider, model and temperature for each user
+//! message from the ordered `[[routing.rules]]` in the config.
+
+use crate::config::{ModelRouteConfig, RoutingConfig, RoutingRule};
+use crate::observability::{Observer, ObserverEvent};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// `@path` mentions: an `@` at the start of a word followed by something
+/// path-like (`@src/main.rs`, `@README.md`), so `@alice` and emails don't count.
+static FILE_MENTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)@[\w~-]*[./][\w./~-]*\w").unwrap());
+
+/// Prefix of the `hint:` routes registered for rules that name a provider.
+const HINT_PREFIX: &str = "routing.";
+
+/// Whether `message` mentions a file with `@path`.
+pub fn has_file_mention(message: &str) -> bool {
+    FILE_MENTION.is_match(message)
+}
+
+/// Where one user message goes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteChoice {
+    /// Name of the matching rule; `None` when the defaults were used.
+    pub rule: Option<String>,
+    pub provider: String,
+    pub model: String,
+    pub temperature: f64,
+}
+
+impl RouteChoice {
+    /// The defaults, used when routing is skipped or nothing matches.
+    pub fn default_route(provider: &str, model: &str, temperature: f64) -> Self {
+        Self {
+            rule: None,
+            provider: provider.to_string(),
+            model: model.to_string(),
+            temperature,
+        }
+    }
+}
+
+struct CompiledRule {
+    rule: RoutingRule,
+    pattern: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn matches(&self, message: &str, channel: &str) -> bool {
+        let rule = &self.rule;
+        let len = message.chars().count();
+        rule.min_length.is_none_or(|min| len >= min)
+            && rule.max_length.is_none_or(|max| len <= max)
+            && (rule.channels.is_empty() || rule.channels.iter().any(|c| c == channel))
+            && rule
+                .file_mentions
+                .is_none_or(|wanted| has_file_mention(message) == wanted)
+            && self.pattern.as_ref().is_none_or(|re| re.is_match(message))
+    }
+}
+
+/// The compiled `[routing]` rules, evaluated once per user message.
+#[derive(Default)]
+pub struct MessageRouter {
+    rules: Vec<CompiledRule>,
+}
+
+impl MessageRouter {
+    /// Compile the rules; an invalid `pattern` is a config error.
+    pub fn from_config(config: &RoutingConfig) -> Result<Self> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let pattern = rule
+                    .pattern
+                    .as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .with_context(|| format!("Invalid pattern in routing rule `{}`", rule.name))?;
+                Ok(CompiledRule {
+                    rule: rule.clone(),
+                    pattern,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The first rule, in config order, whose conditions all hold.
+    pub fn matching_rule(&self, message: &str, channel: &str) -> Option<&RoutingRule> {
+        self.rules
+            .iter()
+            .find(|compiled| compiled.matches(message, channel))
+            .map(|compiled| &compiled.rule)
+    }
+
+    /// Route `message` from `channel`. The matching rule's fields replace
+    /// those of `defaults`; with no match, `defaults` is returned as is.
+    pub fn route(&self, message: &str, channel: &str, defaults: RouteChoice) -> RouteChoice {
+        let Some(rule) = self.matching_rule(message, channel) else {
+            return defaults;
+        };
+        let choice = RouteChoice {
+            rule: Some(rule.name.clone()),
+            provider: rule.provider.clone().unwrap_or(defaults.provider),
+            model: rule.model.clone().unwrap_or(defaults.model),
+            temperature: rule.temperature.unwrap_or(defaults.temperature),
+        };
+        tracing::info!(
+            rule = rule.name.as_str(),
+            provider = choice.provider.as_str(),
+            model = choice.model.as_str(),
+            temperature = choice.temperature,
+            "Routed message"
+        );
+        choice
+    }
+
+    /// `[[model_routes]]` entries for rules that switch provider, so a
+    /// routed provider built with them can serve [`Self::model_for`].
+    pub fn model_routes(&self, default_model: &str) -> Vec<ModelRouteConfig> {
+        self.rules
+            .iter()
+            .filter_map(|compiled| {
+                let rule = &compiled.rule;
+                rule.provider.as_ref().map(|provider| ModelRouteConfig {
+                    hint: format!("{HINT_PREFIX}{}", rule.name),
+                    provider: provider.clone(),
+                    model: rule
+                        .model
+                        .clone()
+                        .unwrap_or_else(|| default_model.to_string()),
+                    api_key: None,
+                })
+            })
+            .collect()
+    }
+
+    /// The model string to request from a provider built with
+    /// [`Self::model_routes`]: a `hint:` when the route switched provider.
+    pub fn model_for(choice: &RouteChoice, default_provider: &str) -> String {
+        match &choice.rule {
+            Some(rule) if choice.provider != default_provider => {
+                format!("hint:{HINT_PREFIX}{rule}")
+            }
+            _ => choice.model.clone(),
+        }
+    }
+}
+
+/// Route one user message and report the choice to `observer`. `pinned`
+/// means the model was chosen explicitly for this message (`--model`,
+/// `/model`, an inline directive), which always wins over the rules.
+pub fn route_message(
+    router: &MessageRouter,
+    pinned: bool,
+    message: &str,
+    channel: &str,
+    defaults: RouteChoice,
+    observer: &dyn Observer,
+) -> RouteChoice {
+    if pinned || router.is_empty() {
+        return defaults;
+    }
+    let choice = router.route(message, channel, defaults);
+    observer.record_event(&ObserverEvent::MessageRouted {
+        rule: choice.rule.clone(),
+        provider: choice.provider.clone(),
+        model: choice.model.clone(),
+        temperature: choice.temperature,
+    });
+    choice
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str) -> RoutingRule {
+        RoutingRule {
+            name: name.into(),
+            ..RoutingRule::default()
+        }
+    }
+
+    fn router(rules: Vec<RoutingRule>) -> MessageRouter {
+        MessageRouter::from_config(&RoutingConfig { rules }).unwrap()
+    }
+
+    fn defaults() -> RouteChoice {
+        RouteChoice::default_route("anthropic", "claude-sonnet", 0.7)
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let router = router(vec![
+            RoutingRule {
+                max_length: Some(20),
+                model: Some("cheap".into()),
+                ..rule("small-talk")
+            },
+            RoutingRule {
+                pattern: Some(r"(?i)\brefactor\b".into()),
+                model: Some("strong".into()),
+                ..rule("coding")
+            },
+        ]);
+
+        assert_eq!(router.route("what's 2+2", "cli", defaults()).model, "cheap");
+        // Both rules match; the earlier one wins.
+        assert_eq!(
+            router
+                .route("refactor this", "cli", defaults())
+                .rule
+                .as_deref(),
+            Some("small-talk")
+        );
+        assert_eq!(
+            router
+                .route(
+                    "please refactor the parser module for me",
+                    "cli",
+                    defaults()
+                )
+                .model,
+            "strong"
+        );
+    }
+
+    #[test]
+    fn no_match_falls_back_to_defaults() {
+        let router = router(vec![RoutingRule {
+            channels: vec!["telegram".into()],
+            model: Some("cheap".into()),
+            ..rule("telegram")
+        }]);
+
+        assert_eq!(router.route("hi", "cli", defaults()), defaults());
+        assert_eq!(router.route("hi", "telegram", defaults()).model, "cheap");
+    }
+
+    #[test]
+    fn unset_route_fields_keep_the_defaults() {
+        let router = router(vec![RoutingRule {
+            temperature: Some(0.1),
+            ..rule("precise")
+        }]);
+
+        let choice = router.route("anything", "cli", defaults());
+        assert_eq!(choice.rule.as_deref(), Some("precise"));
+        assert_eq!(choice.provider, "anthropic");
+        assert_eq!(choice.model, "claude-sonnet");
+        assert_eq!(choice.temperature, 0.1);
+    }
+
+    #[test]
+    fn file_mentions_condition() {
+        let router = router(vec![RoutingRule {
+            file_mentions: Some(true),
+            ..rule("files")
+        }]);
+
+        assert!(router
+            .matching_rule("look at @src/main.rs", "cli")
+            .is_some());
+        assert!(router.matching_rule("@README.md please", "cli").is_some());
+        assert!(router.matching_rule("ping @alice", "cli").is_none());
+        assert!(router.matching_rule("mail me@example.com", "cli").is_none());
+    }
+
+    #[test]
+    fn invalid_pattern_is_a_config_error() {
+        let config = RoutingConfig {
+            rules: vec![RoutingRule {
+                pattern: Some("(".into()),
+                ..rule("broken")
+            }],
+        };
+        let err = MessageRouter::from_config(&config).err().unwrap();
+        assert!(err.to_string().contains("broken"));
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        routes: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    impl Observer for RecordingObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            if let ObserverEvent::MessageRouted { rule, .. } = event {
+                self.routes.lock().unwrap().push(rule.clone());
+            }
+        }
+
+        fn record_metric(&self, _metric: &crate::observability::traits::ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    #[test]
+    fn explicit_model_choice_wins_over_rules() {
+        let router = router(vec![RoutingRule {
+            model: Some("cheap".into()),
+            ..rule("everything")
+        }]);
+        let observer = RecordingObserver::default();
+        let pinned = RouteChoice::default_route("anthropic", "user-picked", 0.7);
+
+        let choice = route_message(&router, true, "hi", "cli", pinned.clone(), &observer);
+        assert_eq!(choice, pinned);
+        assert!(observer.routes.lock().unwrap().is_empty());
+
+        let choice = route_message(&router, false, "hi", "cli", defaults(), &observer);
+        assert_eq!(choice.model, "cheap");
+        assert_eq!(
+            *observer.routes.lock().unwrap(),
+            [Some("everything".to_string())]
+        );
+    }
+
+    #[test]
+    fn provider_switch_goes_through_a_hint_route() {
+        let router = router(vec![
+            RoutingRule {
+                provider: Some("groq".into()),
+                model: Some("llama".into()),
+                ..rule("fast")
+            },
+            RoutingRule {
+                model: Some("opus".into()),
+                ..rule("strong")
+            },
+        ]);
+
+        let routes = router.model_routes("claude-sonnet");
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].hint, "routing.fast");
+        assert_eq!(routes[0].provider, "groq");
+        assert_eq!(routes[0].model, "llama");
+
+        let fast = router.route("hi", "cli", defaults());
+        assert_eq!(
+            MessageRouter::model_for(&fast, "anthropic"),
+            "hint:routing.fast"
+        );
+        let strong = RouteChoice {
+            rule: Some("strong".into()),
+            model: "opus".into(),
+            ..defaults()
+        };
+        assert_eq!(MessageRouter::model_for(&strong, "anthropic"), "opus");
+    }
+}
@@ -0,0 +1,409 @@
+//! Per-workspace CLI session transcripts.
+//!
+//! Each `zeroclaw agent` run writes its conversation (without the system
+//! prompt) to `<workspace>/sessions/<date>-<time>-agent-<id>.jsonl`, one
+//! [`ChatMessage`] per line. `--continue` reloads the most recently written
+//! transcript and keeps appending to it. The date prefix lets memory hygiene
+//! archive old sessions like any other session file.
+//!
+//! The `todo` tool's task list is saved next to the transcript as
+//! `<transcript>.todo.json` and restored with it.
+//!
+//! Interactive mode rewrites the history in place: `/retry` drops the last
+//! turn's replies with [`truncate_last_turn`], and `/fork <name>` copies the
+//! transcript with [`SessionStore::fork`] and keeps recording to the copy.
+
+use crate::providers::ChatMessage;
+use crate::tools::todo::TodoItem;
+use anyhow::{Context, Result};
+use chrono::Local;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const SESSION_FILE_EXT: &str = "jsonl";
+
+/// How an agent run treats session transcripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SessionMode {
+    /// Neither read nor write transcripts (background runs: cron, heartbeat).
+    #[default]
+    Ephemeral,
+    /// Record a new transcript.
+    New,
+    /// Resume the most recent transcript (`--continue`) and keep recording to it.
+    Continue,
+}
+
+/// A saved session transcript.
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub path: PathBuf,
+    pub messages: Vec<ChatMessage>,
+    pub todos: Vec<TodoItem>,
+}
+
+/// Reads and writes session transcripts under `<workspace>/sessions`.
+pub struct SessionStore {
+    sessions_dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            sessions_dir: workspace_dir.join("sessions"),
+        }
+    }
+
+    /// Path for a brand-new session transcript.
+    pub fn new_session_path(&self) -> PathBuf {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        self.sessions_dir.join(format!(
+            "{}-agent-{}.{SESSION_FILE_EXT}",
+            Local::now().format("%Y-%m-%d-%H%M%S"),
+            &id[..8]
+        ))
+    }
+
+    /// Copy the transcript at `from` (and its task list) under a new id
+    /// tagged with `name`, and return the copy's path.
+    pub fn fork(&self, from: &Path, name: &str) -> Result<PathBuf> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            anyhow::bail!("Invalid fork name '{name}': use letters, digits, '-' and '_'");
+        }
+        let fresh = self.new_session_path();
+        let stem = fresh
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let to = fresh.with_file_name(format!("{stem}-{name}.{SESSION_FILE_EXT}"));
+        fs::copy(from, &to)
+            .with_context(|| format!("Failed to copy session file {}", from.display()))?;
+        if todo_path(from).exists() {
+            fs::copy(todo_path(from), todo_path(&to))?;
+        }
+        Ok(to)
+    }
+
+    /// Overwrite the transcript at `path` with `history`, skipping system messages.
+    pub fn save(&self, path: &Path, history: &[ChatMessage]) -> Result<()> {
+        fs::create_dir_all(&self.sessions_dir)?;
+
+        let mut buf = Vec::new();
+        for msg in history.iter().filter(|m| m.role != "system") {
+            serde_json::to_writer(&mut buf, msg)?;
+            buf.push(b'\n');
+        }
+
+        let tmp = path.with_extension("jsonl.tmp");
+        let mut file = fs::File::create(&tmp)
+            .with_context(|| format!("Failed to create session file {}", tmp.display()))?;
+        file.write_all(&buf)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Save the task list for the transcript at `path`; an empty list
+    /// removes it.
+    pub fn save_todos(&self, path: &Path, todos: &[TodoItem]) -> Result<()> {
+        let todo_path = todo_path(path);
+        if todos.is_empty() {
+            if todo_path.exists() {
+                fs::remove_file(&todo_path)?;
+            }
+            return Ok(());
+        }
+        fs::create_dir_all(&self.sessions_dir)?;
+        fs::write(&todo_path, serde_json::to_vec_pretty(todos)?)
+            .with_context(|| format!("Failed to write {}", todo_path.display()))?;
+        Ok(())
+    }
+
+    /// Load a transcript, ignoring malformed lines.
+    pub fn load(&self, path: &Path) -> Result<Session> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read session file {}", path.display()))?;
+        let messages = raw
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str::<ChatMessage>(line).ok())
+            .collect();
+        let todos = fs::read_to_string(todo_path(path))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Ok(Session {
+            path: path.to_path_buf(),
+            messages,
+            todos,
+        })
+    }
+
+    /// The most recently written session transcript, if any.
+    pub fn latest(&self) -> Result<Option<Session>> {
+        if !self.sessions_dir.is_dir() {
+            return Ok(None);
+        }
+
+        let mut newest: Option<(std::time::SystemTime, PathBuf)> = None;
+        for entry in fs::read_dir(&self.sessions_dir)? {
+            let path = entry?.path();
+            if !path.is_file()
+                || path.extension().and_then(|e| e.to_str()) != Some(SESSION_FILE_EXT)
+            {
+                continue;
+            }
+            let modified = fs::metadata(&path)?.modified()?;
+            let is_newer = newest.as_ref().map_or(true, |(time, best)| {
+                modified > *time || (modified == *time && path > *best)
+            });
+            if is_newer {
+                newest = Some((modified, path));
+            }
+        }
+
+        newest.map(|(_, path)| self.load(&path)).transpose()
+    }
+}
+
+fn todo_path(transcript: &Path) -> PathBuf {
+    transcript.with_extension(format!("{SESSION_FILE_EXT}.todo.json"))
+}
+
+/// Build the starting history: the system prompt followed by any resumed messages.
+pub fn initial_history(system_prompt: &str, resumed: Option<&Session>) -> Vec<ChatMessage> {
+    let mut history = vec![ChatMessage::system(system_prompt)];
+    if let Some(session) = resumed {
+        history.extend(
+            session
+                .messages
+                .iter()
+                .filter(|m| m.role != "system")
+                .cloned(),
+        );
+    }
+    history
+}
+
+/// Drop everything after the user message `user_message` (the last one with
+/// that content), keeping the message itself so the turn can run again.
+/// Returns the removed replies, tool calls and tool results, or `None` when
+/// the message is no longer in the history (e.g. it was compacted away).
+pub fn truncate_last_turn(
+    history: &mut Vec<ChatMessage>,
+    user_message: &str,
+) -> Option<Vec<ChatMessage>> {
+    let start = history
+        .iter()
+        .rposition(|m| m.role == "user" && m.content == user_message)?;
+    Some(history.split_off(start + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn latest_is_none_without_sessions() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        assert!(store.latest().unwrap().is_none());
+    }
+
+    #[test]
+    fn save_skips_system_prompt_and_roundtrips() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        let path = store.new_session_path();
+        store
+            .save(
+                &path,
+                &[
+                    ChatMessage::system("sys"),
+                    ChatMessage::user("hi"),
+                    ChatMessage::assistant("hello"),
+                ],
+            )
+            .unwrap();
+
+        let session = store.load(&path).unwrap();
+        assert_eq!(session.messages.len(), 2);
+        assert_eq!(session.messages[0].role, "user");
+        assert_eq!(session.messages[1].content, "hello");
+    }
+
+    #[test]
+    fn continue_loads_latest_prior_session_as_starting_context() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        fs::create_dir_all(tmp.path().join("sessions")).unwrap();
+
+        let older = tmp
+            .path()
+            .join("sessions/2026-01-01-090000-agent-aaaaaaaa.jsonl");
+        store
+            .save(&older, &[ChatMessage::user("old question")])
+            .unwrap();
+        let newer = tmp
+            .path()
+            .join("sessions/2026-01-02-090000-agent-bbbbbbbb.jsonl");
+        store
+            .save(
+                &newer,
+                &[
+                    ChatMessage::user("remember the number 42"),
+                    ChatMessage::assistant("Noted: 42."),
+                ],
+            )
+            .unwrap();
+        // Bump the newer file so mtime ordering is unambiguous.
+        let file = fs::OpenOptions::new().append(true).open(&newer).unwrap();
+        file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+
+        let resumed = store.latest().unwrap().expect("latest session");
+        assert_eq!(resumed.path, newer);
+
+        let history = initial_history("system prompt", Some(&resumed));
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].role, "system");
+        assert_eq!(history[1].content, "remember the number 42");
+        assert_eq!(history[2].content, "Noted: 42.");
+    }
+
+    #[test]
+    fn todo_list_roundtrips_with_the_session() {
+        use crate::tools::todo::{TodoList, TodoStatus};
+
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        let path = store.new_session_path();
+        let list = TodoList::default();
+        list.set(vec![
+            TodoItem {
+                text: "write parser".into(),
+                status: TodoStatus::Pending,
+            },
+            TodoItem {
+                text: "add tests".into(),
+                status: TodoStatus::Pending,
+            },
+        ]);
+        list.check(1).unwrap();
+        store.save(&path, &[ChatMessage::user("hi")]).unwrap();
+        store.save_todos(&path, &list.items()).unwrap();
+
+        let session = store.latest().unwrap().expect("saved session");
+        assert_eq!(session.path, path, "the todo file is not a transcript");
+        assert_eq!(session.todos, list.items());
+        assert_eq!(session.todos[1].status, TodoStatus::InProgress);
+
+        store.save_todos(&path, &[]).unwrap();
+        assert!(store.load(&path).unwrap().todos.is_empty());
+    }
+
+    #[test]
+    fn load_ignores_malformed_lines() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        fs::create_dir_all(tmp.path().join("sessions")).unwrap();
+        let path = tmp
+            .path()
+            .join("sessions/2026-01-01-000000-agent-cccccccc.jsonl");
+        fs::write(
+            &path,
+            "{\"role\":\"user\",\"content\":\"ok\"}\nnot json\n\n",
+        )
+        .unwrap();
+
+        let session = store.load(&path).unwrap();
+        assert_eq!(session.messages.len(), 1);
+    }
+
+    fn multi_tool_turn(question: &str, answer: &str) -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::user(question),
+            ChatMessage::assistant(
+                "<tool_call>\n{\"name\": \"shell\", \"arguments\": {}}\n</tool_call>",
+            ),
+            ChatMessage::user("[Tool results]\n<tool_result name=\"shell\">\nok\n</tool_result>"),
+            ChatMessage::assistant(
+                r#"{"content": null, "tool_calls": [{"id": "1", "name": "file_write", "arguments": "{}"}]}"#,
+            ),
+            ChatMessage::tool(r#"{"tool_call_id": "1", "content": "written"}"#),
+            ChatMessage::tool(r#"{"tool_call_id": "2", "content": "written"}"#),
+            ChatMessage::assistant(answer),
+        ]
+    }
+
+    #[test]
+    fn truncate_last_turn_drops_replies_and_tool_results() {
+        let mut history = vec![ChatMessage::system("sys")];
+        history.extend(multi_tool_turn("first", "first answer"));
+        history.extend(multi_tool_turn("second", "second answer"));
+        let before_second = history.len() - 6;
+
+        let removed = truncate_last_turn(&mut history, "second").unwrap();
+        assert_eq!(removed.len(), 6);
+        assert_eq!(removed[0].role, "assistant");
+        assert_eq!(removed[5].content, "second answer");
+        assert_eq!(history.len(), before_second);
+        assert_eq!(history.last().unwrap().content, "second");
+        // The earlier turn, tool results included, is untouched.
+        assert_eq!(history[7].content, "first answer");
+
+        // Truncating again is a no-op that still keeps the user message.
+        assert!(truncate_last_turn(&mut history, "second")
+            .unwrap()
+            .is_empty());
+        assert_eq!(history.len(), before_second);
+    }
+
+    #[test]
+    fn truncate_last_turn_matches_the_latest_repeat_and_missing_messages() {
+        let mut history = vec![ChatMessage::system("sys")];
+        history.extend(multi_tool_turn("again", "one"));
+        history.extend(multi_tool_turn("again", "two"));
+
+        let removed = truncate_last_turn(&mut history, "again").unwrap();
+        assert_eq!(removed.last().unwrap().content, "two");
+        assert_eq!(history.len(), 9);
+
+        // A message that is gone from the history cannot be retried.
+        assert!(truncate_last_turn(&mut history, "compacted away").is_none());
+        assert_eq!(history.len(), 9);
+    }
+
+    #[test]
+    fn fork_copies_transcript_and_todos_under_a_new_id() {
+        use crate::tools::todo::TodoStatus;
+
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        let path = store.new_session_path();
+        store.save(&path, &multi_tool_turn("q", "a")).unwrap();
+        let todos = [TodoItem {
+            text: "explore".into(),
+            status: TodoStatus::Pending,
+        }];
+        store.save_todos(&path, &todos).unwrap();
+
+        let fork = store.fork(&path, "alt-plan").unwrap();
+        assert_ne!(fork, path);
+        assert!(fork.to_string_lossy().ends_with("-alt-plan.jsonl"));
+        let forked = store.load(&fork).unwrap();
+        assert_eq!(forked.messages.len(), 7);
+        assert_eq!(forked.todos, todos);
+
+        // The original is independent of the fork.
+        store.save(&fork, &[ChatMessage::user("diverged")]).unwrap();
+        assert_eq!(store.load(&path).unwrap().messages.len(), 7);
+        assert!(store.fork(&path, "../escape").is_err());
+    }
+}
@@ -0,0 +1,647 @@
+//! Orderly shutdown of `zeroclaw agent` on SIGINT/SIGTERM.
+//!
+//! Tools register the process groups they spawn and the temp paths they
+//! create. Signals are turned into cancellation: the first Ctrl-C while a turn
+//! is running cancels just that turn (interactive mode returns to the prompt);
+//! Ctrl-C between turns, a second Ctrl-C, SIGTERM, or closing the Windows
+//! console shuts the whole run down.
+//!
+//! A cancelled turn first terminates its registered children — SIGTERM to
+//! each process group, then SIGKILL after [`GRACE_PERIOD`] — and is only
+//! then dropped, so the session and checkpoint journal are written from the
+//! history it left behind. On Windows, which has no process groups, each
+//! registered child is put in its own job object created with
+//! `JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE`: stopping it terminates the job, and
+//! dropping its registration closes the job, so nothing the command started
+//! outlives it. Windows has no polite equivalent of SIGTERM for console
+//! processes, so there the grace period is skipped.
+
+use crate::agent::output::Interrupted;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// How long children get to exit after SIGTERM before they are killed.
+pub const GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// Extra time an interrupted run gets to save its state after its children
+/// have been stopped.
+const WIND_DOWN: Duration = Duration::from_secs(2);
+
+/// Registered children, temp paths, and the cancellation tokens signals act on.
+pub struct Coordinator {
+    next_id: AtomicU64,
+    children: Mutex<HashMap<u64, Arc<ChildGroup>>>,
+    temp_paths: Mutex<HashMap<u64, PathBuf>>,
+    shutdown: CancellationToken,
+    turn: Mutex<Option<CancellationToken>>,
+}
+
+impl Default for Coordinator {
+    fn default() -> Self {
+        Self {
+            next_id: AtomicU64::new(0),
+            children: Mutex::new(HashMap::new()),
+            temp_paths: Mutex::new(HashMap::new()),
+            shutdown: CancellationToken::new(),
+            turn: Mutex::new(None),
+        }
+    }
+}
+
+static COORDINATOR: LazyLock<Coordinator> = LazyLock::new(Coordinator::default);
+
+/// The process-wide coordinator.
+pub fn coordinator() -> &'static Coordinator {
+    &COORDINATOR
+}
+
+/// Removes its child or temp path from the registry when dropped.
+pub struct Registration {
+    coordinator: &'static Coordinator,
+    id: u64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Ok(mut children) = self.coordinator.children.lock() {
+            children.remove(&self.id);
+        }
+        if let Ok(mut paths) = self.coordinator.temp_paths.lock() {
+            paths.remove(&self.id);
+        }
+    }
+}
+
+/// Register a spawned child together with everything it starts: its process
+/// group on Unix, a kill-on-close job object on Windows. `None` if the child
+/// has already exited or (on Windows) its job could not be set up.
+pub fn register_child(child: &tokio::process::Child) -> Option<Registration> {
+    let group = ChildGroup::of(child)?;
+    let coordinator = coordinator();
+    let id = coordinator.next_id.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut children) = coordinator.children.lock() {
+        children.insert(id, Arc::new(group));
+    }
+    Some(Registration { coordinator, id })
+}
+
+/// Register a temp file or directory to remove if the run is interrupted.
+pub fn register_temp_path(path: PathBuf) -> Registration {
+    let coordinator = coordinator();
+    let id = coordinator.next_id.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut paths) = coordinator.temp_paths.lock() {
+        paths.insert(id, path);
+    }
+    Registration { coordinator, id }
+}
+
+/// Whether SIGTERM (or a Ctrl-C between turns) asked the run to stop.
+pub fn is_shutting_down() -> bool {
+    coordinator().shutdown.is_cancelled()
+}
+
+/// Resolves once a shutdown has been requested.
+pub async fn shutdown_requested() {
+    coordinator().shutdown.cancelled().await;
+}
+
+impl Coordinator {
+    /// Ctrl-C: cancel the running turn, or shut down if there is none.
+    pub fn on_interrupt(&self) {
+        let turn = self.turn.lock().ok().and_then(|turn| turn.clone());
+        match turn {
+            Some(turn) if !turn.is_cancelled() => {
+                tracing::info!("Interrupt received; cancelling the current turn");
+                turn.cancel();
+            }
+            _ => self.on_terminate(),
+        }
+    }
+
+    /// SIGTERM: always shut down.
+    pub fn on_terminate(&self) {
+        tracing::info!("Shutdown requested");
+        self.shutdown.cancel();
+    }
+
+    /// Start a turn that Ctrl-C can cancel on its own.
+    pub fn begin_turn(&'static self) -> TurnScope {
+        let token = self.shutdown.child_token();
+        if let Ok(mut turn) = self.turn.lock() {
+            *turn = Some(token.clone());
+        }
+        TurnScope {
+            coordinator: self,
+            token,
+        }
+    }
+
+    fn child_groups(&self) -> Vec<Arc<ChildGroup>> {
+        self.children
+            .lock()
+            .map(|children| children.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// SIGTERM every registered process group, wait up to `grace` for them
+    /// to exit, then SIGKILL whatever is left.
+    pub async fn terminate_children(&self, grace: Duration) {
+        let groups = self.child_groups();
+        if groups.is_empty() {
+            return;
+        }
+        tracing::info!(count = groups.len(), "Stopping child processes");
+        for group in &groups {
+            group.signal(Signal::Terminate);
+        }
+        let deadline = tokio::time::Instant::now() + grace;
+        while tokio::time::Instant::now() < deadline {
+            if !groups.iter().any(|group| group.alive()) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        for group in &groups {
+            if group.alive() {
+                tracing::warn!(pid = group.pid, "Child ignored SIGTERM; killing it");
+                group.signal(Signal::Kill);
+            }
+        }
+    }
+
+    /// Remove registered temp paths (best effort).
+    pub fn remove_temp_paths(&self) {
+        let paths: Vec<PathBuf> = self
+            .temp_paths
+            .lock()
+            .map(|mut paths| paths.drain().map(|(_, path)| path).collect())
+            .unwrap_or_default();
+        for path in paths {
+            let removed = if path.is_dir() {
+                std::fs::remove_dir_all(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+            if let Err(e) = removed {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to remove {}: {e}", path.display());
+                }
+            }
+        }
+    }
+}
+
+/// A running turn; clears itself as the current turn when dropped.
+pub struct TurnScope {
+    coordinator: &'static Coordinator,
+    token: CancellationToken,
+}
+
+impl TurnScope {
+    /// Run `turn` until it finishes (`Some`) or is cancelled (`None`). A
+    /// cancelled turn's children are stopped before the turn is dropped.
+    pub async fn run<F: Future>(&self, turn: F) -> Option<F::Output> {
+        let mut turn = Box::pin(turn);
+        tokio::select! {
+            output = &mut turn => Some(output),
+            () = self.token.cancelled() => {
+                self.coordinator.terminate_children(GRACE_PERIOD).await;
+                drop(turn);
+                None
+            }
+        }
+    }
+}
+
+impl Drop for TurnScope {
+    fn drop(&mut self) {
+        if let Ok(mut turn) = self.coordinator.turn.lock() {
+            *turn = None;
+        }
+    }
+}
+
+/// Start a turn on the process-wide coordinator.
+pub fn begin_turn() -> TurnScope {
+    coordinator().begin_turn()
+}
+
+/// Route SIGINT/SIGTERM (Ctrl-C / console close on Windows) to the
+/// process-wide coordinator.
+pub fn install_signal_handlers() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let (Ok(mut interrupt), Ok(mut terminate)) = (
+            signal(SignalKind::interrupt()),
+            signal(SignalKind::terminate()),
+        ) else {
+            tracing::warn!("Failed to install signal handlers");
+            return;
+        };
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = interrupt.recv() => coordinator().on_interrupt(),
+                    _ = terminate.recv() => coordinator().on_terminate(),
+                }
+            }
+        });
+    }
+    #[cfg(windows)]
+    {
+        use tokio::signal::windows::{ctrl_c, ctrl_close};
+        let (Ok(mut interrupt), Ok(mut close)) = (ctrl_c(), ctrl_close()) else {
+            tracing::warn!("Failed to install console control handlers");
+            return;
+        };
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = interrupt.recv() => coordinator().on_interrupt(),
+                    _ = close.recv() => coordinator().on_terminate(),
+                }
+            }
+        });
+    }
+}
+
+/// Await `run` unless a shutdown is requested. An interrupted run gets a
+/// moment to stop its turn and save its session; afterwards registered
+/// children and temp paths are cleaned up and [`Interrupted`] is returned.
+pub async fn run_until_shutdown(run: impl Future<Output = Result<String>>) -> Result<String> {
+    let coordinator = coordinator();
+    let mut run = Box::pin(run);
+    let result = tokio::select! {
+        result = &mut run => result,
+        () = coordinator.shutdown.cancelled() => {
+            match tokio::time::timeout(GRACE_PERIOD + WIND_DOWN, &mut run).await {
+                Ok(Ok(output)) => Ok(output),
+                Ok(Err(_)) | Err(_) => Err(Interrupted.into()),
+            }
+        }
+    };
+    drop(run);
+    coordinator.terminate_children(GRACE_PERIOD).await;
+    coordinator.remove_temp_paths();
+    result
+}
+
+enum Signal {
+    Terminate,
+    Kill,
+}
+
+/// A registered child and everything it started.
+struct ChildGroup {
+    /// The child's pid, which on Unix is also its process group id.
+    pid: u32,
+    #[cfg(windows)]
+    job: job::Job,
+}
+
+#[cfg(unix)]
+impl ChildGroup {
+    fn of(child: &tokio::process::Child) -> Option<Self> {
+        Some(Self { pid: child.id()? })
+    }
+
+    fn signal(&self, signal: Signal) {
+        let Ok(pid) = libc::pid_t::try_from(self.pid) else {
+            return;
+        };
+        let signal = match signal {
+            Signal::Terminate => libc::SIGTERM,
+            Signal::Kill => libc::SIGKILL,
+        };
+        // SAFETY: kill(2) with a negative pid signals that process group; it
+        // has no memory-safety preconditions.
+        unsafe {
+            libc::kill(-pid, signal);
+        }
+    }
+
+    fn alive(&self) -> bool {
+        let Ok(pid) = libc::pid_t::try_from(self.pid) else {
+            return false;
+        };
+        // SAFETY: signal 0 only checks that the process group exists.
+        unsafe { libc::kill(-pid, 0) == 0 }
+    }
+}
+
+#[cfg(windows)]
+impl ChildGroup {
+    /// Put the child in a fresh job. Anything it spawned before being
+    /// assigned escapes the job; shell commands are assigned right after
+    /// spawn, before the shell has parsed its command line.
+    fn of(child: &tokio::process::Child) -> Option<Self> {
+        let pid = child.id()?;
+        let process = child.raw_handle()?;
+        let job = job::Job::new()
+            .and_then(|job| job.assign(process).map(|()| job))
+            .map_err(|e| tracing::warn!(pid, "Failed to put child in a job object: {e}"))
+            .ok()?;
+        Some(Self { pid, job })
+    }
+
+    /// Console processes cannot be asked to exit, so both signals end the
+    /// whole job at once.
+    fn signal(&self, _signal: Signal) {
+        self.job.terminate();
+    }
+
+    fn alive(&self) -> bool {
+        self.job.active_processes() > 0
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+impl ChildGroup {
+    fn of(child: &tokio::process::Child) -> Option<Self> {
+        Some(Self { pid: child.id()? })
+    }
+
+    fn signal(&self, _signal: Signal) {}
+
+    fn alive(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(windows)]
+mod job {
+    use std::io;
+    use std::os::windows::io::RawHandle;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectBasicAccountingInformation,
+        JobObjectExtendedLimitInformation, QueryInformationJobObject, SetInformationJobObject,
+        TerminateJobObject, JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    /// An anonymous job object whose processes are killed when it is closed.
+    pub struct Job(HANDLE);
+
+    // SAFETY: job handles may be used and closed from any thread.
+    unsafe impl Send for Job {}
+    // SAFETY: the job APIs used here are safe to call concurrently.
+    unsafe impl Sync for Job {}
+
+    impl Job {
+        pub fn new() -> io::Result<Self> {
+            // SAFETY: null security attributes and name create an anonymous
+            // job with default security.
+            let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if handle.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let job = Self(handle);
+            let mut limits = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            // SAFETY: `limits` is the struct this information class expects
+            // and lives for the duration of the call.
+            let set = unsafe {
+                SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    std::ptr::from_ref(&limits).cast(),
+                    size_of_u32::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>(),
+                )
+            };
+            if set == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(job)
+        }
+
+        pub fn assign(&self, process: RawHandle) -> io::Result<()> {
+            // SAFETY: both handles are open; the caller keeps the process
+            // handle alive for the call.
+            if unsafe { AssignProcessToJobObject(self.0, process.cast()) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        pub fn terminate(&self) {
+            // SAFETY: the job handle is open until `drop`.
+            unsafe {
+                TerminateJobObject(self.0, 1);
+            }
+        }
+
+        pub fn active_processes(&self) -> u32 {
+            let mut info = JOBOBJECT_BASIC_ACCOUNTING_INFORMATION::default();
+            // SAFETY: `info` is the struct this information class expects
+            // and is writable for the duration of the call.
+            let queried = unsafe {
+                QueryInformationJobObject(
+                    self.0,
+                    JobObjectBasicAccountingInformation,
+                    std::ptr::from_mut(&mut info).cast(),
+                    size_of_u32::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>(),
+                    std::ptr::null_mut(),
+                )
+            };
+            if queried == 0 {
+                0
+            } else {
+                info.ActiveProcesses
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            // SAFETY: the handle is open and closed exactly once. Closing the
+            // last handle kills whatever is still in the job.
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    const fn size_of_u32<T>() -> u32 {
+        std::mem::size_of::<T>() as u32
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::Stdio;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    fn coordinator() -> &'static Coordinator {
+        Box::leak(Box::default())
+    }
+
+    /// Spawn `script` in its own process group and wait until it prints "ready".
+    async fn spawn_ready(script: &str) -> tokio::process::Child {
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .process_group(0)
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let mut line = String::new();
+        BufReader::new(stdout).read_line(&mut line).await.unwrap();
+        assert_eq!(line.trim(), "ready");
+        child
+    }
+
+    fn register(coordinator: &'static Coordinator, child: &tokio::process::Child) {
+        let group = ChildGroup::of(child).unwrap();
+        coordinator
+            .children
+            .lock()
+            .unwrap()
+            .insert(0, Arc::new(group));
+    }
+
+    #[tokio::test]
+    async fn child_that_exits_on_sigterm_is_not_killed() {
+        let coordinator = coordinator();
+        let mut child = spawn_ready("echo ready; sleep 30").await;
+        register(coordinator, &child);
+
+        coordinator
+            .terminate_children(Duration::from_millis(300))
+            .await;
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGTERM));
+    }
+
+    #[tokio::test]
+    async fn child_trapping_sigterm_is_killed_after_grace_period() {
+        let coordinator = coordinator();
+        let mut child = spawn_ready("trap '' TERM; echo ready; sleep 30").await;
+        register(coordinator, &child);
+
+        let started = std::time::Instant::now();
+        coordinator
+            .terminate_children(Duration::from_millis(300))
+            .await;
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+        assert!(started.elapsed() >= Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn first_interrupt_cancels_the_turn_then_shuts_down() {
+        let coordinator = coordinator();
+        let turn = coordinator.begin_turn();
+        coordinator.on_interrupt();
+        assert!(turn.token.is_cancelled());
+        assert!(!coordinator.shutdown.is_cancelled());
+
+        coordinator.on_interrupt();
+        assert!(coordinator.shutdown.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn terminate_shuts_down_even_mid_turn() {
+        let coordinator = coordinator();
+        let turn = coordinator.begin_turn();
+        coordinator.on_terminate();
+        assert!(coordinator.shutdown.is_cancelled());
+        assert!(turn.token.is_cancelled(), "turns are children of shutdown");
+        assert!(turn.run(std::future::pending::<()>()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn cancelled_turn_is_dropped_after_its_children_stop() {
+        let coordinator = coordinator();
+        let turn = coordinator.begin_turn();
+        let mut child = spawn_ready("echo ready; sleep 30").await;
+        register(coordinator, &child);
+
+        let cancel = turn.token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            cancel.cancel();
+        });
+        assert!(turn.run(std::future::pending::<()>()).await.is_none());
+        assert_eq!(child.wait().await.unwrap().signal(), Some(libc::SIGTERM));
+    }
+
+    #[test]
+    fn registrations_are_removed_on_drop() {
+        let registration = register_temp_path(PathBuf::from("/tmp/zeroclaw-test-path"));
+        let id = registration.id;
+        assert!(super::coordinator()
+            .temp_paths
+            .lock()
+            .unwrap()
+            .contains_key(&id));
+        drop(registration);
+        assert!(!super::coordinator()
+            .temp_paths
+            .lock()
+            .unwrap()
+            .contains_key(&id));
+    }
+}
+
+#[cfg(all(test, windows))]
+mod windows_tests {
+    use super::*;
+
+    fn spawn_tree() -> tokio::process::Child {
+        tokio::process::Command::new("cmd")
+            .args(["/C", "ping -n 30 127.0.0.1 > NUL"])
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn registered_process_tree_is_killed() {
+        let mut child = spawn_tree();
+        let group = Arc::new(ChildGroup::of(&child).unwrap());
+        let coordinator = Coordinator::default();
+        coordinator
+            .children
+            .lock()
+            .unwrap()
+            .insert(0, group.clone());
+        assert!(group.alive());
+
+        coordinator
+            .terminate_children(Duration::from_millis(500))
+            .await;
+
+        let status = tokio::time::timeout(Duration::from_secs(5), child.wait())
+            .await
+            .expect("child should have been killed")
+            .unwrap();
+        assert!(!status.success());
+        assert!(!group.alive());
+    }
+
+    #[tokio::test]
+    async fn dropping_the_group_kills_the_tree() {
+        let mut child = spawn_tree();
+        let group = ChildGroup::of(&child).unwrap();
+        drop(group);
+
+        let status = tokio::time::timeout(Duration::from_secs(5), child.wait())
+            .await
+            .expect("closing the job should have killed the child")
+            .unwrap();
+        assert!(!status.success());
+    }
+}
@@ -0,0 +1,269 @@
+//! `zeroclaw task <name> [args...]`: run a predefined agent workflow.
+//!
+//! A task is a TOML file named `<name>.toml` in `.zeroclaw/tasks/` under the
+//! workspace, or in `~/.zeroclaw/tasks/` for tasks shared by every
+//! workspace; the workspace copy wins. For example:
+//!
+//! ```toml
+//! description = "Summarize a GitHub issue"
+//! prompt_template = "Read issue #{{number}} in {{repo}} and summarize it."
+//! required_args = ["repo", "number"]
+//! tools = ["http_request", "file_write"]
+//! model = "anthropic/claude-sonnet-4"
+//! ```
+//!
+//! Positional arguments fill `required_args` in order and are interpolated
+//! into `prompt_template` as `{{name}}`. The rendered prompt is answered by
+//! the single-shot agent path, restricted to `tools` when the list is set.
+
+use crate::agent::batch::render_template;
+use crate::agent::loop_::SingleShot;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Task directory name, under the workspace's `.zeroclaw/` and under the config directory.
+const TASKS_DIR: &str = "tasks";
+
+/// One `<name>.toml` task file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskDefinition {
+    /// Shown by `zeroclaw task` when no task name is given
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Prompt sent to the agent; `{{arg}}` is replaced by the argument's value
+    pub prompt_template: String,
+    /// Argument names, filled in order from the command line
+    #[serde(default)]
+    pub required_args: Vec<String>,
+    /// Tools the agent may use; empty allows every tool
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Model override; unset uses `default_model`
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl TaskDefinition {
+    /// Interpolate `args` (in `required_args` order) into the prompt template.
+    pub fn render(&self, name: &str, args: &[String]) -> Result<String> {
+        if args.len() != self.required_args.len() {
+            let usage: Vec<String> = self
+                .required_args
+                .iter()
+                .map(|arg| format!("<{arg}>"))
+                .collect();
+            anyhow::bail!(
+                "Task '{name}' takes {} argument(s), got {}\nUsage: zeroclaw task {name} {}",
+                self.required_args.len(),
+                args.len(),
+                usage.join(" ")
+            );
+        }
+        let fields: Map<String, Value> = self
+            .required_args
+            .iter()
+            .cloned()
+            .zip(args.iter().cloned().map(Value::String))
+            .collect();
+        render_template(&self.prompt_template, &fields)
+            .with_context(|| format!("Task '{name}' has an invalid prompt_template"))
+    }
+}
+
+/// Task directories in priority order: the workspace's, then the user's.
+pub fn task_dirs(config: &Config) -> Vec<PathBuf> {
+    let mut dirs = vec![config.workspace_dir.join(".zeroclaw").join(TASKS_DIR)];
+    if let Some(config_dir) = config.config_path.parent() {
+        dirs.push(config_dir.join(TASKS_DIR));
+    }
+    dirs
+}
+
+/// Find and parse `<name>.toml` in the first directory that has it.
+pub fn load_task(dirs: &[PathBuf], name: &str) -> Result<(PathBuf, TaskDefinition)> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!("Invalid task name '{name}': use letters, digits, '-' and '_'");
+    }
+    let path = dirs
+        .iter()
+        .map(|dir| dir.join(format!("{name}.toml")))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            let searched: Vec<String> = dirs.iter().map(|d| d.display().to_string()).collect();
+            anyhow::anyhow!(
+                "Task '{name}' not found (looked in {})",
+                searched.join(", ")
+            )
+        })?;
+    let task = parse_task(&path)?;
+    Ok((path, task))
+}
+
+fn parse_task(path: &Path) -> Result<TaskDefinition> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&raw).with_context(|| format!("Invalid task file {}", path.display()))
+}
+
+/// Every task visible from this workspace, by name; shadowed copies are skipped.
+pub fn list_tasks(dirs: &[PathBuf]) -> Vec<(String, TaskDefinition)> {
+    let mut tasks: Vec<(String, TaskDefinition)> = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        paths.sort();
+        for path in paths {
+            if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if tasks.iter().any(|(existing, _)| existing == name) {
+                continue;
+            }
+            match parse_task(&path) {
+                Ok(task) => tasks.push((name.to_string(), task)),
+                Err(e) => tracing::warn!("Skipping task {}: {e:#}", path.display()),
+            }
+        }
+    }
+    tasks
+}
+
+/// Handle `zeroclaw task`: list tasks without a name, otherwise run one and
+/// print its answer.
+pub async fn run(mut config: Config, name: Option<&str>, args: &[String]) -> Result<()> {
+    let dirs = task_dirs(&config);
+    let Some(name) = name else {
+        let tasks = list_tasks(&dirs);
+        if tasks.is_empty() {
+            println!("No tasks defined. Add one as .zeroclaw/tasks/<name>.toml in the workspace.");
+        }
+        for (name, task) in tasks {
+            let args: Vec<String> = task
+                .required_args
+                .iter()
+                .map(|arg| format!("<{arg}>"))
+                .collect();
+            println!(
+                "  {} {} — {}",
+                console::style(&name).white().bold(),
+                args.join(" "),
+                task.description.as_deref().unwrap_or("")
+            );
+        }
+        return Ok(());
+    };
+
+    let (path, task) = load_task(&dirs, name)?;
+    let prompt = task.render(name, args)?;
+    tracing::info!(task = name, path = %path.display(), "Running task");
+    if let Some(model) = &task.model {
+        config.default_model = Some(model.clone());
+    }
+    let agent = SingleShot::with_tools(config, &task.tools)
+        .await
+        .with_context(|| format!("Task '{name}'"))?;
+    let response = agent.run(&prompt).await?;
+    println!("{response}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_task(dir: &Path, name: &str, body: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join(format!("{name}.toml")), body).unwrap();
+    }
+
+    const ISSUE_TASK: &str = r#"
+description = "Summarize an issue"
+prompt_template = "Summarize issue #{{number}} in {{repo}}."
+required_args = ["repo", "number"]
+tools = ["http_request"]
+model = "fast-model"
+"#;
+
+    #[test]
+    fn render_fills_required_args_in_order() {
+        let task: TaskDefinition = toml::from_str(ISSUE_TASK).unwrap();
+        assert_eq!(task.tools, ["http_request"]);
+        assert_eq!(task.model.as_deref(), Some("fast-model"));
+        let prompt = task
+            .render("issue", &["acme/app".into(), "42".into()])
+            .unwrap();
+        assert_eq!(prompt, "Summarize issue #42 in acme/app.");
+    }
+
+    #[test]
+    fn render_reports_usage_on_wrong_arg_count() {
+        let task: TaskDefinition = toml::from_str(ISSUE_TASK).unwrap();
+        let err = task.render("issue", &["acme/app".into()]).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("takes 2 argument(s), got 1"), "{message}");
+        assert!(message.contains("zeroclaw task issue <repo> <number>"));
+    }
+
+    #[test]
+    fn render_rejects_placeholders_that_are_not_args() {
+        let task: TaskDefinition = toml::from_str("prompt_template = \"Hello {{who}}\"").unwrap();
+        assert!(task.render("greet", &[]).is_err());
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(toml::from_str::<TaskDefinition>(
+            "prompt_template = \"x\"\nrequired_arg = [\"typo\"]"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn workspace_tasks_shadow_user_tasks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().join("workspace/.zeroclaw/tasks");
+        let user = tmp.path().join("home/tasks");
+        write_task(&user, "review", "prompt_template = \"user review\"");
+        write_task(&user, "deploy", "prompt_template = \"user deploy\"");
+        write_task(
+            &workspace,
+            "review",
+            "prompt_template = \"workspace review\"",
+        );
+        let dirs = [workspace, user];
+
+        let (_, task) = load_task(&dirs, "review").unwrap();
+        assert_eq!(task.prompt_template, "workspace review");
+        let (_, task) = load_task(&dirs, "deploy").unwrap();
+        assert_eq!(task.prompt_template, "user deploy");
+
+        let names: Vec<String> = list_tasks(&dirs).into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, ["review", "deploy"]);
+    }
+
+    #[test]
+    fn task_names_cannot_escape_the_tasks_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_task(tmp.path(), "secret", "prompt_template = \"x\"");
+        let dirs = [tmp.path().join("tasks")];
+        let err = load_task(&dirs, "../secret").unwrap_err();
+        assert!(err.to_string().contains("Invalid task name"));
+        assert!(load_task(&dirs, "missing")
+            .unwrap_err()
+            .to_string()
+            .contains("not found"));
+    }
+}
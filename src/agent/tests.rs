@@ -8,12 +8,12 @@
 //!   3. Multi-step tool chain (tool A → tool B → response)
 //!   4. Max-iteration bailout
 //!   5. Unknown tool name recovery
-//!   6. Tool execution failure recovery
+//!   6. Tool execution failure recovery (incl. transient-failure retry)
 //!   7. Parallel tool dispatch
 //!   8. History trimming during long conversations
 //!   9. Memory auto-save round-trip
 //!  10. Native vs XML dispatcher integration
-//!  11. Empty / whitespace-only LLM responses
+//!  11. Empty / whitespace-only LLM responses (nudge, then finish-reason error)
 //!  12. Mixed text + tool call responses
 //!  13. Multi-tool batch in a single response
 //!  14. System prompt generation & tool instructions
@@ -23,16 +23,19 @@
 //!  18. Conversation history fidelity (tool call → tool result → assistant)
 //!  19. Builder validation (missing required fields)
 //!  20. Idempotent system prompt insertion
+//!  21. Structured `Agent::send` responses and events
+//!  22. Custom context builders
 
-use crate::agent::agent::Agent;
-use crate::agent::dispatcher::{
-    NativeToolDispatcher, ToolDispatcher, ToolExecutionResult, XmlToolDispatcher,
-};
+use crate::agent::agent::{Agent, AgentEvent};
+use crate::agent::context_builder::{BuiltContext, ContextBuilder};
+use crate::agent::dispatcher::{NativeToolDispatcher, ToolDispatcher, XmlToolDispatcher};
+use crate::agent::loop_::EMPTY_RESPONSE_NUDGE;
 use crate::config::{AgentConfig, MemoryConfig};
+use crate::error::ZeroClawError;
 use crate::memory::{self, Memory};
 use crate::observability::{NoopObserver, Observer};
 use crate::providers::{
-    ChatMessage, ChatRequest, ChatResponse, ConversationMessage, Provider, ToolCall,
+    ChatMessage, ChatRequest, ChatResponse, ConversationMessage, FinishReason, Provider, ToolCall,
     ToolResultMessage,
 };
 use crate::tools::{Tool, ToolResult};
@@ -93,6 +96,8 @@ impl Provider for ScriptedProvider {
             return Ok(ChatResponse {
                 text: Some("done".into()),
                 tool_calls: vec![],
+                reasoning: None,
+                finish_reason: None,
             });
         }
         Ok(guard.remove(0))
@@ -156,6 +161,7 @@ impl Tool for EchoTool {
             success: true,
             output: msg,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 }
@@ -182,6 +188,7 @@ impl Tool for FailingTool {
             success: false,
             output: String::new(),
             error: Some("intentional failure".into()),
+            artifacts: Vec::new(),
         })
     }
 }
@@ -246,10 +253,70 @@ impl Tool for CountingTool {
             success: true,
             output: format!("call #{}", *c),
             error: None,
+            artifacts: Vec::new(),
         })
     }
 }
 
+/// A tool that fails its first `failures` calls, then succeeds.
+struct FlakyTool {
+    failures: usize,
+    retryable: bool,
+    count: Arc<Mutex<usize>>,
+}
+
+impl FlakyTool {
+    fn new(failures: usize, retryable: bool) -> (Self, Arc<Mutex<usize>>) {
+        let count = Arc::new(Mutex::new(0));
+        (
+            Self {
+                failures,
+                retryable,
+                count: count.clone(),
+            },
+            count,
+        )
+    }
+}
+
+#[async_trait]
+impl Tool for FlakyTool {
+    fn name(&self) -> &str {
+        "flaky"
+    }
+
+    fn description(&self) -> &str {
+        "Fails before succeeding"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({"type": "object"})
+    }
+
+    async fn execute(&self, _args: serde_json::Value) -> Result<ToolResult> {
+        let mut c = self.count.lock().unwrap();
+        *c += 1;
+        if *c <= self.failures {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("connection reset".into()),
+                artifacts: Vec::new(),
+            });
+        }
+        Ok(ToolResult {
+            success: true,
+            output: "flaky ok".into(),
+            error: None,
+            artifacts: Vec::new(),
+        })
+    }
+
+    fn is_retryable(&self, _args: &serde_json::Value, _error: &str) -> bool {
+        self.retryable
+    }
+}
+
 fn make_memory() -> Arc<dyn Memory> {
     let cfg = MemoryConfig {
         backend: "none".into(),
@@ -328,6 +395,8 @@ fn tool_response(calls: Vec<ToolCall>) -> ChatResponse {
     ChatResponse {
         text: Some(String::new()),
         tool_calls: calls,
+        reasoning: None,
+        finish_reason: None,
     }
 }
 
@@ -336,6 +405,8 @@ fn text_response(text: &str) -> ChatResponse {
     ChatResponse {
         text: Some(text.into()),
         tool_calls: vec![],
+        reasoning: None,
+        finish_reason: None,
     }
 }
 
@@ -346,6 +417,8 @@ fn xml_tool_response(name: &str, args: &str) -> ChatResponse {
             "<tool_call>\n{{\"name\": \"{name}\", \"arguments\": {args}}}\n</tool_call>"
         )),
         tool_calls: vec![],
+        reasoning: None,
+        finish_reason: None,
     }
 }
 
@@ -501,12 +574,10 @@ async fn turn_handles_unknown_tool_gracefully() {
     );
 
     // Verify the tool result mentioned "Unknown tool"
-    let has_tool_result = agent.history().iter().any(|msg| match msg {
-        ConversationMessage::ToolResults(results) => {
-            results.iter().any(|r| r.content.contains("Unknown tool"))
-        }
-        _ => false,
-    });
+    let has_tool_result = agent
+        .history()
+        .iter()
+        .any(|msg| msg.role == "tool" && msg.content.contains("Unknown tool"));
     assert!(
         has_tool_result,
         "Expected tool result with 'Unknown tool' message"
@@ -565,6 +636,64 @@ async fn turn_recovers_from_tool_error() {
     );
 }
 
+/// Content of the first native tool result in the agent's history.
+fn first_tool_result(agent: &Agent) -> String {
+    let msg = agent
+        .history()
+        .iter()
+        .find(|msg| msg.role == "tool")
+        .expect("tool result in history");
+    let result: serde_json::Value = serde_json::from_str(&msg.content).unwrap();
+    result["content"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn turn_retries_transient_tool_failure() {
+    let provider = Box::new(ScriptedProvider::new(vec![
+        tool_response(vec![ToolCall {
+            id: "tc1".into(),
+            name: "flaky".into(),
+            arguments: "{}".into(),
+        }]),
+        text_response("Recovered"),
+    ]));
+    let (tool, count) = FlakyTool::new(1, true);
+    let config = AgentConfig {
+        tool_retry_backoff_ms: 1,
+        ..AgentConfig::default()
+    };
+
+    let mut agent = build_agent_with_config(provider, vec![Box::new(tool)], config);
+    let response = agent.turn("call flaky").await.unwrap();
+
+    assert_eq!(response, "Recovered");
+    assert_eq!(*count.lock().unwrap(), 2);
+    assert_eq!(first_tool_result(&agent), "flaky ok");
+}
+
+#[tokio::test]
+async fn turn_does_not_retry_non_retryable_failure() {
+    let provider = Box::new(ScriptedProvider::new(vec![
+        tool_response(vec![ToolCall {
+            id: "tc1".into(),
+            name: "flaky".into(),
+            arguments: "{}".into(),
+        }]),
+        text_response("Gave up"),
+    ]));
+    let (tool, count) = FlakyTool::new(1, false);
+    let config = AgentConfig {
+        tool_retry_backoff_ms: 1,
+        ..AgentConfig::default()
+    };
+
+    let mut agent = build_agent_with_config(provider, vec![Box::new(tool)], config);
+    agent.turn("call flaky").await.unwrap();
+
+    assert_eq!(*count.lock().unwrap(), 1);
+    assert!(first_tool_result(&agent).contains("connection reset"));
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // 7. Provider error propagation
 // ═══════════════════════════════════════════════════════════════════════════
@@ -578,7 +707,10 @@ async fn turn_propagates_provider_error() {
     );
 
     let result = agent.turn("hello").await;
-    assert!(result.is_err(), "Expected provider error to propagate");
+    assert!(
+        matches!(result, Err(ZeroClawError::ProviderError(_))),
+        "Expected provider error to propagate"
+    );
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -615,8 +747,7 @@ async fn history_trims_after_max_messages() {
     );
 
     // System prompt should always be preserved
-    let first = &agent.history()[0];
-    assert!(matches!(first, ConversationMessage::Chat(c) if c.role == "system"));
+    assert_eq!(agent.history()[0].role, "system");
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -665,6 +796,30 @@ async fn auto_save_disabled_does_not_store() {
     assert_eq!(count, 0, "Expected 0 memory entries with auto_save off");
 }
 
+#[tokio::test]
+async fn auto_save_files_stated_preferences_when_inference_enabled() {
+    let (mem, _tmp) = make_sqlite_memory();
+    let provider = Box::new(ScriptedProvider::new(vec![text_response("Noted")]));
+    let mut agent = Agent::builder()
+        .provider(provider)
+        .tools(vec![])
+        .memory(mem.clone())
+        .observer(make_observer())
+        .tool_dispatcher(Box::new(NativeToolDispatcher))
+        .workspace_dir(std::env::temp_dir())
+        .auto_save(true)
+        .infer_memory_categories(true)
+        .build()
+        .unwrap();
+
+    let _ = agent.turn("My preference is short answers").await.unwrap();
+
+    let preference = memory::MemoryCategory::Custom("preference".into());
+    let entries = mem.list(Some(&preference), None).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].content, "My preference is short answers");
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // 10. Native vs XML dispatcher integration
 // ═══════════════════════════════════════════════════════════════════════════
@@ -715,31 +870,101 @@ async fn xml_dispatcher_does_not_send_tool_specs() {
 // 11. Empty / whitespace-only LLM responses
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// Helper: an empty completion with the given finish reason.
+fn empty_response(finish_reason: Option<FinishReason>) -> ChatResponse {
+    ChatResponse {
+        text: Some("  \n".into()),
+        tool_calls: vec![],
+        reasoning: None,
+        finish_reason,
+    }
+}
+
+fn nudge_count(agent: &Agent) -> usize {
+    agent
+        .history()
+        .iter()
+        .filter(|m| m.role == "user" && m.content == EMPTY_RESPONSE_NUDGE)
+        .count()
+}
+
 #[tokio::test]
-async fn turn_handles_empty_text_response() {
+async fn turn_retries_empty_text_response_with_nudge() {
     let provider = Box::new(ScriptedProvider::new(vec![ChatResponse {
         text: Some(String::new()),
         tool_calls: vec![],
+        reasoning: None,
+        finish_reason: None,
     }]));
 
     let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
 
+    // The scripted queue is exhausted after the empty reply, so the retry gets "done"
     let response = agent.turn("hi").await.unwrap();
-    assert!(response.is_empty());
+    assert_eq!(response, "done");
+    assert_eq!(nudge_count(&agent), 1);
 }
 
 #[tokio::test]
-async fn turn_handles_none_text_response() {
+async fn turn_retries_none_text_response_with_nudge() {
     let provider = Box::new(ScriptedProvider::new(vec![ChatResponse {
         text: None,
         tool_calls: vec![],
+        reasoning: None,
+        finish_reason: None,
     }]));
 
     let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
 
-    // Should not panic — falls back to empty string
     let response = agent.turn("hi").await.unwrap();
-    assert!(response.is_empty());
+    assert_eq!(response, "done");
+}
+
+#[tokio::test]
+async fn turn_errors_on_repeated_empty_response_after_truncation() {
+    let provider = Box::new(ScriptedProvider::new(vec![
+        empty_response(Some(FinishReason::Length)),
+        empty_response(Some(FinishReason::Length)),
+    ]));
+
+    let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
+
+    let err = agent.turn("hi").await.unwrap_err().to_string();
+    assert!(err.contains("empty response"), "{err}");
+    assert!(err.contains("finish reason: length"), "{err}");
+    assert!(err.contains("output tokens"), "{err}");
+}
+
+#[tokio::test]
+async fn turn_errors_on_repeated_empty_response_after_content_filter() {
+    let provider = Box::new(ScriptedProvider::new(vec![
+        empty_response(None),
+        empty_response(Some(FinishReason::ContentFilter)),
+    ]));
+
+    let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
+
+    let err = agent.turn("hi").await.unwrap_err().to_string();
+    assert!(err.contains("finish reason: content_filter"), "{err}");
+    assert!(err.contains("content filter"), "{err}");
+}
+
+#[tokio::test]
+async fn empty_response_is_never_saved_to_memory() {
+    let (mem, _tmp) = make_sqlite_memory();
+    let provider = Box::new(ScriptedProvider::new(vec![
+        empty_response(Some(FinishReason::Stop)),
+        empty_response(Some(FinishReason::Stop)),
+    ]));
+
+    let mut agent = build_agent_with_memory(provider, vec![], mem.clone(), true);
+
+    assert!(agent.turn("hi").await.is_err());
+    let saved = mem
+        .list(Some(&crate::memory::MemoryCategory::Daily), None)
+        .await
+        .unwrap();
+    assert!(saved.is_empty(), "empty response was stored: {saved:?}");
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -756,6 +981,8 @@ async fn turn_preserves_text_alongside_tool_calls() {
                 name: "echo".into(),
                 arguments: r#"{"message": "hi"}"#.into(),
             }],
+            reasoning: None,
+            finish_reason: None,
         },
         text_response("Here are the results"),
     ]));
@@ -773,10 +1000,10 @@ async fn turn_preserves_text_alongside_tool_calls() {
     );
 
     // The intermediate text should be in history
-    let has_intermediate = agent.history().iter().any(|msg| match msg {
-        ConversationMessage::Chat(c) => c.role == "assistant" && c.content.contains("Let me check"),
-        _ => false,
-    });
+    let has_intermediate = agent
+        .history()
+        .iter()
+        .any(|msg| msg.role == "assistant" && msg.content.contains("Let me check"));
     assert!(has_intermediate, "Intermediate text should be in history");
 }
 
@@ -845,9 +1072,9 @@ async fn system_prompt_injected_on_first_turn() {
     let _ = agent.turn("hi").await.unwrap();
 
     // First message should be the system prompt
-    let first = &agent.history()[0];
-    assert!(
-        matches!(first, ConversationMessage::Chat(c) if c.role == "system"),
+    assert_eq!(
+        agent.history()[0].role,
+        "system",
         "First history entry should be system prompt"
     );
 }
@@ -870,7 +1097,7 @@ async fn system_prompt_not_duplicated_on_second_turn() {
     let system_count = agent
         .history()
         .iter()
-        .filter(|msg| matches!(msg, ConversationMessage::Chat(c) if c.role == "system"))
+        .filter(|msg| msg.role == "system")
         .count();
     assert_eq!(system_count, 1, "System prompt should appear exactly once");
 }
@@ -901,8 +1128,8 @@ async fn history_contains_all_expected_entries_after_tool_loop() {
     // Expected history entries:
     //   0: system prompt
     //   1: user message "test"
-    //   2: AssistantToolCalls
-    //   3: ToolResults
+    //   2: assistant message carrying the native tool calls
+    //   3: tool result
     //   4: assistant "final answer"
     let history = agent.history();
     assert!(
@@ -911,16 +1138,14 @@ async fn history_contains_all_expected_entries_after_tool_loop() {
         history.len()
     );
 
-    assert!(matches!(&history[0], ConversationMessage::Chat(c) if c.role == "system"));
-    assert!(matches!(&history[1], ConversationMessage::Chat(c) if c.role == "user"));
-    assert!(matches!(
-        &history[2],
-        ConversationMessage::AssistantToolCalls { .. }
-    ));
-    assert!(matches!(&history[3], ConversationMessage::ToolResults(_)));
-    assert!(
-        matches!(&history[4], ConversationMessage::Chat(c) if c.role == "assistant" && c.content == "final answer")
-    );
+    assert_eq!(history[0].role, "system");
+    assert_eq!(history[1].role, "user");
+    assert_eq!(history[2].role, "assistant");
+    assert!(history[2].content.contains("\"tool_calls\""));
+    assert_eq!(history[3].role, "tool");
+    assert!(history[3].content.contains("tc1"));
+    assert_eq!(history[4].role, "assistant");
+    assert_eq!(history[4].content, "final answer");
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -937,7 +1162,28 @@ async fn builder_fails_without_provider() {
         .workspace_dir(std::path::PathBuf::from("/tmp"))
         .build();
 
-    assert!(result.is_err(), "Building without provider should fail");
+    assert!(
+        matches!(result, Err(ZeroClawError::ConfigError(_))),
+        "Building without provider should fail"
+    );
+}
+
+#[tokio::test]
+async fn builder_rejects_duplicate_tool_names() {
+    let result = Agent::builder()
+        .provider(Box::new(ScriptedProvider::new(vec![])))
+        .tools(vec![Box::new(EchoTool), Box::new(EchoTool)])
+        .memory(make_memory())
+        .observer(make_observer())
+        .tool_dispatcher(Box::new(NativeToolDispatcher))
+        .workspace_dir(std::env::temp_dir())
+        .build();
+
+    match result {
+        Err(ZeroClawError::ConfigError(e)) => assert!(e.to_string().contains("'echo'")),
+        Err(e) => panic!("expected a config error, got {e}"),
+        Ok(_) => panic!("duplicate tool names should fail"),
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -979,85 +1225,7 @@ async fn multi_turn_maintains_growing_history() {
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
-// 18. Tool call with stringified JSON arguments (common LLM pattern)
-// ═══════════════════════════════════════════════════════════════════════════
-
-#[tokio::test]
-async fn native_dispatcher_handles_stringified_arguments() {
-    let dispatcher = NativeToolDispatcher;
-    let response = ChatResponse {
-        text: Some(String::new()),
-        tool_calls: vec![ToolCall {
-            id: "tc1".into(),
-            name: "echo".into(),
-            arguments: r#"{"message": "hello"}"#.into(),
-        }],
-    };
-
-    let (_, calls) = dispatcher.parse_response(&response);
-    assert_eq!(calls.len(), 1);
-    assert_eq!(calls[0].name, "echo");
-    assert_eq!(
-        calls[0].arguments.get("message").unwrap().as_str().unwrap(),
-        "hello"
-    );
-}
-
-// ═══════════════════════════════════════════════════════════════════════════
-// 19. XML dispatcher edge cases
-// ═══════════════════════════════════════════════════════════════════════════
-
-#[test]
-fn xml_dispatcher_handles_nested_json() {
-    let response = ChatResponse {
-        text: Some(
-            r#"<tool_call>
-{"name": "file_write", "arguments": {"path": "test.json", "content": "{\"key\": \"value\"}"}}
-</tool_call>"#
-                .into(),
-        ),
-        tool_calls: vec![],
-    };
-
-    let dispatcher = XmlToolDispatcher;
-    let (_, calls) = dispatcher.parse_response(&response);
-    assert_eq!(calls.len(), 1);
-    assert_eq!(calls[0].name, "file_write");
-    assert_eq!(
-        calls[0].arguments.get("path").unwrap().as_str().unwrap(),
-        "test.json"
-    );
-}
-
-#[test]
-fn xml_dispatcher_handles_empty_tool_call_tag() {
-    let response = ChatResponse {
-        text: Some("<tool_call>\n</tool_call>\nSome text".into()),
-        tool_calls: vec![],
-    };
-
-    let dispatcher = XmlToolDispatcher;
-    let (text, calls) = dispatcher.parse_response(&response);
-    assert!(calls.is_empty());
-    assert!(text.contains("Some text"));
-}
-
-#[test]
-fn xml_dispatcher_handles_unclosed_tool_call() {
-    let response = ChatResponse {
-        text: Some("Before\n<tool_call>\n{\"name\": \"shell\"}".into()),
-        tool_calls: vec![],
-    };
-
-    let dispatcher = XmlToolDispatcher;
-    let (text, calls) = dispatcher.parse_response(&response);
-    // Should not panic — just treat as text
-    assert!(calls.is_empty());
-    assert!(text.contains("Before"));
-}
-
-// ═══════════════════════════════════════════════════════════════════════════
-// 20. ConversationMessage serialization round-trip
+// 18. ConversationMessage serialization round-trip
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[test]
@@ -1112,126 +1280,7 @@ fn conversation_message_serialization_roundtrip() {
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
-// 21. Tool dispatcher format_results
-// ═══════════════════════════════════════════════════════════════════════════
-
-#[test]
-fn xml_format_results_includes_status_and_output() {
-    let dispatcher = XmlToolDispatcher;
-    let results = vec![
-        ToolExecutionResult {
-            name: "shell".into(),
-            output: "file1.txt\nfile2.txt".into(),
-            success: true,
-            tool_call_id: None,
-        },
-        ToolExecutionResult {
-            name: "file_read".into(),
-            output: "Error: file not found".into(),
-            success: false,
-            tool_call_id: None,
-        },
-    ];
-
-    let msg = dispatcher.format_results(&results);
-    let content = match msg {
-        ConversationMessage::Chat(c) => c.content,
-        _ => panic!("Expected Chat variant"),
-    };
-
-    assert!(content.contains("shell"));
-    assert!(content.contains("file1.txt"));
-    assert!(content.contains("ok"));
-    assert!(content.contains("file_read"));
-    assert!(content.contains("error"));
-}
-
-#[test]
-fn native_format_results_maps_tool_call_ids() {
-    let dispatcher = NativeToolDispatcher;
-    let results = vec![
-        ToolExecutionResult {
-            name: "a".into(),
-            output: "out1".into(),
-            success: true,
-            tool_call_id: Some("tc-001".into()),
-        },
-        ToolExecutionResult {
-            name: "b".into(),
-            output: "out2".into(),
-            success: true,
-            tool_call_id: Some("tc-002".into()),
-        },
-    ];
-
-    let msg = dispatcher.format_results(&results);
-    match msg {
-        ConversationMessage::ToolResults(r) => {
-            assert_eq!(r.len(), 2);
-            assert_eq!(r[0].tool_call_id, "tc-001");
-            assert_eq!(r[0].content, "out1");
-            assert_eq!(r[1].tool_call_id, "tc-002");
-            assert_eq!(r[1].content, "out2");
-        }
-        _ => panic!("Expected ToolResults"),
-    }
-}
-
-// ═══════════════════════════════════════════════════════════════════════════
-// 22. to_provider_messages conversion
-// ═══════════════════════════════════════════════════════════════════════════
-
-#[test]
-fn xml_dispatcher_converts_history_to_provider_messages() {
-    let dispatcher = XmlToolDispatcher;
-    let history = vec![
-        ConversationMessage::Chat(ChatMessage::system("sys")),
-        ConversationMessage::Chat(ChatMessage::user("hi")),
-        ConversationMessage::AssistantToolCalls {
-            text: Some("checking".into()),
-            tool_calls: vec![ToolCall {
-                id: "tc1".into(),
-                name: "shell".into(),
-                arguments: "{}".into(),
-            }],
-        },
-        ConversationMessage::ToolResults(vec![ToolResultMessage {
-            tool_call_id: "tc1".into(),
-            content: "ok".into(),
-        }]),
-        ConversationMessage::Chat(ChatMessage::assistant("done")),
-    ];
-
-    let messages = dispatcher.to_provider_messages(&history);
-
-    // Should have: system, user, assistant (from tool calls), user (tool results), assistant
-    assert!(messages.len() >= 4);
-    assert_eq!(messages[0].role, "system");
-    assert_eq!(messages[1].role, "user");
-}
-
-#[test]
-fn native_dispatcher_converts_tool_results_to_tool_messages() {
-    let dispatcher = NativeToolDispatcher;
-    let history = vec![ConversationMessage::ToolResults(vec![
-        ToolResultMessage {
-            tool_call_id: "tc1".into(),
-            content: "output1".into(),
-        },
-        ToolResultMessage {
-            tool_call_id: "tc2".into(),
-            content: "output2".into(),
-        },
-    ])];
-
-    let messages = dispatcher.to_provider_messages(&history);
-    assert_eq!(messages.len(), 2);
-    assert_eq!(messages[0].role, "tool");
-    assert_eq!(messages[1].role, "tool");
-}
-
-// ═══════════════════════════════════════════════════════════════════════════
-// 23. XML tool instructions generation
+// 19. XML tool instructions generation
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[test]
@@ -1255,7 +1304,7 @@ fn native_dispatcher_returns_empty_instructions() {
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
-// 24. Clear history
+// 20. Clear history
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
@@ -1275,14 +1324,11 @@ async fn clear_history_resets_conversation() {
 
     // Next turn should re-inject system prompt
     let _ = agent.turn("hello again").await.unwrap();
-    assert!(matches!(
-        &agent.history()[0],
-        ConversationMessage::Chat(c) if c.role == "system"
-    ));
+    assert_eq!(agent.history()[0].role, "system");
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
-// 25. run_single delegates to turn
+// 21. run_single delegates to turn
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
@@ -1296,3 +1342,112 @@ async fn run_single_delegates_to_turn() {
         "Expected non-empty response from run_single"
     );
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 22. send returns structured responses and reports events
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn send_returns_tool_records_usage_and_emits_events() {
+    let provider = Box::new(ScriptedProvider::new(vec![
+        ChatResponse {
+            text: Some("Let me check...".into()),
+            tool_calls: vec![ToolCall {
+                id: "tc1".into(),
+                name: "echo".into(),
+                arguments: r#"{"message": "hi"}"#.into(),
+            }],
+            reasoning: Some("echo should do it".into()),
+            finish_reason: None,
+        },
+        text_response("Here are the results"),
+    ]));
+    let events: Arc<Mutex<Vec<AgentEvent>>> = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let mut agent = Agent::builder()
+        .provider(provider)
+        .tools(vec![Box::new(EchoTool)])
+        .memory(make_memory())
+        .observer(make_observer())
+        .tool_dispatcher(Box::new(NativeToolDispatcher))
+        .workspace_dir(std::env::temp_dir())
+        .on_event(Arc::new(move |event| {
+            sink.lock().unwrap().push(event.clone())
+        }))
+        .build()
+        .unwrap();
+
+    let response = agent.send("check").await.unwrap();
+
+    assert_eq!(response.text, "Here are the results");
+    assert_eq!(response.tool_calls.len(), 1);
+    assert_eq!(response.tool_calls[0].name, "echo");
+    assert_eq!(response.tool_calls[0].arguments["message"], "hi");
+    assert!(response.tool_calls[0].success);
+    assert_eq!(response.usage.provider_calls, 2);
+    assert!(response.usage.input_tokens > 0);
+    assert!(response.usage.output_tokens > 0);
+
+    let events = events.lock().unwrap();
+    assert_eq!(events[0], AgentEvent::Thinking("echo should do it".into()));
+    assert_eq!(events[1], AgentEvent::Text("Let me check...".into()));
+    assert_eq!(
+        events[2],
+        AgentEvent::ToolCall(response.tool_calls[0].clone())
+    );
+    assert_eq!(events.len(), 3);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 23. Custom context builders replace memory recall
+// ═══════════════════════════════════════════════════════════════════════════
+
+struct FixedContextBuilder {
+    queries: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl ContextBuilder for FixedContextBuilder {
+    async fn build(&self, user_msg: &str, _mem: &dyn Memory) -> BuiltContext {
+        self.queries.lock().unwrap().push(user_msg.to_string());
+        BuiltContext::new("[Docs]\n- deploys run on Fridays\n\n")
+    }
+}
+
+#[tokio::test]
+async fn custom_context_builder_enriches_the_user_message() {
+    let provider = Box::new(ScriptedProvider::new(vec![text_response("Fridays")]));
+    let queries = Arc::new(Mutex::new(Vec::new()));
+    let mut agent = Agent::builder()
+        .provider(provider)
+        .tools(vec![Box::new(EchoTool)])
+        .memory(make_memory())
+        .observer(make_observer())
+        .tool_dispatcher(Box::new(NativeToolDispatcher))
+        .workspace_dir(std::env::temp_dir())
+        .context_builder(Box::new(FixedContextBuilder {
+            queries: Arc::clone(&queries),
+        }))
+        .build()
+        .unwrap();
+
+    agent.turn("when do deploys run?").await.unwrap();
+
+    let user = agent
+        .history()
+        .iter()
+        .find(|msg| msg.role == "user")
+        .map(|msg| msg.content.clone())
+        .unwrap();
+    assert_eq!(
+        user,
+        "[Docs]\n- deploys run on Fridays\n\nwhen do deploys run?"
+    );
+    assert_eq!(*queries.lock().unwrap(), ["when do deploys run?"]);
+}
+
+#[test]
+fn empty_built_context_leaves_the_message_unchanged() {
+    assert_eq!(BuiltContext::default().enrich("hi"), "hi");
+    assert_eq!(BuiltContext::new("ctx\n").enrich("hi"), "ctx\nhi");
+}
@@ -0,0 +1,272 @@
+//! End-of-turn summary: which tools ran, what changed in the workspace and how
+//! many provider round-trips the turn took.
+
+use crate::agent::plan::Plan;
+use crate::checkpoints::{FileChange, FileChangeKind};
+use crate::observability::ObserverEvent;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Shell commands longer than this are truncated in the rendered summary.
+const MAX_SHELL_COMMAND_DISPLAY_CHARS: usize = 80;
+
+/// Per-tool invocation counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ToolUsage {
+    pub calls: usize,
+    pub failures: usize,
+}
+
+/// The prose the model wrote alongside one round of tool calls.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolRationale {
+    pub tools: Vec<String>,
+    pub rationale: String,
+}
+
+/// What happened during a single agent turn.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TurnSummary {
+    /// Tool name → invocation counts.
+    pub tools: BTreeMap<String, ToolUsage>,
+    /// Commands passed to the `shell` tool, in order.
+    pub shell_commands: Vec<String>,
+    /// Files changed during the turn (requires git checkpoints).
+    pub file_changes: Vec<FileChange>,
+    /// Number of provider calls made.
+    pub provider_round_trips: usize,
+    /// Tools whose calls were refused by security policy or approval, in order.
+    pub policy_denials: Vec<String>,
+    /// Plan the turn executed, when `[agent].plan_first` is enabled.
+    pub plan: Option<Plan>,
+    /// Prose the model wrote alongside tool calls, in order.
+    pub assistant_updates: Vec<String>,
+    /// The same prose paired with the tools it preceded (`--explain`).
+    pub tool_rationales: Vec<ToolRationale>,
+}
+
+impl TurnSummary {
+    pub fn record_round_trip(&mut self) {
+        self.provider_round_trips += 1;
+    }
+
+    pub fn record_update(&mut self, text: &str) {
+        self.assistant_updates.push(text.to_string());
+    }
+
+    pub fn record_rationale(&mut self, tools: Vec<String>, rationale: &str) {
+        self.tool_rationales.push(ToolRationale {
+            tools,
+            rationale: rationale.to_string(),
+        });
+    }
+
+    pub fn record_tool(&mut self, name: &str, arguments: &serde_json::Value, success: bool) {
+        let usage = self.tools.entry(name.to_string()).or_default();
+        usage.calls += 1;
+        if !success {
+            usage.failures += 1;
+        }
+
+        if name == "shell" {
+            if let Some(command) = arguments.get("command").and_then(|c| c.as_str()) {
+                self.shell_commands.push(command.to_string());
+            }
+        }
+    }
+
+    pub fn record_policy_denial(&mut self, name: &str) {
+        self.policy_denials.push(name.to_string());
+    }
+
+    pub fn tool_calls(&self) -> usize {
+        self.tools.values().map(|u| u.calls).sum()
+    }
+
+    pub fn tool_failures(&self) -> usize {
+        self.tools.values().map(|u| u.failures).sum()
+    }
+
+    /// Whether the turn used any tools; pure chat turns print no summary.
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty() && self.file_changes.is_empty()
+    }
+
+    pub fn to_observer_event(&self) -> ObserverEvent {
+        ObserverEvent::TurnSummary {
+            tool_calls: self.tool_calls(),
+            tool_failures: self.tool_failures(),
+            files_changed: self.file_changes.len(),
+            lines_added: self.file_changes.iter().map(|c| c.lines_added).sum(),
+            lines_removed: self.file_changes.iter().map(|c| c.lines_removed).sum(),
+            shell_commands: self.shell_commands.len(),
+            provider_round_trips: self.provider_round_trips,
+        }
+    }
+
+    /// Compact, human-readable block for the CLI.
+    pub fn render(&self) -> String {
+        let mut out = String::from("── Turn summary ──\n");
+
+        let tools = self
+            .tools
+            .iter()
+            .map(|(name, usage)| {
+                if usage.failures > 0 {
+                    format!("{name} ×{} ({} failed)", usage.calls, usage.failures)
+                } else {
+                    format!("{name} ×{}", usage.calls)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = writeln!(
+            out,
+            "Tools: {}",
+            if tools.is_empty() { "none" } else { &tools }
+        );
+
+        if !self.file_changes.is_empty() {
+            out.push_str("Files:\n");
+            for change in &self.file_changes {
+                let marker = match change.kind {
+                    FileChangeKind::Created => 'A',
+                    FileChangeKind::Modified => 'M',
+                    FileChangeKind::Deleted => 'D',
+                };
+                let _ = writeln!(
+                    out,
+                    "  {marker} {} (+{} -{})",
+                    change.path, change.lines_added, change.lines_removed
+                );
+            }
+        }
+
+        if !self.shell_commands.is_empty() {
+            out.push_str("Shell:\n");
+            for command in &self.shell_commands {
+                let _ = writeln!(
+                    out,
+                    "  $ {}",
+                    crate::util::truncate_with_ellipsis(command, MAX_SHELL_COMMAND_DISPLAY_CHARS)
+                );
+            }
+        }
+
+        if !self.policy_denials.is_empty() {
+            let _ = writeln!(out, "Denied: {}", self.policy_denials.join(", "));
+        }
+
+        if let Some(plan) = &self.plan {
+            let _ = writeln!(
+                out,
+                "Plan: {}/{} steps done",
+                plan.completed(),
+                plan.steps.len()
+            );
+        }
+
+        let _ = write!(out, "Provider round-trips: {}", self.provider_round_trips);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn records_tool_counts_and_shell_commands() {
+        let mut summary = TurnSummary::default();
+        summary.record_round_trip();
+        summary.record_tool("shell", &json!({"command": "cargo test"}), true);
+        summary.record_tool("shell", &json!({"command": "false"}), false);
+        summary.record_tool("file_read", &json!({"path": "a.txt"}), true);
+        summary.record_round_trip();
+
+        assert_eq!(
+            summary.tools["shell"],
+            ToolUsage {
+                calls: 2,
+                failures: 1
+            }
+        );
+        assert_eq!(summary.tool_calls(), 3);
+        assert_eq!(summary.tool_failures(), 1);
+        assert_eq!(summary.shell_commands, vec!["cargo test", "false"]);
+        assert_eq!(summary.provider_round_trips, 2);
+    }
+
+    #[test]
+    fn render_lists_tools_files_and_commands() {
+        let mut summary = TurnSummary::default();
+        summary.record_round_trip();
+        summary.record_tool("file_write", &json!({}), true);
+        summary.record_tool("shell", &json!({"command": "ls"}), false);
+        summary.file_changes.push(FileChange {
+            path: "src/main.rs".into(),
+            kind: FileChangeKind::Modified,
+            lines_added: 3,
+            lines_removed: 1,
+        });
+
+        let rendered = summary.render();
+        assert!(rendered.contains("Tools: file_write ×1, shell ×1 (1 failed)"));
+        assert!(rendered.contains("  M src/main.rs (+3 -1)"));
+        assert!(rendered.contains("  $ ls"));
+        assert!(rendered.ends_with("Provider round-trips: 1"));
+    }
+
+    #[test]
+    fn observer_event_carries_totals() {
+        let mut summary = TurnSummary::default();
+        summary.record_round_trip();
+        summary.record_tool("shell", &json!({"command": "ls"}), true);
+        summary.file_changes.push(FileChange {
+            path: "a.txt".into(),
+            kind: FileChangeKind::Created,
+            lines_added: 2,
+            lines_removed: 0,
+        });
+
+        match summary.to_observer_event() {
+            ObserverEvent::TurnSummary {
+                tool_calls,
+                tool_failures,
+                files_changed,
+                lines_added,
+                lines_removed,
+                shell_commands,
+                provider_round_trips,
+            } => {
+                assert_eq!(tool_calls, 1);
+                assert_eq!(tool_failures, 0);
+                assert_eq!(files_changed, 1);
+                assert_eq!(lines_added, 2);
+                assert_eq!(lines_removed, 0);
+                assert_eq!(shell_commands, 1);
+                assert_eq!(provider_round_trips, 1);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn render_lists_policy_denials() {
+        let mut summary = TurnSummary::default();
+        summary.record_tool("shell", &json!({"command": "rm -rf /"}), false);
+        summary.record_policy_denial("shell");
+
+        assert_eq!(summary.policy_denials, vec!["shell"]);
+        assert!(summary.render().contains("Denied: shell"));
+    }
+
+    #[test]
+    fn chat_only_turn_is_empty() {
+        let mut summary = TurnSummary::default();
+        summary.record_round_trip();
+        assert!(summary.is_empty());
+    }
+}
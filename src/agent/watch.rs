@@ -0,0 +1,204 @@
+//! `zeroclaw agent --message ... --watch`: re-run the same message whenever a
+//! file in the workspace changes.
+//!
+//! Changes are debounced so a burst of saves triggers one run. Files the run
+//! itself writes (and anything under hidden directories or `target/`) never
+//! trigger another run.
+//!
+//! File watching needs the `watch` feature; other builds reject `--watch`.
+
+#[cfg(feature = "watch")]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(feature = "watch")]
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Quiet period after the last change before a run starts.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Directories whose changes never trigger a run, besides hidden ones.
+const IGNORED_DIRS: &[&str] = &["target", "node_modules"];
+
+/// Whether a change to `path` should trigger a run.
+fn is_relevant(path: &Path, workspace_dir: &Path) -> bool {
+    let relative = path.strip_prefix(workspace_dir).unwrap_or(path);
+    relative.components().all(|component| match component {
+        Component::Normal(name) => {
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && !IGNORED_DIRS.contains(&name.as_ref())
+        }
+        _ => true,
+    })
+}
+
+/// Wait for the next relevant change, then for `debounce` without further
+/// changes. Returns the changed paths, or `None` once the watcher is gone.
+async fn next_change(
+    events: &mut mpsc::UnboundedReceiver<PathBuf>,
+    workspace_dir: &Path,
+    debounce: Duration,
+) -> Option<Vec<PathBuf>> {
+    let mut changed = Vec::new();
+    while changed.is_empty() {
+        let path = events.recv().await?;
+        if is_relevant(&path, workspace_dir) {
+            changed.push(path);
+        }
+    }
+    loop {
+        match tokio::time::timeout(debounce, events.recv()).await {
+            Ok(Some(path)) => {
+                if is_relevant(&path, workspace_dir) && !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+            Ok(None) | Err(_) => return Some(changed),
+        }
+    }
+}
+
+/// Drop changes queued while a run was in progress; they are mostly the
+/// run's own writes.
+fn discard_pending(events: &mut mpsc::UnboundedReceiver<PathBuf>) {
+    while events.try_recv().is_ok() {}
+}
+
+/// Without the `watch` feature there is no file watcher.
+#[cfg(not(feature = "watch"))]
+#[allow(clippy::unused_async)]
+pub async fn run<F, Fut>(_workspace_dir: &Path, _run_once: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    anyhow::bail!(
+        "--watch is not available in this build; rebuild zeroclaw with `--features watch`"
+    )
+}
+
+/// Run `run_once` now and again after every workspace change until Ctrl-C.
+/// A failed run is reported and the watch continues.
+#[cfg(feature = "watch")]
+pub async fn run<F, Fut>(workspace_dir: &Path, mut run_once: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    // Event paths are canonical, so compare against the canonical workspace.
+    let workspace_dir = &workspace_dir
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_dir.to_path_buf());
+    let (tx, mut events) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths {
+            let _ = tx.send(path);
+        }
+    })
+    .context("Failed to start the file watcher")?;
+    watcher
+        .watch(workspace_dir, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", workspace_dir.display()))?;
+
+    let mut run_number = 1_u32;
+    loop {
+        eprintln!(
+            "{}",
+            console::style(format!("── watch: run {run_number} ──")).dim()
+        );
+        if let Err(e) = run_once().await {
+            eprintln!("Error: {e:#}");
+        }
+        // Let the run's own late write events arrive, then drop them.
+        tokio::time::sleep(DEBOUNCE).await;
+        discard_pending(&mut events);
+        eprintln!(
+            "{}",
+            console::style(format!(
+                "── watching {} for changes (Ctrl-C to stop) ──",
+                workspace_dir.display()
+            ))
+            .dim()
+        );
+
+        tokio::select! {
+            changed = next_change(&mut events, workspace_dir, DEBOUNCE) => {
+                let Some(changed) = changed else {
+                    anyhow::bail!("File watcher stopped unexpectedly");
+                };
+                let first = changed[0].strip_prefix(workspace_dir).unwrap_or(&changed[0]);
+                let more = match changed.len() {
+                    1 => String::new(),
+                    n => format!(" and {} more", n - 1),
+                };
+                eprintln!("\nChanged: {}{more}", first.display());
+            }
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!();
+                return Ok(());
+            }
+        }
+        run_number += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hidden_and_build_dirs_are_ignored() {
+        let ws = Path::new("/ws");
+        assert!(is_relevant(Path::new("/ws/src/main.rs"), ws));
+        assert!(is_relevant(Path::new("/ws/README.md"), ws));
+        assert!(!is_relevant(Path::new("/ws/.git/index"), ws));
+        assert!(!is_relevant(Path::new("/ws/.zeroclaw-tmp/run-1/x"), ws));
+        assert!(!is_relevant(Path::new("/ws/target/debug/app"), ws));
+        assert!(!is_relevant(Path::new("/ws/src/.main.rs.swp"), ws));
+    }
+
+    #[tokio::test]
+    async fn burst_of_changes_is_debounced_into_one_trigger() {
+        let ws = Path::new("/ws");
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tx.send(PathBuf::from("/ws/.git/index")).unwrap();
+        tx.send(PathBuf::from("/ws/src/a.rs")).unwrap();
+        tx.send(PathBuf::from("/ws/src/a.rs")).unwrap();
+        tx.send(PathBuf::from("/ws/src/b.rs")).unwrap();
+
+        let changed = next_change(&mut rx, ws, Duration::from_millis(20))
+            .await
+            .unwrap();
+        assert_eq!(
+            changed,
+            [PathBuf::from("/ws/src/a.rs"), PathBuf::from("/ws/src/b.rs")]
+        );
+
+        drop(tx);
+        assert_eq!(
+            next_change(&mut rx, ws, Duration::from_millis(20)).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn changes_during_a_run_are_discarded() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tx.send(PathBuf::from("/ws/notes.md")).unwrap();
+        discard_pending(&mut rx);
+        tx.send(PathBuf::from("/ws/src/a.rs")).unwrap();
+        let changed = next_change(&mut rx, Path::new("/ws"), Duration::from_millis(20))
+            .await
+            .unwrap();
+        assert_eq!(changed, [PathBuf::from("/ws/src/a.rs")]);
+    }
+}
@@ -4,12 +4,14 @@
 //! with session-scoped "Always" allowlists and audit logging.
 
 use crate::config::AutonomyConfig;
-use crate::security::AutonomyLevel;
+use crate::i18n;
+use crate::security::{AutonomyLevel, ConfirmationMode, SecurityPolicy};
 use chrono::Utc;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{self, BufRead, Write};
+use std::sync::Arc;
 
 // ── Types ────────────────────────────────────────────────────────
 
@@ -46,7 +48,8 @@ pub struct ApprovalLogEntry {
 
 /// Manages the interactive approval workflow.
 ///
-/// - Checks config-level `auto_approve` / `always_ask` lists
+/// - Checks per-tool modes set with `/approval`, then config-level
+///   `auto_approve` / `always_ask` lists
 /// - Maintains a session-scoped "always" allowlist
 /// - Records an audit trail of all decisions
 pub struct ApprovalManager {
@@ -56,6 +59,8 @@ pub struct ApprovalManager {
     always_ask: HashSet<String>,
     /// Autonomy level from config.
     autonomy_level: AutonomyLevel,
+    /// Per-tool modes, shared with the session's [`SecurityPolicy`].
+    tool_modes: Arc<RwLock<HashMap<String, ConfirmationMode>>>,
     /// Session-scoped allowlist built from "Always" responses.
     session_allowlist: Mutex<HashSet<String>>,
     /// Audit trail of approval decisions.
@@ -69,22 +74,35 @@ impl ApprovalManager {
             auto_approve: config.auto_approve.iter().cloned().collect(),
             always_ask: config.always_ask.iter().cloned().collect(),
             autonomy_level: config.level,
+            tool_modes: Arc::default(),
             session_allowlist: Mutex::new(HashSet::new()),
             audit_log: Mutex::new(Vec::new()),
         }
     }
 
+    /// Follow the per-tool modes of `policy`, including ones set later with
+    /// [`SecurityPolicy::set_approval_mode`].
+    pub fn with_policy(mut self, policy: &SecurityPolicy) -> Self {
+        self.tool_modes = Arc::clone(&policy.require_tool_approval);
+        self
+    }
+
     /// Check whether a tool call requires interactive approval.
     ///
     /// Returns `true` if the call needs a prompt, `false` if it can proceed.
     pub fn needs_approval(&self, tool_name: &str) -> bool {
-        // Full autonomy never prompts.
-        if self.autonomy_level == AutonomyLevel::Full {
+        // ReadOnly blocks everything — handled elsewhere; no prompt needed.
+        if self.autonomy_level == AutonomyLevel::ReadOnly {
             return false;
         }
 
-        // ReadOnly blocks everything — handled elsewhere; no prompt needed.
-        if self.autonomy_level == AutonomyLevel::ReadOnly {
+        // A per-tool mode overrides the autonomy level and config lists.
+        if let Some(mode) = self.tool_modes.read().get(tool_name).copied() {
+            return mode.requires_confirmation(tool_name);
+        }
+
+        // Full autonomy never prompts.
+        if self.autonomy_level == AutonomyLevel::Full {
             return false;
         }
 
@@ -160,9 +178,10 @@ impl ApprovalManager {
 fn prompt_cli_interactive(request: &ApprovalRequest) -> ApprovalResponse {
     let summary = summarize_args(&request.arguments);
     eprintln!();
-    eprintln!("🔧 Agent wants to execute: {}", request.tool_name);
+    let tool: &dyn std::fmt::Display = &request.tool_name;
+    eprintln!("{}", i18n::tf("approval.request", &[("tool", tool)]));
     eprintln!("   {summary}");
-    eprint!("   [Y]es / [N]o / [A]lways for {}: ", request.tool_name);
+    eprint!("{}", i18n::tf("approval.prompt", &[("tool", tool)]));
     let _ = io::stderr().flush();
 
     let stdin = io::stdin();
@@ -171,10 +190,12 @@ fn prompt_cli_interactive(request: &ApprovalRequest) -> ApprovalResponse {
         return ApprovalResponse::No;
     }
 
-    match line.trim().to_ascii_lowercase().as_str() {
-        "y" | "yes" => ApprovalResponse::Yes,
-        "a" | "always" => ApprovalResponse::Always,
-        _ => ApprovalResponse::No,
+    if i18n::is_answer("answer.yes", &line) {
+        ApprovalResponse::Yes
+    } else if i18n::is_answer("answer.always", &line) {
+        ApprovalResponse::Always
+    } else {
+        ApprovalResponse::No
     }
 }
 
@@ -277,6 +298,37 @@ mod tests {
         assert!(!mgr.needs_approval("shell"));
     }
 
+    #[tokio::test]
+    async fn policy_modes_set_mid_session_override_config() {
+        let supervised = supervised_config();
+        let policy = SecurityPolicy::default();
+        let mgr = ApprovalManager::from_config(&supervised).with_policy(&policy);
+        let full = ApprovalManager::from_config(&full_config()).with_policy(&policy);
+
+        // shell is in always_ask; `never` loosens it.
+        policy
+            .set_approval_mode("shell", ConfirmationMode::Never)
+            .await;
+        assert!(!mgr.needs_approval("shell"));
+
+        // file_read is auto-approved and full autonomy never prompts;
+        // `always` tightens both.
+        policy
+            .set_approval_mode("file_read", ConfirmationMode::Always)
+            .await;
+        assert!(mgr.needs_approval("file_read"));
+        assert!(full.needs_approval("file_read"));
+
+        policy
+            .set_approval_mode("memory_recall", ConfirmationMode::WhenDestructive)
+            .await;
+        policy
+            .set_approval_mode("file_write", ConfirmationMode::WhenDestructive)
+            .await;
+        assert!(!full.needs_approval("memory_recall"));
+        assert!(full.needs_approval("file_write"));
+    }
+
     // ── session allowlist ────────────────────────────────────
 
     #[test]
@@ -7,6 +7,7 @@ use crate::auth::profiles::{
     profile_id, AuthProfile, AuthProfileKind, AuthProfilesData, AuthProfilesStore,
 };
 use crate::config::Config;
+use crate::error::ZeroClawError;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -39,8 +40,8 @@ impl AuthService {
         }
     }
 
-    pub fn load_profiles(&self) -> Result<AuthProfilesData> {
-        self.store.load()
+    pub fn load_profiles(&self) -> Result<AuthProfilesData, ZeroClawError> {
+        self.store.load().map_err(ZeroClawError::OAuthError)
     }
 
     pub fn store_openai_tokens(
@@ -49,10 +50,12 @@ impl AuthService {
         token_set: crate::auth::profiles::TokenSet,
         account_id: Option<String>,
         set_active: bool,
-    ) -> Result<AuthProfile> {
+    ) -> Result<AuthProfile, ZeroClawError> {
         let mut profile = AuthProfile::new_oauth(OPENAI_CODEX_PROVIDER, profile_name, token_set);
         profile.account_id = account_id;
-        self.store.upsert_profile(profile.clone(), set_active)?;
+        self.store
+            .upsert_profile(profile.clone(), set_active)
+            .map_err(ZeroClawError::OAuthError)?;
         Ok(profile)
     }
 
@@ -63,48 +66,43 @@ impl AuthService {
         token: &str,
         metadata: HashMap<String, String>,
         set_active: bool,
-    ) -> Result<AuthProfile> {
+    ) -> Result<AuthProfile, ZeroClawError> {
         let mut profile = AuthProfile::new_token(provider, profile_name, token.to_string());
         profile.metadata.extend(metadata);
-        self.store.upsert_profile(profile.clone(), set_active)?;
+        self.store
+            .upsert_profile(profile.clone(), set_active)
+            .map_err(ZeroClawError::OAuthError)?;
         Ok(profile)
     }
 
-    pub fn set_active_profile(&self, provider: &str, requested_profile: &str) -> Result<String> {
-        let provider = normalize_provider(provider)?;
-        let data = self.store.load()?;
-        let profile_id = resolve_requested_profile_id(&provider, requested_profile);
-
-        let profile = data
-            .profiles
-            .get(&profile_id)
-            .ok_or_else(|| anyhow::anyhow!("Auth profile not found: {profile_id}"))?;
-
-        if profile.provider != provider {
-            anyhow::bail!(
-                "Profile {profile_id} belongs to provider {}, not {}",
-                profile.provider,
-                provider
-            );
-        }
-
-        self.store.set_active_profile(&provider, &profile_id)?;
-        Ok(profile_id)
+    pub fn set_active_profile(
+        &self,
+        provider: &str,
+        requested_profile: &str,
+    ) -> Result<String, ZeroClawError> {
+        self.activate_profile(provider, requested_profile)
+            .map_err(ZeroClawError::OAuthError)
     }
 
-    pub fn remove_profile(&self, provider: &str, requested_profile: &str) -> Result<bool> {
-        let provider = normalize_provider(provider)?;
+    pub fn remove_profile(
+        &self,
+        provider: &str,
+        requested_profile: &str,
+    ) -> Result<bool, ZeroClawError> {
+        let provider = normalize_provider(provider).map_err(ZeroClawError::OAuthError)?;
         let profile_id = resolve_requested_profile_id(&provider, requested_profile);
-        self.store.remove_profile(&profile_id)
+        self.store
+            .remove_profile(&profile_id)
+            .map_err(ZeroClawError::OAuthError)
     }
 
     pub fn get_profile(
         &self,
         provider: &str,
         profile_override: Option<&str>,
-    ) -> Result<Option<AuthProfile>> {
-        let provider = normalize_provider(provider)?;
-        let data = self.store.load()?;
+    ) -> Result<Option<AuthProfile>, ZeroClawError> {
+        let provider = normalize_provider(provider).map_err(ZeroClawError::OAuthError)?;
+        let data = self.store.load().map_err(ZeroClawError::OAuthError)?;
         let Some(profile_id) = select_profile_id(&data, &provider, profile_override) else {
             return Ok(None);
         };
@@ -115,7 +113,7 @@ impl AuthService {
         &self,
         provider: &str,
         profile_override: Option<&str>,
-    ) -> Result<Option<String>> {
+    ) -> Result<Option<String>, ZeroClawError> {
         let profile = self.get_profile(provider, profile_override)?;
         let Some(profile) = profile else {
             return Ok(None);
@@ -132,6 +130,37 @@ impl AuthService {
     pub async fn get_valid_openai_access_token(
         &self,
         profile_override: Option<&str>,
+    ) -> Result<Option<String>, ZeroClawError> {
+        self.refresh_openai_access_token(profile_override)
+            .await
+            .map_err(ZeroClawError::OAuthError)
+    }
+
+    fn activate_profile(&self, provider: &str, requested_profile: &str) -> Result<String> {
+        let provider = normalize_provider(provider)?;
+        let data = self.store.load()?;
+        let profile_id = resolve_requested_profile_id(&provider, requested_profile);
+
+        let profile = data
+            .profiles
+            .get(&profile_id)
+            .ok_or_else(|| anyhow::anyhow!("Auth profile not found: {profile_id}"))?;
+
+        if profile.provider != provider {
+            anyhow::bail!(
+                "Profile {profile_id} belongs to provider {}, not {}",
+                profile.provider,
+                provider
+            );
+        }
+
+        self.store.set_active_profile(&provider, &profile_id)?;
+        Ok(profile_id)
+    }
+
+    async fn refresh_openai_access_token(
+        &self,
+        profile_override: Option<&str>,
     ) -> Result<Option<String>> {
         let data = tokio::task::spawn_blocking({
             let store = self.store.clone();
@@ -295,7 +324,7 @@ fn refresh_lock_for_profile(profile_id: &str) -> Arc<tokio::sync::Mutex<()>> {
     static LOCKS: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
 
     let table = LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
-    let mut guard = table.lock().expect("refresh lock table poisoned");
+    let mut guard = table.lock().unwrap_or_else(|e| e.into_inner());
 
     guard
         .entry(profile_id.to_string())
@@ -305,7 +334,7 @@ fn refresh_lock_for_profile(profile_id: &str) -> Arc<tokio::sync::Mutex<()>> {
 
 fn refresh_backoff_remaining(profile_id: &str) -> Option<u64> {
     let map = REFRESH_BACKOFFS.get_or_init(|| Mutex::new(HashMap::new()));
-    let mut guard = map.lock().ok()?;
+    let mut guard = map.lock().unwrap_or_else(|e| e.into_inner());
     let now = Instant::now();
     let deadline = guard.get(profile_id).copied()?;
     if deadline <= now {
@@ -317,16 +346,16 @@ fn refresh_backoff_remaining(profile_id: &str) -> Option<u64> {
 
 fn set_refresh_backoff(profile_id: &str, duration: Duration) {
     let map = REFRESH_BACKOFFS.get_or_init(|| Mutex::new(HashMap::new()));
-    if let Ok(mut guard) = map.lock() {
-        guard.insert(profile_id.to_string(), Instant::now() + duration);
-    }
+    map.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(profile_id.to_string(), Instant::now() + duration);
 }
 
 fn clear_refresh_backoff(profile_id: &str) {
     let map = REFRESH_BACKOFFS.get_or_init(|| Mutex::new(HashMap::new()));
-    if let Ok(mut guard) = map.lock() {
-        guard.remove(profile_id);
-    }
+    map.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(profile_id);
 }
 
 #[cfg(test)]
@@ -392,4 +421,23 @@ mod tests {
             Some(id_active)
         );
     }
+
+    #[test]
+    fn refresh_backoff_survives_poisoned_lock() {
+        let map = REFRESH_BACKOFFS.get_or_init(|| Mutex::new(HashMap::new()));
+        let _ = std::thread::spawn(move || {
+            let _guard = map.lock().unwrap_or_else(|e| e.into_inner());
+            panic!("simulated panic while holding refresh backoff table");
+        })
+        .join();
+        assert!(map.is_poisoned());
+
+        set_refresh_backoff("poisoned-profile", Duration::from_secs(30));
+        assert!(refresh_backoff_remaining("poisoned-profile").is_some());
+        clear_refresh_backoff("poisoned-profile");
+        assert!(refresh_backoff_remaining("poisoned-profile").is_none());
+
+        let lock = refresh_lock_for_profile("poisoned-profile");
+        assert!(lock.try_lock().is_ok());
+    }
 }
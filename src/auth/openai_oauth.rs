@@ -1,4 +1,5 @@
 use crate::auth::profiles::TokenSet;
+use crate::i18n;
 use anyhow::{Context, Result};
 use base64::Engine;
 use chrono::Utc;
@@ -6,15 +7,23 @@ use reqwest::Client;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
+use std::net::SocketAddr;
 use std::time::{Duration, Instant};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpListener;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 pub const OPENAI_OAUTH_CLIENT_ID: &str = "app_EMoamEEZ73f0CkXaXp7hrann";
 pub const OPENAI_OAUTH_AUTHORIZE_URL: &str = "https://auth.openai.com/oauth/authorize";
 pub const OPENAI_OAUTH_TOKEN_URL: &str = "https://auth.openai.com/oauth/token";
 pub const OPENAI_OAUTH_DEVICE_CODE_URL: &str = "https://auth.openai.com/oauth/device/code";
 pub const OPENAI_OAUTH_REDIRECT_URI: &str = "http://localhost:1455/auth/callback";
+/// Where the loopback server for [`OPENAI_OAUTH_REDIRECT_URI`] listens.
+pub const OPENAI_OAUTH_CALLBACK_ADDR: &str = "127.0.0.1:1455";
+const OPENAI_OAUTH_CALLBACK_PATH: &str = "/auth/callback";
+/// A connection that sends nothing is dropped after this long.
+const CALLBACK_READ_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone)]
 pub struct PkceState {
@@ -239,46 +248,136 @@ pub async fn poll_device_code_tokens(
     }
 }
 
-pub async fn receive_loopback_code(expected_state: &str, timeout: Duration) -> Result<String> {
-    let listener = TcpListener::bind("127.0.0.1:1455")
-        .await
-        .context("Failed to bind callback listener at 127.0.0.1:1455")?;
+/// Loopback OAuth callback server running in its own task.
+///
+/// The task accepts connections until a request arrives on the callback
+/// path, sends the parsed result over a channel and stops. Cancelling the
+/// token (or dropping the server) stops it at once and frees the port, so a
+/// caller can abandon a pending login without waiting for a timeout.
+pub struct CallbackServer {
+    local_addr: SocketAddr,
+    results: mpsc::Receiver<Result<String>>,
+    cancel: CancellationToken,
+}
 
-    let accepted = tokio::time::timeout(timeout, listener.accept())
-        .await
-        .context("Timed out waiting for browser callback")?
-        .context("Failed to accept callback connection")?;
+impl CallbackServer {
+    /// Bind `addr` and start serving. Requests to other paths (e.g. the
+    /// browser's favicon fetch) get a 404 and do not end the flow.
+    pub async fn start(
+        addr: &str,
+        expected_state: &str,
+        cancel: CancellationToken,
+    ) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind callback listener at {addr}"))?;
+        let local_addr = listener.local_addr()?;
+        let (tx, results) = mpsc::channel(1);
+        let expected_state = expected_state.to_string();
+        let task_cancel = cancel.clone();
+        tokio::spawn(async move {
+            let result = loop {
+                let accepted = tokio::select! {
+                    () = task_cancel.cancelled() => break None,
+                    accepted = listener.accept() => accepted,
+                };
+                let stream = match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        break Some(Err(
+                            anyhow::Error::new(e).context("Failed to accept callback connection")
+                        ))
+                    }
+                };
+                let handled = tokio::select! {
+                    () = task_cancel.cancelled() => break None,
+                    handled = handle_callback_connection(stream, &expected_state) => handled,
+                };
+                if handled.is_some() {
+                    break handled;
+                }
+            };
+            // Free the port before the caller sees the channel close.
+            drop(listener);
+            if let Some(result) = result {
+                let _ = tx.send(result).await;
+            }
+        });
+        Ok(Self {
+            local_addr,
+            results,
+            cancel,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
 
-    let (mut stream, _) = accepted;
+    /// Wait for the callback result; `None` once the flow was cancelled.
+    pub async fn recv(&mut self) -> Option<Result<String>> {
+        self.results.recv().await
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+}
+
+impl Drop for CallbackServer {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Serve one callback connection. `None` means the request was not for the
+/// callback path and the server should keep waiting.
+async fn handle_callback_connection(
+    mut stream: TcpStream,
+    expected_state: &str,
+) -> Option<Result<String>> {
     let mut buffer = vec![0_u8; 8192];
-    let bytes_read = stream
-        .read(&mut buffer)
-        .await
-        .context("Failed to read callback request")?;
+    let bytes_read =
+        match tokio::time::timeout(CALLBACK_READ_TIMEOUT, stream.read(&mut buffer)).await {
+            Ok(Ok(n)) => n,
+            _ => return None,
+        };
 
     let request = String::from_utf8_lossy(&buffer[..bytes_read]);
-    let first_line = request
+    let path = request
         .lines()
         .next()
-        .ok_or_else(|| anyhow::anyhow!("Malformed callback request"))?;
-
-    let path = first_line
-        .split_whitespace()
-        .nth(1)
-        .ok_or_else(|| anyhow::anyhow!("Callback request missing path"))?;
-
-    let code = parse_code_from_redirect(path, Some(expected_state))?;
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or_default();
+    if !path.starts_with(OPENAI_OAUTH_CALLBACK_PATH) {
+        let _ = stream
+            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await;
+        return None;
+    }
 
-    let body =
-        "<html><body><h2>ZeroClaw login complete</h2><p>You can close this tab.</p></body></html>";
+    let result = parse_code_from_redirect(path, Some(expected_state));
+    let (status, body) = match &result {
+        Ok(_) => (
+            "200 OK",
+            format!(
+                "<html><body><h2>{}</h2><p>{}</p></body></html>",
+                i18n::t("oauth.title"),
+                i18n::t("oauth.body")
+            ),
+        ),
+        Err(e) => (
+            "400 Bad Request",
+            format!("<html><body><h2>Login failed</h2><p>{e}</p></body></html>"),
+        ),
+    };
     let response = format!(
-        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
         body.len(),
         body
     );
     let _ = stream.write_all(response.as_bytes()).await;
-
-    Ok(code)
+    Some(result)
 }
 
 pub fn parse_code_from_redirect(input: &str, expected_state: Option<&str>) -> Result<String> {
@@ -507,4 +606,65 @@ mod tests {
         let account = extract_account_id_from_jwt(&token);
         assert_eq!(account.as_deref(), Some("acct_123"));
     }
+
+    async fn send_request(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn callback_server_delivers_code_over_channel() {
+        let mut server = CallbackServer::start("127.0.0.1:0", "xyz", CancellationToken::new())
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+
+        let favicon = send_request(addr, "/favicon.ico").await;
+        assert!(favicon.starts_with("HTTP/1.1 404"));
+        let page = send_request(addr, "/auth/callback?code=abc&state=xyz").await;
+        assert!(page.starts_with("HTTP/1.1 200"));
+
+        let code = tokio::time::timeout(Duration::from_secs(5), server.recv())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(code, "abc");
+    }
+
+    #[tokio::test]
+    async fn callback_server_reports_state_mismatch() {
+        let mut server = CallbackServer::start("127.0.0.1:0", "xyz", CancellationToken::new())
+            .await
+            .unwrap();
+        let page = send_request(server.local_addr(), "/auth/callback?code=abc&state=bad").await;
+        assert!(page.starts_with("HTTP/1.1 400"));
+        let err = server.recv().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("state mismatch"));
+    }
+
+    #[tokio::test]
+    async fn cancelling_stops_the_callback_server_promptly() {
+        let cancel = CancellationToken::new();
+        let mut server = CallbackServer::start("127.0.0.1:0", "xyz", cancel.clone())
+            .await
+            .unwrap();
+        let addr = server.local_addr();
+
+        cancel.cancel();
+        let result = tokio::time::timeout(Duration::from_secs(1), server.recv())
+            .await
+            .expect("server should stop well before the login timeout");
+        assert!(result.is_none());
+        assert!(
+            TcpStream::connect(addr).await.is_err(),
+            "listener should be closed after cancellation"
+        );
+    }
 }
@@ -1,9 +1,291 @@
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
-use tokio::io::{self, AsyncBufReadExt, BufReader};
+#[cfg(feature = "line-editor")]
+use rustyline::{
+    completion::{Completer, Pair},
+    config::{Behavior, CompletionType},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Editor, Helper,
+};
+#[cfg(feature = "line-editor")]
+use std::borrow::Cow;
+use std::io::BufRead;
+#[cfg(feature = "line-editor")]
+use std::io::IsTerminal;
+use std::path::PathBuf;
 use uuid::Uuid;
 
-/// CLI channel — stdin/stdout, always available, zero deps
+/// Slash commands offered by tab completion in interactive mode.
+pub const SLASH_COMMANDS: &[&str] = &[
+    "/help",
+    "/clear",
+    "/new",
+    "/memory",
+    "/model",
+    "/temperature",
+    "/retry",
+    "/prompt",
+    "/approval",
+    "/fork",
+    "/skills",
+    "/workspace",
+    "/quit",
+    "/exit",
+];
+
+/// Lines kept in the interactive history file.
+const MAX_HISTORY_LINES: usize = 1000;
+
+const JSON_KEY_COLOR: &str = "\x1b[36m";
+const JSON_STRING_COLOR: &str = "\x1b[32m";
+const JSON_LITERAL_COLOR: &str = "\x1b[33m";
+const COLOR_RESET: &str = "\x1b[0m";
+
+/// Completion and highlighting for the interactive prompt.
+#[cfg(feature = "line-editor")]
+struct CliHelper {
+    tool_names: Vec<String>,
+}
+
+#[cfg(feature = "line-editor")]
+impl Completer for CliHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, matches) = complete_line(line, pos, &self.tool_names);
+        let pairs = matches
+            .into_iter()
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: candidate,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+#[cfg(feature = "line-editor")]
+impl Highlighter for CliHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        if looks_like_json(line) {
+            Cow::Owned(highlight_json(line))
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+
+    fn highlight_char(&self, line: &str, _pos: usize, _forced: bool) -> bool {
+        looks_like_json(line)
+    }
+}
+
+#[cfg(feature = "line-editor")]
+impl Hinter for CliHelper {
+    type Hint = String;
+}
+
+#[cfg(feature = "line-editor")]
+impl Validator for CliHelper {}
+
+#[cfg(feature = "line-editor")]
+impl Helper for CliHelper {}
+
+/// Complete the word before `pos`: slash commands at the start of the line,
+/// tool names anywhere else. Returns the replacement start and candidates.
+fn complete_line(line: &str, pos: usize, tool_names: &[String]) -> (usize, Vec<String>) {
+    let head = &line[..pos];
+    let start = head.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    let word = &head[start..];
+
+    let matches = if start == 0 && word.starts_with('/') {
+        SLASH_COMMANDS
+            .iter()
+            .filter(|cmd| cmd.starts_with(word))
+            .map(|cmd| (*cmd).to_string())
+            .collect()
+    } else if word.is_empty() {
+        Vec::new()
+    } else {
+        tool_names
+            .iter()
+            .filter(|name| name.starts_with(word))
+            .cloned()
+            .collect()
+    };
+    (start, matches)
+}
+
+/// Pasted tool-call JSON (`{"name": ..., "arguments": ...}`) gets highlighted.
+fn looks_like_json(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with('{') || trimmed.starts_with('[')
+}
+
+/// Colour keys, strings and literals of (possibly incomplete) JSON.
+fn highlight_json(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '"' => {
+                let mut end = text.len();
+                let mut escaped = false;
+                for (i, next) in chars.by_ref() {
+                    if escaped {
+                        escaped = false;
+                    } else if next == '\\' {
+                        escaped = true;
+                    } else if next == '"' {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                let is_key = text[end..].trim_start().starts_with(':');
+                let color = if is_key {
+                    JSON_KEY_COLOR
+                } else {
+                    JSON_STRING_COLOR
+                };
+                out.push_str(color);
+                out.push_str(&text[start..end]);
+                out.push_str(COLOR_RESET);
+            }
+            c if c == '-' || c.is_ascii_alphanumeric() => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(i, next)) = chars.peek() {
+                    if next == '.' || next == '-' || next == '+' || next.is_ascii_alphanumeric() {
+                        end = i + next.len_utf8();
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                out.push_str(JSON_LITERAL_COLOR);
+                out.push_str(&text[start..end]);
+                out.push_str(COLOR_RESET);
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Reads interactive input. On a terminal, in builds with the `line-editor`
+/// feature, this is a `rustyline` editor with arrow-key history (persisted
+/// to `history_path`), tab completion of slash commands and tool names, and
+/// highlighting of pasted JSON; otherwise plain lines are read from stdin.
+pub struct LineEditor {
+    #[cfg(feature = "line-editor")]
+    editor: Option<Editor<CliHelper, DefaultHistory>>,
+    #[cfg(feature = "line-editor")]
+    history_path: Option<PathBuf>,
+}
+
+#[cfg(not(feature = "line-editor"))]
+impl LineEditor {
+    pub fn new(_tool_names: Vec<String>, _history_path: Option<PathBuf>) -> Self {
+        Self {}
+    }
+
+    /// Whether the editor draws its own prompt; plain stdin does not.
+    pub fn is_interactive(&self) -> bool {
+        false
+    }
+
+    /// Read one line from stdin. `Ok(None)` means end of input.
+    pub fn read_line(&mut self, _prompt: &str) -> std::io::Result<Option<String>> {
+        read_stdin_line()
+    }
+}
+
+#[cfg(feature = "line-editor")]
+impl LineEditor {
+    pub fn new(tool_names: Vec<String>, history_path: Option<PathBuf>) -> Self {
+        let editor = if std::io::stdin().is_terminal() {
+            match Self::build_editor(tool_names, history_path.as_ref()) {
+                Ok(editor) => Some(editor),
+                Err(e) => {
+                    tracing::warn!("Line editing unavailable, reading plain stdin: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        Self {
+            editor,
+            history_path,
+        }
+    }
+
+    fn build_editor(
+        tool_names: Vec<String>,
+        history_path: Option<&PathBuf>,
+    ) -> rustyline::Result<Editor<CliHelper, DefaultHistory>> {
+        // PreferTerm draws on the terminal directly so stdout stays reserved
+        // for responses.
+        let config = rustyline::Config::builder()
+            .behavior(Behavior::PreferTerm)
+            .completion_type(CompletionType::List)
+            .auto_add_history(true)
+            .max_history_size(MAX_HISTORY_LINES)?
+            .build();
+        let mut editor = Editor::with_config(config)?;
+        editor.set_helper(Some(CliHelper { tool_names }));
+        if let Some(path) = history_path {
+            // A missing history file just means a first session.
+            let _ = editor.load_history(path);
+        }
+        Ok(editor)
+    }
+
+    /// Whether the editor draws its own prompt; plain stdin does not.
+    pub fn is_interactive(&self) -> bool {
+        self.editor.is_some()
+    }
+
+    /// Read one line after showing `prompt` (editor only). `Ok(None)` means
+    /// end of input; Ctrl-C discards the current line and returns it empty.
+    pub fn read_line(&mut self, prompt: &str) -> std::io::Result<Option<String>> {
+        let Some(editor) = self.editor.as_mut() else {
+            return read_stdin_line();
+        };
+
+        match editor.readline(prompt) {
+            Ok(line) => {
+                if let Some(path) = &self.history_path {
+                    if let Err(e) = editor.save_history(path) {
+                        tracing::debug!("Failed to save input history: {e}");
+                    }
+                }
+                Ok(Some(line))
+            }
+            Err(ReadlineError::Interrupted) => Ok(Some(String::new())),
+            Err(ReadlineError::Eof) => Ok(None),
+            Err(ReadlineError::Io(e)) => Err(e),
+            Err(e) => Err(std::io::Error::other(e)),
+        }
+    }
+}
+
+fn read_stdin_line() -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+    match std::io::stdin().lock().read_line(&mut line)? {
+        0 => Ok(None),
+        _ => Ok(Some(line)),
+    }
+}
+
+/// CLI channel — stdin/stdout, always available
 pub struct CliChannel;
 
 impl CliChannel {
@@ -19,16 +301,23 @@ impl Channel for CliChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        println!("{}", message.content);
+        crate::term::response(&message.content);
         Ok(())
     }
 
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
-        let stdin = io::stdin();
-        let reader = BufReader::new(stdin);
-        let mut lines = reader.lines();
+        // The editor blocks, so it lives on its own thread.
+        let (line_tx, mut lines) = tokio::sync::mpsc::channel(1);
+        std::thread::spawn(move || {
+            let mut editor = LineEditor::new(Vec::new(), None);
+            while let Ok(Some(line)) = editor.read_line("") {
+                if line_tx.blocking_send(line).is_err() {
+                    break;
+                }
+            }
+        });
 
-        while let Ok(Some(line)) = lines.next_line().await {
+        while let Some(line) = lines.recv().await {
             let line = line.trim().to_string();
             if line.is_empty() {
                 continue;
@@ -116,6 +405,43 @@ mod tests {
         assert_eq!(msg.timestamp, 1_234_567_890);
     }
 
+    #[test]
+    fn completes_slash_commands_at_line_start() {
+        let (start, matches) = complete_line("/c", 2, &[]);
+        assert_eq!(start, 0);
+        assert_eq!(matches, ["/clear"]);
+
+        let (_, matches) = complete_line("/", 1, &[]);
+        assert_eq!(matches.len(), SLASH_COMMANDS.len());
+    }
+
+    #[test]
+    fn completes_tool_names_for_later_words() {
+        let tools = vec!["file_read".to_string(), "file_write".into(), "shell".into()];
+        let (start, matches) = complete_line("use file_", 9, &tools);
+        assert_eq!(start, 4);
+        assert_eq!(matches, ["file_read", "file_write"]);
+
+        assert!(complete_line("use ", 4, &tools).1.is_empty());
+        assert!(complete_line("say /cl", 7, &tools).1.is_empty());
+    }
+
+    #[test]
+    fn highlights_json_keys_strings_and_literals() {
+        let line = r#"{"name": "shell", "n": 2, "ok": true}"#;
+        assert!(looks_like_json(line));
+        assert!(!looks_like_json("hello {\"a\": 1}"));
+
+        let highlighted = highlight_json(line);
+        assert!(highlighted.contains(&format!("{JSON_KEY_COLOR}\"name\"{COLOR_RESET}")));
+        assert!(highlighted.contains(&format!("{JSON_STRING_COLOR}\"shell\"{COLOR_RESET}")));
+        assert!(highlighted.contains(&format!("{JSON_LITERAL_COLOR}2{COLOR_RESET}")));
+        assert!(highlighted.contains(&format!("{JSON_LITERAL_COLOR}true{COLOR_RESET}")));
+
+        // Unterminated strings while typing are still coloured to the end.
+        assert!(highlight_json(r#"{"na"#).ends_with(&format!("\"na{COLOR_RESET}")));
+    }
+
     #[test]
     fn channel_message_clone() {
         let msg = ChannelMessage {
@@ -10,6 +10,8 @@ pub mod mattermost;
 pub mod qq;
 pub mod signal;
 pub mod slack;
+#[cfg(unix)]
+pub mod socket;
 pub mod telegram;
 pub mod traits;
 pub mod whatsapp;
@@ -26,18 +28,23 @@ pub use mattermost::MattermostChannel;
 pub use qq::QQChannel;
 pub use signal::SignalChannel;
 pub use slack::SlackChannel;
+#[cfg(unix)]
+pub use socket::SocketChannel;
 pub use telegram::TelegramChannel;
-pub use traits::{Channel, SendMessage};
+pub use traits::{Channel, SendMessage, StreamEvent};
 pub use whatsapp::WhatsAppChannel;
 
-use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop};
-use crate::config::Config;
+use crate::agent::classifier::AutoTemperature;
+use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop, ToolLoopOptions};
+use crate::agent::routing::{self, MessageRouter, RouteChoice};
+use crate::config::{Config, SessionOverflow};
 use crate::identity;
 use crate::memory::{self, Memory};
 use crate::observability::{self, Observer};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
 use crate::security::SecurityPolicy;
+use crate::term;
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::{Context, Result};
@@ -66,6 +73,8 @@ const CHANNEL_MESSAGE_TIMEOUT_SECS: u64 = 300;
 const CHANNEL_PARALLELISM_PER_CHANNEL: usize = 4;
 const CHANNEL_MIN_IN_FLIGHT_MESSAGES: usize = 8;
 const CHANNEL_MAX_IN_FLIGHT_MESSAGES: usize = 64;
+const CHANNEL_BUSY_REPLY: &str =
+    "⏳ I'm handling too many conversations right now. Please try again in a moment.";
 const CHANNEL_TYPING_REFRESH_INTERVAL_SECS: u64 = 4;
 const MODEL_CACHE_FILE: &str = "models_cache.json";
 const MODEL_CACHE_PREVIEW_LIMIT: usize = 10;
@@ -107,12 +116,22 @@ struct ChannelRuntimeContext {
     tools_registry: Arc<Vec<Box<dyn Tool>>>,
     observer: Arc<dyn Observer>,
     system_prompt: Arc<String>,
+    timezone: crate::util::LocalTimezone,
     model: Arc<String>,
     temperature: f64,
+    auto_temperature: Option<AutoTemperature>,
+    message_router: Arc<MessageRouter>,
     auto_save_memory: bool,
+    infer_memory_categories: bool,
     max_tool_iterations: usize,
+    max_tool_calls_per_turn: usize,
+    normalize_output: bool,
+    early_tool_dispatch: bool,
+    tool_retry: crate::tools::ToolRetryPolicy,
     min_relevance_score: f64,
     conversation_histories: ConversationHistoryMap,
+    /// `[limits]` budgets, one per sender conversation
+    session_budgets: Arc<crate::cost::SessionBudgets>,
     provider_cache: ProviderCacheMap,
     route_overrides: RouteSelectionMap,
     api_key: Option<String>,
@@ -171,18 +190,40 @@ fn parse_runtime_command(channel_name: &str, content: &str) -> Option<ChannelRun
                 Some(ChannelRuntimeCommand::ShowProviders)
             }
         }
-        "/model" => {
-            let model = parts.collect::<Vec<_>>().join(" ").trim().to_string();
-            if model.is_empty() {
-                Some(ChannelRuntimeCommand::ShowModel)
-            } else {
-                Some(ChannelRuntimeCommand::SetModel(model))
-            }
-        }
+        "/model" => match (parts.next(), parts.next()) {
+            (None, _) => Some(ChannelRuntimeCommand::ShowModel),
+            (Some(model), None) => Some(ChannelRuntimeCommand::SetModel(model.to_string())),
+            // `/model <model-id> <message>` is a one-turn override, not a command.
+            (Some(_), Some(_)) => None,
+        },
         _ => None,
     }
 }
 
+/// Split `/model <model-id> <message>` into the model for this turn and the
+/// message to answer. The sender's session route is left unchanged.
+fn parse_inline_model_directive(channel_name: &str, content: &str) -> Option<(String, String)> {
+    if !supports_runtime_model_switch(channel_name) {
+        return None;
+    }
+
+    let trimmed = content.trim_start();
+    let (command_token, rest) = trimmed.split_once(char::is_whitespace)?;
+    let base_command = command_token.split('@').next().unwrap_or(command_token);
+    if !base_command.eq_ignore_ascii_case("/model") {
+        return None;
+    }
+
+    let rest = rest.trim_start();
+    let (model, message) = rest.split_once(char::is_whitespace)?;
+    let model = model.trim_matches('`');
+    let message = message.trim();
+    if model.is_empty() || message.is_empty() {
+        return None;
+    }
+    Some((model.to_string(), message.to_string()))
+}
+
 fn resolve_provider_alias(name: &str) -> Option<String> {
     let candidate = name.trim();
     if candidate.is_empty() {
@@ -238,6 +279,7 @@ fn clear_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) {
         .lock()
         .unwrap_or_else(|e| e.into_inner())
         .remove(sender_key);
+    ctx.session_budgets.end_session(sender_key);
 }
 
 fn load_cached_model_preview(workspace_dir: &Path, provider_name: &str) -> Vec<String> {
@@ -315,6 +357,7 @@ fn build_models_help_response(current: &ChannelRouteSelection, workspace_dir: &P
         current.provider, current.model
     );
     response.push_str("\nSwitch model with `/model <model-id>`.\n");
+    response.push_str("Ask one question with another model: `/model <model-id> <message>`.\n");
 
     let cached_models = load_cached_model_preview(workspace_dir, &current.provider);
     if cached_models.is_empty() {
@@ -554,12 +597,12 @@ fn spawn_scoped_typing_task(
 }
 
 async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::ChannelMessage) {
-    println!(
+    term::println(format_args!(
         "  💬 [{}] from {}: {}",
         msg.channel,
         msg.sender,
         truncate_with_ellipsis(&msg.content, 80)
-    );
+    ));
 
     let target_channel = ctx.channels_by_name.get(&msg.channel).cloned();
     if handle_runtime_command_if_needed(ctx.as_ref(), &msg, target_channel.as_ref()).await {
@@ -567,7 +610,32 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
     }
 
     let history_key = conversation_history_key(&msg);
-    let route = get_route_selection(ctx.as_ref(), &history_key);
+    let mut route = get_route_selection(ctx.as_ref(), &history_key);
+    // A sender's `/models`/`/model` choice or an inline model directive pins
+    // the route; `[routing]` rules only apply otherwise.
+    let mut pinned = route != default_route_selection(ctx.as_ref());
+    let content = match parse_inline_model_directive(&msg.channel, &msg.content) {
+        Some((model, message)) => {
+            route.model = model;
+            pinned = true;
+            message
+        }
+        None => msg.content.clone(),
+    };
+    let temperature = ctx.auto_temperature.map_or(ctx.temperature, |auto| {
+        auto.select(ctx.temperature, &msg.content)
+    });
+    let routed = routing::route_message(
+        &ctx.message_router,
+        pinned,
+        &content,
+        &msg.channel,
+        RouteChoice::default_route(&route.provider, &route.model, temperature),
+        ctx.observer.as_ref(),
+    );
+    route.provider = routed.provider;
+    route.model = routed.model;
+    let temperature = routed.temperature;
     let active_provider = match get_or_create_provider(ctx.as_ref(), &route.provider).await {
         Ok(provider) => provider,
         Err(err) => {
@@ -586,7 +654,7 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
     };
 
     let memory_context =
-        build_memory_context(ctx.memory.as_ref(), &msg.content, ctx.min_relevance_score).await;
+        build_memory_context(ctx.memory.as_ref(), &content, ctx.min_relevance_score).await;
 
     if ctx.auto_save_memory {
         let autosave_key = conversation_memory_key(&msg);
@@ -594,20 +662,23 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
             .memory
             .store(
                 &autosave_key,
-                &msg.content,
-                crate::memory::MemoryCategory::Conversation,
+                &content,
+                crate::memory::categorize::user_message_category(
+                    &content,
+                    ctx.infer_memory_categories,
+                ),
                 None,
             )
             .await;
     }
 
     let enriched_message = if memory_context.is_empty() {
-        msg.content.clone()
+        content
     } else {
-        format!("{memory_context}{}", msg.content)
+        format!("{memory_context}{content}")
     };
 
-    println!("  ⏳ Processing message...");
+    term::activity("  ⏳ Processing message...");
     let started_at = Instant::now();
 
     // Build history from per-sender conversation cache
@@ -619,7 +690,9 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
         .cloned()
         .unwrap_or_default();
 
-    let mut history = vec![ChatMessage::system(ctx.system_prompt.as_str())];
+    let mut system_prompt = ctx.system_prompt.as_str().to_owned();
+    crate::util::refresh_datetime_section(&mut system_prompt, &ctx.timezone, chrono::Utc::now());
+    let mut history = vec![ChatMessage::system(system_prompt)];
     history.append(&mut prior_turns);
     history.push(ChatMessage::user(&enriched_message));
 
@@ -634,7 +707,7 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
 
     // Set up streaming channel if supported
     let (delta_tx, delta_rx) = if use_streaming {
-        let (tx, rx) = tokio::sync::mpsc::channel::<String>(64);
+        let (tx, rx) = tokio::sync::mpsc::channel::<StreamEvent>(64);
         (Some(tx), Some(rx))
     } else {
         (None, None)
@@ -671,12 +744,17 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
         let draft_id = draft_id_ref.to_string();
         Some(tokio::spawn(async move {
             let mut accumulated = String::new();
-            while let Some(delta) = rx.recv().await {
-                accumulated.push_str(&delta);
-                if let Err(e) = channel
-                    .update_draft(&reply_target, &draft_id, &accumulated)
-                    .await
-                {
+            while let Some(event) = rx.recv().await {
+                let result = match event {
+                    StreamEvent::Text(delta) => {
+                        accumulated.push_str(&delta);
+                        channel
+                            .update_draft(&reply_target, &draft_id, &accumulated)
+                            .await
+                    }
+                    event => channel.draft_event(&reply_target, &draft_id, &event).await,
+                };
+                if let Err(e) = result {
                     tracing::debug!("Draft update failed: {e}");
                 }
             }
@@ -695,6 +773,7 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
         _ => None,
     };
 
+    let budget = ctx.session_budgets.for_session(&history_key);
     let llm_result = tokio::time::timeout(
         Duration::from_secs(CHANNEL_MESSAGE_TIMEOUT_SECS),
         run_tool_call_loop(
@@ -704,12 +783,18 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
             ctx.observer.as_ref(),
             route.provider.as_str(),
             route.model.as_str(),
-            ctx.temperature,
-            true,
-            None,
-            msg.channel.as_str(),
-            ctx.max_tool_iterations,
-            delta_tx,
+            temperature,
+            ToolLoopOptions {
+                channel_name: msg.channel.as_str(),
+                max_tool_iterations: ctx.max_tool_iterations,
+                max_tool_calls_per_turn: ctx.max_tool_calls_per_turn,
+                on_delta: delta_tx,
+                normalize_output: ctx.normalize_output,
+                early_tool_dispatch: ctx.early_tool_dispatch,
+                tool_retry: ctx.tool_retry,
+                budget: budget.as_deref(),
+                ..ToolLoopOptions::default()
+            },
         ),
     )
     .await;
@@ -742,11 +827,11 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
                     turns.remove(0);
                 }
             }
-            println!(
+            term::println(format_args!(
                 "  🤖 Reply ({}ms): {}",
                 started_at.elapsed().as_millis(),
                 truncate_with_ellipsis(&response, 80)
-            );
+            ));
             if let Some(channel) = target_channel.as_ref() {
                 if let Some(ref draft_id) = draft_message_id {
                     if let Err(e) = channel
@@ -762,15 +847,18 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
                     .send(&SendMessage::new(response, &msg.reply_target))
                     .await
                 {
-                    eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
+                    term::eprintln(format_args!(
+                        "  ❌ Failed to reply on {}: {e}",
+                        channel.name()
+                    ));
                 }
             }
         }
         Ok(Err(e)) => {
-            eprintln!(
+            term::eprintln(format_args!(
                 "  ❌ LLM error after {}ms: {e}",
                 started_at.elapsed().as_millis()
-            );
+            ));
             if let Some(channel) = target_channel.as_ref() {
                 if let Some(ref draft_id) = draft_message_id {
                     let _ = channel
@@ -791,11 +879,11 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
                 "LLM response timed out after {}s",
                 CHANNEL_MESSAGE_TIMEOUT_SECS
             );
-            eprintln!(
+            term::eprintln(format_args!(
                 "  ❌ {} (elapsed: {}ms)",
                 timeout_msg,
                 started_at.elapsed().as_millis()
-            );
+            ));
             if let Some(channel) = target_channel.as_ref() {
                 let error_text =
                     "⚠️ Request timed out while waiting for the model. Please try again.";
@@ -813,18 +901,46 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
     }
 }
 
+/// Tell the sender their message was dropped because every session slot is busy.
+async fn reply_busy(ctx: &ChannelRuntimeContext, msg: &traits::ChannelMessage) {
+    tracing::warn!(
+        channel = %msg.channel,
+        sender = %msg.sender,
+        "All session slots busy; rejecting message"
+    );
+    if let Some(channel) = ctx.channels_by_name.get(&msg.channel) {
+        if let Err(e) = channel
+            .send(&SendMessage::new(CHANNEL_BUSY_REPLY, &msg.reply_target))
+            .await
+        {
+            tracing::debug!("Failed to send busy reply on {}: {e}", msg.channel);
+        }
+    }
+}
+
 async fn run_message_dispatch_loop(
     mut rx: tokio::sync::mpsc::Receiver<traits::ChannelMessage>,
     ctx: Arc<ChannelRuntimeContext>,
     max_in_flight_messages: usize,
+    overflow: SessionOverflow,
 ) {
     let semaphore = Arc::new(tokio::sync::Semaphore::new(max_in_flight_messages));
     let mut workers = tokio::task::JoinSet::new();
 
     while let Some(msg) = rx.recv().await {
-        let permit = match Arc::clone(&semaphore).acquire_owned().await {
-            Ok(permit) => permit,
-            Err(_) => break,
+        let permit = match overflow {
+            SessionOverflow::Queue => match Arc::clone(&semaphore).acquire_owned().await {
+                Ok(permit) => permit,
+                Err(_) => break,
+            },
+            SessionOverflow::Reject => match Arc::clone(&semaphore).try_acquire_owned() {
+                Ok(permit) => permit,
+                Err(tokio::sync::TryAcquireError::NoPermits) => {
+                    reply_busy(&ctx, &msg).await;
+                    continue;
+                }
+                Err(tokio::sync::TryAcquireError::Closed) => break,
+            },
         };
 
         let worker_ctx = Arc::clone(&ctx);
@@ -872,12 +988,13 @@ fn load_openclaw_bootstrap_files(
 /// Load workspace identity files and build a system prompt.
 ///
 /// Follows the `OpenClaw` framework structure by default:
+/// 0. Persona — `[agent] name` / `persona`, when configured
 /// 1. Tooling — tool list + descriptions
 /// 2. Safety — guardrail reminder
 /// 3. Skills — compact list with paths (loaded on-demand)
 /// 4. Workspace — working directory
 /// 5. Bootstrap files — AGENTS, SOUL, TOOLS, IDENTITY, USER, BOOTSTRAP, MEMORY
-/// 6. Date & Time — timezone for cache stability
+/// 6. Date & Time — local date, time and timezone (refreshed each turn)
 /// 7. Runtime — host, OS, model
 ///
 /// When `identity_config` is set to AIEOS format, the bootstrap files section
@@ -885,17 +1002,28 @@ fn load_openclaw_bootstrap_files(
 ///
 /// Daily memory files (`memory/*.md`) are NOT injected — they are accessed
 /// on-demand via `memory_recall` / `memory_search` tools.
+#[allow(clippy::too_many_arguments)]
 pub fn build_system_prompt(
     workspace_dir: &std::path::Path,
     model_name: &str,
     tools: &[(&str, &str)],
     skills: &[crate::skills::Skill],
     identity_config: Option<&crate::config::IdentityConfig>,
+    agent_config: Option<&crate::config::AgentConfig>,
     bootstrap_max_chars: Option<usize>,
+    timezone: &crate::util::LocalTimezone,
 ) -> String {
     use std::fmt::Write;
     let mut prompt = String::with_capacity(8192);
 
+    // ── 0. Persona ──────────────────────────────────────────────
+    if let Some(agent_config) = agent_config {
+        let persona = identity::persona_section(agent_config);
+        if !persona.is_empty() {
+            let _ = writeln!(prompt, "{persona}");
+        }
+    }
+
     // ── 1. Tooling ──────────────────────────────────────────────
     if !tools.is_empty() {
         prompt.push_str("## Tools\n\n");
@@ -952,6 +1080,7 @@ pub fn build_system_prompt(
     );
 
     // ── 3. Skills (compact list — load on-demand) ───────────────
+    let skills = crate::skills::skills_for_model(skills, model_name);
     if !skills.is_empty() {
         prompt.push_str("## Available Skills\n\n");
         prompt.push_str(
@@ -959,21 +1088,7 @@ pub fn build_system_prompt(
         );
         prompt.push_str("<available_skills>\n");
         for skill in skills {
-            let _ = writeln!(prompt, "  <skill>");
-            let _ = writeln!(prompt, "    <name>{}</name>", skill.name);
-            let _ = writeln!(
-                prompt,
-                "    <description>{}</description>",
-                skill.description
-            );
-            let location = skill.location.clone().unwrap_or_else(|| {
-                workspace_dir
-                    .join("skills")
-                    .join(&skill.name)
-                    .join("SKILL.md")
-            });
-            let _ = writeln!(prompt, "    <location>{}</location>", location.display());
-            let _ = writeln!(prompt, "  </skill>");
+            prompt.push_str(&skill_prompt_entry(skill, workspace_dir));
         }
         prompt.push_str("</available_skills>\n\n");
     }
@@ -1008,9 +1123,9 @@ pub fn build_system_prompt(
                 }
                 Err(e) => {
                     // Log error but don't fail - fall back to OpenClaw
-                    eprintln!(
+                    term::eprintln(format_args!(
                         "Warning: Failed to load AIEOS identity: {e}. Using OpenClaw format."
-                    );
+                    ));
                     let max_chars = bootstrap_max_chars.unwrap_or(BOOTSTRAP_MAX_CHARS);
                     load_openclaw_bootstrap_files(&mut prompt, workspace_dir, max_chars);
                 }
@@ -1027,9 +1142,11 @@ pub fn build_system_prompt(
     }
 
     // ── 6. Date & Time ──────────────────────────────────────────
-    let now = chrono::Local::now();
-    let tz = now.format("%Z").to_string();
-    let _ = writeln!(prompt, "## Current Date & Time\n\nTimezone: {tz}\n");
+    let _ = writeln!(
+        prompt,
+        "{}\n",
+        crate::util::datetime_section(timezone, chrono::Utc::now())
+    );
 
     // ── 7. Runtime ──────────────────────────────────────────────
     let host =
@@ -1040,7 +1157,13 @@ pub fn build_system_prompt(
         std::env::consts::OS,
     );
 
-    // ── 8. Channel Capabilities ─────────────────────────────────────
+    // ── 8. Reply language ───────────────────────────────────────
+    let language = crate::i18n::language_section();
+    if !language.is_empty() {
+        let _ = writeln!(prompt, "{language}\n");
+    }
+
+    // ── 9. Channel Capabilities ─────────────────────────────────────
     prompt.push_str("## Channel Capabilities\n\n");
     prompt.push_str(
         "- You are running as a Discord bot. You CAN and do send messages to Discord channels.\n",
@@ -1057,6 +1180,22 @@ pub fn build_system_prompt(
     }
 }
 
+/// The `<skill>` entry `build_system_prompt` lists for `skill`.
+pub fn skill_prompt_entry(skill: &crate::skills::Skill, workspace_dir: &std::path::Path) -> String {
+    let location = skill.location.clone().unwrap_or_else(|| {
+        workspace_dir
+            .join("skills")
+            .join(&skill.name)
+            .join("SKILL.md")
+    });
+    format!(
+        "  <skill>\n    <name>{}</name>\n    <description>{}</description>\n    <location>{}</location>\n  </skill>\n",
+        skill.name,
+        skill.description,
+        location.display()
+    )
+}
+
 /// Inject a single workspace file into the prompt with truncation and missing-file markers.
 fn inject_workspace_file(
     prompt: &mut String,
@@ -1120,7 +1259,7 @@ fn bind_telegram_identity(config: &Config, identity: &str) -> Result<()> {
     };
 
     if telegram.allowed_users.iter().any(|u| u == "*") {
-        println!(
+        term::println(
             "⚠️ Telegram allowlist is currently wildcard (`*`) — binding is unnecessary until you remove '*'."
         );
     }
@@ -1131,28 +1270,33 @@ fn bind_telegram_identity(config: &Config, identity: &str) -> Result<()> {
         .map(|entry| normalize_telegram_identity(entry))
         .any(|entry| entry == normalized)
     {
-        println!("✅ Telegram identity already bound: {normalized}");
+        term::println(format_args!(
+            "✅ Telegram identity already bound: {normalized}"
+        ));
         return Ok(());
     }
 
     telegram.allowed_users.push(normalized.clone());
     updated.save()?;
-    println!("✅ Bound Telegram identity: {normalized}");
-    println!("   Saved to {}", updated.config_path.display());
+    term::println(format_args!("✅ Bound Telegram identity: {normalized}"));
+    term::println(format_args!(
+        "   Saved to {}",
+        updated.config_path.display()
+    ));
     match maybe_restart_managed_daemon_service() {
         Ok(true) => {
-            println!("🔄 Detected running managed daemon service; reloaded automatically.");
+            term::println("🔄 Detected running managed daemon service; reloaded automatically.");
         }
         Ok(false) => {
-            println!(
+            term::println(
                 "ℹ️ No managed daemon service detected. If `zeroclaw daemon`/`channel start` is already running, restart it to load the updated allowlist."
             );
         }
         Err(e) => {
-            eprintln!(
+            term::eprintln(format_args!(
                 "⚠️ Allowlist saved, but failed to reload daemon service automatically: {e}\n\
                  Restart service manually with `zeroclaw service stop && zeroclaw service start`."
-            );
+            ));
         }
     }
     Ok(())
@@ -1241,8 +1385,8 @@ pub fn handle_command(command: crate::ChannelCommands, config: &Config) -> Resul
             anyhow::bail!("Doctor must be handled in main.rs (requires async runtime)")
         }
         crate::ChannelCommands::List => {
-            println!("Channels:");
-            println!("  ✅ CLI (always available)");
+            term::println("Channels:");
+            term::println("  ✅ CLI (always available)");
             for (name, configured) in [
                 ("Telegram", config.channels_config.telegram.is_some()),
                 ("Discord", config.channels_config.discord.is_some()),
@@ -1257,12 +1401,16 @@ pub fn handle_command(command: crate::ChannelCommands, config: &Config) -> Resul
                 ("Lark", config.channels_config.lark.is_some()),
                 ("DingTalk", config.channels_config.dingtalk.is_some()),
                 ("QQ", config.channels_config.qq.is_some()),
+                ("Socket", config.channels_config.socket.is_some()),
             ] {
-                println!("  {} {name}", if configured { "✅" } else { "❌" });
+                term::println(format_args!(
+                    "  {} {name}",
+                    if configured { "✅" } else { "❌" }
+                ));
             }
-            println!("\nTo start channels: zeroclaw channel start");
-            println!("To check health:    zeroclaw channel doctor");
-            println!("To configure:      zeroclaw onboard");
+            term::println("\nTo start channels: zeroclaw channel start");
+            term::println("To check health:    zeroclaw channel doctor");
+            term::println("To configure:      zeroclaw onboard");
             Ok(())
         }
         crate::ChannelCommands::Add {
@@ -1436,13 +1584,18 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
         ));
     }
 
+    #[cfg(unix)]
+    if let Some(ref sock) = config.channels_config.socket {
+        channels.push(("Socket", Arc::new(SocketChannel::new(sock.socket_path()))));
+    }
+
     if channels.is_empty() {
-        println!("No real-time channels configured. Run `zeroclaw onboard` first.");
+        term::println("No real-time channels configured. Run `zeroclaw onboard` first.");
         return Ok(());
     }
 
-    println!("🩺 ZeroClaw Channel Doctor");
-    println!();
+    term::println("🩺 ZeroClaw Channel Doctor");
+    term::println("");
 
     let mut healthy = 0_u32;
     let mut unhealthy = 0_u32;
@@ -1455,25 +1608,29 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
         match state {
             ChannelHealthState::Healthy => {
                 healthy += 1;
-                println!("  ✅ {name:<9} healthy");
+                term::println(format_args!("  ✅ {name:<9} healthy"));
             }
             ChannelHealthState::Unhealthy => {
                 unhealthy += 1;
-                println!("  ❌ {name:<9} unhealthy (auth/config/network)");
+                term::println(format_args!(
+                    "  ❌ {name:<9} unhealthy (auth/config/network)"
+                ));
             }
             ChannelHealthState::Timeout => {
                 timeout += 1;
-                println!("  ⏱️  {name:<9} timed out (>10s)");
+                term::println(format_args!("  ⏱️  {name:<9} timed out (>10s)"));
             }
         }
     }
 
     if config.channels_config.webhook.is_some() {
-        println!("  ℹ️  Webhook   check via `zeroclaw gateway` then GET /health");
+        term::println("  ℹ️  Webhook   check via `zeroclaw gateway` then GET /health");
     }
 
-    println!();
-    println!("Summary: {healthy} healthy, {unhealthy} unhealthy, {timeout} timed out");
+    term::println("");
+    term::println(format_args!(
+        "Summary: {healthy} healthy, {unhealthy} unhealthy, {timeout} timed out"
+    ));
     Ok(())
 }
 
@@ -1488,6 +1645,14 @@ pub async fn start_channels(config: Config) -> Result<()> {
         auth_profile_override: None,
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
+        header_overrides: config.provider.overrides.clone(),
+        reasoning_effort: config.provider.reasoning_effort.clone(),
+        custom_providers: config.provider.custom.clone(),
+        response_cache: config.provider.cache.clone(),
+        prompt_audit: crate::observability::prompt_audit::PromptAuditLog::from_config(
+            &config.observability,
+        )?
+        .map(Arc::new),
     };
     let provider: Arc<dyn Provider> = Arc::from(providers::create_resilient_provider_with_options(
         &provider_name,
@@ -1516,12 +1681,15 @@ pub async fn start_channels(config: Config) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
     let temperature = config.default_temperature;
-    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
+    let timezone = crate::util::LocalTimezone::from_config(config.agent.timezone.as_deref());
+    let (mem, memory_degraded) = memory::create_session_memory(
         &config.memory,
         Some(&config.storage.provider.config),
         &config.workspace_dir,
         config.api_key.as_deref(),
-    )?);
+        timezone,
+    )?;
+    let mem: Arc<dyn Memory> = Arc::from(mem);
     let (composio_key, composio_entity_id) = if config.composio.enabled {
         (
             config.composio.api_key.as_deref(),
@@ -1532,7 +1700,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
     };
     // Build system prompt from workspace identity files + skills
     let workspace = config.workspace_dir.clone();
-    let tools_registry = Arc::new(tools::all_tools_with_runtime(
+    let mut tools_registry = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
         runtime,
@@ -1545,64 +1713,18 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &config.agents,
         config.api_key.as_deref(),
         &config,
+    );
+    if memory_degraded {
+        tools_registry.retain(|tool| !tools::is_memory_tool(tool.name()));
+    }
+    let tools_registry = Arc::new(tools::PromptInjectionFilter::wrap_all(
+        tools_registry,
+        config.security.tool_output_injection,
     ));
 
-    let skills = crate::skills::load_skills(&workspace);
+    let skills = crate::skills::load_skills(&workspace, &config.skills.dir_paths());
 
-    // Collect tool descriptions for the prompt
-    let mut tool_descs: Vec<(&str, &str)> = vec![
-        (
-            "shell",
-            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
-        ),
-        (
-            "file_read",
-            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
-        ),
-        (
-            "file_write",
-            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
-        ),
-        (
-            "memory_store",
-            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
-        ),
-        (
-            "memory_recall",
-            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
-        ),
-        (
-            "memory_forget",
-            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
-        ),
-    ];
-
-    if config.browser.enabled {
-        tool_descs.push((
-            "browser_open",
-            "Open approved HTTPS URLs in Brave Browser (allowlist-only, no scraping)",
-        ));
-    }
-    if config.composio.enabled {
-        tool_descs.push((
-            "composio",
-            "Execute actions on 1000+ apps via Composio (Gmail, Notion, GitHub, Slack, etc.). Use action='list' to discover, 'execute' to run (optionally with connected_account_id), 'connect' to OAuth.",
-        ));
-    }
-    tool_descs.push((
-        "schedule",
-        "Manage scheduled tasks (create/list/get/cancel/pause/resume). Supports recurring cron and one-shot delays.",
-    ));
-    tool_descs.push((
-        "pushover",
-        "Send a Pushover notification to your device. Requires PUSHOVER_TOKEN and PUSHOVER_USER_KEY in .env file.",
-    ));
-    if !config.agents.is_empty() {
-        tool_descs.push((
-            "delegate",
-            "Delegate a subtask to a specialized agent. Use when: a task benefits from a different model (e.g. fast summarization, deep reasoning, code generation). The sub-agent runs a single prompt and returns its response.",
-        ));
-    }
+    let tool_descs = tools::tool_descriptions(tools_registry.as_ref())?;
 
     let bootstrap_max_chars = if config.agent.compact_context {
         Some(6000)
@@ -1615,19 +1737,21 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &tool_descs,
         &skills,
         Some(&config.identity),
+        Some(&config.agent),
         bootstrap_max_chars,
+        &timezone,
     );
     system_prompt.push_str(&build_tool_instructions(tools_registry.as_ref()));
 
     if !skills.is_empty() {
-        println!(
+        term::println(format_args!(
             "  🧩 Skills:   {}",
             skills
                 .iter()
                 .map(|s| s.name.as_str())
                 .collect::<Vec<_>>()
                 .join(", ")
-        );
+        ));
     }
 
     // Collect active channels
@@ -1747,33 +1871,43 @@ pub async fn start_channels(config: Config) -> Result<()> {
         )));
     }
 
+    #[cfg(unix)]
+    if let Some(ref sock) = config.channels_config.socket {
+        channels.push(Arc::new(SocketChannel::new(sock.socket_path())));
+    }
+
+    #[cfg(not(unix))]
+    if config.channels_config.socket.is_some() {
+        tracing::warn!("[channels.socket] is only supported on Unix; skipping");
+    }
+
     if channels.is_empty() {
-        println!("No channels configured. Run `zeroclaw onboard` to set up channels.");
+        term::println("No channels configured. Run `zeroclaw onboard` to set up channels.");
         return Ok(());
     }
 
-    println!("🦀 ZeroClaw Channel Server");
-    println!("  🤖 Model:    {model}");
+    term::println("🦀 ZeroClaw Channel Server");
+    term::println(format_args!("  🤖 Model:    {model}"));
     let effective_backend = memory::effective_memory_backend_name(
         &config.memory.backend,
         Some(&config.storage.provider.config),
     );
-    println!(
+    term::println(format_args!(
         "  🧠 Memory:   {} (auto-save: {})",
         effective_backend,
         if config.memory.auto_save { "on" } else { "off" }
-    );
-    println!(
+    ));
+    term::println(format_args!(
         "  📡 Channels: {}",
         channels
             .iter()
             .map(|c| c.name())
             .collect::<Vec<_>>()
             .join(", ")
-    );
-    println!();
-    println!("  Listening for messages... (Ctrl+C to stop)");
-    println!();
+    ));
+    term::println("");
+    term::println("  Listening for messages... (Ctrl+C to stop)");
+    term::println("");
 
     crate::health::mark_component_ok("channels");
 
@@ -1807,9 +1941,14 @@ pub async fn start_channels(config: Config) -> Result<()> {
             .map(|ch| (ch.name().to_string(), Arc::clone(ch)))
             .collect::<HashMap<_, _>>(),
     );
-    let max_in_flight_messages = compute_max_in_flight_messages(channels.len());
+    let max_in_flight_messages = match config.channels_config.max_concurrent_sessions {
+        0 => compute_max_in_flight_messages(channels.len()),
+        limit => limit,
+    };
 
-    println!("  🚦 In-flight message limit: {max_in_flight_messages}");
+    term::println(format_args!(
+        "  🚦 In-flight message limit: {max_in_flight_messages}"
+    ));
 
     let mut provider_cache_seed: HashMap<String, Arc<dyn Provider>> = HashMap::new();
     provider_cache_seed.insert(provider_name.clone(), Arc::clone(&provider));
@@ -1822,12 +1961,24 @@ pub async fn start_channels(config: Config) -> Result<()> {
         tools_registry: Arc::clone(&tools_registry),
         observer,
         system_prompt: Arc::new(system_prompt),
+        timezone,
         model: Arc::new(model.clone()),
         temperature,
+        auto_temperature: AutoTemperature::from_config(&config.agent),
+        message_router: Arc::new(MessageRouter::from_config(&config.routing)?),
         auto_save_memory: config.memory.auto_save,
+        infer_memory_categories: config.memory.infer_categories,
         max_tool_iterations: config.agent.max_tool_iterations,
+        max_tool_calls_per_turn: config.agent.max_tool_calls_per_turn,
+        normalize_output: config.agent.normalize_output,
+        early_tool_dispatch: config.agent.early_tool_dispatch,
+        tool_retry: crate::tools::ToolRetryPolicy::from_config(&config.agent),
         min_relevance_score: config.memory.min_relevance_score,
         conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+        session_budgets: Arc::new(crate::cost::SessionBudgets::from_config(
+            &config.limits,
+            &config.cost,
+        )),
         provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
         route_overrides: Arc::new(Mutex::new(HashMap::new())),
         api_key: config.api_key.clone(),
@@ -1837,7 +1988,13 @@ pub async fn start_channels(config: Config) -> Result<()> {
         workspace_dir: Arc::new(config.workspace_dir.clone()),
     });
 
-    run_message_dispatch_loop(rx, runtime_ctx, max_in_flight_messages).await;
+    run_message_dispatch_loop(
+        rx,
+        runtime_ctx,
+        max_in_flight_messages,
+        config.channels_config.session_overflow,
+    )
+    .await;
 
     // Wait for all channel tasks
     for h in handles {
@@ -1854,6 +2011,7 @@ mod tests {
     use crate::observability::NoopObserver;
     use crate::providers::{ChatMessage, Provider};
     use crate::tools::{Tool, ToolResult};
+    use crate::util::LocalTimezone;
     use std::collections::HashMap;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
@@ -2185,6 +2343,7 @@ mod tests {
                     success: false,
                     output: String::new(),
                     error: Some("unexpected symbol".to_string()),
+                    artifacts: Vec::new(),
                 });
             }
 
@@ -2192,6 +2351,7 @@ mod tests {
                 success: true,
                 output: r#"{"symbol":"BTC","price_usd":65000}"#.to_string(),
                 error: None,
+                artifacts: Vec::new(),
             })
         }
     }
@@ -2212,12 +2372,21 @@ mod tests {
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
             auto_save_memory: false,
+            infer_memory_categories: false,
             max_tool_iterations: 10,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -2264,12 +2433,21 @@ mod tests {
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
             auto_save_memory: false,
+            infer_memory_categories: false,
             max_tool_iterations: 10,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -2325,12 +2503,21 @@ mod tests {
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
             model: Arc::new("default-model".to_string()),
             temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
             auto_save_memory: false,
+            infer_memory_categories: false,
             max_tool_iterations: 5,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -2407,12 +2594,21 @@ mod tests {
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
             model: Arc::new("default-model".to_string()),
             temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
             auto_save_memory: false,
+            infer_memory_categories: false,
             max_tool_iterations: 5,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
             provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
             route_overrides: Arc::new(Mutex::new(route_overrides)),
             api_key: None,
@@ -2447,6 +2643,118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_inline_model_directive_splits_model_and_message() {
+        assert_eq!(
+            parse_inline_model_directive("telegram", "/model gpt-4o what is 2+2?"),
+            Some(("gpt-4o".to_string(), "what is 2+2?".to_string()))
+        );
+        assert_eq!(
+            parse_inline_model_directive("discord", "/model@bot `gpt-4o`  hi there"),
+            Some(("gpt-4o".to_string(), "hi there".to_string()))
+        );
+        assert_eq!(
+            parse_inline_model_directive("telegram", "/model gpt-4o"),
+            None
+        );
+        assert_eq!(parse_inline_model_directive("telegram", "hello"), None);
+        assert_eq!(
+            parse_inline_model_directive("slack", "/model gpt-4o hi"),
+            None
+        );
+
+        assert_eq!(
+            parse_runtime_command("telegram", "/model gpt-4o"),
+            Some(ChannelRuntimeCommand::SetModel("gpt-4o".to_string()))
+        );
+        assert_eq!(parse_runtime_command("telegram", "/model gpt-4o hi"), None);
+    }
+
+    #[tokio::test]
+    async fn process_channel_message_inline_model_directive_applies_to_one_turn() {
+        let channel_impl = Arc::new(TelegramRecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let provider_impl = Arc::new(ModelCaptureProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let mut provider_cache_seed: HashMap<String, Arc<dyn Provider>> = HashMap::new();
+        provider_cache_seed.insert("test-provider".to_string(), Arc::clone(&provider));
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::clone(&provider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
+            model: Arc::new("default-model".to_string()),
+            temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
+            auto_save_memory: false,
+            infer_memory_categories: false,
+            max_tool_iterations: 5,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
+            provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+        });
+
+        for (id, content) in [
+            ("msg-inline-1", "/model gpt-4o this question"),
+            ("msg-inline-2", "and this one"),
+        ] {
+            process_channel_message(
+                runtime_ctx.clone(),
+                traits::ChannelMessage {
+                    id: id.to_string(),
+                    sender: "alice".to_string(),
+                    reply_target: "chat-1".to_string(),
+                    content: content.to_string(),
+                    channel: "telegram".to_string(),
+                    timestamp: 1,
+                },
+            )
+            .await;
+        }
+
+        assert_eq!(
+            provider_impl
+                .models
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .as_slice(),
+            &["gpt-4o".to_string(), "default-model".to_string()]
+        );
+        assert!(runtime_ctx
+            .route_overrides
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty());
+
+        let histories = runtime_ctx
+            .conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let turns = histories.get("telegram_alice").expect("history stored");
+        assert_eq!(turns[0].content, "this question");
+    }
+
     #[tokio::test]
     async fn process_channel_message_respects_configured_max_tool_iterations_above_default() {
         let channel_impl = Arc::new(RecordingChannel::default());
@@ -2465,12 +2773,21 @@ mod tests {
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
             auto_save_memory: false,
+            infer_memory_categories: false,
             max_tool_iterations: 12,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -2518,12 +2835,21 @@ mod tests {
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
             auto_save_memory: false,
+            infer_memory_categories: false,
             max_tool_iterations: 3,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -2622,12 +2948,21 @@ mod tests {
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
             auto_save_memory: false,
+            infer_memory_categories: false,
             max_tool_iterations: 10,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -2661,7 +2996,7 @@ mod tests {
         drop(tx);
 
         let started = Instant::now();
-        run_message_dispatch_loop(rx, runtime_ctx, 2).await;
+        run_message_dispatch_loop(rx, runtime_ctx, 2, SessionOverflow::Queue).await;
         let elapsed = started.elapsed();
 
         assert!(
@@ -2674,6 +3009,71 @@ mod tests {
         assert_eq!(sent_messages.len(), 2);
     }
 
+    #[tokio::test]
+    async fn message_dispatch_rejects_messages_beyond_session_limit() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::new(SlowProvider {
+                delay: Duration::from_millis(250),
+            }),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
+            auto_save_memory: false,
+            infer_memory_categories: false,
+            max_tool_iterations: 10,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+        });
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
+        for (id, sender) in [("1", "alice"), ("2", "bob")] {
+            tx.send(traits::ChannelMessage {
+                id: id.to_string(),
+                sender: sender.to_string(),
+                reply_target: sender.to_string(),
+                content: "hello".to_string(),
+                channel: "test-channel".to_string(),
+                timestamp: 1,
+            })
+            .await
+            .unwrap();
+        }
+        drop(tx);
+
+        run_message_dispatch_loop(rx, runtime_ctx, 1, SessionOverflow::Reject).await;
+
+        let sent_messages = channel_impl.sent_messages.lock().await;
+        assert_eq!(sent_messages.len(), 2);
+        assert_eq!(sent_messages[0], format!("bob:{CHANNEL_BUSY_REPLY}"));
+        assert!(sent_messages[1].starts_with("alice:"));
+    }
+
     #[tokio::test]
     async fn process_channel_message_cancels_scoped_typing_task() {
         let channel_impl = Arc::new(RecordingChannel::default());
@@ -2692,12 +3092,21 @@ mod tests {
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
             auto_save_memory: false,
+            infer_memory_categories: false,
             max_tool_iterations: 10,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -2730,7 +3139,16 @@ mod tests {
     fn prompt_contains_all_sections() {
         let ws = make_workspace();
         let tools = vec![("shell", "Run commands"), ("file_read", "Read files")];
-        let prompt = build_system_prompt(ws.path(), "test-model", &tools, &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "test-model",
+            &tools,
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         // Section headers
         assert!(prompt.contains("## Tools"), "missing Tools section");
@@ -2754,7 +3172,16 @@ mod tests {
             ("shell", "Run commands"),
             ("memory_recall", "Search memory"),
         ];
-        let prompt = build_system_prompt(ws.path(), "gpt-4o", &tools, &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "gpt-4o",
+            &tools,
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         assert!(prompt.contains("**shell**"));
         assert!(prompt.contains("Run commands"));
@@ -2764,17 +3191,71 @@ mod tests {
     #[test]
     fn prompt_injects_safety() {
         let ws = make_workspace();
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         assert!(prompt.contains("Do not exfiltrate private data"));
         assert!(prompt.contains("Do not run destructive commands"));
         assert!(prompt.contains("Prefer `trash` over `rm`"));
     }
 
+    #[test]
+    fn prompt_uses_configured_assistant_name_and_persona() {
+        let ws = make_workspace();
+        let agent = crate::config::AgentConfig {
+            name: Some("Ada".into()),
+            persona: Some("A patient tutor for the Acme support team.".into()),
+            ..crate::config::AgentConfig::default()
+        };
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            Some(&agent),
+            None,
+            &LocalTimezone::System,
+        );
+
+        assert!(prompt.starts_with("## Persona"), "persona should lead");
+        assert!(prompt.contains("Your name is Ada."));
+        assert!(prompt.contains("A patient tutor for the Acme support team."));
+
+        let unbranded = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            Some(&crate::config::AgentConfig::default()),
+            None,
+            &LocalTimezone::System,
+        );
+        assert!(!unbranded.contains("## Persona"));
+    }
+
     #[test]
     fn prompt_injects_workspace_files() {
         let ws = make_workspace();
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         assert!(prompt.contains("### SOUL.md"), "missing SOUL.md header");
         assert!(prompt.contains("Be helpful"), "missing SOUL content");
@@ -2801,7 +3282,16 @@ mod tests {
     fn prompt_missing_file_markers() {
         let tmp = TempDir::new().unwrap();
         // Empty workspace — no files at all
-        let prompt = build_system_prompt(tmp.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            tmp.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         assert!(prompt.contains("[File not found: SOUL.md]"));
         assert!(prompt.contains("[File not found: AGENTS.md]"));
@@ -2812,7 +3302,16 @@ mod tests {
     fn prompt_bootstrap_only_if_exists() {
         let ws = make_workspace();
         // No BOOTSTRAP.md — should not appear
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
         assert!(
             !prompt.contains("### BOOTSTRAP.md"),
             "BOOTSTRAP.md should not appear when missing"
@@ -2820,7 +3319,16 @@ mod tests {
 
         // Create BOOTSTRAP.md — should appear
         std::fs::write(ws.path().join("BOOTSTRAP.md"), "# Bootstrap\nFirst run.").unwrap();
-        let prompt2 = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt2 = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
         assert!(
             prompt2.contains("### BOOTSTRAP.md"),
             "BOOTSTRAP.md should appear when present"
@@ -2840,7 +3348,16 @@ mod tests {
         )
         .unwrap();
 
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         // Daily notes should NOT be in the system prompt (on-demand via tools)
         assert!(
@@ -2856,7 +3373,16 @@ mod tests {
     #[test]
     fn prompt_runtime_metadata() {
         let ws = make_workspace();
-        let prompt = build_system_prompt(ws.path(), "claude-sonnet-4", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "claude-sonnet-4",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         assert!(prompt.contains("Model: claude-sonnet-4"));
         assert!(prompt.contains(&format!("OS: {}", std::env::consts::OS)));
@@ -2875,9 +3401,21 @@ mod tests {
             tools: vec![],
             prompts: vec!["Long prompt content that should NOT appear in system prompt".into()],
             location: None,
+            title: None,
+            models: vec![],
+            priority: 0.0,
         }];
 
-        let prompt = build_system_prompt(ws.path(), "model", &[], &skills, None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &skills,
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         assert!(prompt.contains("<available_skills>"), "missing skills XML");
         assert!(prompt.contains("<name>code-review</name>"));
@@ -2898,7 +3436,16 @@ mod tests {
         let big_content = "x".repeat(BOOTSTRAP_MAX_CHARS + 1000);
         std::fs::write(ws.path().join("AGENTS.md"), &big_content).unwrap();
 
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         assert!(
             prompt.contains("truncated at"),
@@ -2915,7 +3462,16 @@ mod tests {
         let ws = make_workspace();
         std::fs::write(ws.path().join("TOOLS.md"), "").unwrap();
 
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         // Empty file should not produce a header
         assert!(
@@ -2943,7 +3499,16 @@ mod tests {
     #[test]
     fn prompt_contains_channel_capabilities() {
         let ws = make_workspace();
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         assert!(
             prompt.contains("## Channel Capabilities"),
@@ -2962,7 +3527,16 @@ mod tests {
     #[test]
     fn prompt_workspace_path() {
         let ws = make_workspace();
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         assert!(prompt.contains(&format!("Working directory: `{}`", ws.path().display())));
     }
@@ -3082,12 +3656,21 @@ mod tests {
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            timezone: LocalTimezone::System,
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
+            auto_temperature: None,
+            message_router: Arc::new(MessageRouter::default()),
             auto_save_memory: false,
+            infer_memory_categories: false,
             max_tool_iterations: 5,
+            max_tool_calls_per_turn: 0,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            tool_retry: crate::tools::ToolRetryPolicy::default(),
             min_relevance_score: 0.0,
             conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            session_budgets: Arc::default(),
             provider_cache: Arc::new(Mutex::new(HashMap::new())),
             route_overrides: Arc::new(Mutex::new(HashMap::new())),
             api_key: None,
@@ -3176,7 +3759,16 @@ mod tests {
             aieos_inline: None,
         };
 
-        let prompt = build_system_prompt(tmp.path(), "model", &[], &[], Some(&config), None);
+        let prompt = build_system_prompt(
+            tmp.path(),
+            "model",
+            &[],
+            &[],
+            Some(&config),
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         // Should contain AIEOS sections
         assert!(prompt.contains("## Identity"));
@@ -3217,6 +3809,8 @@ mod tests {
             &[],
             Some(&config),
             None,
+            None,
+            &LocalTimezone::System,
         );
 
         assert!(prompt.contains("**Name:** Claw"));
@@ -3234,7 +3828,16 @@ mod tests {
         };
 
         let ws = make_workspace();
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], Some(&config), None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            Some(&config),
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         // Should fall back to OpenClaw format when AIEOS file is not found
         // (Error is logged to stderr with filename, not included in prompt)
@@ -3253,7 +3856,16 @@ mod tests {
         };
 
         let ws = make_workspace();
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], Some(&config), None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            Some(&config),
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         // Should use OpenClaw format (not configured for AIEOS)
         assert!(prompt.contains("### SOUL.md"));
@@ -3271,7 +3883,16 @@ mod tests {
         };
 
         let ws = make_workspace();
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], Some(&config), None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            Some(&config),
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         // Should use OpenClaw format even if aieos_path is set
         assert!(prompt.contains("### SOUL.md"));
@@ -3283,7 +3904,16 @@ mod tests {
     fn none_identity_config_uses_openclaw() {
         let ws = make_workspace();
         // Pass None for identity config
-        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None, None);
+        let prompt = build_system_prompt(
+            ws.path(),
+            "model",
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            &LocalTimezone::System,
+        );
 
         // Should use OpenClaw format
         assert!(prompt.contains("### SOUL.md"));
@@ -0,0 +1,478 @@
+//! Local IPC channel over a Unix domain socket.
+//!
+//! Clients write one JSON request per line, `{"id": "1", "content": "hi"}`
+//! (`id` is optional), and read newline-delimited JSON frames tagged with the
+//! request id:
+//!
+//! ```text
+//! {"type":"start","id":"1"}
+//! {"type":"delta","id":"1","text":"Let me check. "}
+//! {"type":"tool_call","id":"1","name":"file_read","arguments":{"path":"a.txt"}}
+//! {"type":"tool_result","id":"1","name":"file_read","success":true}
+//! {"type":"delta","id":"1","text":"It says hi."}
+//! {"type":"done","id":"1","text":"Let me check. It says hi."}
+//! ```
+//!
+//! Every request ends with one `done` frame. Malformed requests get an
+//! `error` frame. Each connection is its own conversation, and replies still
+//! pending when a client disconnects are dropped.
+
+use crate::channels::traits::{Channel, ChannelMessage, SendMessage, StreamEvent};
+use anyhow::Context;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Frames queued per connection before senders wait on the client.
+const FRAME_BUFFER: usize = 64;
+
+/// Frame writers for connected clients, by connection id.
+type Clients = Arc<Mutex<HashMap<String, mpsc::Sender<String>>>>;
+
+/// One line sent by a client.
+#[derive(Debug, Deserialize)]
+struct SocketRequest {
+    #[serde(default)]
+    id: Option<String>,
+    content: String,
+}
+
+/// Unix socket channel for local frontends.
+/// Replies stream as NDJSON frames through the draft-update hooks.
+pub struct SocketChannel {
+    path: PathBuf,
+    clients: Clients,
+    /// Bytes of each draft already sent as deltas, by reply target.
+    streamed: Mutex<HashMap<String, usize>>,
+    next_connection: AtomicU64,
+}
+
+impl SocketChannel {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            clients: Arc::new(Mutex::new(HashMap::new())),
+            streamed: Mutex::new(HashMap::new()),
+            next_connection: AtomicU64::new(0),
+        }
+    }
+
+    /// Write one frame to the client behind `reply_target`, tagged with its request id.
+    async fn send_frame(&self, reply_target: &str, mut frame: Value) -> anyhow::Result<()> {
+        let (connection, request_id) = split_reply_target(reply_target);
+        frame["id"] = Value::String(request_id.to_string());
+        let client = self
+            .clients
+            .lock()
+            .get(connection)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Socket client {connection} is disconnected"))?;
+        client
+            .send(frame.to_string())
+            .await
+            .map_err(|_| anyhow::anyhow!("Socket client {connection} is disconnected"))
+    }
+}
+
+/// Reply targets are `<connection>:<request id>`.
+fn split_reply_target(reply_target: &str) -> (&str, &str) {
+    reply_target.split_once(':').unwrap_or((reply_target, ""))
+}
+
+/// Read requests from one client until it disconnects, forwarding each as a
+/// `ChannelMessage`; frames queued for the client are written as they arrive.
+async fn serve_connection(
+    stream: UnixStream,
+    connection: String,
+    clients: Clients,
+    tx: mpsc::Sender<ChannelMessage>,
+) -> anyhow::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let (frames, mut pending) = mpsc::channel::<String>(FRAME_BUFFER);
+    clients.lock().insert(connection.clone(), frames.clone());
+
+    let writer_task = tokio::spawn(async move {
+        while let Some(frame) = pending.recv().await {
+            let line = format!("{frame}\n");
+            if writer.write_all(line.as_bytes()).await.is_err() {
+                break; // client went away
+            }
+        }
+    });
+
+    let mut lines = BufReader::new(reader).lines();
+    let result = loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(anyhow::Error::from(e)),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match serde_json::from_str::<SocketRequest>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let message = format!("Invalid request: {e}");
+                let frame = json!({ "type": "error", "message": message });
+                let _ = frames.send(frame.to_string()).await;
+                continue;
+            }
+        };
+        let id = request.id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let msg = ChannelMessage {
+            reply_target: format!("{connection}:{id}"),
+            id,
+            sender: connection.clone(),
+            content: request.content,
+            channel: "socket".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        if tx.send(msg).await.is_err() {
+            break Ok(());
+        }
+    };
+
+    clients.lock().remove(&connection);
+    drop(frames);
+    writer_task.abort();
+    tracing::debug!("Socket client {connection} disconnected");
+    result
+}
+
+#[async_trait]
+impl Channel for SocketChannel {
+    fn name(&self) -> &str {
+        "socket"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        self.send_frame(
+            &message.recipient,
+            json!({ "type": "done", "text": message.content }),
+        )
+        .await
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        // A socket file left by a previous run blocks bind(); anything else is not ours.
+        if let Ok(meta) = std::fs::symlink_metadata(&self.path) {
+            if !meta.file_type().is_socket() {
+                anyhow::bail!(
+                    "{} exists and is not a socket; refusing to replace it",
+                    self.path.display()
+                );
+            }
+            std::fs::remove_file(&self.path)?;
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(&self.path)
+            .with_context(|| format!("Failed to bind {}", self.path.display()))?;
+        std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))?;
+        tracing::info!("Socket channel listening on {}", self.path.display());
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let connection = format!(
+                "socket-{}",
+                self.next_connection.fetch_add(1, Ordering::Relaxed)
+            );
+            let clients = Arc::clone(&self.clients);
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve_connection(stream, connection, clients, tx).await {
+                    tracing::debug!("Socket connection ended with error: {e}");
+                }
+            });
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        self.path.exists()
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        self.send_frame(&message.recipient, json!({ "type": "start" }))
+            .await?;
+        self.streamed.lock().insert(message.recipient.clone(), 0);
+        Ok(Some(message.recipient.clone()))
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        _message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let sent = self.streamed.lock().get(recipient).copied().unwrap_or(0);
+        let delta = text.get(sent..).unwrap_or_default();
+        if delta.is_empty() {
+            return Ok(());
+        }
+        self.send_frame(recipient, json!({ "type": "delta", "text": delta }))
+            .await?;
+        self.streamed
+            .lock()
+            .insert(recipient.to_string(), text.len());
+        Ok(())
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        _message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.streamed.lock().remove(recipient);
+        self.send_frame(recipient, json!({ "type": "done", "text": text }))
+            .await
+    }
+
+    async fn draft_event(
+        &self,
+        recipient: &str,
+        _message_id: &str,
+        event: &StreamEvent,
+    ) -> anyhow::Result<()> {
+        let frame = match event {
+            StreamEvent::Text(text) => json!({ "type": "delta", "text": text }),
+            StreamEvent::ToolCall { name, arguments } => {
+                json!({ "type": "tool_call", "name": name, "arguments": arguments })
+            }
+            StreamEvent::ToolResult { name, success } => {
+                json!({ "type": "tool_result", "name": name, "success": success })
+            }
+        };
+        self.send_frame(recipient, frame).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::Lines;
+    use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+
+    /// Serve one end of a socket pair; returns the client's halves.
+    fn connect(
+        channel: &SocketChannel,
+        tx: mpsc::Sender<ChannelMessage>,
+    ) -> (
+        Lines<BufReader<OwnedReadHalf>>,
+        OwnedWriteHalf,
+        tokio::task::JoinHandle<anyhow::Result<()>>,
+    ) {
+        let (client, server) = UnixStream::pair().unwrap();
+        let server = tokio::spawn(serve_connection(
+            server,
+            "socket-0".into(),
+            Arc::clone(&channel.clients),
+            tx,
+        ));
+        let (reader, writer) = client.into_split();
+        (BufReader::new(reader).lines(), writer, server)
+    }
+
+    async fn next_frame(lines: &mut Lines<BufReader<OwnedReadHalf>>) -> Value {
+        let line = tokio::time::timeout(std::time::Duration::from_secs(5), lines.next_line())
+            .await
+            .expect("frame should arrive")
+            .unwrap()
+            .expect("socket should stay open");
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[tokio::test]
+    async fn request_yields_streamed_response_frames() {
+        let channel = SocketChannel::new(PathBuf::from("/unused.sock"));
+        let (tx, mut rx) = mpsc::channel(4);
+        let (mut lines, mut writer, _server) = connect(&channel, tx);
+
+        writer
+            .write_all(b"{\"id\":\"r1\",\"content\":\"read a.txt\"}\n")
+            .await
+            .unwrap();
+        let msg = rx.recv().await.unwrap();
+        assert_eq!(msg.id, "r1");
+        assert_eq!(msg.channel, "socket");
+        assert_eq!(msg.sender, "socket-0");
+        assert_eq!(msg.reply_target, "socket-0:r1");
+        assert_eq!(msg.content, "read a.txt");
+
+        // Drive the hooks the way the channel runtime does while a reply streams.
+        let target = msg.reply_target.as_str();
+        let draft = channel
+            .send_draft(&SendMessage::new("...", target))
+            .await
+            .unwrap()
+            .unwrap();
+        channel
+            .update_draft(target, &draft, "Let me ")
+            .await
+            .unwrap();
+        channel
+            .update_draft(target, &draft, "Let me check. ")
+            .await
+            .unwrap();
+        channel
+            .draft_event(
+                target,
+                &draft,
+                &StreamEvent::ToolCall {
+                    name: "file_read".into(),
+                    arguments: json!({ "path": "a.txt" }),
+                },
+            )
+            .await
+            .unwrap();
+        channel
+            .draft_event(
+                target,
+                &draft,
+                &StreamEvent::ToolResult {
+                    name: "file_read".into(),
+                    success: true,
+                },
+            )
+            .await
+            .unwrap();
+        channel
+            .finalize_draft(target, &draft, "Let me check. It says hi.")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            next_frame(&mut lines).await,
+            json!({ "type": "start", "id": "r1" })
+        );
+        assert_eq!(
+            next_frame(&mut lines).await,
+            json!({ "type": "delta", "id": "r1", "text": "Let me " })
+        );
+        assert_eq!(
+            next_frame(&mut lines).await,
+            json!({ "type": "delta", "id": "r1", "text": "check. " })
+        );
+        assert_eq!(
+            next_frame(&mut lines).await,
+            json!({
+                "type": "tool_call",
+                "id": "r1",
+                "name": "file_read",
+                "arguments": { "path": "a.txt" }
+            })
+        );
+        assert_eq!(
+            next_frame(&mut lines).await,
+            json!({ "type": "tool_result", "id": "r1", "name": "file_read", "success": true })
+        );
+        assert_eq!(
+            next_frame(&mut lines).await,
+            json!({ "type": "done", "id": "r1", "text": "Let me check. It says hi." })
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_request_gets_error_frame_and_connection_stays_open() {
+        let channel = SocketChannel::new(PathBuf::from("/unused.sock"));
+        let (tx, mut rx) = mpsc::channel(4);
+        let (mut lines, mut writer, _server) = connect(&channel, tx);
+
+        writer.write_all(b"not json\n").await.unwrap();
+        let frame = next_frame(&mut lines).await;
+        assert_eq!(frame["type"], "error");
+        assert!(frame["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid request"));
+
+        writer.write_all(b"{\"content\":\"hi\"}\n").await.unwrap();
+        let msg = rx.recv().await.unwrap();
+        assert_eq!(msg.content, "hi");
+        assert!(!msg.id.is_empty(), "missing ids are generated");
+    }
+
+    #[tokio::test]
+    async fn client_disconnect_unregisters_connection() {
+        let channel = SocketChannel::new(PathBuf::from("/unused.sock"));
+        let (tx, _rx) = mpsc::channel(4);
+        let (lines, writer, server) = connect(&channel, tx);
+
+        drop(writer);
+        drop(lines);
+        server.await.unwrap().unwrap();
+
+        assert!(channel.clients.lock().is_empty());
+        let err = channel
+            .send(&SendMessage::new("late reply", "socket-0:r1"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("disconnected"));
+    }
+
+    #[tokio::test]
+    async fn listen_replaces_stale_socket_and_accepts_clients() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("zeroclaw.sock");
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists(), "stale socket file left behind");
+
+        let channel = Arc::new(SocketChannel::new(path.clone()));
+        let (tx, mut rx) = mpsc::channel(4);
+        let listener = {
+            let channel = Arc::clone(&channel);
+            tokio::spawn(async move { channel.listen(tx).await })
+        };
+
+        let mut client = None;
+        for _ in 0..100 {
+            if let Ok(stream) = UnixStream::connect(&path).await {
+                client = Some(stream);
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let mut client = client.expect("listener should accept connections");
+        client
+            .write_all(b"{\"id\":\"a\",\"content\":\"ping\"}\n")
+            .await
+            .unwrap();
+        let msg = rx.recv().await.unwrap();
+        assert_eq!(msg.content, "ping");
+        assert!(channel.health_check().await);
+        listener.abort();
+    }
+
+    #[tokio::test]
+    async fn listen_refuses_to_replace_regular_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("notes.txt");
+        std::fs::write(&path, "keep me").unwrap();
+
+        let channel = SocketChannel::new(path.clone());
+        let (tx, _rx) = mpsc::channel(1);
+        let err = channel.listen(tx).await.unwrap_err();
+        assert!(err.to_string().contains("not a socket"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+    }
+}
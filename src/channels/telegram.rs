@@ -315,8 +315,12 @@ impl TelegramChannel {
         let pairing = if normalized_allowed.is_empty() {
             let guard = PairingGuard::new(true, &[]);
             if let Some(code) = guard.pairing_code() {
-                println!("  🔐 Telegram pairing required. One-time bind code: {code}");
-                println!("     Send `{TELEGRAM_BIND_COMMAND} <code>` from your Telegram account.");
+                crate::term::println(format_args!(
+                    "  🔐 Telegram pairing required. One-time bind code: {code}"
+                ));
+                crate::term::println(format_args!(
+                    "     Send `{TELEGRAM_BIND_COMMAND} <code>` from your Telegram account."
+                ));
             }
             Some(guard)
         } else {
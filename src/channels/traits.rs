@@ -43,6 +43,20 @@ impl SendMessage {
     }
 }
 
+/// One increment of a streamed response, relayed to channels that support drafts
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    /// Response text to append to the draft
+    Text(String),
+    /// A tool is about to run with these (credential-scrubbed) arguments
+    ToolCall {
+        name: String,
+        arguments: serde_json::Value,
+    },
+    /// A tool finished
+    ToolResult { name: String, success: bool },
+}
+
 /// Core channel trait — implement for any messaging platform
 #[async_trait]
 pub trait Channel: Send + Sync {
@@ -100,6 +114,17 @@ pub trait Channel: Send + Sync {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Report a tool call or result while a draft is streaming.
+    /// Chat platforms ignore these; structured clients can surface them.
+    async fn draft_event(
+        &self,
+        _recipient: &str,
+        _message_id: &str,
+        _event: &StreamEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -183,6 +208,17 @@ mod tests {
             .finalize_draft("bob", "msg_1", "final text")
             .await
             .is_ok());
+        assert!(channel
+            .draft_event(
+                "bob",
+                "msg_1",
+                &StreamEvent::ToolResult {
+                    name: "shell".into(),
+                    success: true,
+                }
+            )
+            .await
+            .is_ok());
     }
 
     #[tokio::test]
@@ -0,0 +1,715 @@
+//! Git checkpoints around agent mutations.
+//!
+//! When `autonomy.git_checkpoints` is enabled and the workspace lives in a git
+//! repository, the agent snapshots the working tree before the first mutating
+//! tool call of a turn and again once the turn completes. Snapshots are plain
+//! commits stored under `refs/zeroclaw/checkpoints/`, built through a throwaway
+//! index file with plumbing commands, so the user's branches, `HEAD` and
+//! staging area are never modified. Non-git workspaces are a silent no-op.
+
+use crate::config::Config;
+use crate::tools::Tool;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Ref namespace holding checkpoint commits.
+pub const CHECKPOINT_REF_PREFIX: &str = "refs/zeroclaw/checkpoints/";
+
+const CHECKPOINT_AUTHOR_NAME: &str = "ZeroClaw";
+const CHECKPOINT_AUTHOR_EMAIL: &str = "zeroclaw@localhost";
+
+// ── Types ────────────────────────────────────────────────────────
+
+/// A recorded checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Ref name below `refs/zeroclaw/checkpoints/` (UTC timestamp + short hash).
+    pub id: String,
+    /// Full commit hash.
+    pub commit: String,
+    /// Commit date (RFC 3339).
+    pub created_at: String,
+    /// First line of the checkpoint message.
+    pub summary: String,
+}
+
+/// How a file changed between two checkpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+/// A file touched between two checkpoints, with line counts (zero for binary files).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    /// Path relative to the repository root.
+    pub path: String,
+    pub kind: FileChangeKind,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+// ── Git plumbing ─────────────────────────────────────────────────
+
+fn git(dir: &Path, args: &[&str], index_file: Option<&Path>) -> Result<String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(dir).args(args);
+    if let Some(index) = index_file {
+        cmd.env("GIT_INDEX_FILE", index);
+    }
+    cmd.env("GIT_AUTHOR_NAME", CHECKPOINT_AUTHOR_NAME)
+        .env("GIT_AUTHOR_EMAIL", CHECKPOINT_AUTHOR_EMAIL)
+        .env("GIT_COMMITTER_NAME", CHECKPOINT_AUTHOR_NAME)
+        .env("GIT_COMMITTER_EMAIL", CHECKPOINT_AUTHOR_EMAIL);
+
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run git {}", args.join(" ")))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
+
+/// Top-level directory of the repository containing `workspace_dir`, if any.
+fn repo_root(workspace_dir: &Path) -> Option<PathBuf> {
+    git(workspace_dir, &["rev-parse", "--show-toplevel"], None)
+        .ok()
+        .filter(|root| !root.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Whether `workspace_dir` is inside a git work tree.
+pub fn is_git_workspace(workspace_dir: &Path) -> bool {
+    repo_root(workspace_dir).is_some()
+}
+
+/// A temporary index file inside `.git`, removed on drop.
+struct ScratchIndex(PathBuf);
+
+impl ScratchIndex {
+    fn new(root: &Path) -> Result<Self> {
+        let git_dir = PathBuf::from(git(root, &["rev-parse", "--absolute-git-dir"], None)?);
+        let nanos = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+        Ok(Self(git_dir.join(format!(
+            "zeroclaw-checkpoint-index-{}-{nanos}",
+            std::process::id()
+        ))))
+    }
+}
+
+impl Drop for ScratchIndex {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn snapshot(root: &Path, message: &str) -> Result<Checkpoint> {
+    let index = ScratchIndex::new(root)?;
+    let head = git(root, &["rev-parse", "--verify", "-q", "HEAD"], None).ok();
+
+    match head.as_deref() {
+        Some(head) => git(root, &["read-tree", head], Some(&index.0))?,
+        None => git(root, &["read-tree", "--empty"], Some(&index.0))?,
+    };
+    git(root, &["add", "-A"], Some(&index.0))?;
+    let tree = git(root, &["write-tree"], Some(&index.0))?;
+
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", message];
+    if let Some(head) = head.as_deref() {
+        args.extend(["-p", head]);
+    }
+    let commit = git(root, &args, None)?;
+
+    let id = format!(
+        "{}-{}",
+        Utc::now().format("%Y%m%dT%H%M%S%6f"),
+        &commit[..commit.len().min(8)]
+    );
+    git(
+        root,
+        &[
+            "update-ref",
+            &format!("{CHECKPOINT_REF_PREFIX}{id}"),
+            &commit,
+        ],
+        None,
+    )?;
+
+    Ok(Checkpoint {
+        id,
+        commit,
+        created_at: Utc::now().to_rfc3339(),
+        summary: message.lines().next().unwrap_or_default().to_string(),
+    })
+}
+
+/// Files changed between two commits, in path order.
+fn diff_commits(root: &Path, from: &str, to: &str) -> Result<Vec<FileChange>> {
+    let statuses = git(
+        root,
+        &[
+            "diff-tree",
+            "-r",
+            "-z",
+            "--no-renames",
+            "--name-status",
+            from,
+            to,
+        ],
+        None,
+    )?;
+    let numstat = git(
+        root,
+        &[
+            "diff-tree",
+            "-r",
+            "-z",
+            "--no-renames",
+            "--numstat",
+            from,
+            to,
+        ],
+        None,
+    )?;
+
+    // numstat -z: "<added>\t<removed>\t<path>\0" ("-" counts for binary files).
+    let mut counts = std::collections::HashMap::new();
+    for entry in numstat.split('\0').filter(|e| !e.is_empty()) {
+        let mut parts = entry.splitn(3, '\t');
+        let (Some(added), Some(removed), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        counts.insert(
+            path.to_string(),
+            (added.parse().unwrap_or(0), removed.parse().unwrap_or(0)),
+        );
+    }
+
+    // name-status -z: "<status>\0<path>\0" pairs.
+    let mut changes = Vec::new();
+    let mut fields = statuses.split('\0').filter(|f| !f.is_empty());
+    while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+        let kind = match status {
+            "A" => FileChangeKind::Created,
+            "D" => FileChangeKind::Deleted,
+            _ => FileChangeKind::Modified,
+        };
+        let (lines_added, lines_removed) = counts.get(path).copied().unwrap_or((0, 0));
+        changes.push(FileChange {
+            path: path.to_string(),
+            kind,
+            lines_added,
+            lines_removed,
+        });
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(changes)
+}
+
+// ── Public API ───────────────────────────────────────────────────
+
+/// Record the current working tree (tracked, modified and untracked files) as
+/// a checkpoint. Returns `Ok(None)` when the workspace is not a git repository.
+pub fn create_checkpoint(workspace_dir: &Path, message: &str) -> Result<Option<Checkpoint>> {
+    match repo_root(workspace_dir) {
+        Some(root) => snapshot(&root, message).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// List checkpoints, newest first. Empty for non-git workspaces.
+pub fn list_checkpoints(workspace_dir: &Path) -> Result<Vec<Checkpoint>> {
+    let Some(root) = repo_root(workspace_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let raw = git(
+        &root,
+        &[
+            "for-each-ref",
+            "--sort=-refname",
+            "--format=%(refname:lstrip=3)%09%(objectname)%09%(creatordate:iso-strict)%09%(contents:subject)",
+            CHECKPOINT_REF_PREFIX,
+        ],
+        None,
+    )?;
+
+    Ok(raw
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, '\t');
+            Some(Checkpoint {
+                id: parts.next()?.to_string(),
+                commit: parts.next()?.to_string(),
+                created_at: parts.next()?.to_string(),
+                summary: parts.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Restore the working tree to a checkpoint (matched by id or commit prefix).
+///
+/// The current state is checkpointed first so the restore itself can be
+/// undone. Files created after the checkpoint are removed; the user's index,
+/// branches and `HEAD` are left untouched.
+pub fn restore_checkpoint(workspace_dir: &Path, id: &str) -> Result<Checkpoint> {
+    let root = repo_root(workspace_dir)
+        .ok_or_else(|| anyhow::anyhow!("Workspace is not a git repository"))?;
+    let id = id.trim();
+    let target = list_checkpoints(&root)?
+        .into_iter()
+        .find(|cp| cp.id == id || (id.len() >= 7 && cp.commit.starts_with(id)))
+        .ok_or_else(|| anyhow::anyhow!("Checkpoint not found: {id}"))?;
+
+    let backup = snapshot(&root, &format!("zeroclaw: before restoring {}", target.id))?;
+
+    // Files present now but absent from the checkpoint were added afterwards.
+    let added = git(
+        &root,
+        &[
+            "diff-tree",
+            "-r",
+            "-z",
+            "--name-only",
+            "--no-renames",
+            "--diff-filter=A",
+            &target.commit,
+            &backup.commit,
+        ],
+        None,
+    )?;
+    for path in added.split('\0').filter(|p| !p.is_empty()) {
+        let _ = std::fs::remove_file(root.join(path));
+    }
+
+    let index = ScratchIndex::new(&root)?;
+    git(&root, &["read-tree", &target.commit], Some(&index.0))?;
+    git(&root, &["checkout-index", "-a", "-f"], Some(&index.0))?;
+
+    Ok(target)
+}
+
+// ── Per-turn hook ────────────────────────────────────────────────
+
+/// Checkpoint bookkeeping for a single agent turn: snapshot before the first
+/// mutating tool call, and again once the turn completes.
+pub struct TurnCheckpoints {
+    workspace_dir: PathBuf,
+    user_message: String,
+    started: AtomicBool,
+    tools_used: Mutex<Vec<String>>,
+    before: Mutex<Option<Checkpoint>>,
+}
+
+impl TurnCheckpoints {
+    /// Returns `None` unless `autonomy.git_checkpoints` is enabled and the
+    /// workspace is a git repository.
+    pub fn from_config(config: &Config, user_message: &str) -> Option<Self> {
+        if !config.autonomy.git_checkpoints {
+            return None;
+        }
+        Self::new(&config.workspace_dir, user_message)
+    }
+
+    pub fn new(workspace_dir: &Path, user_message: &str) -> Option<Self> {
+        if !is_git_workspace(workspace_dir) {
+            return None;
+        }
+        Some(Self {
+            workspace_dir: workspace_dir.to_path_buf(),
+            user_message: user_message.to_string(),
+            started: AtomicBool::new(false),
+            tools_used: Mutex::new(Vec::new()),
+            before: Mutex::new(None),
+        })
+    }
+
+    /// Call before executing `tool`; checkpoints once per turn on the first
+    /// tool that is not [read-only](Tool::is_read_only).
+    pub fn before_tool(&self, tool: &dyn Tool) {
+        let tool_name = tool.name();
+        {
+            let mut used = self.tools_used.lock();
+            if !used.iter().any(|t| t == tool_name) {
+                used.push(tool_name.to_string());
+            }
+        }
+
+        if tool.is_read_only() || self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let message = format!(
+            "zeroclaw: before {tool_name}\n\nUser: {}",
+            truncate_with_ellipsis(&self.user_message, 500)
+        );
+        match create_checkpoint(&self.workspace_dir, &message) {
+            Ok(cp) => *self.before.lock() = cp,
+            Err(e) => tracing::warn!("Failed to create pre-mutation checkpoint: {e}"),
+        }
+    }
+
+    /// Id of the pre-mutation checkpoint, once one was taken; restoring it
+    /// undoes the turn's file changes. Read it before [`Self::finish`].
+    pub fn before_id(&self) -> Option<String> {
+        self.before.lock().as_ref().map(|cp| cp.id.clone())
+    }
+
+    /// Call after the turn; records the post-turn checkpoint if anything
+    /// mutating ran and returns the files changed since the pre-mutation
+    /// checkpoint.
+    pub fn finish(&self) -> Vec<FileChange> {
+        if !self.started.load(Ordering::SeqCst) {
+            return Vec::new();
+        }
+
+        let tools = self.tools_used.lock().join(", ");
+        let message = format!(
+            "zeroclaw: after turn ({tools})\n\nUser: {}",
+            truncate_with_ellipsis(&self.user_message, 500)
+        );
+        let after = match create_checkpoint(&self.workspace_dir, &message) {
+            Ok(cp) => cp,
+            Err(e) => {
+                tracing::warn!("Failed to create post-turn checkpoint: {e}");
+                return Vec::new();
+            }
+        };
+
+        let (Some(before), Some(after)) = (self.before.lock().take(), after) else {
+            return Vec::new();
+        };
+        let Some(root) = repo_root(&self.workspace_dir) else {
+            return Vec::new();
+        };
+        diff_commits(&root, &before.commit, &after.commit).unwrap_or_else(|e| {
+            tracing::warn!("Failed to diff turn checkpoints: {e}");
+            Vec::new()
+        })
+    }
+}
+
+// ── CLI ──────────────────────────────────────────────────────────
+
+pub fn handle_command(command: crate::CheckpointCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::CheckpointCommands::List => {
+            if !is_git_workspace(&config.workspace_dir) {
+                println!(
+                    "Workspace {} is not a git repository; no checkpoints.",
+                    config.workspace_dir.display()
+                );
+                return Ok(());
+            }
+
+            let checkpoints = list_checkpoints(&config.workspace_dir)?;
+            if checkpoints.is_empty() {
+                println!("No checkpoints yet.");
+                println!("\nEnable with:");
+                println!("  [autonomy]");
+                println!("  git_checkpoints = true");
+                return Ok(());
+            }
+
+            println!("📌 Checkpoints ({}):", checkpoints.len());
+            for cp in checkpoints {
+                println!(
+                    "- {} | {} | {} | {}",
+                    cp.id,
+                    &cp.commit[..cp.commit.len().min(8)],
+                    cp.created_at,
+                    cp.summary
+                );
+            }
+            Ok(())
+        }
+        crate::CheckpointCommands::Restore { id } => {
+            let cp = restore_checkpoint(&config.workspace_dir, &id)?;
+            println!("✅ Restored workspace to checkpoint {}", cp.id);
+            println!("  {}", cp.summary);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolResult;
+    use tempfile::TempDir;
+
+    /// Stands in for a registered tool; only its name and read-only flag
+    /// matter to checkpoints.
+    struct FakeTool {
+        name: &'static str,
+        read_only: bool,
+    }
+
+    const FILE_READ: FakeTool = FakeTool {
+        name: "file_read",
+        read_only: true,
+    };
+    const FILE_WRITE: FakeTool = FakeTool {
+        name: "file_write",
+        read_only: false,
+    };
+    const SHELL: FakeTool = FakeTool {
+        name: "shell",
+        read_only: false,
+    };
+
+    #[async_trait::async_trait]
+    impl Tool for FakeTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "Test tool"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        fn is_read_only(&self) -> bool {
+            self.read_only
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<ToolResult> {
+            unreachable!("checkpoints never run tools")
+        }
+    }
+
+    fn run_git(dir: &Path, args: &[&str]) -> String {
+        git(dir, args, None).unwrap()
+    }
+
+    fn init_repo() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        run_git(tmp.path(), &["init", "-q"]);
+        std::fs::write(tmp.path().join("a.txt"), "one\n").unwrap();
+        run_git(tmp.path(), &["add", "a.txt"]);
+        run_git(tmp.path(), &["commit", "-q", "-m", "initial"]);
+        tmp
+    }
+
+    fn branch_state(dir: &Path) -> (String, String, String) {
+        (
+            run_git(dir, &["rev-parse", "HEAD"]),
+            run_git(dir, &["status", "--porcelain"]),
+            run_git(dir, &["diff", "--cached", "--name-only"]),
+        )
+    }
+
+    #[test]
+    fn non_git_workspace_is_noop() {
+        let tmp = TempDir::new().unwrap();
+        assert!(!is_git_workspace(tmp.path()));
+        assert!(create_checkpoint(tmp.path(), "msg").unwrap().is_none());
+        assert!(list_checkpoints(tmp.path()).unwrap().is_empty());
+        assert!(TurnCheckpoints::new(tmp.path(), "hi").is_none());
+    }
+
+    #[test]
+    fn clean_repo_checkpoint_matches_head_tree() {
+        let repo = init_repo();
+        let before = branch_state(repo.path());
+
+        let cp = create_checkpoint(repo.path(), "clean").unwrap().unwrap();
+
+        assert_eq!(branch_state(repo.path()), before);
+        assert_eq!(
+            run_git(
+                repo.path(),
+                &["rev-parse", &format!("{}^{{tree}}", cp.commit)]
+            ),
+            run_git(repo.path(), &["rev-parse", "HEAD^{tree}"])
+        );
+        let listed = list_checkpoints(repo.path()).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, cp.id);
+        assert_eq!(listed[0].commit, cp.commit);
+        assert_eq!(listed[0].summary, "clean");
+    }
+
+    #[test]
+    fn dirty_repo_checkpoint_captures_untracked_and_modified() {
+        let repo = init_repo();
+        std::fs::write(repo.path().join("a.txt"), "two\n").unwrap();
+        std::fs::write(repo.path().join("new.txt"), "fresh\n").unwrap();
+        let before = branch_state(repo.path());
+
+        let cp = create_checkpoint(repo.path(), "dirty").unwrap().unwrap();
+
+        assert_eq!(branch_state(repo.path()), before);
+        assert_eq!(
+            run_git(repo.path(), &["show", &format!("{}:a.txt", cp.commit)]),
+            "two"
+        );
+        assert_eq!(
+            run_git(repo.path(), &["show", &format!("{}:new.txt", cp.commit)]),
+            "fresh"
+        );
+    }
+
+    #[test]
+    fn staged_changes_are_left_staged() {
+        let repo = init_repo();
+        std::fs::write(repo.path().join("staged.txt"), "staged\n").unwrap();
+        run_git(repo.path(), &["add", "staged.txt"]);
+        std::fs::write(repo.path().join("a.txt"), "unstaged\n").unwrap();
+        let before = branch_state(repo.path());
+
+        let cp = create_checkpoint(repo.path(), "staged").unwrap().unwrap();
+
+        assert_eq!(branch_state(repo.path()), before);
+        assert_eq!(
+            run_git(repo.path(), &["diff", "--cached", "--name-only"]),
+            "staged.txt"
+        );
+        assert_eq!(
+            run_git(repo.path(), &["show", &format!("{}:staged.txt", cp.commit)]),
+            "staged"
+        );
+    }
+
+    #[test]
+    fn restore_rewinds_files_without_touching_head_or_index() {
+        let repo = init_repo();
+        std::fs::write(repo.path().join("staged.txt"), "staged\n").unwrap();
+        run_git(repo.path(), &["add", "staged.txt"]);
+        let cp = create_checkpoint(repo.path(), "before edits")
+            .unwrap()
+            .unwrap();
+        let head = run_git(repo.path(), &["rev-parse", "HEAD"]);
+
+        std::fs::write(repo.path().join("a.txt"), "mangled\n").unwrap();
+        std::fs::write(repo.path().join("later.txt"), "later\n").unwrap();
+
+        let restored = restore_checkpoint(repo.path(), &cp.id).unwrap();
+
+        assert_eq!(restored.commit, cp.commit);
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("a.txt")).unwrap(),
+            "one\n"
+        );
+        assert!(!repo.path().join("later.txt").exists());
+        assert_eq!(run_git(repo.path(), &["rev-parse", "HEAD"]), head);
+        assert_eq!(
+            run_git(repo.path(), &["diff", "--cached", "--name-only"]),
+            "staged.txt"
+        );
+        // The pre-restore state is itself recoverable.
+        assert_eq!(list_checkpoints(repo.path()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn restore_unknown_id_fails() {
+        let repo = init_repo();
+        assert!(restore_checkpoint(repo.path(), "nope").is_err());
+    }
+
+    #[test]
+    fn turn_checkpoints_skip_read_only_turns() {
+        let repo = init_repo();
+        let turn = TurnCheckpoints::new(repo.path(), "just look").unwrap();
+        turn.before_tool(&FILE_READ);
+        turn.finish();
+        assert!(list_checkpoints(repo.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn turn_checkpoints_record_before_and_after_mutation() {
+        let repo = init_repo();
+        let turn = TurnCheckpoints::new(repo.path(), "edit a.txt please").unwrap();
+        turn.before_tool(&FILE_READ);
+        turn.before_tool(&FILE_WRITE);
+        std::fs::write(repo.path().join("a.txt"), "edited\n").unwrap();
+        turn.before_tool(&SHELL);
+        turn.finish();
+
+        let checkpoints = list_checkpoints(repo.path()).unwrap();
+        assert_eq!(checkpoints.len(), 2);
+        let after = &checkpoints[0];
+        let before = &checkpoints[1];
+        assert_eq!(before.summary, "zeroclaw: before file_write");
+        assert_eq!(
+            after.summary,
+            "zeroclaw: after turn (file_read, file_write, shell)"
+        );
+        assert_eq!(
+            run_git(repo.path(), &["show", &format!("{}:a.txt", before.commit)]),
+            "one"
+        );
+        assert_eq!(
+            run_git(repo.path(), &["show", &format!("{}:a.txt", after.commit)]),
+            "edited"
+        );
+    }
+
+    #[test]
+    fn turn_checkpoints_report_file_changes() {
+        let repo = init_repo();
+        std::fs::write(repo.path().join("gone.txt"), "bye\n").unwrap();
+        run_git(repo.path(), &["add", "gone.txt"]);
+        run_git(repo.path(), &["commit", "-q", "-m", "add gone"]);
+
+        let turn = TurnCheckpoints::new(repo.path(), "reshuffle").unwrap();
+        turn.before_tool(&FILE_WRITE);
+        std::fs::write(repo.path().join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        std::fs::write(repo.path().join("new.txt"), "fresh\n").unwrap();
+        std::fs::remove_file(repo.path().join("gone.txt")).unwrap();
+
+        let changes = turn.finish();
+        assert_eq!(
+            changes,
+            vec![
+                FileChange {
+                    path: "a.txt".into(),
+                    kind: FileChangeKind::Modified,
+                    lines_added: 2,
+                    lines_removed: 0,
+                },
+                FileChange {
+                    path: "gone.txt".into(),
+                    kind: FileChangeKind::Deleted,
+                    lines_added: 0,
+                    lines_removed: 1,
+                },
+                FileChange {
+                    path: "new.txt".into(),
+                    kind: FileChangeKind::Created,
+                    lines_added: 1,
+                    lines_removed: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn read_only_turn_reports_no_file_changes() {
+        let repo = init_repo();
+        let turn = TurnCheckpoints::new(repo.path(), "look").unwrap();
+        turn.before_tool(&FILE_READ);
+        assert!(turn.finish().is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+//! `zeroclaw completions <shell>` and `zeroclaw examples`.
+//!
+//! Completion scripts are generated from the clap definitions by
+//! `clap_complete`. Bash and fish additionally complete `--provider`,
+//! `--workspace` and `--profile` values at completion time through the hidden
+//! `zeroclaw __complete <kind>` helper, which prints one candidate per line.
+//!
+//! Examples are written against argument ids rather than literal flags and
+//! rendered from the command definitions, so a renamed flag or subcommand
+//! fails the tests instead of printing a stale invocation.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use clap::{Command, ValueEnum};
+use clap_complete::Shell;
+use std::io::Write;
+
+/// Candidates `zeroclaw __complete` can list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionKind {
+    Providers,
+    Workspaces,
+    Profiles,
+}
+
+/// Flags whose values are completed dynamically, and what they complete to.
+const DYNAMIC_FLAGS: &[(&str, &str)] = &[
+    ("provider", "providers"),
+    ("workspace", "workspaces"),
+    ("profile", "profiles"),
+];
+
+/// Write the completion script for `shell` to `out`.
+pub fn generate(shell: Shell, cmd: &mut Command, out: &mut dyn Write) -> Result<()> {
+    let bin = cmd.get_name().to_string();
+    let mut script = Vec::new();
+    clap_complete::generate(shell, cmd, &bin, &mut script);
+    out.write_all(&script)?;
+    match shell {
+        Shell::Bash => out.write_all(bash_dynamic(&bin).as_bytes())?,
+        Shell::Fish => out.write_all(fish_dynamic(&bin).as_bytes())?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Wrap the generated `_<bin>` function so dynamic flags ask the binary.
+fn bash_dynamic(bin: &str) -> String {
+    let mut cases = String::new();
+    for (flag, kind) in DYNAMIC_FLAGS {
+        cases.push_str(&format!("        --{flag}) kind={kind} ;;\n"));
+    }
+    format!(
+        r#"
+_{bin}_dynamic() {{
+    local cur prev kind
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    case "$prev" in
+{cases}        *) _{bin} "$@"; return ;;
+    esac
+    COMPREPLY=( $(compgen -W "$({bin} __complete "$kind" 2>/dev/null)" -- "$cur") )
+}}
+complete -F _{bin}_dynamic -o bashdefault -o default {bin}
+"#
+    )
+}
+
+fn fish_dynamic(bin: &str) -> String {
+    let mut script = String::from("\n");
+    for (flag, kind) in DYNAMIC_FLAGS {
+        script.push_str(&format!(
+            "complete -c {bin} -l {flag} -x -a '({bin} __complete {kind} 2>/dev/null)'\n"
+        ));
+    }
+    script
+}
+
+/// Candidates for `zeroclaw __complete <kind>`.
+pub fn candidates(kind: CompletionKind, config: &Config) -> Vec<String> {
+    match kind {
+        CompletionKind::Providers => crate::providers::list_providers()
+            .into_iter()
+            .map(|provider| provider.name.to_string())
+            .collect(),
+        CompletionKind::Workspaces => {
+            let mut names: Vec<String> = config.workspaces.keys().cloned().collect();
+            names.sort();
+            names
+        }
+        CompletionKind::Profiles => {
+            let Ok(data) = crate::auth::AuthService::from_config(config).load_profiles() else {
+                return Vec::new();
+            };
+            let mut names: Vec<String> = data
+                .profiles
+                .values()
+                .map(|profile| profile.profile_name.clone())
+                .collect();
+            names.sort();
+            names.dedup();
+            names
+        }
+    }
+}
+
+/// A runnable invocation shown by `zeroclaw examples`.
+struct Example {
+    summary: &'static str,
+    /// Shell text piped into the command, if any
+    stdin: Option<&'static str>,
+    /// Subcommand path, e.g. `["auth", "login"]`
+    path: &'static [&'static str],
+    /// Argument id and value; `None` for flags that take no value
+    args: &'static [(&'static str, Option<&'static str>)],
+}
+
+const EXAMPLES: &[Example] = &[
+    Example {
+        summary: "Ask a single question and exit",
+        stdin: None,
+        path: &["agent"],
+        args: &[("message", Some("Summarize README.md in three bullets"))],
+    },
+    Example {
+        summary: "Print only the answer, e.g. for scripts",
+        stdin: None,
+        path: &["agent"],
+        args: &[
+            ("message", Some("Which files changed today?")),
+            ("quiet", None),
+        ],
+    },
+    Example {
+        summary: "Pipe messages in; each line is one turn",
+        stdin: Some("echo \"What is in this directory?\""),
+        path: &["agent"],
+        args: &[("quiet", None)],
+    },
+    Example {
+        summary: "Pick a provider and model for one run",
+        stdin: None,
+        path: &["agent"],
+        args: &[
+            ("provider", Some("anthropic")),
+            ("model", Some("claude-sonnet-4")),
+            ("message", Some("Review src/main.rs")),
+        ],
+    },
+    Example {
+        summary: "Resume the most recent session",
+        stdin: None,
+        path: &["agent"],
+        args: &[("continue_session", None)],
+    },
+    Example {
+        summary: "Run one prompt template over a JSONL file",
+        stdin: None,
+        path: &["batch"],
+        args: &[
+            ("template", Some("prompt.txt")),
+            ("input", Some("items.jsonl")),
+            ("concurrency", Some("8")),
+        ],
+    },
+    Example {
+        summary: "Log in with an OpenAI Codex subscription",
+        stdin: None,
+        path: &["auth", "login"],
+        args: &[("provider", Some("openai-codex"))],
+    },
+    Example {
+        summary: "Paste an Anthropic setup token",
+        stdin: None,
+        path: &["auth", "paste-token"],
+        args: &[("provider", Some("anthropic"))],
+    },
+    Example {
+        summary: "Write a starter config that reads the API key from the environment",
+        stdin: None,
+        path: &["config", "init"],
+        args: &[
+            ("provider", Some("anthropic")),
+            ("api_key_location", Some("env")),
+        ],
+    },
+    Example {
+        summary: "Show the config a run will use, secrets redacted",
+        stdin: None,
+        path: &["export-config"],
+        args: &[("format", Some("json"))],
+    },
+];
+
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ':'))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// Argument vector for `example` (without the binary name), using the flag
+/// names from `root`. Fails if a subcommand or argument id no longer exists.
+fn example_argv(root: &Command, example: &Example) -> Result<Vec<String>> {
+    let mut cmd = root;
+    let mut argv = Vec::new();
+    for name in example.path {
+        cmd = cmd
+            .find_subcommand(name)
+            .with_context(|| format!("no subcommand '{name}'"))?;
+        argv.push(cmd.get_name().to_string());
+    }
+    for (id, value) in example.args {
+        let arg = cmd
+            .get_arguments()
+            .find(|arg| arg.get_id() == *id)
+            .with_context(|| format!("'{}' has no argument '{id}'", cmd.get_name()))?;
+        if let Some(long) = arg.get_long() {
+            argv.push(format!("--{long}"));
+        } else if let Some(short) = arg.get_short() {
+            argv.push(format!("-{short}"));
+        }
+        if let Some(value) = value {
+            argv.push((*value).to_string());
+        }
+    }
+    Ok(argv)
+}
+
+/// The `zeroclaw examples` listing.
+pub fn render_examples(root: &Command) -> Result<String> {
+    let bin = root.get_name();
+    let mut out = String::new();
+    for example in EXAMPLES {
+        let argv = example_argv(root, example)?;
+        let mut line = String::new();
+        if let Some(stdin) = example.stdin {
+            line.push_str(stdin);
+            line.push_str(" | ");
+        }
+        line.push_str(bin);
+        for arg in &argv {
+            line.push(' ');
+            line.push_str(&shell_quote(arg));
+        }
+        out.push_str(&format!("# {}\n{line}\n\n", example.summary));
+    }
+    Ok(out.trim_end().to_string())
+}
+
+/// Every example as the full argument vector clap would receive, for
+/// checking that each one still parses.
+#[cfg(test)]
+pub fn example_invocations(root: &Command) -> Result<Vec<Vec<String>>> {
+    EXAMPLES
+        .iter()
+        .map(|example| {
+            let mut argv = vec![root.get_name().to_string()];
+            argv.extend(example_argv(root, example)?);
+            Ok(argv)
+        })
+        .collect()
+}
@@ -0,0 +1,165 @@
+//! `zeroclaw export-config`: print the fully resolved configuration.
+//!
+//! The dump reflects the config after env overrides and workspace profile
+//! merges, so it shows what a run will actually use. Secret values are
+//! replaced with `***` so the output can be pasted into bug reports.
+
+use super::Config;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde_json::Value;
+
+/// Output format for `zeroclaw export-config`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Toml,
+    Json,
+    Yaml,
+}
+
+const REDACTED: &str = "***";
+
+/// Key suffixes whose values are credentials. `_key` also matches counts
+/// such as `rate_limit_max_keys`; only string values are redacted, so those
+/// numbers are left alone.
+const SECRET_SUFFIXES: &[&str] = &[
+    "_key", "_keys", "token", "tokens", "secret", "password", "db_url",
+];
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_SUFFIXES.iter().any(|suffix| key.ends_with(suffix))
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                if is_secret_key(key) {
+                    redact_secret(entry);
+                } else {
+                    redact(entry);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn redact_secret(value: &mut Value) {
+    match value {
+        Value::String(s) if !s.is_empty() => *s = REDACTED.to_string(),
+        Value::Array(items) => items.iter_mut().for_each(redact_secret),
+        _ => {}
+    }
+}
+
+/// TOML has no null; unset options are simply absent.
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, entry| !entry.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+/// Validate `config` and render it in `format` with secrets redacted.
+pub fn export(config: &Config, format: ExportFormat) -> Result<String> {
+    config.proxy.validate()?;
+    let mut value = serde_json::to_value(config).context("Failed to serialize config")?;
+    redact(&mut value);
+    let rendered = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&value)?,
+        ExportFormat::Yaml => serde_norway::to_string(&value)?,
+        ExportFormat::Toml => {
+            strip_nulls(&mut value);
+            toml::to_string_pretty(&value).context("Failed to render config as TOML")?
+        }
+    };
+    Ok(rendered.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::LarkReceiveMode;
+    use crate::config::LarkConfig;
+
+    fn config_with_secrets() -> Config {
+        let mut config = Config::default();
+        config.api_key = Some("sk-live-123".into());
+        config.default_model = Some("gpt-4o".into());
+        config.reliability.api_keys = vec!["sk-extra".into()];
+        config
+    }
+
+    #[test]
+    fn every_format_round_trips_and_redacts() {
+        let config = config_with_secrets();
+        for format in [ExportFormat::Toml, ExportFormat::Json, ExportFormat::Yaml] {
+            let out = export(&config, format).unwrap();
+            assert!(!out.contains("sk-live-123"), "{format:?} leaked api_key");
+            assert!(!out.contains("sk-extra"), "{format:?} leaked api_keys");
+            let value: Value = match format {
+                ExportFormat::Toml => {
+                    serde_json::to_value(toml::from_str::<toml::Value>(&out).unwrap()).unwrap()
+                }
+                ExportFormat::Json => serde_json::from_str(&out).unwrap(),
+                ExportFormat::Yaml => serde_norway::from_str(&out).unwrap(),
+            };
+            assert_eq!(value["api_key"], "***");
+            assert_eq!(value["default_model"], "gpt-4o");
+        }
+    }
+
+    #[test]
+    fn key_fields_are_redacted() {
+        let mut config = Config::default();
+        config.observability.audit_hmac_key = Some("hmac-secret".into());
+        config.channels_config.lark = Some(LarkConfig {
+            app_id: "cli_123".into(),
+            app_secret: "lark-secret".into(),
+            encrypt_key: Some("lark-encrypt".into()),
+            verification_token: None,
+            allowed_users: Vec::new(),
+            use_feishu: false,
+            receive_mode: LarkReceiveMode::default(),
+            port: None,
+        });
+        let out = export(&config, ExportFormat::Json).unwrap();
+        let value: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(value["observability"]["audit_hmac_key"], "***");
+        let lark = &value["channels_config"]["lark"];
+        assert_eq!(lark["encrypt_key"], "***");
+        assert_eq!(lark["app_secret"], "***");
+        assert_eq!(lark["app_id"], "cli_123");
+        assert_eq!(
+            value["gateway"]["rate_limit_max_keys"],
+            config.gateway.rate_limit_max_keys
+        );
+    }
+
+    #[test]
+    fn exported_toml_parses_back_into_config() {
+        let out = export(&Config::default(), ExportFormat::Toml).unwrap();
+        let parsed: Config = toml::from_str(&out).unwrap();
+        assert_eq!(parsed.default_provider, Config::default().default_provider);
+    }
+
+    #[test]
+    fn secret_keys_are_matched_by_suffix() {
+        assert!(is_secret_key("bot_token"));
+        assert!(is_secret_key("brave_api_key"));
+        assert!(is_secret_key("webhook_secret"));
+        assert!(is_secret_key("paired_tokens"));
+        assert!(is_secret_key("encrypt_key"));
+        assert!(is_secret_key("audit_hmac_key"));
+        assert!(!is_secret_key("max_tokens_hint"));
+        assert!(!is_secret_key("auth_header"));
+    }
+}
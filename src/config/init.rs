@@ -0,0 +1,319 @@
+//! `zeroclaw config init`: write a starter `config.toml`.
+//!
+//! Asks for the provider, model, where the API key lives and the config
+//! directory (or takes them as flags), then writes a config and reads it
+//! back through [`Config::read_from`], the same parsing `load_or_init` uses,
+//! so a file that would not load is reported here rather than on the next
+//! run. An existing config is only replaced with `--force`.
+
+use super::Config;
+use crate::onboard::wizard::{default_model_for_provider, provider_env_var};
+use anyhow::{bail, Context, Result};
+use dialoguer::{Input, Password, Select};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_PROVIDER: &str = "openrouter";
+
+/// Where the provider API key is kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiKeySource {
+    /// Read from the provider's environment variable at run time.
+    Env,
+    /// Stored in `config.toml`, encrypted when `[secrets] encrypt` is on.
+    Config(String),
+}
+
+/// Flags given to `zeroclaw config init`; anything unset is asked for on a
+/// terminal and defaulted otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    /// `env` or `config`.
+    pub api_key_location: Option<String>,
+    pub api_key: Option<String>,
+    pub dir: Option<PathBuf>,
+    pub force: bool,
+}
+
+/// Resolved answers for a starter config.
+#[derive(Debug, Clone)]
+pub struct InitAnswers {
+    pub provider: String,
+    pub model: String,
+    pub api_key: ApiKeySource,
+    /// Directory holding `config.toml` and `workspace/`.
+    pub config_dir: PathBuf,
+}
+
+/// The default config with `answers` filled in.
+pub fn starter_config(answers: &InitAnswers) -> Config {
+    Config {
+        config_path: answers.config_dir.join("config.toml"),
+        workspace_dir: answers.config_dir.join("workspace"),
+        default_provider: Some(answers.provider.clone()),
+        default_model: Some(answers.model.clone()),
+        api_key: match &answers.api_key {
+            ApiKeySource::Env => None,
+            ApiKeySource::Config(key) => Some(key.clone()),
+        },
+        ..Config::default()
+    }
+}
+
+fn is_known_provider(name: &str) -> bool {
+    name.starts_with("custom:")
+        || name.starts_with("anthropic-custom:")
+        || crate::providers::list_providers().into_iter().any(|p| {
+            p.name.eq_ignore_ascii_case(name)
+                || p.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+        })
+}
+
+fn validate(config: &Config) -> Result<()> {
+    let provider = config.default_provider.as_deref().unwrap_or_default();
+    if !is_known_provider(provider) {
+        bail!("Unknown provider '{provider}'; run `zeroclaw providers` to list them");
+    }
+    if config
+        .default_model
+        .as_deref()
+        .is_none_or(|model| model.trim().is_empty())
+    {
+        bail!("A model is required");
+    }
+    config.proxy.validate()
+}
+
+fn refuse_existing(config_path: &Path, force: bool) -> Result<()> {
+    if config_path.exists() && !force {
+        bail!(
+            "{} already exists; pass --force to overwrite it (the old file is kept as config.toml.bak)",
+            config_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Write the starter config for `answers` and return it as read back from
+/// disk. Fails without writing if the config exists and `force` is off.
+pub fn write(answers: &InitAnswers, force: bool) -> Result<Config> {
+    let config = starter_config(answers);
+    validate(&config)?;
+    refuse_existing(&config.config_path, force)?;
+
+    fs::create_dir_all(&config.workspace_dir).with_context(|| {
+        format!(
+            "Failed to create workspace directory {}",
+            config.workspace_dir.display()
+        )
+    })?;
+    config.save()?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&config.config_path, fs::Permissions::from_mode(0o600));
+    }
+
+    let loaded = Config::read_from(&config.config_path, config.workspace_dir.clone())
+        .context("The written config does not load")?;
+    validate(&loaded)?;
+    if loaded.default_provider != config.default_provider
+        || loaded.default_model != config.default_model
+        || loaded.api_key != config.api_key
+    {
+        bail!(
+            "{} did not read back as written",
+            config.config_path.display()
+        );
+    }
+    Ok(loaded)
+}
+
+fn resolve_answers(options: InitOptions, interactive: bool) -> Result<InitAnswers> {
+    let config_dir = match options.dir {
+        Some(dir) => PathBuf::from(shellexpand::tilde(&dir.to_string_lossy()).as_ref()),
+        None => {
+            let default_dir = Config::resolve_dirs()?.0;
+            if interactive {
+                let dir: String = Input::new()
+                    .with_prompt("Config directory")
+                    .default(default_dir.display().to_string())
+                    .interact_text()?;
+                PathBuf::from(shellexpand::tilde(&dir).as_ref())
+            } else {
+                default_dir
+            }
+        }
+    };
+    refuse_existing(&config_dir.join("config.toml"), options.force)?;
+
+    let provider = match options.provider {
+        Some(provider) => provider,
+        None if interactive => Input::new()
+            .with_prompt("Provider (`zeroclaw providers` lists them)")
+            .default(DEFAULT_PROVIDER.to_string())
+            .interact_text()?,
+        None => DEFAULT_PROVIDER.to_string(),
+    };
+    let model = match options.model {
+        Some(model) => model,
+        None if interactive => Input::new()
+            .with_prompt("Model")
+            .default(default_model_for_provider(&provider))
+            .interact_text()?,
+        None => default_model_for_provider(&provider),
+    };
+
+    let env_var = provider_env_var(&provider);
+    let location = match (options.api_key_location, &options.api_key) {
+        (Some(location), _) => location,
+        (None, Some(_)) => "config".to_string(),
+        (None, None) if interactive => {
+            let choice = Select::new()
+                .with_prompt("API key location")
+                .items(&[
+                    format!("Environment variable ({env_var})"),
+                    "Stored in config.toml (encrypted)".to_string(),
+                ])
+                .default(0)
+                .interact()?;
+            if choice == 0 { "env" } else { "config" }.to_string()
+        }
+        (None, None) => "env".to_string(),
+    };
+    let api_key = match location.as_str() {
+        "env" => ApiKeySource::Env,
+        "config" => match options.api_key {
+            Some(key) => ApiKeySource::Config(key),
+            None if interactive => ApiKeySource::Config(
+                Password::new()
+                    .with_prompt(format!("{provider} API key"))
+                    .interact()?,
+            ),
+            None => bail!("--api-key-location config needs --api-key"),
+        },
+        other => bail!("Unknown API key location '{other}'; use env or config"),
+    };
+
+    Ok(InitAnswers {
+        provider,
+        model,
+        api_key,
+        config_dir,
+    })
+}
+
+/// Handle `zeroclaw config init`.
+pub fn run(options: InitOptions) -> Result<()> {
+    let interactive = std::io::stdin().is_terminal();
+    let force = options.force;
+    let answers = resolve_answers(options, interactive)?;
+    let config = write(&answers, force)?;
+    super::schema::persist_active_workspace_config_dir(&answers.config_dir)?;
+
+    crate::term::println(format!("✅ Wrote {}", config.config_path.display()));
+    crate::term::println(format!("  Provider:  {}", answers.provider));
+    crate::term::println(format!("  Model:     {}", answers.model));
+    crate::term::println(format!(
+        "  API key:   {}",
+        match answers.api_key {
+            ApiKeySource::Env => format!("read from {}", provider_env_var(&answers.provider)),
+            ApiKeySource::Config(_) => "stored in config.toml".to_string(),
+        }
+    ));
+    crate::term::println(format!("  Workspace: {}", config.workspace_dir.display()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn answers(dir: &Path) -> InitAnswers {
+        InitAnswers {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4-5-20250929".into(),
+            api_key: ApiKeySource::Config("sk-test".into()),
+            config_dir: dir.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn init_writes_a_config_that_loads_and_validates() {
+        let tmp = TempDir::new().unwrap();
+        let config = write(&answers(tmp.path()), false).unwrap();
+
+        assert_eq!(config.default_provider.as_deref(), Some("anthropic"));
+        assert_eq!(config.api_key.as_deref(), Some("sk-test"));
+        assert!(config.workspace_dir.is_dir());
+
+        let on_disk = fs::read_to_string(tmp.path().join("config.toml")).unwrap();
+        assert!(!on_disk.contains("sk-test"), "API key must be encrypted");
+        let reloaded = Config::read_from(
+            &tmp.path().join("config.toml"),
+            tmp.path().join("workspace"),
+        )
+        .unwrap();
+        assert_eq!(
+            reloaded.default_model.as_deref(),
+            Some("claude-sonnet-4-5-20250929")
+        );
+        assert_eq!(reloaded.api_key.as_deref(), Some("sk-test"));
+        validate(&reloaded).unwrap();
+    }
+
+    #[test]
+    fn overwriting_requires_force() {
+        let tmp = TempDir::new().unwrap();
+        write(&answers(tmp.path()), false).unwrap();
+
+        let env_only = InitAnswers {
+            api_key: ApiKeySource::Env,
+            model: "claude-opus-4-1".into(),
+            ..answers(tmp.path())
+        };
+        let err = write(&env_only, false).unwrap_err();
+        assert!(err.to_string().contains("--force"), "{err}");
+        let kept = Config::read_from(&tmp.path().join("config.toml"), tmp.path().into()).unwrap();
+        assert_eq!(kept.api_key.as_deref(), Some("sk-test"));
+
+        let replaced = write(&env_only, true).unwrap();
+        assert_eq!(replaced.default_model.as_deref(), Some("claude-opus-4-1"));
+        assert_eq!(replaced.api_key, None);
+        assert!(tmp.path().join("config.toml.bak").exists());
+    }
+
+    #[test]
+    fn unknown_providers_and_missing_keys_are_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let bogus = InitAnswers {
+            provider: "not-a-provider".into(),
+            ..answers(tmp.path())
+        };
+        assert!(write(&bogus, false).is_err());
+        assert!(!tmp.path().join("config.toml").exists());
+
+        let options = InitOptions {
+            api_key_location: Some("config".into()),
+            dir: Some(tmp.path().to_path_buf()),
+            ..InitOptions::default()
+        };
+        let err = resolve_answers(options, false).unwrap_err();
+        assert!(err.to_string().contains("--api-key"), "{err}");
+
+        let defaults = resolve_answers(
+            InitOptions {
+                dir: Some(tmp.path().to_path_buf()),
+                ..InitOptions::default()
+            },
+            false,
+        )
+        .unwrap();
+        assert_eq!(defaults.provider, DEFAULT_PROVIDER);
+        assert_eq!(defaults.api_key, ApiKeySource::Env);
+    }
+}
@@ -0,0 +1,130 @@
+//! Upgrades of older `config.toml` layouts to the current schema.
+//!
+//! Each file records the schema it was written for in `config_version`
+//! (files from before the field existed are version 1). On load, a file older
+//! than [`CURRENT_CONFIG_VERSION`] is passed through one migration step per
+//! version, on the raw TOML, before it is deserialized. Adding a schema change
+//! means bumping the constant and adding a `vN_to_vN+1` step to
+//! [`ConfigMigrator::migrate`].
+
+use anyhow::{bail, Result};
+use toml::{Table, Value};
+
+/// Schema version written by this build.
+pub const CURRENT_CONFIG_VERSION: u32 = 2;
+
+/// Version assumed for files without a `config_version` key.
+const UNVERSIONED: u32 = 1;
+
+pub struct ConfigMigrator;
+
+impl ConfigMigrator {
+    /// The `config_version` recorded in `raw`.
+    pub fn version_of(raw: &Value) -> u32 {
+        raw.get("config_version")
+            .and_then(Value::as_integer)
+            .and_then(|version| u32::try_from(version).ok())
+            .unwrap_or(UNVERSIONED)
+    }
+
+    /// Apply every step from `from` up to [`CURRENT_CONFIG_VERSION`] and
+    /// stamp the result with the current version.
+    pub fn migrate(from: u32, raw: Value) -> Result<Value> {
+        let Value::Table(mut table) = raw else {
+            bail!("Config file must be a TOML table");
+        };
+        if from > CURRENT_CONFIG_VERSION {
+            bail!(
+                "Config version {from} is newer than this build supports ({CURRENT_CONFIG_VERSION}); upgrade zeroclaw"
+            );
+        }
+        for version in from.max(UNVERSIONED)..CURRENT_CONFIG_VERSION {
+            match version {
+                1 => v1_to_v2(&mut table),
+                other => bail!("No config migration from version {other}"),
+            }
+        }
+        table.insert(
+            "config_version".into(),
+            Value::Integer(CURRENT_CONFIG_VERSION.into()),
+        );
+        Ok(Value::Table(table))
+    }
+}
+
+/// v1 → v2: `[agent] max_iterations` became `max_tool_iterations`, and the
+/// previously mandatory `default_temperature` gets its default when missing.
+fn v1_to_v2(table: &mut Table) {
+    if let Some(Value::Table(agent)) = table.get_mut("agent") {
+        if let Some(value) = agent.remove("max_iterations") {
+            agent.entry("max_tool_iterations").or_insert(value);
+        }
+    }
+    table
+        .entry("default_temperature")
+        .or_insert(Value::Float(0.7));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn unversioned_file_is_version_one() {
+        let raw: Value = toml::from_str("default_temperature = 0.5").unwrap();
+        assert_eq!(ConfigMigrator::version_of(&raw), 1);
+    }
+
+    #[test]
+    fn v1_renames_keys_and_fills_defaults() {
+        let raw: Value = toml::from_str(
+            r#"
+default_provider = "openrouter"
+[agent]
+max_iterations = 25
+"#,
+        )
+        .unwrap();
+        let migrated = ConfigMigrator::migrate(1, raw).unwrap();
+        assert_eq!(
+            ConfigMigrator::version_of(&migrated),
+            CURRENT_CONFIG_VERSION
+        );
+        assert!(migrated["agent"].get("max_iterations").is_none());
+
+        let config: Config = migrated.try_into().unwrap();
+        assert_eq!(config.agent.max_tool_iterations, 25);
+        assert!((config.default_temperature - 0.7).abs() < f64::EPSILON);
+        assert_eq!(config.default_provider.as_deref(), Some("openrouter"));
+    }
+
+    #[test]
+    fn explicit_new_key_wins_over_the_legacy_one() {
+        let raw: Value = toml::from_str(
+            r#"
+default_temperature = 0.2
+[agent]
+max_iterations = 25
+max_tool_iterations = 8
+"#,
+        )
+        .unwrap();
+        let migrated = ConfigMigrator::migrate(1, raw).unwrap();
+        assert_eq!(
+            migrated["agent"]["max_tool_iterations"].as_integer(),
+            Some(8)
+        );
+        assert_eq!(migrated["default_temperature"].as_float(), Some(0.2));
+    }
+
+    #[test]
+    fn current_version_is_unchanged_and_newer_is_rejected() {
+        let raw: Value = toml::from_str("config_version = 2\ndefault_temperature = 0.3").unwrap();
+        let migrated = ConfigMigrator::migrate(2, raw.clone()).unwrap();
+        assert_eq!(migrated, raw);
+
+        let err = ConfigMigrator::migrate(CURRENT_CONFIG_VERSION + 1, raw).unwrap_err();
+        assert!(err.to_string().contains("newer than this build"));
+    }
+}
@@ -1,3 +1,6 @@
+pub mod export;
+pub mod init;
+pub mod migrate;
 pub mod schema;
 
 #[allow(unused_imports)]
@@ -6,14 +9,17 @@ pub use schema::{
     build_runtime_proxy_client_with_timeouts, runtime_proxy_config, set_runtime_proxy_config,
     AgentConfig, AuditConfig, AutonomyConfig, BrowserComputerUseConfig, BrowserConfig,
     ChannelsConfig, ClassificationRule, ComposioConfig, Config, CostConfig, CronConfig,
-    DelegateAgentConfig, DiscordConfig, DockerRuntimeConfig, GatewayConfig, HardwareConfig,
-    HardwareTransport, HeartbeatConfig, HttpRequestConfig, IMessageConfig, IdentityConfig,
-    LarkConfig, MatrixConfig, MemoryConfig, ModelRouteConfig, ObservabilityConfig,
-    PeripheralBoardConfig, PeripheralsConfig, ProxyConfig, ProxyScope, QueryClassificationConfig,
-    ReliabilityConfig, ResourceLimitsConfig, RuntimeConfig, SandboxBackend, SandboxConfig,
-    SchedulerConfig, SecretsConfig, SecurityConfig, SlackConfig, StorageConfig,
-    StorageProviderConfig, StorageProviderSection, StreamMode, TelegramConfig, TunnelConfig,
-    WebSearchConfig, WebhookConfig,
+    CustomProviderConfig, DelegateAgentConfig, DiscordConfig, DockerRuntimeConfig, GatewayConfig,
+    HardwareConfig, HardwareTransport, HeartbeatConfig, HooksConfig, HttpRequestConfig,
+    IMessageConfig, IdentityConfig, InjectionFilterMode, LarkConfig, LimitsConfig, MatrixConfig,
+    MemoryConfig, MemoryNamespace, ModelRouteConfig, ModelRouterConfig, ObservabilityConfig,
+    PeripheralBoardConfig, PeripheralsConfig, ProviderConfig, ProviderHeaderOverrides, ProxyConfig,
+    ProxyScope, QueryClassificationConfig, RecallScope, ReliabilityConfig, ResourceLimitsConfig,
+    ResponseCacheConfig, RouterRole, RoutingConfig, RoutingRule, RuntimeConfig, SandboxBackend,
+    SandboxConfig, SchedulerConfig, SecretsConfig, SecurityConfig, SessionOverflow, SkillsConfig,
+    SlackConfig, StorageConfig, StorageProviderConfig, StorageProviderSection, StreamMode,
+    TelegramConfig, TemperatureMode, TunnelConfig, WebSearchConfig, WebhookConfig,
+    WorkspaceProfile,
 };
 
 #[cfg(test)]
@@ -1,4 +1,6 @@
+use super::migrate::{ConfigMigrator, CURRENT_CONFIG_VERSION};
 use crate::providers::{is_glm_alias, is_zai_alias};
+use crate::security::keychain::{self, SecretRef};
 use crate::security::AutonomyLevel;
 use anyhow::{Context, Result};
 use directories::UserDirs;
@@ -34,10 +36,17 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "tool.pushover",
     "memory.embeddings",
     "tunnel.custom",
+    "hook.post_run",
 ];
 
-const SUPPORTED_PROXY_SERVICE_SELECTORS: &[&str] =
-    &["provider.*", "channel.*", "tool.*", "memory.*", "tunnel.*"];
+const SUPPORTED_PROXY_SERVICE_SELECTORS: &[&str] = &[
+    "provider.*",
+    "channel.*",
+    "tool.*",
+    "memory.*",
+    "tunnel.*",
+    "hook.*",
+];
 
 static RUNTIME_PROXY_CONFIG: OnceLock<RwLock<ProxyConfig>> = OnceLock::new();
 static RUNTIME_PROXY_CLIENT_CACHE: OnceLock<RwLock<HashMap<String, reqwest::Client>>> =
@@ -53,6 +62,14 @@ pub struct Config {
     /// Path to config.toml - computed from home, not serialized
     #[serde(skip)]
     pub config_path: PathBuf,
+    /// Schema version the file was written for; older files are upgraded
+    /// by [`super::migrate::ConfigMigrator`] on load
+    #[serde(default = "default_config_version")]
+    pub config_version: u32,
+    /// Values loaded from `"$secret:<name>"` keychain placeholders; saved
+    /// back as the placeholder while unchanged
+    #[serde(skip)]
+    pub keychain_secrets: Vec<SecretRef>,
     pub api_key: Option<String>,
     /// Base URL override for provider API (e.g. "http://10.0.0.1:11434" for remote Ollama)
     pub api_url: Option<String>,
@@ -72,6 +89,9 @@ pub struct Config {
     #[serde(default)]
     pub reliability: ReliabilityConfig,
 
+    #[serde(default)]
+    pub provider: ProviderConfig,
+
     #[serde(default)]
     pub scheduler: SchedulerConfig,
 
@@ -86,6 +106,11 @@ pub struct Config {
     #[serde(default)]
     pub query_classification: QueryClassificationConfig,
 
+    /// Per-message routing rules — pick a provider, model and temperature
+    /// from properties of each user message.
+    #[serde(default)]
+    pub routing: RoutingConfig,
+
     #[serde(default)]
     pub heartbeat: HeartbeatConfig,
 
@@ -131,6 +156,10 @@ pub struct Config {
     #[serde(default)]
     pub cost: CostConfig,
 
+    /// Hard per-session spend limits enforced by the agent loop.
+    #[serde(default)]
+    pub limits: LimitsConfig,
+
     #[serde(default)]
     pub peripherals: PeripheralsConfig,
 
@@ -138,9 +167,107 @@ pub struct Config {
     #[serde(default)]
     pub agents: HashMap<String, DelegateAgentConfig>,
 
+    /// Named workspaces selectable with `--workspace <name>` or `/workspace <name>`.
+    #[serde(default)]
+    pub workspaces: HashMap<String, WorkspaceProfile>,
+
     /// Hardware configuration (wizard-driven physical world setup).
     #[serde(default)]
     pub hardware: HardwareConfig,
+
+    #[serde(default)]
+    pub skills: SkillsConfig,
+
+    #[serde(default)]
+    pub hooks: HooksConfig,
+}
+
+// ── Hooks ────────────────────────────────────────────────────────
+
+/// Notifications fired when a `zeroclaw agent` run finishes (`[hooks]`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// URL that receives the run report as a JSON POST
+    #[serde(default)]
+    pub post_run_url: Option<String>,
+    /// Shell command run with the report in `ZEROCLAW_RUN_REPORT`; subject
+    /// to the `[autonomy]` command policy
+    #[serde(default)]
+    pub post_run_command: Option<String>,
+    /// Retries after a failed delivery (default: 2)
+    #[serde(default = "default_hook_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further retry (default: 500)
+    #[serde(default = "default_hook_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Timeout per delivery attempt in seconds (default: 10)
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_hook_max_retries() -> u32 {
+    2
+}
+
+fn default_hook_retry_backoff_ms() -> u64 {
+    500
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            post_run_url: None,
+            post_run_command: None,
+            max_retries: default_hook_max_retries(),
+            retry_backoff_ms: default_hook_retry_backoff_ms(),
+            timeout_secs: default_hook_timeout_secs(),
+        }
+    }
+}
+
+// ── Skills ───────────────────────────────────────────────────────
+
+/// Additional skill sources (`[skills]`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SkillsConfig {
+    /// Extra skill directories (shared team skills, personal skills), lowest
+    /// priority first. Each holds `<name>/SKILL.toml` or `<name>/SKILL.md`.
+    /// A later directory overrides a same-named skill from an earlier one,
+    /// and the workspace `skills/` directory overrides them all.
+    #[serde(default)]
+    pub dirs: Vec<String>,
+}
+
+impl SkillsConfig {
+    /// `dirs` with a leading `~` expanded.
+    pub fn dir_paths(&self) -> Vec<PathBuf> {
+        self.dirs
+            .iter()
+            .map(|dir| PathBuf::from(shellexpand::tilde(dir).as_ref()))
+            .collect()
+    }
+}
+
+// ── Workspaces ───────────────────────────────────────────────────
+
+/// A named entry in the `[workspaces]` registry.
+///
+/// Memory, sessions, skills, checkpoints and the security sandbox are all
+/// rooted at `path` while the workspace is active.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceProfile {
+    /// Workspace directory (`~` is expanded)
+    pub path: PathBuf,
+    /// Model override while this workspace is active
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Autonomy level override while this workspace is active
+    #[serde(default)]
+    pub autonomy: Option<AutonomyLevel>,
 }
 
 // ── Delegate Agents ──────────────────────────────────────────────
@@ -247,18 +374,165 @@ pub struct AgentConfig {
     pub compact_context: bool,
     #[serde(default = "default_agent_max_tool_iterations")]
     pub max_tool_iterations: usize,
+    /// Most tool calls run from a single model response; the rest are
+    /// skipped and the model is told so. 0 = no cap.
+    #[serde(default = "default_agent_max_tool_calls_per_turn")]
+    pub max_tool_calls_per_turn: usize,
     #[serde(default = "default_agent_max_history_messages")]
     pub max_history_messages: usize,
     #[serde(default)]
     pub parallel_tools: bool,
     #[serde(default = "default_agent_tool_dispatcher")]
     pub tool_dispatcher: String,
+    /// Print a tool/file/round-trip summary after each CLI turn that used tools
+    #[serde(default = "default_true")]
+    pub turn_summary: bool,
+    /// Extra attempts for tool failures the tool classifies as transient (0 disables)
+    #[serde(default = "default_agent_tool_max_retries")]
+    pub tool_max_retries: u32,
+    /// Initial retry delay in milliseconds; doubles on each further attempt
+    #[serde(default = "default_agent_tool_retry_backoff_ms")]
+    pub tool_retry_backoff_ms: u64,
+    /// End an interactive CLI session after this many minutes without input (0 disables)
+    #[serde(default)]
+    pub idle_timeout_mins: u64,
+    /// Ask the model for a step-by-step plan before it starts calling tools
+    #[serde(default)]
+    pub plan_first: bool,
+    /// With `plan_first`, show the plan and ask for approval before executing (CLI only)
+    #[serde(default = "default_true")]
+    pub plan_approval: bool,
+    /// Show `shell` tool output line by line while the command runs (CLI only)
+    #[serde(default = "default_true")]
+    pub stream_shell_output: bool,
+    /// Turn CRLF line endings into LF and collapse runs of blank lines in
+    /// model output before it is parsed for tool calls and printed
+    #[serde(default = "default_true")]
+    pub normalize_output: bool,
+    /// With a streaming provider and prompt-guided tools, run each tool
+    /// call as soon as its block is complete instead of after the whole
+    /// response has arrived
+    #[serde(default)]
+    pub early_tool_dispatch: bool,
+    /// Keep the per-run scratch directory under `.zeroclaw-tmp/` instead of
+    /// deleting it when the run ends
+    #[serde(default)]
+    pub keep_temp: bool,
+    /// Tool results larger than this many bytes are written to the scratch
+    /// directory and replaced by a reference and summary (0 disables)
+    #[serde(default = "default_agent_tool_result_offload_bytes")]
+    pub tool_result_offload_bytes: usize,
+    /// Separate models for tool-calling rounds and the final answer
+    #[serde(default)]
+    pub model_router: ModelRouterConfig,
+    /// IANA timezone (e.g. "Europe/Berlin") for the current date/time in the
+    /// system prompt and for daily memory logs; unset uses the system timezone
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// `"auto"` picks `temperature_low` or `temperature_high` per message from
+    /// its wording; `"fixed"` always uses the configured temperature
+    #[serde(default)]
+    pub temperature: TemperatureMode,
+    /// Auto-mode temperature for code, math and other precise requests
+    #[serde(default = "default_agent_temperature_low")]
+    pub temperature_low: f64,
+    /// Auto-mode temperature for brainstorming and creative writing
+    #[serde(default = "default_agent_temperature_high")]
+    pub temperature_high: f64,
+    /// Language for CLI messages and model replies (e.g. "de", "de-DE");
+    /// unset or unsupported uses English
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Name the assistant goes by in the system prompt and REPL banner
+    /// (default: "ZeroClaw")
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Short persona description appended to the system prompt
+    #[serde(default)]
+    pub persona: Option<String>,
+    /// Tools the agent may use; empty allows every tool (set by `--persona`)
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// The model's context window in tokens, for the headroom shown by
+    /// `/context` and `--show-context-budget`
+    #[serde(default)]
+    pub context_window_tokens: Option<u64>,
+}
+
+impl AgentConfig {
+    /// The configured assistant name, or "ZeroClaw".
+    pub fn assistant_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or("ZeroClaw")
+    }
+}
+
+/// How the agent chooses its sampling temperature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureMode {
+    /// Use `default_temperature` (or `--temperature`) for every message
+    #[default]
+    Fixed,
+    /// Classify each message and use `temperature_low` / `temperature_high`
+    Auto,
+}
+
+/// Handling of prompt-injection attempts found in tool output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum InjectionFilterMode {
+    /// Pass tool output through unchanged
+    Off,
+    /// Keep suspicious lines but mark them as untrusted tool output
+    #[default]
+    Escape,
+    /// Replace suspicious lines with a placeholder
+    Redact,
+}
+
+/// Planner/executor model split for the tool-call loop.
+///
+/// ```toml
+/// [agent.model_router]
+/// planner = "anthropic/claude-opus-4-20250514"
+/// executor = "hint:fast"
+/// final_answer = "planner"
+/// ```
+///
+/// Rounds that decide and run tools use one role; once that model stops
+/// calling tools, the other role writes the final answer. Either model may be
+/// a `hint:` route. An unset role falls back to the active model.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ModelRouterConfig {
+    #[serde(default)]
+    pub planner: Option<String>,
+    #[serde(default)]
+    pub executor: Option<String>,
+    /// Which role writes the final answer; the other runs the tool rounds
+    #[serde(default)]
+    pub final_answer: RouterRole,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RouterRole {
+    #[default]
+    Planner,
+    Executor,
 }
 
 fn default_agent_max_tool_iterations() -> usize {
     10
 }
 
+fn default_agent_max_tool_calls_per_turn() -> usize {
+    20
+}
+
 fn default_agent_max_history_messages() -> usize {
     50
 }
@@ -267,14 +541,56 @@ fn default_agent_tool_dispatcher() -> String {
     "auto".into()
 }
 
+fn default_agent_tool_max_retries() -> u32 {
+    2
+}
+
+fn default_agent_tool_retry_backoff_ms() -> u64 {
+    500
+}
+
+fn default_agent_tool_result_offload_bytes() -> usize {
+    16_384
+}
+
+fn default_agent_temperature_low() -> f64 {
+    0.2
+}
+
+fn default_agent_temperature_high() -> f64 {
+    1.0
+}
+
 impl Default for AgentConfig {
     fn default() -> Self {
         Self {
             compact_context: false,
             max_tool_iterations: default_agent_max_tool_iterations(),
+            max_tool_calls_per_turn: default_agent_max_tool_calls_per_turn(),
             max_history_messages: default_agent_max_history_messages(),
             parallel_tools: false,
             tool_dispatcher: default_agent_tool_dispatcher(),
+            turn_summary: true,
+            tool_max_retries: default_agent_tool_max_retries(),
+            tool_retry_backoff_ms: default_agent_tool_retry_backoff_ms(),
+            idle_timeout_mins: 0,
+            plan_first: false,
+            plan_approval: true,
+            stream_shell_output: true,
+            normalize_output: true,
+            early_tool_dispatch: false,
+            keep_temp: false,
+            tool_result_offload_bytes: default_agent_tool_result_offload_bytes(),
+            model_router: ModelRouterConfig::default(),
+            timezone: None,
+            temperature: TemperatureMode::Fixed,
+            temperature_low: default_agent_temperature_low(),
+            temperature_high: default_agent_temperature_high(),
+            locale: None,
+            name: None,
+            persona: None,
+            tools: Vec::new(),
+            context_window_tokens: None,
         }
     }
 }
@@ -449,6 +765,22 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
     prices
 }
 
+// ── Per-session spend limits ──────────────────────────────────────
+
+/// Hard limits on what one session may spend, checked before every provider
+/// call. `0` disables a limit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LimitsConfig {
+    /// Estimated tokens (prompt + completion) one session may use
+    #[serde(default)]
+    pub max_tokens_per_session: u64,
+
+    /// USD one session may spend, priced from `[cost] prices`; models without
+    /// a price are only held to `max_tokens_per_session`
+    #[serde(default)]
+    pub max_cost_per_session: f64,
+}
+
 // ── Peripherals (hardware: STM32, RPi GPIO, etc.) ────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -711,6 +1043,17 @@ pub struct BrowserConfig {
     /// Optional Chrome/Chromium executable path for rust-native backend
     #[serde(default)]
     pub native_chrome_path: Option<String>,
+    /// Enable `browser_screenshot` tool (headless Chromium, requires the
+    /// `browser-screenshot` build feature)
+    #[serde(default)]
+    pub allow_screenshots: bool,
+    /// Enable `browser_js` tool (runs model-written JavaScript in headless
+    /// Chromium, requires the `browser-screenshot` build feature)
+    #[serde(default)]
+    pub allow_scripts: bool,
+    /// Maximum script length (characters) accepted by `browser_js`
+    #[serde(default = "default_browser_max_script_length")]
+    pub max_script_length: usize,
     /// Computer-use sidecar configuration
     #[serde(default)]
     pub computer_use: BrowserComputerUseConfig,
@@ -724,6 +1067,10 @@ fn default_browser_webdriver_url() -> String {
     "http://127.0.0.1:9515".into()
 }
 
+fn default_browser_max_script_length() -> usize {
+    10_000
+}
+
 impl Default for BrowserConfig {
     fn default() -> Self {
         Self {
@@ -734,6 +1081,9 @@ impl Default for BrowserConfig {
             native_headless: default_true(),
             native_webdriver_url: default_browser_webdriver_url(),
             native_chrome_path: None,
+            allow_screenshots: false,
+            allow_scripts: false,
+            max_script_length: default_browser_max_script_length(),
             computer_use: BrowserComputerUseConfig::default(),
         }
     }
@@ -1332,6 +1682,30 @@ impl Default for StorageProviderConfig {
     }
 }
 
+/// Which memory entries context recall draws on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RecallScope {
+    /// Every entry, regardless of session
+    #[default]
+    Global,
+    /// Only entries saved in the active session
+    Session,
+    /// Active-session entries first, then global matches
+    Hybrid,
+}
+
+/// Whether workspaces sharing one memory store see each other's entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryNamespace {
+    /// Entries are keyed by workspace; recall only sees the active one's
+    #[default]
+    Workspace,
+    /// One namespace for every workspace
+    Global,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct MemoryConfig {
@@ -1341,6 +1715,22 @@ pub struct MemoryConfig {
     pub backend: String,
     /// Auto-save conversation context to memory
     pub auto_save: bool,
+    /// Refuse to start when the backend fails to initialize. When false,
+    /// the agent warns and runs without memory (and without memory tools).
+    #[serde(default)]
+    pub required: bool,
+    /// File auto-saved user messages that state a preference or decision
+    /// under the `preference` / `decision` categories instead of `conversation`
+    #[serde(default)]
+    pub infer_categories: bool,
+    /// Which entries recall draws on: all memory (`global`), only the active
+    /// session (`session`), or both with the session's entries first (`hybrid`)
+    #[serde(default)]
+    pub recall_scope: RecallScope,
+    /// For stores shared by every workspace (`postgres`): keep each
+    /// workspace's entries apart (`workspace`) or share them (`global`)
+    #[serde(default)]
+    pub namespace: MemoryNamespace,
     /// Run memory/session hygiene (archiving + retention cleanup)
     #[serde(default = "default_hygiene_enabled")]
     pub hygiene_enabled: bool,
@@ -1353,6 +1743,13 @@ pub struct MemoryConfig {
     /// For sqlite backend: prune conversation rows older than this many days
     #[serde(default = "default_conversation_retention_days")]
     pub conversation_retention_days: u32,
+    /// `zeroclaw memory compact` merges daily entries older than this many
+    /// days into one summary per day; keep it below `archive_after_days`
+    #[serde(default = "default_compact_after_days")]
+    pub compact_after_days: u32,
+    /// Also run daily-log compaction once a day while the daemon is running
+    #[serde(default)]
+    pub compact_in_daemon: bool,
     /// Embedding provider: "none" | "openai" | "custom:URL"
     #[serde(default = "default_embedding_provider")]
     pub embedding_provider: String,
@@ -1424,6 +1821,9 @@ fn default_purge_after_days() -> u32 {
 fn default_conversation_retention_days() -> u32 {
     30
 }
+fn default_compact_after_days() -> u32 {
+    2
+}
 fn default_embedding_model() -> String {
     "text-embedding-3-small".into()
 }
@@ -1457,10 +1857,16 @@ impl Default for MemoryConfig {
         Self {
             backend: "sqlite".into(),
             auto_save: true,
+            required: false,
+            infer_categories: false,
+            recall_scope: RecallScope::default(),
+            namespace: MemoryNamespace::default(),
             hygiene_enabled: default_hygiene_enabled(),
             archive_after_days: default_archive_after_days(),
             purge_after_days: default_purge_after_days(),
             conversation_retention_days: default_conversation_retention_days(),
+            compact_after_days: default_compact_after_days(),
+            compact_in_daemon: false,
             embedding_provider: default_embedding_provider(),
             embedding_model: default_embedding_model(),
             embedding_dimensions: default_embedding_dims(),
@@ -1484,7 +1890,7 @@ impl Default for MemoryConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObservabilityConfig {
-    /// "none" | "log" | "prometheus" | "otel"
+    /// "none" | "log" | "jsonl" | "prometheus" | "otel"
     pub backend: String,
 
     /// OTLP endpoint (e.g. "http://localhost:4318"). Only used when backend = "otel".
@@ -1494,6 +1900,33 @@ pub struct ObservabilityConfig {
     /// Service name reported to the OTel collector. Defaults to "zeroclaw".
     #[serde(default)]
     pub otel_service_name: Option<String>,
+
+    /// Event log file for the "jsonl" backend. Defaults to `~/.zeroclaw/logs/events.jsonl`.
+    #[serde(default)]
+    pub log_path: Option<String>,
+
+    /// Serve `GET /metrics` on this port (backend = "prometheus"). Unset: no
+    /// standalone scrape endpoint; the gateway's `/metrics` still works.
+    #[serde(default)]
+    pub metrics_port: Option<u16>,
+
+    /// Address the metrics endpoint binds to. Defaults to "127.0.0.1".
+    #[serde(default = "default_metrics_host")]
+    pub metrics_host: String,
+
+    /// Write every model prompt and response to a signed, daily-rotated
+    /// audit log next to the event log.
+    #[serde(default)]
+    pub audit_prompts: bool,
+
+    /// HMAC-SHA256 key for signing prompt audit records (stored encrypted
+    /// when secrets.encrypt = true). Required when `audit_prompts` is on.
+    #[serde(default)]
+    pub audit_hmac_key: Option<String>,
+}
+
+fn default_metrics_host() -> String {
+    "127.0.0.1".into()
 }
 
 impl Default for ObservabilityConfig {
@@ -1502,6 +1935,11 @@ impl Default for ObservabilityConfig {
             backend: "none".into(),
             otel_endpoint: None,
             otel_service_name: None,
+            log_path: None,
+            metrics_port: None,
+            metrics_host: default_metrics_host(),
+            audit_prompts: false,
+            audit_hmac_key: None,
         }
     }
 }
@@ -1532,6 +1970,17 @@ pub struct AutonomyConfig {
     /// Tools that always require interactive approval, even after "Always".
     #[serde(default = "default_always_ask")]
     pub always_ask: Vec<String>,
+
+    /// Snapshot git workspaces to `refs/zeroclaw/checkpoints/` before the first
+    /// mutating tool call of a turn and after the turn completes.
+    #[serde(default)]
+    pub git_checkpoints: bool,
+
+    /// Register network-capable tools (browser, HTTP requests, web search,
+    /// Composio) in `full` autonomy. Off by default, since no one approves
+    /// their calls there; other levels always register them.
+    #[serde(default)]
+    pub allow_network: bool,
 }
 
 fn default_auto_approve() -> Vec<String> {
@@ -1587,6 +2036,8 @@ impl Default for AutonomyConfig {
             block_high_risk_commands: true,
             auto_approve: default_auto_approve(),
             always_ask: default_always_ask(),
+            git_checkpoints: false,
+            allow_network: false,
         }
     }
 }
@@ -1602,6 +2053,10 @@ pub struct RuntimeConfig {
     /// Docker runtime settings (used when `kind = "docker"`).
     #[serde(default)]
     pub docker: DockerRuntimeConfig,
+
+    /// Shell for native commands on Windows (`cmd` | `powershell` | `pwsh`).
+    #[serde(default = "default_windows_shell")]
+    pub windows_shell: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1639,6 +2094,10 @@ fn default_runtime_kind() -> String {
     "native".into()
 }
 
+fn default_windows_shell() -> String {
+    "cmd".into()
+}
+
 fn default_docker_image() -> String {
     "alpine:3.20".into()
 }
@@ -1674,6 +2133,7 @@ impl Default for RuntimeConfig {
         Self {
             kind: default_runtime_kind(),
             docker: DockerRuntimeConfig::default(),
+            windows_shell: default_windows_shell(),
         }
     }
 }
@@ -1699,6 +2159,10 @@ pub struct ReliabilityConfig {
     /// Example: `{ "claude-opus-4-20250514" = ["claude-sonnet-4-20250514", "gpt-4o"] }`
     #[serde(default)]
     pub model_fallbacks: std::collections::HashMap<String, Vec<String>>,
+    /// Model to retry with, on the same provider, when the requested model
+    /// is reported as not found or unavailable.
+    #[serde(default)]
+    pub fallback_model: Option<String>,
     /// Initial backoff for channel/daemon restarts.
     #[serde(default = "default_channel_backoff_secs")]
     pub channel_initial_backoff_secs: u64,
@@ -1711,6 +2175,16 @@ pub struct ReliabilityConfig {
     /// Max retries for cron job execution attempts.
     #[serde(default = "default_scheduler_retries")]
     pub scheduler_retries: u32,
+    /// Consecutive provider failures that open its circuit, so calls fail
+    /// fast (or go straight to the fallback chain). 0 disables the breaker.
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: u32,
+    /// Failures further apart than this many seconds start a new count.
+    #[serde(default = "default_circuit_breaker_window_secs")]
+    pub circuit_breaker_window_secs: u64,
+    /// Seconds an open circuit fails fast before a recovery probe.
+    #[serde(default = "default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
 }
 
 fn default_provider_retries() -> u32 {
@@ -1737,6 +2211,18 @@ fn default_scheduler_retries() -> u32 {
     2
 }
 
+fn default_circuit_breaker_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_breaker_window_secs() -> u64 {
+    60
+}
+
+fn default_circuit_breaker_cooldown_secs() -> u64 {
+    30
+}
+
 impl Default for ReliabilityConfig {
     fn default() -> Self {
         Self {
@@ -1745,14 +2231,127 @@ impl Default for ReliabilityConfig {
             fallback_providers: Vec::new(),
             api_keys: Vec::new(),
             model_fallbacks: std::collections::HashMap::new(),
+            fallback_model: None,
             channel_initial_backoff_secs: default_channel_backoff_secs(),
             channel_max_backoff_secs: default_channel_backoff_max_secs(),
             scheduler_poll_secs: default_scheduler_poll_secs(),
             scheduler_retries: default_scheduler_retries(),
+            circuit_breaker_threshold: default_circuit_breaker_threshold(),
+            circuit_breaker_window_secs: default_circuit_breaker_window_secs(),
+            circuit_breaker_cooldown_secs: default_circuit_breaker_cooldown_secs(),
+        }
+    }
+}
+
+// ── Provider HTTP overrides ──────────────────────────────────────
+
+/// Per-provider request header overrides, plus user-registered
+/// OpenAI-compatible endpoints.
+///
+/// Some self-hosted and enterprise gateways expect the API key in a
+/// non-standard header, without the `Bearer ` prefix, or alongside extra
+/// static headers. Overrides are keyed by provider name and only sent to
+/// that provider, never to the rest of the fallback or routing chain.
+///
+/// ```toml
+/// [provider.overrides.vllm]
+/// auth_header = "api-key"
+/// auth_value_template = "{key}"
+///
+/// [provider.overrides.vllm.headers]
+/// "X-Tenant-Id" = "acme"
+///
+/// [[provider.custom]]
+/// name = "vllm"
+/// base_url = "http://localhost:8000/v1"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProviderConfig {
+    /// Header overrides by provider name (`[provider.overrides.<name>]`).
+    /// Only OpenAI-compatible providers can apply them.
+    #[serde(default)]
+    pub overrides: HashMap<String, ProviderHeaderOverrides>,
+    /// Reasoning effort (`low`, `medium`, `high`) requested from reasoning
+    /// models on OpenAI-compatible endpoints. Unset leaves the provider default.
+    #[serde(default)]
+    pub reasoning_effort: Option<String>,
+    /// Custom OpenAI-compatible endpoints, selectable by `name`
+    /// (added with `zeroclaw providers add`).
+    #[serde(default)]
+    pub custom: Vec<CustomProviderConfig>,
+    /// Response cache for identical requests (`[provider.cache]`).
+    #[serde(default)]
+    pub cache: ResponseCacheConfig,
+}
+
+/// Request header overrides for one provider.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ProviderHeaderOverrides {
+    /// Header name used to send the API key (default: provider-specific,
+    /// usually `Authorization`).
+    #[serde(default)]
+    pub auth_header: Option<String>,
+    /// Template for the auth header value; `{key}` is replaced with the
+    /// credential (e.g. `"Bearer {key}"` or `"{key}"`).
+    #[serde(default)]
+    pub auth_value_template: Option<String>,
+    /// Additional static headers attached to every request.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl ProviderHeaderOverrides {
+    /// Whether no override is set.
+    pub fn is_empty(&self) -> bool {
+        self.auth_header.is_none() && self.auth_value_template.is_none() && self.headers.is_empty()
+    }
+}
+
+/// In-memory cache of provider responses, keyed by provider, model,
+/// temperature, messages and tools. Streaming calls are never cached.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResponseCacheConfig {
+    /// Answer repeated identical requests from the cache (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long a cached response stays valid, in seconds (default: 300).
+    #[serde(default = "default_response_cache_ttl_secs")]
+    pub ttl_secs: u64,
+    /// Most responses kept; the oldest is evicted first (default: 256).
+    #[serde(default = "default_response_cache_max_entries")]
+    pub max_entries: usize,
+}
+
+fn default_response_cache_ttl_secs() -> u64 {
+    300
+}
+
+fn default_response_cache_max_entries() -> usize {
+    256
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: default_response_cache_ttl_secs(),
+            max_entries: default_response_cache_max_entries(),
         }
     }
 }
 
+/// A user-registered OpenAI-compatible endpoint (vLLM, LM Studio, ...).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomProviderConfig {
+    /// Provider name used with `--provider` / `default_provider`.
+    pub name: String,
+    /// API root, e.g. `http://localhost:8000/v1`.
+    pub base_url: String,
+    /// Optional API key (encrypted at rest when secrets encryption is on).
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
 // ── Scheduler ────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1854,6 +2453,64 @@ pub struct ClassificationRule {
     pub priority: i32,
 }
 
+// ── Per-message routing ──────────────────────────────────────────
+
+/// Ordered rules that send each user message to a provider, model and
+/// temperature. The first rule whose conditions all hold wins; unset route
+/// fields, and messages no rule matches, use the defaults. An explicit
+/// `--model` (or `/model` in a session) always wins over routing.
+///
+/// ```toml
+/// [[routing.rules]]
+/// name = "small-talk"
+/// max_length = 80
+/// provider = "groq"
+/// model = "llama-3.3-70b-versatile"
+///
+/// [[routing.rules]]
+/// name = "coding"
+/// pattern = "(?i)\\b(fn|class|refactor|stack trace)\\b"
+/// model = "anthropic/claude-opus-4"
+/// temperature = 0.2
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RoutingConfig {
+    #[serde(default)]
+    pub rules: Vec<RoutingRule>,
+}
+
+/// One routing rule: conditions on the message, and the route it selects.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RoutingRule {
+    /// Name shown in logs and observer events.
+    pub name: String,
+    /// Regex the message text must match.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// `true` requires an `@path` file mention, `false` forbids one.
+    #[serde(default)]
+    pub file_mentions: Option<bool>,
+    /// Only match if message length >= N chars.
+    #[serde(default)]
+    pub min_length: Option<usize>,
+    /// Only match if message length <= N chars.
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    /// Only match messages from these channels (`cli`, `telegram`, ...).
+    /// Empty matches every channel.
+    #[serde(default)]
+    pub channels: Vec<String>,
+    /// Provider to use; the default provider when unset.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model to use; the default model when unset.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Temperature to use; the session temperature when unset.
+    #[serde(default)]
+    pub temperature: Option<f64>,
+}
+
 // ── Heartbeat ────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1979,6 +2636,27 @@ pub struct ChannelsConfig {
     pub lark: Option<LarkConfig>,
     pub dingtalk: Option<DingTalkConfig>,
     pub qq: Option<QQConfig>,
+    /// Local Unix socket for desktop frontends (Unix only)
+    #[serde(default)]
+    pub socket: Option<SocketConfig>,
+    /// Maximum messages processed at once across all channels.
+    /// `0` derives the limit from the number of enabled channels.
+    #[serde(default)]
+    pub max_concurrent_sessions: usize,
+    /// What to do with a message that arrives while every session slot is busy.
+    #[serde(default)]
+    pub session_overflow: SessionOverflow,
+}
+
+/// Handling of channel messages beyond `max_concurrent_sessions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionOverflow {
+    /// Wait for a free slot (default).
+    #[default]
+    Queue,
+    /// Reply that the agent is busy and drop the message.
+    Reject,
 }
 
 impl Default for ChannelsConfig {
@@ -1999,6 +2677,9 @@ impl Default for ChannelsConfig {
             lark: None,
             dingtalk: None,
             qq: None,
+            socket: None,
+            max_concurrent_sessions: 0,
+            session_overflow: SessionOverflow::Queue,
         }
     }
 }
@@ -2170,6 +2851,19 @@ fn default_irc_port() -> u16 {
     6697
 }
 
+/// Unix domain socket channel for local IPC.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SocketConfig {
+    /// Socket path (supports `~`); created on start, replacing a stale socket
+    pub path: String,
+}
+
+impl SocketConfig {
+    pub fn socket_path(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.path).as_ref())
+    }
+}
+
 /// How ZeroClaw receives events from Feishu / Lark.
 ///
 /// - `websocket` (default) — persistent WSS long-connection; no public URL required.
@@ -2227,6 +2921,11 @@ pub struct SecurityConfig {
     /// Audit logging configuration
     #[serde(default)]
     pub audit: AuditConfig,
+
+    /// What to do with tool output lines that look like instructions to the
+    /// model ("Ignore all previous instructions", `SYSTEM:` prefixes)
+    #[serde(default)]
+    pub tool_output_injection: InjectionFilterMode,
 }
 
 /// Sandbox configuration for OS-level isolation
@@ -2390,6 +3089,41 @@ pub struct QQConfig {
 
 // ── Config impl ──────────────────────────────────────────────────
 
+fn default_config_version() -> u32 {
+    CURRENT_CONFIG_VERSION
+}
+
+/// Parse `config.toml`, upgrading it first if it was written for an older
+/// schema version, and fill in `"$secret:<name>"` values with `lookup`.
+fn parse_versioned_config(
+    contents: &str,
+    lookup: &mut dyn FnMut(&str) -> Result<String>,
+) -> Result<Config> {
+    let mut raw: toml::Value = toml::from_str(contents).context("Failed to parse config file")?;
+    let version = ConfigMigrator::version_of(&raw);
+    let migrated = version != CURRENT_CONFIG_VERSION;
+    if migrated {
+        raw = ConfigMigrator::migrate(version, raw)?;
+        tracing::info!(
+            from = version,
+            to = CURRENT_CONFIG_VERSION,
+            "Upgraded config schema in memory; the next save writes the new version"
+        );
+    }
+    let keychain_secrets = keychain::resolve_placeholders(&mut raw, lookup)?;
+    let mut config: Config = if migrated {
+        raw.try_into()
+            .context("Failed to parse migrated config file")?
+    } else if keychain_secrets.is_empty() {
+        // Parsing the text keeps line numbers in errors.
+        toml::from_str(contents).context("Failed to parse config file")?
+    } else {
+        raw.try_into().context("Failed to parse config file")?
+    };
+    config.keychain_secrets = keychain_secrets;
+    Ok(config)
+}
+
 impl Default for Config {
     fn default() -> Self {
         let home =
@@ -2399,6 +3133,8 @@ impl Default for Config {
         Self {
             workspace_dir: zeroclaw_dir.join("workspace"),
             config_path: zeroclaw_dir.join("config.toml"),
+            config_version: CURRENT_CONFIG_VERSION,
+            keychain_secrets: Vec::new(),
             api_key: None,
             api_url: None,
             default_provider: Some("openrouter".to_string()),
@@ -2408,6 +3144,7 @@ impl Default for Config {
             autonomy: AutonomyConfig::default(),
             runtime: RuntimeConfig::default(),
             reliability: ReliabilityConfig::default(),
+            provider: ProviderConfig::default(),
             scheduler: SchedulerConfig::default(),
             agent: AgentConfig::default(),
             model_routes: Vec::new(),
@@ -2426,10 +3163,15 @@ impl Default for Config {
             proxy: ProxyConfig::default(),
             identity: IdentityConfig::default(),
             cost: CostConfig::default(),
+            limits: LimitsConfig::default(),
             peripherals: PeripheralsConfig::default(),
             agents: HashMap::new(),
+            workspaces: HashMap::new(),
             hardware: HardwareConfig::default(),
+            skills: SkillsConfig::default(),
+            hooks: HooksConfig::default(),
             query_classification: QueryClassificationConfig::default(),
+            routing: RoutingConfig::default(),
         }
     }
 }
@@ -2441,6 +3183,26 @@ fn default_config_and_workspace_dirs() -> Result<(PathBuf, PathBuf)> {
 
 const ACTIVE_WORKSPACE_STATE_FILE: &str = "active_workspace.toml";
 
+/// Project defaults checked into a workspace, merged over the user's config.
+pub const WORKSPACE_CONFIG_FILE: &str = ".zeroclaw.toml";
+
+/// Recursively overlay `overlay` onto `base`; tables merge, other values replace.
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ActiveWorkspaceState {
     config_dir: String,
@@ -2555,6 +3317,11 @@ pub(crate) fn persist_active_workspace_config_dir(config_dir: &Path) -> Result<(
     Ok(())
 }
 
+/// Expand a leading `~` in a workspace path.
+fn expand_workspace_path(path: &Path) -> PathBuf {
+    PathBuf::from(shellexpand::tilde(&path.to_string_lossy()).as_ref())
+}
+
 fn resolve_config_dir_for_workspace(workspace_dir: &Path) -> (PathBuf, PathBuf) {
     let workspace_config_dir = workspace_dir.to_path_buf();
     if workspace_config_dir.join("config.toml").exists() {
@@ -2621,80 +3388,32 @@ fn encrypt_optional_secret(
 }
 
 impl Config {
-    pub fn load_or_init() -> Result<Self> {
+    /// The config and workspace directories [`Config::load_or_init`] uses.
+    pub(crate) fn resolve_dirs() -> Result<(PathBuf, PathBuf)> {
         let (default_zeroclaw_dir, default_workspace_dir) = default_config_and_workspace_dirs()?;
 
         // Resolution priority:
         // 1. ZEROCLAW_WORKSPACE env override
         // 2. Persisted active workspace marker from onboarding/custom profile
         // 3. Default ~/.zeroclaw layout
-        let (zeroclaw_dir, workspace_dir) = match std::env::var("ZEROCLAW_WORKSPACE") {
+        Ok(match std::env::var("ZEROCLAW_WORKSPACE") {
             Ok(custom_workspace) if !custom_workspace.is_empty() => {
                 resolve_config_dir_for_workspace(&PathBuf::from(custom_workspace))
             }
             _ => load_persisted_workspace_dirs(&default_zeroclaw_dir)?
                 .unwrap_or((default_zeroclaw_dir, default_workspace_dir)),
-        };
+        })
+    }
 
+    pub fn load_or_init() -> Result<Self> {
+        let (zeroclaw_dir, workspace_dir) = Self::resolve_dirs()?;
         let config_path = zeroclaw_dir.join("config.toml");
 
         fs::create_dir_all(&zeroclaw_dir).context("Failed to create config directory")?;
         fs::create_dir_all(&workspace_dir).context("Failed to create workspace directory")?;
 
         if config_path.exists() {
-            // Warn if config file is world-readable (may contain API keys)
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                if let Ok(meta) = fs::metadata(&config_path) {
-                    if meta.permissions().mode() & 0o004 != 0 {
-                        tracing::warn!(
-                            "Config file {:?} is world-readable (mode {:o}). \
-                             Consider restricting with: chmod 600 {:?}",
-                            config_path,
-                            meta.permissions().mode() & 0o777,
-                            config_path,
-                        );
-                    }
-                }
-            }
-
-            let contents =
-                fs::read_to_string(&config_path).context("Failed to read config file")?;
-            let mut config: Config =
-                toml::from_str(&contents).context("Failed to parse config file")?;
-            // Set computed paths that are skipped during serialization
-            config.config_path = config_path.clone();
-            config.workspace_dir = workspace_dir;
-            let store = crate::security::SecretStore::new(&zeroclaw_dir, config.secrets.encrypt);
-            decrypt_optional_secret(&store, &mut config.api_key, "config.api_key")?;
-            decrypt_optional_secret(
-                &store,
-                &mut config.composio.api_key,
-                "config.composio.api_key",
-            )?;
-
-            decrypt_optional_secret(
-                &store,
-                &mut config.browser.computer_use.api_key,
-                "config.browser.computer_use.api_key",
-            )?;
-
-            decrypt_optional_secret(
-                &store,
-                &mut config.web_search.brave_api_key,
-                "config.web_search.brave_api_key",
-            )?;
-
-            decrypt_optional_secret(
-                &store,
-                &mut config.storage.provider.config.db_url,
-                "config.storage.provider.config.db_url",
-            )?;
-
-            for agent in config.agents.values_mut() {
-                decrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
-            }
+            let mut config = Self::read_from(&config_path, workspace_dir)?;
             config.apply_env_overrides();
             Ok(config)
         } else {
@@ -2715,6 +3434,79 @@ impl Config {
         }
     }
 
+    /// Read and decrypt the config file at `config_path`, without env
+    /// overrides.
+    pub(crate) fn read_from(config_path: &Path, workspace_dir: PathBuf) -> Result<Self> {
+        let zeroclaw_dir = config_path
+            .parent()
+            .context("Config path must have a parent directory")?;
+        // Warn if config file is world-readable (may contain API keys)
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Ok(meta) = fs::metadata(config_path) {
+                if meta.permissions().mode() & 0o004 != 0 {
+                    tracing::warn!(
+                        "Config file {:?} is world-readable (mode {:o}). \
+                         Consider restricting with: chmod 600 {:?}",
+                        config_path,
+                        meta.permissions().mode() & 0o777,
+                        config_path,
+                    );
+                }
+            }
+        }
+
+        let contents = fs::read_to_string(config_path).context("Failed to read config file")?;
+        let mut config = parse_versioned_config(&contents, &mut keychain::get)?;
+        // Set computed paths that are skipped during serialization
+        config.config_path = config_path.to_path_buf();
+        config.workspace_dir = workspace_dir;
+        let store = crate::security::SecretStore::new(zeroclaw_dir, config.secrets.encrypt);
+        decrypt_optional_secret(&store, &mut config.api_key, "config.api_key")?;
+        decrypt_optional_secret(
+            &store,
+            &mut config.composio.api_key,
+            "config.composio.api_key",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut config.browser.computer_use.api_key,
+            "config.browser.computer_use.api_key",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut config.web_search.brave_api_key,
+            "config.web_search.brave_api_key",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut config.storage.provider.config.db_url,
+            "config.storage.provider.config.db_url",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut config.observability.audit_hmac_key,
+            "config.observability.audit_hmac_key",
+        )?;
+
+        for agent in config.agents.values_mut() {
+            decrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
+        }
+        for custom in &mut config.provider.custom {
+            decrypt_optional_secret(
+                &store,
+                &mut custom.api_key,
+                "config.provider.custom.*.api_key",
+            )?;
+        }
+        Ok(config)
+    }
+
     /// Apply environment variable overrides to config
     pub fn apply_env_overrides(&mut self) {
         // API Key: ZEROCLAW_API_KEY or API_KEY (generic)
@@ -2940,7 +3732,114 @@ impl Config {
         set_runtime_proxy_config(self.proxy.clone());
     }
 
+    /// Switch to a workspace from the `[workspaces]` registry, or to a
+    /// directory when `name_or_path` is not a registered name.
+    ///
+    /// Re-roots `workspace_dir` (and with it memory, sessions, skills and the
+    /// security sandbox) and applies the workspace's model/autonomy overrides.
+    pub fn apply_workspace(&mut self, name_or_path: &str) -> Result<()> {
+        let target = name_or_path.trim();
+        if target.is_empty() {
+            anyhow::bail!("Workspace name or path must not be empty");
+        }
+
+        let profile = self.workspaces.get(target).cloned();
+        let path = match &profile {
+            Some(profile) => expand_workspace_path(&profile.path),
+            None => {
+                let path = expand_workspace_path(Path::new(target));
+                if path.exists() && !path.is_dir() {
+                    anyhow::bail!("Workspace path {} is not a directory", path.display());
+                }
+                if !path.is_dir() {
+                    anyhow::bail!(
+                        "Unknown workspace '{target}': not a registered name or an existing directory"
+                    );
+                }
+                path
+            }
+        };
+
+        fs::create_dir_all(&path)
+            .with_context(|| format!("Failed to create workspace directory {}", path.display()))?;
+        self.workspace_dir = path;
+        self.apply_workspace_file()?;
+
+        if let Some(profile) = profile {
+            if let Some(model) = profile.model.filter(|m| !m.trim().is_empty()) {
+                self.default_model = Some(model);
+            }
+            if let Some(level) = profile.autonomy {
+                self.autonomy.level = level;
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge `<workspace>/.zeroclaw.toml` over this config.
+    ///
+    /// Only project-level keys are honored (`default_provider`, `default_model`,
+    /// `default_temperature` and `[agent]`); credentials, endpoints and security
+    /// policy stay under the user's control, so any other key is ignored with a
+    /// warning. CLI flags still take precedence over the file.
+    pub fn apply_workspace_file(&mut self) -> Result<()> {
+        let path = self.workspace_dir.join(WORKSPACE_CONFIG_FILE);
+        if !path.is_file() {
+            return Ok(());
+        }
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let overrides: toml::Table =
+            toml::from_str(&raw).with_context(|| format!("Failed to parse {}", path.display()))?;
+        self.merge_workspace_overrides(overrides)
+            .with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    fn merge_workspace_overrides(&mut self, overrides: toml::Table) -> Result<()> {
+        for (key, value) in overrides {
+            match key.as_str() {
+                "default_provider" => {
+                    let provider: String = value.try_into()?;
+                    if provider.contains("://") {
+                        anyhow::bail!(
+                            "default_provider must not point at a custom endpoint URL in {WORKSPACE_CONFIG_FILE}"
+                        );
+                    }
+                    self.default_provider = Some(provider);
+                }
+                "default_model" => self.default_model = Some(value.try_into()?),
+                "default_temperature" => self.default_temperature = value.try_into()?,
+                "agent" => {
+                    let mut agent = toml::Value::try_from(&self.agent)?;
+                    merge_toml(&mut agent, value);
+                    self.agent = agent.try_into()?;
+                }
+                other => tracing::warn!(
+                    key = other,
+                    "Ignoring key in {WORKSPACE_CONFIG_FILE}: only default_provider, default_model, default_temperature and [agent] can be set per workspace"
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Name of the registered workspace rooted at the current `workspace_dir`.
+    pub fn active_workspace_name(&self) -> Option<&str> {
+        self.workspaces
+            .iter()
+            .find(|(_, profile)| expand_workspace_path(&profile.path) == self.workspace_dir)
+            .map(|(name, _)| name.as_str())
+    }
+
     pub fn save(&self) -> Result<()> {
+        // Keychain values still as loaded go back to their placeholders
+        let keychain_secrets = if self.keychain_secrets.is_empty() {
+            Vec::new()
+        } else {
+            let current = toml::Value::try_from(self).context("Failed to serialize config")?;
+            keychain::unchanged(&current, &self.keychain_secrets)
+        };
+
         // Encrypt secrets before serialization
         let mut config_to_save = self.clone();
         let zeroclaw_dir = self
@@ -2974,12 +3873,32 @@ impl Config {
             "config.storage.provider.config.db_url",
         )?;
 
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.observability.audit_hmac_key,
+            "config.observability.audit_hmac_key",
+        )?;
+
         for agent in config_to_save.agents.values_mut() {
             encrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
         }
 
-        let toml_str =
-            toml::to_string_pretty(&config_to_save).context("Failed to serialize config")?;
+        for custom in &mut config_to_save.provider.custom {
+            encrypt_optional_secret(
+                &store,
+                &mut custom.api_key,
+                "config.provider.custom.*.api_key",
+            )?;
+        }
+
+        let toml_str = if keychain_secrets.is_empty() {
+            toml::to_string_pretty(&config_to_save).context("Failed to serialize config")?
+        } else {
+            let mut value =
+                toml::Value::try_from(&config_to_save).context("Failed to serialize config")?;
+            keychain::restore_placeholders(&mut value, &keychain_secrets);
+            toml::to_string_pretty(&value).context("Failed to serialize config")?
+        };
 
         let parent_dir = self
             .config_path
@@ -3206,15 +4125,19 @@ default_temperature = 0.7
                 block_high_risk_commands: true,
                 auto_approve: vec!["file_read".into()],
                 always_ask: vec![],
+                git_checkpoints: true,
+                allow_network: false,
             },
             runtime: RuntimeConfig {
                 kind: "docker".into(),
                 ..RuntimeConfig::default()
             },
             reliability: ReliabilityConfig::default(),
+            provider: ProviderConfig::default(),
             scheduler: SchedulerConfig::default(),
             model_routes: Vec::new(),
             query_classification: QueryClassificationConfig::default(),
+            routing: RoutingConfig::default(),
             heartbeat: HeartbeatConfig {
                 enabled: true,
                 interval_minutes: 15,
@@ -3242,6 +4165,8 @@ default_temperature = 0.7
                 lark: None,
                 dingtalk: None,
                 qq: None,
+                max_concurrent_sessions: 0,
+                session_overflow: SessionOverflow::Queue,
             },
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
@@ -3256,9 +4181,15 @@ default_temperature = 0.7
             agent: AgentConfig::default(),
             identity: IdentityConfig::default(),
             cost: CostConfig::default(),
+            limits: LimitsConfig::default(),
             peripherals: PeripheralsConfig::default(),
             agents: HashMap::new(),
+            workspaces: HashMap::new(),
             hardware: HardwareConfig::default(),
+            skills: SkillsConfig::default(),
+            hooks: HooksConfig::default(),
+            config_version: crate::config::migrate::CURRENT_CONFIG_VERSION,
+            keychain_secrets: Vec::new(),
         };
 
         let toml_str = toml::to_string_pretty(&config).unwrap();
@@ -3358,6 +4289,73 @@ tool_dispatcher = "xml"
         assert_eq!(parsed.agent.tool_dispatcher, "xml");
     }
 
+    #[test]
+    fn provider_header_overrides_deserialize() {
+        let raw = r#"
+default_temperature = 0.7
+[provider.overrides.gateway]
+auth_header = "api-key"
+auth_value_template = "{key}"
+[provider.overrides.gateway.headers]
+"X-Tenant-Id" = "acme"
+"#;
+        let parsed: Config = toml::from_str(raw).unwrap();
+        let gateway = &parsed.provider.overrides["gateway"];
+        assert_eq!(gateway.auth_header.as_deref(), Some("api-key"));
+        assert_eq!(gateway.auth_value_template.as_deref(), Some("{key}"));
+        assert_eq!(gateway.headers["X-Tenant-Id"], "acme");
+        assert!(!gateway.is_empty());
+        assert_eq!(parsed.provider.overrides.len(), 1);
+    }
+
+    #[test]
+    fn old_config_files_are_migrated_on_load() {
+        let config = parse_versioned_config("[agent]\nmax_iterations = 12\n", &mut |_| {
+            anyhow::bail!("no keychain in tests")
+        })
+        .unwrap();
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.agent.max_tool_iterations, 12);
+
+        let saved = toml::to_string(&config).unwrap();
+        assert!(saved.contains(&format!("config_version = {CURRENT_CONFIG_VERSION}")));
+    }
+
+    #[test]
+    fn provider_cache_deserializes_with_defaults() {
+        let raw = r#"
+default_temperature = 0.7
+[provider.cache]
+enabled = true
+ttl_secs = 60
+"#;
+        let parsed: Config = toml::from_str(raw).unwrap();
+        assert!(parsed.provider.cache.enabled);
+        assert_eq!(parsed.provider.cache.ttl_secs, 60);
+        assert_eq!(parsed.provider.cache.max_entries, 256);
+        assert!(!Config::default().provider.cache.enabled);
+    }
+
+    #[test]
+    fn custom_providers_deserialize() {
+        let raw = r#"
+default_temperature = 0.7
+[[provider.custom]]
+name = "vllm"
+base_url = "http://localhost:8000/v1"
+
+[[provider.custom]]
+name = "lmstudio-box"
+base_url = "http://10.0.0.5:1234/v1"
+api_key = "lm-key"
+"#;
+        let parsed: Config = toml::from_str(raw).unwrap();
+        assert_eq!(parsed.provider.custom.len(), 2);
+        assert_eq!(parsed.provider.custom[0].name, "vllm");
+        assert!(parsed.provider.custom[0].api_key.is_none());
+        assert_eq!(parsed.provider.custom[1].api_key.as_deref(), Some("lm-key"));
+    }
+
     #[test]
     fn config_save_and_load_tmpdir() {
         let dir = std::env::temp_dir().join("zeroclaw_test_config");
@@ -3377,9 +4375,11 @@ tool_dispatcher = "xml"
             autonomy: AutonomyConfig::default(),
             runtime: RuntimeConfig::default(),
             reliability: ReliabilityConfig::default(),
+            provider: ProviderConfig::default(),
             scheduler: SchedulerConfig::default(),
             model_routes: Vec::new(),
             query_classification: QueryClassificationConfig::default(),
+            routing: RoutingConfig::default(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
             channels_config: ChannelsConfig::default(),
@@ -3396,9 +4396,15 @@ tool_dispatcher = "xml"
             agent: AgentConfig::default(),
             identity: IdentityConfig::default(),
             cost: CostConfig::default(),
+            limits: LimitsConfig::default(),
             peripherals: PeripheralsConfig::default(),
             agents: HashMap::new(),
+            workspaces: HashMap::new(),
             hardware: HardwareConfig::default(),
+            skills: SkillsConfig::default(),
+            hooks: HooksConfig::default(),
+            config_version: crate::config::migrate::CURRENT_CONFIG_VERSION,
+            keychain_secrets: Vec::new(),
         };
 
         config.save().unwrap();
@@ -3500,6 +4506,41 @@ tool_dispatcher = "xml"
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn config_save_writes_keychain_placeholders_back() {
+        let dir = std::env::temp_dir().join(format!(
+            "zeroclaw_test_keychain_placeholders_{}",
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+
+        let raw = format!(
+            "config_version = {CURRENT_CONFIG_VERSION}\ndefault_temperature = 0.7\n\
+             api_key = \"$secret:openai\"\n[web_search]\nbrave_api_key = \"$secret:brave\"\n"
+        );
+        let mut config =
+            parse_versioned_config(&raw, &mut |name| Ok(format!("{name}-from-keychain"))).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("openai-from-keychain"));
+        assert_eq!(
+            config.web_search.brave_api_key.as_deref(),
+            Some("brave-from-keychain")
+        );
+
+        config.workspace_dir = dir.join("workspace");
+        config.config_path = dir.join("config.toml");
+        config.web_search.brave_api_key = Some("typed-in".into());
+        config.save().unwrap();
+
+        let contents = fs::read_to_string(&config.config_path).unwrap();
+        assert!(!contents.contains("from-keychain"));
+        let stored: Config = toml::from_str(&contents).unwrap();
+        assert_eq!(stored.api_key.as_deref(), Some("$secret:openai"));
+        let brave = stored.web_search.brave_api_key.as_deref().unwrap();
+        assert!(crate::security::SecretStore::is_encrypted(brave));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn config_save_atomic_cleanup() {
         let dir =
@@ -3746,6 +4787,9 @@ allowed_users = ["@ops:matrix.org"]
             lark: None,
             dingtalk: None,
             qq: None,
+            socket: None,
+            max_concurrent_sessions: 0,
+            session_overflow: SessionOverflow::Queue,
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -3909,6 +4953,9 @@ channel_id = "C123"
             lark: None,
             dingtalk: None,
             qq: None,
+            socket: None,
+            max_concurrent_sessions: 0,
+            session_overflow: SessionOverflow::Queue,
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -4137,6 +5184,8 @@ default_temperature = 0.7
         let b = BrowserConfig::default();
         assert!(!b.enabled);
         assert!(b.allowed_domains.is_empty());
+        assert!(!b.allow_scripts);
+        assert_eq!(b.max_script_length, 10_000);
         assert_eq!(b.backend, "agent_browser");
         assert!(b.native_headless);
         assert_eq!(b.native_webdriver_url, "http://127.0.0.1:9515");
@@ -4159,6 +5208,9 @@ default_temperature = 0.7
             native_headless: false,
             native_webdriver_url: "http://localhost:4444".into(),
             native_chrome_path: Some("/usr/bin/chromium".into()),
+            allow_screenshots: true,
+            allow_scripts: true,
+            max_script_length: 2_000,
             computer_use: BrowserComputerUseConfig {
                 endpoint: "https://computer-use.example.com/v1/actions".into(),
                 api_key: Some("test-token".into()),
@@ -4181,6 +5233,9 @@ default_temperature = 0.7
             parsed.native_chrome_path.as_deref(),
             Some("/usr/bin/chromium")
         );
+        assert!(parsed.allow_screenshots);
+        assert!(parsed.allow_scripts);
+        assert_eq!(parsed.max_script_length, 2_000);
         assert_eq!(
             parsed.computer_use.endpoint,
             "https://computer-use.example.com/v1/actions"
@@ -4541,6 +5596,157 @@ default_model = "legacy-model"
         let _ = fs::remove_dir_all(temp_home);
     }
 
+    #[test]
+    fn apply_workspace_uses_registry_path_and_overrides() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.workspaces.insert(
+            "client-a".into(),
+            WorkspaceProfile {
+                path: tmp.path().join("client-a"),
+                model: Some("gpt-4o-mini".into()),
+                autonomy: Some(AutonomyLevel::ReadOnly),
+            },
+        );
+
+        config.apply_workspace("client-a").unwrap();
+        assert_eq!(config.workspace_dir, tmp.path().join("client-a"));
+        assert!(config.workspace_dir.is_dir());
+        assert_eq!(config.default_model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(config.autonomy.level, AutonomyLevel::ReadOnly);
+        assert_eq!(config.active_workspace_name(), Some("client-a"));
+    }
+
+    #[test]
+    fn apply_workspace_accepts_existing_directory() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        let model = config.default_model.clone();
+
+        config
+            .apply_workspace(tmp.path().to_str().unwrap())
+            .unwrap();
+        assert_eq!(config.workspace_dir, tmp.path());
+        assert_eq!(config.default_model, model);
+        assert_eq!(config.active_workspace_name(), None);
+
+        let err = config.apply_workspace("no-such-workspace").unwrap_err();
+        assert!(err.to_string().contains("Unknown workspace"));
+        assert!(config.apply_workspace("  ").is_err());
+
+        let file = tmp.path().join("notes.md");
+        fs::write(&file, "not a workspace").unwrap();
+        let err = config.apply_workspace(file.to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("is not a directory"));
+    }
+
+    #[test]
+    fn workspace_override_becomes_security_policy_root() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config
+            .apply_workspace(tmp.path().to_str().unwrap())
+            .unwrap();
+
+        let policy =
+            crate::security::SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+        assert_eq!(policy.workspace_dir, tmp.path());
+        assert!(policy.is_path_allowed("src/main.rs"));
+        assert!(!policy.is_path_allowed("../outside.txt"));
+    }
+
+    #[test]
+    fn workspace_file_overrides_global_model() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(WORKSPACE_CONFIG_FILE),
+            "default_model = \"project-model\"\n\n[agent]\nmax_tool_iterations = 25\n",
+        )
+        .unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            default_model: Some("global-model".into()),
+            ..Config::default()
+        };
+        config.agent.turn_summary = false;
+
+        config.apply_workspace_file().unwrap();
+        assert_eq!(config.default_model.as_deref(), Some("project-model"));
+        assert_eq!(config.agent.max_tool_iterations, 25);
+        // Keys the file doesn't set keep the user's values.
+        assert!(!config.agent.turn_summary);
+    }
+
+    #[test]
+    fn workspace_file_cannot_override_security_or_credentials() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(WORKSPACE_CONFIG_FILE),
+            "api_key = \"sk-project\"\n\n[autonomy]\nlevel = \"full\"\n",
+        )
+        .unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            api_key: Some("sk-user".into()),
+            ..Config::default()
+        };
+
+        config.apply_workspace_file().unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("sk-user"));
+        assert_eq!(config.autonomy.level, AutonomyLevel::Supervised);
+
+        // Nor can it switch off the prompt-injection filter.
+        fs::write(
+            tmp.path().join(WORKSPACE_CONFIG_FILE),
+            "[agent]\ntool_output_injection = \"off\"\n\n[security]\ntool_output_injection = \"off\"\n",
+        )
+        .unwrap();
+        config.security.tool_output_injection = InjectionFilterMode::Redact;
+        config.apply_workspace_file().unwrap();
+        assert_eq!(
+            config.security.tool_output_injection,
+            InjectionFilterMode::Redact
+        );
+
+        fs::write(
+            tmp.path().join(WORKSPACE_CONFIG_FILE),
+            "default_provider = \"custom:https://attacker.example\"\n",
+        )
+        .unwrap();
+        assert!(config.apply_workspace_file().is_err());
+    }
+
+    #[test]
+    fn missing_workspace_file_is_a_no_op() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let model = config.default_model.clone();
+        config.apply_workspace_file().unwrap();
+        assert_eq!(config.default_model, model);
+    }
+
+    #[test]
+    fn workspaces_registry_roundtrips_toml() {
+        let raw = r#"
+default_temperature = 0.7
+
+[workspaces.client-a]
+path = "/work/client-a"
+model = "claude-sonnet-4"
+autonomy = "full"
+"#;
+        let parsed: Config = toml::from_str(raw).unwrap();
+        let profile = &parsed.workspaces["client-a"];
+        assert_eq!(profile.path, PathBuf::from("/work/client-a"));
+        assert_eq!(profile.autonomy, Some(AutonomyLevel::Full));
+
+        let reparsed: Config = toml::from_str(&toml::to_string(&parsed).unwrap()).unwrap();
+        assert_eq!(reparsed.workspaces, parsed.workspaces);
+    }
+
     #[test]
     fn env_override_empty_values_ignored() {
         let _env_guard = env_override_test_guard();
@@ -0,0 +1,268 @@
+//! Hard per-session spend limits (`[limits]`).
+//!
+//! A [`SessionBudget`] adds up the estimated tokens and cost of every
+//! provider call in one session. The agent loop checks it before each call
+//! and stops once the next call would take the session past
+//! `max_tokens_per_session` or `max_cost_per_session`. Cost is priced from
+//! the `[cost] prices` table; calls to a model without a price only count
+//! towards the token limit.
+
+use super::types::TokenUsage;
+use crate::config::schema::{CostConfig, LimitsConfig, ModelPricing};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Limits {
+    max_tokens: Option<u64>,
+    max_cost_usd: Option<f64>,
+}
+
+impl Limits {
+    fn from_config(config: &LimitsConfig) -> Self {
+        let max_cost = config.max_cost_per_session;
+        Self {
+            max_tokens: (config.max_tokens_per_session > 0)
+                .then_some(config.max_tokens_per_session),
+            max_cost_usd: (max_cost.is_finite() && max_cost > 0.0).then_some(max_cost),
+        }
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.max_tokens.is_none() && self.max_cost_usd.is_none()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Spend {
+    limits: Limits,
+    tokens: u64,
+    cost_usd: f64,
+}
+
+/// Tokens and cost spent by one session, held to its limits.
+pub struct SessionBudget {
+    /// Limits from config; raising the budget adds this much again.
+    allotment: Limits,
+    prices: HashMap<String, ModelPricing>,
+    spend: Mutex<Spend>,
+}
+
+/// The session's spend when its next provider call was refused.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetExhausted {
+    pub tokens_used: u64,
+    pub max_tokens: Option<u64>,
+    pub cost_usd: f64,
+    pub max_cost_usd: Option<f64>,
+}
+
+impl fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(max) = self.max_tokens {
+            parts.push(format!("~{} of {max} tokens", self.tokens_used));
+        }
+        if let Some(max) = self.max_cost_usd {
+            parts.push(format!("${:.4} of ${max:.2}", self.cost_usd));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+impl SessionBudget {
+    /// A budget for one session, or `None` when `[limits]` sets no limit.
+    pub fn from_config(limits: &LimitsConfig, cost: &CostConfig) -> Option<Self> {
+        Self::new(Limits::from_config(limits), cost.prices.clone())
+    }
+
+    fn new(limits: Limits, prices: HashMap<String, ModelPricing>) -> Option<Self> {
+        (!limits.is_unlimited()).then(|| Self {
+            allotment: limits,
+            prices,
+            spend: Mutex::new(Spend {
+                limits,
+                ..Spend::default()
+            }),
+        })
+    }
+
+    /// Price for `model`, listed either as is or as `<provider>/<model>`.
+    fn price(&self, model: &str) -> Option<&ModelPricing> {
+        self.prices.get(model).or_else(|| {
+            self.prices
+                .iter()
+                .find(|(key, _)| key.rsplit_once('/').is_some_and(|(_, name)| name == model))
+                .map(|(_, price)| price)
+        })
+    }
+
+    fn usage(&self, model: &str, input_tokens: u64, output_tokens: u64) -> TokenUsage {
+        let (input_price, output_price) = self
+            .price(model)
+            .map_or((0.0, 0.0), |price| (price.input, price.output));
+        TokenUsage::new(
+            model,
+            input_tokens,
+            output_tokens,
+            input_price,
+            output_price,
+        )
+    }
+
+    /// Refuse a call sending `input_tokens` of prompt to `model` when it
+    /// would take the session past a limit.
+    pub fn check(&self, model: &str, input_tokens: u64) -> Result<(), BudgetExhausted> {
+        let next = self.usage(model, input_tokens, 0);
+        let spend = self.spend.lock();
+        let over_tokens = spend
+            .limits
+            .max_tokens
+            .is_some_and(|max| spend.tokens.saturating_add(next.total_tokens) > max);
+        let over_cost = spend
+            .limits
+            .max_cost_usd
+            .is_some_and(|max| spend.cost_usd + next.cost_usd > max);
+        if over_tokens || over_cost {
+            return Err(BudgetExhausted {
+                tokens_used: spend.tokens,
+                max_tokens: spend.limits.max_tokens,
+                cost_usd: spend.cost_usd,
+                max_cost_usd: spend.limits.max_cost_usd,
+            });
+        }
+        Ok(())
+    }
+
+    /// Add a finished call to the session's spend.
+    pub fn record(&self, model: &str, input_tokens: u64, output_tokens: u64) {
+        let usage = self.usage(model, input_tokens, output_tokens);
+        let mut spend = self.spend.lock();
+        spend.tokens = spend.tokens.saturating_add(usage.total_tokens);
+        spend.cost_usd += usage.cost_usd;
+    }
+
+    /// Give the session another configured allotment on top of its limits.
+    pub fn raise(&self) {
+        let mut spend = self.spend.lock();
+        if let (Some(max), Some(extra)) = (&mut spend.limits.max_tokens, self.allotment.max_tokens)
+        {
+            *max = max.saturating_add(extra);
+        }
+        if let (Some(max), Some(extra)) =
+            (&mut spend.limits.max_cost_usd, self.allotment.max_cost_usd)
+        {
+            *max += extra;
+        }
+    }
+}
+
+/// Budgets for many concurrent sessions, such as channel conversations,
+/// created on first use.
+#[derive(Default)]
+pub struct SessionBudgets {
+    limits: Limits,
+    prices: HashMap<String, ModelPricing>,
+    sessions: Mutex<HashMap<String, Arc<SessionBudget>>>,
+}
+
+impl SessionBudgets {
+    pub fn from_config(limits: &LimitsConfig, cost: &CostConfig) -> Self {
+        Self {
+            limits: Limits::from_config(limits),
+            prices: cost.prices.clone(),
+            sessions: Mutex::default(),
+        }
+    }
+
+    /// The budget of session `key`, or `None` when `[limits]` sets no limit.
+    pub fn for_session(&self, key: &str) -> Option<Arc<SessionBudget>> {
+        if self.limits.is_unlimited() {
+            return None;
+        }
+        let mut sessions = self.sessions.lock();
+        if let Some(budget) = sessions.get(key) {
+            return Some(Arc::clone(budget));
+        }
+        let budget = Arc::new(SessionBudget::new(self.limits, self.prices.clone())?);
+        sessions.insert(key.to_string(), Arc::clone(&budget));
+        Some(budget)
+    }
+
+    /// Forget session `key`, so its next message starts a fresh budget.
+    pub fn end_session(&self, key: &str) {
+        self.sessions.lock().remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(max_tokens_per_session: u64, max_cost_per_session: f64) -> LimitsConfig {
+        LimitsConfig {
+            max_tokens_per_session,
+            max_cost_per_session,
+        }
+    }
+
+    #[test]
+    fn no_limits_means_no_budget() {
+        let cost = CostConfig::default();
+        assert!(SessionBudget::from_config(&limits(0, 0.0), &cost).is_none());
+        assert!(SessionBudget::from_config(&limits(0, f64::NAN), &cost).is_none());
+        assert!(SessionBudgets::default().for_session("a").is_none());
+    }
+
+    #[test]
+    fn the_call_that_would_cross_the_token_limit_is_refused() {
+        let budget =
+            SessionBudget::from_config(&limits(1_000, 0.0), &CostConfig::default()).unwrap();
+        budget.check("any-model", 600).unwrap();
+        budget.record("any-model", 600, 200);
+
+        let exhausted = budget.check("any-model", 300).unwrap_err();
+        assert_eq!(exhausted.tokens_used, 800);
+        assert_eq!(exhausted.max_tokens, Some(1_000));
+        budget.check("any-model", 200).unwrap();
+
+        budget.raise();
+        budget.check("any-model", 300).unwrap();
+        assert_eq!(
+            budget.check("any-model", 1_500).unwrap_err().max_tokens,
+            Some(2_000)
+        );
+    }
+
+    #[test]
+    fn cost_uses_the_price_table_and_skips_unpriced_models() {
+        // claude-sonnet-4: $3 in / $15 out per million tokens.
+        let budget = SessionBudget::from_config(&limits(0, 0.02), &CostConfig::default()).unwrap();
+        budget.record("claude-sonnet-4-20250514", 1_000, 1_000);
+        let exhausted = budget
+            .check("anthropic/claude-sonnet-4-20250514", 1_000)
+            .unwrap_err();
+        assert!((exhausted.cost_usd - 0.018).abs() < 1e-9, "{exhausted:?}");
+        assert_eq!(exhausted.to_string(), "$0.0180 of $0.02");
+
+        // Without a price only the token limit could stop it.
+        budget.check("local-llama", 1_000_000).unwrap();
+    }
+
+    #[test]
+    fn sessions_have_separate_budgets() {
+        let budgets = SessionBudgets::from_config(&limits(100, 0.0), &CostConfig::default());
+        budgets.for_session("alice").unwrap().record("m", 90, 0);
+        assert!(budgets
+            .for_session("alice")
+            .unwrap()
+            .check("m", 20)
+            .is_err());
+        assert!(budgets.for_session("bob").unwrap().check("m", 20).is_ok());
+
+        budgets.end_session("alice");
+        assert!(budgets.for_session("alice").unwrap().check("m", 20).is_ok());
+    }
+}
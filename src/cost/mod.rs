@@ -1,5 +1,7 @@
+pub mod budget;
 pub mod tracker;
 pub mod types;
 
+pub use budget::{BudgetExhausted, SessionBudget, SessionBudgets};
 pub use tracker::CostTracker;
 pub use types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
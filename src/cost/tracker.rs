@@ -149,6 +149,10 @@ impl CostTracker {
             .iter()
             .map(|record| record.usage.total_tokens)
             .sum();
+        let reasoning_tokens: u64 = session_costs
+            .iter()
+            .map(|record| record.usage.reasoning_tokens)
+            .sum();
         let request_count = session_costs.len();
         let by_model = build_session_model_stats(&session_costs);
 
@@ -157,6 +161,7 @@ impl CostTracker {
             daily_cost_usd: daily_cost,
             monthly_cost_usd: monthly_cost,
             total_tokens,
+            reasoning_tokens,
             request_count,
             by_model,
         })
@@ -214,11 +219,13 @@ fn build_session_model_stats(session_costs: &[CostRecord]) -> HashMap<String, Mo
                 model: record.usage.model.clone(),
                 cost_usd: 0.0,
                 total_tokens: 0,
+                reasoning_tokens: 0,
                 request_count: 0,
             });
 
         entry.cost_usd += record.usage.cost_usd;
         entry.total_tokens += record.usage.total_tokens;
+        entry.reasoning_tokens += record.usage.reasoning_tokens;
         entry.request_count += 1;
     }
 
@@ -447,6 +454,32 @@ mod tests {
         assert_eq!(summary.by_model.len(), 1);
     }
 
+    #[test]
+    fn reasoning_tokens_are_counted_separately() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+
+        let usage =
+            TokenUsage::new("test/reasoner", 1000, 500, 1.0, 2.0).with_reasoning_tokens(300);
+        let cost = usage.cost_usd;
+        tracker.record_usage(usage).unwrap();
+        tracker
+            .record_usage(TokenUsage::new("test/reasoner", 10, 10, 1.0, 2.0))
+            .unwrap();
+
+        let summary = tracker.get_summary().unwrap();
+        assert_eq!(summary.reasoning_tokens, 300);
+        assert_eq!(summary.total_tokens, 1520);
+        assert_eq!(summary.by_model["test/reasoner"].reasoning_tokens, 300);
+        assert!(summary.session_cost_usd >= cost);
+        assert_eq!(
+            TokenUsage::new("m", 0, 5, 0.0, 0.0)
+                .with_reasoning_tokens(50)
+                .reasoning_tokens,
+            5
+        );
+    }
+
     #[test]
     fn budget_exceeded_daily_limit() {
         let tmp = TempDir::new().unwrap();
@@ -9,6 +9,9 @@ pub struct TokenUsage {
     pub input_tokens: u64,
     /// Output/completion tokens
     pub output_tokens: u64,
+    /// Reasoning ("thinking") tokens, a subset of `output_tokens`
+    #[serde(default)]
+    pub reasoning_tokens: u64,
     /// Total tokens
     pub total_tokens: u64,
     /// Calculated cost in USD
@@ -48,12 +51,22 @@ impl TokenUsage {
             model,
             input_tokens,
             output_tokens,
+            reasoning_tokens: 0,
             total_tokens,
             cost_usd,
             timestamp: chrono::Utc::now(),
         }
     }
 
+    /// Record how many of the output tokens were spent on reasoning.
+    /// Clamped to `output_tokens`; cost is unchanged since reasoning is billed
+    /// as output.
+    #[must_use]
+    pub fn with_reasoning_tokens(mut self, reasoning_tokens: u64) -> Self {
+        self.reasoning_tokens = reasoning_tokens.min(self.output_tokens);
+        self
+    }
+
     /// Get the total cost.
     pub fn cost(&self) -> f64 {
         self.cost_usd
@@ -120,6 +133,9 @@ pub struct CostSummary {
     pub monthly_cost_usd: f64,
     /// Total tokens used
     pub total_tokens: u64,
+    /// Reasoning tokens included in `total_tokens`
+    #[serde(default)]
+    pub reasoning_tokens: u64,
     /// Number of requests
     pub request_count: usize,
     /// Breakdown by model
@@ -135,6 +151,9 @@ pub struct ModelStats {
     pub cost_usd: f64,
     /// Total tokens for this model
     pub total_tokens: u64,
+    /// Reasoning tokens for this model
+    #[serde(default)]
+    pub reasoning_tokens: u64,
     /// Number of requests for this model
     pub request_count: usize,
 }
@@ -146,6 +165,7 @@ impl Default for CostSummary {
             daily_cost_usd: 0.0,
             monthly_cost_usd: 0.0,
             total_tokens: 0,
+            reasoning_tokens: 0,
             request_count: 0,
             by_model: std::collections::HashMap::new(),
         }
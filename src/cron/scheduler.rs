@@ -6,6 +6,7 @@ use crate::cron::{
     due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job, reschedule_after_run,
     update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
 };
+use crate::runtime::{NativeRuntime, RuntimeAdapter};
 use crate::security::SecurityPolicy;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -131,6 +132,7 @@ async fn run_agent_job(config: &Config, job: &CronJob) -> (bool, String) {
                 model_override,
                 config.default_temperature,
                 vec![],
+                crate::agent::session::SessionMode::Ephemeral,
             )
             .await
         }
@@ -432,10 +434,23 @@ async fn run_job_command_with_timeout(
         );
     }
 
-    let child = match Command::new("sh")
-        .arg("-lc")
-        .arg(&job.command)
-        .current_dir(&config.workspace_dir)
+    let mut command = if cfg!(windows) {
+        match NativeRuntime::new()
+            .with_windows_shell(config.runtime.windows_shell.clone())
+            .build_shell_command(&job.command, &config.workspace_dir)
+        {
+            Ok(command) => command,
+            Err(e) => return (false, format!("spawn error: {e}")),
+        }
+    } else {
+        let mut command = Command::new("sh");
+        command
+            .arg("-lc")
+            .arg(&job.command)
+            .current_dir(&config.workspace_dir);
+        command
+    };
+    let child = match command
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -7,6 +7,7 @@ use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
 const STATUS_FLUSH_SECONDS: u64 = 5;
+const COMPACTION_INTERVAL_SECONDS: u64 = 24 * 60 * 60;
 
 pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     let initial_backoff = config.reliability.channel_initial_backoff_secs.max(1);
@@ -71,6 +72,19 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         ));
     }
 
+    if config.memory.compact_in_daemon {
+        let compaction_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "memory-compaction",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = compaction_cfg.clone();
+                async move { run_compaction_worker(cfg).await }
+            },
+        ));
+    }
+
     if config.cron.enabled {
         let scheduler_cfg = config.clone();
         handles.push(spawn_component_supervisor(
@@ -196,8 +210,16 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
         for task in tasks {
             let prompt = format!("[Heartbeat Task] {task}");
             let temp = config.default_temperature;
-            if let Err(e) =
-                crate::agent::run(config.clone(), Some(prompt), None, None, temp, vec![]).await
+            if let Err(e) = crate::agent::run(
+                config.clone(),
+                Some(prompt),
+                None,
+                None,
+                temp,
+                vec![],
+                crate::agent::session::SessionMode::Ephemeral,
+            )
+            .await
             {
                 crate::health::mark_component_error("heartbeat", e.to_string());
                 tracing::warn!("Heartbeat task failed: {e}");
@@ -208,6 +230,34 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
     }
 }
 
+/// Compact daily memory once a day (first pass at startup).
+async fn run_compaction_worker(config: Config) -> Result<()> {
+    let mut interval = tokio::time::interval(Duration::from_secs(COMPACTION_INTERVAL_SECONDS));
+
+    loop {
+        interval.tick().await;
+
+        match crate::memory::compaction::compact_configured(
+            &config,
+            config.memory.compact_after_days,
+            false,
+        )
+        .await
+        {
+            Ok(days) => {
+                crate::health::mark_component_ok("memory-compaction");
+                if !days.is_empty() {
+                    tracing::info!("Compacted daily memory for {} day(s)", days.len());
+                }
+            }
+            Err(e) => {
+                crate::health::mark_component_error("memory-compaction", e.to_string());
+                tracing::warn!("Memory compaction failed: {e}");
+            }
+        }
+    }
+}
+
 fn has_supervised_channels(config: &Config) -> bool {
     config.channels_config.telegram.is_some()
         || config.channels_config.discord.is_some()
@@ -220,6 +270,7 @@ fn has_supervised_channels(config: &Config) -> bool {
         || config.channels_config.irc.is_some()
         || config.channels_config.lark.is_some()
         || config.channels_config.dingtalk.is_some()
+        || config.channels_config.socket.is_some()
 }
 
 #[cfg(test)]
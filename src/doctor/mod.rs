@@ -355,6 +355,7 @@ fn check_config_semantics(config: &Config, items: &mut Vec<DiagItem>) {
         || cc.email.is_some()
         || cc.irc.is_some()
         || cc.lark.is_some()
+        || cc.socket.is_some()
         || cc.webhook.is_some();
 
     if has_channel {
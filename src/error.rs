@@ -0,0 +1,93 @@
+//! Typed errors for embedding ZeroClaw.
+//!
+//! Only the embedding API, [`Agent`] and [`AuthService`], returns
+//! [`ZeroClawError`], so callers can match on what failed. Every other
+//! public module is part of the `zeroclaw` binary and returns
+//! `anyhow::Result`. Each variant wraps the original `anyhow::Error`: the
+//! message and context chain are unchanged, and
+//! [`ZeroClawError::provider_error`] still finds a typed [`ProviderError`].
+//!
+//! [`Agent`]: crate::agent::Agent
+//! [`AuthService`]: crate::auth::AuthService
+
+use crate::providers::error::ProviderError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ZeroClawError {
+    /// The model provider could not be created or failed a request.
+    #[error(transparent)]
+    ProviderError(anyhow::Error),
+    /// The tool loop could not finish the turn.
+    #[error(transparent)]
+    ToolError(anyhow::Error),
+    /// The memory backend could not be opened.
+    #[error(transparent)]
+    MemoryError(anyhow::Error),
+    /// An auth profile could not be read, stored or refreshed.
+    #[error(transparent)]
+    OAuthError(anyhow::Error),
+    /// The configuration is invalid or incomplete.
+    #[error(transparent)]
+    ConfigError(anyhow::Error),
+}
+
+impl ZeroClawError {
+    /// The wrapped error, with its full context chain.
+    pub fn inner(&self) -> &anyhow::Error {
+        match self {
+            Self::ProviderError(e)
+            | Self::ToolError(e)
+            | Self::MemoryError(e)
+            | Self::OAuthError(e)
+            | Self::ConfigError(e) => e,
+        }
+    }
+
+    pub fn into_inner(self) -> anyhow::Error {
+        match self {
+            Self::ProviderError(e)
+            | Self::ToolError(e)
+            | Self::MemoryError(e)
+            | Self::OAuthError(e)
+            | Self::ConfigError(e) => e,
+        }
+    }
+
+    /// The typed provider failure behind this error, if there is one.
+    pub fn provider_error(&self) -> Option<&ProviderError> {
+        ProviderError::find(self.inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn provider_error_finds_the_typed_cause() {
+        let provider = anyhow::Error::new(ProviderError::AuthFailed {
+            message: "OpenAI API error (401): invalid key".into(),
+        })
+        .context("Agent turn failed");
+        let err = ZeroClawError::ProviderError(provider);
+        assert!(matches!(
+            err.provider_error(),
+            Some(ProviderError::AuthFailed { .. })
+        ));
+
+        let tool = ZeroClawError::ToolError(anyhow::anyhow!("tool loop exhausted"));
+        assert!(tool.provider_error().is_none());
+    }
+
+    #[test]
+    fn display_is_the_wrapped_message() {
+        let err: anyhow::Result<()> = Err(anyhow::anyhow!("no such file")).context("Bad config");
+        let err = ZeroClawError::ConfigError(err.unwrap_err());
+        assert_eq!(err.to_string(), "Bad config");
+        assert_eq!(
+            format!("{:#}", err.into_inner()),
+            "Bad config: no such file"
+        );
+    }
+}
@@ -10,6 +10,7 @@
 use crate::channels::{Channel, SendMessage, WhatsAppChannel};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
+use crate::observability::prometheus::PROMETHEUS_CONTENT_TYPE;
 use crate::providers::{self, Provider};
 use crate::runtime;
 use crate::security::pairing::{constant_time_eq, is_public_bind, PairingGuard};
@@ -306,6 +307,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             auth_profile_override: None,
             zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
             secrets_encrypt: config.secrets.encrypt,
+            header_overrides: config.provider.overrides.clone(),
+            reasoning_effort: config.provider.reasoning_effort.clone(),
+            custom_providers: config.provider.custom.clone(),
+            response_cache: config.provider.cache.clone(),
+            prompt_audit: crate::observability::prompt_audit::PromptAuditLog::from_config(
+                &config.observability,
+            )?
+            .map(Arc::new),
         },
     )?);
     let model = config
@@ -313,12 +322,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "anthropic/claude-sonnet-4".into());
     let temperature = config.default_temperature;
-    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
+    let (mem, _) = memory::create_session_memory(
         &config.memory,
         Some(&config.storage.provider.config),
         &config.workspace_dir,
         config.api_key.as_deref(),
-    )?);
+        crate::util::LocalTimezone::from_config(config.agent.timezone.as_deref()),
+    )?;
+    let mem: Arc<dyn Memory> = Arc::from(mem);
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
     let security = Arc::new(SecurityPolicy::from_config(
@@ -518,9 +529,6 @@ async fn handle_health(State(state): State<AppState>) -> impl IntoResponse {
     Json(body)
 }
 
-/// Prometheus content type for text exposition format.
-const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
-
 /// GET /metrics — Prometheus text exposition format
 async fn handle_metrics(State(state): State<AppState>) -> impl IntoResponse {
     let body = if let Some(prom) = state
@@ -747,6 +755,8 @@ async fn handle_webhook(
                     duration,
                     success: true,
                     error_message: None,
+                    input_tokens: Some(crate::util::estimate_tokens(message.chars().count())),
+                    output_tokens: Some(crate::util::estimate_tokens(response.chars().count())),
                 });
             state.observer.record_metric(
                 &crate::observability::traits::ObserverMetric::RequestLatency(duration),
@@ -759,6 +769,7 @@ async fn handle_webhook(
                     duration,
                     tokens_used: None,
                     cost_usd: None,
+                    interrupted: false,
                 });
 
             let body = serde_json::json!({"response": response, "model": state.model});
@@ -776,6 +787,8 @@ async fn handle_webhook(
                     duration,
                     success: false,
                     error_message: Some(sanitized.clone()),
+                    input_tokens: None,
+                    output_tokens: None,
                 });
             state.observer.record_metric(
                 &crate::observability::traits::ObserverMetric::RequestLatency(duration),
@@ -794,6 +807,7 @@ async fn handle_webhook(
                     duration,
                     tokens_used: None,
                     cost_usd: None,
+                    interrupted: false,
                 });
 
             tracing::error!("Webhook provider error: {}", sanitized);
@@ -0,0 +1,311 @@
+//! Translations for user-facing CLI strings.
+//!
+//! Messages live in static tables keyed by dotted names; `{name}`
+//! placeholders are filled by [`tf`]. The active locale comes from
+//! `[agent] locale` and is set once at startup. A key missing from the
+//! active locale falls back to English, and an unknown key renders as
+//! itself, so a gap in a table never panics.
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Locale {
+    En = 0,
+    De = 1,
+}
+
+impl Locale {
+    /// Parse a locale tag such as `de`, `de-DE` or `de_AT.UTF-8`; unset or
+    /// unsupported tags use English.
+    pub fn from_config(tag: Option<&str>) -> Self {
+        let Some(tag) = tag.map(str::trim).filter(|t| !t.is_empty()) else {
+            return Self::En;
+        };
+        let language = tag
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" => Self::En,
+            "de" => Self::De,
+            _ => {
+                tracing::warn!("Unsupported locale '{tag}'; using English");
+                Self::En
+            }
+        }
+    }
+
+    /// English name of the language, for the system prompt.
+    pub fn language_name(self) -> &'static str {
+        match self {
+            Self::En => "English",
+            Self::De => "German",
+        }
+    }
+
+    fn table(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::En => EN,
+            Self::De => DE,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::De,
+            _ => Self::En,
+        }
+    }
+}
+
+const EN: &[(&str, &str)] = &[
+    ("tool.executing", "🔧 Executing {count} tool call(s)"),
+    ("explain.rationale", "🧭 Why {tools}: {rationale}"),
+    (
+        "context.compacted",
+        "🧹 Context window exceeded; compacted history, retrying",
+    ),
+    ("plan.confirm", "Proceed with this plan? [Y/n] "),
+    ("plan.rejected", "Plan rejected; nothing was executed."),
+    ("turn.cancelled", "Turn cancelled."),
+    (
+        "budget.exhausted",
+        "⛔ Session budget exhausted ({spent}); stopped before the next model call.",
+    ),
+    (
+        "budget.raise",
+        "⛔ Session budget exhausted ({spent}). Raise it for this session? [y/N] ",
+    ),
+    ("approval.request", "🔧 Agent wants to execute: {tool}"),
+    ("approval.prompt", "   [Y]es / [N]o / [A]lways for {tool}: "),
+    ("answer.yes", "y,yes"),
+    ("answer.always", "a,always"),
+    ("repl.banner", "🦀 {name} Interactive Mode"),
+    ("repl.help_hint", "Type /help for commands."),
+    (
+        "repl.clear_warning",
+        "This will clear the current conversation and delete all session memory.",
+    ),
+    (
+        "repl.clear_keeps_core",
+        "Core memories (long-term facts/preferences) will be preserved.",
+    ),
+    ("repl.confirm", "Continue? [y/N] "),
+    ("repl.cancelled", "Cancelled."),
+    ("repl.cleared", "Conversation cleared."),
+    (
+        "repl.cleared_entries",
+        "Conversation cleared ({count} memory entries removed).",
+    ),
+    ("oauth.title", "ZeroClaw login complete"),
+    ("oauth.body", "You can close this tab."),
+    (
+        "provider.auth_failed",
+        "Your {provider} credentials were rejected — run `{command}` to set them again",
+    ),
+    (
+        "provider.model_not_found",
+        "Model {model} is not available from {provider}; check default_model or set [reliability] fallback_model",
+    ),
+    (
+        "provider.rate_limited",
+        "{provider} is rate limiting requests; try again shortly",
+    ),
+    (
+        "provider.context_length",
+        "The conversation no longer fits the context window of {model}; start a new session",
+    ),
+    (
+        "provider.content_filtered",
+        "{provider} refused the request under its content policy",
+    ),
+    (
+        "provider.network",
+        "Could not reach {provider}; check your network connection and proxy settings",
+    ),
+    (
+        "provider.quota_exceeded",
+        "Your {provider} plan, balance or quota does not cover this request; check the account or set [reliability] fallback_model",
+    ),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("tool.executing", "🔧 Führe {count} Tool-Aufruf(e) aus"),
+    ("explain.rationale", "🧭 Warum {tools}: {rationale}"),
+    (
+        "context.compacted",
+        "🧹 Kontextfenster überschritten; Verlauf komprimiert, neuer Versuch",
+    ),
+    ("plan.confirm", "Mit diesem Plan fortfahren? [J/n] "),
+    ("plan.rejected", "Plan abgelehnt; nichts wurde ausgeführt."),
+    ("turn.cancelled", "Durchlauf abgebrochen."),
+    (
+        "budget.exhausted",
+        "⛔ Sitzungsbudget aufgebraucht ({spent}); vor dem nächsten Modellaufruf angehalten.",
+    ),
+    (
+        "budget.raise",
+        "⛔ Sitzungsbudget aufgebraucht ({spent}). Für diese Sitzung erhöhen? [j/N] ",
+    ),
+    ("approval.request", "🔧 Der Agent möchte ausführen: {tool}"),
+    ("approval.prompt", "   [J]a / [N]ein / [I]mmer für {tool}: "),
+    ("answer.yes", "j,ja,y,yes"),
+    ("answer.always", "i,immer,a,always"),
+    ("repl.banner", "🦀 {name} Interaktiver Modus"),
+    ("repl.help_hint", "Gib /help für eine Befehlsübersicht ein."),
+    (
+        "repl.clear_warning",
+        "Dies leert die aktuelle Unterhaltung und löscht den gesamten Sitzungsspeicher.",
+    ),
+    (
+        "repl.clear_keeps_core",
+        "Kernerinnerungen (langfristige Fakten/Vorlieben) bleiben erhalten.",
+    ),
+    ("repl.confirm", "Fortfahren? [j/N] "),
+    ("repl.cancelled", "Abgebrochen."),
+    ("repl.cleared", "Unterhaltung geleert."),
+    (
+        "repl.cleared_entries",
+        "Unterhaltung geleert ({count} Speichereinträge entfernt).",
+    ),
+    ("oauth.title", "ZeroClaw-Anmeldung abgeschlossen"),
+    ("oauth.body", "Du kannst diesen Tab schließen."),
+    (
+        "provider.auth_failed",
+        "Deine Zugangsdaten für {provider} wurden abgelehnt — führe `{command}` aus, um sie neu zu setzen",
+    ),
+    (
+        "provider.model_not_found",
+        "Modell {model} ist bei {provider} nicht verfügbar; prüfe default_model oder setze [reliability] fallback_model",
+    ),
+    (
+        "provider.rate_limited",
+        "{provider} drosselt Anfragen; versuche es gleich noch einmal",
+    ),
+    (
+        "provider.context_length",
+        "Die Unterhaltung passt nicht mehr in das Kontextfenster von {model}; starte eine neue Sitzung",
+    ),
+    (
+        "provider.content_filtered",
+        "{provider} hat die Anfrage aufgrund seiner Inhaltsrichtlinien abgelehnt",
+    ),
+    (
+        "provider.network",
+        "{provider} ist nicht erreichbar; prüfe Netzwerkverbindung und Proxy-Einstellungen",
+    ),
+    (
+        "provider.quota_exceeded",
+        "Dein {provider}-Tarif, Guthaben oder Kontingent deckt diese Anfrage nicht ab; prüfe das Konto oder setze [reliability] fallback_model",
+    ),
+];
+
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    Locale::from_u8(LOCALE.load(Ordering::Relaxed))
+}
+
+fn lookup(locale: Locale, key: &'static str) -> &'static str {
+    let find = |table: &'static [(&'static str, &'static str)]| {
+        table.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    };
+    find(locale.table()).or_else(|| find(EN)).unwrap_or(key)
+}
+
+/// The message for `key` in the active locale.
+pub fn t(key: &'static str) -> &'static str {
+    lookup(locale(), key)
+}
+
+/// The message for `key` with `{name}` placeholders filled from `args`.
+pub fn tf(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    let mut message = t(key).to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    message
+}
+
+/// Whether `input` is one of the comma-separated answers under `key`
+/// (e.g. `answer.yes`), ignoring case and surrounding whitespace.
+pub fn is_answer(key: &'static str, input: &str) -> bool {
+    let input = input.trim().to_lowercase();
+    t(key).split(',').any(|answer| answer == input)
+}
+
+/// System prompt clause asking the model to answer in the configured
+/// language; empty for English.
+pub fn language_section() -> String {
+    match locale() {
+        Locale::En => String::new(),
+        other => format!(
+            "## Language\n\nRespond in {} unless the user explicitly asks for another language.",
+            other.language_name()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(message: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = message
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn every_english_key_resolves_in_every_locale() {
+        for locale in [Locale::En, Locale::De] {
+            for (key, english) in EN {
+                let message = lookup(locale, key);
+                assert!(!message.is_empty(), "{locale:?} {key}");
+                assert_eq!(
+                    placeholders(message),
+                    placeholders(english),
+                    "{locale:?} {key} must use the same placeholders as English"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn translations_only_use_english_keys() {
+        for (key, _) in DE {
+            assert!(
+                EN.iter().any(|(k, _)| k == key),
+                "German key {key} has no English message"
+            );
+        }
+    }
+
+    #[test]
+    fn missing_keys_fall_back_to_english_then_to_the_key() {
+        assert_eq!(lookup(Locale::De, "no.such.key"), "no.such.key");
+        assert_eq!(lookup(Locale::En, "repl.cancelled"), "Cancelled.");
+        assert_eq!(lookup(Locale::De, "repl.cancelled"), "Abgebrochen.");
+    }
+
+    #[test]
+    fn locale_tags_are_parsed_leniently() {
+        assert_eq!(Locale::from_config(None), Locale::En);
+        assert_eq!(Locale::from_config(Some("de")), Locale::De);
+        assert_eq!(Locale::from_config(Some("de-AT")), Locale::De);
+        assert_eq!(Locale::from_config(Some("de_DE.UTF-8")), Locale::De);
+        assert_eq!(Locale::from_config(Some("EN-gb")), Locale::En);
+        assert_eq!(Locale::from_config(Some("tlh")), Locale::En);
+    }
+}
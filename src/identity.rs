@@ -4,7 +4,7 @@
 //! portable AI identity. This module handles loading and converting AIEOS v1.1
 //! JSON to ZeroClaw's system prompt format.
 
-use crate::config::IdentityConfig;
+use crate::config::{AgentConfig, IdentityConfig};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -974,6 +974,29 @@ pub fn aieos_to_system_prompt(identity: &AieosIdentity) -> String {
     prompt.trim().to_string()
 }
 
+/// `## Persona` prompt section for `[agent] name` / `persona`; empty when
+/// neither is set so the default prompt is unchanged.
+pub fn persona_section(agent: &AgentConfig) -> String {
+    let persona = agent
+        .persona
+        .as_deref()
+        .map(str::trim)
+        .filter(|persona| !persona.is_empty());
+    if agent.name.is_none() && persona.is_none() {
+        return String::new();
+    }
+    let mut section = format!(
+        "## Persona\n\nYour name is {}. Refer to yourself by this name.",
+        agent.assistant_name()
+    );
+    if let Some(persona) = persona {
+        section.push_str("\n\n");
+        section.push_str(persona);
+    }
+    section.push('\n');
+    section
+}
+
 /// Check if AIEOS identity is configured and should be used.
 ///
 /// Returns true if format is "aieos" and either aieos_path or aieos_inline is set.
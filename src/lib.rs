@@ -42,15 +42,18 @@ pub mod agent;
 pub mod approval;
 pub mod auth;
 pub mod channels;
+pub mod checkpoints;
 pub mod config;
 pub mod cost;
 pub mod cron;
 pub mod daemon;
 pub mod doctor;
+pub mod error;
 pub mod gateway;
 pub mod hardware;
 pub mod health;
 pub mod heartbeat;
+pub mod i18n;
 pub mod identity;
 pub mod integrations;
 pub mod memory;
@@ -58,17 +61,22 @@ pub mod migration;
 pub mod observability;
 pub mod onboard;
 pub mod peripherals;
+pub mod personas;
+pub mod prompts;
 pub mod providers;
 pub mod rag;
 pub mod runtime;
 pub mod security;
 pub mod service;
 pub mod skills;
+pub mod term;
 pub mod tools;
 pub mod tunnel;
 pub mod util;
+pub mod workspace;
 
 pub use config::Config;
+pub use error::ZeroClawError;
 
 /// Service management subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -198,6 +206,114 @@ pub enum CronCommands {
     },
 }
 
+/// Git checkpoint subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CheckpointCommands {
+    /// List workspace checkpoints (newest first)
+    List,
+    /// Restore the workspace files to a checkpoint
+    Restore {
+        /// Checkpoint ID (or commit hash prefix)
+        id: String,
+    },
+}
+
+/// Memory maintenance subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MemoryCommands {
+    /// Merge old daily entries into one provider-written summary per day
+    Compact {
+        /// Compact entries older than this many days (default: `[memory] compact_after_days`)
+        #[arg(long)]
+        older_than_days: Option<u32>,
+        /// Show which entries would be merged without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Prompt template subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PromptCommands {
+    /// List prompt templates with their variables
+    List,
+}
+
+/// Prompt inspection subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ContextCommands {
+    /// Print the system prompt a run would start with (and the enriched message, if given)
+    Show {
+        /// Also show the user message as it would be sent, with its memory context
+        #[arg(long)]
+        message: Option<String>,
+        /// Print the estimated token count on stderr
+        #[arg(long)]
+        count_tokens: bool,
+    },
+}
+
+/// Config file subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ConfigCommands {
+    /// Write a starter config.toml (prompts on a terminal for anything not given)
+    Init {
+        /// Provider name (default: openrouter)
+        #[arg(long)]
+        provider: Option<String>,
+        /// Model (default: the provider's recommended model)
+        #[arg(long)]
+        model: Option<String>,
+        /// Where the API key lives: env (the provider's variable) or config
+        #[arg(long, value_parser = ["env", "config"])]
+        api_key_location: Option<String>,
+        /// API key to store in the config (implies --api-key-location config)
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Directory for config.toml and workspace/ (default: ~/.zeroclaw)
+        #[arg(long)]
+        dir: Option<std::path::PathBuf>,
+        /// Replace an existing config.toml (the old one is kept as config.toml.bak)
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Keychain secret subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SecretCommands {
+    /// Store a secret in the system keychain; reference it in config.toml as "$secret:<name>"
+    Add {
+        /// Secret name (letters, digits, '-', '_' or '.')
+        name: String,
+    },
+}
+
+/// Workspace registry subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WorkspaceCommands {
+    /// List registered workspaces
+    List,
+    /// Register a named workspace
+    Add {
+        /// Workspace name (letters, digits, '-', '_' or '.')
+        name: String,
+        /// Workspace directory (created if missing)
+        path: String,
+        /// Model to use while this workspace is active
+        #[arg(long)]
+        model: Option<String>,
+        /// Autonomy level while this workspace is active (readonly, supervised, full)
+        #[arg(long)]
+        autonomy: Option<String>,
+    },
+    /// Remove a workspace from the registry (files are kept)
+    Remove {
+        /// Workspace name
+        name: String,
+    },
+}
+
 /// Integration subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum IntegrationCommands {
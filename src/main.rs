@@ -33,7 +33,7 @@
 )]
 
 use anyhow::{bail, Result};
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use dialoguer::{Input, Password};
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
@@ -43,17 +43,22 @@ mod agent;
 mod approval;
 mod auth;
 mod channels;
+mod checkpoints;
+mod completions;
 mod rag {
     pub use zeroclaw::rag::*;
 }
 mod config;
+mod cost;
 mod cron;
 mod daemon;
 mod doctor;
+mod error;
 mod gateway;
 mod hardware;
 mod health;
 mod heartbeat;
+mod i18n;
 mod identity;
 mod integrations;
 mod memory;
@@ -61,15 +66,19 @@ mod migration;
 mod observability;
 mod onboard;
 mod peripherals;
+mod personas;
+mod prompts;
 mod providers;
 mod runtime;
 mod security;
 mod service;
 mod skillforge;
 mod skills;
+mod term;
 mod tools;
 mod tunnel;
 mod util;
+mod workspace;
 
 use config::Config;
 
@@ -83,6 +92,23 @@ pub use zeroclaw::{HardwareCommands, PeripheralCommands};
 #[command(version = "0.1.0")]
 #[command(about = "The fastest, smallest AI assistant.", long_about = None)]
 struct Cli {
+    /// Workspace to run in: a name from `[workspaces]` or a directory path
+    #[arg(long, global = true, value_name = "NAME_OR_PATH")]
+    workspace: Option<String>,
+
+    /// Cancel an `agent` run that takes longer than N seconds (exit code 124)
+    #[arg(long, global = true, value_name = "N")]
+    timeout_secs: Option<u64>,
+
+    /// Bypass the `[provider.cache]` response cache for this run
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Emoji and color in status output: `auto` (terminals only, honoring
+    /// NO_COLOR and CLICOLOR_FORCE), `always` or `never`
+    #[arg(long, global = true, default_value = "auto", value_parser = ["auto", "always", "never"])]
+    color: String,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -144,9 +170,117 @@ enum Commands {
         #[arg(short, long, default_value = "0.7")]
         temperature: f64,
 
+        /// Load a persona from `.zeroclaw/personas/<name>.toml` (system prompt,
+        /// tools and temperature)
+        #[arg(long)]
+        persona: Option<String>,
+
+        /// Read the message from a file (`-` reads stdin) instead of --message
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["message", "prompt"])]
+        file: Option<String>,
+
+        /// Run the prompt template `.zeroclaw/prompts/<name>.md` as the message
+        #[arg(long, conflicts_with = "message")]
+        prompt: Option<String>,
+
+        /// Value for a prompt template variable (name=value; repeatable)
+        #[arg(long = "var", value_name = "NAME=VALUE", requires = "prompt")]
+        vars: Vec<String>,
+
         /// Attach a peripheral (board:path, e.g. nucleo-f401re:/dev/ttyACM0)
         #[arg(long)]
         peripheral: Vec<String>,
+
+        /// Resume the most recent session in this workspace
+        #[arg(long = "continue")]
+        continue_session: bool,
+
+        /// Print only the final response (no progress on stderr)
+        #[arg(short, long, conflicts_with = "verbose")]
+        quiet: bool,
+
+        /// Show tool arguments and timing; repeat (-vv) for provider request metadata
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+
+        /// Have the model write a step-by-step plan (shown for approval) before it acts
+        #[arg(long)]
+        plan: bool,
+
+        /// Print the model's reasoning (thinking) dimmed on stderr
+        #[arg(long)]
+        show_thinking: bool,
+
+        /// Print, dimmed on stderr, the prose the model wrote to justify each round of tool calls
+        #[arg(long)]
+        explain: bool,
+
+        /// Print an estimated token breakdown of each request before it is sent (stderr)
+        #[arg(
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            num_args = 0..=1,
+            default_missing_value = "text"
+        )]
+        show_context_budget: Option<agent::context_budget::BudgetFormat>,
+
+        /// Keep the run's scratch directory (`.zeroclaw-tmp/run-*`) after exit
+        #[arg(long)]
+        keep_temp: bool,
+
+        /// Result format for single-message mode: text, or json with `response`
+        /// and `assistant_updates`
+        #[arg(long, default_value = "text", value_parser = ["text", "json"])]
+        output: String,
+
+        /// Re-run the message whenever a workspace file changes (until Ctrl-C)
+        #[arg(long, requires = "message", conflicts_with = "continue_session")]
+        watch: bool,
+    },
+
+    /// Run one agent message with tool results answered from a replay file
+    Replay {
+        /// JSON file of `{"tool", "args", "result"}` records
+        file: std::path::PathBuf,
+
+        /// Message to send to the agent
+        #[arg(short, long)]
+        message: String,
+    },
+
+    /// Run one prompt template over every record of a JSONL file or every file of a glob
+    Batch {
+        /// Prompt template; `{{field}}` is filled from each JSONL record,
+        /// `{{content}}`, `{{path}}` and `{{name}}` from each matched file
+        #[arg(long)]
+        template: std::path::PathBuf,
+
+        /// JSONL file or quoted file glob (e.g. "docs/**/*.md")
+        #[arg(long)]
+        input: String,
+
+        /// Number of inputs processed at once
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+
+        /// JSONL results file; inputs whose id is already present are skipped
+        #[arg(long, default_value = "results.jsonl")]
+        output: std::path::PathBuf,
+
+        /// Limit provider requests per minute across the whole batch (0 = no limit)
+        #[arg(long, default_value = "0")]
+        requests_per_minute: u32,
+    },
+
+    /// Run a predefined task from `.zeroclaw/tasks/<name>.toml`; lists tasks when no name is given
+    Task {
+        /// Task name (the file name without `.toml`)
+        name: Option<String>,
+
+        /// Values for the task's `required_args`, in order
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
     },
 
     /// Start the gateway server (webhooks, websockets)
@@ -198,8 +332,11 @@ enum Commands {
         model_command: ModelCommands,
     },
 
-    /// List supported AI providers
-    Providers,
+    /// List supported AI providers or register a custom endpoint
+    Providers {
+        #[command(subcommand)]
+        provider_command: Option<ProviderCommands>,
+    },
 
     /// Manage channels (telegram, discord, slack)
     Channel {
@@ -231,6 +368,36 @@ enum Commands {
         auth_command: AuthCommands,
     },
 
+    /// List or restore git workspace checkpoints
+    Checkpoints {
+        #[command(subcommand)]
+        checkpoint_command: CheckpointCommands,
+    },
+
+    /// Maintain the memory backend (daily-log compaction)
+    Memory {
+        #[command(subcommand)]
+        memory_command: MemoryCommands,
+    },
+
+    /// List the prompt templates `agent --prompt` can run
+    Prompts {
+        #[command(subcommand)]
+        prompts_command: PromptCommands,
+    },
+
+    /// Inspect the prompt a run would send, without calling a provider
+    Context {
+        #[command(subcommand)]
+        context_command: ContextCommands,
+    },
+
+    /// Manage named workspaces (list, add, remove)
+    Workspace {
+        #[command(subcommand)]
+        workspace_command: WorkspaceCommands,
+    },
+
     /// Discover and introspect USB hardware
     Hardware {
         #[command(subcommand)]
@@ -242,6 +409,42 @@ enum Commands {
         #[command(subcommand)]
         peripheral_command: zeroclaw::PeripheralCommands,
     },
+
+    /// Print a shell completion script (e.g. `source <(zeroclaw completions bash)`)
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Print runnable example invocations
+    Examples,
+
+    /// Create a starter config
+    Config {
+        #[command(subcommand)]
+        config_command: ConfigCommands,
+    },
+
+    /// Store secrets in the system keychain for "$secret:<name>" config values
+    Secret {
+        #[command(subcommand)]
+        secret_command: SecretCommands,
+    },
+
+    /// Print the fully resolved config (env overrides and workspace applied, secrets redacted)
+    ExportConfig {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = config::export::ExportFormat::Toml)]
+        format: config::export::ExportFormat,
+    },
+
+    /// List completion candidates, one per line (used by completion scripts)
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(value_enum)]
+        kind: completions::CompletionKind,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -395,6 +598,12 @@ enum CronCommands {
 
 #[derive(Subcommand, Debug)]
 enum ModelCommands {
+    /// List every model the provider offers (cached for 1 hour; OpenRouter includes pricing)
+    List {
+        /// Provider name (defaults to configured default provider)
+        #[arg(long)]
+        provider: Option<String>,
+    },
     /// Refresh and cache provider models
     Refresh {
         /// Provider name (defaults to configured default provider)
@@ -407,6 +616,28 @@ enum ModelCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum ProviderCommands {
+    /// List supported and custom providers (default)
+    List,
+    /// Register an OpenAI-compatible endpoint (vLLM, LM Studio, ...)
+    Add {
+        /// Name used with --provider / default_provider
+        name: String,
+
+        /// Endpoint base URL (e.g. http://localhost:8000/v1)
+        base_url: String,
+
+        /// Optional API key for the endpoint
+        #[arg(long)]
+        api_key: Option<String>,
+
+        /// Save without checking that the endpoint is reachable
+        #[arg(long)]
+        no_verify: bool,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum DoctorCommands {
     /// Probe model catalogs across providers and report availability
@@ -464,6 +695,107 @@ enum SkillCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum CheckpointCommands {
+    /// List workspace checkpoints (newest first)
+    List,
+    /// Restore the workspace files to a checkpoint
+    Restore {
+        /// Checkpoint ID (or commit hash prefix)
+        id: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum MemoryCommands {
+    /// Merge old daily entries into one provider-written summary per day
+    Compact {
+        /// Compact entries older than this many days (default: `[memory] compact_after_days`)
+        #[arg(long)]
+        older_than_days: Option<u32>,
+        /// Show which entries would be merged without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PromptCommands {
+    /// List prompt templates with their variables
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum ContextCommands {
+    /// Print the system prompt a run would start with (and the enriched message, if given)
+    Show {
+        /// Also show the user message as it would be sent, with its memory context
+        #[arg(long)]
+        message: Option<String>,
+        /// Print the estimated token count on stderr
+        #[arg(long)]
+        count_tokens: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Write a starter config.toml (prompts on a terminal for anything not given)
+    Init {
+        /// Provider name (default: openrouter)
+        #[arg(long)]
+        provider: Option<String>,
+        /// Model (default: the provider's recommended model)
+        #[arg(long)]
+        model: Option<String>,
+        /// Where the API key lives: env (the provider's variable) or config
+        #[arg(long, value_parser = ["env", "config"])]
+        api_key_location: Option<String>,
+        /// API key to store in the config (implies --api-key-location config)
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Directory for config.toml and workspace/ (default: ~/.zeroclaw)
+        #[arg(long)]
+        dir: Option<std::path::PathBuf>,
+        /// Replace an existing config.toml (the old one is kept as config.toml.bak)
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SecretCommands {
+    /// Store a secret in the system keychain; reference it in config.toml as "$secret:<name>"
+    Add {
+        /// Secret name (letters, digits, '-', '_' or '.')
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WorkspaceCommands {
+    /// List registered workspaces
+    List,
+    /// Register a named workspace
+    Add {
+        /// Workspace name (letters, digits, '-', '_' or '.')
+        name: String,
+        /// Workspace directory (created if missing)
+        path: String,
+        /// Model to use while this workspace is active
+        #[arg(long)]
+        model: Option<String>,
+        /// Autonomy level while this workspace is active (readonly, supervised, full)
+        #[arg(long)]
+        autonomy: Option<String>,
+    },
+    /// Remove a workspace from the registry (files are kept)
+    Remove {
+        /// Workspace name
+        name: String,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum IntegrationCommands {
     /// Show details about a specific integration
@@ -484,9 +816,13 @@ async fn main() -> Result<()> {
     }
 
     let cli = Cli::parse();
+    term::init(term::ColorChoice::parse(&cli.color).unwrap_or_default());
 
-    // Initialize logging - respects RUST_LOG env var, defaults to INFO
+    // Initialize logging on stderr (stdout carries only command output) -
+    // respects RUST_LOG env var, defaults to INFO
     let subscriber = fmt::Subscriber::builder()
+        .with_writer(std::io::stderr)
+        .with_ansi(term::styled(term::Stream::Stderr))
         .with_env_filter(
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
         )
@@ -536,22 +872,201 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    match &cli.command {
+        Commands::Completions { shell } => {
+            return completions::generate(*shell, &mut Cli::command(), &mut std::io::stdout());
+        }
+        Commands::Examples => {
+            println!("{}", completions::render_examples(&Cli::command())?);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Runs before the config is loaded, which fails on a missing secret.
+    if let Commands::Secret { secret_command } = cli.command {
+        return security::keychain::handle_command(secret_command);
+    }
+
+    // `config init` must run before load_or_init creates a default config.
+    if let Commands::Config {
+        config_command:
+            ConfigCommands::Init {
+                provider,
+                model,
+                api_key_location,
+                api_key,
+                dir,
+                force,
+            },
+    } = cli.command
+    {
+        return config::init::run(config::init::InitOptions {
+            provider,
+            model,
+            api_key_location,
+            api_key,
+            dir,
+            force,
+        });
+    }
+
     // All other commands need config loaded first
     let mut config = Config::load_or_init()?;
     config.apply_env_overrides();
 
+    if let Commands::Complete { kind } = cli.command {
+        for candidate in completions::candidates(kind, &config) {
+            println!("{candidate}");
+        }
+        return Ok(());
+    }
+
+    // Commands that save the config get it without per-workspace overrides,
+    // so `.zeroclaw.toml` values and `--no-cache` never reach config.toml.
+    let saved_config = config.clone();
+    if !matches!(cli.command, Commands::Workspace { .. }) {
+        match cli.workspace.as_deref() {
+            Some(workspace) => config.apply_workspace(workspace)?,
+            None => config.apply_workspace_file()?,
+        }
+    }
+    if cli.no_cache {
+        config.provider.cache.enabled = false;
+    }
+
+    i18n::set_locale(i18n::Locale::from_config(config.agent.locale.as_deref()));
+
+    if let Commands::ExportConfig { format } = cli.command {
+        println!("{}", config::export::export(&config, format)?);
+        return Ok(());
+    }
+
     match cli.command {
-        Commands::Onboard { .. } => unreachable!(),
+        Commands::Onboard { .. }
+        | Commands::Completions { .. }
+        | Commands::Examples
+        | Commands::ExportConfig { .. }
+        | Commands::Config { .. }
+        | Commands::Secret { .. }
+        | Commands::Complete { .. } => unreachable!(),
 
         Commands::Agent {
-            message,
+            mut message,
             provider,
-            model,
-            temperature,
+            mut model,
+            mut temperature,
+            persona,
+            file,
+            prompt,
+            vars,
             peripheral,
-        } => agent::run(config, message, provider, model, temperature, peripheral)
+            continue_session,
+            quiet,
+            verbose,
+            plan,
+            show_thinking,
+            explain,
+            show_context_budget,
+            keep_temp,
+            output,
+            watch,
+        } => {
+            agent::output::set_verbosity(agent::output::Verbosity::from_flags(quiet, verbose));
+            agent::output::set_show_thinking(show_thinking);
+            agent::output::set_explain(explain);
+            agent::context_budget::set_report_format(show_context_budget);
+            agent::output::set_json_output(output == "json");
+            if plan {
+                config.agent.plan_first = true;
+            }
+            if keep_temp {
+                config.agent.keep_temp = true;
+            }
+            if let Some(name) = &persona {
+                let persona = personas::find_persona(&personas::persona_dirs(&config), name)?;
+                temperature = persona.apply(&mut config)?;
+            }
+            if let Some(path) = &file {
+                message = Some(agent::cli::read_message_file(path)?);
+            }
+            if let Some(name) = &prompt {
+                let template = prompts::find_prompt(&prompts::prompt_dirs(&config), name)?;
+                message = Some(template.render(&prompts::parse_vars(&vars)?)?);
+                if let Some(t) = template.temperature {
+                    temperature = t;
+                }
+                model = model.or(template.model);
+            }
+            let session_mode = if continue_session {
+                agent::session::SessionMode::Continue
+            } else {
+                agent::session::SessionMode::New
+            };
+            if watch {
+                let workspace_dir = config.workspace_dir.clone();
+                return agent::watch::run(&workspace_dir, || {
+                    agent::run_with_timeout(
+                        &config,
+                        cli.timeout_secs,
+                        agent::run(
+                            config.clone(),
+                            message.clone(),
+                            provider.clone(),
+                            model.clone(),
+                            temperature,
+                            peripheral.clone(),
+                            session_mode,
+                        ),
+                    )
+                })
+                .await;
+            }
+            let run = agent::run(
+                config.clone(),
+                message,
+                provider,
+                model,
+                temperature,
+                peripheral,
+                session_mode,
+            );
+            agent::shutdown::install_signal_handlers();
+            let run = agent::run_with_timeout(&config, cli.timeout_secs, run);
+            if let Err(e) = agent::shutdown::run_until_shutdown(run).await {
+                let code = agent::output::exit_code(&e);
+                if code != 1 {
+                    eprintln!("Error: {e:#}");
+                    std::process::exit(code);
+                }
+                return Err(e);
+            }
+            Ok(())
+        }
+
+        Commands::Replay { file, message } => agent::replay::run(&config, &file, &message).await,
+
+        Commands::Batch {
+            template,
+            input,
+            concurrency,
+            output,
+            requests_per_minute,
+        } => {
+            agent::batch::run(
+                config,
+                agent::batch::BatchOptions {
+                    template,
+                    input,
+                    concurrency,
+                    output,
+                    requests_per_minute,
+                },
+            )
             .await
-            .map(|_| ()),
+        }
+
+        Commands::Task { name, args } => agent::task::run(config, name.as_deref(), &args).await,
 
         Commands::Gateway { port, host } => {
             let port = port.unwrap_or(config.gateway.port);
@@ -662,6 +1177,14 @@ async fn main() -> Result<()> {
         Commands::Cron { cron_command } => cron::handle_command(cron_command, &config),
 
         Commands::Models { model_command } => match model_command {
+            ModelCommands::List { provider } => {
+                let config_for_list = config.clone();
+                tokio::task::spawn_blocking(move || {
+                    onboard::run_models_list(&config_for_list, provider.as_deref())
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("models list task failed: {e}"))?
+            }
             ModelCommands::Refresh { provider, force } => {
                 let config_for_refresh = config.clone();
                 tokio::task::spawn_blocking(move || {
@@ -672,7 +1195,31 @@ async fn main() -> Result<()> {
             }
         },
 
-        Commands::Providers => {
+        Commands::Providers {
+            provider_command:
+                Some(ProviderCommands::Add {
+                    name,
+                    base_url,
+                    api_key,
+                    no_verify,
+                }),
+        } => {
+            let entry = providers::custom::add_provider(
+                &saved_config,
+                &name,
+                &base_url,
+                api_key,
+                !no_verify,
+            )
+            .await?;
+            println!(
+                "✅ Added provider '{}' ({}). Use it with --provider {}",
+                entry.name, entry.base_url, entry.name
+            );
+            Ok(())
+        }
+
+        Commands::Providers { .. } => {
             let providers = providers::list_providers();
             let current = config
                 .default_provider
@@ -700,6 +1247,17 @@ async fn main() -> Result<()> {
                     p.name, p.display_name, local_tag, marker, aliases
                 );
             }
+            if !config.provider.custom.is_empty() {
+                println!("\nCustom providers ({}):\n", config.provider.custom.len());
+                for entry in &config.provider.custom {
+                    let marker = if entry.name.eq_ignore_ascii_case(&current) {
+                        " (active)"
+                    } else {
+                        ""
+                    };
+                    println!("  {:<19} {}{}", entry.name, entry.base_url, marker);
+                }
+            }
             println!("\n  custom:<URL>   Any OpenAI-compatible endpoint");
             println!("  anthropic-custom:<URL>  Any Anthropic-compatible endpoint");
             Ok(())
@@ -725,16 +1283,14 @@ async fn main() -> Result<()> {
         Commands::Channel { channel_command } => match channel_command {
             ChannelCommands::Start => channels::start_channels(config).await,
             ChannelCommands::Doctor => channels::doctor_channels(config).await,
-            other => channels::handle_command(other, &config),
+            other => channels::handle_command(other, &saved_config),
         },
 
         Commands::Integrations {
             integration_command,
         } => integrations::handle_command(integration_command, &config),
 
-        Commands::Skills { skill_command } => {
-            skills::handle_command(skill_command, &config.workspace_dir)
-        }
+        Commands::Skills { skill_command } => skills::handle_command(skill_command, &config),
 
         Commands::Migrate { migrate_command } => {
             migration::handle_command(migrate_command, &config).await
@@ -742,12 +1298,30 @@ async fn main() -> Result<()> {
 
         Commands::Auth { auth_command } => handle_auth_command(auth_command, &config).await,
 
+        Commands::Checkpoints { checkpoint_command } => {
+            checkpoints::handle_command(checkpoint_command, &config)
+        }
+
+        Commands::Memory { memory_command } => {
+            memory::compaction::handle_command(memory_command, &config).await
+        }
+
+        Commands::Prompts { prompts_command } => prompts::handle_command(prompts_command, &config),
+
+        Commands::Context { context_command } => {
+            agent::cli::handle_context_command(context_command, &config).await
+        }
+
+        Commands::Workspace { workspace_command } => {
+            workspace::handle_command(workspace_command, &saved_config)
+        }
+
         Commands::Hardware { hardware_command } => {
             hardware::handle_command(hardware_command.clone(), &config)
         }
 
         Commands::Peripheral { peripheral_command } => {
-            peripherals::handle_command(peripheral_command.clone(), &config)
+            peripherals::handle_command(peripheral_command.clone(), &saved_config)
         }
     }
 }
@@ -869,6 +1443,29 @@ fn read_plain_input(prompt: &str) -> Result<String> {
     Ok(input.trim().to_string())
 }
 
+/// Wait for the browser callback; Ctrl-C cancels the pending login at once.
+async fn receive_openai_callback(expected_state: &str) -> Result<String> {
+    let cancel = tokio_util::sync::CancellationToken::new();
+    let mut server = auth::openai_oauth::CallbackServer::start(
+        auth::openai_oauth::OPENAI_OAUTH_CALLBACK_ADDR,
+        expected_state,
+        cancel.clone(),
+    )
+    .await?;
+    let result = tokio::select! {
+        result = tokio::time::timeout(std::time::Duration::from_secs(180), server.recv()) => result,
+        _ = tokio::signal::ctrl_c() => {
+            cancel.cancel();
+            bail!("Login cancelled");
+        }
+    };
+    match result {
+        Ok(Some(code)) => code,
+        Ok(None) => bail!("Callback server stopped before the browser returned"),
+        Err(_) => bail!("Timed out waiting for browser callback"),
+    }
+}
+
 fn extract_openai_account_id_for_profile(access_token: &str) -> Option<String> {
     let account_id = auth::openai_oauth::extract_account_id_from_jwt(access_token);
     if account_id.is_none() {
@@ -963,14 +1560,9 @@ async fn handle_auth_command(auth_command: AuthCommands, config: &Config) -> Res
             println!("Open this URL in your browser and authorize access:");
             println!("{authorize_url}");
             println!();
-            println!("Waiting for callback at http://localhost:1455/auth/callback ...");
+            println!("Waiting for callback at http://localhost:1455/auth/callback (Ctrl-C to cancel) ...");
 
-            let code = match auth::openai_oauth::receive_loopback_code(
-                &pkce.state,
-                std::time::Duration::from_secs(180),
-            )
-            .await
-            {
+            let code = match receive_openai_callback(&pkce.state).await {
                 Ok(code) => code,
                 Err(e) => {
                     println!("Callback capture failed: {e}");
@@ -1192,10 +1784,47 @@ async fn handle_auth_command(auth_command: AuthCommands, config: &Config) -> Res
 #[cfg(test)]
 mod tests {
     use super::*;
-    use clap::CommandFactory;
 
     #[test]
     fn cli_definition_has_no_flag_conflicts() {
         Cli::command().debug_assert();
     }
+
+    #[test]
+    fn bash_completions_cover_every_subcommand() {
+        let mut cmd = Cli::command();
+        let mut script = Vec::new();
+        completions::generate(clap_complete::Shell::Bash, &mut cmd, &mut script).unwrap();
+        let script = String::from_utf8(script).unwrap();
+
+        for sub in Cli::command()
+            .get_subcommands()
+            .filter(|sub| !sub.is_hide_set())
+        {
+            assert!(
+                script.contains(sub.get_name()),
+                "bash completions miss `{}`",
+                sub.get_name()
+            );
+        }
+        assert!(script.contains("zeroclaw __complete \"$kind\""));
+    }
+
+    #[test]
+    fn examples_parse_against_the_cli() {
+        let cmd = Cli::command();
+        let invocations = completions::example_invocations(&cmd).unwrap();
+        assert!(!invocations.is_empty());
+        for argv in invocations {
+            if let Err(e) = Cli::try_parse_from(&argv) {
+                panic!("example `{}` no longer parses: {e}", argv.join(" "));
+            }
+        }
+
+        let rendered = completions::render_examples(&cmd).unwrap();
+        assert!(
+            rendered.contains("zeroclaw agent --message 'Summarize README.md in three bullets'")
+        );
+        assert!(rendered.contains("zeroclaw auth login --provider openai-codex"));
+    }
 }
@@ -0,0 +1,152 @@
+//! Heuristic category inference for auto-saved user messages, enabled with
+//! `[memory] infer_categories`. Durable statements (preferences, decisions)
+//! are filed apart from ordinary conversation so recall can favour them.
+
+use super::traits::MemoryCategory;
+
+/// Category for stated likes, dislikes and standing instructions.
+pub const PREFERENCE_CATEGORY: &str = "preference";
+/// Category for choices the user or team has settled on.
+pub const DECISION_CATEGORY: &str = "decision";
+
+const PREFERENCE_PHRASES: &[&str] = &[
+    "my preference is",
+    "my preferences are",
+    "i prefer",
+    "i'd prefer",
+    "i would prefer",
+    "i like",
+    "i love",
+    "i don't like",
+    "i do not like",
+    "i hate",
+    "i dislike",
+    "my favorite",
+    "my favourite",
+    "please always",
+    "please never",
+    "always use",
+    "never use",
+];
+
+const DECISION_PHRASES: &[&str] = &[
+    "we decided",
+    "we've decided",
+    "we have decided",
+    "i decided",
+    "i've decided",
+    "i have decided",
+    "the decision is",
+    "decision:",
+    "we agreed",
+    "we're going with",
+    "we are going with",
+    "let's go with",
+    "we will use",
+    "we'll use",
+];
+
+/// Category for an auto-saved user message: [`infer_category`] when `infer`
+/// is set, otherwise [`MemoryCategory::Conversation`].
+pub fn user_message_category(text: &str, infer: bool) -> MemoryCategory {
+    if infer {
+        infer_category(text)
+    } else {
+        MemoryCategory::Conversation
+    }
+}
+
+/// Classify `text` as a preference, a decision or plain conversation.
+/// Questions are always conversation ("do you prefer tabs?").
+pub fn infer_category(text: &str) -> MemoryCategory {
+    let lower = text.trim().to_lowercase().replace('’', "'");
+    if lower.is_empty() || lower.ends_with('?') {
+        return MemoryCategory::Conversation;
+    }
+
+    if contains_phrase(&lower, DECISION_PHRASES) {
+        MemoryCategory::Custom(DECISION_CATEGORY.into())
+    } else if contains_phrase(&lower, PREFERENCE_PHRASES) {
+        MemoryCategory::Custom(PREFERENCE_CATEGORY.into())
+    } else {
+        MemoryCategory::Conversation
+    }
+}
+
+/// Whether any phrase occurs in `text` starting at a word boundary.
+fn contains_phrase(text: &str, phrases: &[&str]) -> bool {
+    phrases.iter().any(|phrase| {
+        text.match_indices(phrase).any(|(at, _)| {
+            text[..at]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(name: &str) -> MemoryCategory {
+        MemoryCategory::Custom(name.into())
+    }
+
+    #[test]
+    fn stated_preferences_are_categorized_as_preference() {
+        assert_eq!(
+            infer_category("My preference is tabs over spaces"),
+            custom(PREFERENCE_CATEGORY)
+        );
+        assert_eq!(
+            infer_category("Please always answer in German."),
+            custom(PREFERENCE_CATEGORY)
+        );
+        assert_eq!(
+            infer_category("I don’t like long summaries"),
+            custom(PREFERENCE_CATEGORY)
+        );
+    }
+
+    #[test]
+    fn decisions_are_categorized_as_decision() {
+        assert_eq!(
+            infer_category("We decided to ship on Fridays only"),
+            custom(DECISION_CATEGORY)
+        );
+        assert_eq!(
+            infer_category("OK, let's go with Postgres"),
+            custom(DECISION_CATEGORY)
+        );
+    }
+
+    #[test]
+    fn questions_and_chatter_stay_conversation() {
+        assert_eq!(
+            infer_category("Do you prefer tabs or spaces?"),
+            MemoryCategory::Conversation
+        );
+        assert_eq!(
+            infer_category("What's the weather today"),
+            MemoryCategory::Conversation
+        );
+        // "i like" inside another word is not a preference
+        assert_eq!(
+            infer_category("Show me the wiki likes count"),
+            MemoryCategory::Conversation
+        );
+    }
+
+    #[test]
+    fn inference_is_opt_in() {
+        assert_eq!(
+            user_message_category("My preference is dark mode", false),
+            MemoryCategory::Conversation
+        );
+        assert_eq!(
+            user_message_category("My preference is dark mode", true),
+            custom(PREFERENCE_CATEGORY)
+        );
+    }
+}
@@ -0,0 +1,436 @@
+//! Daily-log compaction (`zeroclaw memory compact`).
+//!
+//! Auto-saved `Daily` entries are one-line, truncated notes that pile up and
+//! drown out recall. Compaction groups the entries older than
+//! `[memory] compact_after_days` by day, asks the provider for one
+//! consolidated summary per day and stores it under
+//! `daily_summary:<YYYY-MM-DD>` in place of the originals (see
+//! [`Memory::replace_daily`]). A day that holds only its summary is skipped,
+//! so running it again changes nothing; a day interrupted mid-way still has
+//! its originals and is merged, together with the summary, on the next run.
+//!
+//! Each day's prompt is routed like a message from the `memory-compaction`
+//! channel, so a `[[routing.rules]]` entry with
+//! `channels = ["memory-compaction"]` can send compaction to a cheap model.
+
+use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use crate::agent::routing::{MessageRouter, RouteChoice};
+use crate::config::Config;
+use crate::providers::{self, Provider};
+use crate::util::LocalTimezone;
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+/// Key prefix of the consolidated entry for a day; the date follows.
+pub const SUMMARY_KEY_PREFIX: &str = "daily_summary:";
+
+/// Routing channel compaction prompts are sent from.
+pub const COMPACTION_CHANNEL: &str = "memory-compaction";
+
+const COMPACTION_SYSTEM_PROMPT: &str = "You consolidate an assistant's daily memory log. \
+Merge the entries into one concise summary of that day: decisions made, facts learned, work \
+done and open items. Keep names, paths and numbers exactly. Reply with the summary only, as a \
+single paragraph.";
+
+/// The `Daily` entries of one day, merged into a single summary.
+#[derive(Debug, Clone)]
+pub struct DayBatch {
+    pub date: NaiveDate,
+    pub entries: Vec<MemoryEntry>,
+}
+
+impl DayBatch {
+    pub fn summary_key(&self) -> String {
+        format!("{SUMMARY_KEY_PREFIX}{}", self.date)
+    }
+
+    /// The user prompt asking for this day's summary.
+    pub fn prompt(&self) -> String {
+        let mut prompt = format!("Daily log for {}:\n", self.date);
+        for entry in &self.entries {
+            prompt.push_str("- ");
+            prompt.push_str(entry_text(entry));
+            prompt.push('\n');
+        }
+        prompt
+    }
+}
+
+/// The date a summary entry covers. The markdown backend keys entries by
+/// position, so its summaries are recognised by the `**key**:` prefix.
+fn summary_date(entry: &MemoryEntry) -> Option<NaiveDate> {
+    let rest = entry.key.strip_prefix(SUMMARY_KEY_PREFIX).or_else(|| {
+        entry
+            .content
+            .strip_prefix("**")
+            .and_then(|c| c.strip_prefix(SUMMARY_KEY_PREFIX))
+    })?;
+    NaiveDate::parse_from_str(rest.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// The day an entry was logged, in the offset it was stored with.
+fn entry_date(entry: &MemoryEntry) -> Option<NaiveDate> {
+    if let Some(date) = summary_date(entry) {
+        return Some(date);
+    }
+    let raw = entry.timestamp.trim();
+    DateTime::parse_from_rfc3339(raw)
+        .map(|timestamp| timestamp.date_naive())
+        .or_else(|_| NaiveDate::parse_from_str(raw, "%Y-%m-%d"))
+        .ok()
+}
+
+/// Entry content without the markdown backend's `**key**: ` prefix.
+fn entry_text(entry: &MemoryEntry) -> &str {
+    entry
+        .content
+        .strip_prefix("**")
+        .and_then(|c| c.split_once("**: "))
+        .map_or(entry.content.as_str(), |(_, text)| text)
+}
+
+/// `Daily` entries logged before `cutoff`, grouped by day, oldest first.
+/// Days that hold nothing but their summary are left out.
+pub fn plan(entries: Vec<MemoryEntry>, cutoff: NaiveDate) -> Vec<DayBatch> {
+    let mut batches: Vec<DayBatch> = Vec::new();
+    for entry in entries {
+        if entry.category != MemoryCategory::Daily {
+            continue;
+        }
+        let Some(date) = entry_date(&entry).filter(|date| *date < cutoff) else {
+            continue;
+        };
+        match batches.iter_mut().find(|batch| batch.date == date) {
+            Some(batch) => batch.entries.push(entry),
+            None => batches.push(DayBatch {
+                date,
+                entries: vec![entry],
+            }),
+        }
+    }
+    batches.retain(|batch| batch.entries.iter().any(|e| summary_date(e).is_none()));
+    for batch in &mut batches {
+        // Previous summary first, then the log in the order it was written.
+        batch.entries.sort_by(|a, b| {
+            summary_date(b)
+                .is_some()
+                .cmp(&summary_date(a).is_some())
+                .then_with(|| a.timestamp.cmp(&b.timestamp))
+        });
+    }
+    batches.sort_by_key(|batch| batch.date);
+    batches
+}
+
+/// Summarize one day with `provider` and replace its entries. Returns the
+/// summary stored.
+pub async fn compact_day(
+    memory: &dyn Memory,
+    provider: &dyn Provider,
+    model: &str,
+    temperature: f64,
+    batch: &DayBatch,
+) -> Result<String> {
+    let reply = provider
+        .chat_with_system(
+            Some(COMPACTION_SYSTEM_PROMPT),
+            &batch.prompt(),
+            model,
+            temperature,
+        )
+        .await?;
+    // One line, so the markdown backend keeps it as a single entry.
+    let summary = reply.split_whitespace().collect::<Vec<_>>().join(" ");
+    if summary.is_empty() {
+        anyhow::bail!(
+            "The model returned an empty summary for {}; entries kept",
+            batch.date
+        );
+    }
+    let keys: Vec<String> = batch.entries.iter().map(|e| e.key.clone()).collect();
+    memory
+        .replace_daily(batch.date, &keys, &batch.summary_key(), &summary)
+        .await?;
+    Ok(summary)
+}
+
+/// Compact the configured backend's daily entries older than
+/// `older_than_days`. With `dry_run` nothing is sent or written. Returns the
+/// days merged (or that would be).
+pub async fn compact_configured(
+    config: &Config,
+    older_than_days: u32,
+    dry_run: bool,
+) -> Result<Vec<DayBatch>> {
+    let timezone = LocalTimezone::from_config(config.agent.timezone.as_deref());
+    let memory = super::create_memory_with_storage(
+        &config.memory,
+        Some(&config.storage.provider.config),
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        timezone,
+    )?;
+    let today = timezone.localize(Utc::now()).date_naive();
+    let cutoff = today - Duration::days(i64::from(older_than_days));
+    let batches = plan(
+        memory.list(Some(&MemoryCategory::Daily), None).await?,
+        cutoff,
+    );
+    if dry_run || batches.is_empty() {
+        return Ok(batches);
+    }
+
+    let (provider_name, default_model) =
+        crate::agent::loop_::resolve_provider_and_model(config, None, None);
+    let router = MessageRouter::from_config(&config.routing)?;
+    let mut model_routes = config.model_routes.clone();
+    model_routes.extend(router.model_routes(default_model));
+    let provider = providers::create_routed_provider_with_options(
+        provider_name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &model_routes,
+        default_model,
+        &providers::ProviderRuntimeOptions::default()
+            .with_provider_config(&config.provider)
+            .with_observability_config(&config.observability)?,
+    )?;
+
+    for batch in &batches {
+        let route = router.route(
+            &batch.prompt(),
+            COMPACTION_CHANNEL,
+            RouteChoice::default_route(provider_name, default_model, config.default_temperature),
+        );
+        let model = MessageRouter::model_for(&route, provider_name);
+        compact_day(
+            memory.as_ref(),
+            provider.as_ref(),
+            &model,
+            route.temperature,
+            batch,
+        )
+        .await?;
+        tracing::info!(
+            date = %batch.date,
+            entries = batch.entries.len(),
+            "Compacted daily memory"
+        );
+    }
+    Ok(batches)
+}
+
+pub async fn handle_command(command: crate::MemoryCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::MemoryCommands::Compact {
+            older_than_days,
+            dry_run,
+        } => {
+            let days = older_than_days.unwrap_or(config.memory.compact_after_days);
+            let batches = compact_configured(config, days, dry_run).await?;
+            if batches.is_empty() {
+                println!("No daily entries older than {days} day(s) to compact.");
+                return Ok(());
+            }
+            for batch in &batches {
+                println!(
+                    "{}: {} entr{} → {}",
+                    batch.date,
+                    batch.entries.len(),
+                    if batch.entries.len() == 1 { "y" } else { "ies" },
+                    batch.summary_key()
+                );
+                if dry_run {
+                    for entry in &batch.entries {
+                        println!(
+                            "    - {}",
+                            crate::util::truncate_with_ellipsis(entry_text(entry), 100)
+                        );
+                    }
+                }
+            }
+            if dry_run {
+                println!("Dry run: nothing was changed.");
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MarkdownMemory;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Answers each prompt with the next canned reply and records the prompts.
+    struct ReplayProvider {
+        replies: Mutex<Vec<String>>,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    impl ReplayProvider {
+        fn new(replies: &[&str]) -> Self {
+            Self {
+                replies: Mutex::new(replies.iter().rev().map(|r| r.to_string()).collect()),
+                prompts: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ReplayProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            self.prompts.lock().unwrap().push(message.to_string());
+            self.replies
+                .lock()
+                .unwrap()
+                .pop()
+                .ok_or_else(|| anyhow::anyhow!("no replies left"))
+        }
+    }
+
+    fn seed_day(tmp: &TempDir, date: &str, lines: &[&str]) {
+        let dir = tmp.path().join("memory");
+        std::fs::create_dir_all(&dir).unwrap();
+        let body: String = lines.iter().map(|l| format!("\n- {l}\n")).collect();
+        std::fs::write(
+            dir.join(format!("{date}.md")),
+            format!("# Daily Log — {date}\n{body}"),
+        )
+        .unwrap();
+    }
+
+    fn date(raw: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d").unwrap()
+    }
+
+    async fn daily(mem: &dyn Memory) -> Vec<MemoryEntry> {
+        mem.list(Some(&MemoryCategory::Daily), None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn plan_groups_old_days_and_skips_recent_ones() {
+        let tmp = TempDir::new().unwrap();
+        seed_day(
+            &tmp,
+            "2026-10-01",
+            &["**user_msg**: fix the parser", "**assistant_resp**: done"],
+        );
+        seed_day(&tmp, "2026-10-02", &["**user_msg**: deploy"]);
+        seed_day(&tmp, "2026-10-15", &["**user_msg**: today"]);
+        let mem = MarkdownMemory::new(tmp.path());
+
+        let batches = plan(daily(&mem).await, date("2026-10-09"));
+        let days: Vec<(NaiveDate, usize)> =
+            batches.iter().map(|b| (b.date, b.entries.len())).collect();
+        assert_eq!(days, [(date("2026-10-01"), 2), (date("2026-10-02"), 1)]);
+        assert_eq!(
+            batches[0].prompt(),
+            "Daily log for 2026-10-01:\n- fix the parser\n- done\n"
+        );
+        assert_eq!(batches[0].summary_key(), "daily_summary:2026-10-01");
+    }
+
+    #[tokio::test]
+    async fn compaction_replaces_each_day_with_one_summary() {
+        let tmp = TempDir::new().unwrap();
+        seed_day(
+            &tmp,
+            "2026-10-01",
+            &["**user_msg**: fix the parser", "**assistant_resp**: done"],
+        );
+        let mem = MarkdownMemory::new(tmp.path());
+        let provider = ReplayProvider::new(&["Fixed the\nparser."]);
+
+        let batches = plan(daily(&mem).await, date("2026-10-09"));
+        let summary = compact_day(&mem, &provider, "cheap", 0.2, &batches[0])
+            .await
+            .unwrap();
+        assert_eq!(summary, "Fixed the parser.");
+
+        let entries = daily(&mem).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].timestamp, "2026-10-01");
+        assert_eq!(
+            entries[0].content,
+            "**daily_summary:2026-10-01**: Fixed the parser."
+        );
+        assert!(!tmp.path().join("memory/2026-10-01.md.tmp").exists());
+
+        // Idempotent: the day now holds only its summary.
+        assert!(plan(daily(&mem).await, date("2026-10-09")).is_empty());
+    }
+
+    #[tokio::test]
+    async fn later_entries_are_merged_with_the_existing_summary() {
+        let tmp = TempDir::new().unwrap();
+        // As left by a run interrupted after writing the summary.
+        seed_day(
+            &tmp,
+            "2026-10-01",
+            &[
+                "**daily_summary:2026-10-01**: Fixed the parser.",
+                "**user_msg**: ship it",
+            ],
+        );
+        let mem = MarkdownMemory::new(tmp.path());
+        let provider = ReplayProvider::new(&["Fixed and shipped the parser."]);
+
+        let batches = plan(daily(&mem).await, date("2026-10-09"));
+        assert_eq!(batches.len(), 1);
+        compact_day(&mem, &provider, "cheap", 0.2, &batches[0])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            provider.prompts.lock().unwrap()[0],
+            "Daily log for 2026-10-01:\n- Fixed the parser.\n- ship it\n"
+        );
+        let entries = daily(&mem).await;
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0]
+            .content
+            .ends_with("Fixed and shipped the parser."));
+    }
+
+    #[tokio::test]
+    async fn failed_summary_keeps_the_originals() {
+        let tmp = TempDir::new().unwrap();
+        seed_day(&tmp, "2026-10-01", &["**user_msg**: fix the parser"]);
+        let mem = MarkdownMemory::new(tmp.path());
+
+        let batches = plan(daily(&mem).await, date("2026-10-09"));
+        for provider in [ReplayProvider::new(&[]), ReplayProvider::new(&["  "])] {
+            assert!(compact_day(&mem, &provider, "cheap", 0.2, &batches[0])
+                .await
+                .is_err());
+        }
+        assert_eq!(daily(&mem).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn dry_run_leaves_the_backend_untouched() {
+        let tmp = TempDir::new().unwrap();
+        seed_day(&tmp, "2020-01-01", &["**user_msg**: old news"]);
+        let mut config = Config::default();
+        config.workspace_dir = tmp.path().to_path_buf();
+        config.memory.backend = "markdown".into();
+        config.memory.hygiene_enabled = false;
+
+        let batches = compact_configured(&config, 7, true).await.unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].date, date("2020-01-01"));
+        let content = std::fs::read_to_string(tmp.path().join("memory/2020-01-01.md")).unwrap();
+        assert!(content.contains("old news"));
+    }
+}
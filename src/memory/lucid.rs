@@ -1,7 +1,7 @@
 use super::sqlite::SqliteMemory;
 use super::traits::{Memory, MemoryCategory, MemoryEntry};
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{Local, NaiveDate};
 use parking_lot::Mutex;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -375,6 +375,16 @@ impl Memory for LucidMemory {
         self.local.forget(key).await
     }
 
+    async fn replace_daily(
+        &self,
+        date: NaiveDate,
+        keys: &[String],
+        key: &str,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        self.local.replace_daily(date, keys, key, content).await
+    }
+
     async fn count(&self) -> anyhow::Result<usize> {
         self.local.count().await
     }
@@ -1,6 +1,7 @@
 use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use crate::util::LocalTimezone;
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{NaiveDate, Utc};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
@@ -11,12 +12,19 @@ use tokio::fs;
 ///   workspace/memory/YYYY-MM-DD.md — daily logs (append-only)
 pub struct MarkdownMemory {
     workspace_dir: PathBuf,
+    timezone: LocalTimezone,
 }
 
 impl MarkdownMemory {
     pub fn new(workspace_dir: &Path) -> Self {
+        Self::with_timezone(workspace_dir, LocalTimezone::System)
+    }
+
+    /// Daily logs are named after the date in `timezone`.
+    pub fn with_timezone(workspace_dir: &Path, timezone: LocalTimezone) -> Self {
         Self {
             workspace_dir: workspace_dir.to_path_buf(),
+            timezone,
         }
     }
 
@@ -29,7 +37,7 @@ impl MarkdownMemory {
     }
 
     fn daily_path(&self) -> PathBuf {
-        let date = Local::now().format("%Y-%m-%d").to_string();
+        let date = self.timezone.date_key(Utc::now());
         self.memory_dir().join(format!("{date}.md"))
     }
 
@@ -51,7 +59,11 @@ impl MarkdownMemory {
             let header = if path == self.core_path() {
                 "# Long-Term Memory\n\n"
             } else {
-                let date = Local::now().format("%Y-%m-%d").to_string();
+                // Named like the file, so the header and filename never disagree.
+                let date = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default();
                 &format!("# Daily Log — {date}\n\n")
             };
             format!("{header}{content}\n")
@@ -210,6 +222,40 @@ impl Memory for MarkdownMemory {
         }
     }
 
+    /// Rewrites `memory/<date>.md` with `content` in place of the `keys`
+    /// lines. The new file is written beside the old one and renamed over it.
+    async fn replace_daily(
+        &self,
+        date: NaiveDate,
+        keys: &[String],
+        key: &str,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        self.ensure_dirs().await?;
+        let path = self.memory_dir().join(format!("{date}.md"));
+        let existing = if path.exists() {
+            fs::read_to_string(&path).await?
+        } else {
+            String::new()
+        };
+
+        let mut updated = format!("# Daily Log — {date}\n\n- **{key}**: {content}\n");
+        let kept = existing
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .enumerate()
+            .filter(|(i, _)| !keys.contains(&format!("{date}:{i}")));
+        for (_, line) in kept {
+            updated.push_str(&format!("\n{line}\n"));
+        }
+
+        let staged = path.with_extension("md.tmp");
+        fs::write(&staged, updated).await?;
+        fs::rename(&staged, &path).await?;
+        Ok(())
+    }
+
     async fn forget(&self, _key: &str) -> anyhow::Result<bool> {
         // Markdown memory is append-only by design (audit trail)
         // Return false to indicate the entry wasn't removed
@@ -271,6 +317,27 @@ mod tests {
         assert!(content.contains("Finished tests"));
     }
 
+    #[tokio::test]
+    async fn markdown_daily_log_uses_configured_timezone() {
+        // UTC+14 and UTC-12 are 26 hours apart, so they never share a date.
+        let mut names = Vec::new();
+        for zone in [chrono_tz::Etc::GMTMinus14, chrono_tz::Etc::GMTPlus12] {
+            let tmp = TempDir::new().unwrap();
+            let timezone = LocalTimezone::Named(zone);
+            let mem = MarkdownMemory::with_timezone(tmp.path(), timezone);
+            mem.store("note", "evening session", MemoryCategory::Daily, None)
+                .await
+                .unwrap();
+
+            let date = timezone.date_key(Utc::now());
+            let path = tmp.path().join("memory").join(format!("{date}.md"));
+            let content = sync_fs::read_to_string(path).unwrap();
+            assert!(content.starts_with(&format!("# Daily Log — {date}")));
+            names.push(date);
+        }
+        assert_ne!(names[0], names[1]);
+    }
+
     #[tokio::test]
     async fn markdown_recall_keyword() {
         let (_tmp, mem) = temp_workspace();
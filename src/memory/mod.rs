@@ -1,9 +1,12 @@
 pub mod backend;
+pub mod categorize;
 pub mod chunker;
+pub mod compaction;
 pub mod embeddings;
 pub mod hygiene;
 pub mod lucid;
 pub mod markdown;
+pub mod namespaced;
 pub mod none;
 pub mod postgres;
 pub mod response_cache;
@@ -19,15 +22,17 @@ pub use backend::{
 };
 pub use lucid::LucidMemory;
 pub use markdown::MarkdownMemory;
+pub use namespaced::NamespacedMemory;
 pub use none::NoneMemory;
 pub use postgres::PostgresMemory;
 pub use response_cache::ResponseCache;
 pub use sqlite::SqliteMemory;
 pub use traits::Memory;
 #[allow(unused_imports)]
-pub use traits::{MemoryCategory, MemoryEntry};
+pub use traits::{MemoryCategory, MemoryEntry, MemorySearchFilter};
 
-use crate::config::{MemoryConfig, StorageProviderConfig};
+use crate::config::{MemoryConfig, MemoryNamespace, RecallScope, StorageProviderConfig};
+use crate::util::LocalTimezone;
 use anyhow::Context;
 use std::path::Path;
 use std::sync::Arc;
@@ -35,6 +40,7 @@ use std::sync::Arc;
 fn create_memory_with_builders<F, G>(
     backend_name: &str,
     workspace_dir: &Path,
+    timezone: LocalTimezone,
     mut sqlite_builder: F,
     mut postgres_builder: G,
     unknown_context: &str,
@@ -50,13 +56,19 @@ where
             Ok(Box::new(LucidMemory::new(workspace_dir, local)))
         }
         MemoryBackendKind::Postgres => Ok(Box::new(postgres_builder()?)),
-        MemoryBackendKind::Markdown => Ok(Box::new(MarkdownMemory::new(workspace_dir))),
+        MemoryBackendKind::Markdown => Ok(Box::new(MarkdownMemory::with_timezone(
+            workspace_dir,
+            timezone,
+        ))),
         MemoryBackendKind::None => Ok(Box::new(NoneMemory::new())),
         MemoryBackendKind::Unknown => {
             tracing::warn!(
                 "Unknown memory backend '{backend_name}'{unknown_context}, falling back to markdown"
             );
-            Ok(Box::new(MarkdownMemory::new(workspace_dir)))
+            Ok(Box::new(MarkdownMemory::with_timezone(
+                workspace_dir,
+                timezone,
+            )))
         }
     }
 }
@@ -81,15 +93,17 @@ pub fn create_memory(
     workspace_dir: &Path,
     api_key: Option<&str>,
 ) -> anyhow::Result<Box<dyn Memory>> {
-    create_memory_with_storage(config, None, workspace_dir, api_key)
+    create_memory_with_storage(config, None, workspace_dir, api_key, LocalTimezone::System)
 }
 
 /// Factory: create memory with optional storage-provider override.
+/// `timezone` dates the markdown backend's daily logs.
 pub fn create_memory_with_storage(
     config: &MemoryConfig,
     storage_provider: Option<&StorageProviderConfig>,
     workspace_dir: &Path,
     api_key: Option<&str>,
+    timezone: LocalTimezone,
 ) -> anyhow::Result<Box<dyn Memory>> {
     let backend_name = effective_memory_backend_name(&config.backend, storage_provider);
     let backend_kind = classify_memory_backend(&backend_name);
@@ -181,15 +195,59 @@ pub fn create_memory_with_storage(
         )
     }
 
-    create_memory_with_builders(
+    let mem = create_memory_with_builders(
         &backend_name,
         workspace_dir,
+        timezone,
         || build_sqlite_memory(config, workspace_dir, api_key),
         || build_postgres_memory(storage_provider),
         "",
+    )?;
+
+    // Postgres is the one store every workspace shares.
+    if backend_kind == MemoryBackendKind::Postgres && config.namespace == MemoryNamespace::Workspace
+    {
+        return Ok(Box::new(NamespacedMemory::new(mem, workspace_dir)));
+    }
+    Ok(mem)
+}
+
+/// Factory for agent sessions: like [`create_memory_with_storage`], but when
+/// the backend fails to initialize and `[memory] required` is off, warns and
+/// falls back to [`NoneMemory`]. The flag is `true` after a fallback, so the
+/// caller can drop the memory tools.
+pub fn create_session_memory(
+    config: &MemoryConfig,
+    storage_provider: Option<&StorageProviderConfig>,
+    workspace_dir: &Path,
+    api_key: Option<&str>,
+    timezone: LocalTimezone,
+) -> anyhow::Result<(Box<dyn Memory>, bool)> {
+    fallback_on_init_failure(
+        create_memory_with_storage(config, storage_provider, workspace_dir, api_key, timezone),
+        config.required,
     )
 }
 
+fn fallback_on_init_failure(
+    created: anyhow::Result<Box<dyn Memory>>,
+    required: bool,
+) -> anyhow::Result<(Box<dyn Memory>, bool)> {
+    match created {
+        Ok(mem) => Ok((mem, false)),
+        Err(e) if required => {
+            Err(e.context("memory backend failed to initialize ([memory] required = true)"))
+        }
+        Err(e) => {
+            tracing::warn!(
+                "⚠️  Memory backend failed to initialize, continuing WITHOUT memory \
+                 (memory tools disabled; set [memory] required = true to fail instead): {e:#}"
+            );
+            Ok((Box::new(NoneMemory::new()), true))
+        }
+    }
+}
+
 pub fn create_memory_for_migration(
     backend: &str,
     workspace_dir: &Path,
@@ -212,6 +270,7 @@ pub fn create_memory_for_migration(
     create_memory_with_builders(
         backend,
         workspace_dir,
+        LocalTimezone::System,
         || SqliteMemory::new(workspace_dir),
         || anyhow::bail!("postgres backend is not available in migration context"),
         " during migration",
@@ -244,6 +303,38 @@ pub fn create_response_cache(config: &MemoryConfig, workspace_dir: &Path) -> Opt
     }
 }
 
+/// Recall up to `limit` entries for `query` within `scope`. Without an
+/// active session every scope recalls globally.
+pub async fn recall_scoped(
+    mem: &dyn Memory,
+    query: &str,
+    limit: usize,
+    scope: RecallScope,
+    session_id: Option<&str>,
+) -> anyhow::Result<Vec<MemoryEntry>> {
+    let Some(session_id) = session_id else {
+        return mem.recall(query, limit, None).await;
+    };
+    match scope {
+        RecallScope::Global => mem.recall(query, limit, None).await,
+        RecallScope::Session => mem.recall(query, limit, Some(session_id)).await,
+        RecallScope::Hybrid => {
+            let mut entries = mem.recall(query, limit, Some(session_id)).await?;
+            if entries.len() < limit {
+                for entry in mem.recall(query, limit, None).await? {
+                    if entries.len() >= limit {
+                        break;
+                    }
+                    if !entries.iter().any(|e| e.id == entry.id) {
+                        entries.push(entry);
+                    }
+                }
+            }
+            Ok(entries)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,9 +439,139 @@ mod tests {
             ..StorageProviderConfig::default()
         };
 
-        let error = create_memory_with_storage(&cfg, Some(&storage), tmp.path(), None)
-            .err()
-            .expect("postgres without db_url should be rejected");
+        let error = create_memory_with_storage(
+            &cfg,
+            Some(&storage),
+            tmp.path(),
+            None,
+            LocalTimezone::System,
+        )
+        .err()
+        .expect("postgres without db_url should be rejected");
         assert!(error.to_string().contains("db_url"));
     }
+
+    fn failing_postgres_config(required: bool) -> (MemoryConfig, StorageProviderConfig) {
+        let cfg = MemoryConfig {
+            backend: "postgres".into(),
+            required,
+            ..MemoryConfig::default()
+        };
+        let storage = StorageProviderConfig {
+            provider: "postgres".into(),
+            db_url: None,
+            ..StorageProviderConfig::default()
+        };
+        (cfg, storage)
+    }
+
+    #[test]
+    fn session_memory_falls_back_to_none_when_init_fails() {
+        let tmp = TempDir::new().unwrap();
+        let (cfg, storage) = failing_postgres_config(false);
+
+        let (mem, degraded) = create_session_memory(
+            &cfg,
+            Some(&storage),
+            tmp.path(),
+            None,
+            LocalTimezone::System,
+        )
+        .expect("optional memory should degrade instead of failing");
+
+        assert!(degraded);
+        assert_eq!(mem.name(), "none");
+    }
+
+    #[test]
+    fn required_session_memory_fails_when_init_fails() {
+        let tmp = TempDir::new().unwrap();
+        let (cfg, storage) = failing_postgres_config(true);
+
+        let error = create_session_memory(
+            &cfg,
+            Some(&storage),
+            tmp.path(),
+            None,
+            LocalTimezone::System,
+        )
+        .err()
+        .expect("required memory should fail hard");
+
+        assert!(format!("{error:#}").contains("db_url"));
+        assert!(error.to_string().contains("required"));
+    }
+
+    #[test]
+    fn session_memory_is_not_degraded_when_init_succeeds() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = MemoryConfig {
+            backend: "sqlite".into(),
+            ..MemoryConfig::default()
+        };
+
+        let (mem, degraded) =
+            create_session_memory(&cfg, None, tmp.path(), None, LocalTimezone::System).unwrap();
+
+        assert!(!degraded);
+        assert_eq!(mem.name(), "sqlite");
+    }
+
+    async fn two_session_memory(tmp: &TempDir) -> SqliteMemory {
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store(
+            "mine",
+            "deploy uses blue green",
+            MemoryCategory::Conversation,
+            Some("session-a"),
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "theirs",
+            "deploy deploy deploy with canary releases",
+            MemoryCategory::Conversation,
+            Some("session-b"),
+        )
+        .await
+        .unwrap();
+        mem
+    }
+
+    #[tokio::test]
+    async fn session_scope_excludes_other_sessions() {
+        let tmp = TempDir::new().unwrap();
+        let mem = two_session_memory(&tmp).await;
+
+        let entries = recall_scoped(&mem, "deploy", 5, RecallScope::Session, Some("session-a"))
+            .await
+            .unwrap();
+
+        let keys: Vec<&str> = entries.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["mine"]);
+    }
+
+    #[tokio::test]
+    async fn hybrid_scope_ranks_session_matches_first() {
+        let tmp = TempDir::new().unwrap();
+        let mem = two_session_memory(&tmp).await;
+
+        let entries = recall_scoped(&mem, "deploy", 5, RecallScope::Hybrid, Some("session-a"))
+            .await
+            .unwrap();
+
+        let keys: Vec<&str> = entries.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["mine", "theirs"]);
+    }
+
+    #[tokio::test]
+    async fn scopes_recall_globally_without_a_session() {
+        let tmp = TempDir::new().unwrap();
+        let mem = two_session_memory(&tmp).await;
+
+        let entries = recall_scoped(&mem, "deploy", 5, RecallScope::Session, None)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+    }
 }
@@ -0,0 +1,266 @@
+//! Per-workspace namespacing for memory stores shared by every workspace.
+//!
+//! Local backends keep their files under the workspace, so each workspace
+//! already has its own memory. A `postgres` table is shared, so with
+//! `[memory] namespace = "workspace"` (the default) [`NamespacedMemory`]
+//! stores keys as `ws-<hash>/<key>`, where the hash is taken from the
+//! workspace directory, and recall, list and count only see the active
+//! workspace's entries. Entries written before namespacing carry no prefix
+//! and stay visible in every workspace.
+
+use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+const NAMESPACE_PREFIX: &str = "ws-";
+const NAMESPACE_HASH_LEN: usize = 12;
+
+/// Candidates fetched per requested result, since other workspaces'
+/// entries are filtered out after recall.
+const RECALL_OVERFETCH_FACTOR: usize = 4;
+
+/// Namespace for `workspace_dir`: `ws-` and a short hash of its canonical path.
+pub fn workspace_namespace(workspace_dir: &Path) -> String {
+    let path = workspace_dir
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_dir.to_path_buf());
+    let digest = Sha256::digest(path.to_string_lossy().as_bytes());
+    format!(
+        "{NAMESPACE_PREFIX}{}",
+        &hex::encode(digest)[..NAMESPACE_HASH_LEN]
+    )
+}
+
+/// The namespace a stored key belongs to, if any.
+fn namespace_of(key: &str) -> Option<&str> {
+    let (namespace, _) = key.split_once('/')?;
+    let hash = namespace.strip_prefix(NAMESPACE_PREFIX)?;
+    (hash.len() == NAMESPACE_HASH_LEN && hash.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then_some(namespace)
+}
+
+/// Wraps a shared store so one workspace only sees its own entries.
+pub struct NamespacedMemory {
+    inner: Box<dyn Memory>,
+    namespace: String,
+}
+
+impl NamespacedMemory {
+    pub fn new(inner: Box<dyn Memory>, workspace_dir: &Path) -> Self {
+        Self {
+            inner,
+            namespace: workspace_namespace(workspace_dir),
+        }
+    }
+
+    fn scoped_key(&self, key: &str) -> String {
+        format!("{}/{key}", self.namespace)
+    }
+
+    /// `entry` with its key as the caller wrote it, or `None` if it belongs
+    /// to another workspace.
+    fn visible(&self, mut entry: MemoryEntry) -> Option<MemoryEntry> {
+        match namespace_of(&entry.key).map(|namespace| namespace == self.namespace) {
+            Some(true) => {
+                entry.key.drain(..=self.namespace.len());
+                Some(entry)
+            }
+            Some(false) => None,
+            None => Some(entry),
+        }
+    }
+
+    fn visible_entries(&self, entries: Vec<MemoryEntry>) -> Vec<MemoryEntry> {
+        entries
+            .into_iter()
+            .filter_map(|entry| self.visible(entry))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Memory for NamespacedMemory {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .store(&self.scoped_key(key), content, category, session_id)
+            .await
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let candidates = self
+            .inner
+            .recall(
+                query,
+                limit.saturating_mul(RECALL_OVERFETCH_FACTOR),
+                session_id,
+            )
+            .await?;
+        let mut entries = self.visible_entries(candidates);
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        if let Some(entry) = self.inner.get(&self.scoped_key(key)).await? {
+            return Ok(self.visible(entry));
+        }
+        Ok(self
+            .inner
+            .get(key)
+            .await?
+            .and_then(|entry| self.visible(entry)))
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        Ok(self.visible_entries(self.inner.list(category, session_id).await?))
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        if self.inner.forget(&self.scoped_key(key)).await? {
+            return Ok(true);
+        }
+        if namespace_of(key).is_some() {
+            return Ok(false);
+        }
+        self.inner.forget(key).await
+    }
+
+    async fn replace_daily(
+        &self,
+        date: NaiveDate,
+        keys: &[String],
+        key: &str,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        let keys: Vec<String> = keys.iter().map(|k| self.scoped_key(k)).collect();
+        self.inner
+            .replace_daily(date, &keys, &self.scoped_key(key), content)
+            .await
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        Ok(self.list(None, None).await?.len())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use tempfile::TempDir;
+
+    /// Two workspaces over one store, as with a shared postgres table.
+    fn shared_store(store: &TempDir, workspace: &Path) -> NamespacedMemory {
+        NamespacedMemory::new(
+            Box::new(SqliteMemory::new(store.path()).unwrap()),
+            workspace,
+        )
+    }
+
+    #[tokio::test]
+    async fn recall_stays_within_the_workspace() {
+        let store = TempDir::new().unwrap();
+        let workspaces = TempDir::new().unwrap();
+        let alpha_dir = workspaces.path().join("alpha");
+        let beta_dir = workspaces.path().join("beta");
+        std::fs::create_dir_all(&alpha_dir).unwrap();
+        std::fs::create_dir_all(&beta_dir).unwrap();
+        let alpha = shared_store(&store, &alpha_dir);
+        let beta = shared_store(&store, &beta_dir);
+
+        alpha
+            .store(
+                "client",
+                "Alpha wants weekly reports",
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        beta.store(
+            "client",
+            "Beta wants daily reports",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let recalled = alpha.recall("reports", 10, None).await.unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert_eq!(recalled[0].key, "client");
+        assert_eq!(recalled[0].content, "Alpha wants weekly reports");
+        let recalled = beta.recall("reports", 10, None).await.unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert_eq!(recalled[0].content, "Beta wants daily reports");
+
+        // Without the wrapper (`namespace = "global"`) both are visible.
+        let global = SqliteMemory::new(store.path()).unwrap();
+        assert_eq!(global.recall("reports", 10, None).await.unwrap().len(), 2);
+
+        assert_eq!(
+            alpha.get("client").await.unwrap().unwrap().content,
+            "Alpha wants weekly reports"
+        );
+        assert_eq!(alpha.count().await.unwrap(), 1);
+        assert!(alpha.forget("client").await.unwrap());
+        assert!(alpha.get("client").await.unwrap().is_none());
+        assert!(beta.get("client").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn entries_from_before_namespacing_stay_visible() {
+        let store = TempDir::new().unwrap();
+        SqliteMemory::new(store.path())
+            .unwrap()
+            .store(
+                "legacy",
+                "Old shared report notes",
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let alpha = shared_store(&store, Path::new("/work/alpha"));
+        let recalled = alpha.recall("report", 10, None).await.unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert_eq!(recalled[0].key, "legacy");
+        assert!(alpha.get("legacy").await.unwrap().is_some());
+    }
+
+    #[test]
+    fn namespaces_are_stable_and_recognizable() {
+        let ns = workspace_namespace(Path::new("/work/alpha"));
+        assert_eq!(ns, workspace_namespace(Path::new("/work/alpha")));
+        assert_ne!(ns, workspace_namespace(Path::new("/work/beta")));
+        assert_eq!(namespace_of(&format!("{ns}/client")), Some(ns.as_str()));
+        assert_eq!(namespace_of("client"), None);
+        assert_eq!(namespace_of("ws-notahash/client"), None);
+    }
+}
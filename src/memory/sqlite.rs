@@ -3,7 +3,7 @@ use super::traits::{Memory, MemoryCategory, MemoryEntry};
 use super::vector;
 use anyhow::Context;
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{Local, NaiveDate, TimeZone};
 use parking_lot::Mutex;
 use rusqlite::{params, Connection};
 use std::fmt::Write as _;
@@ -760,6 +760,56 @@ impl Memory for SqliteMemory {
         .await?
     }
 
+    /// Writes the entry dated local midnight of `date` and deletes the old
+    /// rows in the same transaction.
+    async fn replace_daily(
+        &self,
+        date: NaiveDate,
+        keys: &[String],
+        key: &str,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        let embedding_bytes = self
+            .get_or_compute_embedding(content)
+            .await?
+            .map(|emb| vector::vec_to_bytes(&emb));
+
+        let conn = self.conn.clone();
+        let keys = keys.to_vec();
+        let key = key.to_string();
+        let content = content.to_string();
+
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let mut conn = conn.lock();
+            let created_at = date
+                .and_hms_opt(0, 0, 0)
+                .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+                .map_or_else(|| date.to_string(), |midnight| midnight.to_rfc3339());
+            let now = Local::now().to_rfc3339();
+            let cat = Self::category_to_str(&MemoryCategory::Daily);
+            let id = Uuid::new_v4().to_string();
+
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, NULL)
+                 ON CONFLICT(key) DO UPDATE SET
+                    content = excluded.content,
+                    category = excluded.category,
+                    embedding = excluded.embedding,
+                    created_at = excluded.created_at,
+                    updated_at = excluded.updated_at",
+                params![id, key, content, cat, embedding_bytes, created_at, now],
+            )?;
+            for old in keys.iter().filter(|old| **old != key) {
+                tx.execute("DELETE FROM memories WHERE key = ?1", params![old])?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?
+    }
+
     async fn count(&self) -> anyhow::Result<usize> {
         let conn = self.conn.clone();
 
@@ -804,6 +854,31 @@ mod tests {
         assert!(mem.health_check().await);
     }
 
+    #[tokio::test]
+    async fn sqlite_recovers_after_task_panics_holding_connection() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("before", "stored before panic", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let conn = mem.conn.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let _guard = conn.lock();
+            panic!("simulated panic while holding memory state");
+        })
+        .await;
+        assert!(result.is_err());
+
+        // The lock must be released and the backend fully usable afterwards.
+        mem.store("after", "stored after panic", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert!(mem.get("before").await.unwrap().is_some());
+        assert!(mem.get("after").await.unwrap().is_some());
+        assert_eq!(mem.count().await.unwrap(), 2);
+        assert!(mem.health_check().await);
+    }
+
     #[tokio::test]
     async fn sqlite_store_and_get() {
         let (_tmp, mem) = temp_sqlite();
@@ -1091,6 +1166,46 @@ mod tests {
         assert_ne!(h1, h2);
     }
 
+    // ── Daily compaction tests ───────────────────────────────────
+
+    #[tokio::test]
+    async fn replace_daily_swaps_entries_for_a_dated_summary() {
+        let (_tmp, mem) = temp_sqlite();
+        for (key, content) in [("a", "fixed the parser"), ("b", "shipped it")] {
+            mem.store(key, content, MemoryCategory::Daily, None)
+                .await
+                .unwrap();
+        }
+        mem.store("pref", "likes Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let keys = vec!["a".to_string(), "b".to_string()];
+        mem.replace_daily(
+            date,
+            &keys,
+            "daily_summary:2026-10-01",
+            "Fixed and shipped.",
+        )
+        .await
+        .unwrap();
+
+        let daily = mem.list(Some(&MemoryCategory::Daily), None).await.unwrap();
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].key, "daily_summary:2026-10-01");
+        assert!(daily[0].timestamp.starts_with("2026-10-01T00:00:00"));
+        assert!(mem.get("pref").await.unwrap().is_some());
+
+        // Re-running with the summary among the keys keeps it.
+        let keys = vec!["daily_summary:2026-10-01".to_string()];
+        mem.replace_daily(date, &keys, "daily_summary:2026-10-01", "Shipped.")
+            .await
+            .unwrap();
+        let summary = mem.get("daily_summary:2026-10-01").await.unwrap().unwrap();
+        assert_eq!(summary.content, "Shipped.");
+    }
+
     // ── Schema tests ─────────────────────────────────────────────
 
     #[tokio::test]
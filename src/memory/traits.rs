@@ -1,6 +1,13 @@
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Candidates fetched per requested result when `search` filters after recall.
+const SEARCH_OVERFETCH_FACTOR: usize = 4;
+
+/// Minimum recall candidates considered by the default `search`.
+const SEARCH_MIN_CANDIDATES: usize = 50;
+
 /// A single memory entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryEntry {
@@ -38,6 +45,70 @@ impl std::fmt::Display for MemoryCategory {
     }
 }
 
+impl MemoryCategory {
+    /// Parse a category name as used by tools and the CLI.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "core" => Self::Core,
+            "daily" => Self::Daily,
+            "conversation" => Self::Conversation,
+            other => Self::Custom(other.to_string()),
+        }
+    }
+}
+
+/// Constraints for [`Memory::search`]
+#[derive(Debug, Clone, Default)]
+pub struct MemorySearchFilter {
+    /// Keywords to rank by; empty returns the most recent matching entries
+    pub query: String,
+    /// Only entries in this category
+    pub category: Option<MemoryCategory>,
+    /// Only entries stored at or after this instant
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries stored at or before this instant
+    pub until: Option<DateTime<Utc>>,
+    /// Only entries from this session
+    pub session_id: Option<String>,
+    /// Maximum number of results
+    pub limit: usize,
+}
+
+impl MemorySearchFilter {
+    /// Whether `entry` satisfies the category and time-range constraints.
+    /// Entries with unparseable timestamps never match a time range.
+    pub fn matches(&self, entry: &MemoryEntry) -> bool {
+        if self
+            .category
+            .as_ref()
+            .is_some_and(|category| *category != entry.category)
+        {
+            return false;
+        }
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        let Some(timestamp) = parse_entry_timestamp(&entry.timestamp) else {
+            return false;
+        };
+        self.since.map_or(true, |since| timestamp >= since)
+            && self.until.map_or(true, |until| timestamp <= until)
+    }
+}
+
+/// Parse a [`MemoryEntry::timestamp`]: RFC 3339, or a `YYYY-MM-DD` date
+/// (markdown daily files) taken as midnight UTC.
+pub fn parse_entry_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(raw) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|midnight| midnight.and_utc())
+}
+
 /// Core memory trait — implement for any persistence backend
 #[async_trait]
 pub trait Memory: Send + Sync {
@@ -61,6 +132,28 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// Recall memories matching a query, restricted by category, time range
+    /// and session. The default over-fetches from `recall` (or `list` for an
+    /// empty query) and filters the candidates.
+    async fn search(&self, filter: &MemorySearchFilter) -> anyhow::Result<Vec<MemoryEntry>> {
+        let candidates = if filter.query.trim().is_empty() {
+            self.list(filter.category.as_ref(), filter.session_id.as_deref())
+                .await?
+        } else {
+            let fetch = filter
+                .limit
+                .saturating_mul(SEARCH_OVERFETCH_FACTOR)
+                .max(SEARCH_MIN_CANDIDATES);
+            self.recall(&filter.query, fetch, filter.session_id.as_deref())
+                .await?
+        };
+        Ok(candidates
+            .into_iter()
+            .filter(|entry| filter.matches(entry))
+            .take(filter.limit)
+            .collect())
+    }
+
     /// Get a specific memory by key
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>>;
 
@@ -74,6 +167,26 @@ pub trait Memory: Send + Sync {
     /// Remove a memory by key
     async fn forget(&self, key: &str) -> anyhow::Result<bool>;
 
+    /// Replace the `Daily` entries `keys`, all logged on `date`, with one
+    /// entry `key` holding `content` (daily-log compaction). The new entry is
+    /// written before the old ones are removed, so an interrupted call leaves
+    /// both rather than neither. The default stores the entry, dated now, and
+    /// forgets the old keys.
+    async fn replace_daily(
+        &self,
+        _date: NaiveDate,
+        keys: &[String],
+        key: &str,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        self.store(key, content, MemoryCategory::Daily, None)
+            .await?;
+        for old in keys.iter().filter(|old| old.as_str() != key) {
+            self.forget(old).await?;
+        }
+        Ok(())
+    }
+
     /// Count total memories
     async fn count(&self) -> anyhow::Result<usize>;
 
@@ -107,6 +220,45 @@ mod tests {
         assert_eq!(conversation, "\"conversation\"");
     }
 
+    fn entry_at(category: MemoryCategory, timestamp: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: "id".into(),
+            key: "key".into(),
+            content: "content".into(),
+            category,
+            timestamp: timestamp.into(),
+            session_id: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn search_filter_matches_category_and_range() {
+        let filter = MemorySearchFilter {
+            category: Some(MemoryCategory::Core),
+            since: parse_entry_timestamp("2026-02-10"),
+            until: parse_entry_timestamp("2026-02-20T00:00:00Z"),
+            limit: 5,
+            ..MemorySearchFilter::default()
+        };
+
+        assert!(filter.matches(&entry_at(MemoryCategory::Core, "2026-02-15T12:00:00+02:00")));
+        assert!(!filter.matches(&entry_at(MemoryCategory::Daily, "2026-02-15T12:00:00Z")));
+        assert!(!filter.matches(&entry_at(MemoryCategory::Core, "2026-01-01T00:00:00Z")));
+        assert!(!filter.matches(&entry_at(MemoryCategory::Core, "MEMORY")));
+        assert!(MemorySearchFilter::default()
+            .matches(&entry_at(MemoryCategory::Daily, "not a timestamp")));
+    }
+
+    #[test]
+    fn category_from_name_maps_builtins() {
+        assert_eq!(MemoryCategory::from_name("core"), MemoryCategory::Core);
+        assert_eq!(
+            MemoryCategory::from_name("project"),
+            MemoryCategory::Custom("project".into())
+        );
+    }
+
     #[test]
     fn memory_entry_roundtrip_preserves_optional_fields() {
         let entry = MemoryEntry {
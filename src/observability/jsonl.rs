@@ -0,0 +1,288 @@
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use crate::config::ObservabilityConfig;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::any::Any;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Log file used when `[observability].log_path` is unset.
+pub fn default_log_path() -> PathBuf {
+    directories::UserDirs::new()
+        .map_or_else(
+            || PathBuf::from(".zeroclaw"),
+            |u| u.home_dir().join(".zeroclaw"),
+        )
+        .join("logs")
+        .join("events.jsonl")
+}
+
+/// Path the JSONL observer writes to for this config.
+pub fn log_path(config: &ObservabilityConfig) -> PathBuf {
+    config
+        .log_path
+        .as_deref()
+        .map_or_else(default_log_path, PathBuf::from)
+}
+
+/// Appends one JSON object per event to a log file, so runs can be
+/// inspected afterwards (`log_query` tool, `jq`, ...).
+///
+/// Each line has `ts` (RFC 3339), `event` (same names as the log backend,
+/// e.g. `tool.call`) and the event's fields.
+pub struct JsonlObserver {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+impl JsonlObserver {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            file: Mutex::new(None),
+        }
+    }
+
+    fn write_line(&self, mut record: Value) {
+        record["ts"] = json!(chrono::Utc::now().to_rfc3339());
+        let mut guard = self.file.lock();
+        if guard.is_none() {
+            if let Some(parent) = self.path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            match OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+            {
+                Ok(file) => *guard = Some(file),
+                Err(e) => {
+                    tracing::warn!("Failed to open {}: {e}", self.path.display());
+                    return;
+                }
+            }
+        }
+        if let Some(file) = guard.as_mut() {
+            let _ = writeln!(file, "{record}");
+        }
+    }
+}
+
+fn millis(duration: &std::time::Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// JSON form of an event, or `None` for high-volume events not worth keeping.
+fn event_record(event: &ObserverEvent) -> Option<Value> {
+    let record = match event {
+        ObserverEvent::AgentStart { provider, model } => {
+            json!({"event": "agent.start", "provider": provider, "model": model})
+        }
+        ObserverEvent::LlmRequest {
+            provider,
+            model,
+            messages_count,
+        } => json!({
+            "event": "llm.request",
+            "provider": provider,
+            "model": model,
+            "messages_count": messages_count,
+        }),
+        ObserverEvent::LlmResponse {
+            provider,
+            model,
+            duration,
+            success,
+            error_message,
+            input_tokens,
+            output_tokens,
+        } => json!({
+            "event": "llm.response",
+            "provider": provider,
+            "model": model,
+            "duration_ms": millis(duration),
+            "success": success,
+            "error": error_message,
+            "input_tokens": input_tokens,
+            "output_tokens": output_tokens,
+        }),
+        ObserverEvent::RateLimit {
+            provider,
+            remaining_requests,
+            limit_requests,
+            remaining_tokens,
+            limit_tokens,
+        } => json!({
+            "event": "llm.rate_limit",
+            "provider": provider,
+            "remaining_requests": remaining_requests,
+            "limit_requests": limit_requests,
+            "remaining_tokens": remaining_tokens,
+            "limit_tokens": limit_tokens,
+        }),
+        ObserverEvent::CircuitBreaker { provider, state } => json!({
+            "event": "llm.circuit_breaker",
+            "provider": provider,
+            "state": state,
+        }),
+        ObserverEvent::MessageRouted {
+            rule,
+            provider,
+            model,
+            temperature,
+        } => json!({
+            "event": "agent.route",
+            "rule": rule,
+            "provider": provider,
+            "model": model,
+            "temperature": temperature,
+        }),
+        ObserverEvent::AgentEnd {
+            provider,
+            model,
+            duration,
+            tokens_used,
+            cost_usd,
+            interrupted,
+        } => json!({
+            "event": "agent.end",
+            "provider": provider,
+            "model": model,
+            "duration_ms": millis(duration),
+            "tokens": tokens_used,
+            "cost_usd": cost_usd,
+            "interrupted": interrupted,
+        }),
+        ObserverEvent::AgentTimeout { timeout } => json!({
+            "event": "agent.timeout",
+            "timeout_secs": timeout.as_secs(),
+        }),
+        ObserverEvent::BudgetExhausted {
+            tokens_used,
+            max_tokens,
+            cost_usd,
+            max_cost_usd,
+        } => json!({
+            "event": "agent.budget_exhausted",
+            "tokens": tokens_used,
+            "max_tokens": max_tokens,
+            "cost_usd": cost_usd,
+            "max_cost_usd": max_cost_usd,
+        }),
+        ObserverEvent::ToolCallStart { tool } => json!({"event": "tool.start", "tool": tool}),
+        ObserverEvent::ToolCall {
+            tool,
+            duration,
+            success,
+        } => json!({
+            "event": "tool.call",
+            "tool": tool,
+            "duration_ms": millis(duration),
+            "success": success,
+        }),
+        ObserverEvent::TurnComplete => json!({"event": "turn.complete"}),
+        ObserverEvent::TurnSummary {
+            tool_calls,
+            tool_failures,
+            files_changed,
+            lines_added,
+            lines_removed,
+            shell_commands,
+            provider_round_trips,
+        } => json!({
+            "event": "turn.summary",
+            "tool_calls": tool_calls,
+            "tool_failures": tool_failures,
+            "files_changed": files_changed,
+            "lines_added": lines_added,
+            "lines_removed": lines_removed,
+            "shell_commands": shell_commands,
+            "provider_round_trips": provider_round_trips,
+        }),
+        ObserverEvent::ChannelMessage { channel, direction } => json!({
+            "event": "channel.message",
+            "channel": channel,
+            "direction": direction,
+        }),
+        ObserverEvent::Error { component, message } => json!({
+            "event": "error",
+            "component": component,
+            "error": message,
+        }),
+        ObserverEvent::ToolOutput { .. } | ObserverEvent::HeartbeatTick => return None,
+    };
+    Some(record)
+}
+
+impl Observer for JsonlObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        if let Some(record) = event_record(event) {
+            self.write_line(record);
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn flush(&self) {
+        if let Some(file) = self.file.lock().as_mut() {
+            let _ = file.flush();
+        }
+    }
+
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn jsonl_observer_appends_one_line_per_event() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("logs/events.jsonl");
+        let obs = JsonlObserver::new(path.clone());
+
+        obs.record_event(&ObserverEvent::ToolCall {
+            tool: "shell".into(),
+            duration: Duration::from_millis(12),
+            success: false,
+        });
+        obs.record_event(&ObserverEvent::HeartbeatTick);
+        obs.record_event(&ObserverEvent::Error {
+            component: "provider".into(),
+            message: "timeout".into(),
+        });
+        obs.flush();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "tool.call");
+        assert_eq!(lines[0]["duration_ms"], 12);
+        assert_eq!(lines[0]["success"], false);
+        assert_eq!(lines[1]["event"], "error");
+        assert_eq!(lines[1]["error"], "timeout");
+        assert!(lines[1]["ts"].is_string());
+    }
+
+    #[test]
+    fn log_path_prefers_configured_path() {
+        let cfg = ObservabilityConfig {
+            log_path: Some("/tmp/zc-events.jsonl".into()),
+            ..ObservabilityConfig::default()
+        };
+        assert_eq!(log_path(&cfg), PathBuf::from("/tmp/zc-events.jsonl"));
+        assert!(log_path(&ObservabilityConfig::default()).ends_with("logs/events.jsonl"));
+    }
+}
@@ -17,15 +17,66 @@ impl Observer for LogObserver {
             ObserverEvent::AgentStart { provider, model } => {
                 info!(provider = %provider, model = %model, "agent.start");
             }
+            ObserverEvent::RateLimit {
+                provider,
+                remaining_requests,
+                limit_requests,
+                remaining_tokens,
+                limit_tokens,
+            } => {
+                tracing::debug!(
+                    provider = %provider,
+                    remaining_requests = ?remaining_requests,
+                    limit_requests = ?limit_requests,
+                    remaining_tokens = ?remaining_tokens,
+                    limit_tokens = ?limit_tokens,
+                    "llm.rate_limit"
+                );
+            }
+            ObserverEvent::CircuitBreaker { provider, state } => {
+                info!(provider = %provider, state = %state, "llm.circuit_breaker");
+            }
+            ObserverEvent::MessageRouted {
+                rule,
+                provider,
+                model,
+                temperature,
+            } => {
+                info!(
+                    rule = rule.as_deref().unwrap_or("default"),
+                    provider = %provider,
+                    model = %model,
+                    temperature,
+                    "agent.route"
+                );
+            }
             ObserverEvent::AgentEnd {
                 provider,
                 model,
                 duration,
                 tokens_used,
                 cost_usd,
+                interrupted,
             } => {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
-                info!(provider = %provider, model = %model, duration_ms = ms, tokens = ?tokens_used, cost_usd = ?cost_usd, "agent.end");
+                info!(provider = %provider, model = %model, duration_ms = ms, tokens = ?tokens_used, cost_usd = ?cost_usd, interrupted = interrupted, "agent.end");
+            }
+            ObserverEvent::AgentTimeout { timeout } => {
+                tracing::warn!(timeout_secs = timeout.as_secs(), "agent.timeout");
+            }
+            ObserverEvent::BudgetExhausted {
+                tokens_used,
+                max_tokens,
+                cost_usd,
+                max_cost_usd,
+            } => {
+                tracing::warn!(
+                    tokens = tokens_used,
+                    max_tokens = ?max_tokens,
+                    cost_usd = cost_usd,
+                    max_cost_usd = ?max_cost_usd,
+                    "agent.budget_exhausted"
+                );
             }
             ObserverEvent::ToolCallStart { tool } => {
                 info!(tool = %tool, "tool.start");
@@ -38,9 +89,32 @@ impl Observer for LogObserver {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
                 info!(tool = %tool, duration_ms = ms, success = success, "tool.call");
             }
+            ObserverEvent::ToolOutput { tool, line } => {
+                tracing::debug!(tool = %tool, line = %line, "tool.output");
+            }
             ObserverEvent::TurnComplete => {
                 info!("turn.complete");
             }
+            ObserverEvent::TurnSummary {
+                tool_calls,
+                tool_failures,
+                files_changed,
+                lines_added,
+                lines_removed,
+                shell_commands,
+                provider_round_trips,
+            } => {
+                info!(
+                    tool_calls = tool_calls,
+                    tool_failures = tool_failures,
+                    files_changed = files_changed,
+                    lines_added = lines_added,
+                    lines_removed = lines_removed,
+                    shell_commands = shell_commands,
+                    provider_round_trips = provider_round_trips,
+                    "turn.summary"
+                );
+            }
             ObserverEvent::ChannelMessage { channel, direction } => {
                 info!(channel = %channel, direction = %direction, "channel.message");
             }
@@ -68,6 +142,8 @@ impl Observer for LogObserver {
                 duration,
                 success,
                 error_message,
+                input_tokens,
+                output_tokens,
             } => {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
                 info!(
@@ -76,6 +152,8 @@ impl Observer for LogObserver {
                     duration_ms = ms,
                     success = success,
                     error = ?error_message,
+                    input_tokens = ?input_tokens,
+                    output_tokens = ?output_tokens,
                     "llm.response"
                 );
             }
@@ -132,6 +210,7 @@ mod tests {
             duration: Duration::from_millis(500),
             tokens_used: Some(100),
             cost_usd: Some(0.0015),
+            interrupted: false,
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             provider: "openrouter".into(),
@@ -139,6 +218,7 @@ mod tests {
             duration: Duration::ZERO,
             tokens_used: None,
             cost_usd: None,
+            interrupted: false,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "shell".into(),
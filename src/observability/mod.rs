@@ -1,8 +1,10 @@
+pub mod jsonl;
 pub mod log;
 pub mod multi;
 pub mod noop;
 pub mod otel;
 pub mod prometheus;
+pub mod prompt_audit;
 pub mod traits;
 pub mod verbose;
 
@@ -10,6 +12,7 @@ pub mod verbose;
 pub use self::log::LogObserver;
 #[allow(unused_imports)]
 pub use self::multi::MultiObserver;
+pub use jsonl::JsonlObserver;
 pub use noop::NoopObserver;
 pub use otel::OtelObserver;
 pub use prometheus::PrometheusObserver;
@@ -23,7 +26,13 @@ use crate::config::ObservabilityConfig;
 pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
     match config.backend.as_str() {
         "log" => Box::new(LogObserver::new()),
-        "prometheus" => Box::new(PrometheusObserver::new()),
+        "jsonl" => Box::new(JsonlObserver::new(jsonl::log_path(config))),
+        "prometheus" => {
+            if let Some(port) = config.metrics_port {
+                prometheus::spawn_metrics_server(&config.metrics_host, port);
+            }
+            Box::new(PrometheusObserver::shared())
+        }
         "otel" | "opentelemetry" | "otlp" => {
             match OtelObserver::new(
                 config.otel_endpoint.as_deref(),
@@ -87,6 +96,15 @@ mod tests {
         assert_eq!(create_observer(&cfg).name(), "log");
     }
 
+    #[test]
+    fn factory_jsonl_returns_jsonl() {
+        let cfg = ObservabilityConfig {
+            backend: "jsonl".into(),
+            ..ObservabilityConfig::default()
+        };
+        assert_eq!(create_observer(&cfg).name(), "jsonl");
+    }
+
     #[test]
     fn factory_prometheus_returns_prometheus() {
         let cfg = ObservabilityConfig {
@@ -102,6 +120,7 @@ mod tests {
             backend: "otel".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
@@ -112,6 +131,7 @@ mod tests {
             backend: "opentelemetry".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
@@ -122,6 +142,7 @@ mod tests {
             backend: "otlp".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
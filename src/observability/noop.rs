@@ -44,6 +44,7 @@ mod tests {
             duration: Duration::from_millis(100),
             tokens_used: Some(42),
             cost_usd: Some(0.001),
+            interrupted: false,
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             provider: "test".into(),
@@ -51,6 +52,7 @@ mod tests {
             duration: Duration::ZERO,
             tokens_used: None,
             cost_usd: None,
+            interrupted: false,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "shell".into(),
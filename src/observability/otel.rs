@@ -186,13 +186,22 @@ impl Observer for OtelObserver {
             }
             ObserverEvent::LlmRequest { .. }
             | ObserverEvent::ToolCallStart { .. }
-            | ObserverEvent::TurnComplete => {}
+            | ObserverEvent::ToolOutput { .. }
+            | ObserverEvent::TurnComplete
+            | ObserverEvent::TurnSummary { .. }
+            | ObserverEvent::RateLimit { .. }
+            | ObserverEvent::CircuitBreaker { .. }
+            | ObserverEvent::MessageRouted { .. }
+            | ObserverEvent::AgentTimeout { .. }
+            | ObserverEvent::BudgetExhausted { .. } => {}
             ObserverEvent::LlmResponse {
                 provider,
                 model,
                 duration,
                 success,
                 error_message: _,
+                input_tokens: _,
+                output_tokens: _,
             } => {
                 let secs = duration.as_secs_f64();
                 let attrs = [
@@ -231,6 +240,7 @@ impl Observer for OtelObserver {
                 duration,
                 tokens_used,
                 cost_usd,
+                interrupted,
             } => {
                 let secs = duration.as_secs_f64();
                 let start_time = SystemTime::now()
@@ -246,6 +256,7 @@ impl Observer for OtelObserver {
                             KeyValue::new("provider", provider.clone()),
                             KeyValue::new("model", model.clone()),
                             KeyValue::new("duration_s", secs),
+                            KeyValue::new("interrupted", *interrupted),
                         ]),
                 );
                 if let Some(t) = tokens_used {
@@ -411,6 +422,8 @@ mod tests {
             duration: Duration::from_millis(250),
             success: true,
             error_message: None,
+            input_tokens: None,
+            output_tokens: None,
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             provider: "openrouter".into(),
@@ -418,6 +431,7 @@ mod tests {
             duration: Duration::from_millis(500),
             tokens_used: Some(100),
             cost_usd: Some(0.0015),
+            interrupted: false,
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             provider: "openrouter".into(),
@@ -425,6 +439,7 @@ mod tests {
             duration: Duration::ZERO,
             tokens_used: None,
             cost_usd: None,
+            interrupted: false,
         });
         obs.record_event(&ObserverEvent::ToolCallStart {
             tool: "shell".into(),
@@ -489,6 +504,8 @@ mod tests {
             duration: Duration::from_millis(0),
             success: false,
             error_message: Some("404 Not Found".into()),
+            input_tokens: None,
+            output_tokens: None,
         });
     }
 
@@ -2,8 +2,18 @@ use super::traits::{Observer, ObserverEvent, ObserverMetric};
 use prometheus::{
     Encoder, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounterVec, Registry, TextEncoder,
 };
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+
+/// Prometheus content type for text exposition format.
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Process-wide metrics shared by every observer from `create_observer`, so
+/// one scrape covers the gateway, channels and agent runs.
+static SHARED: LazyLock<PrometheusObserver> = LazyLock::new(PrometheusObserver::new);
 
 /// Prometheus-backed observer — exposes metrics for scraping via `/metrics`.
+#[derive(Clone)]
 pub struct PrometheusObserver {
     registry: Registry,
 
@@ -13,11 +23,14 @@ pub struct PrometheusObserver {
     channel_messages: IntCounterVec,
     heartbeat_ticks: prometheus::IntCounter,
     errors: IntCounterVec,
+    llm_requests: IntCounterVec,
+    token_usage: IntCounterVec,
 
     // Histograms
     agent_duration: HistogramVec,
     tool_duration: HistogramVec,
     request_latency: Histogram,
+    llm_latency: HistogramVec,
 
     // Gauges
     tokens_used: prometheus::IntGauge,
@@ -57,6 +70,31 @@ impl PrometheusObserver {
         )
         .expect("valid metric");
 
+        let llm_requests = IntCounterVec::new(
+            prometheus::Opts::new("zeroclaw_llm_requests_total", "Total LLM provider requests"),
+            &["provider", "model"],
+        )
+        .expect("valid metric");
+
+        let token_usage = IntCounterVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_token_usage_total",
+                "Estimated tokens by provider and type (input/output)",
+            ),
+            &["provider", "type"],
+        )
+        .expect("valid metric");
+
+        let llm_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "zeroclaw_llm_latency_seconds",
+                "LLM provider call latency in seconds",
+            )
+            .buckets(vec![0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0]),
+            &["provider", "model"],
+        )
+        .expect("valid metric");
+
         let agent_duration = HistogramVec::new(
             HistogramOpts::new(
                 "zeroclaw_agent_duration_seconds",
@@ -110,6 +148,9 @@ impl PrometheusObserver {
         registry.register(Box::new(channel_messages.clone())).ok();
         registry.register(Box::new(heartbeat_ticks.clone())).ok();
         registry.register(Box::new(errors.clone())).ok();
+        registry.register(Box::new(llm_requests.clone())).ok();
+        registry.register(Box::new(token_usage.clone())).ok();
+        registry.register(Box::new(llm_latency.clone())).ok();
         registry.register(Box::new(agent_duration.clone())).ok();
         registry.register(Box::new(tool_duration.clone())).ok();
         registry.register(Box::new(request_latency.clone())).ok();
@@ -124,16 +165,27 @@ impl PrometheusObserver {
             channel_messages,
             heartbeat_ticks,
             errors,
+            llm_requests,
+            token_usage,
             agent_duration,
             tool_duration,
             request_latency,
+            llm_latency,
             tokens_used,
             active_sessions,
             queue_depth,
         }
     }
 
-    /// Encode all registered metrics into Prometheus text exposition format.
+    /// A handle on the process-wide metrics.
+    pub fn shared() -> Self {
+        SHARED.clone()
+    }
+
+    /// Encode all registered metrics into Prometheus text exposition format
+    /// with the crate's `TextEncoder`, which writes one `# HELP`/`# TYPE`
+    /// pair per family and escapes label values (model and tool names come
+    /// from config and model output).
     pub fn encode(&self) -> String {
         let encoder = TextEncoder::new();
         let families = self.registry.gather();
@@ -157,6 +209,7 @@ impl Observer for PrometheusObserver {
                 duration,
                 tokens_used,
                 cost_usd: _,
+                interrupted: _,
             } => {
                 // Agent duration is recorded via the histogram with provider/model labels
                 self.agent_duration
@@ -166,10 +219,41 @@ impl Observer for PrometheusObserver {
                     self.tokens_used.set(i64::try_from(*t).unwrap_or(i64::MAX));
                 }
             }
+            ObserverEvent::LlmRequest {
+                provider, model, ..
+            } => {
+                self.llm_requests
+                    .with_label_values(&[provider, model])
+                    .inc();
+            }
+            ObserverEvent::LlmResponse {
+                provider,
+                model,
+                duration,
+                input_tokens,
+                output_tokens,
+                ..
+            } => {
+                self.llm_latency
+                    .with_label_values(&[provider, model])
+                    .observe(duration.as_secs_f64());
+                for (kind, tokens) in [("input", input_tokens), ("output", output_tokens)] {
+                    if let Some(tokens) = tokens {
+                        self.token_usage
+                            .with_label_values(&[provider.as_str(), kind])
+                            .inc_by(*tokens);
+                    }
+                }
+            }
             ObserverEvent::ToolCallStart { tool: _ }
+            | ObserverEvent::ToolOutput { .. }
             | ObserverEvent::TurnComplete
-            | ObserverEvent::LlmRequest { .. }
-            | ObserverEvent::LlmResponse { .. } => {}
+            | ObserverEvent::TurnSummary { .. }
+            | ObserverEvent::RateLimit { .. }
+            | ObserverEvent::CircuitBreaker { .. }
+            | ObserverEvent::MessageRouted { .. }
+            | ObserverEvent::AgentTimeout { .. }
+            | ObserverEvent::BudgetExhausted { .. } => {}
             ObserverEvent::ToolCall {
                 tool,
                 duration,
@@ -230,6 +314,44 @@ impl Observer for PrometheusObserver {
     }
 }
 
+/// Serve the process-wide metrics on `GET /metrics` at `host:port`. Only
+/// the first call starts a server; it needs a running Tokio runtime.
+pub fn spawn_metrics_server(host: &str, port: u16) {
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        tracing::warn!("Metrics endpoint not started: no async runtime");
+        return;
+    };
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let addr = format!("{host}:{port}");
+    runtime.spawn(async move {
+        match tokio::net::TcpListener::bind(&addr).await {
+            Ok(listener) => {
+                tracing::info!("Prometheus metrics on http://{addr}/metrics");
+                if let Err(e) = serve_metrics(listener).await {
+                    tracing::warn!("Metrics endpoint stopped: {e}");
+                }
+            }
+            Err(e) => tracing::warn!("Failed to bind metrics endpoint on {addr}: {e}"),
+        }
+    });
+}
+
+async fn serve_metrics(listener: tokio::net::TcpListener) -> std::io::Result<()> {
+    let app = axum::Router::new().route(
+        "/metrics",
+        axum::routing::get(|| async {
+            (
+                [(axum::http::header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+                PrometheusObserver::shared().encode(),
+            )
+        }),
+    );
+    axum::serve(listener, app).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -253,6 +375,7 @@ mod tests {
             duration: Duration::from_millis(500),
             tokens_used: Some(100),
             cost_usd: None,
+            interrupted: false,
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             provider: "openrouter".into(),
@@ -260,6 +383,7 @@ mod tests {
             duration: Duration::ZERO,
             tokens_used: None,
             cost_usd: None,
+            interrupted: false,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "shell".into(),
@@ -372,6 +496,130 @@ mod tests {
         assert!(output.contains(r#"zeroclaw_errors_total{component="channels"} 1"#));
     }
 
+    #[test]
+    fn llm_calls_record_requests_latency_and_tokens() {
+        let obs = PrometheusObserver::new();
+        obs.record_event(&ObserverEvent::LlmRequest {
+            provider: "openrouter".into(),
+            model: "claude-sonnet".into(),
+            messages_count: 3,
+        });
+        obs.record_event(&ObserverEvent::LlmResponse {
+            provider: "openrouter".into(),
+            model: "claude-sonnet".into(),
+            duration: Duration::from_millis(800),
+            success: true,
+            error_message: None,
+            input_tokens: Some(1200),
+            output_tokens: Some(300),
+        });
+        obs.record_event(&ObserverEvent::LlmResponse {
+            provider: "openrouter".into(),
+            model: "claude-sonnet".into(),
+            duration: Duration::from_millis(100),
+            success: false,
+            error_message: Some("timeout".into()),
+            input_tokens: None,
+            output_tokens: None,
+        });
+
+        let output = obs.encode();
+        assert!(output.contains(
+            r#"zeroclaw_llm_requests_total{model="claude-sonnet",provider="openrouter"} 1"#
+        ));
+        assert!(output
+            .contains(r#"zeroclaw_token_usage_total{provider="openrouter",type="input"} 1200"#));
+        assert!(output
+            .contains(r#"zeroclaw_token_usage_total{provider="openrouter",type="output"} 300"#));
+        assert!(output.contains(
+            r#"zeroclaw_llm_latency_seconds_count{model="claude-sonnet",provider="openrouter"} 2"#
+        ));
+    }
+
+    #[test]
+    fn shared_handles_use_one_registry() {
+        PrometheusObserver::shared().record_event(&ObserverEvent::ChannelMessage {
+            channel: "shared-registry-test".into(),
+            direction: "inbound".into(),
+        });
+        assert!(PrometheusObserver::shared()
+            .encode()
+            .contains(r#"channel="shared-registry-test""#));
+    }
+
+    #[tokio::test]
+    async fn metrics_endpoint_serves_shared_metrics() {
+        PrometheusObserver::shared().record_event(&ObserverEvent::ChannelMessage {
+            channel: "scrape-test".into(),
+            direction: "inbound".into(),
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_metrics(listener));
+
+        let response = reqwest::get(format!("http://{addr}/metrics"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()["content-type"].to_str().unwrap(),
+            PROMETHEUS_CONTENT_TYPE
+        );
+        let body = response.text().await.unwrap();
+        assert!(body.contains(r#"channel="scrape-test""#));
+    }
+
+    #[test]
+    fn exposition_escapes_label_values() {
+        let obs = PrometheusObserver::new();
+        obs.record_event(&ObserverEvent::LlmRequest {
+            provider: "custom:https://llm.internal/v1".into(),
+            model: "odd\"model\\name\nline2".into(),
+            messages_count: 1,
+        });
+
+        let output = obs.encode();
+        assert!(output.contains(
+            r#"zeroclaw_llm_requests_total{model="odd\"model\\name\nline2",provider="custom:https://llm.internal/v1"} 1"#
+        ));
+        // The newline is escaped, so the sample stays on one line.
+        assert!(!output.lines().any(|line| line.starts_with("line2")));
+    }
+
+    #[test]
+    fn exposition_declares_each_family_once_before_its_samples() {
+        let obs = PrometheusObserver::new();
+        for (tool, success) in [("shell", true), ("shell", false), ("file_read", true)] {
+            obs.record_event(&ObserverEvent::ToolCall {
+                tool: tool.into(),
+                duration: Duration::from_millis(10),
+                success,
+            });
+        }
+        obs.record_metric(&ObserverMetric::TokensUsed(7));
+
+        let output = obs.encode();
+        for (family, kind) in [
+            ("zeroclaw_tool_calls_total", "counter"),
+            ("zeroclaw_tool_duration_seconds", "histogram"),
+            ("zeroclaw_tokens_used_last", "gauge"),
+        ] {
+            let type_line = format!("# TYPE {family} {kind}");
+            let declarations: Vec<usize> = output
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| line.starts_with(&format!("# TYPE {family} ")))
+                .map(|(index, _)| index)
+                .collect();
+            assert_eq!(declarations.len(), 1, "{family} declared once");
+            assert!(output.contains(&type_line), "{type_line}");
+            let first_sample = output
+                .lines()
+                .position(|line| line.starts_with(family))
+                .expect("family has samples");
+            assert!(declarations[0] < first_sample, "{family} typed before use");
+        }
+    }
+
     #[test]
     fn gauge_reflects_latest_value() {
         let obs = PrometheusObserver::new();
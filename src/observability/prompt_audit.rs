@@ -0,0 +1,232 @@
+//! Prompt audit log (`[observability] audit_prompts`).
+//!
+//! Every model call is appended as one JSON line holding the system prompt,
+//! the user message and the assistant response, with a timestamp and the
+//! session ID of the process that made it. Each line carries an `hmac`
+//! field: HMAC-SHA256, keyed by `audit_hmac_key`, over the same record
+//! serialized without it, so edits to the file can be detected with
+//! [`verify_line`]. A new file is started every UTC day.
+
+use crate::config::ObservabilityConfig;
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// One model call as written to the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptAuditRecord {
+    /// RFC 3339 time the response was received.
+    pub ts: String,
+    pub session_id: String,
+    pub provider: String,
+    pub model: String,
+    pub system_prompt: Option<String>,
+    pub user_message: String,
+    pub assistant_response: String,
+    /// Hex HMAC-SHA256 of the record without this field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hmac: Option<String>,
+}
+
+fn signature(record: &PromptAuditRecord, key: &[u8]) -> Result<String> {
+    let unsigned = PromptAuditRecord {
+        hmac: None,
+        ..record.clone()
+    };
+    let payload = serde_json::to_vec(&unsigned)?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).context("Invalid audit HMAC key")?;
+    mac.update(&payload);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Whether `line` is a record whose `hmac` matches its content under `key`.
+pub fn verify_line(line: &str, key: &str) -> Result<bool> {
+    let record: PromptAuditRecord =
+        serde_json::from_str(line).context("Malformed prompt audit record")?;
+    let Some(claimed) = record.hmac.as_deref() else {
+        return Ok(false);
+    };
+    let Ok(claimed) = hex::decode(claimed) else {
+        return Ok(false);
+    };
+    let unsigned = PromptAuditRecord {
+        hmac: None,
+        ..record
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).context("Invalid audit HMAC key")?;
+    mac.update(&serde_json::to_vec(&unsigned)?);
+    Ok(mac.verify_slice(&claimed).is_ok())
+}
+
+/// Directory the audit files are written to: next to the event log.
+pub fn audit_dir(config: &ObservabilityConfig) -> PathBuf {
+    super::jsonl::log_path(config)
+        .parent()
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+}
+
+/// Audit file for one UTC day.
+pub fn audit_file(dir: &Path, day: NaiveDate) -> PathBuf {
+    dir.join(format!("prompt-audit-{}.jsonl", day.format("%Y-%m-%d")))
+}
+
+/// Append-only, signed, daily-rotated log of model calls.
+pub struct PromptAuditLog {
+    dir: PathBuf,
+    key: String,
+    session_id: String,
+    file: Mutex<Option<(NaiveDate, File)>>,
+}
+
+// Hand-written so the key never ends up in debug output.
+impl std::fmt::Debug for PromptAuditLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PromptAuditLog")
+            .field("dir", &self.dir)
+            .field("session_id", &self.session_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PromptAuditLog {
+    pub fn new(dir: PathBuf, key: &str) -> Result<Self> {
+        if key.is_empty() {
+            anyhow::bail!(
+                "observability.audit_prompts is enabled but observability.audit_hmac_key is not set"
+            );
+        }
+        Ok(Self {
+            dir,
+            key: key.to_string(),
+            session_id: uuid::Uuid::new_v4().to_string(),
+            file: Mutex::new(None),
+        })
+    }
+
+    /// The log for this config, or `None` when prompt auditing is off.
+    pub fn from_config(config: &ObservabilityConfig) -> Result<Option<Self>> {
+        if !config.audit_prompts {
+            return Ok(None);
+        }
+        let key = config.audit_hmac_key.as_deref().unwrap_or_default();
+        Self::new(audit_dir(config), key).map(Some)
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Sign and append one model call.
+    pub fn record(
+        &self,
+        provider: &str,
+        model: &str,
+        system_prompt: Option<&str>,
+        user_message: &str,
+        assistant_response: &str,
+    ) -> Result<()> {
+        let now = Utc::now();
+        let mut record = PromptAuditRecord {
+            ts: now.to_rfc3339(),
+            session_id: self.session_id.clone(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            system_prompt: system_prompt.map(str::to_string),
+            user_message: user_message.to_string(),
+            assistant_response: assistant_response.to_string(),
+            hmac: None,
+        };
+        record.hmac = Some(signature(&record, self.key.as_bytes())?);
+        let line = serde_json::to_string(&record)?;
+
+        let today = now.date_naive();
+        let mut guard = self.file.lock();
+        if guard.as_ref().map_or(true, |(day, _)| *day != today) {
+            *guard = Some((today, self.open(today)?));
+        }
+        if let Some((_, file)) = guard.as_mut() {
+            writeln!(file, "{line}")?;
+            file.sync_data()?;
+        }
+        Ok(())
+    }
+
+    fn open(&self, day: NaiveDate) -> Result<File> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = audit_file(&self.dir, day);
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn records_are_signed_and_verifiable() {
+        let tmp = TempDir::new().unwrap();
+        let log = PromptAuditLog::new(tmp.path().to_path_buf(), "secret").unwrap();
+        log.record("openai", "gpt-4o", Some("be brief"), "hi", "hello")
+            .unwrap();
+        log.record("openai", "gpt-4o", None, "again", "hello again")
+            .unwrap();
+
+        let path = audit_file(tmp.path(), Utc::now().date_naive());
+        let content = std::fs::read_to_string(path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first: PromptAuditRecord = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first.session_id, log.session_id());
+        assert_eq!(first.system_prompt.as_deref(), Some("be brief"));
+        assert_eq!(first.user_message, "hi");
+        assert_eq!(first.assistant_response, "hello");
+        assert!(verify_line(lines[0], "secret").unwrap());
+        assert!(verify_line(lines[1], "secret").unwrap());
+        assert!(!verify_line(lines[0], "other key").unwrap());
+
+        let tampered = lines[0].replace("\"hello\"", "\"goodbye\"");
+        assert!(!verify_line(&tampered, "secret").unwrap());
+    }
+
+    #[test]
+    fn enabling_without_a_key_is_an_error() {
+        let config = ObservabilityConfig {
+            audit_prompts: true,
+            ..ObservabilityConfig::default()
+        };
+        let err = PromptAuditLog::from_config(&config).err().unwrap();
+        assert!(err.to_string().contains("audit_hmac_key"));
+
+        assert!(PromptAuditLog::from_config(&ObservabilityConfig::default())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn files_are_named_by_day() {
+        let day = NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        assert_eq!(
+            audit_file(Path::new("/logs"), day),
+            PathBuf::from("/logs/prompt-audit-2026-03-09.jsonl")
+        );
+    }
+}
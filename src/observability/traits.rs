@@ -23,6 +23,32 @@ pub enum ObserverEvent {
         duration: Duration,
         success: bool,
         error_message: Option<String>,
+        /// Estimated prompt tokens; `None` when the call failed.
+        input_tokens: Option<u64>,
+        /// Estimated completion tokens; `None` when the call failed.
+        output_tokens: Option<u64>,
+    },
+    /// Request/token budget reported by the provider's rate-limit headers.
+    RateLimit {
+        provider: String,
+        remaining_requests: Option<u64>,
+        limit_requests: Option<u64>,
+        remaining_tokens: Option<u64>,
+        limit_tokens: Option<u64>,
+    },
+    /// A provider's circuit breaker changed state (`closed`, `open`,
+    /// `half_open`).
+    CircuitBreaker {
+        provider: String,
+        state: String,
+    },
+    /// `[routing]` chose where a user message goes. `rule` is `None` when
+    /// no rule matched and the defaults were used.
+    MessageRouted {
+        rule: Option<String>,
+        provider: String,
+        model: String,
+        temperature: f64,
     },
     AgentEnd {
         provider: String,
@@ -30,6 +56,20 @@ pub enum ObserverEvent {
         duration: Duration,
         tokens_used: Option<u64>,
         cost_usd: Option<f64>,
+        /// The run was stopped by SIGINT/SIGTERM rather than finishing.
+        interrupted: bool,
+    },
+    /// The run was cancelled by `--timeout-secs` before it finished.
+    AgentTimeout {
+        timeout: Duration,
+    },
+    /// The session reached its `[limits]` budget and the agent loop stopped
+    /// before the next provider call.
+    BudgetExhausted {
+        tokens_used: u64,
+        max_tokens: Option<u64>,
+        cost_usd: f64,
+        max_cost_usd: Option<f64>,
     },
     /// A tool call is about to be executed.
     ToolCallStart {
@@ -40,8 +80,24 @@ pub enum ObserverEvent {
         duration: Duration,
         success: bool,
     },
+    /// A line of output from a running tool (streamed shell output).
+    ToolOutput {
+        tool: String,
+        line: String,
+    },
     /// The agent produced a final answer for the current user message.
     TurnComplete,
+    /// Totals for a completed turn: tool usage, workspace changes and
+    /// provider round-trips.
+    TurnSummary {
+        tool_calls: usize,
+        tool_failures: usize,
+        files_changed: usize,
+        lines_added: usize,
+        lines_removed: usize,
+        shell_commands: usize,
+        provider_round_trips: usize,
+    },
     ChannelMessage {
         channel: String,
         direction: String,
@@ -87,6 +87,8 @@ mod tests {
             duration: Duration::from_millis(12),
             success: true,
             error_message: None,
+            input_tokens: None,
+            output_tokens: None,
         });
         obs.record_event(&ObserverEvent::ToolCallStart {
             tool: "shell".into(),
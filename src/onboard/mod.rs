@@ -1,6 +1,8 @@
 pub mod wizard;
 
-pub use wizard::{run_channels_repair_wizard, run_models_refresh, run_quick_setup, run_wizard};
+pub use wizard::{
+    run_channels_repair_wizard, run_models_list, run_models_refresh, run_quick_setup, run_wizard,
+};
 
 #[cfg(test)]
 mod tests {
@@ -14,5 +16,6 @@ mod tests {
         assert_reexport_exists(run_channels_repair_wizard);
         assert_reexport_exists(run_quick_setup);
         assert_reexport_exists(run_models_refresh);
+        assert_reexport_exists(run_models_list);
     }
 }
@@ -1,4 +1,6 @@
-use crate::config::schema::{DingTalkConfig, IrcConfig, QQConfig, StreamMode, WhatsAppConfig};
+use crate::config::schema::{
+    DingTalkConfig, IrcConfig, QQConfig, SessionOverflow, StreamMode, WhatsAppConfig,
+};
 use crate::config::{
     AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, DiscordConfig,
     HeartbeatConfig, IMessageConfig, MatrixConfig, MemoryConfig, ObservabilityConfig,
@@ -17,7 +19,7 @@ use console::style;
 use dialoguer::{Confirm, Input, Select};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
@@ -54,6 +56,7 @@ const LIVE_MODEL_MAX_OPTIONS: usize = 120;
 const MODEL_PREVIEW_LIMIT: usize = 20;
 const MODEL_CACHE_FILE: &str = "models_cache.json";
 const MODEL_CACHE_TTL_SECS: u64 = 12 * 60 * 60;
+const MODEL_LIST_CACHE_TTL_SECS: u64 = 60 * 60;
 const CUSTOM_MODEL_SENTINEL: &str = "__custom_model__";
 
 // ── Main wizard entry point ──────────────────────────────────────
@@ -118,6 +121,7 @@ pub fn run_wizard() -> Result<Config> {
         autonomy: AutonomyConfig::default(),
         runtime: RuntimeConfig::default(),
         reliability: crate::config::ReliabilityConfig::default(),
+        provider: crate::config::ProviderConfig::default(),
         scheduler: crate::config::schema::SchedulerConfig::default(),
         agent: crate::config::schema::AgentConfig::default(),
         model_routes: Vec::new(),
@@ -136,10 +140,17 @@ pub fn run_wizard() -> Result<Config> {
         proxy: crate::config::ProxyConfig::default(),
         identity: crate::config::IdentityConfig::default(),
         cost: crate::config::CostConfig::default(),
+        limits: crate::config::LimitsConfig::default(),
         peripherals: crate::config::PeripheralsConfig::default(),
         agents: std::collections::HashMap::new(),
+        workspaces: std::collections::HashMap::new(),
         hardware: hardware_config,
         query_classification: crate::config::QueryClassificationConfig::default(),
+        routing: crate::config::RoutingConfig::default(),
+        skills: crate::config::SkillsConfig::default(),
+        hooks: crate::config::HooksConfig::default(),
+        config_version: crate::config::migrate::CURRENT_CONFIG_VERSION,
+        keychain_secrets: Vec::new(),
     };
 
     println!(
@@ -271,10 +282,16 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
     MemoryConfig {
         backend: backend.to_string(),
         auto_save: profile.auto_save_default,
+        required: false,
+        infer_categories: false,
+        recall_scope: crate::config::RecallScope::default(),
+        namespace: crate::config::MemoryNamespace::default(),
         hygiene_enabled: profile.uses_sqlite_hygiene,
         archive_after_days: if profile.uses_sqlite_hygiene { 7 } else { 0 },
         purge_after_days: if profile.uses_sqlite_hygiene { 30 } else { 0 },
         conversation_retention_days: 30,
+        compact_after_days: 2,
+        compact_in_daemon: false,
         embedding_provider: "none".to_string(),
         embedding_model: "text-embedding-3-small".to_string(),
         embedding_dimensions: 1536,
@@ -342,6 +359,7 @@ pub fn run_quick_setup(
         autonomy: AutonomyConfig::default(),
         runtime: RuntimeConfig::default(),
         reliability: crate::config::ReliabilityConfig::default(),
+        provider: crate::config::ProviderConfig::default(),
         scheduler: crate::config::schema::SchedulerConfig::default(),
         agent: crate::config::schema::AgentConfig::default(),
         model_routes: Vec::new(),
@@ -360,10 +378,17 @@ pub fn run_quick_setup(
         proxy: crate::config::ProxyConfig::default(),
         identity: crate::config::IdentityConfig::default(),
         cost: crate::config::CostConfig::default(),
+        limits: crate::config::LimitsConfig::default(),
         peripherals: crate::config::PeripheralsConfig::default(),
         agents: std::collections::HashMap::new(),
+        workspaces: std::collections::HashMap::new(),
         hardware: crate::config::HardwareConfig::default(),
+        skills: crate::config::SkillsConfig::default(),
+        hooks: crate::config::HooksConfig::default(),
         query_classification: crate::config::QueryClassificationConfig::default(),
+        routing: crate::config::RoutingConfig::default(),
+        config_version: crate::config::migrate::CURRENT_CONFIG_VERSION,
+        keychain_secrets: Vec::new(),
     };
 
     config.save()?;
@@ -493,7 +518,7 @@ const MINIMAX_ONBOARD_MODELS: [(&str, &str); 5] = [
     ("MiniMax-M2", "MiniMax M2 (legacy)"),
 ];
 
-fn default_model_for_provider(provider: &str) -> String {
+pub(crate) fn default_model_for_provider(provider: &str) -> String {
     match canonical_provider_name(provider) {
         "anthropic" => "claude-sonnet-4-5-20250929".into(),
         "openrouter" => "anthropic/claude-sonnet-4.6".into(),
@@ -1014,6 +1039,13 @@ fn fetch_openai_compatible_models(
 }
 
 fn fetch_openrouter_models(api_key: Option<&str>) -> Result<Vec<String>> {
+    fetch_openrouter_models_with_pricing(api_key).map(|(models, _)| models)
+}
+
+/// OpenRouter's model list also carries per-token prices.
+fn fetch_openrouter_models_with_pricing(
+    api_key: Option<&str>,
+) -> Result<(Vec<String>, BTreeMap<String, ModelPricing>)> {
     let client = build_model_fetch_client()?;
     let mut request = client.get("https://openrouter.ai/api/v1/models");
     if let Some(api_key) = api_key {
@@ -1027,7 +1059,49 @@ fn fetch_openrouter_models(api_key: Option<&str>) -> Result<Vec<String>> {
         .json()
         .context("failed to parse OpenRouter model list response")?;
 
-    Ok(parse_openai_compatible_model_ids(&payload))
+    Ok((
+        parse_openai_compatible_model_ids(&payload),
+        parse_openrouter_pricing(&payload),
+    ))
+}
+
+/// Parse `data[].pricing.{prompt,completion}` (USD per token, as strings).
+fn parse_openrouter_pricing(payload: &Value) -> BTreeMap<String, ModelPricing> {
+    let Some(data) = payload.get("data").and_then(Value::as_array) else {
+        return BTreeMap::new();
+    };
+
+    let per_million = |pricing: &Value, field: &str| -> Option<f64> {
+        let raw = pricing.get(field)?;
+        let per_token = match raw {
+            Value::String(text) => text.trim().parse::<f64>().ok()?,
+            other => other.as_f64()?,
+        };
+        (per_token >= 0.0).then_some(per_token * 1_000_000.0)
+    };
+
+    let mut prices = BTreeMap::new();
+    for model in data {
+        let (Some(id), Some(pricing)) = (
+            model.get("id").and_then(Value::as_str),
+            model.get("pricing"),
+        ) else {
+            continue;
+        };
+        if let (Some(prompt), Some(completion)) = (
+            per_million(pricing, "prompt"),
+            per_million(pricing, "completion"),
+        ) {
+            prices.insert(
+                id.trim().to_string(),
+                ModelPricing {
+                    prompt_per_million: prompt,
+                    completion_per_million: completion,
+                },
+            );
+        }
+    }
+    prices
 }
 
 fn fetch_anthropic_models(api_key: Option<&str>) -> Result<Vec<String>> {
@@ -1155,11 +1229,20 @@ fn fetch_live_models_for_provider(provider_name: &str, api_key: &str) -> Result<
     Ok(models)
 }
 
+/// Model price in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct ModelPricing {
+    prompt_per_million: f64,
+    completion_per_million: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ModelCacheEntry {
     provider: String,
     fetched_at_unix: u64,
     models: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pricing: BTreeMap<String, ModelPricing>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -1170,6 +1253,7 @@ struct ModelCacheState {
 #[derive(Debug, Clone)]
 struct CachedModels {
     models: Vec<String>,
+    pricing: BTreeMap<String, ModelPricing>,
     age_secs: u64,
 }
 
@@ -1220,6 +1304,15 @@ fn cache_live_models_for_provider(
     workspace_dir: &Path,
     provider_name: &str,
     models: &[String],
+) -> Result<()> {
+    cache_live_models_with_pricing(workspace_dir, provider_name, models, BTreeMap::new())
+}
+
+fn cache_live_models_with_pricing(
+    workspace_dir: &Path,
+    provider_name: &str,
+    models: &[String],
+    pricing: BTreeMap<String, ModelPricing>,
 ) -> Result<()> {
     let normalized_models = normalize_model_ids(models.to_vec());
     if normalized_models.is_empty() {
@@ -1236,11 +1329,13 @@ fn cache_live_models_for_provider(
     {
         entry.fetched_at_unix = now;
         entry.models = normalized_models;
+        entry.pricing = pricing;
     } else {
         state.entries.push(ModelCacheEntry {
             provider: provider_name.to_string(),
             fetched_at_unix: now,
             models: normalized_models,
+            pricing,
         });
     }
 
@@ -1274,6 +1369,7 @@ fn load_cached_models_for_provider_internal(
 
     Ok(Some(CachedModels {
         models: entry.models,
+        pricing: entry.pricing,
         age_secs,
     }))
 }
@@ -1367,7 +1463,7 @@ pub fn run_models_refresh(
         }
     }
 
-    let api_key = config.api_key.clone().unwrap_or_default();
+    let api_key = model_list_api_key(config, &provider_name).unwrap_or_default();
 
     match fetch_live_models_for_provider(&provider_name, &api_key) {
         Ok(models) if !models.is_empty() => {
@@ -1413,6 +1509,125 @@ pub fn run_models_refresh(
     }
 }
 
+/// Fetch the full model list for `provider_name` (built-in or `[[provider.custom]]`).
+fn fetch_model_list(
+    config: &Config,
+    provider_name: &str,
+) -> Result<(Vec<String>, BTreeMap<String, ModelPricing>)> {
+    if let Some(custom) = crate::providers::custom::find(&config.provider.custom, provider_name) {
+        let base_url = crate::providers::custom::normalize_base_url(&custom.base_url)?;
+        let models = fetch_openai_compatible_models(
+            &format!("{base_url}/models"),
+            custom.api_key.as_deref(),
+            true,
+        )?;
+        return Ok((models, BTreeMap::new()));
+    }
+
+    if !supports_live_model_fetch(provider_name) {
+        anyhow::bail!("Provider '{provider_name}' does not support live model discovery yet");
+    }
+
+    let api_key = model_list_api_key(config, provider_name);
+    if canonical_provider_name(provider_name) == "openrouter" {
+        let api_key = api_key
+            .or_else(|| std::env::var("OPENROUTER_API_KEY").ok())
+            .filter(|key| !key.trim().is_empty());
+        return fetch_openrouter_models_with_pricing(api_key.as_deref());
+    }
+
+    // An empty key makes the fetch fall back to the provider's env var.
+    Ok((
+        fetch_live_models_for_provider(provider_name, api_key.as_deref().unwrap_or_default())?,
+        BTreeMap::new(),
+    ))
+}
+
+/// The configured key for `provider_name`: a `[[model_routes]]` key for that
+/// provider, or the top-level `api_key` when it is the default provider.
+/// Another provider's key is never sent.
+fn model_list_api_key(config: &Config, provider_name: &str) -> Option<String> {
+    let canonical = canonical_provider_name(provider_name);
+    let route_key = config
+        .model_routes
+        .iter()
+        .filter(|route| canonical_provider_name(&route.provider) == canonical)
+        .find_map(|route| route.api_key.clone());
+    let default_provider = config.default_provider.as_deref().unwrap_or("openrouter");
+    let default_key = (canonical_provider_name(default_provider) == canonical)
+        .then(|| config.api_key.clone())
+        .flatten();
+    [route_key, default_key]
+        .into_iter()
+        .flatten()
+        .find(|key| !key.trim().is_empty())
+}
+
+fn format_model_list(models: &[String], pricing: &BTreeMap<String, ModelPricing>) -> String {
+    use std::fmt::Write as _;
+
+    let width = models.iter().map(String::len).max().unwrap_or(0);
+    let mut out = String::new();
+    for model in models {
+        match pricing.get(model) {
+            Some(price) => {
+                let _ = writeln!(
+                    out,
+                    "{model:<width$}  ${:.2} in / ${:.2} out per 1M tokens",
+                    price.prompt_per_million, price.completion_per_million
+                );
+            }
+            None => {
+                let _ = writeln!(out, "{model}");
+            }
+        }
+    }
+    out
+}
+
+/// `zeroclaw models list`: print every model the provider offers, sorted.
+/// Results are cached for an hour; OpenRouter entries include pricing.
+pub fn run_models_list(config: &Config, provider_override: Option<&str>) -> Result<()> {
+    let provider_name = provider_override
+        .or(config.default_provider.as_deref())
+        .unwrap_or("openrouter")
+        .trim()
+        .to_string();
+
+    if provider_name.is_empty() {
+        anyhow::bail!("Provider name cannot be empty");
+    }
+
+    let wants_pricing = canonical_provider_name(&provider_name) == "openrouter";
+    let cached = load_cached_models_for_provider(
+        &config.workspace_dir,
+        &provider_name,
+        MODEL_LIST_CACHE_TTL_SECS,
+    )?
+    .filter(|cached| !wants_pricing || !cached.pricing.is_empty());
+
+    let (models, pricing) = if let Some(cached) = cached {
+        (cached.models, cached.pricing)
+    } else {
+        let (models, pricing) = fetch_model_list(config, &provider_name)
+            .with_context(|| format!("failed to list models for provider '{provider_name}'"))?;
+        let models = normalize_model_ids(models);
+        if models.is_empty() {
+            anyhow::bail!("Provider '{provider_name}' returned an empty model list");
+        }
+        cache_live_models_with_pricing(
+            &config.workspace_dir,
+            &provider_name,
+            &models,
+            pricing.clone(),
+        )?;
+        (models, pricing)
+    };
+
+    print!("{}", format_model_list(&models, &pricing));
+    Ok(())
+}
+
 // ── Step helpers ─────────────────────────────────────────────────
 
 fn print_step(current: u8, total: u8, title: &str) {
@@ -1987,7 +2202,7 @@ fn setup_provider(workspace_dir: &Path) -> Result<(String, String, String, Optio
 }
 
 /// Map provider name to its conventional env var
-fn provider_env_var(name: &str) -> &'static str {
+pub(crate) fn provider_env_var(name: &str) -> &'static str {
     match canonical_provider_name(name) {
         "openrouter" => "OPENROUTER_API_KEY",
         "anthropic" => "ANTHROPIC_API_KEY",
@@ -2460,6 +2675,9 @@ fn setup_channels() -> Result<ChannelsConfig> {
         lark: None,
         dingtalk: None,
         qq: None,
+        socket: None,
+        max_concurrent_sessions: 0,
+        session_overflow: SessionOverflow::Queue,
     };
 
     loop {
@@ -3813,6 +4031,9 @@ fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Result<()>
          - **file_write** — Write file contents\n\
            - Use when: applying focused edits, scaffolding files, or updating docs/code.\n\
            - Don't use when: unsure about side effects or when the file should remain user-owned.\n\
+         - **multi_file_write** — Write several files atomically\n\
+           - Use when: one change spans files that must stay consistent (all are written or none).\n\
+           - Don't use when: only one file changes.\n\
          - **memory_store** — Save to memory\n\
            - Use when: preserving durable preferences, decisions, or key context.\n\
            - Don't use when: info is transient, noisy, or sensitive without explicit need.\n\
@@ -4508,6 +4729,7 @@ mod tests {
             "shell",
             "file_read",
             "file_write",
+            "multi_file_write",
             "memory_store",
             "memory_recall",
             "memory_forget",
@@ -4646,6 +4868,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn model_list_key_belongs_to_the_named_provider() {
+        let mut config = Config {
+            default_provider: Some("anthropic".into()),
+            api_key: Some("sk-ant-default".into()),
+            ..Config::default()
+        };
+        config.model_routes.push(crate::config::ModelRouteConfig {
+            hint: "fast".into(),
+            provider: "grok".into(),
+            model: "grok-3-mini".into(),
+            api_key: Some("xai-route".into()),
+        });
+
+        assert_eq!(
+            model_list_api_key(&config, "anthropic").as_deref(),
+            Some("sk-ant-default")
+        );
+        assert_eq!(
+            model_list_api_key(&config, "xai").as_deref(),
+            Some("xai-route")
+        );
+        // The default provider's key is never sent to another provider.
+        assert_eq!(model_list_api_key(&config, "openrouter"), None);
+        assert_eq!(model_list_api_key(&config, "groq"), None);
+    }
+
     #[test]
     fn canonical_provider_name_normalizes_regional_aliases() {
         assert_eq!(canonical_provider_name("qwen-intl"), "qwen");
@@ -4950,6 +5199,7 @@ mod tests {
                 provider: "openai".to_string(),
                 fetched_at_unix: now_unix_secs().saturating_sub(MODEL_CACHE_TTL_SECS + 120),
                 models: vec!["gpt-5.1".to_string()],
+                pricing: BTreeMap::new(),
             }],
         };
 
@@ -4978,6 +5228,85 @@ mod tests {
         run_models_refresh(&config, None, false).unwrap();
     }
 
+    #[test]
+    fn parse_openrouter_pricing_converts_to_per_million() {
+        let payload = serde_json::json!({
+            "data": [
+                {"id": "anthropic/claude-sonnet-4-6", "pricing": {"prompt": "0.000003", "completion": "0.000015"}},
+                {"id": "free/model", "pricing": {"prompt": "0", "completion": "0"}},
+                {"id": "no-pricing/model"}
+            ]
+        });
+
+        let pricing = parse_openrouter_pricing(&payload);
+        assert_eq!(pricing.len(), 2);
+        let sonnet = pricing["anthropic/claude-sonnet-4-6"];
+        assert!((sonnet.prompt_per_million - 3.0).abs() < 1e-9);
+        assert!((sonnet.completion_per_million - 15.0).abs() < 1e-9);
+        assert!(pricing["free/model"].prompt_per_million.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn format_model_list_aligns_pricing_columns() {
+        let models = vec!["a/long-model".to_string(), "b/m".to_string()];
+        let mut pricing = BTreeMap::new();
+        pricing.insert(
+            "b/m".to_string(),
+            ModelPricing {
+                prompt_per_million: 0.5,
+                completion_per_million: 1.5,
+            },
+        );
+
+        let out = format_model_list(&models, &pricing);
+        assert_eq!(
+            out,
+            "a/long-model\nb/m           $0.50 in / $1.50 out per 1M tokens\n"
+        );
+    }
+
+    #[test]
+    fn run_models_list_uses_fresh_cache_without_network() {
+        let tmp = TempDir::new().unwrap();
+        cache_live_models_for_provider(tmp.path(), "openai", &["gpt-5.1".to_string()]).unwrap();
+
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            default_provider: Some("openai".to_string()),
+            ..Config::default()
+        };
+
+        run_models_list(&config, None).unwrap();
+    }
+
+    #[test]
+    fn model_cache_round_trips_pricing() {
+        let tmp = TempDir::new().unwrap();
+        cache_live_models_for_provider(tmp.path(), "openrouter", &["x/y".to_string()]).unwrap();
+
+        let cached =
+            load_cached_models_for_provider(tmp.path(), "openrouter", MODEL_LIST_CACHE_TTL_SECS)
+                .unwrap()
+                .unwrap();
+        assert!(cached.pricing.is_empty());
+
+        let mut pricing = BTreeMap::new();
+        pricing.insert(
+            "x/y".to_string(),
+            ModelPricing {
+                prompt_per_million: 1.0,
+                completion_per_million: 2.0,
+            },
+        );
+        cache_live_models_with_pricing(tmp.path(), "openrouter", &["x/y".to_string()], pricing)
+            .unwrap();
+        let cached =
+            load_cached_models_for_provider(tmp.path(), "openrouter", MODEL_LIST_CACHE_TTL_SECS)
+                .unwrap()
+                .unwrap();
+        assert_eq!(cached.pricing.len(), 1);
+    }
+
     #[test]
     fn run_models_refresh_rejects_unsupported_provider() {
         let tmp = TempDir::new().unwrap();
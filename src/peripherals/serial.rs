@@ -94,6 +94,7 @@ impl SerialTransport {
             success: ok,
             output: result,
             error,
+            artifacts: Vec::new(),
         })
     }
 
@@ -200,7 +201,7 @@ impl Tool for GpioReadTool {
     }
 
     fn description(&self) -> &str {
-        "Read the value (0 or 1) of a GPIO pin on a connected peripheral (e.g. STM32 Nucleo)"
+        "Read the value (0 or 1) of a GPIO pin on a connected peripheral (e.g. STM32 Nucleo). Use when: checking sensor/button state, LED status."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -239,7 +240,7 @@ impl Tool for GpioWriteTool {
     }
 
     fn description(&self) -> &str {
-        "Set a GPIO pin high (1) or low (0) on a connected peripheral (e.g. turn on/off LED)"
+        "Set a GPIO pin high (1) or low (0) on a connected peripheral. Use when: turning an LED on/off, controlling actuators."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -40,7 +40,7 @@ impl Tool for UnoQGpioReadTool {
     }
 
     fn description(&self) -> &str {
-        "Read GPIO pin value (0 or 1) on Arduino Uno Q. Requires zeroclaw-uno-q-bridge app running."
+        "Read GPIO pin value (0 or 1) on Arduino Uno Q. Requires zeroclaw-uno-q-bridge app running. Use when: checking sensor/button state, LED status."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -68,12 +68,14 @@ impl Tool for UnoQGpioReadTool {
                         success: false,
                         output: resp.clone(),
                         error: Some(resp),
+                        artifacts: Vec::new(),
                     })
                 } else {
                     Ok(ToolResult {
                         success: true,
                         output: resp,
                         error: None,
+                        artifacts: Vec::new(),
                     })
                 }
             }
@@ -81,6 +83,7 @@ impl Tool for UnoQGpioReadTool {
                 success: false,
                 output: format!("Bridge error: {}", e),
                 error: Some(e.to_string()),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -96,7 +99,7 @@ impl Tool for UnoQGpioWriteTool {
     }
 
     fn description(&self) -> &str {
-        "Set GPIO pin high (1) or low (0) on Arduino Uno Q. Requires zeroclaw-uno-q-bridge app running."
+        "Set GPIO pin high (1) or low (0) on Arduino Uno Q. Requires zeroclaw-uno-q-bridge app running. Use when: turning an LED on/off, controlling actuators."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -132,12 +135,14 @@ impl Tool for UnoQGpioWriteTool {
                         success: false,
                         output: resp.clone(),
                         error: Some(resp),
+                        artifacts: Vec::new(),
                     })
                 } else {
                     Ok(ToolResult {
                         success: true,
                         output: "done".into(),
                         error: None,
+                        artifacts: Vec::new(),
                     })
                 }
             }
@@ -145,6 +150,7 @@ impl Tool for UnoQGpioWriteTool {
                 success: false,
                 output: format!("Bridge error: {}", e),
                 error: Some(e.to_string()),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -0,0 +1,255 @@
+//! Named personas selected with `zeroclaw agent --persona <name>`.
+//!
+//! A persona is a TOML file named `<name>.toml` in `.zeroclaw/personas/`
+//! under the workspace, or in `~/.zeroclaw/personas/` for personas shared by
+//! every workspace; the workspace copy wins. For example:
+//!
+//! ```toml
+//! system_prompt_template = """
+//! You are {{assistant_name}}, a terse code reviewer working in {{workspace}}.
+//! Point out bugs and risky changes; skip praise and style nits.
+//! """
+//! tools = ["file_read", "shell"]
+//! temperature = 0.2
+//! ```
+//!
+//! The rendered template replaces `[agent] persona` in the system prompt,
+//! `tools` restricts the tool registry (empty allows every tool) and
+//! `temperature` replaces `--temperature`. The built-in `default` persona
+//! changes nothing.
+
+use crate::agent::batch::render_template;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Persona directory name, under the workspace's `.zeroclaw/` and under the config directory.
+const PERSONAS_DIR: &str = "personas";
+
+/// Name of the built-in persona that keeps the configured behaviour.
+pub const DEFAULT_PERSONA: &str = "default";
+
+fn default_persona_temperature() -> f64 {
+    0.7
+}
+
+/// One `<name>.toml` persona file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Persona {
+    /// Name the persona is selected by; defaults to the file name
+    #[serde(default)]
+    pub name: String,
+    /// System prompt text; `{{assistant_name}}` and `{{workspace}}` are filled in
+    #[serde(default)]
+    pub system_prompt_template: String,
+    /// Tools the agent may use; empty allows every tool
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Sampling temperature (default: 0.7, the `--temperature` default)
+    #[serde(default = "default_persona_temperature")]
+    pub temperature: f64,
+}
+
+impl Default for Persona {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PERSONA.into(),
+            system_prompt_template: String::new(),
+            tools: Vec::new(),
+            temperature: default_persona_temperature(),
+        }
+    }
+}
+
+impl Persona {
+    /// Render the system prompt template for `config`.
+    pub fn render_system_prompt(&self, config: &Config) -> Result<String> {
+        let mut fields = Map::new();
+        fields.insert(
+            "assistant_name".into(),
+            Value::String(config.agent.assistant_name().to_string()),
+        );
+        fields.insert(
+            "workspace".into(),
+            Value::String(config.workspace_dir.display().to_string()),
+        );
+        render_template(&self.system_prompt_template, &fields).with_context(|| {
+            format!(
+                "Persona '{}' has an invalid system_prompt_template",
+                self.name
+            )
+        })
+    }
+
+    /// Override the config fields this persona sets and return its temperature.
+    pub fn apply(&self, config: &mut Config) -> Result<f64> {
+        if !(0.0..=2.0).contains(&self.temperature) {
+            anyhow::bail!(
+                "Persona '{}' has temperature {}; use a value between 0.0 and 2.0",
+                self.name,
+                self.temperature
+            );
+        }
+        let prompt = self.render_system_prompt(config)?;
+        if !prompt.trim().is_empty() {
+            config.agent.persona = Some(prompt);
+        }
+        if !self.tools.is_empty() {
+            config.agent.tools = self.tools.clone();
+        }
+        Ok(self.temperature)
+    }
+}
+
+/// Persona directories in priority order: the workspace's, then the user's.
+pub fn persona_dirs(config: &Config) -> Vec<PathBuf> {
+    let mut dirs = vec![config.workspace_dir.join(".zeroclaw").join(PERSONAS_DIR)];
+    if let Some(config_dir) = config.config_path.parent() {
+        dirs.push(config_dir.join(PERSONAS_DIR));
+    }
+    dirs
+}
+
+fn parse_persona(path: &Path) -> Result<Persona> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut persona: Persona =
+        toml::from_str(&raw).with_context(|| format!("Invalid persona file {}", path.display()))?;
+    if persona.name.is_empty() {
+        if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+            persona.name = stem.to_string();
+        }
+    }
+    Ok(persona)
+}
+
+/// Every persona in `dir`, sorted by file name. Unreadable or invalid files
+/// are skipped with a warning.
+pub fn load_personas(dir: &Path) -> Vec<Persona> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("toml"))
+        .filter_map(|path| match parse_persona(&path) {
+            Ok(persona) => Some(persona),
+            Err(e) => {
+                tracing::warn!("Skipping persona {}: {e:#}", path.display());
+                None
+            }
+        })
+        .collect()
+}
+
+/// Find the persona called `name` in the first directory that has it.
+/// `default` is built in unless a directory defines its own.
+pub fn find_persona(dirs: &[PathBuf], name: &str) -> Result<Persona> {
+    for dir in dirs {
+        if let Some(persona) = load_personas(dir).into_iter().find(|p| p.name == name) {
+            return Ok(persona);
+        }
+    }
+    if name == DEFAULT_PERSONA {
+        return Ok(Persona::default());
+    }
+    let searched: Vec<String> = dirs.iter().map(|d| d.display().to_string()).collect();
+    anyhow::bail!(
+        "Persona '{name}' not found (looked in {})",
+        searched.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_persona(dir: &Path, file: &str, body: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join(format!("{file}.toml")), body).unwrap();
+    }
+
+    const REVIEWER: &str = r#"
+system_prompt_template = "You are {{assistant_name}}, a terse code reviewer."
+tools = ["file_read", "shell"]
+temperature = 0.2
+"#;
+
+    #[test]
+    fn load_personas_names_them_after_the_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_persona(tmp.path(), "reviewer", REVIEWER);
+        write_persona(
+            tmp.path(),
+            "teacher-v2",
+            "name = \"teacher\"\nsystem_prompt_template = \"Explain every step.\"\n",
+        );
+        write_persona(tmp.path(), "broken", "temperature = \"hot\"");
+        std::fs::write(tmp.path().join("notes.md"), "not a persona").unwrap();
+
+        let personas = load_personas(tmp.path());
+        let names: Vec<&str> = personas.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["reviewer", "teacher"]);
+        assert_eq!(personas[0].tools, ["file_read", "shell"]);
+        assert_eq!(personas[0].temperature, 0.2);
+        assert_eq!(personas[1].temperature, 0.7);
+        assert!(load_personas(&tmp.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn workspace_persona_shadows_the_shared_one() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().join("workspace");
+        let shared = tmp.path().join("shared");
+        write_persona(&workspace, "reviewer", REVIEWER);
+        write_persona(&shared, "reviewer", "temperature = 1.0");
+        write_persona(&shared, "teacher", "temperature = 0.9");
+        let dirs = [workspace, shared];
+
+        assert_eq!(find_persona(&dirs, "reviewer").unwrap().temperature, 0.2);
+        assert_eq!(find_persona(&dirs, "teacher").unwrap().temperature, 0.9);
+        let err = find_persona(&dirs, "pirate").unwrap_err();
+        assert!(err.to_string().contains("Persona 'pirate' not found"));
+    }
+
+    #[test]
+    fn default_persona_keeps_the_configured_behaviour() {
+        let tmp = tempfile::tempdir().unwrap();
+        let persona = find_persona(&[tmp.path().to_path_buf()], DEFAULT_PERSONA).unwrap();
+        assert_eq!(persona, Persona::default());
+
+        let mut config = Config::default();
+        config.agent.persona = Some("Be friendly.".into());
+        let before = config.clone();
+        assert_eq!(persona.apply(&mut config).unwrap(), 0.7);
+        assert_eq!(config.agent.persona, before.agent.persona);
+        assert_eq!(config.agent.tools, before.agent.tools);
+    }
+
+    #[test]
+    fn apply_overrides_prompt_tools_and_temperature() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_persona(tmp.path(), "reviewer", REVIEWER);
+        let persona = find_persona(&[tmp.path().to_path_buf()], "reviewer").unwrap();
+
+        let mut config = Config::default();
+        config.agent.name = Some("Claw".into());
+        assert_eq!(persona.apply(&mut config).unwrap(), 0.2);
+        assert_eq!(
+            config.agent.persona.as_deref(),
+            Some("You are Claw, a terse code reviewer.")
+        );
+        assert_eq!(config.agent.tools, ["file_read", "shell"]);
+
+        let hot = Persona {
+            temperature: 3.0,
+            ..Persona::default()
+        };
+        assert!(hot.apply(&mut config).is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Named prompt templates run with `zeroclaw agent --prompt <name>` or the
+//! interactive `/prompt <name>` command.
+//!
+//! A prompt is a Markdown file named `<name>.md` in `.zeroclaw/prompts/`
+//! under the workspace, or in `~/.zeroclaw/prompts/` for prompts shared by
+//! every workspace; the workspace copy wins. Optional YAML front matter
+//! declares its variables and overrides:
+//!
+//! ```markdown
+//! ---
+//! description: Daily pull request review
+//! required: [pr]
+//! defaults:
+//!   focus: correctness and error handling
+//! model: anthropic/claude-sonnet-4
+//! temperature: 0.2
+//! ---
+//! Review pull request #{{pr}}. Focus on {{focus}}.
+//! ```
+//!
+//! Values come from `--var name=value` (or `name=value` after `/prompt`),
+//! falling back to `defaults`. A missing required variable or a placeholder
+//! with no value is an error; nothing is left blank.
+
+use crate::agent::batch::render_template;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Prompt directory name, under the workspace's `.zeroclaw/` and under the config directory.
+const PROMPTS_DIR: &str = "prompts";
+
+/// YAML front matter of a prompt file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PromptFrontMatter {
+    description: Option<String>,
+    required: Vec<String>,
+    defaults: BTreeMap<String, String>,
+    model: Option<String>,
+    temperature: Option<f64>,
+}
+
+/// One `<name>.md` prompt template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptTemplate {
+    /// File name without the `.md` extension
+    pub name: String,
+    pub description: Option<String>,
+    /// Variables that must be given because they have no default
+    pub required: Vec<String>,
+    /// Values used for variables that are not given
+    pub defaults: BTreeMap<String, String>,
+    /// Model for the run; `--model` still wins
+    pub model: Option<String>,
+    /// Sampling temperature; replaces `--temperature`
+    pub temperature: Option<f64>,
+    /// Template text with `{{placeholders}}`
+    pub body: String,
+}
+
+impl PromptTemplate {
+    /// Parse a prompt file's content; `name` is its file name without `.md`.
+    pub fn parse(name: &str, content: &str) -> Result<Self> {
+        let (meta, body) = split_front_matter(content)?;
+        if let Some(t) = meta.temperature {
+            if !(0.0..=2.0).contains(&t) {
+                anyhow::bail!("temperature {t} is out of range; use a value between 0.0 and 2.0");
+            }
+        }
+        Ok(Self {
+            name: name.to_string(),
+            description: meta.description,
+            required: meta.required,
+            defaults: meta.defaults,
+            model: meta.model,
+            temperature: meta.temperature,
+            body: body.trim().to_string(),
+        })
+    }
+
+    /// Declared variables as `(name, default)`, required ones first.
+    pub fn variables(&self) -> Vec<(&str, Option<&str>)> {
+        let required = self
+            .required
+            .iter()
+            .filter(|name| !self.defaults.contains_key(*name))
+            .map(|name| (name.as_str(), None));
+        let defaults = self
+            .defaults
+            .iter()
+            .map(|(name, value)| (name.as_str(), Some(value.as_str())));
+        required.chain(defaults).collect()
+    }
+
+    /// Fill the template with `vars`, falling back to `defaults`.
+    pub fn render(&self, vars: &BTreeMap<String, String>) -> Result<String> {
+        let missing: Vec<&str> = self
+            .required
+            .iter()
+            .filter(|name| !vars.contains_key(*name) && !self.defaults.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "Prompt '{}' is missing required variable(s): {} (pass them as name=value)",
+                self.name,
+                missing.join(", ")
+            );
+        }
+        let mut fields = Map::new();
+        for (name, value) in self.defaults.iter().chain(vars) {
+            fields.insert(name.clone(), Value::String(value.clone()));
+        }
+        render_template(&self.body, &fields)
+            .with_context(|| format!("Prompt '{}' could not be rendered", self.name))
+    }
+}
+
+/// Split `---`-delimited YAML front matter off a prompt file. Files without
+/// front matter have no variables and use their whole content as the body.
+fn split_front_matter(content: &str) -> Result<(PromptFrontMatter, &str)> {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return Ok((PromptFrontMatter::default(), content));
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let yaml = &rest[..offset];
+            let meta = if yaml.trim().is_empty() {
+                PromptFrontMatter::default()
+            } else {
+                serde_norway::from_str(yaml)?
+            };
+            return Ok((meta, &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    anyhow::bail!("Unterminated front matter: missing closing `---`")
+}
+
+/// Parse `name=value` pairs from `--var` or `/prompt` arguments.
+pub fn parse_vars<S: AsRef<str>>(args: &[S]) -> Result<BTreeMap<String, String>> {
+    let mut vars = BTreeMap::new();
+    for arg in args {
+        let arg = arg.as_ref();
+        let Some((name, value)) = arg.split_once('=') else {
+            anyhow::bail!("Invalid variable '{arg}'; use name=value");
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Invalid variable '{arg}'; the name is empty");
+        }
+        vars.insert(name.to_string(), value.to_string());
+    }
+    Ok(vars)
+}
+
+/// Prompt directories in priority order: the workspace's, then the user's.
+pub fn prompt_dirs(config: &Config) -> Vec<PathBuf> {
+    let mut dirs = vec![config.workspace_dir.join(".zeroclaw").join(PROMPTS_DIR)];
+    if let Some(config_dir) = config.config_path.parent() {
+        dirs.push(config_dir.join(PROMPTS_DIR));
+    }
+    dirs
+}
+
+fn read_prompt(path: &Path) -> Result<PromptTemplate> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    PromptTemplate::parse(name, &raw)
+        .with_context(|| format!("Invalid prompt file {}", path.display()))
+}
+
+/// Every prompt in `dir`, sorted by name. Unreadable or invalid files are
+/// skipped with a warning.
+pub fn load_prompts(dir: &Path) -> Vec<PromptTemplate> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("md"))
+        .filter_map(|path| match read_prompt(&path) {
+            Ok(prompt) => Some(prompt),
+            Err(e) => {
+                tracing::warn!("Skipping prompt {}: {e:#}", path.display());
+                None
+            }
+        })
+        .collect()
+}
+
+/// Every prompt visible from `dirs`, sorted by name; a name found in an
+/// earlier directory hides the same name in later ones.
+pub fn list_prompts(dirs: &[PathBuf]) -> Vec<PromptTemplate> {
+    let mut prompts: BTreeMap<String, PromptTemplate> = BTreeMap::new();
+    for dir in dirs {
+        for prompt in load_prompts(dir) {
+            prompts.entry(prompt.name.clone()).or_insert(prompt);
+        }
+    }
+    prompts.into_values().collect()
+}
+
+/// Find the prompt called `name` in the first directory that has it.
+pub fn find_prompt(dirs: &[PathBuf], name: &str) -> Result<PromptTemplate> {
+    for dir in dirs {
+        let path = dir.join(format!("{name}.md"));
+        if path.is_file() {
+            return read_prompt(&path);
+        }
+    }
+    let searched: Vec<String> = dirs.iter().map(|d| d.display().to_string()).collect();
+    anyhow::bail!(
+        "Prompt '{name}' not found (looked in {})",
+        searched.join(", ")
+    )
+}
+
+pub fn handle_command(command: crate::PromptCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::PromptCommands::List => {
+            let dirs = prompt_dirs(config);
+            let prompts = list_prompts(&dirs);
+            if prompts.is_empty() {
+                let searched: Vec<String> = dirs.iter().map(|d| d.display().to_string()).collect();
+                println!("No prompts found (looked in {}).", searched.join(", "));
+                return Ok(());
+            }
+            for prompt in &prompts {
+                match &prompt.description {
+                    Some(description) => println!("{}: {description}", prompt.name),
+                    None => println!("{}", prompt.name),
+                }
+                for (name, default) in prompt.variables() {
+                    match default {
+                        Some(value) => println!("    {name} (default: {value})"),
+                        None => println!("    {name} (required)"),
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REVIEW: &str = "---
+description: Daily PR review
+required: [pr]
+defaults:
+  focus: correctness
+temperature: 0.2
+---
+Review PR #{{pr}} with a focus on {{focus}}.
+";
+
+    fn vars(pairs: &[&str]) -> BTreeMap<String, String> {
+        parse_vars(pairs).unwrap()
+    }
+
+    #[test]
+    fn render_substitutes_variables_and_defaults() {
+        let prompt = PromptTemplate::parse("review", REVIEW).unwrap();
+        assert_eq!(prompt.description.as_deref(), Some("Daily PR review"));
+        assert_eq!(prompt.temperature, Some(0.2));
+        assert_eq!(
+            prompt.variables(),
+            [("pr", None), ("focus", Some("correctness"))]
+        );
+
+        assert_eq!(
+            prompt.render(&vars(&["pr=123"])).unwrap(),
+            "Review PR #123 with a focus on correctness."
+        );
+        assert_eq!(
+            prompt
+                .render(&vars(&["pr=7", "focus=naming = clarity"]))
+                .unwrap(),
+            "Review PR #7 with a focus on naming = clarity."
+        );
+    }
+
+    #[test]
+    fn render_lists_missing_required_variables() {
+        let prompt = PromptTemplate::parse(
+            "triage",
+            "---\nrequired: [repo, issue]\n---\nTriage {{repo}}#{{issue}}.",
+        )
+        .unwrap();
+        let err = prompt.render(&BTreeMap::new()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Prompt 'triage' is missing required variable(s): repo, issue"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn unknown_placeholder_is_an_error() {
+        let prompt = PromptTemplate::parse("plain", "Summarize {{topic}}.").unwrap();
+        let err = prompt.render(&BTreeMap::new()).unwrap_err();
+        assert!(format!("{err:#}").contains("Template field 'topic' is missing"));
+        assert_eq!(
+            prompt.render(&vars(&["topic=rust"])).unwrap(),
+            "Summarize rust."
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_front_matter_and_vars() {
+        assert!(PromptTemplate::parse("x", "---\ntemperature: 3.0\n---\nhi").is_err());
+        assert!(PromptTemplate::parse("x", "---\nmodels: [a]\n---\nhi").is_err());
+        assert!(PromptTemplate::parse("x", "---\nrequired: [a]\nhi").is_err());
+        assert!(parse_vars(&["pr"]).is_err());
+        assert!(parse_vars(&["=1"]).is_err());
+    }
+
+    #[test]
+    fn workspace_prompt_shadows_the_shared_one() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().join("workspace");
+        let shared = tmp.path().join("shared");
+        std::fs::create_dir_all(&workspace).unwrap();
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(workspace.join("review.md"), REVIEW).unwrap();
+        std::fs::write(shared.join("review.md"), "Shared review").unwrap();
+        std::fs::write(shared.join("standup.md"), "Write my standup.").unwrap();
+        std::fs::write(shared.join("notes.txt"), "not a prompt").unwrap();
+        let dirs = [workspace, shared];
+
+        let names: Vec<String> = list_prompts(&dirs).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["review", "standup"]);
+        assert_eq!(find_prompt(&dirs, "review").unwrap().temperature, Some(0.2));
+        let err = find_prompt(&dirs, "deploy").unwrap_err();
+        assert!(err.to_string().contains("Prompt 'deploy' not found"));
+    }
+}
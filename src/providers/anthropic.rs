@@ -1,6 +1,6 @@
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ToolCall as ProviderToolCall,
+    FinishReason, Provider, ProviderCapabilities, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -83,8 +83,28 @@ enum NativeContentOut {
         #[serde(skip_serializing_if = "Option::is_none")]
         cache_control: Option<CacheControl>,
     },
+    #[serde(rename = "image")]
+    Image {
+        source: ImageSource,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct ImageSource {
+    #[serde(rename = "type")]
+    source_type: String,
+    media_type: String,
+    data: String,
 }
 
+/// Image types the Messages API accepts inline.
+const SUPPORTED_IMAGE_TYPES: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp"];
+
+/// Opening of an inline image marker (`[IMAGE:data:<mime>;base64,<data>]`).
+const IMAGE_MARKER: &str = "[IMAGE:data:";
+
 #[derive(Debug, Serialize)]
 struct NativeToolSpec {
     name: String,
@@ -128,6 +148,8 @@ struct SystemBlock {
 struct NativeChatResponse {
     #[serde(default)]
     content: Vec<NativeContentIn>,
+    #[serde(default)]
+    stop_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,6 +158,9 @@ struct NativeContentIn {
     kind: String,
     #[serde(default)]
     text: Option<String>,
+    /// Extended-thinking block content (`type: "thinking"`).
+    #[serde(default)]
+    thinking: Option<String>,
     #[serde(default)]
     id: Option<String>,
     #[serde(default)]
@@ -197,7 +222,8 @@ impl AnthropicProvider {
             if let Some(last_content) = last_msg.content.last_mut() {
                 match last_content {
                     NativeContentOut::Text { cache_control, .. }
-                    | NativeContentOut::ToolResult { cache_control, .. } => {
+                    | NativeContentOut::ToolResult { cache_control, .. }
+                    | NativeContentOut::Image { cache_control, .. } => {
                         *cache_control = Some(CacheControl::ephemeral());
                     }
                     NativeContentOut::ToolUse { .. } => {}
@@ -281,6 +307,55 @@ impl AnthropicProvider {
         })
     }
 
+    /// Content blocks for a user message, with every inline
+    /// `[IMAGE:data:<mime>;base64,<data>]` marker turned into an image block.
+    /// Image types the API doesn't accept are replaced by a short note.
+    fn user_content(text: &str) -> Vec<NativeContentOut> {
+        let mut blocks = Vec::new();
+        let mut pending = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find(IMAGE_MARKER) {
+            let body = &rest[start + IMAGE_MARKER.len()..];
+            let Some(end) = body.find(']') else {
+                break;
+            };
+            let Some((media_type, data)) = body[..end].split_once(";base64,") else {
+                pending.push_str(&rest[..start + IMAGE_MARKER.len()]);
+                rest = body;
+                continue;
+            };
+            pending.push_str(&rest[..start]);
+            if SUPPORTED_IMAGE_TYPES.contains(&media_type) {
+                if !pending.trim().is_empty() {
+                    blocks.push(NativeContentOut::Text {
+                        text: std::mem::take(&mut pending),
+                        cache_control: None,
+                    });
+                }
+                pending.clear();
+                blocks.push(NativeContentOut::Image {
+                    source: ImageSource {
+                        source_type: "base64".to_string(),
+                        media_type: media_type.to_string(),
+                        data: data.to_string(),
+                    },
+                    cache_control: None,
+                });
+            } else {
+                pending.push_str(&format!("[image omitted: {media_type} is not supported]"));
+            }
+            rest = &body[end + 1..];
+        }
+        pending.push_str(rest);
+        if !pending.trim().is_empty() || blocks.is_empty() {
+            blocks.push(NativeContentOut::Text {
+                text: pending,
+                cache_control: None,
+            });
+        }
+        blocks
+    }
+
     fn convert_messages(messages: &[ChatMessage]) -> (Option<SystemPrompt>, Vec<NativeMessage>) {
         let mut system_text = None;
         let mut native_messages = Vec::new();
@@ -324,10 +399,7 @@ impl AnthropicProvider {
                 _ => {
                     native_messages.push(NativeMessage {
                         role: "user".to_string(),
-                        content: vec![NativeContentOut::Text {
-                            text: msg.content.clone(),
-                            cache_control: None,
-                        }],
+                        content: Self::user_content(&msg.content),
                     });
                 }
             }
@@ -360,6 +432,7 @@ impl AnthropicProvider {
 
     fn parse_native_response(response: NativeChatResponse) -> ProviderChatResponse {
         let mut text_parts = Vec::new();
+        let mut thinking_parts = Vec::new();
         let mut tool_calls = Vec::new();
 
         for block in response.content {
@@ -371,6 +444,11 @@ impl AnthropicProvider {
                         }
                     }
                 }
+                "thinking" => {
+                    if let Some(thinking) = block.thinking.filter(|t| !t.trim().is_empty()) {
+                        thinking_parts.push(thinking.trim().to_string());
+                    }
+                }
                 "tool_use" => {
                     let name = block.name.unwrap_or_default();
                     if name.is_empty() {
@@ -396,6 +474,12 @@ impl AnthropicProvider {
                 Some(text_parts.join("\n"))
             },
             tool_calls,
+            reasoning: if thinking_parts.is_empty() {
+                None
+            } else {
+                Some(thinking_parts.join("\n\n"))
+            },
+            finish_reason: response.stop_reason.as_deref().map(FinishReason::parse),
         }
     }
 
@@ -414,9 +498,9 @@ impl Provider for AnthropicProvider {
         temperature: f64,
     ) -> anyhow::Result<String> {
         let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
-            )
+            super::ProviderError::AuthFailed {
+                message: "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token).".into(),
+            }
         })?;
 
         let request = ChatRequest {
@@ -441,6 +525,8 @@ impl Provider for AnthropicProvider {
 
         let response = request.send().await?;
 
+        super::rate_limit::observe("Anthropic", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("Anthropic", response).await);
         }
@@ -456,9 +542,9 @@ impl Provider for AnthropicProvider {
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
         let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
-            )
+            super::ProviderError::AuthFailed {
+                message: "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token).".into(),
+            }
         })?;
 
         let (system_prompt, mut messages) = Self::convert_messages(request.messages);
@@ -485,6 +571,8 @@ impl Provider for AnthropicProvider {
             .json(&native_request);
 
         let response = self.apply_auth(req, credential).send().await?;
+        super::rate_limit::observe("Anthropic", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("Anthropic", response).await);
         }
@@ -493,8 +581,12 @@ impl Provider for AnthropicProvider {
         Ok(Self::parse_native_response(native_response))
     }
 
-    fn supports_native_tools(&self) -> bool {
-        true
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            vision: true,
+            ..ProviderCapabilities::default()
+        }
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -1074,6 +1166,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn user_image_markers_become_image_blocks() {
+        let content = AnthropicProvider::user_content(
+            "[Tool artifacts]\n[IMAGE:data:image/png;base64,aGVsbG8=]\n[IMAGE:data:image/svg+xml;base64,PHN2Zy8+]",
+        );
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"type": "text", "text": "[Tool artifacts]\n"},
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "aGVsbG8="}},
+                {"type": "text", "text": "\n[image omitted: image/svg+xml is not supported]"}
+            ])
+        );
+
+        let plain = AnthropicProvider::user_content("See [IMAGE:/tmp/a.png]");
+        assert_eq!(
+            serde_json::to_value(&plain).unwrap(),
+            serde_json::json!([{"type": "text", "text": "See [IMAGE:/tmp/a.png]"}])
+        );
+    }
+
     #[test]
     fn backward_compatibility_native_chat_request() {
         // Test that requests without cache_control serialize identically to old format
@@ -1097,6 +1211,36 @@ mod tests {
         assert!(json.contains(r#""system":"System""#));
     }
 
+    #[test]
+    fn parse_native_response_separates_thinking_blocks() {
+        let response: NativeChatResponse = serde_json::from_str(
+            r#"{"content":[
+                {"type":"thinking","thinking":"The user wants the date.","signature":"sig"},
+                {"type":"text","text":"Let me check."},
+                {"type":"tool_use","id":"toolu_1","name":"shell","input":{"command":"date"}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let parsed = AnthropicProvider::parse_native_response(response);
+        assert_eq!(parsed.text.as_deref(), Some("Let me check."));
+        assert_eq!(
+            parsed.reasoning.as_deref(),
+            Some("The user wants the date.")
+        );
+        assert_eq!(parsed.tool_calls.len(), 1);
+    }
+
+    #[test]
+    fn parse_native_response_keeps_stop_reason() {
+        let response: NativeChatResponse =
+            serde_json::from_str(r#"{"content":[],"stop_reason":"max_tokens"}"#).unwrap();
+
+        let parsed = AnthropicProvider::parse_native_response(response);
+        assert!(parsed.text.is_none());
+        assert_eq!(parsed.finish_reason, Some(FinishReason::Length));
+    }
+
     #[tokio::test]
     async fn warmup_without_key_is_noop() {
         let provider = AnthropicProvider::new(None);
@@ -0,0 +1,299 @@
+//! Prompt auditing for provider calls (`[observability] audit_prompts`).
+//!
+//! [`AuditingProvider`] wraps the provider chain and writes every call's
+//! system prompt, last user message and response to a
+//! [`PromptAuditLog`]. A call whose record cannot be written fails, so no
+//! model output is used without being logged. Streamed calls are recorded
+//! when their final chunk arrives; by then the text has been shown, so a
+//! write failure there is only reported.
+
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, ProviderCapabilities, StreamChunk, StreamOptions,
+    StreamResult, ToolCall, ToolsPayload,
+};
+use super::Provider;
+use crate::observability::prompt_audit::PromptAuditLog;
+use crate::tools::ToolSpec;
+use anyhow::Context;
+use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
+use std::sync::Arc;
+
+/// Wraps a provider and records every call in the prompt audit log.
+pub struct AuditingProvider {
+    name: String,
+    inner: Box<dyn Provider>,
+    log: Arc<PromptAuditLog>,
+}
+
+impl AuditingProvider {
+    pub fn new(name: &str, inner: Box<dyn Provider>, log: Arc<PromptAuditLog>) -> Self {
+        Self {
+            name: name.to_string(),
+            inner,
+            log,
+        }
+    }
+
+    fn record(
+        &self,
+        model: &str,
+        system_prompt: Option<&str>,
+        user_message: &str,
+        response: &str,
+    ) -> anyhow::Result<()> {
+        self.log
+            .record(&self.name, model, system_prompt, user_message, response)
+            .context("Failed to write prompt audit log")
+    }
+
+    fn record_history(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        response: &str,
+    ) -> anyhow::Result<()> {
+        let (system_prompt, user_message) = prompt_parts(messages);
+        self.record(model, system_prompt, user_message, response)
+    }
+
+    fn audited_stream(
+        &self,
+        model: &str,
+        system_prompt: Option<&str>,
+        user_message: &str,
+        inner: stream::BoxStream<'static, StreamResult<StreamChunk>>,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let log = self.log.clone();
+        let provider = self.name.clone();
+        let model = model.to_string();
+        let system_prompt = system_prompt.map(str::to_string);
+        let user_message = user_message.to_string();
+        let mut text = String::new();
+        inner
+            .inspect(move |chunk| {
+                let Ok(chunk) = chunk else {
+                    return;
+                };
+                text.push_str(&chunk.delta);
+                if chunk.is_final {
+                    let response = with_tool_calls(&text, &chunk.tool_calls);
+                    if let Err(e) = log.record(
+                        &provider,
+                        &model,
+                        system_prompt.as_deref(),
+                        &user_message,
+                        &response,
+                    ) {
+                        tracing::error!("Failed to write prompt audit log: {e:#}");
+                    }
+                }
+            })
+            .boxed()
+    }
+}
+
+/// The first system message and the last user message.
+fn prompt_parts(messages: &[ChatMessage]) -> (Option<&str>, &str) {
+    let system_prompt = messages
+        .iter()
+        .find(|m| m.role == "system")
+        .map(|m| m.content.as_str());
+    let user_message = messages
+        .iter()
+        .rfind(|m| m.role == "user")
+        .map_or("", |m| m.content.as_str());
+    (system_prompt, user_message)
+}
+
+/// Response text followed by any tool calls as a JSON array.
+fn with_tool_calls(text: &str, tool_calls: &[ToolCall]) -> String {
+    if tool_calls.is_empty() {
+        return text.to_string();
+    }
+    let calls = serde_json::to_string(tool_calls).unwrap_or_default();
+    if text.is_empty() {
+        calls
+    } else {
+        format!("{text}\n{calls}")
+    }
+}
+
+fn response_text(response: &ChatResponse) -> String {
+    with_tool_calls(
+        response.text.as_deref().unwrap_or_default(),
+        &response.tool_calls,
+    )
+}
+
+#[async_trait]
+impl Provider for AuditingProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let text = self
+            .inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await?;
+        self.record(model, system_prompt, message, &text)?;
+        Ok(text)
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let text = self
+            .inner
+            .chat_with_history(messages, model, temperature)
+            .await?;
+        self.record_history(model, messages, &text)?;
+        Ok(text)
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let messages = request.messages;
+        let response = self.inner.chat(request, model, temperature).await?;
+        self.record_history(model, messages, &response_text(&response))?;
+        Ok(response)
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let response = self
+            .inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await?;
+        self.record_history(model, messages, &response_text(&response))?;
+        Ok(response)
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let inner =
+            self.inner
+                .stream_chat_with_system(system_prompt, message, model, temperature, options);
+        self.audited_stream(model, system_prompt, message, inner)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let inner = self
+            .inner
+            .stream_chat_with_history(messages, model, temperature, options);
+        let (system_prompt, user_message) = prompt_parts(messages);
+        self.audited_stream(model, system_prompt, user_message, inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::prompt_audit::{audit_file, verify_line, PromptAuditRecord};
+    use tempfile::TempDir;
+
+    struct EchoProvider;
+
+    #[async_trait]
+    impl Provider for EchoProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(format!("echo: {message}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn every_call_is_recorded_with_its_prompt() {
+        let tmp = TempDir::new().unwrap();
+        let log = Arc::new(PromptAuditLog::new(tmp.path().to_path_buf(), "k").unwrap());
+        let provider = AuditingProvider::new("mock", Box::new(EchoProvider), log.clone());
+
+        let messages = [
+            ChatMessage::system("sys"),
+            ChatMessage::user("first"),
+            ChatMessage::assistant("echo: first"),
+            ChatMessage::user("second"),
+        ];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: None,
+        };
+        let response = provider.chat(request, "m", 0.0).await.unwrap();
+        assert_eq!(response.text.as_deref(), Some("echo: second"));
+        provider.simple_chat("hi", "m", 0.0).await.unwrap();
+
+        let path = audit_file(tmp.path(), chrono::Utc::now().date_naive());
+        let content = std::fs::read_to_string(path).unwrap();
+        let records: Vec<PromptAuditRecord> = content
+            .lines()
+            .map(|line| {
+                assert!(verify_line(line, "k").unwrap());
+                serde_json::from_str(line).unwrap()
+            })
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].system_prompt.as_deref(), Some("sys"));
+        assert_eq!(records[0].user_message, "second");
+        assert_eq!(records[0].assistant_response, "echo: second");
+        assert_eq!(records[0].provider, "mock");
+        assert_eq!(records[1].system_prompt, None);
+        assert_eq!(records[1].user_message, "hi");
+        assert!(records.iter().all(|r| r.session_id == log.session_id()));
+    }
+}
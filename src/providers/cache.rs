@@ -0,0 +1,397 @@
+//! Response cache for identical provider requests (`[provider.cache]`).
+//!
+//! Loops and batch runs can send the exact same request more than once. When
+//! enabled, [`CachingProvider`] keys each non-streaming call by a SHA-256 of
+//! the provider, model, temperature, messages and tool definitions, and
+//! answers repeats from memory until the entry's TTL runs out. Errors are
+//! never cached, streaming calls always reach the provider, and `--no-cache`
+//! turns the cache off for a run.
+
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, ProviderCapabilities, StreamChunk, StreamOptions,
+    StreamResult, ToolsPayload,
+};
+use super::Provider;
+use crate::config::ResponseCacheConfig;
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+use futures_util::stream;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+enum Cached {
+    Text(String),
+    Chat(ChatResponse),
+}
+
+struct Entry {
+    stored_at: Instant,
+    value: Cached,
+}
+
+/// Entries by key, plus insertion order for evicting the oldest.
+#[derive(Default)]
+struct Store {
+    entries: HashMap<String, Entry>,
+    order: VecDeque<String>,
+}
+
+/// Wraps a provider and answers repeated identical requests from memory.
+pub struct CachingProvider {
+    name: String,
+    inner: Box<dyn Provider>,
+    ttl: Duration,
+    max_entries: usize,
+    store: Mutex<Store>,
+}
+
+impl CachingProvider {
+    pub fn new(name: &str, inner: Box<dyn Provider>, config: &ResponseCacheConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            inner,
+            ttl: Duration::from_secs(config.ttl_secs),
+            max_entries: config.max_entries.max(1),
+            store: Mutex::new(Store::default()),
+        }
+    }
+
+    fn key(
+        &self,
+        kind: &str,
+        model: &str,
+        temperature: f64,
+        messages: &[ChatMessage],
+        tools: &str,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        for part in [kind, self.name.as_str(), model] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update(temperature.to_bits().to_le_bytes());
+        for message in messages {
+            hasher.update(message.role.as_bytes());
+            hasher.update([0]);
+            hasher.update(message.content.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update(tools.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    fn get(&self, key: &str) -> Option<Cached> {
+        let mut store = self.store.lock();
+        let fresh = store.entries.get(key)?.stored_at.elapsed() < self.ttl;
+        if fresh {
+            tracing::debug!(provider = self.name, "Response cache hit");
+            return store.entries.get(key).map(|entry| entry.value.clone());
+        }
+        store.entries.remove(key);
+        store.order.retain(|k| k != key);
+        None
+    }
+
+    fn put(&self, key: String, value: Cached) {
+        let mut store = self.store.lock();
+        if store.entries.contains_key(&key) {
+            store.order.retain(|k| k != &key);
+        }
+        while store.entries.len() >= self.max_entries {
+            let Some(oldest) = store.order.pop_front() else {
+                break;
+            };
+            store.entries.remove(&oldest);
+        }
+        store.order.push_back(key.clone());
+        store.entries.insert(
+            key,
+            Entry {
+                stored_at: Instant::now(),
+                value,
+            },
+        );
+    }
+
+    async fn cached<F>(&self, key: String, call: F) -> anyhow::Result<Cached>
+    where
+        F: Future<Output = anyhow::Result<Cached>>,
+    {
+        if let Some(hit) = self.get(&key) {
+            return Ok(hit);
+        }
+        let value = call.await?;
+        self.put(key, value.clone());
+        Ok(value)
+    }
+}
+
+fn tool_specs_fingerprint(tools: Option<&[ToolSpec]>) -> String {
+    tools
+        .unwrap_or_default()
+        .iter()
+        .map(|tool| format!("{}\0{}\0{}\0", tool.name, tool.description, tool.parameters))
+        .collect()
+}
+
+fn into_text(value: Cached) -> String {
+    match value {
+        Cached::Text(text) => text,
+        Cached::Chat(response) => response.text.unwrap_or_default(),
+    }
+}
+
+fn into_chat(value: Cached) -> ChatResponse {
+    match value {
+        Cached::Chat(response) => response,
+        Cached::Text(text) => ChatResponse {
+            text: Some(text),
+            tool_calls: Vec::new(),
+            reasoning: None,
+            finish_reason: None,
+        },
+    }
+}
+
+#[async_trait]
+impl Provider for CachingProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut messages = Vec::with_capacity(2);
+        if let Some(system_prompt) = system_prompt {
+            messages.push(ChatMessage::system(system_prompt));
+        }
+        messages.push(ChatMessage::user(message));
+        let key = self.key("system", model, temperature, &messages, "");
+        let value = self
+            .cached(key, async {
+                self.inner
+                    .chat_with_system(system_prompt, message, model, temperature)
+                    .await
+                    .map(Cached::Text)
+            })
+            .await?;
+        Ok(into_text(value))
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let key = self.key("history", model, temperature, messages, "");
+        let value = self
+            .cached(key, async {
+                self.inner
+                    .chat_with_history(messages, model, temperature)
+                    .await
+                    .map(Cached::Text)
+            })
+            .await?;
+        Ok(into_text(value))
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let tools = tool_specs_fingerprint(request.tools);
+        let key = self.key("chat", model, temperature, request.messages, &tools);
+        let value = self
+            .cached(key, async {
+                self.inner
+                    .chat(request, model, temperature)
+                    .await
+                    .map(Cached::Chat)
+            })
+            .await?;
+        Ok(into_chat(value))
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let tools_json = serde_json::Value::Array(tools.to_vec()).to_string();
+        let key = self.key("tools", model, temperature, messages, &tools_json);
+        let value = self
+            .cached(key, async {
+                self.inner
+                    .chat_with_tools(messages, tools, model, temperature)
+                    .await
+                    .map(Cached::Chat)
+            })
+            .await?;
+        Ok(into_chat(value))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_system(system_prompt, message, model, temperature, options)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_history(messages, model, temperature, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            if message == "fail" {
+                anyhow::bail!("500 upstream error");
+            }
+            Ok(format!("answer {n}"))
+        }
+    }
+
+    fn cached_provider(config: &ResponseCacheConfig) -> (CachingProvider, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let inner = CountingProvider {
+            calls: calls.clone(),
+        };
+        (CachingProvider::new("mock", Box::new(inner), config), calls)
+    }
+
+    fn config() -> ResponseCacheConfig {
+        ResponseCacheConfig {
+            enabled: true,
+            ..ResponseCacheConfig::default()
+        }
+    }
+
+    fn request(messages: &[ChatMessage]) -> ChatRequest<'_> {
+        ChatRequest {
+            messages,
+            tools: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn identical_request_is_served_from_cache() {
+        let (provider, calls) = cached_provider(&config());
+        let messages = [ChatMessage::system("sys"), ChatMessage::user("hi")];
+
+        let first = provider.chat(request(&messages), "m", 0.7).await.unwrap();
+        let second = provider.chat(request(&messages), "m", 0.7).await.unwrap();
+        assert_eq!(first.text, second.text);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn changed_temperature_or_model_misses() {
+        let (provider, calls) = cached_provider(&config());
+        let messages = [ChatMessage::user("hi")];
+
+        provider.chat(request(&messages), "m", 0.7).await.unwrap();
+        let other = provider.chat(request(&messages), "m", 0.2).await.unwrap();
+        provider
+            .chat(request(&messages), "other", 0.7)
+            .await
+            .unwrap();
+        assert_eq!(other.text.as_deref(), Some("answer 1"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached() {
+        let (provider, calls) = cached_provider(&config());
+        assert!(provider.simple_chat("fail", "m", 0.0).await.is_err());
+        assert!(provider.simple_chat("fail", "m", 0.0).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_refetched() {
+        let (provider, calls) = cached_provider(&ResponseCacheConfig {
+            ttl_secs: 0,
+            ..config()
+        });
+        provider.simple_chat("hi", "m", 0.0).await.unwrap();
+        provider.simple_chat("hi", "m", 0.0).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn oldest_entry_is_evicted_at_capacity() {
+        let (provider, calls) = cached_provider(&ResponseCacheConfig {
+            max_entries: 2,
+            ..config()
+        });
+        for message in ["a", "b", "c", "b", "a"] {
+            provider.simple_chat(message, "m", 0.0).await.unwrap();
+        }
+        // "a" was evicted by "c"; "b" stayed cached.
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
@@ -0,0 +1,282 @@
+//! Per-provider circuit breaker for the reliable wrapper.
+//!
+//! After `failure_threshold` consecutive failures within `window`, the
+//! provider's circuit opens: calls to it fail fast for `cooldown`, so the
+//! fallback chain is used straight away instead of paying the full retry
+//! budget. Once the cooldown has passed the circuit is half-open and the
+//! next call is a probe — success closes it, failure opens it again.
+//! Transitions are queued so the agent loop can report them as
+//! `ObserverEvent::CircuitBreaker`.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// State of one provider's circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through normally.
+    Closed,
+    /// Calls fail fast until the cooldown ends.
+    Open,
+    /// The cooldown ended; the next call tests recovery.
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// A provider's circuit changed state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitTransition {
+    pub provider: String,
+    pub state: CircuitState,
+}
+
+static TRANSITIONS: Mutex<Vec<CircuitTransition>> = Mutex::new(Vec::new());
+
+/// Drain the transitions recorded since the last call.
+pub fn take_transitions() -> Vec<CircuitTransition> {
+    std::mem::take(&mut *TRANSITIONS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Thresholds from `[reliability]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerSettings {
+    /// Consecutive failures that open the circuit; 0 disables the breaker.
+    pub failure_threshold: u32,
+    /// Failures further apart than this start a new count.
+    pub window: Duration,
+    /// How long an open circuit fails fast before a probe is allowed.
+    pub cooldown: Duration,
+}
+
+impl CircuitBreakerSettings {
+    pub fn from_config(config: &crate::config::ReliabilityConfig) -> Self {
+        Self {
+            failure_threshold: config.circuit_breaker_threshold,
+            window: Duration::from_secs(config.circuit_breaker_window_secs),
+            cooldown: Duration::from_secs(config.circuit_breaker_cooldown_secs),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Circuit {
+    state: CircuitState,
+    failures: u32,
+    first_failure: Option<Instant>,
+    opened_at: Option<Instant>,
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            failures: 0,
+            first_failure: None,
+            opened_at: None,
+        }
+    }
+}
+
+/// Circuits for every provider in a reliable chain, keyed by provider name.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    settings: CircuitBreakerSettings,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(settings: CircuitBreakerSettings) -> Self {
+        Self {
+            settings,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a call to `provider` may go ahead. An open circuit whose
+    /// cooldown has passed becomes half-open and lets the call through.
+    pub fn allow(&self, provider: &str) -> bool {
+        self.allow_at(provider, Instant::now())
+    }
+
+    pub fn record_success(&self, provider: &str) {
+        let mut circuits = self.lock();
+        let Some(circuit) = circuits.get_mut(provider) else {
+            return;
+        };
+        let was_closed = circuit.state == CircuitState::Closed;
+        *circuit = Circuit::default();
+        if !was_closed {
+            transition(provider, CircuitState::Closed);
+        }
+    }
+
+    pub fn record_failure(&self, provider: &str) {
+        self.record_failure_at(provider, Instant::now());
+    }
+
+    pub fn state(&self, provider: &str) -> CircuitState {
+        self.lock()
+            .get(provider)
+            .map_or(CircuitState::Closed, |circuit| circuit.state)
+    }
+
+    fn allow_at(&self, provider: &str, now: Instant) -> bool {
+        let mut circuits = self.lock();
+        let Some(circuit) = circuits.get_mut(provider) else {
+            return true;
+        };
+        match circuit.state {
+            CircuitState::Closed | CircuitState::HalfOpen => true,
+            CircuitState::Open => {
+                let cooled_down = circuit
+                    .opened_at
+                    .is_none_or(|opened| now.duration_since(opened) >= self.settings.cooldown);
+                if cooled_down {
+                    circuit.state = CircuitState::HalfOpen;
+                    transition(provider, CircuitState::HalfOpen);
+                }
+                cooled_down
+            }
+        }
+    }
+
+    fn record_failure_at(&self, provider: &str, now: Instant) {
+        if self.settings.failure_threshold == 0 {
+            return;
+        }
+        let mut circuits = self.lock();
+        let circuit = circuits.entry(provider.to_string()).or_default();
+        match circuit.state {
+            CircuitState::Open => {}
+            CircuitState::HalfOpen => {
+                circuit.state = CircuitState::Open;
+                circuit.opened_at = Some(now);
+                transition(provider, CircuitState::Open);
+            }
+            CircuitState::Closed => {
+                let in_window = circuit
+                    .first_failure
+                    .is_some_and(|first| now.duration_since(first) <= self.settings.window);
+                if in_window {
+                    circuit.failures += 1;
+                } else {
+                    circuit.failures = 1;
+                    circuit.first_failure = Some(now);
+                }
+                if circuit.failures >= self.settings.failure_threshold {
+                    circuit.state = CircuitState::Open;
+                    circuit.opened_at = Some(now);
+                    tracing::warn!(
+                        provider,
+                        failures = circuit.failures,
+                        cooldown_secs = self.settings.cooldown.as_secs(),
+                        "Circuit opened, failing fast until the cooldown ends"
+                    );
+                    transition(provider, CircuitState::Open);
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Circuit>> {
+        self.circuits.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn transition(provider: &str, state: CircuitState) {
+    TRANSITIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(CircuitTransition {
+            provider: provider.to_string(),
+            state,
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(threshold: u32) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerSettings {
+            failure_threshold: threshold,
+            window: Duration::from_secs(60),
+            cooldown: Duration::from_secs(30),
+        })
+    }
+
+    #[test]
+    fn consecutive_failures_open_the_circuit() {
+        let breaker = breaker(3);
+        let now = Instant::now();
+
+        breaker.record_failure_at("p", now);
+        breaker.record_failure_at("p", now);
+        assert_eq!(breaker.state("p"), CircuitState::Closed);
+        breaker.record_failure_at("p", now);
+
+        assert_eq!(breaker.state("p"), CircuitState::Open);
+        assert!(!breaker.allow_at("p", now + Duration::from_secs(10)));
+        assert!(breaker.allow_at("other", now));
+    }
+
+    #[test]
+    fn failures_outside_the_window_start_a_new_count() {
+        let breaker = breaker(2);
+        let now = Instant::now();
+
+        breaker.record_failure_at("p", now);
+        breaker.record_failure_at("p", now + Duration::from_secs(61));
+
+        assert_eq!(breaker.state("p"), CircuitState::Closed);
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let breaker = breaker(2);
+        let now = Instant::now();
+
+        breaker.record_failure_at("p", now);
+        breaker.record_success("p");
+        breaker.record_failure_at("p", now);
+
+        assert_eq!(breaker.state("p"), CircuitState::Closed);
+    }
+
+    #[test]
+    fn half_open_probe_closes_or_reopens_the_circuit() {
+        let breaker = breaker(1);
+        let now = Instant::now();
+        breaker.record_failure_at("p", now);
+
+        let after_cooldown = now + Duration::from_secs(30);
+        assert!(breaker.allow_at("p", after_cooldown));
+        assert_eq!(breaker.state("p"), CircuitState::HalfOpen);
+        breaker.record_failure_at("p", after_cooldown);
+        assert_eq!(breaker.state("p"), CircuitState::Open);
+        assert!(!breaker.allow_at("p", after_cooldown + Duration::from_secs(1)));
+
+        assert!(breaker.allow_at("p", after_cooldown + Duration::from_secs(30)));
+        breaker.record_success("p");
+        assert_eq!(breaker.state("p"), CircuitState::Closed);
+    }
+
+    #[test]
+    fn zero_threshold_disables_the_breaker() {
+        let breaker = breaker(0);
+        for _ in 0..10 {
+            breaker.record_failure("p");
+        }
+        assert!(breaker.allow("p"));
+    }
+}
@@ -2,9 +2,11 @@
 //! Most LLM APIs follow the same `/v1/chat/completions` format.
 //! This module provides a single implementation that works for all of them.
 
+use crate::providers::tool_stream::{ToolCallAccumulator, ToolCallDelta};
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, StreamChunk, StreamError, StreamOptions, StreamResult, ToolCall as ProviderToolCall,
+    FinishReason, Provider, StreamChunk, StreamError, StreamOptions, StreamResult,
+    ToolCall as ProviderToolCall,
 };
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
@@ -26,6 +28,12 @@ pub struct OpenAiCompatibleProvider {
     /// GLM/Zhipu does not support the responses API.
     supports_responses_fallback: bool,
     user_agent: Option<String>,
+    /// Template for the auth header value; `{key}` is replaced with the credential.
+    auth_value_template: Option<String>,
+    /// Static headers attached to every request.
+    extra_headers: Vec<(String, String)>,
+    /// `reasoning_effort` sent with chat requests for reasoning models.
+    reasoning_effort: Option<String>,
 }
 
 /// How the provider expects the API key to be sent.
@@ -96,9 +104,61 @@ impl OpenAiCompatibleProvider {
             auth_header: auth_style,
             supports_responses_fallback,
             user_agent: user_agent.map(ToString::to_string),
+            auth_value_template: None,
+            extra_headers: Vec::new(),
+            reasoning_effort: None,
         }
     }
 
+    /// Override how the credential is sent and attach extra static headers.
+    ///
+    /// `auth_header` replaces the provider's default header name and
+    /// `auth_value_template` controls the header value (`{key}` is substituted
+    /// with the credential, e.g. `"Bearer {key}"` or `"{key}"`).
+    pub fn with_header_overrides(
+        mut self,
+        overrides: &crate::config::ProviderHeaderOverrides,
+    ) -> Self {
+        if let Some(header) = overrides
+            .auth_header
+            .as_deref()
+            .map(str::trim)
+            .filter(|h| !h.is_empty())
+        {
+            self.auth_header = AuthStyle::Custom(header.to_string());
+        }
+        self.auth_value_template = overrides.auth_value_template.clone();
+        let mut headers: Vec<(String, String)> = overrides
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        headers.sort();
+        self.extra_headers = headers;
+        self
+    }
+
+    /// Ask reasoning models for a given effort (`low`, `medium`, `high`) on
+    /// every chat request. `None` leaves the parameter out.
+    pub fn with_reasoning_effort(mut self, effort: Option<&str>) -> Self {
+        self.reasoning_effort = effort
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map(ToString::to_string);
+        self
+    }
+
+    fn credential(&self) -> Result<&str, super::ProviderError> {
+        self.credential
+            .as_deref()
+            .ok_or_else(|| super::ProviderError::AuthFailed {
+                message: format!(
+                    "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
+                    self.name
+                ),
+            })
+    }
+
     fn http_client(&self) -> Client {
         if let Some(ua) = self.user_agent.as_deref() {
             let mut headers = HeaderMap::new();
@@ -212,6 +272,8 @@ struct ApiChatRequest {
     tools: Option<Vec<serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -228,6 +290,8 @@ struct ApiChatResponse {
 #[derive(Debug, Deserialize)]
 struct Choice {
     message: ResponseMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -259,6 +323,15 @@ impl ResponseMessage {
             _ => self.reasoning_content.clone().filter(|c| !c.is_empty()),
         }
     }
+
+    /// `reasoning_content` as separate reasoning, unless it already stands in
+    /// for the answer because `content` was empty.
+    fn separate_reasoning(&self) -> Option<String> {
+        match &self.content {
+            Some(c) if !c.is_empty() => self.reasoning_content.clone().filter(|r| !r.is_empty()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -287,6 +360,8 @@ struct NativeChatRequest {
     tools: Option<Vec<serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -360,11 +435,32 @@ struct StreamDelta {
     /// Reasoning/thinking models may stream output via `reasoning_content`.
     #[serde(default)]
     reasoning_content: Option<String>,
+    /// Fragments of native tool calls, keyed by `index`.
+    #[serde(default)]
+    tool_calls: Vec<ToolCallDelta>,
 }
 
 /// Parse SSE (Server-Sent Events) stream from OpenAI-compatible providers.
 /// Handles the `data: {...}` format and `[DONE]` sentinel.
 fn parse_sse_line(line: &str) -> StreamResult<Option<String>> {
+    Ok(parse_sse_data(line)?.as_ref().and_then(delta_text))
+}
+
+/// Text carried by a stream chunk: `content`, else `reasoning_content`.
+fn delta_text(chunk: &StreamChunkResponse) -> Option<String> {
+    let choice = chunk.choices.first()?;
+    if let Some(content) = &choice.delta.content {
+        if !content.is_empty() {
+            return Some(content.clone());
+        }
+    }
+    // Fallback to reasoning_content for thinking models
+    choice.delta.reasoning_content.clone()
+}
+
+/// Decode one SSE line into a stream chunk; `None` for blank lines, comments
+/// and the `[DONE]` sentinel.
+fn parse_sse_data(line: &str) -> StreamResult<Option<StreamChunkResponse>> {
     let line = line.trim();
 
     // Skip empty lines and comments
@@ -383,19 +479,7 @@ fn parse_sse_line(line: &str) -> StreamResult<Option<String>> {
 
         // Parse JSON delta
         let chunk: StreamChunkResponse = serde_json::from_str(data).map_err(StreamError::Json)?;
-
-        // Extract content from delta
-        if let Some(choice) = chunk.choices.first() {
-            if let Some(content) = &choice.delta.content {
-                if !content.is_empty() {
-                    return Ok(Some(content.clone()));
-                }
-            }
-            // Fallback to reasoning_content for thinking models
-            if let Some(reasoning) = &choice.delta.reasoning_content {
-                return Ok(Some(reasoning.clone()));
-            }
-        }
+        return Ok(Some(chunk));
     }
 
     Ok(None)
@@ -412,6 +496,7 @@ fn sse_bytes_to_chunks(
     tokio::spawn(async move {
         // Buffer for incomplete lines
         let mut buffer = String::new();
+        let mut tool_calls = ToolCallAccumulator::default();
 
         // Get response body as bytes stream
         match response.error_for_status_ref() {
@@ -448,8 +533,16 @@ fn sse_bytes_to_chunks(
                         let line = buffer.drain(..=pos).collect::<String>();
                         buffer = buffer[pos + 1..].to_string();
 
-                        match parse_sse_line(&line) {
-                            Ok(Some(content)) => {
+                        match parse_sse_data(&line) {
+                            Ok(Some(parsed)) => {
+                                if let Some(choice) = parsed.choices.first() {
+                                    for delta in &choice.delta.tool_calls {
+                                        tool_calls.push(delta);
+                                    }
+                                }
+                                let Some(content) = delta_text(&parsed) else {
+                                    continue;
+                                };
                                 let mut chunk = StreamChunk::delta(content);
                                 if count_tokens {
                                     chunk = chunk.with_token_estimate();
@@ -473,8 +566,12 @@ fn sse_bytes_to_chunks(
             }
         }
 
-        // Send final chunk
-        let _ = tx.send(Ok(StreamChunk::final_chunk())).await;
+        // Send final chunk, with any streamed tool calls reassembled
+        let final_chunk = match tool_calls.finish() {
+            Ok(calls) => Ok(StreamChunk::final_chunk().with_tool_calls(calls)),
+            Err(e) => Err(StreamError::InvalidSse(e)),
+        };
+        let _ = tx.send(final_chunk).await;
     });
 
     // Convert channel receiver to stream
@@ -549,17 +646,52 @@ fn parse_responses_response_body(
     })
 }
 
+/// Resolve the auth header name and value for a credential.
+///
+/// Without a template, `Bearer` keeps the `Bearer ` prefix and the other styles
+/// send the raw credential.
+fn auth_header_pair(
+    style: &AuthStyle,
+    template: Option<&str>,
+    credential: &str,
+) -> (String, String) {
+    let (name, default_value) = match style {
+        AuthStyle::Bearer => ("Authorization".to_string(), format!("Bearer {credential}")),
+        AuthStyle::XApiKey => ("x-api-key".to_string(), credential.to_string()),
+        AuthStyle::Custom(header) => (header.clone(), credential.to_string()),
+    };
+    let value = template.map_or(default_value, |t| t.replace("{key}", credential));
+    (name, value)
+}
+
+/// Attach the auth header and any configured static headers to a request.
+fn apply_request_headers(
+    mut req: reqwest::RequestBuilder,
+    style: &AuthStyle,
+    template: Option<&str>,
+    extra_headers: &[(String, String)],
+    credential: &str,
+) -> reqwest::RequestBuilder {
+    for (name, value) in extra_headers {
+        req = req.header(name, value);
+    }
+    let (name, value) = auth_header_pair(style, template, credential);
+    req.header(name, value)
+}
+
 impl OpenAiCompatibleProvider {
     fn apply_auth_header(
         &self,
         req: reqwest::RequestBuilder,
         credential: &str,
     ) -> reqwest::RequestBuilder {
-        match &self.auth_header {
-            AuthStyle::Bearer => req.header("Authorization", format!("Bearer {credential}")),
-            AuthStyle::XApiKey => req.header("x-api-key", credential),
-            AuthStyle::Custom(header) => req.header(header, credential),
-        }
+        apply_request_headers(
+            req,
+            &self.auth_header,
+            self.auth_value_template.as_deref(),
+            &self.extra_headers,
+            credential,
+        )
     }
 
     async fn chat_via_responses(
@@ -587,8 +719,7 @@ impl OpenAiCompatibleProvider {
             .await?;
 
         if !response.status().is_success() {
-            let error = response.text().await?;
-            anyhow::bail!("{} Responses API error: {error}", self.name);
+            return Err(super::api_error(&format!("{} Responses", self.name), response).await);
         }
 
         let body = response.text().await?;
@@ -718,6 +849,7 @@ impl OpenAiCompatibleProvider {
     }
 
     fn parse_native_response(message: ResponseMessage) -> ProviderChatResponse {
+        let reasoning = message.separate_reasoning();
         let tool_calls = message
             .tool_calls
             .unwrap_or_default()
@@ -737,6 +869,8 @@ impl OpenAiCompatibleProvider {
         ProviderChatResponse {
             text: message.content,
             tool_calls,
+            reasoning,
+            finish_reason: None,
         }
     }
 
@@ -760,6 +894,101 @@ impl OpenAiCompatibleProvider {
         .iter()
         .any(|hint| lower.contains(hint))
     }
+
+    /// Stream a chat completion for an already-built message list.
+    fn stream_messages(
+        &self,
+        messages: Vec<Message>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let credential = match self.credential.as_ref() {
+            Some(value) => value.clone(),
+            None => {
+                let provider_name = self.name.clone();
+                return stream::once(async move {
+                    Err(StreamError::Provider(format!(
+                        "{} API key not set",
+                        provider_name
+                    )))
+                })
+                .boxed();
+            }
+        };
+
+        let request = ApiChatRequest {
+            model: model.to_string(),
+            messages,
+            temperature,
+            stream: Some(options.enabled),
+            tools: None,
+            tool_choice: None,
+            reasoning_effort: self.reasoning_effort.clone(),
+        };
+
+        let url = self.chat_completions_url();
+        let client = self.http_client();
+        let auth_header = self.auth_header.clone();
+        let auth_value_template = self.auth_value_template.clone();
+        let extra_headers = self.extra_headers.clone();
+
+        // Use a channel to bridge the async HTTP response to the stream
+        let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
+
+        tokio::spawn(async move {
+            // Build request with auth
+            let mut req_builder = client.post(&url).json(&request);
+
+            // Apply auth header and configured static headers
+            req_builder = apply_request_headers(
+                req_builder,
+                &auth_header,
+                auth_value_template.as_deref(),
+                &extra_headers,
+                &credential,
+            );
+
+            // Set accept header for streaming
+            req_builder = req_builder.header("Accept", "text/event-stream");
+
+            // Send request
+            let response = match req_builder.send().await {
+                Ok(r) => r,
+                Err(e) => {
+                    let _ = tx.send(Err(StreamError::Http(e))).await;
+                    return;
+                }
+            };
+
+            // Check status
+            if !response.status().is_success() {
+                let status = response.status();
+                let error = match response.text().await {
+                    Ok(e) => e,
+                    Err(_) => format!("HTTP error: {}", status),
+                };
+                let _ = tx
+                    .send(Err(StreamError::Provider(format!("{}: {}", status, error))))
+                    .await;
+                return;
+            }
+
+            // Convert to chunk stream and forward to channel
+            let mut chunk_stream = sse_bytes_to_chunks(response, options.count_tokens);
+            while let Some(chunk) = chunk_stream.next().await {
+                if tx.send(chunk).await.is_err() {
+                    break; // Receiver dropped
+                }
+            }
+        });
+
+        // Convert channel receiver to stream
+        stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|chunk| (chunk, rx))
+        })
+        .boxed()
+    }
 }
 
 #[async_trait]
@@ -767,6 +996,9 @@ impl Provider for OpenAiCompatibleProvider {
     fn capabilities(&self) -> crate::providers::traits::ProviderCapabilities {
         crate::providers::traits::ProviderCapabilities {
             native_tool_calling: true,
+            vision: false,
+            streaming: true,
+            embeddings: false,
         }
     }
 
@@ -777,12 +1009,7 @@ impl Provider for OpenAiCompatibleProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
-                self.name
-            )
-        })?;
+        let credential = self.credential()?;
 
         let mut messages = Vec::new();
 
@@ -805,6 +1032,7 @@ impl Provider for OpenAiCompatibleProvider {
             stream: Some(false),
             tools: None,
             tool_choice: None,
+            reasoning_effort: self.reasoning_effort.clone(),
         };
 
         let url = self.chat_completions_url();
@@ -814,8 +1042,12 @@ impl Provider for OpenAiCompatibleProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe(&self.name, response.headers());
+
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after =
+                super::rate_limit::retry_advice(response.headers(), std::time::SystemTime::now());
             let error = response.text().await?;
             let sanitized = super::sanitize_api_error(&error);
 
@@ -824,14 +1056,23 @@ impl Provider for OpenAiCompatibleProvider {
                     .chat_via_responses(credential, system_prompt, message, model)
                     .await
                     .map_err(|responses_err| {
-                        anyhow::anyhow!(
-                            "{} API error ({status}): {sanitized} (chat completions unavailable; responses fallback failed: {responses_err})",
-                            self.name
+                        super::ProviderError::from_response(
+                            &self.name,
+                            status,
+                            &format!("{sanitized} (chat completions unavailable; responses fallback failed: {responses_err})"),
+                            retry_after,
                         )
+                        .into()
                     });
             }
 
-            anyhow::bail!("{} API error ({status}): {sanitized}", self.name);
+            return Err(super::ProviderError::from_response(
+                &self.name,
+                status,
+                &sanitized,
+                retry_after,
+            )
+            .into());
         }
 
         let body = response.text().await?;
@@ -866,12 +1107,7 @@ impl Provider for OpenAiCompatibleProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
-                self.name
-            )
-        })?;
+        let credential = self.credential()?;
 
         let api_messages: Vec<Message> = messages
             .iter()
@@ -888,6 +1124,7 @@ impl Provider for OpenAiCompatibleProvider {
             stream: Some(false),
             tools: None,
             tool_choice: None,
+            reasoning_effort: self.reasoning_effort.clone(),
         };
 
         let url = self.chat_completions_url();
@@ -896,6 +1133,8 @@ impl Provider for OpenAiCompatibleProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe(&self.name, response.headers());
+
         if !response.status().is_success() {
             let status = response.status();
 
@@ -914,10 +1153,16 @@ impl Provider for OpenAiCompatibleProvider {
                         )
                         .await
                         .map_err(|responses_err| {
-                            anyhow::anyhow!(
-                                "{} API error (chat completions unavailable; responses fallback failed: {responses_err})",
-                                self.name
+                            super::ProviderError::from_status(
+                                status,
+                                "",
+                                format!(
+                                    "{} API error (chat completions unavailable; responses fallback failed: {responses_err})",
+                                    self.name
+                                ),
+                                None,
                             )
+                            .into()
                         });
                 }
             }
@@ -958,12 +1203,7 @@ impl Provider for OpenAiCompatibleProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
-                self.name
-            )
-        })?;
+        let credential = self.credential()?;
 
         let api_messages: Vec<Message> = messages
             .iter()
@@ -988,6 +1228,7 @@ impl Provider for OpenAiCompatibleProvider {
             } else {
                 Some("auto".to_string())
             },
+            reasoning_effort: self.reasoning_effort.clone(),
         };
 
         let url = self.chat_completions_url();
@@ -996,6 +1237,8 @@ impl Provider for OpenAiCompatibleProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe(&self.name, response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error(&self.name, response).await);
         }
@@ -1009,6 +1252,7 @@ impl Provider for OpenAiCompatibleProvider {
             .ok_or_else(|| anyhow::anyhow!("No response from {}", self.name))?;
 
         let text = choice.message.effective_content_optional();
+        let reasoning = choice.message.separate_reasoning();
         let tool_calls = choice
             .message
             .tool_calls
@@ -1026,7 +1270,12 @@ impl Provider for OpenAiCompatibleProvider {
             })
             .collect::<Vec<_>>();
 
-        Ok(ProviderChatResponse { text, tool_calls })
+        Ok(ProviderChatResponse {
+            text,
+            tool_calls,
+            reasoning,
+            finish_reason: None,
+        })
     }
 
     async fn chat(
@@ -1035,12 +1284,7 @@ impl Provider for OpenAiCompatibleProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
-                self.name
-            )
-        })?;
+        let credential = self.credential()?;
 
         let tools = Self::convert_tool_specs(request.tools);
         let native_request = NativeChatRequest {
@@ -1050,16 +1294,24 @@ impl Provider for OpenAiCompatibleProvider {
             stream: Some(false),
             tool_choice: tools.as_ref().map(|_| "auto".to_string()),
             tools,
+            reasoning_effort: self.reasoning_effort.clone(),
         };
 
         let url = self.chat_completions_url();
         let response = self
-            .apply_auth_header(self.http_client().post(&url).json(&native_request), credential)
+            .apply_auth_header(
+                self.http_client().post(&url).json(&native_request),
+                credential,
+            )
             .send()
             .await?;
 
+        super::rate_limit::observe(&self.name, response.headers());
+
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after =
+                super::rate_limit::retry_advice(response.headers(), std::time::SystemTime::now());
             let error = response.text().await?;
             let sanitized = super::sanitize_api_error(&error);
 
@@ -1072,6 +1324,8 @@ impl Provider for OpenAiCompatibleProvider {
                 return Ok(ProviderChatResponse {
                     text: Some(text),
                     tool_calls: vec![],
+                    reasoning: None,
+                    finish_reason: None,
                 });
             }
 
@@ -1090,36 +1344,40 @@ impl Provider for OpenAiCompatibleProvider {
                         .map(|text| ProviderChatResponse {
                             text: Some(text),
                             tool_calls: vec![],
+                            reasoning: None,
+                            finish_reason: None,
                         })
                         .map_err(|responses_err| {
-                            anyhow::anyhow!(
-                                "{} API error ({status}): {sanitized} (chat completions unavailable; responses fallback failed: {responses_err})",
-                                self.name
+                            super::ProviderError::from_response(
+                                &self.name,
+                                status,
+                                &format!("{sanitized} (chat completions unavailable; responses fallback failed: {responses_err})"),
+                                retry_after,
                             )
+                            .into()
                         });
                 }
             }
 
-            anyhow::bail!("{} API error ({status}): {sanitized}", self.name);
+            return Err(super::ProviderError::from_response(
+                &self.name,
+                status,
+                &sanitized,
+                retry_after,
+            )
+            .into());
         }
 
         let native_response: ApiChatResponse = response.json().await?;
-        let message = native_response
+        let choice = native_response
             .choices
             .into_iter()
             .next()
-            .map(|choice| choice.message)
             .ok_or_else(|| anyhow::anyhow!("No response from {}", self.name))?;
 
-        Ok(Self::parse_native_response(message))
-    }
-
-    fn supports_native_tools(&self) -> bool {
-        true
-    }
-
-    fn supports_streaming(&self) -> bool {
-        true
+        let mut chat_response = Self::parse_native_response(choice.message);
+        chat_response.finish_reason = choice.finish_reason.as_deref().map(FinishReason::parse);
+        Ok(chat_response)
     }
 
     fn stream_chat_with_system(
@@ -1130,20 +1388,6 @@ impl Provider for OpenAiCompatibleProvider {
         temperature: f64,
         options: StreamOptions,
     ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
-        let credential = match self.credential.as_ref() {
-            Some(value) => value.clone(),
-            None => {
-                let provider_name = self.name.clone();
-                return stream::once(async move {
-                    Err(StreamError::Provider(format!(
-                        "{} API key not set",
-                        provider_name
-                    )))
-                })
-                .boxed();
-            }
-        };
-
         let mut messages = Vec::new();
         if let Some(sys) = system_prompt {
             messages.push(Message {
@@ -1156,74 +1400,24 @@ impl Provider for OpenAiCompatibleProvider {
             content: message.to_string(),
         });
 
-        let request = ApiChatRequest {
-            model: model.to_string(),
-            messages,
-            temperature,
-            stream: Some(options.enabled),
-            tools: None,
-            tool_choice: None,
-        };
-
-        let url = self.chat_completions_url();
-        let client = self.http_client();
-        let auth_header = self.auth_header.clone();
-
-        // Use a channel to bridge the async HTTP response to the stream
-        let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
-
-        tokio::spawn(async move {
-            // Build request with auth
-            let mut req_builder = client.post(&url).json(&request);
-
-            // Apply auth header
-            req_builder = match &auth_header {
-                AuthStyle::Bearer => {
-                    req_builder.header("Authorization", format!("Bearer {}", credential))
-                }
-                AuthStyle::XApiKey => req_builder.header("x-api-key", &credential),
-                AuthStyle::Custom(header) => req_builder.header(header, &credential),
-            };
-
-            // Set accept header for streaming
-            req_builder = req_builder.header("Accept", "text/event-stream");
-
-            // Send request
-            let response = match req_builder.send().await {
-                Ok(r) => r,
-                Err(e) => {
-                    let _ = tx.send(Err(StreamError::Http(e))).await;
-                    return;
-                }
-            };
-
-            // Check status
-            if !response.status().is_success() {
-                let status = response.status();
-                let error = match response.text().await {
-                    Ok(e) => e,
-                    Err(_) => format!("HTTP error: {}", status),
-                };
-                let _ = tx
-                    .send(Err(StreamError::Provider(format!("{}: {}", status, error))))
-                    .await;
-                return;
-            }
-
-            // Convert to chunk stream and forward to channel
-            let mut chunk_stream = sse_bytes_to_chunks(response, options.count_tokens);
-            while let Some(chunk) = chunk_stream.next().await {
-                if tx.send(chunk).await.is_err() {
-                    break; // Receiver dropped
-                }
-            }
-        });
+        self.stream_messages(messages, model, temperature, options)
+    }
 
-        // Convert channel receiver to stream
-        stream::unfold(rx, |mut rx| async move {
-            rx.recv().await.map(|chunk| (chunk, rx))
-        })
-        .boxed()
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let messages = messages
+            .iter()
+            .map(|m| Message {
+                role: m.role.clone(),
+                content: m.content.clone(),
+            })
+            .collect();
+        self.stream_messages(messages, model, temperature, options)
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -1244,6 +1438,7 @@ impl Provider for OpenAiCompatibleProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ProviderHeaderOverrides;
 
     fn make_provider(name: &str, url: &str, key: Option<&str>) -> OpenAiCompatibleProvider {
         OpenAiCompatibleProvider::new(name, url, key, AuthStyle::Bearer)
@@ -1304,6 +1499,7 @@ mod tests {
             stream: Some(false),
             tools: None,
             tool_choice: None,
+            reasoning_effort: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("llama-3.3-70b"));
@@ -1312,6 +1508,29 @@ mod tests {
         // tools/tool_choice should be omitted when None
         assert!(!json.contains("tools"));
         assert!(!json.contains("tool_choice"));
+        assert!(!json.contains("reasoning_effort"));
+    }
+
+    #[test]
+    fn reasoning_effort_is_sent_when_configured() {
+        let provider = make_provider("test", "https://example.com", None)
+            .with_reasoning_effort(Some(" high "));
+        assert_eq!(provider.reasoning_effort.as_deref(), Some("high"));
+        let req = ApiChatRequest {
+            model: "deepseek-reasoner".to_string(),
+            messages: vec![],
+            temperature: 0.7,
+            stream: None,
+            tools: None,
+            tool_choice: None,
+            reasoning_effort: provider.reasoning_effort.clone(),
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains(r#""reasoning_effort":"high""#));
+
+        let provider =
+            make_provider("test", "https://example.com", None).with_reasoning_effort(Some(""));
+        assert!(provider.reasoning_effort.is_none());
     }
 
     #[test]
@@ -1377,6 +1596,67 @@ mod tests {
         assert!(matches!(p.auth_header, AuthStyle::Custom(_)));
     }
 
+    #[test]
+    fn header_overrides_apply_custom_auth_name_and_template() {
+        let p = make_provider("custom", "https://gw.example.com", Some("key"))
+            .with_header_overrides(&ProviderHeaderOverrides {
+                auth_header: Some("api-key".into()),
+                auth_value_template: Some("Token {key}".into()),
+                ..ProviderHeaderOverrides::default()
+            });
+        let request = p
+            .apply_auth_header(
+                reqwest::Client::new().get("https://gw.example.com"),
+                "secret",
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()["api-key"], "Token secret");
+        assert!(request.headers().get("authorization").is_none());
+    }
+
+    #[test]
+    fn header_overrides_template_without_bearer_prefix() {
+        let p = make_provider("custom", "https://gw.example.com", Some("key"))
+            .with_header_overrides(&ProviderHeaderOverrides {
+                auth_value_template: Some("{key}".into()),
+                ..ProviderHeaderOverrides::default()
+            });
+        let request = p
+            .apply_auth_header(
+                reqwest::Client::new().get("https://gw.example.com"),
+                "secret",
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()["authorization"], "secret");
+    }
+
+    #[test]
+    fn header_overrides_attach_extra_headers_to_every_request() {
+        let overrides = ProviderHeaderOverrides {
+            headers: [("X-Tenant-Id", "acme"), ("X-Env", "prod")]
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            ..ProviderHeaderOverrides::default()
+        };
+        let p = make_provider("custom", "https://gw.example.com", Some("key"))
+            .with_header_overrides(&overrides);
+
+        for builder in [
+            reqwest::Client::new().post("https://gw.example.com/v1/chat/completions"),
+            reqwest::Client::new().get("https://gw.example.com/v1/models"),
+        ] {
+            let request = p.apply_auth_header(builder, "secret").build().unwrap();
+            assert_eq!(request.headers()["x-tenant-id"], "acme");
+            assert_eq!(request.headers()["x-env"], "prod");
+            assert_eq!(request.headers()["authorization"], "Bearer secret");
+        }
+    }
+
     #[tokio::test]
     async fn all_compatible_providers_fail_without_key() {
         let providers = vec![
@@ -1644,6 +1924,27 @@ mod tests {
         assert_eq!(parsed.tool_calls[0].name, "shell");
     }
 
+    #[test]
+    fn parse_native_response_keeps_reasoning_content_separate() {
+        let message: ResponseMessage = serde_json::from_str(
+            r#"{"content":"It is 4.","reasoning_content":"2 + 2 is basic arithmetic."}"#,
+        )
+        .unwrap();
+        let parsed = OpenAiCompatibleProvider::parse_native_response(message);
+        assert_eq!(parsed.text.as_deref(), Some("It is 4."));
+        assert_eq!(
+            parsed.reasoning.as_deref(),
+            Some("2 + 2 is basic arithmetic.")
+        );
+
+        // Reasoning that stands in for missing content is the answer, not
+        // additional reasoning.
+        let message: ResponseMessage =
+            serde_json::from_str(r#"{"content":"","reasoning_content":"Only this"}"#).unwrap();
+        assert_eq!(message.effective_content(), "Only this");
+        assert!(message.separate_reasoning().is_none());
+    }
+
     #[test]
     fn convert_messages_for_native_maps_tool_result_payload() {
         let input = vec![ChatMessage::tool(
@@ -1758,6 +2059,7 @@ mod tests {
             stream: Some(false),
             tools: Some(tools),
             tool_choice: Some("auto".to_string()),
+            reasoning_effort: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"tools\""));
@@ -1969,6 +2271,33 @@ mod tests {
         assert_eq!(result, Some("thinking...".to_string()));
     }
 
+    #[test]
+    fn sse_tool_call_fragments_reassemble_across_lines() {
+        let lines = [
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"shell","arguments":""}}]}}]}"#,
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":1,"id":"call_2","type":"function","function":{"name":"file_read","arguments":"{"path":"}}]}}]}"#,
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{"command":"ls"}"}}]}}]}"#,
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":1,"function":{"arguments":""README.md"}"}}]}}]}"#,
+            r#"data: {"choices":[{"delta":{},"finish_reason":"tool_calls"}]}"#,
+            "data: [DONE]",
+        ];
+        let mut acc = ToolCallAccumulator::default();
+        for line in lines {
+            if let Some(chunk) = parse_sse_data(line).unwrap() {
+                assert_eq!(delta_text(&chunk), None, "tool deltas carry no text");
+                for delta in &chunk.choices[0].delta.tool_calls {
+                    acc.push(delta);
+                }
+            }
+        }
+        let calls = acc.finish().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments, r#"{"command":"ls"}"#);
+        assert_eq!(calls[1].name, "file_read");
+        assert_eq!(calls[1].arguments, r#"{"path":"README.md"}"#);
+    }
+
     #[test]
     fn parse_sse_line_done_sentinel() {
         let line = "data: [DONE]";
@@ -13,7 +13,7 @@
 
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ToolCall as ProviderToolCall,
+    Provider, ProviderCapabilities, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -335,6 +335,8 @@ impl CopilotProvider {
 
         let response = req.send().await?;
 
+        super::rate_limit::observe("GitHub Copilot", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("GitHub Copilot", response).await);
         }
@@ -363,6 +365,8 @@ impl CopilotProvider {
         Ok(ProviderChatResponse {
             text: choice.message.content,
             tool_calls,
+            reasoning: None,
+            finish_reason: None,
         })
     }
 
@@ -518,10 +522,16 @@ impl CopilotProvider {
                 tokio::fs::remove_file(&access_token_path).await.ok();
             }
 
-            anyhow::bail!(
-                "Failed to get Copilot API key ({status}): {sanitized}. \
-                 Ensure your GitHub account has an active Copilot subscription."
-            );
+            return Err(super::ProviderError::from_status(
+                status,
+                &sanitized,
+                format!(
+                    "Failed to get Copilot API key ({status}): {sanitized}. \
+                     Ensure your GitHub account has an active Copilot subscription."
+                ),
+                None,
+            )
+            .into());
         }
 
         let info: ApiKeyInfo = response.json().await?;
@@ -638,8 +648,11 @@ impl Provider for CopilotProvider {
         .await
     }
 
-    fn supports_native_tools(&self) -> bool {
-        true
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            ..ProviderCapabilities::default()
+        }
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -0,0 +1,331 @@
+//! User-registered OpenAI-compatible endpoints.
+//!
+//! Entries live under `[[provider.custom]]` in `config.toml` and are added with
+//! `zeroclaw providers add <name> <base-url>`. Each one becomes selectable by
+//! name via `--provider <name>` or `default_provider`, and talks to
+//! `<base-url>/v1/chat/completions` (vLLM, LM Studio, llama.cpp server, ...).
+
+use crate::config::{Config, CustomProviderConfig};
+use crate::providers::compatible::{AuthStyle, OpenAiCompatibleProvider};
+use crate::providers::traits::{
+    ChatMessage, ChatRequest, ChatResponse, Provider, ProviderCapabilities, StreamChunk,
+    StreamOptions, StreamResult,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures_util::stream;
+
+/// Timeout for the reachability probe run before saving a new endpoint.
+const REACHABILITY_TIMEOUT_SECS: u64 = 10;
+
+/// OpenAI-compatible provider backed by a `[[provider.custom]]` entry.
+pub struct CustomProvider {
+    inner: OpenAiCompatibleProvider,
+}
+
+impl CustomProvider {
+    /// Only the entry's own `api_key` is sent; the global `api_key` is never
+    /// forwarded to a user-supplied endpoint.
+    pub fn new(config: &CustomProviderConfig) -> Result<Self> {
+        let base_url = normalize_base_url(&config.base_url)?;
+        let key = config
+            .api_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|k| !k.is_empty());
+        Ok(Self {
+            inner: OpenAiCompatibleProvider::new_no_responses_fallback(
+                &config.name,
+                &base_url,
+                key,
+                AuthStyle::Bearer,
+            ),
+        })
+    }
+
+    /// Apply `[provider.overrides.<name>]`, as for built-in compatible providers.
+    pub fn with_header_overrides(
+        mut self,
+        overrides: &crate::config::ProviderHeaderOverrides,
+    ) -> Self {
+        self.inner = self.inner.with_header_overrides(overrides);
+        self
+    }
+
+    /// Apply `[provider].reasoning_effort`.
+    pub fn with_reasoning_effort(mut self, effort: Option<&str>) -> Self {
+        self.inner = self.inner.with_reasoning_effort(effort);
+        self
+    }
+}
+
+/// Normalize a user-supplied endpoint to the `.../v1` API root.
+///
+/// Accepts `http://host:8000`, `http://host:8000/v1` or the full
+/// `.../v1/chat/completions` URL.
+pub fn normalize_base_url(raw: &str) -> Result<String> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    let url = reqwest::Url::parse(trimmed).with_context(|| format!("Invalid base URL: {raw}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("Base URL must use http:// or https://: {raw}");
+    }
+    if url.host_str().is_none() {
+        anyhow::bail!("Base URL must include a host: {raw}");
+    }
+
+    let root = trimmed
+        .strip_suffix("/chat/completions")
+        .unwrap_or(trimmed)
+        .trim_end_matches('/');
+    if root.ends_with("/v1") {
+        Ok(root.to_string())
+    } else {
+        Ok(format!("{root}/v1"))
+    }
+}
+
+/// Look up a registered endpoint by name (case-insensitive).
+pub fn find<'a>(
+    custom: &'a [CustomProviderConfig],
+    name: &str,
+) -> Option<&'a CustomProviderConfig> {
+    custom
+        .iter()
+        .find(|entry| entry.name.eq_ignore_ascii_case(name.trim()))
+}
+
+fn validate_name(name: &str, existing: &[CustomProviderConfig]) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        anyhow::bail!("Invalid provider name '{name}': use letters, digits, '-', '_' or '.'");
+    }
+
+    let builtin = super::list_providers().into_iter().any(|p| {
+        p.name.eq_ignore_ascii_case(name) || p.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+    });
+    if builtin || name.eq_ignore_ascii_case("custom") {
+        anyhow::bail!("'{name}' is a built-in provider name; choose another name");
+    }
+
+    if find(existing, name).is_some() {
+        anyhow::bail!("A custom provider named '{name}' already exists");
+    }
+    Ok(())
+}
+
+/// Probe `<base>/models`; any HTTP response (even 401/404) counts as reachable.
+async fn check_reachable(base_url: &str, api_key: Option<&str>) -> Result<()> {
+    let client = crate::config::build_runtime_proxy_client_with_timeouts(
+        "provider.custom",
+        REACHABILITY_TIMEOUT_SECS,
+        REACHABILITY_TIMEOUT_SECS,
+    );
+    let mut request = client.get(format!("{base_url}/models"));
+    if let Some(key) = api_key {
+        request = request.bearer_auth(key);
+    }
+    request
+        .send()
+        .await
+        .with_context(|| format!("Endpoint {base_url} is not reachable"))?;
+    Ok(())
+}
+
+/// `zeroclaw providers add`: validate, probe and persist a new endpoint.
+pub async fn add_provider(
+    config: &Config,
+    name: &str,
+    base_url: &str,
+    api_key: Option<String>,
+    verify: bool,
+) -> Result<CustomProviderConfig> {
+    let name = name.trim();
+    validate_name(name, &config.provider.custom)?;
+    let base_url = normalize_base_url(base_url)?;
+    let api_key = api_key.filter(|k| !k.trim().is_empty());
+
+    if verify {
+        check_reachable(&base_url, api_key.as_deref()).await?;
+    }
+
+    let entry = CustomProviderConfig {
+        name: name.to_string(),
+        base_url,
+        api_key,
+    };
+    let mut updated = config.clone();
+    updated.provider.custom.push(entry.clone());
+    updated.save()?;
+    Ok(entry)
+}
+
+#[async_trait]
+impl Provider for CustomProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        self.inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        self.inner
+            .chat_with_history(messages, model, temperature)
+            .await
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        self.inner.chat(request, model, temperature).await
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    async fn warmup(&self) -> Result<()> {
+        self.inner.warmup().await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        self.inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_system(system_prompt, message, model, temperature, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(name: &str, base_url: &str) -> CustomProviderConfig {
+        CustomProviderConfig {
+            name: name.into(),
+            base_url: base_url.into(),
+            api_key: None,
+        }
+    }
+
+    #[test]
+    fn normalize_base_url_targets_v1_root() {
+        assert_eq!(
+            normalize_base_url("http://localhost:8000").unwrap(),
+            "http://localhost:8000/v1"
+        );
+        assert_eq!(
+            normalize_base_url("http://localhost:8000/v1/").unwrap(),
+            "http://localhost:8000/v1"
+        );
+        assert_eq!(
+            normalize_base_url("https://gpu.example.com/v1/chat/completions").unwrap(),
+            "https://gpu.example.com/v1"
+        );
+        assert!(normalize_base_url("ftp://example.com").is_err());
+        assert!(normalize_base_url("not a url").is_err());
+    }
+
+    #[test]
+    fn validate_name_rejects_builtins_duplicates_and_bad_chars() {
+        let existing = vec![entry("vllm", "http://localhost:8000/v1")];
+        assert!(validate_name("lmstudio-box", &existing).is_ok());
+        assert!(validate_name("openai", &existing).is_err());
+        assert!(validate_name("VLLM", &existing).is_err());
+        assert!(validate_name("has space", &existing).is_err());
+        assert!(validate_name("", &existing).is_err());
+    }
+
+    #[test]
+    fn find_is_case_insensitive() {
+        let existing = vec![entry("vllm", "http://localhost:8000/v1")];
+        assert!(find(&existing, "VLLM").is_some());
+        assert!(find(&existing, "other").is_none());
+    }
+
+    #[test]
+    fn custom_provider_requires_valid_base_url() {
+        let mut cfg = entry("vllm", "http://localhost:8000");
+        cfg.api_key = Some("entry-key".into());
+        assert!(CustomProvider::new(&cfg).is_ok());
+        assert!(CustomProvider::new(&entry("bad", "nope")).is_err());
+    }
+
+    #[tokio::test]
+    async fn add_provider_persists_entry_without_probe() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            config_path: tmp.path().join("config.toml"),
+            workspace_dir: tmp.path().join("workspace"),
+            ..Config::default()
+        };
+
+        let added = add_provider(&config, "vllm", "http://localhost:8000", None, false)
+            .await
+            .unwrap();
+        assert_eq!(added.base_url, "http://localhost:8000/v1");
+
+        let saved: Config =
+            toml::from_str(&std::fs::read_to_string(tmp.path().join("config.toml")).unwrap())
+                .unwrap();
+        assert_eq!(saved.provider.custom.len(), 1);
+        assert_eq!(saved.provider.custom[0].name, "vllm");
+    }
+
+    #[tokio::test]
+    async fn add_provider_rejects_unreachable_endpoint() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+
+        let err = add_provider(&config, "dead", "http://127.0.0.1:9", None, true)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not reachable"));
+        assert!(!tmp.path().join("config.toml").exists());
+    }
+}
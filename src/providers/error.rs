@@ -0,0 +1,480 @@
+//! Typed provider failures.
+//!
+//! Providers turn failed HTTP responses and missing credentials into a
+//! [`ProviderError`] (via [`super::api_error`] or [`ProviderError::from_status`])
+//! and return it inside `anyhow::Error`, so the `Provider` trait keeps its
+//! signature while callers can tell an invalid key from a missing model or a
+//! rate limit with [`ProviderError::find`]. Each variant displays exactly like
+//! the plain message it replaces. [`ProviderError::classify`] also covers
+//! transport failures and `error_for_status` errors from reqwest; anything
+//! else is untyped and treated as transient by the retry logic.
+
+use super::rate_limit::RetryAdvice;
+use crate::i18n;
+use reqwest::StatusCode;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProviderError {
+    /// The key or token was missing, invalid or lacks access.
+    #[error("{message}")]
+    AuthFailed { message: String },
+    /// The requested model does not exist or is not served to this account.
+    #[error("{message}")]
+    ModelNotFound { message: String },
+    #[error("{message}")]
+    RateLimited {
+        message: String,
+        retry_after: Option<RetryAdvice>,
+    },
+    /// A 429 caused by the account's plan, balance or quota rather than by
+    /// request rate; waiting does not help.
+    #[error("{message}")]
+    QuotaExceeded { message: String },
+    /// The request did not fit the model's context window.
+    #[error("{message}")]
+    ContextLengthExceeded { message: String },
+    /// The provider's moderation or content policy refused the request.
+    #[error("{message}")]
+    ContentFiltered { message: String },
+    /// The provider could not be reached.
+    #[error("{message}")]
+    Network { message: String },
+    /// Any other error response.
+    #[error("{message}")]
+    Api {
+        status: u16,
+        message: String,
+        retry_after: Option<RetryAdvice>,
+    },
+}
+
+const CONTEXT_LENGTH_HINTS: [&str; 9] = [
+    "context_length_exceeded",
+    "maximum context length",
+    "context length",
+    "context window",
+    "prompt is too long",
+    "input is too long",
+    "exceeds the maximum number of tokens",
+    "reduce the length of the messages",
+    "exceeds the model's context",
+];
+
+const MODEL_UNAVAILABLE_HINTS: [&str; 9] = [
+    "model_not_found",
+    "not_found_error",
+    "not found",
+    "does not exist",
+    "no such model",
+    "unknown model",
+    "not available",
+    "unavailable",
+    "decommissioned",
+];
+
+const AUTH_HINTS: [&str; 10] = [
+    "invalid api key",
+    "incorrect api key",
+    "invalid_api_key",
+    "invalid x-api-key",
+    "api key not valid",
+    "missing api key",
+    "api key not set",
+    "authentication_error",
+    "unauthorized",
+    "invalid token",
+];
+
+const CONTENT_FILTER_HINTS: [&str; 6] = [
+    "content_filter",
+    "content management policy",
+    "content_policy_violation",
+    "flagged by moderation",
+    "flagged for moderation",
+    "safety system",
+];
+
+const QUOTA_HINTS: [&str; 13] = [
+    "plan does not include",
+    "doesn't include",
+    "not include",
+    "insufficient balance",
+    "insufficient_balance",
+    "insufficient quota",
+    "insufficient_quota",
+    "quota exhausted",
+    "out of credits",
+    "no available package",
+    "no resource package",
+    "package not active",
+    "purchase package",
+];
+
+/// Business codes some providers (Z.AI / GLM) send with a 429 when the plan
+/// or balance, not the request rate, is the problem.
+const QUOTA_CODES: [u16; 2] = [1113, 1311];
+
+fn contains_any(text: &str, hints: &[&str]) -> bool {
+    hints.iter().any(|hint| text.contains(hint))
+}
+
+/// Provider wording for a request that exceeded the context window.
+pub(crate) fn mentions_context_length(text: &str) -> bool {
+    contains_any(&text.to_lowercase(), &CONTEXT_LENGTH_HINTS)
+}
+
+/// Provider wording for a model that does not exist or is not served.
+pub(crate) fn mentions_model_unavailable(text: &str) -> bool {
+    let text = text.to_lowercase();
+    text.contains("model")
+        && !contains_any(&text, &CONTEXT_LENGTH_HINTS)
+        && contains_any(&text, &MODEL_UNAVAILABLE_HINTS)
+}
+
+/// A 429 body blaming the plan or balance instead of the request rate.
+fn mentions_quota(lower: &str) -> bool {
+    contains_any(lower, &QUOTA_HINTS)
+        || lower
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|token| token.parse::<u16>().ok())
+            .any(|code| QUOTA_CODES.contains(&code))
+}
+
+impl ProviderError {
+    /// Classify an error response from `provider`. `body` should already be
+    /// sanitized; it becomes part of the message.
+    pub fn from_response(
+        provider: &str,
+        status: StatusCode,
+        body: &str,
+        retry_after: Option<RetryAdvice>,
+    ) -> Self {
+        let message = format!("{provider} API error ({status}): {body}");
+        Self::from_status(status, body, message, retry_after)
+    }
+
+    /// Classify an error response by `status` and `body`, keeping `message`
+    /// as the text shown to the user.
+    pub fn from_status(
+        status: StatusCode,
+        body: &str,
+        message: String,
+        retry_after: Option<RetryAdvice>,
+    ) -> Self {
+        let lower = body.to_lowercase();
+        let code = status.as_u16();
+
+        if contains_any(&lower, &CONTENT_FILTER_HINTS) {
+            Self::ContentFiltered { message }
+        } else if contains_any(&lower, &CONTEXT_LENGTH_HINTS) {
+            Self::ContextLengthExceeded { message }
+        } else if matches!(code, 401 | 403) || contains_any(&lower, &AUTH_HINTS) {
+            Self::AuthFailed { message }
+        } else if code == 429 && mentions_quota(&lower) {
+            Self::QuotaExceeded { message }
+        } else if code == 429 {
+            Self::RateLimited {
+                message,
+                retry_after,
+            }
+        } else if status.is_client_error() && mentions_model_unavailable(body) {
+            Self::ModelNotFound { message }
+        } else {
+            Self::Api {
+                status: code,
+                message,
+                retry_after,
+            }
+        }
+    }
+
+    /// The typed provider error carried by `err`, if any.
+    pub fn find(err: &anyhow::Error) -> Option<&Self> {
+        err.chain().find_map(|e| e.downcast_ref::<Self>())
+    }
+
+    /// Like [`find`](Self::find), but also recognizes reqwest errors:
+    /// transport failures that never produced a response, and error statuses
+    /// raised by `error_for_status`.
+    pub fn classify(err: &anyhow::Error) -> Option<Self> {
+        if let Some(typed) = Self::find(err) {
+            return Some(typed.clone());
+        }
+        let e = err
+            .chain()
+            .find_map(|e| e.downcast_ref::<reqwest::Error>())?;
+        if let Some(status) = e.status() {
+            Some(Self::from_status(status, "", e.to_string(), None))
+        } else if e.is_connect() || e.is_timeout() {
+            Some(Self::Network {
+                message: e.to_string(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The wait advised by the provider's response headers.
+    pub fn retry_after(&self) -> Option<RetryAdvice> {
+        match self {
+            Self::RateLimited { retry_after, .. } | Self::Api { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Whether repeating the same request can succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } | Self::Network { .. } => true,
+            Self::Api { status, .. } => !(400..500).contains(status) || *status == 408,
+            Self::AuthFailed { .. }
+            | Self::ModelNotFound { .. }
+            | Self::QuotaExceeded { .. }
+            | Self::ContextLengthExceeded { .. }
+            | Self::ContentFiltered { .. } => false,
+        }
+    }
+
+    /// What the user can do about this error, for `provider` (the config
+    /// name, e.g. `openrouter`) and `model`.
+    pub fn user_hint(&self, provider: &str, model: &str) -> Option<String> {
+        let hint = match self {
+            Self::AuthFailed { .. } => {
+                let command = match provider {
+                    "openai-codex" => "zeroclaw auth login --provider openai-codex",
+                    "anthropic" => "zeroclaw auth paste-token --provider anthropic",
+                    _ => "zeroclaw onboard",
+                };
+                i18n::tf(
+                    "provider.auth_failed",
+                    &[("provider", &provider), ("command", &command)],
+                )
+            }
+            Self::ModelNotFound { .. } => i18n::tf(
+                "provider.model_not_found",
+                &[("provider", &provider), ("model", &model)],
+            ),
+            Self::RateLimited { .. } => {
+                i18n::tf("provider.rate_limited", &[("provider", &provider)])
+            }
+            Self::QuotaExceeded { .. } => {
+                i18n::tf("provider.quota_exceeded", &[("provider", &provider)])
+            }
+            Self::ContextLengthExceeded { .. } => {
+                i18n::tf("provider.context_length", &[("model", &model)])
+            }
+            Self::ContentFiltered { .. } => {
+                i18n::tf("provider.content_filtered", &[("provider", &provider)])
+            }
+            Self::Network { .. } => i18n::tf("provider.network", &[("provider", &provider)]),
+            Self::Api { .. } => return None,
+        };
+        Some(hint)
+    }
+}
+
+/// Put the user hint for a typed provider error in front of `err`; other
+/// errors are returned unchanged.
+pub fn with_user_hint(err: anyhow::Error, provider: &str, model: &str) -> anyhow::Error {
+    match ProviderError::classify(&err).and_then(|e| e.user_hint(provider, model)) {
+        Some(hint) => err.context(hint),
+        None => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(provider: &str, status: u16, body: &str) -> ProviderError {
+        ProviderError::from_response(provider, StatusCode::from_u16(status).unwrap(), body, None)
+    }
+
+    macro_rules! assert_variant {
+        ($error:expr, $variant:pat) => {
+            let error = $error;
+            assert!(matches!(error, $variant), "got {error:?}");
+        };
+    }
+
+    #[test]
+    fn openai_bodies() {
+        assert_variant!(
+            classify(
+                "OpenAI",
+                401,
+                r#"{"error":{"message":"Incorrect API key provided: sk-***.","type":"invalid_request_error","code":"invalid_api_key"}}"#
+            ),
+            ProviderError::AuthFailed { .. }
+        );
+        assert_variant!(
+            classify(
+                "OpenAI",
+                404,
+                r#"{"error":{"message":"The model `gpt-9` does not exist or you do not have access to it.","code":"model_not_found"}}"#
+            ),
+            ProviderError::ModelNotFound { .. }
+        );
+        assert_variant!(
+            classify(
+                "OpenAI",
+                400,
+                r#"{"error":{"message":"This model's maximum context length is 128000 tokens.","code":"context_length_exceeded"}}"#
+            ),
+            ProviderError::ContextLengthExceeded { .. }
+        );
+        assert_variant!(
+            classify(
+                "Azure OpenAI",
+                400,
+                r#"{"error":{"code":"content_filter","message":"The response was filtered due to the prompt triggering Azure OpenAI's content management policy."}}"#
+            ),
+            ProviderError::ContentFiltered { .. }
+        );
+    }
+
+    #[test]
+    fn anthropic_bodies() {
+        assert_variant!(
+            classify(
+                "Anthropic",
+                401,
+                r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#
+            ),
+            ProviderError::AuthFailed { .. }
+        );
+        assert_variant!(
+            classify(
+                "Anthropic",
+                429,
+                r#"{"type":"error","error":{"type":"rate_limit_error","message":"Number of request tokens has exceeded your per-minute rate limit"}}"#
+            ),
+            ProviderError::RateLimited { .. }
+        );
+        assert_variant!(
+            classify(
+                "Anthropic",
+                400,
+                r#"{"type":"error","error":{"type":"invalid_request_error","message":"prompt is too long: 210000 tokens > 200000 maximum"}}"#
+            ),
+            ProviderError::ContextLengthExceeded { .. }
+        );
+        assert_variant!(
+            classify(
+                "Anthropic",
+                404,
+                r#"{"type":"error","error":{"type":"not_found_error","message":"model: claude-9-opus"}}"#
+            ),
+            ProviderError::ModelNotFound { .. }
+        );
+        assert_variant!(
+            classify(
+                "Anthropic",
+                529,
+                r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#
+            ),
+            ProviderError::Api { status: 529, .. }
+        );
+    }
+
+    #[test]
+    fn openrouter_gemini_ollama_and_glm_bodies() {
+        assert_variant!(
+            classify(
+                "OpenRouter",
+                403,
+                r#"{"error":{"code":403,"message":"Input was flagged by moderation","metadata":{"reasons":["violence"]}}}"#
+            ),
+            ProviderError::ContentFiltered { .. }
+        );
+        assert_variant!(
+            classify(
+                "OpenRouter",
+                402,
+                r#"{"error":{"code":402,"message":"Insufficient credits"}}"#
+            ),
+            ProviderError::Api { status: 402, .. }
+        );
+        assert_variant!(
+            classify(
+                "Gemini",
+                400,
+                r#"{"error":{"code":400,"message":"API key not valid. Please pass a valid API key.","status":"INVALID_ARGUMENT"}}"#
+            ),
+            ProviderError::AuthFailed { .. }
+        );
+        assert_variant!(
+            classify(
+                "Gemini",
+                404,
+                r#"{"error":{"code":404,"message":"models/gemini-9 is not found for API version v1beta","status":"NOT_FOUND"}}"#
+            ),
+            ProviderError::ModelNotFound { .. }
+        );
+        assert_variant!(
+            classify(
+                "Ollama",
+                404,
+                r#"{"error":"model \"llama9\" not found, try pulling it first"}"#
+            ),
+            ProviderError::ModelNotFound { .. }
+        );
+        assert_variant!(
+            classify(
+                "GLM",
+                429,
+                r#"{"error":{"code":"1113","message":"Insufficient balance or no resource package"}}"#
+            ),
+            ProviderError::QuotaExceeded { .. }
+        );
+        assert_variant!(
+            classify(
+                "GLM",
+                429,
+                r#"{"error":{"code":"1311","message":"The current account plan does not include glm-5"}}"#
+            ),
+            ProviderError::QuotaExceeded { .. }
+        );
+        assert_variant!(
+            classify("GLM", 500, "internal error"),
+            ProviderError::Api { status: 500, .. }
+        );
+    }
+
+    #[test]
+    fn display_matches_the_plain_message() {
+        let err = classify("OpenAI", 429, "slow down");
+        assert_eq!(
+            err.to_string(),
+            "OpenAI API error (429 Too Many Requests): slow down"
+        );
+    }
+
+    #[test]
+    fn find_sees_through_context_and_drives_retry_decisions() {
+        let err = anyhow::Error::new(classify("OpenAI", 401, "bad key")).context("turn failed");
+        let typed = ProviderError::find(&err).unwrap();
+        assert!(!typed.is_retryable());
+        assert!(classify("OpenAI", 429, "").is_retryable());
+        assert!(classify("OpenAI", 503, "").is_retryable());
+        assert!(!classify("OpenAI", 400, "bad request").is_retryable());
+        assert!(!classify("OpenAI", 429, "insufficient_quota").is_retryable());
+        assert!(ProviderError::find(&anyhow::anyhow!("plain")).is_none());
+    }
+
+    #[test]
+    fn auth_hint_names_the_fix() {
+        let err = with_user_hint(
+            anyhow::Error::new(classify("OpenRouter", 401, "invalid key")),
+            "openrouter",
+            "gpt-4o",
+        );
+        let hint = err.to_string();
+        assert!(hint.contains("openrouter"), "{hint}");
+        assert!(hint.contains("zeroclaw onboard"), "{hint}");
+        assert!(ProviderError::find(&err).is_some());
+
+        let untouched = with_user_hint(anyhow::anyhow!("plain"), "openrouter", "gpt-4o");
+        assert_eq!(untouched.to_string(), "plain");
+    }
+}
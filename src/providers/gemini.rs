@@ -335,15 +335,17 @@ impl Provider for GeminiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let auth = self.auth.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Gemini API key not found. Options:\n\
-                 1. Set GEMINI_API_KEY env var\n\
-                 2. Run `gemini` CLI to authenticate (tokens will be reused)\n\
-                 3. Get an API key from https://aistudio.google.com/app/apikey\n\
-                 4. Run `zeroclaw onboard` to configure"
-            )
-        })?;
+        let auth = self
+            .auth
+            .as_ref()
+            .ok_or_else(|| super::ProviderError::AuthFailed {
+                message: "Gemini API key not found. Options:\n\
+                          1. Set GEMINI_API_KEY env var\n\
+                          2. Run `gemini` CLI to authenticate (tokens will be reused)\n\
+                          3. Get an API key from https://aistudio.google.com/app/apikey\n\
+                          4. Run `zeroclaw onboard` to configure"
+                    .into(),
+            })?;
 
         // Build request
         let system_instruction = system_prompt.map(|sys| Content {
@@ -375,9 +377,7 @@ impl Provider for GeminiProvider {
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Gemini API error ({status}): {error_text}");
+            return Err(super::api_error("Gemini", response).await);
         }
 
         let result: GenerateContentResponse = response.json().await?;
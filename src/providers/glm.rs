@@ -95,10 +95,12 @@ impl GlmProvider {
 
     fn generate_token(&self) -> anyhow::Result<String> {
         if self.api_key_id.is_empty() || self.api_key_secret.is_empty() {
-            anyhow::bail!(
-                "GLM API key not set or invalid format. Expected 'id.secret'. \
-                 Run `zeroclaw onboard` or set GLM_API_KEY env var."
-            );
+            return Err(super::ProviderError::AuthFailed {
+                message: "GLM API key not set or invalid format. Expected 'id.secret'. \
+                          Run `zeroclaw onboard` or set GLM_API_KEY env var."
+                    .into(),
+            }
+            .into());
         }
 
         let now_ms = SystemTime::now()
@@ -191,8 +193,7 @@ impl Provider for GlmProvider {
             .await?;
 
         if !response.status().is_success() {
-            let error = response.text().await?;
-            anyhow::bail!("GLM API error: {error}");
+            return Err(super::api_error("GLM", response).await);
         }
 
         let chat_response: ChatResponse = response.json().await?;
@@ -238,8 +239,7 @@ impl Provider for GlmProvider {
             .await?;
 
         if !response.status().is_success() {
-            let error = response.text().await?;
-            anyhow::bail!("GLM API error: {error}");
+            return Err(super::api_error("GLM", response).await);
         }
 
         let chat_response: ChatResponse = response.json().await?;
@@ -1,18 +1,27 @@
 pub mod anthropic;
+pub mod audit;
+pub mod cache;
+pub mod circuit_breaker;
 pub mod compatible;
 pub mod copilot;
+pub mod custom;
+pub mod error;
 pub mod gemini;
+pub mod model_fallback;
 pub mod ollama;
 pub mod openai;
 pub mod openai_codex;
 pub mod openrouter;
+pub mod rate_limit;
 pub mod reliable;
 pub mod router;
+pub mod tool_stream;
 pub mod traits;
 
+pub use error::ProviderError;
 #[allow(unused_imports)]
 pub use traits::{
-    ChatMessage, ChatRequest, ChatResponse, ConversationMessage, Provider, ToolCall,
+    ChatMessage, ChatRequest, ChatResponse, ConversationMessage, FinishReason, Provider, ToolCall,
     ToolResultMessage,
 };
 
@@ -20,6 +29,7 @@ use compatible::{AuthStyle, OpenAiCompatibleProvider};
 use reliable::ReliableProvider;
 use serde::Deserialize;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 const MAX_API_ERROR_CHARS: usize = 200;
 const MINIMAX_INTL_BASE_URL: &str = "https://api.minimax.io/v1";
@@ -237,7 +247,13 @@ fn refresh_minimax_oauth_access_token(name: &str, refresh_token: &str) -> anyhow
             .and_then(|base| base.status_msg.as_deref())
             .filter(|msg| !msg.trim().is_empty())
             .unwrap_or(body.as_str());
-        anyhow::bail!("MiniMax OAuth refresh failed (HTTP {status}): {detail}");
+        return Err(ProviderError::from_status(
+            status,
+            detail,
+            format!("MiniMax OAuth refresh failed (HTTP {status}): {detail}"),
+            None,
+        )
+        .into());
     }
 
     if let Some(payload) = parsed {
@@ -352,6 +368,16 @@ pub struct ProviderRuntimeOptions {
     pub auth_profile_override: Option<String>,
     pub zeroclaw_dir: Option<PathBuf>,
     pub secrets_encrypt: bool,
+    /// Request header overrides by provider name (`[provider.overrides]`).
+    pub header_overrides: std::collections::HashMap<String, crate::config::ProviderHeaderOverrides>,
+    /// `reasoning_effort` requested from OpenAI-compatible reasoning models.
+    pub reasoning_effort: Option<String>,
+    /// User-registered OpenAI-compatible endpoints (`[[provider.custom]]`).
+    pub custom_providers: Vec<crate::config::CustomProviderConfig>,
+    /// Response cache wrapped around the resilient provider chain.
+    pub response_cache: crate::config::ResponseCacheConfig,
+    /// Prompt audit log every call is written to, when enabled.
+    pub prompt_audit: Option<Arc<crate::observability::prompt_audit::PromptAuditLog>>,
 }
 
 impl Default for ProviderRuntimeOptions {
@@ -360,10 +386,70 @@ impl Default for ProviderRuntimeOptions {
             auth_profile_override: None,
             zeroclaw_dir: None,
             secrets_encrypt: true,
+            header_overrides: std::collections::HashMap::new(),
+            reasoning_effort: None,
+            custom_providers: Vec::new(),
+            response_cache: crate::config::ResponseCacheConfig::default(),
+            prompt_audit: None,
         }
     }
 }
 
+impl ProviderRuntimeOptions {
+    /// Copy the `[provider]` header overrides and custom endpoints from
+    /// config into these options.
+    pub fn with_provider_config(mut self, provider: &crate::config::ProviderConfig) -> Self {
+        self.header_overrides = provider.overrides.clone();
+        self.reasoning_effort = provider.reasoning_effort.clone();
+        self.custom_providers = provider.custom.clone();
+        self.response_cache = provider.cache.clone();
+        self
+    }
+
+    /// Header overrides configured for provider `name`, if any.
+    fn header_overrides_for(&self, name: &str) -> Option<&crate::config::ProviderHeaderOverrides> {
+        self.header_overrides
+            .get(name)
+            .filter(|overrides| !overrides.is_empty())
+    }
+
+    /// Open the prompt audit log when `[observability] audit_prompts` is on.
+    pub fn with_observability_config(
+        mut self,
+        observability: &crate::config::ObservabilityConfig,
+    ) -> anyhow::Result<Self> {
+        self.prompt_audit =
+            crate::observability::prompt_audit::PromptAuditLog::from_config(observability)?
+                .map(Arc::new);
+        Ok(self)
+    }
+}
+
+/// Refuse `[provider.overrides.<name>]` for a provider that cannot send
+/// custom auth or static headers, instead of silently dropping them.
+fn reject_header_overrides(name: &str, options: &ProviderRuntimeOptions) -> anyhow::Result<()> {
+    if options.header_overrides_for(name).is_some() {
+        anyhow::bail!(
+            "[provider.overrides.{name}] is set, but provider `{name}` cannot apply custom auth \
+             or static headers. Only OpenAI-compatible providers (including custom endpoints) \
+             support them."
+        );
+    }
+    Ok(())
+}
+
+/// Wrap `provider` in the prompt audit log, if one is configured.
+fn with_prompt_audit(
+    name: &str,
+    provider: Box<dyn Provider>,
+    options: &ProviderRuntimeOptions,
+) -> Box<dyn Provider> {
+    match &options.prompt_audit {
+        Some(log) => Box::new(audit::AuditingProvider::new(name, provider, log.clone())),
+        None => provider,
+    }
+}
+
 fn is_secret_char(c: char) -> bool {
     c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')
 }
@@ -438,15 +524,41 @@ pub fn sanitize_api_error(input: &str) -> String {
     format!("{}...", &scrubbed[..end])
 }
 
-/// Build a sanitized provider error from a failed HTTP response.
+/// Build a sanitized, classified [`ProviderError`] from a failed HTTP
+/// response, with any retry delay advised by the response headers attached.
 pub async fn api_error(provider: &str, response: reqwest::Response) -> anyhow::Error {
     let status = response.status();
+    let retry_after = rate_limit::retry_advice(response.headers(), std::time::SystemTime::now());
     let body = response
         .text()
         .await
         .unwrap_or_else(|_| "<failed to read provider error body>".to_string());
     let sanitized = sanitize_api_error(&body);
-    anyhow::anyhow!("{provider} API error ({status}): {sanitized}")
+    anyhow::Error::new(ProviderError::from_response(
+        provider,
+        status,
+        &sanitized,
+        retry_after,
+    ))
+}
+
+/// Whether a provider error means the request did not fit the model's
+/// context window. These come back as plain 400s (413 on some gateways), so
+/// they are told apart from other bad requests by the provider's wording.
+pub fn is_context_length_error(err: &anyhow::Error) -> bool {
+    match ProviderError::find(err) {
+        Some(typed) => matches!(typed, ProviderError::ContextLengthExceeded { .. }),
+        None => error::mentions_context_length(&format!("{err:#}")),
+    }
+}
+
+/// Whether `err` says the requested model does not exist or is not served
+/// right now, as opposed to a problem with the request or the provider.
+pub fn is_model_unavailable_error(err: &anyhow::Error) -> bool {
+    match ProviderError::find(err) {
+        Some(typed) => matches!(typed, ProviderError::ModelNotFound { .. }),
+        None => error::mentions_model_unavailable(&format!("{err:#}")),
+    }
 }
 
 /// Resolve API key for a provider from config and environment variables.
@@ -582,53 +694,89 @@ pub fn create_provider_with_options(
 ) -> anyhow::Result<Box<dyn Provider>> {
     match name {
         "openai-codex" | "openai_codex" | "codex" => {
+            reject_header_overrides(name, options)?;
             Ok(Box::new(openai_codex::OpenAiCodexProvider::new(options)))
         }
-        _ => create_provider_with_url(name, api_key, None),
+        _ => create_provider_with_url_and_options(name, api_key, None, options),
     }
 }
 
 /// Factory: create the right provider from config with optional custom base URL
-#[allow(clippy::too_many_lines)]
 pub fn create_provider_with_url(
     name: &str,
     api_key: Option<&str>,
     api_url: Option<&str>,
+) -> anyhow::Result<Box<dyn Provider>> {
+    create_provider_with_url_and_options(name, api_key, api_url, &ProviderRuntimeOptions::default())
+}
+
+/// Factory: create provider with optional custom base URL and runtime options.
+/// Header overrides configured for `name` apply to OpenAI-compatible
+/// providers; other providers refuse them.
+#[allow(clippy::too_many_lines)]
+fn create_provider_with_url_and_options(
+    name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    options: &ProviderRuntimeOptions,
 ) -> anyhow::Result<Box<dyn Provider>> {
     let resolved_credential = resolve_provider_credential(name, api_key);
     #[allow(clippy::option_as_ref_deref)]
     let key = resolved_credential.as_ref().map(String::as_str);
+    let overrides = options.header_overrides_for(name);
+    let compat = |provider: OpenAiCompatibleProvider| -> Box<dyn Provider> {
+        let provider = match overrides {
+            Some(overrides) => provider.with_header_overrides(overrides),
+            None => provider,
+        };
+        Box::new(provider.with_reasoning_effort(options.reasoning_effort.as_deref()))
+    };
+    // Providers with their own request format have no place for header overrides.
+    let native = |provider: Box<dyn Provider>| -> anyhow::Result<Box<dyn Provider>> {
+        reject_header_overrides(name, options)?;
+        Ok(provider)
+    };
+    if let Some(entry) = custom::find(&options.custom_providers, name) {
+        let provider = custom::CustomProvider::new(entry)?;
+        let provider = match overrides {
+            Some(overrides) => provider.with_header_overrides(overrides),
+            None => provider,
+        };
+        return Ok(Box::new(
+            provider.with_reasoning_effort(options.reasoning_effort.as_deref()),
+        ));
+    }
     match name {
         // ── Primary providers (custom implementations) ───────
-        "openrouter" => Ok(Box::new(openrouter::OpenRouterProvider::new(key))),
-        "anthropic" => Ok(Box::new(anthropic::AnthropicProvider::new(key))),
-        "openai" => Ok(Box::new(openai::OpenAiProvider::with_base_url(api_url, key))),
+        "openrouter" => native(Box::new(openrouter::OpenRouterProvider::new(key))),
+        "anthropic" => native(Box::new(anthropic::AnthropicProvider::new(key))),
+        "openai" => native(Box::new(openai::OpenAiProvider::with_base_url(api_url, key))),
         // Ollama uses api_url for custom base URL (e.g. remote Ollama instance)
-        "ollama" => Ok(Box::new(ollama::OllamaProvider::new(api_url, key))),
+        "ollama" => native(Box::new(ollama::OllamaProvider::new(api_url, key))),
         "gemini" | "google" | "google-gemini" => {
-            Ok(Box::new(gemini::GeminiProvider::new(key)))
+            native(Box::new(gemini::GeminiProvider::new(key)))
         }
 
         // ── OpenAI-compatible providers ──────────────────────
-        "venice" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "venice" => Ok(compat(OpenAiCompatibleProvider::new(
             "Venice", "https://api.venice.ai", key, AuthStyle::Bearer,
         ))),
-        "vercel" | "vercel-ai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "vercel" | "vercel-ai" => Ok(compat(OpenAiCompatibleProvider::new(
             "Vercel AI Gateway", "https://api.vercel.ai", key, AuthStyle::Bearer,
         ))),
-        "cloudflare" | "cloudflare-ai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "cloudflare" | "cloudflare-ai" => Ok(compat(OpenAiCompatibleProvider::new(
             "Cloudflare AI Gateway",
             "https://gateway.ai.cloudflare.com/v1",
             key,
             AuthStyle::Bearer,
         ))),
-        name if moonshot_base_url(name).is_some() => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if moonshot_base_url(name).is_some() => Ok(compat(OpenAiCompatibleProvider::new(
             "Moonshot",
             moonshot_base_url(name).expect("checked in guard"),
             key,
             AuthStyle::Bearer,
         ))),
-        "kimi-code" | "kimi_coding" | "kimi_for_coding" => Ok(Box::new(
+        "kimi-code" | "kimi_coding" | "kimi_for_coding" => Ok(compat(
             OpenAiCompatibleProvider::new_with_user_agent(
                 "Kimi Code",
                 "https://api.kimi.com/coding/v1",
@@ -637,42 +785,42 @@ pub fn create_provider_with_url(
                 "KimiCLI/0.77",
             ),
         )),
-        "synthetic" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "synthetic" => Ok(compat(OpenAiCompatibleProvider::new(
             "Synthetic", "https://api.synthetic.com", key, AuthStyle::Bearer,
         ))),
-        "opencode" | "opencode-zen" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "opencode" | "opencode-zen" => Ok(compat(OpenAiCompatibleProvider::new(
             "OpenCode Zen", "https://opencode.ai/zen/v1", key, AuthStyle::Bearer,
         ))),
-        name if zai_base_url(name).is_some() => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if zai_base_url(name).is_some() => Ok(compat(OpenAiCompatibleProvider::new(
             "Z.AI",
             zai_base_url(name).expect("checked in guard"),
             key,
             AuthStyle::Bearer,
         ))),
         name if glm_base_url(name).is_some() => {
-            Ok(Box::new(OpenAiCompatibleProvider::new_no_responses_fallback(
+            Ok(compat(OpenAiCompatibleProvider::new_no_responses_fallback(
                 "GLM",
                 glm_base_url(name).expect("checked in guard"),
                 key,
                 AuthStyle::Bearer,
             )))
         }
-        name if minimax_base_url(name).is_some() => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if minimax_base_url(name).is_some() => Ok(compat(OpenAiCompatibleProvider::new(
             "MiniMax",
             minimax_base_url(name).expect("checked in guard"),
             key,
             AuthStyle::Bearer,
         ))),
-        "bedrock" | "aws-bedrock" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "bedrock" | "aws-bedrock" => Ok(compat(OpenAiCompatibleProvider::new(
             "Amazon Bedrock",
             "https://bedrock-runtime.us-east-1.amazonaws.com",
             key,
             AuthStyle::Bearer,
         ))),
-        name if is_qianfan_alias(name) => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if is_qianfan_alias(name) => Ok(compat(OpenAiCompatibleProvider::new(
             "Qianfan", "https://aip.baidubce.com", key, AuthStyle::Bearer,
         ))),
-        name if qwen_base_url(name).is_some() => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if qwen_base_url(name).is_some() => Ok(compat(OpenAiCompatibleProvider::new(
             "Qwen",
             qwen_base_url(name).expect("checked in guard"),
             key,
@@ -680,46 +828,46 @@ pub fn create_provider_with_url(
         ))),
 
         // ── Extended ecosystem (community favorites) ─────────
-        "groq" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "groq" => Ok(compat(OpenAiCompatibleProvider::new(
             "Groq", "https://api.groq.com/openai", key, AuthStyle::Bearer,
         ))),
-        "mistral" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "mistral" => Ok(compat(OpenAiCompatibleProvider::new(
             "Mistral", "https://api.mistral.ai/v1", key, AuthStyle::Bearer,
         ))),
-        "xai" | "grok" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "xai" | "grok" => Ok(compat(OpenAiCompatibleProvider::new(
             "xAI", "https://api.x.ai", key, AuthStyle::Bearer,
         ))),
-        "deepseek" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "deepseek" => Ok(compat(OpenAiCompatibleProvider::new(
             "DeepSeek", "https://api.deepseek.com", key, AuthStyle::Bearer,
         ))),
-        "together" | "together-ai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "together" | "together-ai" => Ok(compat(OpenAiCompatibleProvider::new(
             "Together AI", "https://api.together.xyz", key, AuthStyle::Bearer,
         ))),
-        "fireworks" | "fireworks-ai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "fireworks" | "fireworks-ai" => Ok(compat(OpenAiCompatibleProvider::new(
             "Fireworks AI", "https://api.fireworks.ai/inference/v1", key, AuthStyle::Bearer,
         ))),
-        "perplexity" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "perplexity" => Ok(compat(OpenAiCompatibleProvider::new(
             "Perplexity", "https://api.perplexity.ai", key, AuthStyle::Bearer,
         ))),
-        "cohere" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "cohere" => Ok(compat(OpenAiCompatibleProvider::new(
             "Cohere", "https://api.cohere.com/compatibility", key, AuthStyle::Bearer,
         ))),
         "copilot" | "github-copilot" => {
-            Ok(Box::new(copilot::CopilotProvider::new(api_key)))
+            native(Box::new(copilot::CopilotProvider::new(api_key)))
         },
         "lmstudio" | "lm-studio" => {
             let lm_studio_key = api_key
                 .map(str::trim)
                 .filter(|value| !value.is_empty())
                 .unwrap_or("lm-studio");
-            Ok(Box::new(OpenAiCompatibleProvider::new(
+            Ok(compat(OpenAiCompatibleProvider::new(
                 "LM Studio",
                 "http://localhost:1234/v1",
                 Some(lm_studio_key),
                 AuthStyle::Bearer,
             )))
         }
-        "nvidia" | "nvidia-nim" | "build.nvidia.com" => Ok(Box::new(
+        "nvidia" | "nvidia-nim" | "build.nvidia.com" => Ok(compat(
             OpenAiCompatibleProvider::new(
                 "NVIDIA NIM",
                 "https://integrate.api.nvidia.com/v1",
@@ -729,12 +877,12 @@ pub fn create_provider_with_url(
         )),
 
         // ── AI inference routers ─────────────────────────────
-        "astrai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "astrai" => Ok(compat(OpenAiCompatibleProvider::new(
             "Astrai", "https://as-trai.com/v1", key, AuthStyle::Bearer,
         ))),
 
         // ── Cloud AI endpoints ───────────────────────────────
-        "ovhcloud" | "ovh" => Ok(Box::new(openai::OpenAiProvider::with_base_url(
+        "ovhcloud" | "ovh" => native(Box::new(openai::OpenAiProvider::with_base_url(
             Some("https://oai.endpoints.kepler.ai.cloud.ovh.net/v1"),
             key,
         ))),
@@ -747,7 +895,7 @@ pub fn create_provider_with_url(
                 "Custom provider",
                 "custom:https://your-api.com",
             )?;
-            Ok(Box::new(OpenAiCompatibleProvider::new(
+            Ok(compat(OpenAiCompatibleProvider::new(
                 "Custom",
                 &base_url,
                 key,
@@ -763,7 +911,7 @@ pub fn create_provider_with_url(
                 "Anthropic-custom provider",
                 "anthropic-custom:https://your-api.com",
             )?;
-            Ok(Box::new(anthropic::AnthropicProvider::with_base_url(
+            native(Box::new(anthropic::AnthropicProvider::with_base_url(
                 key,
                 Some(&base_url),
             )))
@@ -793,6 +941,21 @@ pub fn create_resilient_provider(
     )
 }
 
+/// Wrap `provider` so a model-unavailable error is retried with
+/// `[reliability] fallback_model`, when one is configured.
+fn with_fallback_model(
+    name: &str,
+    provider: Box<dyn Provider>,
+    reliability: &crate::config::ReliabilityConfig,
+) -> Box<dyn Provider> {
+    match reliability.fallback_model.as_deref().map(str::trim) {
+        Some(fallback) if !fallback.is_empty() => Box::new(
+            model_fallback::ModelFallbackProvider::new(name, provider, fallback),
+        ),
+        _ => provider,
+    }
+}
+
 /// Create provider chain with retry/fallback behavior and auth runtime options.
 pub fn create_resilient_provider_with_options(
     primary_name: &str,
@@ -807,9 +970,12 @@ pub fn create_resilient_provider_with_options(
         "openai-codex" | "openai_codex" | "codex" => {
             create_provider_with_options(primary_name, api_key, options)?
         }
-        _ => create_provider_with_url(primary_name, api_key, api_url)?,
+        _ => create_provider_with_url_and_options(primary_name, api_key, api_url, options)?,
     };
-    providers.push((primary_name.to_string(), primary_provider));
+    providers.push((
+        primary_name.to_string(),
+        with_fallback_model(primary_name, primary_provider, reliability),
+    ));
 
     for fallback in &reliability.fallback_providers {
         if fallback == primary_name || providers.iter().any(|(name, _)| name == fallback) {
@@ -818,7 +984,13 @@ pub fn create_resilient_provider_with_options(
 
         // Fallback providers don't use the custom api_url (it's specific to primary).
         match create_provider_with_options(fallback, api_key, options) {
-            Ok(provider) => providers.push((fallback.clone(), provider)),
+            Ok(provider) => providers.push((
+                fallback.clone(),
+                with_fallback_model(fallback, provider, reliability),
+            )),
+            // Overrides configured for this provider must not be dropped
+            // along with it.
+            Err(error) if options.header_overrides_for(fallback).is_some() => return Err(error),
             Err(_error) => {
                 tracing::warn!(
                     fallback_provider = fallback,
@@ -834,9 +1006,22 @@ pub fn create_resilient_provider_with_options(
         reliability.provider_backoff_ms,
     )
     .with_api_keys(reliability.api_keys.clone())
-    .with_model_fallbacks(reliability.model_fallbacks.clone());
-
-    Ok(Box::new(reliable))
+    .with_model_fallbacks(reliability.model_fallbacks.clone())
+    .with_circuit_breaker(circuit_breaker::CircuitBreakerSettings::from_config(
+        reliability,
+    ));
+
+    // Audit outside the cache so answers served from it are recorded too.
+    let provider: Box<dyn Provider> = if options.response_cache.enabled {
+        Box::new(cache::CachingProvider::new(
+            primary_name,
+            Box::new(reliable),
+            &options.response_cache,
+        ))
+    } else {
+        Box::new(reliable)
+    };
+    Ok(with_prompt_audit(primary_name, provider, options))
 }
 
 /// Create a RouterProvider if model routes are configured, otherwise return a
@@ -849,9 +1034,37 @@ pub fn create_routed_provider(
     reliability: &crate::config::ReliabilityConfig,
     model_routes: &[crate::config::ModelRouteConfig],
     default_model: &str,
+) -> anyhow::Result<Box<dyn Provider>> {
+    create_routed_provider_with_options(
+        primary_name,
+        api_key,
+        api_url,
+        reliability,
+        model_routes,
+        default_model,
+        &ProviderRuntimeOptions::default(),
+    )
+}
+
+/// Same as [`create_routed_provider`], with runtime options applied to every
+/// routed provider.
+pub fn create_routed_provider_with_options(
+    primary_name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    reliability: &crate::config::ReliabilityConfig,
+    model_routes: &[crate::config::ModelRouteConfig],
+    default_model: &str,
+    options: &ProviderRuntimeOptions,
 ) -> anyhow::Result<Box<dyn Provider>> {
     if model_routes.is_empty() {
-        return create_resilient_provider(primary_name, api_key, api_url, reliability);
+        return create_resilient_provider_with_options(
+            primary_name,
+            api_key,
+            api_url,
+            reliability,
+            options,
+        );
     }
 
     // Collect unique provider names needed
@@ -862,7 +1075,12 @@ pub fn create_routed_provider(
         }
     }
 
-    // Create each provider (with its own resilience wrapper)
+    // Create each provider (with its own resilience wrapper); the router as
+    // a whole is audited once below.
+    let route_options = ProviderRuntimeOptions {
+        prompt_audit: None,
+        ..options.clone()
+    };
     let mut providers: Vec<(String, Box<dyn Provider>)> = Vec::new();
     for name in &needed {
         let routed_credential = model_routes
@@ -877,10 +1095,10 @@ pub fn create_routed_provider(
         let key = routed_credential.or(api_key);
         // Only use api_url for the primary provider
         let url = if name == primary_name { api_url } else { None };
-        match create_resilient_provider(name, key, url, reliability) {
+        match create_resilient_provider_with_options(name, key, url, reliability, &route_options) {
             Ok(provider) => providers.push((name.clone(), provider)),
             Err(e) => {
-                if name == primary_name {
+                if name == primary_name || route_options.header_overrides_for(name).is_some() {
                     return Err(e);
                 }
                 tracing::warn!(
@@ -905,11 +1123,12 @@ pub fn create_routed_provider(
         })
         .collect();
 
-    Ok(Box::new(router::RouterProvider::new(
+    let router = Box::new(router::RouterProvider::new(
         providers,
         routes,
         default_model.to_string(),
-    )))
+    ));
+    Ok(with_prompt_audit(primary_name, router, options))
 }
 
 /// Information about a supported provider for display purposes.
@@ -1646,6 +1865,9 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            circuit_breaker_threshold: 5,
+            circuit_breaker_window_secs: 60,
+            circuit_breaker_cooldown_secs: 30,
         };
 
         let provider = create_resilient_provider(
@@ -1669,6 +1891,66 @@ mod tests {
         assert!(provider.is_err());
     }
 
+    fn options_with_overrides_for(name: &str) -> ProviderRuntimeOptions {
+        let overrides = crate::config::ProviderHeaderOverrides {
+            auth_header: Some("api-key".into()),
+            ..crate::config::ProviderHeaderOverrides::default()
+        };
+        ProviderRuntimeOptions {
+            header_overrides: std::iter::once((name.to_string(), overrides)).collect(),
+            ..ProviderRuntimeOptions::default()
+        }
+    }
+
+    #[test]
+    fn header_overrides_are_refused_by_providers_that_cannot_send_them() {
+        for name in [
+            "openrouter",
+            "anthropic",
+            "openai",
+            "gemini",
+            "openai-codex",
+        ] {
+            let options = options_with_overrides_for(name);
+            let err = create_provider_with_options(name, Some("key"), &options)
+                .err()
+                .unwrap_or_else(|| panic!("{name} accepted header overrides"));
+            assert!(err.to_string().contains("[provider.overrides."), "{err}");
+        }
+        let options = options_with_overrides_for("groq");
+        assert!(create_provider_with_options("groq", Some("key"), &options).is_ok());
+    }
+
+    #[test]
+    fn header_overrides_only_apply_to_their_provider_in_the_chain() {
+        let reliability = crate::config::ReliabilityConfig {
+            fallback_providers: vec!["anthropic".into()],
+            ..crate::config::ReliabilityConfig::default()
+        };
+        // groq's overrides are not passed on to the anthropic fallback.
+        let options = options_with_overrides_for("groq");
+        assert!(create_resilient_provider_with_options(
+            "groq",
+            Some("key"),
+            None,
+            &reliability,
+            &options
+        )
+        .is_ok());
+
+        // Overrides for a fallback that cannot apply them are an error, not
+        // a skipped fallback.
+        let options = options_with_overrides_for("anthropic");
+        assert!(create_resilient_provider_with_options(
+            "groq",
+            Some("key"),
+            None,
+            &reliability,
+            &options
+        )
+        .is_err());
+    }
+
     #[test]
     fn ollama_with_custom_url() {
         let provider = create_provider_with_url("ollama", None, Some("http://10.100.2.32:11434"));
@@ -1871,6 +2153,49 @@ mod tests {
         assert_eq!(result, input);
     }
 
+    #[test]
+    fn context_length_errors_are_told_apart_from_other_400s() {
+        for message in [
+            "OpenAI API error (400 Bad Request): This model's maximum context length is 8192 tokens",
+            "Anthropic API error (400 Bad Request): prompt is too long: 210000 tokens > 200000 maximum",
+            "{\"error\":{\"code\":\"context_length_exceeded\"}}",
+            "Gemini API error (400): The input token count (1200000) exceeds the maximum number of tokens allowed",
+            "Groq API error (400): Please reduce the length of the messages or completion.",
+        ] {
+            assert!(
+                is_context_length_error(&anyhow::anyhow!("{message}")),
+                "{message}"
+            );
+        }
+        assert!(!is_context_length_error(&anyhow::anyhow!(
+            "OpenAI API error (400 Bad Request): invalid value for 'temperature'"
+        )));
+        assert!(!is_context_length_error(&anyhow::anyhow!(
+            "429 Too Many Requests: rate limit reached"
+        )));
+    }
+
+    #[test]
+    fn model_unavailable_errors_are_recognized() {
+        for message in [
+            "OpenAI API error (404): {\"error\":{\"code\":\"model_not_found\"}}",
+            "Anthropic API error (404 Not Found): model: claude-9 not found",
+            "Groq API error (400): The model `llama-2-70b` has been decommissioned",
+            "OpenRouter API error (503): Model is currently unavailable",
+        ] {
+            assert!(
+                is_model_unavailable_error(&anyhow::anyhow!("{message}")),
+                "{message}"
+            );
+        }
+        assert!(!is_model_unavailable_error(&anyhow::anyhow!(
+            "OpenAI API error (400 Bad Request): This model's maximum context length is 8192 tokens"
+        )));
+        assert!(!is_model_unavailable_error(&anyhow::anyhow!(
+            "503 Service Unavailable"
+        )));
+    }
+
     #[test]
     fn scrub_github_personal_access_token() {
         let input = "auth failed with token ghp_abc123def456";
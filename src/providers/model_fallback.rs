@@ -0,0 +1,258 @@
+//! Same-provider model substitution (`[reliability] fallback_model`).
+//!
+//! Unlike provider failover and `model_fallbacks`, which move on after
+//! retries are exhausted, this reacts only to "model not found/unavailable"
+//! errors: the same provider is asked again right away with the configured
+//! fallback model, and the substitution is logged.
+
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, ProviderCapabilities, StreamChunk, StreamOptions,
+    StreamResult, ToolsPayload,
+};
+use super::{is_model_unavailable_error, Provider};
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+use futures_util::stream;
+
+pub struct ModelFallbackProvider {
+    name: String,
+    inner: Box<dyn Provider>,
+    fallback_model: String,
+}
+
+impl ModelFallbackProvider {
+    pub fn new(name: &str, inner: Box<dyn Provider>, fallback_model: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            inner,
+            fallback_model: fallback_model.to_string(),
+        }
+    }
+
+    /// The model to retry with after `err` from `model`, if any.
+    fn substitute(&self, model: &str, err: &anyhow::Error) -> Option<&str> {
+        if model == self.fallback_model || !is_model_unavailable_error(err) {
+            return None;
+        }
+        tracing::warn!(
+            provider = self.name,
+            model,
+            fallback_model = self.fallback_model,
+            error = %err,
+            "Model unavailable, retrying with fallback model"
+        );
+        Some(&self.fallback_model)
+    }
+}
+
+#[async_trait]
+impl Provider for ModelFallbackProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        match self
+            .inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await
+        {
+            Err(e) => match self.substitute(model, &e) {
+                Some(fallback) => {
+                    self.inner
+                        .chat_with_system(system_prompt, message, fallback, temperature)
+                        .await
+                }
+                None => Err(e),
+            },
+            ok => ok,
+        }
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        match self
+            .inner
+            .chat_with_history(messages, model, temperature)
+            .await
+        {
+            Err(e) => match self.substitute(model, &e) {
+                Some(fallback) => {
+                    self.inner
+                        .chat_with_history(messages, fallback, temperature)
+                        .await
+                }
+                None => Err(e),
+            },
+            ok => ok,
+        }
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        match self.inner.chat(request, model, temperature).await {
+            Err(e) => match self.substitute(model, &e) {
+                Some(fallback) => self.inner.chat(request, fallback, temperature).await,
+                None => Err(e),
+            },
+            ok => ok,
+        }
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        match self
+            .inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await
+        {
+            Err(e) => match self.substitute(model, &e) {
+                Some(fallback) => {
+                    self.inner
+                        .chat_with_tools(messages, tools, fallback, temperature)
+                        .await
+                }
+                None => Err(e),
+            },
+            ok => ok,
+        }
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_system(system_prompt, message, model, temperature, options)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_history(messages, model, temperature, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    /// Fails every model except `available` with a model-not-found error.
+    struct SingleModelProvider {
+        available: &'static str,
+        seen: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Provider for SingleModelProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.seen.lock().push(model.to_string());
+            if model == self.available {
+                Ok(format!("answer from {model}"))
+            } else if model == "flaky" {
+                anyhow::bail!("OpenAI API error (500): internal server error")
+            } else {
+                anyhow::bail!(
+                    "OpenAI API error (404): {{\"error\":{{\"code\":\"model_not_found\",\"message\":\"The model `{model}` does not exist\"}}}}"
+                )
+            }
+        }
+    }
+
+    fn provider(available: &'static str) -> (ModelFallbackProvider, Arc<Mutex<Vec<String>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let inner = SingleModelProvider {
+            available,
+            seen: seen.clone(),
+        };
+        (
+            ModelFallbackProvider::new("openai", Box::new(inner), "gpt-4o-mini"),
+            seen,
+        )
+    }
+
+    #[tokio::test]
+    async fn unavailable_model_is_retried_with_fallback() {
+        let (provider, seen) = provider("gpt-4o-mini");
+        let answer = provider
+            .simple_chat("hi", "gpt-5-preview", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(answer, "answer from gpt-4o-mini");
+        assert_eq!(*seen.lock(), ["gpt-5-preview", "gpt-4o-mini"]);
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_substituted() {
+        let (provider, seen) = provider("gpt-4o-mini");
+        assert!(provider.simple_chat("hi", "flaky", 0.0).await.is_err());
+        assert_eq!(*seen.lock(), ["flaky"]);
+    }
+
+    #[tokio::test]
+    async fn unavailable_fallback_is_not_retried_again() {
+        let (provider, seen) = provider("none");
+        let err = provider
+            .simple_chat("hi", "gpt-5-preview", 0.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("gpt-4o-mini"));
+        assert_eq!(*seen.lock(), ["gpt-5-preview", "gpt-4o-mini"]);
+    }
+}
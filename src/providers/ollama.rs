@@ -101,10 +101,12 @@ impl OllamaProvider {
         }
 
         if requests_cloud && self.api_key.is_none() {
-            anyhow::bail!(
-                "Model '{}' requested cloud routing, but no API key is configured. Set OLLAMA_API_KEY or config api_key.",
-                model
-            );
+            return Err(super::ProviderError::AuthFailed {
+                message: format!(
+                    "Model '{model}' requested cloud routing, but no API key is configured. Set OLLAMA_API_KEY or config api_key."
+                ),
+            }
+            .into());
         }
 
         let should_auth = self.api_key.is_some() && !self.is_local_endpoint();
@@ -160,11 +162,15 @@ impl OllamaProvider {
                 status,
                 sanitized
             );
-            anyhow::bail!(
-                "Ollama API error ({}): {}. Is Ollama running? (brew install ollama && ollama serve)",
+            return Err(super::ProviderError::from_status(
                 status,
-                sanitized
-            );
+                &sanitized,
+                format!(
+                    "Ollama API error ({status}): {sanitized}. Is Ollama running? (brew install ollama && ollama serve)"
+                ),
+                None,
+            )
+            .into());
         }
 
         let chat_response: ApiChatResponse = match serde_json::from_slice(&body) {
@@ -1,6 +1,6 @@
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ToolCall as ProviderToolCall,
+    FinishReason, Provider, ProviderCapabilities, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -112,6 +112,8 @@ struct NativeChatResponse {
 #[derive(Debug, Deserialize)]
 struct NativeChoice {
     message: NativeResponseMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -132,6 +134,13 @@ impl NativeResponseMessage {
             _ => self.reasoning_content.clone(),
         }
     }
+
+    fn separate_reasoning(&self) -> Option<String> {
+        match &self.content {
+            Some(c) if !c.is_empty() => self.reasoning_content.clone().filter(|r| !r.is_empty()),
+            _ => None,
+        }
+    }
 }
 
 impl OpenAiProvider {
@@ -150,6 +159,14 @@ impl OpenAiProvider {
         }
     }
 
+    fn credential(&self) -> Result<&str, super::ProviderError> {
+        self.credential
+            .as_deref()
+            .ok_or_else(|| super::ProviderError::AuthFailed {
+                message: "OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.".into(),
+            })
+    }
+
     fn convert_tools(tools: Option<&[ToolSpec]>) -> Option<Vec<NativeToolSpec>> {
         tools.map(|items| {
             items
@@ -235,6 +252,7 @@ impl OpenAiProvider {
 
     fn parse_native_response(message: NativeResponseMessage) -> ProviderChatResponse {
         let text = message.effective_content();
+        let reasoning = message.separate_reasoning();
         let tool_calls = message
             .tool_calls
             .unwrap_or_default()
@@ -246,7 +264,12 @@ impl OpenAiProvider {
             })
             .collect::<Vec<_>>();
 
-        ProviderChatResponse { text, tool_calls }
+        ProviderChatResponse {
+            text,
+            tool_calls,
+            reasoning,
+            finish_reason: None,
+        }
     }
 
     fn http_client(&self) -> Client {
@@ -263,9 +286,7 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
+        let credential = self.credential()?;
 
         let mut messages = Vec::new();
 
@@ -295,6 +316,8 @@ impl Provider for OpenAiProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe("OpenAI", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("OpenAI", response).await);
         }
@@ -315,9 +338,7 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
+        let credential = self.credential()?;
 
         let tools = Self::convert_tools(request.tools);
         let native_request = NativeChatRequest {
@@ -336,22 +357,29 @@ impl Provider for OpenAiProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe("OpenAI", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("OpenAI", response).await);
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        let message = native_response
+        let choice = native_response
             .choices
             .into_iter()
             .next()
-            .map(|c| c.message)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))?;
-        Ok(Self::parse_native_response(message))
+        let mut chat_response = Self::parse_native_response(choice.message);
+        chat_response.finish_reason = choice.finish_reason.as_deref().map(FinishReason::parse);
+        Ok(chat_response)
     }
 
-    fn supports_native_tools(&self) -> bool {
-        true
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            embeddings: true,
+            ..ProviderCapabilities::default()
+        }
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -351,18 +351,14 @@ impl Provider for OpenAiCodexProvider {
             .auth
             .get_valid_openai_access_token(self.auth_profile_override.as_deref())
             .await?
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "OpenAI Codex auth profile not found. Run `zeroclaw auth login --provider openai-codex`."
-                )
+            .ok_or_else(|| super::ProviderError::AuthFailed {
+                message: "OpenAI Codex auth profile not found. Run `zeroclaw auth login --provider openai-codex`.".into(),
             })?;
         let account_id = profile
             .and_then(|profile| profile.account_id)
             .or_else(|| extract_account_id_from_jwt(&access_token))
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "OpenAI Codex account id not found in auth profile/token. Run `zeroclaw auth login --provider openai-codex` again."
-                )
+            .ok_or_else(|| super::ProviderError::AuthFailed {
+                message: "OpenAI Codex account id not found in auth profile/token. Run `zeroclaw auth login --provider openai-codex` again.".into(),
             })?;
         let normalized_model = normalize_model_id(model);
 
@@ -403,6 +399,8 @@ impl Provider for OpenAiCodexProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe("OpenAI Codex", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("OpenAI Codex", response).await);
         }
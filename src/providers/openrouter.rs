@@ -1,6 +1,6 @@
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ToolCall as ProviderToolCall,
+    FinishReason, Provider, ProviderCapabilities, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -98,12 +98,17 @@ struct NativeChatResponse {
 #[derive(Debug, Deserialize)]
 struct NativeChoice {
     message: NativeResponseMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct NativeResponseMessage {
     #[serde(default)]
     content: Option<String>,
+    /// Reasoning tokens returned by thinking models.
+    #[serde(default)]
+    reasoning: Option<String>,
     #[serde(default)]
     tool_calls: Option<Vec<NativeToolCall>>,
 }
@@ -115,6 +120,14 @@ impl OpenRouterProvider {
         }
     }
 
+    fn credential(&self) -> Result<&str, super::ProviderError> {
+        self.credential
+            .as_deref()
+            .ok_or_else(|| super::ProviderError::AuthFailed {
+                message: "OpenRouter API key not set. Run `zeroclaw onboard` or set OPENROUTER_API_KEY env var.".into(),
+            })
+    }
+
     fn convert_tools(tools: Option<&[ToolSpec]>) -> Option<Vec<NativeToolSpec>> {
         let items = tools?;
         if items.is_empty() {
@@ -217,6 +230,8 @@ impl OpenRouterProvider {
         ProviderChatResponse {
             text: message.content,
             tool_calls,
+            reasoning: message.reasoning.filter(|r| !r.is_empty()),
+            finish_reason: None,
         }
     }
 
@@ -248,8 +263,7 @@ impl Provider for OpenRouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let credential = self.credential.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("OpenRouter API key not set. Run `zeroclaw onboard` or set OPENROUTER_API_KEY env var."))?;
+        let credential = self.credential()?;
 
         let mut messages = Vec::new();
 
@@ -284,6 +298,8 @@ impl Provider for OpenRouterProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe("OpenRouter", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("OpenRouter", response).await);
         }
@@ -304,8 +320,7 @@ impl Provider for OpenRouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let credential = self.credential.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("OpenRouter API key not set. Run `zeroclaw onboard` or set OPENROUTER_API_KEY env var."))?;
+        let credential = self.credential()?;
 
         let api_messages: Vec<Message> = messages
             .iter()
@@ -334,6 +349,8 @@ impl Provider for OpenRouterProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe("OpenRouter", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("OpenRouter", response).await);
         }
@@ -354,11 +371,7 @@ impl Provider for OpenRouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-            "OpenRouter API key not set. Run `zeroclaw onboard` or set OPENROUTER_API_KEY env var."
-        )
-        })?;
+        let credential = self.credential()?;
 
         let tools = Self::convert_tools(request.tools);
         let native_request = NativeChatRequest {
@@ -382,22 +395,28 @@ impl Provider for OpenRouterProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe("OpenRouter", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("OpenRouter", response).await);
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        let message = native_response
+        let choice = native_response
             .choices
             .into_iter()
             .next()
-            .map(|c| c.message)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenRouter"))?;
-        Ok(Self::parse_native_response(message))
+        let mut chat_response = Self::parse_native_response(choice.message);
+        chat_response.finish_reason = choice.finish_reason.as_deref().map(FinishReason::parse);
+        Ok(chat_response)
     }
 
-    fn supports_native_tools(&self) -> bool {
-        true
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            ..ProviderCapabilities::default()
+        }
     }
 
     async fn chat_with_tools(
@@ -407,11 +426,7 @@ impl Provider for OpenRouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "OpenRouter API key not set. Run `zeroclaw onboard` or set OPENROUTER_API_KEY env var."
-            )
-        })?;
+        let credential = self.credential()?;
 
         // Convert tool JSON values to NativeToolSpec
         let native_tools: Option<Vec<NativeToolSpec>> = if tools.is_empty() {
@@ -470,18 +485,21 @@ impl Provider for OpenRouterProvider {
             .send()
             .await?;
 
+        super::rate_limit::observe("OpenRouter", response.headers());
+
         if !response.status().is_success() {
             return Err(super::api_error("OpenRouter", response).await);
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        let message = native_response
+        let choice = native_response
             .choices
             .into_iter()
             .next()
-            .map(|c| c.message)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenRouter"))?;
-        Ok(Self::parse_native_response(message))
+        let mut chat_response = Self::parse_native_response(choice.message);
+        chat_response.finish_reason = choice.finish_reason.as_deref().map(FinishReason::parse);
+        Ok(chat_response)
     }
 }
 
@@ -704,6 +722,7 @@ mod tests {
                     arguments: r#"{"path":"test.txt"}"#.into(),
                 },
             }]),
+            reasoning: None,
         };
 
         let response = OpenRouterProvider::parse_native_response(message);
@@ -714,6 +733,16 @@ mod tests {
         assert_eq!(response.tool_calls[0].name, "file_read");
     }
 
+    #[test]
+    fn parse_native_response_keeps_reasoning_field() {
+        let message: NativeResponseMessage =
+            serde_json::from_str(r#"{"content":"Paris.","reasoning":"Capital of France."}"#)
+                .unwrap();
+        let response = OpenRouterProvider::parse_native_response(message);
+        assert_eq!(response.text.as_deref(), Some("Paris."));
+        assert_eq!(response.reasoning.as_deref(), Some("Capital of France."));
+    }
+
     #[test]
     fn convert_messages_parses_assistant_tool_call_payload() {
         let messages = vec![ChatMessage {
@@ -0,0 +1,459 @@
+//! Rate-limit headers returned by provider APIs.
+//!
+//! On a failed request the advised wait (`Retry-After`, `retry-after-ms`,
+//! `x-ratelimit-reset*`, `anthropic-ratelimit-*-reset`) is attached to the
+//! error's [`ProviderError`] so the reliable wrapper can sleep for at
+//! least that long. On every response the remaining/limit counters are kept
+//! as the latest [`RateLimitSnapshot`], which the agent loop reports as an
+//! `ObserverEvent::RateLimit`.
+
+use super::ProviderError;
+use reqwest::header::HeaderMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Values above this in `x-ratelimit-reset` are Unix timestamps, not seconds.
+const UNIX_TIMESTAMP_THRESHOLD: f64 = 1_000_000_000.0;
+
+/// A wait advised by the provider and the header it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryAdvice {
+    pub delay: Duration,
+    pub source: &'static str,
+}
+
+/// Request/token budget reported by the provider.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub remaining_requests: Option<u64>,
+    pub limit_requests: Option<u64>,
+    pub remaining_tokens: Option<u64>,
+    pub limit_tokens: Option<u64>,
+}
+
+/// The most recent [`RateLimitStatus`] seen, and which provider sent it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitSnapshot {
+    pub provider: String,
+    pub status: RateLimitStatus,
+}
+
+static LATEST: Mutex<Option<RateLimitSnapshot>> = Mutex::new(None);
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+fn header_u64(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names
+        .iter()
+        .find_map(|name| header(headers, name).and_then(|v| v.parse().ok()))
+}
+
+fn seconds(secs: f64) -> Option<Duration> {
+    (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+fn until(target: SystemTime, now: SystemTime) -> Duration {
+    target.duration_since(now).unwrap_or(Duration::ZERO)
+}
+
+/// `Retry-After`: delta seconds or an HTTP-date.
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    if let Ok(secs) = value.parse::<f64>() {
+        return seconds(secs);
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(until(SystemTime::from(date), now))
+}
+
+/// Go-style durations used by OpenAI: `1s`, `6m0s`, `20ms`, `1h2m3.5s`.
+fn parse_go_duration(value: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            "us" | "µs" => 0.000_001,
+            "ns" => 0.000_000_001,
+            _ => return None,
+        };
+        total += number * scale;
+        rest = &rest[unit_len..];
+    }
+    seconds(total)
+}
+
+/// `x-ratelimit-reset`: seconds until reset, a Unix timestamp, or RFC 3339.
+fn parse_reset(value: &str, now: SystemTime) -> Option<Duration> {
+    if let Ok(number) = value.parse::<f64>() {
+        if number > UNIX_TIMESTAMP_THRESHOLD {
+            return Some(until(SystemTime::UNIX_EPOCH + seconds(number)?, now));
+        }
+        return seconds(number);
+    }
+    if let Some(duration) = parse_go_duration(value) {
+        return Some(duration);
+    }
+    let date = chrono::DateTime::parse_from_rfc3339(value).ok()?;
+    Some(until(SystemTime::from(date), now))
+}
+
+/// The wait a failed response advises. Explicit `Retry-After` forms win;
+/// otherwise the reset time of an exhausted budget (or the soonest reset if
+/// none is reported as exhausted).
+pub fn retry_advice(headers: &HeaderMap, now: SystemTime) -> Option<RetryAdvice> {
+    if let Some(ms) = header(headers, "retry-after-ms").and_then(|v| v.parse::<f64>().ok()) {
+        if let Some(delay) = seconds(ms / 1000.0) {
+            return Some(RetryAdvice {
+                delay,
+                source: "retry-after-ms",
+            });
+        }
+    }
+    if let Some(delay) = header(headers, "retry-after").and_then(|v| parse_retry_after(v, now)) {
+        return Some(RetryAdvice {
+            delay,
+            source: "retry-after",
+        });
+    }
+
+    // (reset header, matching remaining header)
+    const RESETS: [(&str, &str); 6] = [
+        ("x-ratelimit-reset", "x-ratelimit-remaining"),
+        (
+            "x-ratelimit-reset-requests",
+            "x-ratelimit-remaining-requests",
+        ),
+        ("x-ratelimit-reset-tokens", "x-ratelimit-remaining-tokens"),
+        (
+            "anthropic-ratelimit-requests-reset",
+            "anthropic-ratelimit-requests-remaining",
+        ),
+        (
+            "anthropic-ratelimit-tokens-reset",
+            "anthropic-ratelimit-tokens-remaining",
+        ),
+        (
+            "anthropic-ratelimit-input-tokens-reset",
+            "anthropic-ratelimit-input-tokens-remaining",
+        ),
+    ];
+    let candidates: Vec<(RetryAdvice, bool)> = RESETS
+        .iter()
+        .filter_map(|(reset, remaining)| {
+            let delay = parse_reset(header(headers, reset)?, now)?;
+            let exhausted = header(headers, remaining) == Some("0");
+            Some((
+                RetryAdvice {
+                    delay,
+                    source: reset,
+                },
+                exhausted,
+            ))
+        })
+        .collect();
+
+    let exhausted = candidates
+        .iter()
+        .filter(|(_, exhausted)| *exhausted)
+        .map(|(advice, _)| *advice)
+        .max_by_key(|advice| advice.delay);
+    exhausted.or_else(|| {
+        candidates
+            .iter()
+            .map(|(advice, _)| *advice)
+            .min_by_key(|advice| advice.delay)
+    })
+}
+
+impl RateLimitStatus {
+    /// Budget counters from OpenAI-style, Anthropic or generic headers;
+    /// `None` when the response carries none.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let status = Self {
+            remaining_requests: header_u64(
+                headers,
+                &[
+                    "x-ratelimit-remaining-requests",
+                    "anthropic-ratelimit-requests-remaining",
+                    "x-ratelimit-remaining",
+                ],
+            ),
+            limit_requests: header_u64(
+                headers,
+                &[
+                    "x-ratelimit-limit-requests",
+                    "anthropic-ratelimit-requests-limit",
+                    "x-ratelimit-limit",
+                ],
+            ),
+            remaining_tokens: header_u64(
+                headers,
+                &[
+                    "x-ratelimit-remaining-tokens",
+                    "anthropic-ratelimit-tokens-remaining",
+                ],
+            ),
+            limit_tokens: header_u64(
+                headers,
+                &[
+                    "x-ratelimit-limit-tokens",
+                    "anthropic-ratelimit-tokens-limit",
+                ],
+            ),
+        };
+        (status != Self::default()).then_some(status)
+    }
+}
+
+/// Remember the budget reported by a response from `provider`.
+pub fn observe(provider: &str, headers: &HeaderMap) {
+    if let Some(status) = RateLimitStatus::from_headers(headers) {
+        *LATEST.lock().unwrap_or_else(|e| e.into_inner()) = Some(RateLimitSnapshot {
+            provider: provider.to_string(),
+            status,
+        });
+    }
+}
+
+/// The budget reported since the last call, if any.
+pub fn take_latest() -> Option<RateLimitSnapshot> {
+    LATEST.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// The advised wait attached to `err` by a provider, if any.
+pub fn retry_advice_from_error(err: &anyhow::Error) -> Option<RetryAdvice> {
+    ProviderError::find(err).and_then(ProviderError::retry_after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::Provider;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    /// Serve one canned HTTP response and return the base URL.
+    async fn serve_once(
+        status: &'static str,
+        extra_headers: Vec<(&'static str, String)>,
+    ) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(head_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..head_end]
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= head_end + 4 + content_length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+
+            let body = r#"{"error":{"message":"Rate limit reached"}}"#;
+            let mut response = format!("HTTP/1.1 {status}\r\n");
+            for (name, value) in extra_headers {
+                response.push_str(&format!("{name}: {value}\r\n"));
+            }
+            response.push_str(&format!(
+                "content-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            ));
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        format!("http://{addr}")
+    }
+
+    async fn mock_429_error(extra_headers: Vec<(&'static str, String)>) -> anyhow::Error {
+        let base = serve_once("429 Too Many Requests", extra_headers).await;
+        let response = reqwest::get(format!("{base}/v1/chat/completions"))
+            .await
+            .unwrap();
+        crate::providers::api_error("Mock", response).await
+    }
+
+    #[test]
+    fn parses_go_durations() {
+        assert_eq!(parse_go_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_go_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_go_duration("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(
+            parse_go_duration("1h2m3.5s"),
+            Some(Duration::from_secs_f64(3723.5))
+        );
+        assert_eq!(parse_go_duration("soon"), None);
+    }
+
+    #[test]
+    fn exhausted_budget_reset_wins_over_sooner_reset() {
+        let now = SystemTime::now();
+        let advice = retry_advice(
+            &headers(&[
+                ("x-ratelimit-reset-requests", "2s"),
+                ("x-ratelimit-remaining-requests", "10"),
+                ("x-ratelimit-reset-tokens", "45s"),
+                ("x-ratelimit-remaining-tokens", "0"),
+            ]),
+            now,
+        )
+        .unwrap();
+        assert_eq!(advice.delay, Duration::from_secs(45));
+        assert_eq!(advice.source, "x-ratelimit-reset-tokens");
+
+        assert!(retry_advice(&HeaderMap::new(), now).is_none());
+    }
+
+    #[test]
+    fn reads_rate_limit_status_from_openai_and_anthropic_headers() {
+        let status = RateLimitStatus::from_headers(&headers(&[
+            ("x-ratelimit-remaining-requests", "59"),
+            ("x-ratelimit-limit-requests", "60"),
+            ("x-ratelimit-remaining-tokens", "149000"),
+        ]))
+        .unwrap();
+        assert_eq!(status.remaining_requests, Some(59));
+        assert_eq!(status.limit_requests, Some(60));
+        assert_eq!(status.remaining_tokens, Some(149_000));
+        assert_eq!(status.limit_tokens, None);
+
+        let status = RateLimitStatus::from_headers(&headers(&[
+            ("anthropic-ratelimit-requests-remaining", "4"),
+            ("anthropic-ratelimit-requests-limit", "50"),
+        ]))
+        .unwrap();
+        assert_eq!(status.remaining_requests, Some(4));
+        assert_eq!(status.limit_requests, Some(50));
+
+        assert!(RateLimitStatus::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[tokio::test]
+    async fn mock_server_retry_after_seconds() {
+        let err = mock_429_error(vec![("retry-after", "20".into())]).await;
+        assert!(err
+            .to_string()
+            .starts_with("Mock API error (429 Too Many Requests)"));
+        let advice = retry_advice_from_error(&err).unwrap();
+        assert_eq!(advice.delay, Duration::from_secs(20));
+        assert_eq!(advice.source, "retry-after");
+    }
+
+    #[tokio::test]
+    async fn mock_server_retry_after_http_date() {
+        let date = chrono::Utc::now() + chrono::Duration::seconds(30);
+        let err = mock_429_error(vec![(
+            "retry-after",
+            date.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        )])
+        .await;
+        let advice = retry_advice_from_error(&err).unwrap();
+        assert_eq!(advice.source, "retry-after");
+        assert!(advice.delay > Duration::from_secs(25) && advice.delay <= Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn mock_server_retry_after_ms() {
+        let err = mock_429_error(vec![("retry-after-ms", "1500".into())]).await;
+        let advice = retry_advice_from_error(&err).unwrap();
+        assert_eq!(advice.delay, Duration::from_millis(1500));
+        assert_eq!(advice.source, "retry-after-ms");
+    }
+
+    #[tokio::test]
+    async fn mock_server_x_ratelimit_reset_seconds_and_timestamp() {
+        let err = mock_429_error(vec![("x-ratelimit-reset", "12".into())]).await;
+        let advice = retry_advice_from_error(&err).unwrap();
+        assert_eq!(advice.delay, Duration::from_secs(12));
+        assert_eq!(advice.source, "x-ratelimit-reset");
+
+        let reset_at = chrono::Utc::now().timestamp() + 40;
+        let err = mock_429_error(vec![("x-ratelimit-reset", reset_at.to_string())]).await;
+        let advice = retry_advice_from_error(&err).unwrap();
+        assert!(advice.delay > Duration::from_secs(35) && advice.delay <= Duration::from_secs(40));
+    }
+
+    #[tokio::test]
+    async fn mock_server_openai_reset_headers() {
+        let err = mock_429_error(vec![
+            ("x-ratelimit-remaining-requests", "0".into()),
+            ("x-ratelimit-reset-requests", "6m0s".into()),
+            ("x-ratelimit-remaining-tokens", "5000".into()),
+            ("x-ratelimit-reset-tokens", "120ms".into()),
+        ])
+        .await;
+        let advice = retry_advice_from_error(&err).unwrap();
+        assert_eq!(advice.delay, Duration::from_secs(360));
+        assert_eq!(advice.source, "x-ratelimit-reset-requests");
+    }
+
+    #[tokio::test]
+    async fn mock_server_anthropic_reset_headers() {
+        let reset_at = chrono::Utc::now() + chrono::Duration::seconds(15);
+        let err = mock_429_error(vec![
+            ("anthropic-ratelimit-requests-remaining", "0".into()),
+            ("anthropic-ratelimit-requests-reset", reset_at.to_rfc3339()),
+        ])
+        .await;
+        let advice = retry_advice_from_error(&err).unwrap();
+        assert_eq!(advice.source, "anthropic-ratelimit-requests-reset");
+        assert!(advice.delay > Duration::from_secs(10) && advice.delay <= Duration::from_secs(15));
+    }
+
+    #[tokio::test]
+    async fn mock_server_compatible_provider_attaches_advice() {
+        let base = serve_once("429 Too Many Requests", vec![("retry-after", "7".into())]).await;
+        let provider = crate::providers::compatible::OpenAiCompatibleProvider::new(
+            "Mock",
+            &format!("{base}/v1"),
+            Some("test-key"),
+            crate::providers::compatible::AuthStyle::Bearer,
+        );
+        let err = provider
+            .chat_with_system(None, "hi", "mock-model", 0.0)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            retry_advice_from_error(&err).map(|a| a.delay),
+            Some(Duration::from_secs(7))
+        );
+    }
+}
@@ -1,116 +1,45 @@
-use super::traits::{ChatMessage, ChatResponse, StreamChunk, StreamOptions, StreamResult};
-use super::Provider;
+use super::circuit_breaker::{CircuitBreaker, CircuitBreakerSettings, CircuitState};
+use super::traits::{
+    ChatMessage, ChatResponse, ProviderCapabilities, StreamChunk, StreamOptions, StreamResult,
+};
+use super::{Provider, ProviderError};
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
-/// Check if an error is non-retryable (client errors that won't resolve with retries).
+/// Whether the error is one retries cannot fix (bad key, unknown model,
+/// exhausted quota, other 4xx). Untyped errors count as transient.
 fn is_non_retryable(err: &anyhow::Error) -> bool {
-    if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>() {
-        if let Some(status) = reqwest_err.status() {
-            let code = status.as_u16();
-            return status.is_client_error() && code != 429 && code != 408;
-        }
-    }
-    let msg = err.to_string();
-    for word in msg.split(|c: char| !c.is_ascii_digit()) {
-        if let Ok(code) = word.parse::<u16>() {
-            if (400..500).contains(&code) {
-                return code != 429 && code != 408;
-            }
-        }
-    }
-
-    let msg_lower = msg.to_lowercase();
-    let auth_failure_hints = [
-        "invalid api key",
-        "incorrect api key",
-        "missing api key",
-        "api key not set",
-        "authentication failed",
-        "auth failed",
-        "unauthorized",
-        "forbidden",
-        "permission denied",
-        "access denied",
-        "invalid token",
-    ];
-
-    if auth_failure_hints
-        .iter()
-        .any(|hint| msg_lower.contains(hint))
-    {
-        return true;
-    }
-
-    let model_catalog_mismatch = msg_lower.contains("model")
-        && (msg_lower.contains("not found")
-            || msg_lower.contains("unknown")
-            || msg_lower.contains("unsupported")
-            || msg_lower.contains("does not exist")
-            || msg_lower.contains("invalid"));
-
-    model_catalog_mismatch
+    ProviderError::classify(err).is_some_and(|e| !e.is_retryable())
 }
 
-/// Check if an error is a rate-limit (429) error.
+/// Whether the provider answered 429, for a transient limit or a quota.
 fn is_rate_limited(err: &anyhow::Error) -> bool {
-    if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>() {
-        if let Some(status) = reqwest_err.status() {
-            return status.as_u16() == 429;
-        }
-    }
-    let msg = err.to_string();
-    msg.contains("429")
-        && (msg.contains("Too Many") || msg.contains("rate") || msg.contains("limit"))
+    matches!(
+        ProviderError::find(err),
+        Some(ProviderError::RateLimited { .. } | ProviderError::QuotaExceeded { .. })
+    )
 }
 
-/// Check if a 429 is a business/quota-plan error that retries cannot fix.
-///
-/// Examples:
-/// - plan does not include requested model
-/// - insufficient balance / package not active
-/// - known provider business codes (e.g. Z.AI: 1311, 1113)
+/// Whether a 429 is a plan, balance or quota error that retries cannot fix.
 fn is_non_retryable_rate_limit(err: &anyhow::Error) -> bool {
-    if !is_rate_limited(err) {
-        return false;
-    }
-
-    let msg = err.to_string();
-    let lower = msg.to_lowercase();
+    matches!(
+        ProviderError::find(err),
+        Some(ProviderError::QuotaExceeded { .. })
+    )
+}
 
-    let business_hints = [
-        "plan does not include",
-        "doesn't include",
-        "not include",
-        "insufficient balance",
-        "insufficient_balance",
-        "insufficient quota",
-        "insufficient_quota",
-        "quota exhausted",
-        "out of credits",
-        "no available package",
-        "package not active",
-        "purchase package",
-        "model not available for your plan",
-    ];
-
-    if business_hints.iter().any(|hint| lower.contains(hint)) {
-        return true;
-    }
-
-    // Known provider business codes observed for 429 where retry is futile.
-    for token in lower.split(|c: char| !c.is_ascii_digit()) {
-        if let Ok(code) = token.parse::<u16>() {
-            if matches!(code, 1113 | 1311) {
-                return true;
-            }
-        }
+/// The provider-advised wait (in milliseconds) and the header it came from.
+/// Structured advice attached from response headers wins over a value
+/// scraped from the error message.
+fn advised_retry_ms(err: &anyhow::Error) -> Option<(u64, &'static str)> {
+    if let Some(advice) = super::rate_limit::retry_advice_from_error(err) {
+        let millis = u64::try_from(advice.delay.as_millis()).unwrap_or(u64::MAX);
+        return Some((millis, advice.source));
     }
-
-    false
+    parse_retry_after_ms(err).map(|millis| (millis, "error message"))
 }
 
 /// Try to extract a Retry-After value (in milliseconds) from an error message.
@@ -189,6 +118,8 @@ pub struct ReliableProvider {
     key_index: AtomicUsize,
     /// Per-model fallback chains: model_name → [fallback_model_1, fallback_model_2, ...]
     model_fallbacks: HashMap<String, Vec<String>>,
+    /// Fails fast on providers that keep failing; `None` when disabled.
+    circuit_breaker: Option<CircuitBreaker>,
 }
 
 impl ReliableProvider {
@@ -204,6 +135,7 @@ impl ReliableProvider {
             api_keys: Vec::new(),
             key_index: AtomicUsize::new(0),
             model_fallbacks: HashMap::new(),
+            circuit_breaker: None,
         }
     }
 
@@ -219,6 +151,58 @@ impl ReliableProvider {
         self
     }
 
+    /// Open a provider's circuit after repeated failures (see
+    /// [`super::circuit_breaker`]). A zero threshold leaves it disabled.
+    pub fn with_circuit_breaker(mut self, settings: CircuitBreakerSettings) -> Self {
+        self.circuit_breaker =
+            (settings.failure_threshold > 0).then(|| CircuitBreaker::new(settings));
+        self
+    }
+
+    /// Whether `provider_name` may be called, recording a fast-fail in
+    /// `failures` when its circuit is open.
+    fn circuit_allows(&self, provider_name: &str, model: &str, failures: &mut Vec<String>) -> bool {
+        let allowed = self
+            .circuit_breaker
+            .as_ref()
+            .is_none_or(|breaker| breaker.allow(provider_name));
+        if !allowed {
+            tracing::warn!(
+                provider = provider_name,
+                model,
+                "Circuit open, skipping provider"
+            );
+            failures.push(format!(
+                "provider={provider_name} model={model}: circuit open; skipped"
+            ));
+        }
+        allowed
+    }
+
+    fn circuit_is_open(&self, provider_name: &str) -> bool {
+        self.circuit_breaker
+            .as_ref()
+            .is_some_and(|breaker| breaker.state(provider_name) == CircuitState::Open)
+    }
+
+    fn record_success(&self, provider_name: &str) {
+        if let Some(breaker) = &self.circuit_breaker {
+            breaker.record_success(provider_name);
+        }
+    }
+
+    /// Count a failure towards the circuit. Client errors (bad request,
+    /// unknown model) say nothing about the provider's health and are
+    /// ignored.
+    fn record_failure(&self, provider_name: &str, non_retryable: bool, rate_limited: bool) {
+        if non_retryable && !rate_limited {
+            return;
+        }
+        if let Some(breaker) = &self.circuit_breaker {
+            breaker.record_failure(provider_name);
+        }
+    }
+
     /// Build the list of models to try: [original, fallback1, fallback2, ...]
     fn model_chain<'a>(&'a self, model: &'a str) -> Vec<&'a str> {
         let mut chain = vec![model];
@@ -239,17 +223,106 @@ impl ReliableProvider {
 
     /// Compute backoff duration, respecting Retry-After if present.
     fn compute_backoff(&self, base: u64, err: &anyhow::Error) -> u64 {
-        if let Some(retry_after) = parse_retry_after_ms(err) {
+        if let Some((retry_after, _)) = advised_retry_ms(err) {
             // Use Retry-After but cap at 30s to avoid indefinite waits
             retry_after.min(30_000).max(base)
         } else {
             base
         }
     }
+
+    /// Where the wait chosen by `compute_backoff` came from, for logging.
+    fn backoff_source(&self, base: u64, err: &anyhow::Error) -> &'static str {
+        match advised_retry_ms(err) {
+            Some((retry_after, source)) if retry_after.min(30_000) > base => source,
+            _ => "exponential backoff",
+        }
+    }
+
+    /// Stream from the first provider that supports streaming, using the
+    /// head of the model chain. `start` opens the stream on that provider.
+    fn stream_first<F>(
+        &self,
+        model: &str,
+        options: StreamOptions,
+        start: F,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>>
+    where
+        F: Fn(&dyn Provider, &str) -> stream::BoxStream<'static, StreamResult<StreamChunk>>,
+    {
+        // Try each provider/model combination for streaming
+        // For streaming, we use the first provider that supports it and has streaming enabled
+        for (provider_name, provider) in &self.providers {
+            if !provider.supports_streaming() || !options.enabled {
+                continue;
+            }
+
+            // Clone provider data for the stream
+            let provider_clone = provider_name.clone();
+
+            // Try the first model in the chain for streaming
+            let current_model = match self.model_chain(model).first() {
+                Some(m) => m.to_string(),
+                None => model.to_string(),
+            };
+
+            // For streaming, we attempt once and propagate errors
+            // The caller can retry the entire request if needed
+            let stream = start(provider.as_ref(), &current_model);
+
+            // Use a channel to bridge the stream with logging
+            let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
+
+            tokio::spawn(async move {
+                let mut stream = stream;
+                while let Some(chunk) = stream.next().await {
+                    if let Err(ref e) = chunk {
+                        tracing::warn!(
+                            provider = provider_clone,
+                            model = current_model,
+                            "Streaming error: {e}"
+                        );
+                    }
+                    if tx.send(chunk).await.is_err() {
+                        break; // Receiver dropped
+                    }
+                }
+            });
+
+            // Convert channel receiver to stream
+            return stream::unfold(rx, |mut rx| async move {
+                rx.recv().await.map(|chunk| (chunk, rx))
+            })
+            .boxed();
+        }
+
+        // No streaming support available
+        stream::once(async move {
+            Err(super::traits::StreamError::Provider(
+                "No provider supports streaming".to_string(),
+            ))
+        })
+        .boxed()
+    }
 }
 
 #[async_trait]
 impl Provider for ReliableProvider {
+    /// Tool calling and vision follow the primary provider, since the
+    /// request shape is chosen before any fallback happens; streaming is
+    /// available when any provider in the chain can stream.
+    fn capabilities(&self) -> ProviderCapabilities {
+        let primary = self
+            .providers
+            .first()
+            .map(|(_, p)| p.capabilities())
+            .unwrap_or_default();
+        ProviderCapabilities {
+            streaming: self.providers.iter().any(|(_, p)| p.supports_streaming()),
+            ..primary
+        }
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         for (name, provider) in &self.providers {
             tracing::info!(provider = name, "Warming up provider connection pool");
@@ -272,6 +345,9 @@ impl Provider for ReliableProvider {
 
         for current_model in &models {
             for (provider_name, provider) in &self.providers {
+                if !self.circuit_allows(provider_name, current_model, &mut failures) {
+                    continue;
+                }
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
@@ -280,6 +356,7 @@ impl Provider for ReliableProvider {
                         .await
                     {
                         Ok(resp) => {
+                            self.record_success(provider_name);
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
                                     provider = provider_name,
@@ -293,10 +370,11 @@ impl Provider for ReliableProvider {
                         }
                         Err(e) => {
                             let non_retryable_rate_limit = is_non_retryable_rate_limit(&e);
-                            let non_retryable = is_non_retryable(&e) || non_retryable_rate_limit;
+                            let non_retryable = is_non_retryable(&e);
                             let rate_limited = is_rate_limited(&e);
                             let failure_reason = failure_reason(rate_limited, non_retryable);
                             let error_detail = compact_error_detail(&e);
+                            self.record_failure(provider_name, non_retryable, rate_limited);
 
                             push_failure(
                                 &mut failures,
@@ -330,6 +408,10 @@ impl Provider for ReliableProvider {
                                 break;
                             }
 
+                            if self.circuit_is_open(provider_name) {
+                                break;
+                            }
+
                             if attempt < self.max_retries {
                                 let wait = self.compute_backoff(backoff_ms, &e);
                                 tracing::warn!(
@@ -337,6 +419,7 @@ impl Provider for ReliableProvider {
                                     model = *current_model,
                                     attempt = attempt + 1,
                                     backoff_ms = wait,
+                                    delay_source = self.backoff_source(backoff_ms, &e),
                                     reason = failure_reason,
                                     error = %error_detail,
                                     "Provider call failed, retrying"
@@ -381,6 +464,9 @@ impl Provider for ReliableProvider {
 
         for current_model in &models {
             for (provider_name, provider) in &self.providers {
+                if !self.circuit_allows(provider_name, current_model, &mut failures) {
+                    continue;
+                }
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
@@ -389,6 +475,7 @@ impl Provider for ReliableProvider {
                         .await
                     {
                         Ok(resp) => {
+                            self.record_success(provider_name);
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
                                     provider = provider_name,
@@ -402,10 +489,11 @@ impl Provider for ReliableProvider {
                         }
                         Err(e) => {
                             let non_retryable_rate_limit = is_non_retryable_rate_limit(&e);
-                            let non_retryable = is_non_retryable(&e) || non_retryable_rate_limit;
+                            let non_retryable = is_non_retryable(&e);
                             let rate_limited = is_rate_limited(&e);
                             let failure_reason = failure_reason(rate_limited, non_retryable);
                             let error_detail = compact_error_detail(&e);
+                            self.record_failure(provider_name, non_retryable, rate_limited);
 
                             push_failure(
                                 &mut failures,
@@ -438,6 +526,10 @@ impl Provider for ReliableProvider {
                                 break;
                             }
 
+                            if self.circuit_is_open(provider_name) {
+                                break;
+                            }
+
                             if attempt < self.max_retries {
                                 let wait = self.compute_backoff(backoff_ms, &e);
                                 tracing::warn!(
@@ -445,6 +537,7 @@ impl Provider for ReliableProvider {
                                     model = *current_model,
                                     attempt = attempt + 1,
                                     backoff_ms = wait,
+                                    delay_source = self.backoff_source(backoff_ms, &e),
                                     reason = failure_reason,
                                     error = %error_detail,
                                     "Provider call failed, retrying"
@@ -470,13 +563,6 @@ impl Provider for ReliableProvider {
         )
     }
 
-    fn supports_native_tools(&self) -> bool {
-        self.providers
-            .first()
-            .map(|(_, p)| p.supports_native_tools())
-            .unwrap_or(false)
-    }
-
     async fn chat_with_tools(
         &self,
         messages: &[ChatMessage],
@@ -489,6 +575,9 @@ impl Provider for ReliableProvider {
 
         for current_model in &models {
             for (provider_name, provider) in &self.providers {
+                if !self.circuit_allows(provider_name, current_model, &mut failures) {
+                    continue;
+                }
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
@@ -497,6 +586,7 @@ impl Provider for ReliableProvider {
                         .await
                     {
                         Ok(resp) => {
+                            self.record_success(provider_name);
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
                                     provider = provider_name,
@@ -510,10 +600,11 @@ impl Provider for ReliableProvider {
                         }
                         Err(e) => {
                             let non_retryable_rate_limit = is_non_retryable_rate_limit(&e);
-                            let non_retryable = is_non_retryable(&e) || non_retryable_rate_limit;
+                            let non_retryable = is_non_retryable(&e);
                             let rate_limited = is_rate_limited(&e);
                             let failure_reason = failure_reason(rate_limited, non_retryable);
                             let error_detail = compact_error_detail(&e);
+                            self.record_failure(provider_name, non_retryable, rate_limited);
 
                             push_failure(
                                 &mut failures,
@@ -546,6 +637,10 @@ impl Provider for ReliableProvider {
                                 break;
                             }
 
+                            if self.circuit_is_open(provider_name) {
+                                break;
+                            }
+
                             if attempt < self.max_retries {
                                 let wait = self.compute_backoff(backoff_ms, &e);
                                 tracing::warn!(
@@ -553,6 +648,7 @@ impl Provider for ReliableProvider {
                                     model = *current_model,
                                     attempt = attempt + 1,
                                     backoff_ms = wait,
+                                    delay_source = self.backoff_source(backoff_ms, &e),
                                     reason = failure_reason,
                                     error = %error_detail,
                                     "Provider call failed, retrying"
@@ -578,10 +674,6 @@ impl Provider for ReliableProvider {
         )
     }
 
-    fn supports_streaming(&self) -> bool {
-        self.providers.iter().any(|(_, p)| p.supports_streaming())
-    }
-
     fn stream_chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -590,65 +682,21 @@ impl Provider for ReliableProvider {
         temperature: f64,
         options: StreamOptions,
     ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
-        // Try each provider/model combination for streaming
-        // For streaming, we use the first provider that supports it and has streaming enabled
-        for (provider_name, provider) in &self.providers {
-            if !provider.supports_streaming() || !options.enabled {
-                continue;
-            }
-
-            // Clone provider data for the stream
-            let provider_clone = provider_name.clone();
-
-            // Try the first model in the chain for streaming
-            let current_model = match self.model_chain(model).first() {
-                Some(m) => m.to_string(),
-                None => model.to_string(),
-            };
-
-            // For streaming, we attempt once and propagate errors
-            // The caller can retry the entire request if needed
-            let stream = provider.stream_chat_with_system(
-                system_prompt,
-                message,
-                &current_model,
-                temperature,
-                options,
-            );
-
-            // Use a channel to bridge the stream with logging
-            let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
-
-            tokio::spawn(async move {
-                let mut stream = stream;
-                while let Some(chunk) = stream.next().await {
-                    if let Err(ref e) = chunk {
-                        tracing::warn!(
-                            provider = provider_clone,
-                            model = current_model,
-                            "Streaming error: {e}"
-                        );
-                    }
-                    if tx.send(chunk).await.is_err() {
-                        break; // Receiver dropped
-                    }
-                }
-            });
-
-            // Convert channel receiver to stream
-            return stream::unfold(rx, |mut rx| async move {
-                rx.recv().await.map(|chunk| (chunk, rx))
-            })
-            .boxed();
-        }
+        self.stream_first(model, options, |provider, model| {
+            provider.stream_chat_with_system(system_prompt, message, model, temperature, options)
+        })
+    }
 
-        // No streaming support available
-        stream::once(async move {
-            Err(super::traits::StreamError::Provider(
-                "No provider supports streaming".to_string(),
-            ))
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_first(model, options, |provider, model| {
+            provider.stream_chat_with_history(messages, model, temperature, options)
         })
-        .boxed()
     }
 }
 
@@ -675,7 +723,7 @@ mod tests {
         ) -> anyhow::Result<String> {
             let attempt = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
             if attempt <= self.fail_until_attempt {
-                anyhow::bail!(self.error);
+                return Err(mock_error(self.error));
             }
             Ok(self.response.to_string())
         }
@@ -688,12 +736,26 @@ mod tests {
         ) -> anyhow::Result<String> {
             let attempt = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
             if attempt <= self.fail_until_attempt {
-                anyhow::bail!(self.error);
+                return Err(mock_error(self.error));
             }
             Ok(self.response.to_string())
         }
     }
 
+    /// A typed provider error for a `"<status> <body>"` spec, or an untyped
+    /// error when the spec does not start with a status code.
+    fn mock_error(spec: &str) -> anyhow::Error {
+        let status = spec
+            .split_whitespace()
+            .next()
+            .and_then(|code| code.parse::<u16>().ok())
+            .and_then(|code| reqwest::StatusCode::from_u16(code).ok());
+        match status {
+            Some(status) => ProviderError::from_status(status, spec, spec.to_string(), None).into(),
+            None => anyhow::anyhow!("{spec}"),
+        }
+    }
+
     /// Mock that records which model was used for each call.
     struct ModelAwareMock {
         calls: Arc<AtomicUsize>,
@@ -714,7 +776,7 @@ mod tests {
             self.calls.fetch_add(1, Ordering::SeqCst);
             self.models_seen.lock().push(model.to_string());
             if self.fail_models.contains(&model) {
-                anyhow::bail!("500 model {} unavailable", model);
+                return Err(mock_error(&format!("500 model {model} unavailable")));
             }
             Ok(self.response.to_string())
         }
@@ -844,31 +906,38 @@ mod tests {
 
     #[test]
     fn non_retryable_detects_common_patterns() {
-        assert!(is_non_retryable(&anyhow::anyhow!("400 Bad Request")));
-        assert!(is_non_retryable(&anyhow::anyhow!("401 Unauthorized")));
-        assert!(is_non_retryable(&anyhow::anyhow!("403 Forbidden")));
-        assert!(is_non_retryable(&anyhow::anyhow!("404 Not Found")));
-        assert!(is_non_retryable(&anyhow::anyhow!(
-            "invalid api key provided"
+        assert!(is_non_retryable(&mock_error("400 Bad Request")));
+        assert!(is_non_retryable(&mock_error("401 Unauthorized")));
+        assert!(is_non_retryable(&mock_error("403 Forbidden")));
+        assert!(is_non_retryable(&mock_error("404 Not Found")));
+        assert!(is_non_retryable(&mock_error(
+            "400 invalid api key provided"
         )));
-        assert!(is_non_retryable(&anyhow::anyhow!("authentication failed")));
-        assert!(is_non_retryable(&anyhow::anyhow!(
-            "model glm-4.7 not found"
+        assert!(is_non_retryable(&mock_error("404 model glm-4.7 not found")));
+        assert!(is_non_retryable(&mock_error(
+            "400 unsupported model: glm-4.7"
         )));
-        assert!(is_non_retryable(&anyhow::anyhow!(
-            "unsupported model: glm-4.7"
+        assert!(is_non_retryable(
+            &mock_error("401 Unauthorized").context("chat request failed")
+        ));
+        assert!(!is_non_retryable(&mock_error("429 Too Many Requests")));
+        assert!(!is_non_retryable(&mock_error("408 Request Timeout")));
+        assert!(!is_non_retryable(&mock_error("500 Internal Server Error")));
+        assert!(!is_non_retryable(&mock_error("502 Bad Gateway")));
+        assert!(!is_non_retryable(&mock_error(
+            "503 model overloaded, try again later"
         )));
-        assert!(!is_non_retryable(&anyhow::anyhow!("429 Too Many Requests")));
-        assert!(!is_non_retryable(&anyhow::anyhow!("408 Request Timeout")));
-        assert!(!is_non_retryable(&anyhow::anyhow!(
-            "500 Internal Server Error"
-        )));
-        assert!(!is_non_retryable(&anyhow::anyhow!("502 Bad Gateway")));
+    }
+
+    #[test]
+    fn untyped_errors_are_retryable_whatever_their_wording() {
         assert!(!is_non_retryable(&anyhow::anyhow!("timeout")));
         assert!(!is_non_retryable(&anyhow::anyhow!("connection reset")));
+        assert!(!is_non_retryable(&anyhow::anyhow!("401 Unauthorized")));
         assert!(!is_non_retryable(&anyhow::anyhow!(
-            "model overloaded, try again later"
+            "invalid api key provided"
         )));
+        assert!(!is_rate_limited(&anyhow::anyhow!("429 Too Many Requests")));
     }
 
     #[tokio::test]
@@ -881,7 +950,7 @@ mod tests {
                     calls: Arc::clone(&calls),
                     fail_until_attempt: usize::MAX,
                     response: "never",
-                    error: "unsupported model: glm-4.7",
+                    error: "404 unsupported model: glm-4.7",
                 }),
             )],
             3,
@@ -895,7 +964,7 @@ mod tests {
         let msg = err.to_string();
 
         assert!(msg.contains("non_retryable"));
-        assert!(msg.contains("error=unsupported model: glm-4.7"));
+        assert!(msg.contains("error=404 unsupported model: glm-4.7"));
         // Non-retryable errors should not consume retry budget.
         assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
@@ -1147,21 +1216,19 @@ mod tests {
 
     #[test]
     fn rate_limited_detection() {
-        assert!(is_rate_limited(&anyhow::anyhow!("429 Too Many Requests")));
-        assert!(is_rate_limited(&anyhow::anyhow!(
-            "HTTP 429 rate limit exceeded"
-        )));
-        assert!(!is_rate_limited(&anyhow::anyhow!("401 Unauthorized")));
-        assert!(!is_rate_limited(&anyhow::anyhow!(
-            "500 Internal Server Error"
+        assert!(is_rate_limited(&mock_error("429 Too Many Requests")));
+        assert!(is_rate_limited(&mock_error("429 rate limit exceeded")));
+        assert!(is_rate_limited(&mock_error(
+            "429 Too Many Requests: insufficient_quota"
         )));
+        assert!(!is_rate_limited(&mock_error("401 Unauthorized")));
+        assert!(!is_rate_limited(&mock_error("500 Internal Server Error")));
     }
 
     #[test]
     fn non_retryable_rate_limit_detects_plan_restricted_model() {
-        let err = anyhow::anyhow!(
-            "{}",
-            "API error (429 Too Many Requests): {\"code\":1311,\"message\":\"the current account plan does not include glm-5\"}"
+        let err = mock_error(
+            "429 Too Many Requests: {\"code\":1311,\"message\":\"the current account plan does not include glm-5\"}",
         );
         assert!(
             is_non_retryable_rate_limit(&err),
@@ -1171,9 +1238,8 @@ mod tests {
 
     #[test]
     fn non_retryable_rate_limit_detects_insufficient_balance() {
-        let err = anyhow::anyhow!(
-            "{}",
-            "API error (429 Too Many Requests): {\"code\":1113,\"message\":\"insufficient balance\"}"
+        let err = mock_error(
+            "429 Too Many Requests: {\"code\":1113,\"message\":\"insufficient balance\"}",
         );
         assert!(
             is_non_retryable_rate_limit(&err),
@@ -1183,7 +1249,7 @@ mod tests {
 
     #[test]
     fn non_retryable_rate_limit_does_not_flag_generic_429() {
-        let err = anyhow::anyhow!("429 Too Many Requests: rate limit exceeded");
+        let err = mock_error("429 Too Many Requests: rate limit exceeded");
         assert!(
             !is_non_retryable_rate_limit(&err),
             "generic rate-limit 429 should remain retryable"
@@ -1211,11 +1277,76 @@ mod tests {
         assert_eq!(provider.compute_backoff(500, &err), 500);
     }
 
+    fn advised_error(delay: Duration, source: &'static str) -> anyhow::Error {
+        ProviderError::RateLimited {
+            message: "Mock API error (429 Too Many Requests): slow down".into(),
+            retry_after: Some(crate::providers::rate_limit::RetryAdvice { delay, source }),
+        }
+        .into()
+    }
+
+    #[test]
+    fn compute_backoff_uses_header_advice() {
+        let provider = ReliableProvider::new(vec![], 0, 500);
+        let err = advised_error(Duration::from_secs(4), "x-ratelimit-reset-requests");
+        assert_eq!(provider.compute_backoff(500, &err), 4000);
+        assert_eq!(
+            provider.backoff_source(500, &err),
+            "x-ratelimit-reset-requests"
+        );
+
+        // Advice shorter than the exponential backoff does not shrink the wait.
+        let err = advised_error(Duration::from_millis(100), "retry-after-ms");
+        assert_eq!(provider.compute_backoff(500, &err), 500);
+        assert_eq!(provider.backoff_source(500, &err), "exponential backoff");
+    }
+
+    struct AdvisedRateLimitMock {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Provider for AdvisedRateLimitMock {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(advised_error(Duration::from_millis(300), "retry-after-ms"));
+            }
+            Ok("ok".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_waits_for_advised_delay() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(AdvisedRateLimitMock {
+                    calls: Arc::clone(&calls),
+                }),
+            )],
+            1,
+            1,
+        );
+
+        let started = std::time::Instant::now();
+        let result = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(result, "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() >= Duration::from_millis(300));
+    }
+
     // ── §2.1 API auth error (401/403) tests ──────────────────
 
     #[test]
     fn non_retryable_detects_401() {
-        let err = anyhow::anyhow!("API error (401 Unauthorized): invalid api key");
+        let err = mock_error("401 Unauthorized: invalid api key");
         assert!(
             is_non_retryable(&err),
             "401 errors must be detected as non-retryable"
@@ -1224,7 +1355,7 @@ mod tests {
 
     #[test]
     fn non_retryable_detects_403() {
-        let err = anyhow::anyhow!("API error (403 Forbidden): access denied");
+        let err = mock_error("403 Forbidden: access denied");
         assert!(
             is_non_retryable(&err),
             "403 errors must be detected as non-retryable"
@@ -1233,7 +1364,7 @@ mod tests {
 
     #[test]
     fn non_retryable_detects_404() {
-        let err = anyhow::anyhow!("API error (404 Not Found): model not found");
+        let err = mock_error("404 Not Found: model not found");
         assert!(
             is_non_retryable(&err),
             "404 errors must be detected as non-retryable"
@@ -1242,7 +1373,7 @@ mod tests {
 
     #[test]
     fn non_retryable_does_not_flag_429() {
-        let err = anyhow::anyhow!("429 Too Many Requests");
+        let err = mock_error("429 Too Many Requests");
         assert!(
             !is_non_retryable(&err),
             "429 must NOT be treated as non-retryable (it is retryable with backoff)"
@@ -1251,7 +1382,7 @@ mod tests {
 
     #[test]
     fn non_retryable_does_not_flag_408() {
-        let err = anyhow::anyhow!("408 Request Timeout");
+        let err = mock_error("408 Request Timeout");
         assert!(
             !is_non_retryable(&err),
             "408 must NOT be treated as non-retryable (it is retryable)"
@@ -1260,7 +1391,7 @@ mod tests {
 
     #[test]
     fn non_retryable_does_not_flag_500() {
-        let err = anyhow::anyhow!("500 Internal Server Error");
+        let err = mock_error("500 Internal Server Error");
         assert!(
             !is_non_retryable(&err),
             "500 must NOT be treated as non-retryable (server errors are retryable)"
@@ -1269,7 +1400,7 @@ mod tests {
 
     #[test]
     fn non_retryable_does_not_flag_502() {
-        let err = anyhow::anyhow!("502 Bad Gateway");
+        let err = mock_error("502 Bad Gateway");
         assert!(
             !is_non_retryable(&err),
             "502 must NOT be treated as non-retryable"
@@ -1329,7 +1460,7 @@ mod tests {
                     calls: Arc::clone(&calls),
                     fail_until_attempt: usize::MAX,
                     response: "never",
-                    error: "API error (401 Unauthorized): invalid key",
+                    error: "401 Unauthorized: invalid key",
                 }),
             )],
             5,
@@ -1355,7 +1486,7 @@ mod tests {
                     calls: Arc::clone(&calls),
                     fail_until_attempt: usize::MAX,
                     response: "never",
-                    error: "API error (429 Too Many Requests): {\"code\":1311,\"message\":\"plan does not include glm-5\"}",
+                    error: "429 Too Many Requests: {\"code\":1311,\"message\":\"plan does not include glm-5\"}",
                 }),
             )],
             5,
@@ -1390,4 +1521,110 @@ mod tests {
                 .await
         }
     }
+
+    fn breaker_settings(threshold: u32, cooldown: Duration) -> CircuitBreakerSettings {
+        CircuitBreakerSettings {
+            failure_threshold: threshold,
+            window: Duration::from_secs(60),
+            cooldown,
+        }
+    }
+
+    #[tokio::test]
+    async fn open_circuit_skips_straight_to_fallback() {
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let fallback_calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![
+                (
+                    "primary".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&primary_calls),
+                        fail_until_attempt: usize::MAX,
+                        response: "never",
+                        error: "primary down",
+                    }),
+                ),
+                (
+                    "fallback".into(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&fallback_calls),
+                        fail_until_attempt: 0,
+                        response: "from fallback",
+                        error: "fallback down",
+                    }),
+                ),
+            ],
+            2,
+            1,
+        )
+        .with_circuit_breaker(breaker_settings(3, Duration::from_secs(60)));
+
+        let first = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(first, "from fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 3);
+
+        let second = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(second, "from fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 3);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn open_circuit_fails_fast_then_recovers_after_cooldown() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(MockProvider {
+                    calls: Arc::clone(&calls),
+                    fail_until_attempt: 3,
+                    response: "back",
+                    error: "503 service unavailable",
+                }),
+            )],
+            2,
+            1,
+        )
+        .with_circuit_breaker(breaker_settings(3, Duration::from_millis(50)));
+
+        assert!(provider.simple_chat("hello", "test", 0.0).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let err = provider
+            .simple_chat("hello", "test", 0.0)
+            .await
+            .expect_err("open circuit should fail fast");
+        assert!(err.to_string().contains("circuit open"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let result = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(result, "back");
+        let breaker = provider.circuit_breaker.as_ref().unwrap();
+        assert_eq!(breaker.state("primary"), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn client_errors_do_not_open_the_circuit() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(MockProvider {
+                    calls: Arc::clone(&calls),
+                    fail_until_attempt: usize::MAX,
+                    response: "never",
+                    error: "400 bad request",
+                }),
+            )],
+            2,
+            1,
+        )
+        .with_circuit_breaker(breaker_settings(1, Duration::from_secs(60)));
+
+        assert!(provider.simple_chat("hello", "test", 0.0).await.is_err());
+        assert!(provider.simple_chat("hello", "test", 0.0).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
 }
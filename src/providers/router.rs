@@ -1,4 +1,4 @@
-use super::traits::{ChatMessage, ChatRequest, ChatResponse};
+use super::traits::{ChatMessage, ChatRequest, ChatResponse, ProviderCapabilities};
 use super::Provider;
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -151,11 +151,18 @@ impl Provider for RouterProvider {
             .await
     }
 
-    fn supports_native_tools(&self) -> bool {
-        self.providers
+    /// The default provider's capabilities. Streaming is not routed, so it
+    /// is never advertised.
+    fn capabilities(&self) -> ProviderCapabilities {
+        let default = self
+            .providers
             .get(self.default_index)
-            .map(|(_, p)| p.supports_native_tools())
-            .unwrap_or(false)
+            .map(|(_, p)| p.capabilities())
+            .unwrap_or_default();
+        ProviderCapabilities {
+            streaming: false,
+            ..default
+        }
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -0,0 +1,178 @@
+//! Reassembly of natively streamed tool calls.
+//!
+//! OpenAI-compatible streams send each tool call in pieces: the first delta
+//! for an `index` carries its `id` and function `name`, later deltas append
+//! fragments of the `arguments` JSON. Deltas for several calls can be
+//! interleaved. [`ToolCallAccumulator`] collects them per index and, once the
+//! stream ends, yields complete [`ToolCall`]s whose arguments are valid JSON.
+
+use super::traits::ToolCall;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// One `choices[].delta.tool_calls[]` entry of a streamed response.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ToolCallDelta {
+    #[serde(default)]
+    pub index: usize,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: Option<FunctionDelta>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FunctionDelta {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct PartialToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+/// Collects streamed tool-call deltas until the stream ends.
+#[derive(Debug, Default)]
+pub struct ToolCallAccumulator {
+    calls: BTreeMap<usize, PartialToolCall>,
+}
+
+impl ToolCallAccumulator {
+    pub fn push(&mut self, delta: &ToolCallDelta) {
+        let call = self.calls.entry(delta.index).or_default();
+        if let Some(id) = delta.id.as_deref().filter(|id| !id.is_empty()) {
+            if call.id.is_empty() {
+                call.id = id.to_string();
+            }
+        }
+        if let Some(function) = &delta.function {
+            if let Some(name) = function.name.as_deref().filter(|name| !name.is_empty()) {
+                if call.name.is_empty() {
+                    call.name = name.to_string();
+                }
+            }
+            if let Some(fragment) = &function.arguments {
+                call.arguments.push_str(fragment);
+            }
+        }
+    }
+
+    /// The completed calls in index order. Fails if a call never got a name
+    /// or its arguments do not form valid JSON (e.g. a truncated stream).
+    pub fn finish(self) -> Result<Vec<ToolCall>, String> {
+        self.calls
+            .into_iter()
+            .map(|(index, call)| {
+                if call.name.is_empty() {
+                    return Err(format!("streamed tool call {index} has no name"));
+                }
+                let arguments = if call.arguments.trim().is_empty() {
+                    "{}".to_string()
+                } else {
+                    call.arguments
+                };
+                serde_json::from_str::<serde_json::Value>(&arguments).map_err(|e| {
+                    format!(
+                        "streamed arguments for tool call {index} ({}) are not valid JSON: {e}",
+                        call.name
+                    )
+                })?;
+                let id = if call.id.is_empty() {
+                    format!("call_{index}")
+                } else {
+                    call.id
+                };
+                Ok(ToolCall {
+                    id,
+                    name: call.name,
+                    arguments,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(index: usize, id: Option<&str>, name: Option<&str>, args: &str) -> ToolCallDelta {
+        ToolCallDelta {
+            index,
+            id: id.map(str::to_string),
+            function: Some(FunctionDelta {
+                name: name.map(str::to_string),
+                arguments: Some(args.to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn interleaved_fragments_reassemble_per_index() {
+        let mut acc = ToolCallAccumulator::default();
+        for d in [
+            delta(0, Some("call_a"), Some("shell"), ""),
+            delta(1, Some("call_b"), Some("file_read"), "{\"pa"),
+            delta(0, None, None, "{\"command\":"),
+            delta(1, None, None, "th\":\"src/ma"),
+            delta(0, None, None, " \"ls -la\"}"),
+            delta(1, None, None, "in.rs\"}"),
+        ] {
+            acc.push(&d);
+        }
+
+        let calls = acc.finish().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].id, "call_a");
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&calls[0].arguments).unwrap(),
+            serde_json::json!({"command": "ls -la"})
+        );
+        assert_eq!(calls[1].id, "call_b");
+        assert_eq!(calls[1].name, "file_read");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&calls[1].arguments).unwrap(),
+            serde_json::json!({"path": "src/main.rs"})
+        );
+    }
+
+    #[test]
+    fn deserializes_openai_delta_shape() {
+        let raw = r#"{"index":2,"id":"call_x","type":"function","function":{"name":"todo","arguments":""}}"#;
+        let mut acc = ToolCallAccumulator::default();
+        acc.push(&serde_json::from_str(raw).unwrap());
+        let calls = acc.finish().unwrap();
+        assert_eq!(calls[0].name, "todo");
+        assert_eq!(
+            calls[0].arguments, "{}",
+            "no arguments means an empty object"
+        );
+    }
+
+    #[test]
+    fn truncated_arguments_are_an_error() {
+        let mut acc = ToolCallAccumulator::default();
+        acc.push(&delta(
+            0,
+            Some("call_a"),
+            Some("shell"),
+            "{\"command\": \"ls",
+        ));
+        let err = acc.finish().unwrap_err();
+        assert!(err.contains("shell"));
+        assert!(err.contains("not valid JSON"));
+    }
+
+    #[test]
+    fn missing_id_gets_a_positional_one() {
+        let mut acc = ToolCallAccumulator::default();
+        acc.push(&delta(3, None, Some("shell"), "{}"));
+        assert_eq!(acc.finish().unwrap()[0].id, "call_3");
+    }
+}
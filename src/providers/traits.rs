@@ -56,6 +56,51 @@ pub struct ChatResponse {
     pub text: Option<String>,
     /// Tool calls requested by the LLM.
     pub tool_calls: Vec<ToolCall>,
+    /// Reasoning ("thinking") the model produced before its answer, kept apart
+    /// from `text` so it never reaches tool-call parsing, memory or JSON output.
+    pub reasoning: Option<String>,
+    /// Why the provider stopped generating, when it says.
+    pub finish_reason: Option<FinishReason>,
+}
+
+/// Why a provider stopped generating a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FinishReason {
+    /// Natural end of the answer (`stop`, `end_turn`, `stop_sequence`).
+    Stop,
+    /// Hit the output token limit (`length`, `max_tokens`).
+    Length,
+    /// Blocked or refused by the provider (`content_filter`, `refusal`, `SAFETY`).
+    ContentFilter,
+    /// Stopped to call tools (`tool_calls`, `tool_use`).
+    ToolCalls,
+    /// Anything else, as reported.
+    Other(String),
+}
+
+impl FinishReason {
+    /// Map a provider's raw finish/stop reason onto the common variants.
+    pub fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "stop" | "end_turn" | "stop_sequence" => Self::Stop,
+            "length" | "max_tokens" => Self::Length,
+            "content_filter" | "refusal" | "safety" => Self::ContentFilter,
+            "tool_calls" | "tool_use" | "function_call" => Self::ToolCalls,
+            _ => Self::Other(raw.trim().to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for FinishReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stop => f.write_str("stop"),
+            Self::Length => f.write_str("length"),
+            Self::ContentFilter => f.write_str("content_filter"),
+            Self::ToolCalls => f.write_str("tool_calls"),
+            Self::Other(raw) => f.write_str(raw),
+        }
+    }
 }
 
 impl ChatResponse {
@@ -68,6 +113,113 @@ impl ChatResponse {
     pub fn text_or_empty(&self) -> &str {
         self.text.as_deref().unwrap_or("")
     }
+
+    /// Move inline `<think>…</think>` blocks out of `text` into `reasoning`,
+    /// appending to any reasoning the provider already returned as a field.
+    #[must_use]
+    pub fn with_reasoning_separated(mut self) -> Self {
+        let Some(text) = self.text.take() else {
+            return self;
+        };
+        let (answer, inline) = split_think_tags(&text);
+        self.reasoning = match (self.reasoning.take(), inline) {
+            (Some(field), Some(inline)) => Some(format!("{field}\n\n{inline}")),
+            (field, inline) => field.or(inline),
+        };
+        self.text = if answer.is_empty() && !self.tool_calls.is_empty() {
+            None
+        } else {
+            Some(answer)
+        };
+        self
+    }
+
+    /// Apply [`normalize_output`] to `text`.
+    #[must_use]
+    pub fn with_normalized_text(mut self) -> Self {
+        self.text = self.text.map(|text| normalize_output(&text));
+        self
+    }
+}
+
+/// Clean up line endings and spacing in model output (`[agent] normalize_output`).
+///
+/// CRLF and lone CR become LF, and runs of blank lines outside code fences
+/// collapse to a single empty line. Fenced blocks keep their blank lines,
+/// since they may hold code.
+pub fn normalize_output(text: &str) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut normalized = String::with_capacity(text.len());
+    let mut in_fence = false;
+    let mut previous_blank = false;
+    for line in text.split('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let blank = !in_fence && line.trim().is_empty();
+        if blank && previous_blank {
+            continue;
+        }
+        previous_blank = blank;
+        normalized.push_str(if blank { "" } else { line });
+        normalized.push('\n');
+    }
+    normalized.pop();
+    normalized
+}
+
+const THINK_TAGS: [(&str, &str); 2] = [("<think>", "</think>"), ("<thinking>", "</thinking>")];
+
+/// Split reasoning-model output into `(answer, reasoning)`.
+///
+/// Handles `<think>…</think>` and `<thinking>…</thinking>` blocks, output that
+/// starts mid-thought with only a closing `</think>` (some servers strip the
+/// opening tag), and an unclosed `<think>` (everything after it is reasoning).
+/// Text without tags is returned unchanged.
+pub fn split_think_tags(text: &str) -> (String, Option<String>) {
+    let mut answer = String::new();
+    let mut reasoning: Vec<String> = Vec::new();
+    let mut rest = text;
+
+    // Opening tag stripped by the server: everything before the first
+    // closing tag is reasoning.
+    for (open, close) in THINK_TAGS {
+        if let Some(end) = rest.find(close).filter(|&end| !rest[..end].contains(open)) {
+            reasoning.push(rest[..end].trim().to_string());
+            rest = &rest[end + close.len()..];
+            break;
+        }
+    }
+
+    loop {
+        let next = THINK_TAGS
+            .iter()
+            .filter_map(|(open, close)| rest.find(open).map(|start| (start, *open, *close)))
+            .min_by_key(|(start, _, _)| *start);
+        let Some((start, open, close)) = next else {
+            answer.push_str(rest);
+            break;
+        };
+        answer.push_str(&rest[..start]);
+        let inner = &rest[start + open.len()..];
+        match inner.find(close) {
+            Some(end) => {
+                reasoning.push(inner[..end].trim().to_string());
+                rest = &inner[end + close.len()..];
+            }
+            None => {
+                reasoning.push(inner.trim().to_string());
+                break;
+            }
+        }
+    }
+
+    if reasoning.is_empty() {
+        return (text.to_string(), None);
+    }
+    reasoning.retain(|r| !r.is_empty());
+    let reasoning = (!reasoning.is_empty()).then(|| reasoning.join("\n\n"));
+    (answer.trim().to_string(), reasoning)
 }
 
 /// Request payload for provider chat calls.
@@ -108,6 +260,8 @@ pub struct StreamChunk {
     pub is_final: bool,
     /// Approximate token count for this chunk (estimated).
     pub token_count: usize,
+    /// Natively streamed tool calls, reassembled; set on the final chunk.
+    pub tool_calls: Vec<ToolCall>,
 }
 
 impl StreamChunk {
@@ -117,6 +271,7 @@ impl StreamChunk {
             delta: text.into(),
             is_final: false,
             token_count: 0,
+            tool_calls: Vec::new(),
         }
     }
 
@@ -126,6 +281,7 @@ impl StreamChunk {
             delta: String::new(),
             is_final: true,
             token_count: 0,
+            tool_calls: Vec::new(),
         }
     }
 
@@ -135,9 +291,16 @@ impl StreamChunk {
             delta: message.into(),
             is_final: true,
             token_count: 0,
+            tool_calls: Vec::new(),
         }
     }
 
+    /// Attach the tool calls the stream produced.
+    pub fn with_tool_calls(mut self, tool_calls: Vec<ToolCall>) -> Self {
+        self.tool_calls = tool_calls;
+        self
+    }
+
     /// Estimate tokens (rough approximation: ~4 chars per token).
     pub fn with_token_estimate(mut self) -> Self {
         self.token_count = self.delta.len().div_ceil(4);
@@ -205,6 +368,23 @@ pub struct ProviderCapabilities {
     ///
     /// When `false`, tools must be injected via system prompt as text.
     pub native_tool_calling: bool,
+
+    /// Whether the provider accepts image inputs.
+    ///
+    /// When `true`, image artifacts returned by tools are attached to the
+    /// next user message as `[IMAGE:data:<mime>;base64,...]` markers for
+    /// the provider to translate into its native image parts. When `false`,
+    /// artifacts are referenced by their saved file path instead.
+    pub vision: bool,
+
+    /// Whether the provider can stream responses incrementally.
+    ///
+    /// When `true` and the caller wants live output, tool-less rounds are
+    /// streamed through `stream_chat_with_history` instead of buffered.
+    pub streaming: bool,
+
+    /// Whether the provider's API also serves text embeddings.
+    pub embeddings: bool,
 }
 
 /// Provider-specific tool payload formats.
@@ -333,6 +513,8 @@ pub trait Provider: Send + Sync {
                 return Ok(ChatResponse {
                     text: Some(text),
                     tool_calls: Vec::new(),
+                    reasoning: None,
+                    finish_reason: None,
                 });
             }
         }
@@ -343,6 +525,8 @@ pub trait Provider: Send + Sync {
         Ok(ChatResponse {
             text: Some(text),
             tool_calls: Vec::new(),
+            reasoning: None,
+            finish_reason: None,
         })
     }
 
@@ -351,6 +535,12 @@ pub trait Provider: Send + Sync {
         self.capabilities().native_tool_calling
     }
 
+    /// Whether provider accepts image inputs.
+    /// Default implementation uses capabilities().vision.
+    fn supports_vision(&self) -> bool {
+        self.capabilities().vision
+    }
+
     /// Warm up the HTTP connection pool (TLS handshake, DNS, HTTP/2 setup).
     /// Default implementation is a no-op; providers with HTTP clients should override.
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -371,13 +561,15 @@ pub trait Provider: Send + Sync {
         Ok(ChatResponse {
             text: Some(text),
             tool_calls: Vec::new(),
+            reasoning: None,
+            finish_reason: None,
         })
     }
 
     /// Whether provider supports streaming responses.
-    /// Default implementation returns false.
+    /// Default implementation uses capabilities().streaming.
     fn supports_streaming(&self) -> bool {
-        false
+        self.capabilities().streaming
     }
 
     /// Streaming chat with optional system prompt.
@@ -458,6 +650,9 @@ mod tests {
         fn capabilities(&self) -> ProviderCapabilities {
             ProviderCapabilities {
                 native_tool_calling: true,
+                vision: false,
+                streaming: true,
+                embeddings: false,
             }
         }
 
@@ -493,6 +688,8 @@ mod tests {
         let empty = ChatResponse {
             text: None,
             tool_calls: vec![],
+            reasoning: None,
+            finish_reason: None,
         };
         assert!(!empty.has_tool_calls());
         assert_eq!(empty.text_or_empty(), "");
@@ -504,11 +701,80 @@ mod tests {
                 name: "shell".into(),
                 arguments: "{}".into(),
             }],
+            reasoning: None,
+            finish_reason: None,
         };
         assert!(with_tools.has_tool_calls());
         assert_eq!(with_tools.text_or_empty(), "Let me check");
     }
 
+    #[test]
+    fn split_think_tags_separates_reasoning() {
+        let (answer, reasoning) =
+            split_think_tags("<think>User greets me.</think>\n\nHello there!");
+        assert_eq!(answer, "Hello there!");
+        assert_eq!(reasoning.as_deref(), Some("User greets me."));
+
+        let (answer, reasoning) = split_think_tags("A<thinking>one</thinking>B<think>two</think>C");
+        assert_eq!(answer, "ABC");
+        assert_eq!(reasoning.as_deref(), Some("one\n\ntwo"));
+
+        // Opening tag stripped by the server.
+        let (answer, reasoning) = split_think_tags("planning the reply</think>Done.");
+        assert_eq!(answer, "Done.");
+        assert_eq!(reasoning.as_deref(), Some("planning the reply"));
+
+        // Output cut off mid-thought.
+        let (answer, reasoning) = split_think_tags("Hi <think>still going");
+        assert_eq!(answer, "Hi");
+        assert_eq!(reasoning.as_deref(), Some("still going"));
+
+        let (answer, reasoning) = split_think_tags("no tags here ");
+        assert_eq!(answer, "no tags here ");
+        assert!(reasoning.is_none());
+    }
+
+    #[test]
+    fn normalize_output_fixes_line_endings_and_blank_runs() {
+        assert_eq!(
+            normalize_output("Done.\r\n\r\n\r\n  \r\nNext\rline\r\n"),
+            "Done.\n\nNext\nline\n"
+        );
+        // Blank lines inside a fence are code and stay.
+        assert_eq!(
+            normalize_output("```py\r\na = 1\r\n\r\n\r\nb = 2\r\n```\r\n\r\n\r\nok"),
+            "```py\na = 1\n\n\nb = 2\n```\n\nok"
+        );
+        assert_eq!(normalize_output(""), "");
+    }
+
+    #[test]
+    fn with_reasoning_separated_merges_field_and_tags() {
+        let response = ChatResponse {
+            text: Some("<think>inline</think><tool_call>{}</tool_call>".into()),
+            tool_calls: vec![],
+            reasoning: Some("structured".into()),
+            finish_reason: None,
+        }
+        .with_reasoning_separated();
+        assert_eq!(response.text.as_deref(), Some("<tool_call>{}</tool_call>"));
+        assert_eq!(response.reasoning.as_deref(), Some("structured\n\ninline"));
+
+        let response = ChatResponse {
+            text: Some("<think>only thinking</think>".into()),
+            tool_calls: vec![ToolCall {
+                id: "1".into(),
+                name: "shell".into(),
+                arguments: "{}".into(),
+            }],
+            reasoning: None,
+            finish_reason: None,
+        }
+        .with_reasoning_separated();
+        assert!(response.text.is_none());
+        assert_eq!(response.reasoning.as_deref(), Some("only thinking"));
+    }
+
     #[test]
     fn tool_call_serialization() {
         let tc = ToolCall {
@@ -539,18 +805,24 @@ mod tests {
     fn provider_capabilities_default() {
         let caps = ProviderCapabilities::default();
         assert!(!caps.native_tool_calling);
+        assert!(!caps.vision);
+        assert!(!caps.streaming);
+        assert!(!caps.embeddings);
     }
 
     #[test]
     fn provider_capabilities_equality() {
         let caps1 = ProviderCapabilities {
             native_tool_calling: true,
+            ..ProviderCapabilities::default()
         };
         let caps2 = ProviderCapabilities {
             native_tool_calling: true,
+            ..ProviderCapabilities::default()
         };
         let caps3 = ProviderCapabilities {
             native_tool_calling: false,
+            ..ProviderCapabilities::default()
         };
 
         assert_eq!(caps1, caps2);
@@ -561,6 +833,7 @@ mod tests {
     fn supports_native_tools_reflects_capabilities_default_mapping() {
         let provider = CapabilityMockProvider;
         assert!(provider.supports_native_tools());
+        assert!(provider.supports_streaming());
     }
 
     #[test]
@@ -852,6 +1125,26 @@ mod tests {
         assert!(text.contains("CUSTOM_TOOL_INSTRUCTIONS"));
     }
 
+    #[test]
+    fn finish_reason_parses_provider_spellings() {
+        assert_eq!(FinishReason::parse("stop"), FinishReason::Stop);
+        assert_eq!(FinishReason::parse("end_turn"), FinishReason::Stop);
+        assert_eq!(FinishReason::parse("length"), FinishReason::Length);
+        assert_eq!(FinishReason::parse("max_tokens"), FinishReason::Length);
+        assert_eq!(FinishReason::parse("MAX_TOKENS"), FinishReason::Length);
+        assert_eq!(
+            FinishReason::parse("content_filter"),
+            FinishReason::ContentFilter
+        );
+        assert_eq!(FinishReason::parse("SAFETY"), FinishReason::ContentFilter);
+        assert_eq!(FinishReason::parse("tool_use"), FinishReason::ToolCalls);
+        assert_eq!(
+            FinishReason::parse("pause_turn"),
+            FinishReason::Other("pause_turn".into())
+        );
+        assert_eq!(FinishReason::Length.to_string(), "length");
+    }
+
     #[tokio::test]
     async fn provider_chat_prompt_guided_rejects_non_prompt_payload() {
         let provider = InvalidConvertProvider;
@@ -3,7 +3,7 @@ pub mod native;
 pub mod traits;
 
 pub use docker::DockerRuntime;
-pub use native::NativeRuntime;
+pub use native::{NativeRuntime, WINDOWS_SHELLS};
 pub use traits::RuntimeAdapter;
 
 use crate::config::RuntimeConfig;
@@ -11,7 +11,18 @@ use crate::config::RuntimeConfig;
 /// Factory: create the right runtime from config
 pub fn create_runtime(config: &RuntimeConfig) -> anyhow::Result<Box<dyn RuntimeAdapter>> {
     match config.kind.as_str() {
-        "native" => Ok(Box::new(NativeRuntime::new())),
+        "native" => {
+            if !WINDOWS_SHELLS.contains(&config.windows_shell.as_str()) {
+                anyhow::bail!(
+                    "Unknown runtime.windows_shell '{}'. Supported values: {}",
+                    config.windows_shell,
+                    WINDOWS_SHELLS.join(", ")
+                );
+            }
+            Ok(Box::new(
+                NativeRuntime::new().with_windows_shell(config.windows_shell.clone()),
+            ))
+        }
         "docker" => Ok(Box::new(DockerRuntime::new(config.docker.clone()))),
         "cloudflare" => anyhow::bail!(
             "runtime.kind='cloudflare' is not implemented yet. Use runtime.kind='native' for now."
@@ -1,12 +1,45 @@
 use super::traits::RuntimeAdapter;
 use std::path::{Path, PathBuf};
 
-/// Native runtime — full access, runs on Mac/Linux/Docker/Raspberry Pi
-pub struct NativeRuntime;
+/// Native runtime — full access, runs on Mac/Linux/Windows/Docker/Raspberry Pi
+pub struct NativeRuntime {
+    windows_shell: String,
+}
+
+/// Shells accepted for `runtime.windows_shell`.
+pub const WINDOWS_SHELLS: &[&str] = &["cmd", "powershell", "pwsh"];
 
 impl NativeRuntime {
     pub fn new() -> Self {
-        Self
+        Self {
+            windows_shell: "cmd".into(),
+        }
+    }
+
+    /// Shell that runs commands on Windows: `cmd` (default), `powershell`
+    /// or `pwsh`. Other platforms always use `sh -c`.
+    pub fn with_windows_shell(mut self, shell: impl Into<String>) -> Self {
+        self.windows_shell = shell.into();
+        self
+    }
+}
+
+/// Program and leading arguments that run a command string through the
+/// platform shell.
+fn shell_invocation(windows_shell: &str) -> (&'static str, &'static [&'static str]) {
+    if cfg!(windows) {
+        windows_shell_invocation(windows_shell)
+    } else {
+        ("sh", &["-c"])
+    }
+}
+
+fn windows_shell_invocation(shell: &str) -> (&'static str, &'static [&'static str]) {
+    const POWERSHELL_ARGS: &[&str] = &["-NoLogo", "-NoProfile", "-NonInteractive", "-Command"];
+    match shell {
+        "powershell" => ("powershell", POWERSHELL_ARGS),
+        "pwsh" => ("pwsh", POWERSHELL_ARGS),
+        _ => ("cmd", &["/C"]),
     }
 }
 
@@ -39,8 +72,9 @@ impl RuntimeAdapter for NativeRuntime {
         command: &str,
         workspace_dir: &Path,
     ) -> anyhow::Result<tokio::process::Command> {
-        let mut process = tokio::process::Command::new("sh");
-        process.arg("-c").arg(command).current_dir(workspace_dir);
+        let (program, args) = shell_invocation(&self.windows_shell);
+        let mut process = tokio::process::Command::new(program);
+        process.args(args).arg(command).current_dir(workspace_dir);
         Ok(process)
     }
 }
@@ -89,4 +123,36 @@ mod tests {
         let debug = format!("{command:?}");
         assert!(debug.contains("echo hello"));
     }
+
+    #[test]
+    fn windows_shell_invocations() {
+        assert_eq!(windows_shell_invocation("cmd"), ("cmd", &["/C"][..]));
+        let (program, args) = windows_shell_invocation("pwsh");
+        assert_eq!(program, "pwsh");
+        assert_eq!(args.last(), Some(&"-Command"));
+        assert_eq!(windows_shell_invocation("powershell").0, "powershell");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_ignores_windows_shell_setting() {
+        assert_eq!(shell_invocation("pwsh"), ("sh", &["-c"][..]));
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn windows_runs_commands_through_configured_shell() {
+        let cwd = std::env::temp_dir();
+        for shell in WINDOWS_SHELLS.iter().filter(|s| **s != "pwsh") {
+            let output = NativeRuntime::new()
+                .with_windows_shell(*shell)
+                .build_shell_command("echo hello", &cwd)
+                .unwrap()
+                .output()
+                .await
+                .unwrap();
+            assert!(output.status.success(), "{shell} failed");
+            assert!(String::from_utf8_lossy(&output.stdout).contains("hello"));
+        }
+    }
 }
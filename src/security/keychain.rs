@@ -0,0 +1,310 @@
+//! Secrets kept in the OS keychain.
+//!
+//! `zeroclaw secret add <name>` stores a value under the `zeroclaw` service
+//! in the platform keychain (macOS Keychain, the Secret Service on Linux,
+//! Windows Credential Manager). Any string in `config.toml` written as
+//! `"$secret:<name>"` is replaced with that value when the config is loaded,
+//! and written back as the placeholder when the config is saved, so the
+//! secret itself never reaches the file.
+//!
+//! The keychain backends are only compiled in with the `keychain` feature;
+//! without it, storing a secret or loading a config with a placeholder fails
+//! with an error saying so.
+
+use anyhow::{bail, Context, Result};
+use std::io::{IsTerminal, Read};
+use toml::Value;
+
+/// Keychain service the secrets are stored under.
+const SERVICE: &str = "zeroclaw";
+
+/// Prefix marking a config value as a keychain reference.
+pub const PLACEHOLDER_PREFIX: &str = "$secret:";
+
+/// The secret a config value refers to, if it is a `"$secret:<name>"`
+/// placeholder.
+pub fn placeholder_name(value: &str) -> Option<&str> {
+    value
+        .strip_prefix(PLACEHOLDER_PREFIX)
+        .filter(|name| !name.is_empty())
+}
+
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        bail!("Invalid secret name '{name}': use letters, digits, '-', '_' or '.'");
+    }
+    Ok(())
+}
+
+/// Error for builds without the `keychain` feature.
+const NO_KEYCHAIN: &str =
+    "This build has no keychain support; rebuild zeroclaw with `--features keychain`";
+
+/// Fail before prompting for a value the keychain could not store.
+fn ensure_available() -> Result<()> {
+    if cfg!(feature = "keychain") {
+        Ok(())
+    } else {
+        bail!(NO_KEYCHAIN)
+    }
+}
+
+#[cfg(feature = "keychain")]
+fn entry(name: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, name)
+        .with_context(|| format!("Failed to open keychain entry for secret '{name}'"))
+}
+
+/// Store `value` as secret `name`, replacing any previous value.
+#[cfg(feature = "keychain")]
+pub fn set(name: &str, value: &str) -> Result<()> {
+    validate_name(name)?;
+    entry(name)?
+        .set_password(value)
+        .with_context(|| format!("Failed to store secret '{name}' in the system keychain"))
+}
+
+/// Store `value` as secret `name`, replacing any previous value.
+#[cfg(not(feature = "keychain"))]
+pub fn set(name: &str, _value: &str) -> Result<()> {
+    validate_name(name)?;
+    bail!(NO_KEYCHAIN)
+}
+
+/// Fetch secret `name` from the keychain.
+#[cfg(feature = "keychain")]
+pub fn get(name: &str) -> Result<String> {
+    match entry(name)?.get_password() {
+        Ok(value) => Ok(value),
+        Err(keyring::Error::NoEntry) => bail!(
+            "Secret '{name}' is not in the system keychain; add it with `zeroclaw secret add {name}`"
+        ),
+        Err(e) => Err(e)
+            .with_context(|| format!("Failed to read secret '{name}' from the system keychain")),
+    }
+}
+
+/// Fetch secret `name` from the keychain.
+#[cfg(not(feature = "keychain"))]
+pub fn get(name: &str) -> Result<String> {
+    bail!("Secret '{name}' cannot be read. {NO_KEYCHAIN}")
+}
+
+/// A config value filled in from the keychain.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretRef {
+    /// Table keys (and array indices) leading to the value.
+    pub path: Vec<String>,
+    pub name: String,
+    value: String,
+}
+
+// Hand-written so the secret never ends up in debug output.
+impl std::fmt::Debug for SecretRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretRef")
+            .field("path", &self.path)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Replace every `"$secret:<name>"` string in `value` with `lookup(name)`
+/// and return where each one was.
+pub fn resolve_placeholders(
+    value: &mut Value,
+    lookup: &mut dyn FnMut(&str) -> Result<String>,
+) -> Result<Vec<SecretRef>> {
+    let mut resolved = Vec::new();
+    resolve_at(value, &mut Vec::new(), lookup, &mut resolved)?;
+    Ok(resolved)
+}
+
+fn resolve_at(
+    value: &mut Value,
+    path: &mut Vec<String>,
+    lookup: &mut dyn FnMut(&str) -> Result<String>,
+    resolved: &mut Vec<SecretRef>,
+) -> Result<()> {
+    match value {
+        Value::String(s) => {
+            if let Some(name) = placeholder_name(s) {
+                let name = name.to_string();
+                let secret = lookup(&name).with_context(|| {
+                    format!("Failed to resolve config value {}", path.join("."))
+                })?;
+                *s = secret.clone();
+                resolved.push(SecretRef {
+                    path: path.clone(),
+                    name,
+                    value: secret,
+                });
+            }
+        }
+        Value::Table(table) => {
+            for (key, entry) in table.iter_mut() {
+                path.push(key.clone());
+                resolve_at(entry, path, lookup, resolved)?;
+                path.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (index, entry) in items.iter_mut().enumerate() {
+                path.push(index.to_string());
+                resolve_at(entry, path, lookup, resolved)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn value_at<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, key| match value {
+        Value::Table(table) => table.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+fn value_at_mut<'a>(value: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    path.iter().try_fold(value, |value, key| match value {
+        Value::Table(table) => table.get_mut(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+        _ => None,
+    })
+}
+
+/// The references whose value in `value` is still the secret they
+/// resolved to; ones changed since loading are left out.
+pub fn unchanged(value: &Value, refs: &[SecretRef]) -> Vec<SecretRef> {
+    refs.iter()
+        .filter(|r| value_at(value, &r.path).and_then(Value::as_str) == Some(r.value.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Write each reference back into `value` as its placeholder.
+pub fn restore_placeholders(value: &mut Value, refs: &[SecretRef]) {
+    for r in refs {
+        if let Some(slot) = value_at_mut(value, &r.path) {
+            *slot = Value::String(format!("{PLACEHOLDER_PREFIX}{}", r.name));
+        }
+    }
+}
+
+/// Handle `zeroclaw secret` subcommands.
+pub fn handle_command(command: crate::SecretCommands) -> Result<()> {
+    match command {
+        crate::SecretCommands::Add { name } => {
+            validate_name(&name)?;
+            ensure_available()?;
+            let value = if std::io::stdin().is_terminal() {
+                dialoguer::Password::new()
+                    .with_prompt(format!("Value for secret '{name}'"))
+                    .interact()?
+            } else {
+                let mut value = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut value)
+                    .context("Failed to read the secret from stdin")?;
+                value.trim_end_matches(['\r', '\n']).to_string()
+            };
+            if value.is_empty() {
+                bail!("Refusing to store an empty secret");
+            }
+            set(&name, &value)?;
+            crate::term::println(format!(
+                "✅ Stored secret '{name}' in the system keychain. Use it in config.toml as \"{PLACEHOLDER_PREFIX}{name}\"."
+            ));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_keychain(name: &str) -> Result<String> {
+        match name {
+            "openai" => Ok("sk-from-keychain".into()),
+            "bot" => Ok("bot-token".into()),
+            _ => bail!("no secret '{name}'"),
+        }
+    }
+
+    #[test]
+    fn placeholders_are_resolved_anywhere_in_the_config() {
+        let mut value: Value = toml::from_str(
+            r#"
+api_key = "$secret:openai"
+default_model = "gpt-4o"
+
+[channels_config.telegram]
+bot_token = "$secret:bot"
+
+[[provider.custom]]
+api_key = "$secret:openai"
+"#,
+        )
+        .unwrap();
+        let refs = resolve_placeholders(&mut value, &mut fake_keychain).unwrap();
+
+        assert_eq!(value["api_key"].as_str(), Some("sk-from-keychain"));
+        assert_eq!(
+            value["channels_config"]["telegram"]["bot_token"].as_str(),
+            Some("bot-token")
+        );
+        assert_eq!(
+            value["provider"]["custom"][0]["api_key"].as_str(),
+            Some("sk-from-keychain")
+        );
+        assert_eq!(value["default_model"].as_str(), Some("gpt-4o"));
+        let paths: Vec<String> = refs.iter().map(|r| r.path.join(".")).collect();
+        assert_eq!(
+            paths,
+            [
+                "api_key",
+                "channels_config.telegram.bot_token",
+                "provider.custom.0.api_key"
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_secrets_name_the_config_value() {
+        let mut value: Value = toml::from_str("api_key = \"$secret:nope\"").unwrap();
+        let err = resolve_placeholders(&mut value, &mut fake_keychain).unwrap_err();
+        assert!(format!("{err:#}").contains("api_key"), "{err:#}");
+    }
+
+    #[test]
+    fn only_unchanged_values_are_written_back_as_placeholders() {
+        let mut loaded: Value =
+            toml::from_str("api_key = \"$secret:openai\"\n[x]\ntoken = \"$secret:bot\"").unwrap();
+        let refs = resolve_placeholders(&mut loaded, &mut fake_keychain).unwrap();
+
+        loaded["x"]["token"] = Value::String("typed-in".into());
+        let keep = unchanged(&loaded, &refs);
+        assert_eq!(keep.len(), 1);
+        restore_placeholders(&mut loaded, &keep);
+        assert_eq!(loaded["api_key"].as_str(), Some("$secret:openai"));
+        assert_eq!(loaded["x"]["token"].as_str(), Some("typed-in"));
+    }
+
+    #[test]
+    fn secret_names_are_restricted() {
+        assert!(validate_name("openai.prod-key_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("a b").is_err());
+        assert_eq!(placeholder_name("$secret:x"), Some("x"));
+        assert_eq!(placeholder_name("$secret:"), None);
+        assert_eq!(placeholder_name("sk-123"), None);
+    }
+}
@@ -5,6 +5,7 @@ pub mod detect;
 pub mod docker;
 #[cfg(target_os = "linux")]
 pub mod firejail;
+pub mod keychain;
 #[cfg(feature = "sandbox-landlock")]
 pub mod landlock;
 pub mod pairing;
@@ -18,7 +19,7 @@ pub use audit::{AuditEvent, AuditEventType, AuditLogger};
 pub use detect::create_sandbox;
 #[allow(unused_imports)]
 pub use pairing::PairingGuard;
-pub use policy::{AutonomyLevel, SecurityPolicy};
+pub use policy::{AutonomyLevel, ConfirmationMode, PolicyViolation, SecurityPolicy, ToolClass};
 #[allow(unused_imports)]
 pub use secrets::SecretStore;
 #[allow(unused_imports)]
@@ -1,6 +1,8 @@
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Instant;
 
 /// How much autonomy the agent has
@@ -31,6 +33,125 @@ pub enum ToolOperation {
     Act,
 }
 
+/// Why the security policy refused a tool call. Tools return it as their
+/// error, so callers can tell a refusal from a runtime failure by type.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PolicyViolation {
+    #[error("Action blocked: autonomy is read-only")]
+    ReadOnly,
+    #[error("Command not allowed by security policy: {0}")]
+    CommandNotAllowed(String),
+    #[error("Command blocked: high-risk command is disallowed by policy")]
+    HighRiskCommand,
+    /// The command may run once the model resends it with `approved=true`.
+    #[error("Command requires explicit approval (approved=true): {0}-risk operation")]
+    ApprovalRequired(&'static str),
+    #[error("Path not allowed by security policy: {0}")]
+    PathNotAllowed(String),
+    #[error("Rate limit exceeded: action budget exhausted")]
+    RateLimited,
+}
+
+impl PolicyViolation {
+    /// Whether the call was denied outright. Rate limits and missing
+    /// approvals are transient and do not count.
+    pub fn is_denial(&self) -> bool {
+        !matches!(self, Self::ApprovalRequired(_) | Self::RateLimited)
+    }
+}
+
+/// Groups of tools that the policy enables or disables as a whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolClass {
+    /// Tools that reach hosts outside the machine: browser, HTTP requests,
+    /// web search and Composio.
+    Network,
+}
+
+/// Tool names in [`ToolClass::Network`].
+pub const NETWORK_TOOL_NAMES: &[&str] = &[
+    "browser",
+    "browser_open",
+    "browser_screenshot",
+    "browser_js",
+    "http_request",
+    "web_search_tool",
+    "composio",
+];
+
+impl ToolClass {
+    /// The class `tool_name` belongs to, if any.
+    pub fn of(tool_name: &str) -> Option<Self> {
+        NETWORK_TOOL_NAMES
+            .contains(&tool_name)
+            .then_some(Self::Network)
+    }
+}
+
+/// When a call to one tool must be confirmed, overriding the autonomy level
+/// and the `auto_approve` / `always_ask` lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmationMode {
+    /// Confirm every call, even in full autonomy.
+    Always,
+    /// Confirm calls unless the tool only reads (see [`READ_ONLY_TOOL_NAMES`]).
+    WhenDestructive,
+    /// Never confirm.
+    Never,
+}
+
+/// Tools that only observe, so [`ConfirmationMode::WhenDestructive`] lets
+/// them run without a prompt.
+pub const READ_ONLY_TOOL_NAMES: &[&str] = &[
+    "file_read",
+    "memory_recall",
+    "memory_search",
+    "web_search_tool",
+    "image_info",
+    "log_query",
+    "cron_list",
+    "cron_runs",
+    "hardware_board_info",
+    "hardware_memory_map",
+    "hardware_memory_read",
+];
+
+impl ConfirmationMode {
+    /// Parse `always`, `when_destructive` (or `when-destructive`) or `never`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "always" => Some(Self::Always),
+            "when_destructive" => Some(Self::WhenDestructive),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::WhenDestructive => "when_destructive",
+            Self::Never => "never",
+        }
+    }
+
+    /// Whether a call to `tool_name` must be confirmed under this mode.
+    pub fn requires_confirmation(self, tool_name: &str) -> bool {
+        match self {
+            Self::Always => true,
+            Self::WhenDestructive => !READ_ONLY_TOOL_NAMES.contains(&tool_name),
+            Self::Never => false,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfirmationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Sliding-window action tracker for rate limiting.
 #[derive(Debug)]
 pub struct ActionTracker {
@@ -88,6 +209,11 @@ pub struct SecurityPolicy {
     pub max_cost_per_day_cents: u32,
     pub require_approval_for_medium_risk: bool,
     pub block_high_risk_commands: bool,
+    pub allow_network: bool,
+    /// Per-tool confirmation modes set during the session. Clones of the
+    /// policy share the map, so [`SecurityPolicy::set_approval_mode`] takes
+    /// effect everywhere at once.
+    pub require_tool_approval: Arc<RwLock<HashMap<String, ConfirmationMode>>>,
     pub tracker: ActionTracker,
 }
 
@@ -137,6 +263,8 @@ impl Default for SecurityPolicy {
             max_cost_per_day_cents: 500,
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
+            allow_network: false,
+            require_tool_approval: Arc::default(),
             tracker: ActionTracker::new(),
         }
     }
@@ -184,6 +312,42 @@ fn contains_single_ampersand(s: &str) -> bool {
     false
 }
 
+/// Whether `path` is a Windows absolute or drive-relative path: `C:\x`,
+/// `C:/x`, `C:x`, or a UNC/device path (`\\server\share`, `\\?\C:\x`).
+fn is_windows_absolute(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let has_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    has_drive || path.starts_with(r"\\") || path.starts_with("//")
+}
+
+/// `path.starts_with(root)`, with Windows semantics on Windows: verbatim
+/// prefixes (`\\?\`) are ignored, `/` equals `\` and case is folded.
+fn path_within(path: &Path, root: &Path) -> bool {
+    if !cfg!(windows) {
+        return path.starts_with(root);
+    }
+    let path = windows_comparable(path);
+    let root = windows_comparable(root);
+    path == root
+        || path
+            .strip_prefix(&root)
+            .is_some_and(|rest| rest.starts_with('\\'))
+}
+
+/// Normalize a Windows path for prefix comparison: `\\?\C:\Work\` and
+/// `c:/work` both become `c:\work`; `\\?\UNC\srv\share` becomes `\\srv\share`.
+fn windows_comparable(path: &Path) -> String {
+    let raw = path.to_string_lossy().replace('/', "\\");
+    let unprefixed = if let Some(unc) = raw.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{unc}")
+    } else if let Some(rest) = raw.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        raw
+    };
+    unprefixed.trim_end_matches('\\').to_lowercase()
+}
+
 impl SecurityPolicy {
     /// Classify command risk. Any high-risk segment marks the whole command high.
     pub fn command_risk_level(&self, command: &str) -> CommandRiskLevel {
@@ -310,22 +474,19 @@ impl SecurityPolicy {
         &self,
         command: &str,
         approved: bool,
-    ) -> Result<CommandRiskLevel, String> {
+    ) -> Result<CommandRiskLevel, PolicyViolation> {
         if !self.is_command_allowed(command) {
-            return Err(format!("Command not allowed by security policy: {command}"));
+            return Err(PolicyViolation::CommandNotAllowed(command.to_string()));
         }
 
         let risk = self.command_risk_level(command);
 
         if risk == CommandRiskLevel::High {
             if self.block_high_risk_commands {
-                return Err("Command blocked: high-risk command is disallowed by policy".into());
+                return Err(PolicyViolation::HighRiskCommand);
             }
             if self.autonomy == AutonomyLevel::Supervised && !approved {
-                return Err(
-                    "Command requires explicit approval (approved=true): high-risk operation"
-                        .into(),
-                );
+                return Err(PolicyViolation::ApprovalRequired("high"));
             }
         }
 
@@ -334,9 +495,7 @@ impl SecurityPolicy {
             && self.require_approval_for_medium_risk
             && !approved
         {
-            return Err(
-                "Command requires explicit approval (approved=true): medium-risk operation".into(),
-            );
+            return Err(PolicyViolation::ApprovalRequired("medium"));
         }
 
         Ok(risk)
@@ -493,8 +652,12 @@ impl SecurityPolicy {
             path.to_string()
         };
 
-        // Block absolute paths when workspace_only is set
-        if self.workspace_only && Path::new(&expanded).is_absolute() {
+        // Block absolute paths when workspace_only is set. Drive (`C:\`),
+        // drive-relative (`C:foo`) and UNC (`\\server\share`) forms count
+        // as absolute on every platform.
+        if self.workspace_only
+            && (Path::new(&expanded).is_absolute() || is_windows_absolute(&expanded))
+        {
             return false;
         }
 
@@ -511,7 +674,7 @@ impl SecurityPolicy {
                 forbidden.clone()
             };
             let forbidden_path = Path::new(&forbidden_expanded);
-            if expanded_path.starts_with(forbidden_path) {
+            if path_within(expanded_path, forbidden_path) {
                 return false;
             }
         }
@@ -529,7 +692,7 @@ impl SecurityPolicy {
             .workspace_dir
             .canonicalize()
             .unwrap_or_else(|_| self.workspace_dir.clone());
-        resolved.starts_with(workspace_root)
+        path_within(resolved, &workspace_root)
     }
 
     /// Check if autonomy level permits any action at all
@@ -545,18 +708,17 @@ impl SecurityPolicy {
         &self,
         operation: ToolOperation,
         operation_name: &str,
-    ) -> Result<(), String> {
+    ) -> Result<(), PolicyViolation> {
         match operation {
             ToolOperation::Read => Ok(()),
             ToolOperation::Act => {
                 if !self.can_act() {
-                    return Err(format!(
-                        "Security policy: read-only mode, cannot perform '{operation_name}'"
-                    ));
+                    tracing::debug!(operation = operation_name, "Refused in read-only mode");
+                    return Err(PolicyViolation::ReadOnly);
                 }
 
                 if !self.record_action() {
-                    return Err("Rate limit exceeded: action budget exhausted".to_string());
+                    return Err(PolicyViolation::RateLimited);
                 }
 
                 Ok(())
@@ -564,6 +726,33 @@ impl SecurityPolicy {
         }
     }
 
+    /// Whether tools of `class` may be registered at all. In full autonomy no
+    /// human approves individual calls, so network tools need the explicit
+    /// `[autonomy] allow_network = true` opt-in there.
+    pub fn allows_tool_class(&self, class: ToolClass) -> bool {
+        match class {
+            ToolClass::Network => self.autonomy != AutonomyLevel::Full || self.allow_network,
+        }
+    }
+
+    /// Whether the tool called `tool_name` may be registered.
+    pub fn allows_tool(&self, tool_name: &str) -> bool {
+        ToolClass::of(tool_name).is_none_or(|class| self.allows_tool_class(class))
+    }
+
+    /// Set how calls to `tool` are confirmed for the rest of the session.
+    #[allow(clippy::unused_async)]
+    pub async fn set_approval_mode(&self, tool: &str, mode: ConfirmationMode) {
+        self.require_tool_approval
+            .write()
+            .insert(tool.to_string(), mode);
+    }
+
+    /// The confirmation mode set for `tool`, if any.
+    pub fn approval_mode(&self, tool: &str) -> Option<ConfirmationMode> {
+        self.require_tool_approval.read().get(tool).copied()
+    }
+
     /// Record an action and check if the rate limit has been exceeded.
     /// Returns `true` if the action is allowed, `false` if rate-limited.
     pub fn record_action(&self) -> bool {
@@ -591,6 +780,8 @@ impl SecurityPolicy {
             max_cost_per_day_cents: autonomy_config.max_cost_per_day_cents,
             require_approval_for_medium_risk: autonomy_config.require_approval_for_medium_risk,
             block_high_risk_commands: autonomy_config.block_high_risk_commands,
+            allow_network: autonomy_config.allow_network,
+            require_tool_approval: Arc::default(),
             tracker: ActionTracker::new(),
         }
     }
@@ -620,6 +811,64 @@ mod tests {
 
     // ── AutonomyLevel ────────────────────────────────────────
 
+    #[test]
+    fn network_tools_need_opt_in_only_in_full_autonomy() {
+        assert!(default_policy().allows_tool("http_request"));
+        assert!(readonly_policy().allows_tool("composio"));
+        assert!(!full_policy().allows_tool("http_request"));
+        assert!(!full_policy().allows_tool_class(ToolClass::Network));
+        assert!(full_policy().allows_tool("shell"));
+
+        let opted_in = SecurityPolicy {
+            allow_network: true,
+            ..full_policy()
+        };
+        assert!(opted_in.allows_tool("browser_open"));
+        assert_eq!(ToolClass::of("web_search_tool"), Some(ToolClass::Network));
+        assert_eq!(ToolClass::of("file_read"), None);
+    }
+
+    #[tokio::test]
+    async fn set_approval_mode_is_shared_by_clones() {
+        let policy = default_policy();
+        let clone = policy.clone();
+        assert_eq!(policy.approval_mode("shell"), None);
+
+        policy
+            .set_approval_mode("shell", ConfirmationMode::Never)
+            .await;
+        assert_eq!(clone.approval_mode("shell"), Some(ConfirmationMode::Never));
+        clone
+            .set_approval_mode("shell", ConfirmationMode::Always)
+            .await;
+        assert_eq!(
+            policy.approval_mode("shell"),
+            Some(ConfirmationMode::Always)
+        );
+    }
+
+    #[test]
+    fn confirmation_mode_parses_and_classifies_tools() {
+        assert_eq!(
+            ConfirmationMode::parse("when-destructive"),
+            Some(ConfirmationMode::WhenDestructive)
+        );
+        assert_eq!(
+            ConfirmationMode::parse("NEVER"),
+            Some(ConfirmationMode::Never)
+        );
+        assert_eq!(ConfirmationMode::parse("sometimes"), None);
+        assert_eq!(
+            ConfirmationMode::WhenDestructive.to_string(),
+            "when_destructive"
+        );
+
+        assert!(ConfirmationMode::WhenDestructive.requires_confirmation("file_write"));
+        assert!(!ConfirmationMode::WhenDestructive.requires_confirmation("file_read"));
+        assert!(ConfirmationMode::Always.requires_confirmation("file_read"));
+        assert!(!ConfirmationMode::Never.requires_confirmation("shell"));
+    }
+
     #[test]
     fn autonomy_default_is_supervised() {
         assert_eq!(AutonomyLevel::default(), AutonomyLevel::Supervised);
@@ -664,7 +913,8 @@ mod tests {
         let err = p
             .enforce_tool_operation(ToolOperation::Act, "memory_store")
             .unwrap_err();
-        assert!(err.contains("read-only mode"));
+        assert_eq!(err, PolicyViolation::ReadOnly);
+        assert!(err.is_denial());
     }
 
     #[test]
@@ -676,7 +926,8 @@ mod tests {
         let err = p
             .enforce_tool_operation(ToolOperation::Act, "memory_store")
             .unwrap_err();
-        assert!(err.contains("Rate limit exceeded"));
+        assert_eq!(err, PolicyViolation::RateLimited);
+        assert!(!err.is_denial());
     }
 
     // ── is_command_allowed ───────────────────────────────────
@@ -809,8 +1060,7 @@ mod tests {
         };
 
         let denied = p.validate_command_execution("touch test.txt", false);
-        assert!(denied.is_err());
-        assert!(denied.unwrap_err().contains("requires explicit approval"),);
+        assert_eq!(denied, Err(PolicyViolation::ApprovalRequired("medium")));
 
         let allowed = p.validate_command_execution("touch test.txt", true);
         assert_eq!(allowed.unwrap(), CommandRiskLevel::Medium);
@@ -825,16 +1075,14 @@ mod tests {
         };
 
         let result = p.validate_command_execution("rm -rf /tmp/test", true);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("high-risk"));
+        assert_eq!(result, Err(PolicyViolation::HighRiskCommand));
     }
 
     #[test]
     fn validate_command_rejects_background_chain_bypass() {
         let p = default_policy();
         let result = p.validate_command_execution("ls & python3 -c 'print(1)'", false);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not allowed"));
+        assert!(matches!(result, Err(PolicyViolation::CommandNotAllowed(_))));
     }
 
     // ── is_path_allowed ─────────────────────────────────────
@@ -1484,6 +1732,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn is_path_allowed_blocks_windows_absolute_paths() {
+        let policy = default_policy();
+        for path in [
+            r"C:\Windows\System32",
+            "c:/Users/me/.ssh",
+            "D:secrets.txt",
+            r"\\server\share\file",
+            r"\\?\C:\Windows",
+            "//server/share",
+        ] {
+            assert!(!policy.is_path_allowed(path), "{path} must be blocked");
+        }
+        assert!(policy.is_path_allowed(r"src\main.rs"));
+    }
+
+    #[test]
+    fn windows_comparable_strips_verbatim_prefix_and_folds_case() {
+        assert_eq!(
+            windows_comparable(Path::new(r"\\?\C:\Users\Me\Work\")),
+            r"c:\users\me\work"
+        );
+        assert_eq!(windows_comparable(Path::new("C:/users/me")), r"c:\users\me");
+        assert_eq!(
+            windows_comparable(Path::new(r"\\?\UNC\srv\Share\dir")),
+            r"\\srv\share\dir"
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn resolved_path_check_uses_windows_semantics() {
+        let policy = SecurityPolicy {
+            workspace_dir: PathBuf::from(r"C:\Users\Me\Workspace"),
+            ..SecurityPolicy::default()
+        };
+        assert!(policy.is_resolved_path_allowed(Path::new(r"\\?\c:\users\me\workspace\a.txt")));
+        assert!(policy.is_resolved_path_allowed(Path::new(r"C:\USERS\ME\WORKSPACE")));
+        assert!(!policy.is_resolved_path_allowed(Path::new(r"C:\Users\Me\Workspace2\a.txt")));
+        assert!(!policy.is_resolved_path_allowed(Path::new(r"C:\Users\Me\.ssh\id_rsa")));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn forbidden_paths_match_case_insensitively_on_windows() {
+        let policy = SecurityPolicy {
+            workspace_only: false,
+            forbidden_paths: vec![r"C:\Windows".into()],
+            ..SecurityPolicy::default()
+        };
+        assert!(!policy.is_path_allowed(r"c:\windows\system32\drivers"));
+        assert!(policy.is_path_allowed(r"C:\Work\notes.txt"));
+    }
+
     #[test]
     fn is_path_allowed_blocks_url_encoded_traversal() {
         let policy = default_policy();
@@ -9,10 +9,14 @@ use std::time::{Duration, SystemTime};
 const OPEN_SKILLS_REPO_URL: &str = "https://github.com/besoeasy/open-skills";
 const OPEN_SKILLS_SYNC_MARKER: &str = ".zeroclaw-open-skills-sync";
 const OPEN_SKILLS_SYNC_INTERVAL_SECS: u64 = 60 * 60 * 24 * 7;
+/// How many category directories deep skills are searched for; also stops
+/// symlink cycles.
+const MAX_SKILL_DIR_DEPTH: usize = 8;
 
 /// A skill is a user-defined or community-built capability.
-/// Skills live in `~/.zeroclaw/workspace/skills/<name>/SKILL.md`
-/// and can include tool definitions, prompts, and automation scripts.
+/// Skills live in `~/.zeroclaw/workspace/skills/<name>/SKILL.md`, optionally
+/// grouped in category directories (`skills/rust/clippy/SKILL.md` is the skill
+/// `rust/clippy`), and can include tool definitions, prompts, and automation scripts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
     pub name: String,
@@ -28,6 +32,38 @@ pub struct Skill {
     pub prompts: Vec<String>,
     #[serde(skip)]
     pub location: Option<PathBuf>,
+    /// Display title from SKILL.md front matter.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Offer the skill only to models whose name contains one of these
+    /// (case-insensitive); empty means every model.
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Higher-priority skills are listed first in the prompt.
+    #[serde(default)]
+    pub priority: f32,
+}
+
+impl Skill {
+    /// Whether the skill should be offered to `model`.
+    pub fn applies_to_model(&self, model: &str) -> bool {
+        let model = model.to_lowercase();
+        self.models.is_empty()
+            || self
+                .models
+                .iter()
+                .any(|m| model.contains(&m.to_lowercase()))
+    }
+}
+
+/// Skills offered to `model`, highest priority first (ties keep load order).
+pub fn skills_for_model<'a>(skills: &'a [Skill], model: &str) -> Vec<&'a Skill> {
+    let mut applicable: Vec<&Skill> = skills
+        .iter()
+        .filter(|skill| skill.applies_to_model(model))
+        .collect();
+    applicable.sort_by(|a, b| b.priority.total_cmp(&a.priority));
+    applicable
 }
 
 /// A tool defined by a skill (shell command, HTTP call, etc.)
@@ -63,38 +99,107 @@ struct SkillMeta {
     author: Option<String>,
     #[serde(default)]
     tags: Vec<String>,
+    #[serde(default)]
+    models: Vec<String>,
+    #[serde(default)]
+    priority: f32,
+}
+
+/// Metadata from the YAML front matter of a SKILL.md file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SkillFrontMatter {
+    title: Option<String>,
+    description: Option<String>,
+    version: Option<String>,
+    author: Option<String>,
+    tags: Vec<String>,
+    models: Vec<String>,
+    priority: f32,
+}
+
+/// Split `---`-delimited YAML front matter off a Markdown skill. Files
+/// without front matter return the default metadata and the whole content.
+fn parse_front_matter(content: &str) -> Result<(SkillFrontMatter, &str)> {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return Ok((SkillFrontMatter::default(), content));
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let yaml = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            let meta = if yaml.trim().is_empty() {
+                SkillFrontMatter::default()
+            } else {
+                serde_norway::from_str(yaml)?
+            };
+            return Ok((meta, body));
+        }
+        offset += line.len();
+    }
+    anyhow::bail!("Unterminated front matter: missing closing `---`")
 }
 
 fn default_version() -> String {
     "0.1.0".to_string()
 }
 
-/// Load all skills from the workspace skills directory
-pub fn load_skills(workspace_dir: &Path) -> Vec<Skill> {
+/// Load all skills: open-skills, then each of `extra_dirs` in order, then
+/// the workspace skills directory. A skill from a later source replaces a
+/// same-named skill from an earlier one.
+pub fn load_skills(workspace_dir: &Path, extra_dirs: &[PathBuf]) -> Vec<Skill> {
     let mut skills = Vec::new();
 
     if let Some(open_skills_dir) = ensure_open_skills_repo() {
-        skills.extend(load_open_skills(&open_skills_dir));
+        merge_skills(&mut skills, load_open_skills(&open_skills_dir));
+    }
+
+    for dir in extra_dirs {
+        merge_skills(&mut skills, load_skills_from_directory(dir));
     }
 
-    skills.extend(load_workspace_skills(workspace_dir));
+    merge_skills(&mut skills, load_workspace_skills(workspace_dir));
     skills
 }
 
+/// Append `source`, replacing (in place) any skill with the same name.
+fn merge_skills(skills: &mut Vec<Skill>, source: Vec<Skill>) {
+    for skill in source {
+        match skills
+            .iter_mut()
+            .find(|existing| existing.name == skill.name)
+        {
+            Some(existing) => *existing = skill,
+            None => skills.push(skill),
+        }
+    }
+}
+
 fn load_workspace_skills(workspace_dir: &Path) -> Vec<Skill> {
     let skills_dir = workspace_dir.join("skills");
     load_skills_from_directory(&skills_dir)
 }
 
 fn load_skills_from_directory(skills_dir: &Path) -> Vec<Skill> {
-    if !skills_dir.exists() {
-        return Vec::new();
-    }
-
     let mut skills = Vec::new();
+    collect_skills(skills_dir, "", 0, &mut skills);
+    skills
+}
 
-    let Ok(entries) = std::fs::read_dir(skills_dir) else {
-        return skills;
+/// Walk `dir`, which sits at `prefix` (`""` or `"rust/"`) below the skills
+/// root. A directory with a SKILL.toml or SKILL.md is a skill; any other
+/// directory is a category and is searched for nested skills.
+fn collect_skills(dir: &Path, prefix: &str, depth: usize, skills: &mut Vec<Skill>) {
+    if depth > MAX_SKILL_DIR_DEPTH {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
     };
 
     for entry in entries.flatten() {
@@ -102,6 +207,10 @@ fn load_skills_from_directory(skills_dir: &Path) -> Vec<Skill> {
         if !path.is_dir() {
             continue;
         }
+        let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let relative_name = format!("{prefix}{dir_name}");
 
         // Try SKILL.toml first, then SKILL.md
         let manifest_path = path.join("SKILL.toml");
@@ -112,13 +221,13 @@ fn load_skills_from_directory(skills_dir: &Path) -> Vec<Skill> {
                 skills.push(skill);
             }
         } else if md_path.exists() {
-            if let Ok(skill) = load_skill_md(&md_path, &path) {
+            if let Ok(skill) = load_skill_md(&md_path, &relative_name) {
                 skills.push(skill);
             }
+        } else {
+            collect_skills(&path, &format!("{relative_name}/"), depth + 1, skills);
         }
     }
-
-    skills
 }
 
 fn load_open_skills(repo_dir: &Path) -> Vec<Skill> {
@@ -301,27 +410,34 @@ fn load_skill_toml(path: &Path) -> Result<Skill> {
         tools: manifest.tools,
         prompts: manifest.prompts,
         location: Some(path.to_path_buf()),
+        title: None,
+        models: manifest.skill.models,
+        priority: manifest.skill.priority,
     })
 }
 
-/// Load a skill from a SKILL.md file (simpler format)
-fn load_skill_md(path: &Path, dir: &Path) -> Result<Skill> {
+/// Load a skill from a SKILL.md file (simpler format). `name` is the skill
+/// directory's path relative to the skills root, e.g. `rust/clippy`.
+/// YAML front matter may set `title`, `description`, `version`, `author`,
+/// `tags`, `models` and `priority`.
+fn load_skill_md(path: &Path, name: &str) -> Result<Skill> {
     let content = std::fs::read_to_string(path)?;
-    let name = dir
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
+    let (meta, body) = parse_front_matter(&content)?;
 
     Ok(Skill {
-        name,
-        description: extract_description(&content),
-        version: "0.1.0".to_string(),
-        author: None,
-        tags: Vec::new(),
+        name: name.to_string(),
+        description: meta
+            .description
+            .unwrap_or_else(|| extract_description(body)),
+        version: meta.version.unwrap_or_else(|| "0.1.0".to_string()),
+        author: meta.author,
+        tags: meta.tags,
         tools: Vec::new(),
-        prompts: vec![content],
+        prompts: vec![body.to_string()],
         location: Some(path.to_path_buf()),
+        title: meta.title,
+        models: meta.models,
+        priority: meta.priority,
     })
 }
 
@@ -342,6 +458,9 @@ fn load_open_skill_md(path: &Path) -> Result<Skill> {
         tools: Vec::new(),
         prompts: vec![content],
         location: Some(path.to_path_buf()),
+        title: None,
+        models: Vec::new(),
+        priority: 0.0,
     })
 }
 
@@ -453,10 +572,11 @@ fn copy_dir_recursive(src: &Path, dest: &Path) -> Result<()> {
 
 /// Handle the `skills` CLI command
 #[allow(clippy::too_many_lines)]
-pub fn handle_command(command: crate::SkillCommands, workspace_dir: &Path) -> Result<()> {
+pub fn handle_command(command: crate::SkillCommands, config: &crate::config::Config) -> Result<()> {
+    let workspace_dir = &config.workspace_dir;
     match command {
         crate::SkillCommands::List => {
-            let skills = load_skills(workspace_dir);
+            let skills = load_skills(workspace_dir, &config.skills.dir_paths());
             if skills.is_empty() {
                 println!("No skills installed.");
                 println!();
@@ -472,8 +592,11 @@ pub fn handle_command(command: crate::SkillCommands, workspace_dir: &Path) -> Re
                         "  {} {} — {}",
                         console::style(&skill.name).white().bold(),
                         console::style(format!("v{}", skill.version)).dim(),
-                        skill.description
+                        skill.title.as_deref().unwrap_or(&skill.description)
                     );
+                    if !skill.models.is_empty() {
+                        println!("    Models: {}", skill.models.join(", "));
+                    }
                     if !skill.tools.is_empty() {
                         println!(
                             "    Tools: {}",
@@ -585,8 +708,8 @@ pub fn handle_command(command: crate::SkillCommands, workspace_dir: &Path) -> Re
             Ok(())
         }
         crate::SkillCommands::Remove { name } => {
-            // Reject path traversal attempts
-            if name.contains("..") || name.contains('/') || name.contains('\\') {
+            // Reject path traversal attempts; `/` separates nested skill names
+            if name.contains("..") || name.starts_with('/') || name.contains('\\') {
                 anyhow::bail!("Invalid skill name: {name}");
             }
 
@@ -626,7 +749,7 @@ mod tests {
     #[test]
     fn load_empty_skills_dir() {
         let dir = tempfile::tempdir().unwrap();
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert!(skills.is_empty());
     }
 
@@ -655,7 +778,7 @@ command = "echo hello"
         )
         .unwrap();
 
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert_eq!(skills.len(), 1);
         assert_eq!(skills[0].name, "test-skill");
         assert_eq!(skills[0].tools.len(), 1);
@@ -675,7 +798,7 @@ command = "echo hello"
         )
         .unwrap();
 
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert_eq!(skills.len(), 1);
         assert_eq!(skills[0].name, "md-skill");
         assert!(skills[0].description.contains("cool things"));
@@ -698,6 +821,9 @@ command = "echo hello"
             tools: vec![],
             prompts: vec!["Do the thing.".to_string()],
             location: None,
+            title: None,
+            models: vec![],
+            priority: 0.0,
         }];
         let prompt = skills_to_prompt(&skills);
         assert!(prompt.contains("test"));
@@ -723,7 +849,7 @@ command = "echo hello"
     fn load_nonexistent_dir() {
         let dir = tempfile::tempdir().unwrap();
         let fake = dir.path().join("nonexistent");
-        let skills = load_skills(&fake);
+        let skills = load_skills(&fake, &[]);
         assert!(skills.is_empty());
     }
 
@@ -734,7 +860,7 @@ command = "echo hello"
         fs::create_dir_all(&skills_dir).unwrap();
         // A file, not a directory — should be ignored
         fs::write(skills_dir.join("not-a-skill.txt"), "hello").unwrap();
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert!(skills.is_empty());
     }
 
@@ -745,10 +871,92 @@ command = "echo hello"
         let empty_skill = skills_dir.join("empty-skill");
         fs::create_dir_all(&empty_skill).unwrap();
         // Directory exists but no SKILL.toml or SKILL.md
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert!(skills.is_empty());
     }
 
+    #[test]
+    fn load_nested_skills_named_by_relative_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let skills_dir = dir.path().join("skills");
+        for path in ["rust/clippy", "python/lint/ruff", "top"] {
+            let skill_dir = skills_dir.join(path);
+            fs::create_dir_all(&skill_dir).unwrap();
+            fs::write(skill_dir.join("SKILL.md"), format!("# {path}\nNested.\n")).unwrap();
+        }
+        // Directories inside a skill are the skill's own files, not skills.
+        let inner = skills_dir.join("top/examples");
+        fs::create_dir_all(&inner).unwrap();
+        fs::write(inner.join("SKILL.md"), "# inner\n").unwrap();
+
+        let mut names: Vec<String> = load_skills(dir.path(), &[])
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["python/lint/ruff", "rust/clippy", "top"]);
+    }
+
+    #[test]
+    fn md_skill_front_matter_sets_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_dir = dir.path().join("skills").join("review");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\ntitle: Code Review\ntags: [review, quality]\nversion: 2.1.0\nmodels: [claude, gpt-4]\npriority: 1.5\n---\n# Review\nCheck diffs carefully.\n",
+        )
+        .unwrap();
+
+        let skills = load_skills(dir.path(), &[]);
+        assert_eq!(skills.len(), 1);
+        let skill = &skills[0];
+        assert_eq!(skill.name, "review");
+        assert_eq!(skill.title.as_deref(), Some("Code Review"));
+        assert_eq!(skill.tags, vec!["review", "quality"]);
+        assert_eq!(skill.version, "2.1.0");
+        assert_eq!(skill.models, vec!["claude", "gpt-4"]);
+        assert!((skill.priority - 1.5).abs() < f32::EPSILON);
+        assert_eq!(skill.description, "Check diffs carefully.");
+        assert!(!skill.prompts[0].contains("priority"));
+    }
+
+    #[test]
+    fn front_matter_requires_closing_delimiter() {
+        assert!(parse_front_matter("---\ntitle: x\n# body\n").is_err());
+        let (meta, body) = parse_front_matter("# Plain\ntext\n").unwrap();
+        assert!(meta.title.is_none());
+        assert_eq!(body, "# Plain\ntext\n");
+    }
+
+    #[test]
+    fn skills_for_model_filters_and_orders_by_priority() {
+        let skill = |name: &str, models: &[&str], priority: f32| Skill {
+            name: name.into(),
+            description: String::new(),
+            version: "1.0.0".into(),
+            author: None,
+            tags: Vec::new(),
+            tools: Vec::new(),
+            prompts: Vec::new(),
+            location: None,
+            title: None,
+            models: models.iter().map(|m| (*m).to_string()).collect(),
+            priority,
+        };
+        let skills = vec![
+            skill("any", &[], 0.0),
+            skill("claude-only", &["Claude"], 2.0),
+            skill("gpt-only", &["gpt-4"], 5.0),
+        ];
+
+        let names: Vec<&str> = skills_for_model(&skills, "anthropic/claude-sonnet-4")
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["claude-only", "any"]);
+    }
+
     #[test]
     fn load_multiple_skills() {
         let dir = tempfile::tempdir().unwrap();
@@ -764,7 +972,7 @@ command = "echo hello"
             .unwrap();
         }
 
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert_eq!(skills.len(), 3);
     }
 
@@ -806,7 +1014,7 @@ command = "https://api.example.com/deploy"
         )
         .unwrap();
 
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert_eq!(skills.len(), 1);
         let s = &skills[0];
         assert_eq!(s.name, "multi-tool");
@@ -836,7 +1044,7 @@ description = "Bare minimum"
         )
         .unwrap();
 
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert_eq!(skills.len(), 1);
         assert_eq!(skills[0].version, "0.1.0"); // default version
         assert!(skills[0].author.is_none());
@@ -853,7 +1061,7 @@ description = "Bare minimum"
 
         fs::write(skill_dir.join("SKILL.toml"), "this is not valid toml {{{{").unwrap();
 
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert!(skills.is_empty()); // broken skill is skipped
     }
 
@@ -866,7 +1074,7 @@ description = "Bare minimum"
 
         fs::write(skill_dir.join("SKILL.md"), "# Just a Heading\n").unwrap();
 
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert_eq!(skills.len(), 1);
         assert_eq!(skills[0].description, "No description");
     }
@@ -888,6 +1096,9 @@ description = "Bare minimum"
             }],
             prompts: vec![],
             location: None,
+            title: None,
+            models: vec![],
+            priority: 0.0,
         }];
         let prompt = skills_to_prompt(&skills);
         assert!(prompt.contains("weather"));
@@ -917,10 +1128,49 @@ description = "Bare minimum"
         .unwrap();
         fs::write(skill_dir.join("SKILL.md"), "# From MD\nMD description\n").unwrap();
 
-        let skills = load_skills(dir.path());
+        let skills = load_skills(dir.path(), &[]);
         assert_eq!(skills.len(), 1);
         assert_eq!(skills[0].name, "from-toml"); // TOML takes priority
     }
+
+    fn write_skill(skills_dir: &Path, name: &str, description: &str) {
+        let skill_dir = skills_dir.join(name);
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.toml"),
+            format!("[skill]\nname = \"{name}\"\ndescription = \"{description}\"\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn later_skill_dirs_override_same_named_skills() {
+        let dir = tempfile::tempdir().unwrap();
+        let team = dir.path().join("team");
+        let personal = dir.path().join("personal");
+        write_skill(&team, "deploy", "team deploy");
+        write_skill(&team, "review", "team review");
+        write_skill(&personal, "deploy", "personal deploy");
+
+        let skills = load_skills(&dir.path().join("workspace"), &[team, personal]);
+        assert_eq!(skills.len(), 2);
+        let deploy = skills.iter().find(|s| s.name == "deploy").unwrap();
+        assert_eq!(deploy.description, "personal deploy");
+        let review = skills.iter().find(|s| s.name == "review").unwrap();
+        assert_eq!(review.description, "team review");
+    }
+
+    #[test]
+    fn workspace_skills_override_extra_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared = dir.path().join("shared");
+        write_skill(&shared, "deploy", "shared deploy");
+        write_skill(&dir.path().join("skills"), "deploy", "workspace deploy");
+
+        let skills = load_skills(dir.path(), &[shared]);
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].description, "workspace deploy");
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,226 @@
+//! Terminal styling for CLI status output.
+//!
+//! Status lines carry emoji markers and ANSI dimming, which garble logs
+//! under cron, CI or a pipe. Every status line goes through this module:
+//! when a stream is not styled, [`render`] swaps the markers for ASCII tags
+//! (`[tool]`, `[ok]`, `[err]`, ...) and strips escape codes. Whether stdout
+//! and stderr are styled is decided once by [`init`] from `--color`,
+//! `NO_COLOR`, `CLICOLOR_FORCE` and whether each stream is a terminal.
+//! Model output is printed as-is with [`response`].
+
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// `--color auto|always|never`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Style a stream only when it is a terminal (default).
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(Self::Auto),
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Whether a stream gets emoji and color. An explicit `--color` wins;
+/// in `auto`, a non-empty `CLICOLOR_FORCE` other than `0` turns styling on,
+/// then a non-empty `NO_COLOR` turns it off, then the stream must be a
+/// terminal.
+pub fn resolve(
+    choice: ColorChoice,
+    no_color: Option<&str>,
+    clicolor_force: Option<&str>,
+    is_terminal: bool,
+) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            if clicolor_force.is_some_and(|v| !v.is_empty() && v != "0") {
+                true
+            } else if no_color.is_some_and(|v| !v.is_empty()) {
+                false
+            } else {
+                is_terminal
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Styling {
+    stdout: bool,
+    stderr: bool,
+}
+
+static STYLING: OnceLock<Styling> = OnceLock::new();
+
+fn detect(choice: ColorChoice) -> Styling {
+    let no_color = std::env::var("NO_COLOR").ok();
+    let clicolor_force = std::env::var("CLICOLOR_FORCE").ok();
+    let styled = |is_terminal| {
+        resolve(
+            choice,
+            no_color.as_deref(),
+            clicolor_force.as_deref(),
+            is_terminal,
+        )
+    };
+    Styling {
+        stdout: styled(std::io::stdout().is_terminal()),
+        stderr: styled(std::io::stderr().is_terminal()),
+    }
+}
+
+/// Decide styling for this process; later calls are ignored. Without it,
+/// styling is detected as for `--color auto` on first use.
+pub fn init(choice: ColorChoice) {
+    let styling = *STYLING.get_or_init(|| detect(choice));
+    console::set_colors_enabled(styling.stdout);
+    console::set_colors_enabled_stderr(styling.stderr);
+}
+
+/// Whether `stream` gets emoji and color.
+pub fn styled(stream: Stream) -> bool {
+    let styling = STYLING.get_or_init(|| detect(ColorChoice::Auto));
+    match stream {
+        Stream::Stdout => styling.stdout,
+        Stream::Stderr => styling.stderr,
+    }
+}
+
+/// Emoji markers and their plain replacements. Variants with the emoji
+/// presentation selector come first so it is not left behind.
+const MARKERS: &[(&str, &str)] = &[
+    ("⚠️", "[warn]"),
+    ("⚠", "[warn]"),
+    ("ℹ️", "[info]"),
+    ("ℹ", "[info]"),
+    ("⏱️", "[timeout]"),
+    ("⏱", "[timeout]"),
+    ("🔧", "[tool]"),
+    ("✅", "[ok]"),
+    ("❌", "[err]"),
+    ("⏳", "[wait]"),
+    ("💬", "[msg]"),
+    ("🤖", "[reply]"),
+    ("💭", "[thinking]"),
+    ("🧭", "[why]"),
+    ("🧹", "[compact]"),
+    ("📋", "[plan]"),
+    ("🦀", "[zeroclaw]"),
+    ("🔄", "[reload]"),
+    ("🩺", "[doctor]"),
+    ("🚦", "[limit]"),
+    ("🔐", "[auth]"),
+    ("🧠", "[memory]"),
+    ("🧩", "[skills]"),
+    ("📡", "[channels]"),
+    ("🌍", "[web]"),
+];
+
+/// `text` as it should appear on a styled or plain stream.
+pub fn render(text: &str, styled: bool) -> Cow<'_, str> {
+    if styled {
+        return Cow::Borrowed(text);
+    }
+    let mut plain = console::strip_ansi_codes(text);
+    for (marker, tag) in MARKERS {
+        if plain.contains(marker) {
+            plain = Cow::Owned(plain.replace(marker, tag));
+        }
+    }
+    plain
+}
+
+/// `text` dimmed on a styled stream.
+pub fn dim(text: impl Display, stream: Stream) -> String {
+    if styled(stream) {
+        format!("\x1b[2m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+/// A status line on stdout.
+pub fn println(text: impl Display) {
+    println!("{}", render(&text.to_string(), styled(Stream::Stdout)));
+}
+
+/// A status or error line on stderr.
+pub fn eprintln(text: impl Display) {
+    eprintln!("{}", render(&text.to_string(), styled(Stream::Stderr)));
+}
+
+/// Status on stderr without a trailing newline.
+pub fn eprint(text: impl Display) {
+    eprint!("{}", render(&text.to_string(), styled(Stream::Stderr)));
+}
+
+/// A transient activity line ("Processing message...") on stdout, shown
+/// only when stdout is a terminal so it does not pile up in logs.
+pub fn activity(text: impl Display) {
+    if std::io::stdout().is_terminal() {
+        println(text);
+    }
+}
+
+/// Model output or command data on stdout, unchanged.
+pub fn response(text: impl Display) {
+    println!("{text}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATUS: &str = "🦀 ZeroClaw Channel Server\n  🤖 Model:    gpt-4o\n  ✅ telegram  healthy\n  ❌ discord   unhealthy\n  ⏱️  slack     timed out\n🔧 Executing 2 tool call(s)\n\x1b[2m💭 thinking\x1b[0m";
+
+    #[test]
+    fn styled_rendering_is_unchanged() {
+        assert_eq!(render(STATUS, true), STATUS);
+    }
+
+    #[test]
+    fn plain_rendering_uses_ascii_tags_and_no_escapes() {
+        assert_eq!(
+            render(STATUS, false),
+            "[zeroclaw] ZeroClaw Channel Server\n  [reply] Model:    gpt-4o\n  [ok] telegram  healthy\n  [err] discord   unhealthy\n  [timeout]  slack     timed out\n[tool] Executing 2 tool call(s)\n[thinking] thinking"
+        );
+        assert_eq!(render("⚠️ careful", false), "[warn] careful");
+        assert!(matches!(render("plain text", false), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn color_choice_and_environment() {
+        use ColorChoice::{Always, Auto, Never};
+        assert!(resolve(Always, Some("1"), None, false));
+        assert!(!resolve(Never, None, Some("1"), true));
+        assert!(resolve(Auto, None, None, true));
+        assert!(!resolve(Auto, None, None, false));
+        assert!(!resolve(Auto, Some("1"), None, true));
+        assert!(resolve(Auto, Some(""), None, true));
+        assert!(resolve(Auto, None, Some("1"), false));
+        assert!(!resolve(Auto, None, Some("0"), false));
+        assert_eq!(ColorChoice::parse("never"), Some(Never));
+        assert_eq!(ColorChoice::parse("sometimes"), None);
+    }
+}
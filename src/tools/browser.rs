@@ -6,7 +6,7 @@
 //! Computer-use (OS-level) actions are supported via an optional sidecar endpoint.
 
 use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use crate::security::{PolicyViolation, SecurityPolicy};
 use anyhow::Context;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -633,6 +633,7 @@ impl BrowserTool {
                 success: true,
                 output: serde_json::to_string_pretty(&output).unwrap_or_default(),
                 error: None,
+                artifacts: Vec::new(),
             })
         }
 
@@ -780,6 +781,7 @@ impl BrowserTool {
                     success: true,
                     output,
                     error: None,
+                    artifacts: Vec::new(),
                 });
             }
 
@@ -797,6 +799,7 @@ impl BrowserTool {
                 success: false,
                 output: String::new(),
                 error,
+                artifacts: Vec::new(),
             });
         }
 
@@ -805,6 +808,7 @@ impl BrowserTool {
                 success: true,
                 output: body,
                 error: None,
+                artifacts: Vec::new(),
             });
         }
 
@@ -815,6 +819,7 @@ impl BrowserTool {
                 "computer-use sidecar request failed with status {status}: {}",
                 body.trim()
             )),
+            artifacts: Vec::new(),
         })
     }
 
@@ -843,12 +848,14 @@ impl BrowserTool {
                 success: true,
                 output,
                 error: None,
+                artifacts: Vec::new(),
             })
         } else {
             Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: resp.error,
+                artifacts: Vec::new(),
             })
         }
     }
@@ -986,11 +993,7 @@ impl Tool for BrowserTool {
     async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
         // Security checks
         if !self.security.can_act() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
-            });
+            return Err(PolicyViolation::ReadOnly.into());
         }
 
         if !self.security.record_action() {
@@ -998,6 +1001,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -1008,6 +1012,7 @@ impl Tool for BrowserTool {
                     success: false,
                     output: String::new(),
                     error: Some(error.to_string()),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -1023,6 +1028,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Unknown action: {action_str}")),
+                artifacts: Vec::new(),
             });
         }
 
@@ -1035,6 +1041,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some(unavailable_action_for_backend_error(action_str, backend)),
+                artifacts: Vec::new(),
             });
         }
 
@@ -1045,6 +1052,7 @@ impl Tool for BrowserTool {
                     success: false,
                     output: String::new(),
                     error: Some(e.to_string()),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -1933,7 +1941,7 @@ fn unavailable_action_for_backend_error(action: &str, backend: ResolvedBackend)
     )
 }
 
-fn normalize_domains(domains: Vec<String>) -> Vec<String> {
+pub(crate) fn normalize_domains(domains: Vec<String>) -> Vec<String> {
     domains
         .into_iter()
         .map(|d| d.trim().to_lowercase())
@@ -1965,7 +1973,7 @@ fn endpoint_reachable(endpoint: &reqwest::Url, timeout: Duration) -> bool {
     std::net::TcpStream::connect_timeout(&addr, timeout).is_ok()
 }
 
-fn extract_host(url_str: &str) -> anyhow::Result<String> {
+pub(crate) fn extract_host(url_str: &str) -> anyhow::Result<String> {
     // Simple host extraction without url crate
     let url = url_str.trim();
     let without_scheme = url
@@ -1992,7 +2000,7 @@ fn extract_host(url_str: &str) -> anyhow::Result<String> {
     Ok(host.to_lowercase())
 }
 
-fn is_private_host(host: &str) -> bool {
+pub(crate) fn is_private_host(host: &str) -> bool {
     // Strip brackets from IPv6 addresses like [::1]
     let bare = host
         .strip_prefix('[')
@@ -2058,7 +2066,7 @@ fn is_non_global_v6(v6: std::net::Ipv6Addr) -> bool {
         || v6.to_ipv4_mapped().is_some_and(is_non_global_v4)
 }
 
-fn host_matches_allowlist(host: &str, allowed: &[String]) -> bool {
+pub(crate) fn host_matches_allowlist(host: &str, allowed: &[String]) -> bool {
     allowed.iter().any(|pattern| {
         if pattern == "*" {
             return true;
@@ -2074,6 +2082,51 @@ fn host_matches_allowlist(host: &str, allowed: &[String]) -> bool {
     })
 }
 
+/// Check a URL for the headless Chromium tools (`browser_screenshot`,
+/// `browser_js`): http(s) only, on a public host in `allowed_domains`.
+/// `tool` names the tool in the error for an empty allowlist.
+pub(crate) fn validate_allowlisted_url(
+    raw_url: &str,
+    allowed_domains: &[String],
+    tool: &str,
+) -> anyhow::Result<String> {
+    let url = raw_url.trim();
+
+    if url.is_empty() {
+        anyhow::bail!("URL cannot be empty");
+    }
+
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        anyhow::bail!("Only http:// and https:// URLs are allowed");
+    }
+
+    if allowed_domains.is_empty() {
+        anyhow::bail!(
+            "{tool} is enabled but no allowed_domains are configured. \
+            Add [browser].allowed_domains in config.toml"
+        );
+    }
+
+    let host = extract_host(url)?;
+
+    if is_private_host(&host) {
+        anyhow::bail!("Blocked local/private host: {host}");
+    }
+
+    if !host_matches_allowlist(&host, allowed_domains) {
+        anyhow::bail!("Host '{host}' not in browser.allowed_domains");
+    }
+
+    Ok(url.to_string())
+}
+
+/// Whether a page may load `url`: the same check as
+/// [`validate_allowlisted_url`], for requests the page itself makes.
+#[cfg(feature = "browser-screenshot")]
+pub(crate) fn is_allowlisted_url(url: &str, allowed_domains: &[String]) -> bool {
+    !allowed_domains.is_empty() && validate_allowlisted_url(url, allowed_domains, "").is_ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2398,4 +2451,19 @@ mod tests {
             "Action 'mouse_move' is unavailable for backend 'rust_native'"
         );
     }
+
+    #[test]
+    fn validate_allowlisted_url_names_the_tool_without_allowlist() {
+        let allowed = vec!["example.com".to_string()];
+        assert_eq!(
+            validate_allowlisted_url(" https://docs.example.com/a ", &allowed, "browser_js")
+                .unwrap(),
+            "https://docs.example.com/a"
+        );
+        assert!(validate_allowlisted_url("https://evil.com", &allowed, "browser_js").is_err());
+        assert!(validate_allowlisted_url("http://10.0.0.1", &allowed, "browser_js").is_err());
+
+        let err = validate_allowlisted_url("https://example.com", &[], "browser_js").unwrap_err();
+        assert!(err.to_string().starts_with("browser_js is enabled"));
+    }
 }
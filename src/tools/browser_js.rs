@@ -0,0 +1,480 @@
+use super::browser::{normalize_domains, validate_allowlisted_url};
+use super::traits::{Tool, ToolResult};
+use crate::security::{PolicyViolation, SecurityPolicy};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum time to wait for page load + script evaluation.
+const BROWSER_JS_TIMEOUT_SECS: u64 = 30;
+
+/// Console methods captured into `console_output`.
+const CAPTURED_CONSOLE_METHODS: &[&str] = &["log", "info", "warn", "error", "debug"];
+
+/// Run JavaScript against an allowlisted web page in headless Chromium.
+///
+/// Lets the agent click, fill forms or read DOM state that a plain fetch
+/// cannot reach. Requires the `browser-screenshot` build feature (shared
+/// chromiumoxide backend) and `[browser].allow_scripts = true`. Scripts
+/// longer than `[browser].max_script_length` characters are rejected, and
+/// every request the page makes, including navigations and `fetch` calls
+/// from the script, must stay on `[browser].allowed_domains`.
+pub struct BrowserJsTool {
+    security: Arc<SecurityPolicy>,
+    allowed_domains: Vec<String>,
+    chrome_path: Option<String>,
+    max_script_length: usize,
+}
+
+/// Outcome of a wrapped script evaluation.
+#[derive(Debug, PartialEq)]
+struct ScriptOutcome {
+    result: Result<Value, String>,
+    console_output: Vec<String>,
+}
+
+impl BrowserJsTool {
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        allowed_domains: Vec<String>,
+        chrome_path: Option<String>,
+        max_script_length: usize,
+    ) -> Self {
+        Self {
+            security,
+            allowed_domains: normalize_domains(allowed_domains),
+            chrome_path,
+            max_script_length,
+        }
+    }
+
+    fn validate_url(&self, raw_url: &str) -> anyhow::Result<String> {
+        validate_allowlisted_url(raw_url, &self.allowed_domains, "browser_js")
+    }
+
+    fn validate_script(&self, script: &str) -> anyhow::Result<()> {
+        if script.trim().is_empty() {
+            anyhow::bail!("Script cannot be empty");
+        }
+
+        let length = script.chars().count();
+        if length > self.max_script_length {
+            anyhow::bail!(
+                "Script is {length} characters; browser.max_script_length is {}",
+                self.max_script_length
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn evaluate(&self, url: &str, script: &str) -> anyhow::Result<ScriptOutcome> {
+        let raw = tokio::time::timeout(
+            Duration::from_secs(BROWSER_JS_TIMEOUT_SECS),
+            native::evaluate(
+                url,
+                &wrap_script(script),
+                self.chrome_path.as_deref(),
+                &self.allowed_domains,
+            ),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("Script timed out after {BROWSER_JS_TIMEOUT_SECS}s"))??;
+
+        parse_outcome(raw)
+    }
+}
+
+/// Wrap a user script so that console output is captured and exceptions are
+/// returned as data instead of aborting the evaluation.
+///
+/// The script is embedded as a JSON string and run through indirect `eval`,
+/// so both expressions (`document.title`) and statement blocks work, and a
+/// returned promise is awaited.
+fn wrap_script(script: &str) -> String {
+    let source = serde_json::to_string(script).unwrap_or_else(|_| "\"\"".into());
+    let methods = serde_json::to_string(CAPTURED_CONSOLE_METHODS).unwrap_or_default();
+    format!(
+        r#"(async () => {{
+  const __zcConsole = [];
+  const __zcOriginal = {{}};
+  const __zcFormat = (arg) => {{
+    if (typeof arg === "string") return arg;
+    try {{ return JSON.stringify(arg); }} catch (_) {{ return String(arg); }}
+  }};
+  for (const method of {methods}) {{
+    __zcOriginal[method] = console[method];
+    console[method] = (...args) => {{
+      __zcConsole.push(args.map(__zcFormat).join(" "));
+      __zcOriginal[method].apply(console, args);
+    }};
+  }}
+  try {{
+    const value = await (0, eval)({source});
+    return {{ ok: true, result: value === undefined ? null : value, console: __zcConsole }};
+  }} catch (err) {{
+    return {{ ok: false, error: String((err && err.stack) || err), console: __zcConsole }};
+  }} finally {{
+    for (const method of Object.keys(__zcOriginal)) console[method] = __zcOriginal[method];
+  }}
+}})()"#
+    )
+}
+
+/// Decode the object returned by [`wrap_script`].
+fn parse_outcome(raw: Value) -> anyhow::Result<ScriptOutcome> {
+    let console_output = raw
+        .get("console")
+        .and_then(Value::as_array)
+        .map(|lines| {
+            lines
+                .iter()
+                .map(|line| line.as_str().map_or_else(|| line.to_string(), String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let result = match raw.get("ok").and_then(Value::as_bool) {
+        Some(true) => Ok(raw.get("result").cloned().unwrap_or(Value::Null)),
+        Some(false) => Err(raw
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown JavaScript error")
+            .to_string()),
+        None => anyhow::bail!("Browser returned an unexpected evaluation result"),
+    };
+
+    Ok(ScriptOutcome {
+        result,
+        console_output,
+    })
+}
+
+#[async_trait]
+impl Tool for BrowserJsTool {
+    fn name(&self) -> &str {
+        "browser_js"
+    }
+
+    fn description(&self) -> &str {
+        "Load an allowlisted web page in headless Chromium and run JavaScript on it (click, fill forms, read DOM). Returns {result, console_output}."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "http(s) URL to load (must match browser.allowed_domains)"
+                },
+                "script": {
+                    "type": "string",
+                    "description": "JavaScript to evaluate in the page; the value of the last expression (awaited if a promise) is returned"
+                }
+            },
+            "required": ["url", "script"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let url = args
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'url' parameter"))?;
+
+        let script = args
+            .get("script")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'script' parameter"))?;
+
+        if !self.security.can_act() {
+            return Err(PolicyViolation::ReadOnly.into());
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: rate limit exceeded".into()),
+                artifacts: Vec::new(),
+            });
+        }
+
+        let url = match self
+            .validate_url(url)
+            .and_then(|url| self.validate_script(script).map(|()| url))
+        {
+            Ok(v) => v,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                    artifacts: Vec::new(),
+                })
+            }
+        };
+
+        let outcome = match self.evaluate(&url, script).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Browser JavaScript failed: {e}")),
+                    artifacts: Vec::new(),
+                })
+            }
+        };
+
+        match outcome.result {
+            Ok(result) => Ok(ToolResult {
+                success: true,
+                output: serde_json::to_string_pretty(&json!({
+                    "result": result,
+                    "console_output": outcome.console_output,
+                }))?,
+                error: None,
+                artifacts: Vec::new(),
+            }),
+            Err(message) => Ok(ToolResult {
+                success: false,
+                output: serde_json::to_string_pretty(&json!({
+                    "console_output": outcome.console_output,
+                }))?,
+                error: Some(format!("JavaScript exception: {message}")),
+                artifacts: Vec::new(),
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "browser-screenshot")]
+mod native {
+    use crate::tools::browser::is_allowlisted_url;
+    use chromiumoxide::browser::{Browser, BrowserConfig};
+    use chromiumoxide::cdp::browser_protocol::fetch::{
+        ContinueRequestParams, EventRequestPaused, FailRequestParams,
+    };
+    use chromiumoxide::cdp::browser_protocol::network::ErrorReason;
+    use chromiumoxide::cdp::js_protocol::runtime::EvaluateParams;
+    use chromiumoxide::listeners::EventStream;
+    use chromiumoxide::Page;
+    use futures_util::StreamExt;
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    pub async fn evaluate(
+        url: &str,
+        expression: &str,
+        chrome_path: Option<&str>,
+        allowed_domains: &[String],
+    ) -> anyhow::Result<Value> {
+        let mut builder = BrowserConfig::builder().enable_request_intercept();
+        if let Some(path) = chrome_path {
+            builder = builder.chrome_executable(path);
+        }
+        let config = builder.build().map_err(|e| anyhow::anyhow!(e))?;
+
+        let (mut browser, mut handler) = Browser::launch(config).await?;
+        let handler_task = tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if event.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let result = async {
+            let page = Arc::new(browser.new_page("about:blank").await?);
+            let guard_task = tokio::spawn(guard_requests(
+                Arc::clone(&page),
+                page.event_listener::<EventRequestPaused>().await?,
+                allowed_domains.to_vec(),
+            ));
+
+            let outcome = async {
+                page.goto(url).await?;
+                page.wait_for_navigation().await?;
+
+                let params = EvaluateParams::builder()
+                    .expression(expression)
+                    .await_promise(true)
+                    .return_by_value(true)
+                    .build()
+                    .map_err(|e| anyhow::anyhow!(e))?;
+                let value = page
+                    .evaluate_expression(params)
+                    .await?
+                    .into_value::<Value>()?;
+                Ok(value)
+            }
+            .await;
+
+            guard_task.abort();
+            outcome
+        }
+        .await;
+
+        let _ = browser.close().await;
+        handler_task.abort();
+        result
+    }
+
+    /// Let a paused request through only when it targets an allowlisted
+    /// host. This covers the initial load and its redirects as well as
+    /// anything the script starts: navigations, `fetch`, XHR, form posts.
+    async fn guard_requests(
+        page: Arc<Page>,
+        mut paused: EventStream<EventRequestPaused>,
+        allowed_domains: Vec<String>,
+    ) {
+        while let Some(event) = paused.next().await {
+            let request_id = event.request_id.clone();
+            let sent = if is_allowlisted_url(&event.request.url, &allowed_domains) {
+                page.execute(ContinueRequestParams::new(request_id))
+                    .await
+                    .map(drop)
+            } else {
+                tracing::warn!(
+                    url = %event.request.url,
+                    "browser_js blocked a request outside browser.allowed_domains"
+                );
+                page.execute(FailRequestParams::new(
+                    request_id,
+                    ErrorReason::BlockedByClient,
+                ))
+                .await
+                .map(drop)
+            };
+            if sent.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "browser-screenshot"))]
+mod native {
+    use serde_json::Value;
+
+    pub async fn evaluate(
+        _url: &str,
+        _expression: &str,
+        _chrome_path: Option<&str>,
+        _allowed_domains: &[String],
+    ) -> anyhow::Result<Value> {
+        anyhow::bail!(
+            "browser_js requires the `browser-screenshot` feature. \
+            Rebuild with `cargo build --features browser-screenshot`"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    fn test_tool(allowed: Vec<String>, max_script_length: usize) -> BrowserJsTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: std::env::temp_dir(),
+            ..SecurityPolicy::default()
+        });
+        BrowserJsTool::new(security, allowed, None, max_script_length)
+    }
+
+    #[test]
+    fn browser_js_tool_name_and_schema() {
+        let tool = test_tool(vec!["example.com".into()], 100);
+        assert_eq!(tool.name(), "browser_js");
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["url"].is_object());
+        assert!(schema["properties"]["script"].is_object());
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&json!("url")));
+        assert!(required.contains(&json!("script")));
+    }
+
+    #[test]
+    fn validate_url_enforces_allowlist() {
+        let tool = test_tool(vec!["example.com".into()], 100);
+        assert!(tool.validate_url("https://example.com/form").is_ok());
+        assert!(tool.validate_url("https://evil.com").is_err());
+        assert!(tool.validate_url("http://127.0.0.1").is_err());
+        assert!(tool.validate_url("javascript:alert(1)").is_err());
+    }
+
+    #[test]
+    fn validate_script_enforces_max_length() {
+        let tool = test_tool(vec!["example.com".into()], 10);
+        assert!(tool.validate_script("1 + 1").is_ok());
+        assert!(tool.validate_script("   ").is_err());
+        let err = tool.validate_script("document.title").unwrap_err();
+        assert!(err.to_string().contains("max_script_length"));
+    }
+
+    #[tokio::test]
+    async fn execute_rejects_overlong_script() {
+        let tool = test_tool(vec!["example.com".into()], 5);
+        let result = tool
+            .execute(json!({"url": "https://example.com", "script": "document.title"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("max_script_length"));
+    }
+
+    #[tokio::test]
+    async fn execute_blocks_readonly_autonomy() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tool = BrowserJsTool::new(security, vec!["example.com".into()], None, 100);
+        let err = tool
+            .execute(json!({"url": "https://example.com", "script": "1"}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
+    }
+
+    #[test]
+    fn wrap_script_embeds_source_as_string_literal() {
+        let wrapped = wrap_script("console.log(\"hi\"); `${1}`\n</script>");
+        assert!(wrapped.contains(r#"(0, eval)("console.log(\"hi\"); `${1}`\n</script>")"#));
+        assert!(wrapped.contains("\"log\""));
+    }
+
+    #[test]
+    fn parse_outcome_reads_result_and_console() {
+        let outcome = parse_outcome(json!({
+            "ok": true,
+            "result": {"clicked": true},
+            "console": ["a", "b"]
+        }))
+        .unwrap();
+        assert_eq!(outcome.result, Ok(json!({"clicked": true})));
+        assert_eq!(outcome.console_output, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn parse_outcome_surfaces_exceptions_as_errors() {
+        let outcome = parse_outcome(json!({
+            "ok": false,
+            "error": "TypeError: x is undefined",
+            "console": ["before"]
+        }))
+        .unwrap();
+        assert_eq!(outcome.result, Err("TypeError: x is undefined".into()));
+        assert_eq!(outcome.console_output, vec!["before"]);
+
+        assert!(parse_outcome(json!("garbage")).is_err());
+    }
+}
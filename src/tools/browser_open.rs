@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use crate::security::{PolicyViolation, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
@@ -83,11 +83,7 @@ impl Tool for BrowserOpenTool {
             .ok_or_else(|| anyhow::anyhow!("Missing 'url' parameter"))?;
 
         if !self.security.can_act() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
-            });
+            return Err(PolicyViolation::ReadOnly.into());
         }
 
         if !self.security.record_action() {
@@ -95,6 +91,7 @@ impl Tool for BrowserOpenTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -105,6 +102,7 @@ impl Tool for BrowserOpenTool {
                     success: false,
                     output: String::new(),
                     error: Some(e.to_string()),
+                    artifacts: Vec::new(),
                 })
             }
         };
@@ -114,11 +112,13 @@ impl Tool for BrowserOpenTool {
                 success: true,
                 output: format!("Opened in Brave: {url}"),
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to open Brave Browser: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -440,12 +440,14 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = BrowserOpenTool::new(security, vec!["example.com".into()]);
-        let result = tool
+        let err = tool
             .execute(json!({"url": "https://example.com"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result.error.unwrap().contains("read-only"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
     }
 
     #[tokio::test]
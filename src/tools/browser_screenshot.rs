@@ -0,0 +1,373 @@
+use super::browser::{normalize_domains, validate_allowlisted_url};
+use super::traits::{Artifact, ArtifactData, Tool, ToolResult};
+use crate::security::{PolicyViolation, SecurityPolicy};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum time to wait for page load + capture.
+const BROWSER_SCREENSHOT_TIMEOUT_SECS: u64 = 30;
+
+/// Capture a screenshot of an allowlisted web page with headless Chromium.
+///
+/// Requires the `browser-screenshot` build feature and
+/// `[browser].allow_screenshots = true`.
+pub struct BrowserScreenshotTool {
+    security: Arc<SecurityPolicy>,
+    allowed_domains: Vec<String>,
+    chrome_path: Option<String>,
+}
+
+impl BrowserScreenshotTool {
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        allowed_domains: Vec<String>,
+        chrome_path: Option<String>,
+    ) -> Self {
+        Self {
+            security,
+            allowed_domains: normalize_domains(allowed_domains),
+            chrome_path,
+        }
+    }
+
+    fn validate_url(&self, raw_url: &str) -> anyhow::Result<String> {
+        validate_allowlisted_url(raw_url, &self.allowed_domains, "browser_screenshot")
+    }
+
+    /// Resolve `output_path` inside the workspace, creating parent directories.
+    async fn resolve_output_path(&self, output_path: &str) -> anyhow::Result<PathBuf> {
+        if !self.security.is_path_allowed(output_path) {
+            return Err(PolicyViolation::PathNotAllowed(output_path.to_string()).into());
+        }
+
+        let full_path = self.security.workspace_dir.join(output_path);
+        let Some(parent) = full_path.parent() else {
+            anyhow::bail!("Invalid path: missing parent directory");
+        };
+        let Some(file_name) = full_path.file_name() else {
+            anyhow::bail!("Invalid path: missing file name");
+        };
+
+        tokio::fs::create_dir_all(parent).await?;
+
+        // Resolve parent AFTER creation to block symlink escapes.
+        let resolved_parent = tokio::fs::canonicalize(parent).await?;
+        if !self.security.is_resolved_path_allowed(&resolved_parent) {
+            anyhow::bail!(
+                "Resolved path escapes workspace: {}",
+                resolved_parent.display()
+            );
+        }
+
+        let resolved_target = resolved_parent.join(file_name);
+        if let Ok(meta) = tokio::fs::symlink_metadata(&resolved_target).await {
+            if meta.file_type().is_symlink() {
+                anyhow::bail!(
+                    "Refusing to write through symlink: {}",
+                    resolved_target.display()
+                );
+            }
+        }
+
+        Ok(resolved_target)
+    }
+
+    async fn capture(&self, url: &str, target: &Path) -> anyhow::Result<ToolResult> {
+        let png = tokio::time::timeout(
+            Duration::from_secs(BROWSER_SCREENSHOT_TIMEOUT_SECS),
+            native::capture_png(url, self.chrome_path.as_deref(), &self.allowed_domains),
+        )
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!("Screenshot timed out after {BROWSER_SCREENSHOT_TIMEOUT_SECS}s")
+        })??;
+
+        let (width, height) = png_dimensions(&png)
+            .ok_or_else(|| anyhow::anyhow!("Browser returned an invalid PNG image"))?;
+
+        tokio::fs::write(target, &png).await?;
+
+        Ok(ToolResult {
+            success: true,
+            output: serde_json::to_string_pretty(&json!({
+                "path": target.display().to_string(),
+                "width": width,
+                "height": height,
+            }))?,
+            error: None,
+            artifacts: vec![Artifact {
+                mime_type: "image/png".into(),
+                data: ArtifactData::Path(target.to_path_buf()),
+            }],
+        })
+    }
+}
+
+/// Read width/height from a PNG IHDR chunk.
+fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 24 || !bytes.starts_with(b"\x89PNG\r\n\x1a\n") || &bytes[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
+    let height = u32::from_be_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]);
+    Some((width, height))
+}
+
+#[async_trait]
+impl Tool for BrowserScreenshotTool {
+    fn name(&self) -> &str {
+        "browser_screenshot"
+    }
+
+    fn description(&self) -> &str {
+        "Render an allowlisted web page in headless Chromium and save a PNG screenshot to the workspace. Returns {path, width, height}."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "http(s) URL to capture (must match browser.allowed_domains)"
+                },
+                "output_path": {
+                    "type": "string",
+                    "description": "Relative path within the workspace for the PNG file"
+                }
+            },
+            "required": ["url", "output_path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let url = args
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'url' parameter"))?;
+
+        let output_path = args
+            .get("output_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'output_path' parameter"))?;
+
+        if !self.security.can_act() {
+            return Err(PolicyViolation::ReadOnly.into());
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: rate limit exceeded".into()),
+                artifacts: Vec::new(),
+            });
+        }
+
+        let url = match self.validate_url(url) {
+            Ok(v) => v,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                    artifacts: Vec::new(),
+                })
+            }
+        };
+
+        let target = match self.resolve_output_path(output_path).await {
+            Ok(p) => p,
+            Err(e) if e.is::<PolicyViolation>() => return Err(e),
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                    artifacts: Vec::new(),
+                })
+            }
+        };
+
+        match self.capture(&url, &target).await {
+            Ok(result) => Ok(result),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Browser screenshot failed: {e}")),
+                artifacts: Vec::new(),
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "browser-screenshot")]
+mod native {
+    use crate::tools::browser::is_allowlisted_url;
+    use chromiumoxide::browser::{Browser, BrowserConfig};
+    use chromiumoxide::page::ScreenshotParams;
+    use futures_util::StreamExt;
+
+    pub async fn capture_png(
+        url: &str,
+        chrome_path: Option<&str>,
+        allowed_domains: &[String],
+    ) -> anyhow::Result<Vec<u8>> {
+        let mut builder = BrowserConfig::builder();
+        if let Some(path) = chrome_path {
+            builder = builder.chrome_executable(path);
+        }
+        let config = builder.build().map_err(|e| anyhow::anyhow!(e))?;
+
+        let (mut browser, mut handler) = Browser::launch(config).await?;
+        let handler_task = tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if event.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let result = async {
+            let page = browser.new_page(url).await?;
+            page.wait_for_navigation().await?;
+
+            // Redirects must not escape the allowlist.
+            if let Some(final_url) = page.url().await? {
+                if !is_allowlisted_url(&final_url, allowed_domains) {
+                    anyhow::bail!(
+                        "Page redirected to a URL outside browser.allowed_domains: {final_url}"
+                    );
+                }
+            }
+
+            let png = page
+                .screenshot(ScreenshotParams::builder().full_page(true).build())
+                .await?;
+            Ok(png)
+        }
+        .await;
+
+        let _ = browser.close().await;
+        handler_task.abort();
+        result
+    }
+}
+
+#[cfg(not(feature = "browser-screenshot"))]
+mod native {
+    pub async fn capture_png(
+        _url: &str,
+        _chrome_path: Option<&str>,
+        _allowed_domains: &[String],
+    ) -> anyhow::Result<Vec<u8>> {
+        anyhow::bail!(
+            "browser_screenshot requires the `browser-screenshot` feature. \
+            Rebuild with `cargo build --features browser-screenshot`"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn test_tool(workspace: PathBuf, allowed: Vec<String>) -> BrowserScreenshotTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        });
+        BrowserScreenshotTool::new(security, allowed, None)
+    }
+
+    #[test]
+    fn browser_screenshot_tool_name_and_schema() {
+        let tool = test_tool(std::env::temp_dir(), vec!["example.com".into()]);
+        assert_eq!(tool.name(), "browser_screenshot");
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["url"].is_object());
+        assert!(schema["properties"]["output_path"].is_object());
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&json!("url")));
+        assert!(required.contains(&json!("output_path")));
+    }
+
+    #[test]
+    fn validate_url_enforces_allowlist() {
+        let tool = test_tool(std::env::temp_dir(), vec!["example.com".into()]);
+        assert!(tool.validate_url("https://example.com/page").is_ok());
+        assert!(tool.validate_url("https://docs.example.com").is_ok());
+        assert!(tool.validate_url("https://evil.com").is_err());
+        assert!(tool.validate_url("http://localhost:8080").is_err());
+        assert!(tool.validate_url("file:///etc/passwd").is_err());
+        assert!(tool.validate_url("").is_err());
+    }
+
+    #[test]
+    fn validate_url_requires_allowlist() {
+        let tool = test_tool(std::env::temp_dir(), vec![]);
+        let err = tool.validate_url("https://example.com").unwrap_err();
+        assert!(err.to_string().contains("allowed_domains"));
+    }
+
+    #[tokio::test]
+    async fn resolve_output_path_stays_in_workspace() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(tmp.path().to_path_buf(), vec!["example.com".into()]);
+
+        let target = tool.resolve_output_path("shots/home.png").await.unwrap();
+        assert!(target.ends_with("shots/home.png"));
+        assert!(tmp.path().join("shots").is_dir());
+
+        assert!(tool.resolve_output_path("../outside.png").await.is_err());
+        assert!(tool.resolve_output_path("/etc/shot.png").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_rejects_disallowed_url() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(tmp.path().to_path_buf(), vec!["example.com".into()]);
+        let result = tool
+            .execute(json!({"url": "https://evil.com", "output_path": "shot.png"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("allowed_domains"));
+    }
+
+    #[tokio::test]
+    async fn execute_blocks_readonly_autonomy() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tool = BrowserScreenshotTool::new(security, vec!["example.com".into()], None);
+        let err = tool
+            .execute(json!({"url": "https://example.com", "output_path": "shot.png"}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
+    }
+
+    #[test]
+    fn png_dimensions_reads_ihdr() {
+        let bytes = [
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, // signature
+            0x00, 0x00, 0x00, 0x0D, // IHDR length
+            b'I', b'H', b'D', b'R', //
+            0x00, 0x00, 0x05, 0x00, // width 1280
+            0x00, 0x00, 0x02, 0xD0, // height 720
+        ];
+        assert_eq!(png_dimensions(&bytes), Some((1280, 720)));
+        assert_eq!(png_dimensions(b"not a png"), None);
+    }
+}
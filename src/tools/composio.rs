@@ -339,6 +339,74 @@ impl ComposioTool {
             .ok_or_else(|| anyhow::anyhow!("No redirect URL in Composio v2 response"))
     }
 
+    /// List the entity's connected accounts, optionally for one app.
+    ///
+    /// Always filtered by `entity_id`, so one user of a shared API key never
+    /// sees (or disconnects) another user's accounts.
+    pub async fn list_connections(
+        &self,
+        entity_id: &str,
+        app_name: Option<&str>,
+    ) -> anyhow::Result<Vec<ComposioConnectedAccount>> {
+        let (url, query) = Self::build_list_connections_request(entity_id, app_name);
+
+        let resp = self
+            .client()
+            .get(&url)
+            .header("x-api-key", &self.api_key)
+            .query(&query)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = response_error(resp).await;
+            anyhow::bail!("Composio v3 connection status failed: {err}");
+        }
+
+        let body: ComposioConnectedAccountsResponse = resp
+            .json()
+            .await
+            .context("Failed to decode Composio v3 connected accounts response")?;
+        Ok(body.items)
+    }
+
+    fn build_list_connections_request(
+        entity_id: &str,
+        app_name: Option<&str>,
+    ) -> (String, Vec<(&'static str, String)>) {
+        let url = format!("{COMPOSIO_API_BASE_V3}/connected_accounts");
+        let mut query = vec![("user_ids", entity_id.to_string()), ("limit", "50".into())];
+        if let Some(app) = app_name.map(str::trim).filter(|app| !app.is_empty()) {
+            query.push(("toolkit_slugs", app.to_ascii_lowercase()));
+        }
+        (url, query)
+    }
+
+    /// Revoke a connected account.
+    pub async fn disconnect(&self, connected_account_id: &str) -> anyhow::Result<()> {
+        let url = Self::build_disconnect_url(connected_account_id);
+
+        let resp = self
+            .client()
+            .delete(&url)
+            .header("x-api-key", &self.api_key)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = response_error(resp).await;
+            anyhow::bail!("Composio v3 disconnect failed: {err}");
+        }
+        Ok(())
+    }
+
+    fn build_disconnect_url(connected_account_id: &str) -> String {
+        format!(
+            "{COMPOSIO_API_BASE_V3}/connected_accounts/{}",
+            connected_account_id.trim()
+        )
+    }
+
     async fn resolve_auth_config_id(&self, app_name: &str) -> anyhow::Result<String> {
         let url = format!("{COMPOSIO_API_BASE_V3}/auth_configs");
 
@@ -390,7 +458,8 @@ impl Tool for ComposioTool {
     fn description(&self) -> &str {
         "Execute actions on 1000+ apps via Composio (Gmail, Notion, GitHub, Slack, etc.). \
          Use action='list' to see available actions, action='execute' with action_name/tool_slug, params, and optional connected_account_id, \
-         or action='connect' with app/auth_config_id to get OAuth URL."
+         action='connect' with app/auth_config_id to get OAuth URL, action='status' to see connected apps, \
+         or action='disconnect' with connected_account_id or app to revoke a connection."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -399,12 +468,12 @@ impl Tool for ComposioTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "description": "The operation: 'list' (list available actions), 'execute' (run an action), or 'connect' (get OAuth URL)",
-                    "enum": ["list", "execute", "connect"]
+                    "description": "The operation: 'list' (list available actions), 'execute' (run an action), 'connect' (get OAuth URL), 'status' (connected apps and their auth state), or 'disconnect' (revoke a connection)",
+                    "enum": ["list", "execute", "connect", "status", "disconnect"]
                 },
                 "app": {
                     "type": "string",
-                    "description": "Toolkit slug filter for 'list', or toolkit/app for 'connect' (e.g. 'gmail', 'notion', 'github')"
+                    "description": "Toolkit slug filter for 'list' and 'status', or toolkit/app for 'connect' and 'disconnect' (e.g. 'gmail', 'notion', 'github')"
                 },
                 "action_name": {
                     "type": "string",
@@ -428,7 +497,7 @@ impl Tool for ComposioTool {
                 },
                 "connected_account_id": {
                     "type": "string",
-                    "description": "Optional connected account ID for execute flow when a specific account is required"
+                    "description": "Connected account ID for 'disconnect', or for 'execute' when a specific account is required"
                 }
             },
             "required": ["action"]
@@ -477,27 +546,21 @@ impl Tool for ComposioTool {
                             success: true,
                             output,
                             error: None,
+                            artifacts: Vec::new(),
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Failed to list actions: {e}")),
+                        artifacts: Vec::new(),
                     }),
                 }
             }
 
             "execute" => {
-                if let Err(error) = self
-                    .security
-                    .enforce_tool_operation(ToolOperation::Act, "composio.execute")
-                {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some(error),
-                    });
-                }
+                self.security
+                    .enforce_tool_operation(ToolOperation::Act, "composio.execute")?;
 
                 let action_name = args
                     .get("tool_slug")
@@ -522,27 +585,21 @@ impl Tool for ComposioTool {
                             success: true,
                             output,
                             error: None,
+                            artifacts: Vec::new(),
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Action execution failed: {e}")),
+                        artifacts: Vec::new(),
                     }),
                 }
             }
 
             "connect" => {
-                if let Err(error) = self
-                    .security
-                    .enforce_tool_operation(ToolOperation::Act, "composio.connect")
-                {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some(error),
-                    });
-                }
+                self.security
+                    .enforce_tool_operation(ToolOperation::Act, "composio.connect")?;
 
                 let app = args.get("app").and_then(|v| v.as_str());
                 let auth_config_id = args.get("auth_config_id").and_then(|v| v.as_str());
@@ -562,12 +619,84 @@ impl Tool for ComposioTool {
                             success: true,
                             output: format!("Open this URL to connect {target}:\n{url}"),
                             error: None,
+                            artifacts: Vec::new(),
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Failed to get connection URL: {e}")),
+                        artifacts: Vec::new(),
+                    }),
+                }
+            }
+
+            "status" => {
+                let app = args.get("app").and_then(|v| v.as_str());
+                match self.list_connections(entity_id, app).await {
+                    Ok(accounts) => Ok(ToolResult {
+                        success: true,
+                        output: format_connections(&accounts),
+                        error: None,
+                        artifacts: Vec::new(),
+                    }),
+                    Err(e) => Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Failed to get connection status: {e}")),
+                        artifacts: Vec::new(),
+                    }),
+                }
+            }
+
+            "disconnect" => {
+                self.security
+                    .enforce_tool_operation(ToolOperation::Act, "composio.disconnect")?;
+
+                let app = args.get("app").and_then(|v| v.as_str());
+                let account_ref = args.get("connected_account_id").and_then(|v| v.as_str());
+
+                if app.is_none() && account_ref.is_none() {
+                    anyhow::bail!("Missing 'connected_account_id' or 'app' for disconnect");
+                }
+
+                // Resolve the target among this entity's own accounts first,
+                // so an id belonging to another user is never revoked.
+                let accounts = match self.list_connections(entity_id, app).await {
+                    Ok(accounts) => accounts,
+                    Err(e) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(format!("Failed to look up connections: {e}")),
+                            artifacts: Vec::new(),
+                        })
+                    }
+                };
+                let account = match select_disconnect_target(&accounts, account_ref, app) {
+                    Ok(account) => account,
+                    Err(error) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(error),
+                            artifacts: Vec::new(),
+                        })
+                    }
+                };
+
+                match self.disconnect(&account.id).await {
+                    Ok(()) => Ok(ToolResult {
+                        success: true,
+                        output: format!("Disconnected {} ({})", account.app(), account.id),
+                        error: None,
+                        artifacts: Vec::new(),
+                    }),
+                    Err(e) => Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Failed to disconnect: {e}")),
+                        artifacts: Vec::new(),
                     }),
                 }
             }
@@ -576,8 +705,9 @@ impl Tool for ComposioTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!(
-                    "Unknown action '{action}'. Use 'list', 'execute', or 'connect'."
+                    "Unknown action '{action}'. Use 'list', 'execute', 'connect', 'status', or 'disconnect'."
                 )),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -631,6 +761,54 @@ fn extract_redirect_url(result: &serde_json::Value) -> Option<String> {
         .map(ToString::to_string)
 }
 
+fn format_connections(accounts: &[ComposioConnectedAccount]) -> String {
+    if accounts.is_empty() {
+        return "No connected apps.".to_string();
+    }
+    let lines: Vec<String> = accounts
+        .iter()
+        .map(|account| format!("- {}: {} ({})", account.app(), account.status(), account.id))
+        .collect();
+    format!(
+        "{} connected account(s):\n{}",
+        accounts.len(),
+        lines.join("\n")
+    )
+}
+
+/// Pick the account to revoke from the entity's own accounts: by id when
+/// given, otherwise the single account for `app`.
+fn select_disconnect_target<'a>(
+    accounts: &'a [ComposioConnectedAccount],
+    account_ref: Option<&str>,
+    app: Option<&str>,
+) -> Result<&'a ComposioConnectedAccount, String> {
+    if let Some(id) = account_ref.map(str::trim).filter(|id| !id.is_empty()) {
+        return accounts
+            .iter()
+            .find(|account| account.id == id)
+            .ok_or_else(|| format!("No connected account '{id}' for this entity"));
+    }
+
+    let app = app.unwrap_or_default();
+    let matching: Vec<_> = accounts
+        .iter()
+        .filter(|account| account.app().eq_ignore_ascii_case(app))
+        .collect();
+    match matching.as_slice() {
+        [] => Err(format!("No connected account for '{app}'")),
+        [account] => Ok(account),
+        many => Err(format!(
+            "'{app}' has {} connected accounts; pass connected_account_id (one of: {})",
+            many.len(),
+            many.iter()
+                .map(|account| account.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
 async fn response_error(resp: reqwest::Response) -> String {
     let status = resp.status();
     let body = resp.text().await.unwrap_or_default();
@@ -750,6 +928,36 @@ impl ComposioAuthConfig {
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct ComposioConnectedAccountsResponse {
+    #[serde(default)]
+    items: Vec<ComposioConnectedAccount>,
+}
+
+/// A connected account as reported by Composio v3.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComposioConnectedAccount {
+    pub id: String,
+    /// Auth state, e.g. `ACTIVE`, `INITIATED`, `EXPIRED`, `FAILED`.
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    toolkit: Option<ComposioToolkitRef>,
+}
+
+impl ComposioConnectedAccount {
+    pub fn app(&self) -> &str {
+        self.toolkit
+            .as_ref()
+            .and_then(|toolkit| toolkit.slug.as_deref().or(toolkit.name.as_deref()))
+            .unwrap_or("?")
+    }
+
+    pub fn status(&self) -> &str {
+        self.status.as_deref().unwrap_or("UNKNOWN")
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComposioAction {
     pub name: String,
@@ -763,7 +971,7 @@ pub struct ComposioAction {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use crate::security::{AutonomyLevel, PolicyViolation, SecurityPolicy};
 
     fn test_security() -> Arc<SecurityPolicy> {
         Arc::new(SecurityPolicy::default())
@@ -838,6 +1046,30 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn disconnect_without_target_returns_error() {
+        let tool = ComposioTool::new("test-key", None, test_security());
+        let result = tool.execute(json!({"action": "disconnect"})).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn disconnect_blocked_in_readonly_mode() {
+        let readonly = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tool = ComposioTool::new("test-key", None, readonly);
+        let err = tool
+            .execute(json!({"action": "disconnect", "app": "gmail"}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
+    }
+
     #[tokio::test]
     async fn execute_blocked_in_readonly_mode() {
         let readonly = Arc::new(SecurityPolicy {
@@ -845,19 +1077,17 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = ComposioTool::new("test-key", None, readonly);
-        let result = tool
+        let err = tool
             .execute(json!({
                 "action": "execute",
                 "action_name": "GITHUB_LIST_REPOS"
             }))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("read-only mode"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
     }
 
     #[tokio::test]
@@ -867,19 +1097,17 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = ComposioTool::new("test-key", None, limited);
-        let result = tool
+        let err = tool
             .execute(json!({
                 "action": "execute",
                 "action_name": "GITHUB_LIST_REPOS"
             }))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("Rate limit exceeded"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::RateLimited)
+        );
     }
 
     // ── API response parsing ──────────────────────────────────
@@ -1107,4 +1335,70 @@ mod tests {
         assert!(body.get("connected_account_id").is_none());
         assert!(body.get("user_id").is_none());
     }
+
+    fn connected_accounts() -> Vec<ComposioConnectedAccount> {
+        let body = r#"{"items": [
+            {"id": "ca_gmail", "status": "ACTIVE", "toolkit": {"slug": "gmail"}},
+            {"id": "ca_notion", "status": "EXPIRED", "toolkit": {"slug": "notion"}},
+            {"id": "ca_gh_1", "status": "ACTIVE", "toolkit": {"slug": "github"}},
+            {"id": "ca_gh_2", "status": "INITIATED", "toolkit": {"slug": "github"}}
+        ]}"#;
+        serde_json::from_str::<ComposioConnectedAccountsResponse>(body)
+            .unwrap()
+            .items
+    }
+
+    #[test]
+    fn status_reports_each_app_and_auth_state() {
+        let output = format_connections(&connected_accounts());
+        assert!(output.starts_with("4 connected account(s):"));
+        assert!(output.contains("- gmail: ACTIVE (ca_gmail)"));
+        assert!(output.contains("- notion: EXPIRED (ca_notion)"));
+        assert_eq!(format_connections(&[]), "No connected apps.");
+    }
+
+    #[test]
+    fn connected_account_defaults_missing_fields() {
+        let account: ComposioConnectedAccount = serde_json::from_str(r#"{"id": "ca_1"}"#).unwrap();
+        assert_eq!(account.app(), "?");
+        assert_eq!(account.status(), "UNKNOWN");
+    }
+
+    #[test]
+    fn list_connections_request_is_scoped_to_the_entity() {
+        let (url, query) = ComposioTool::build_list_connections_request("workspace-user", None);
+        assert_eq!(
+            url,
+            "https://backend.composio.dev/api/v3/connected_accounts"
+        );
+        assert!(query.contains(&("user_ids", "workspace-user".to_string())));
+        assert!(!query.iter().any(|(key, _)| *key == "toolkit_slugs"));
+
+        let (_, query) = ComposioTool::build_list_connections_request("u", Some(" Gmail "));
+        assert!(query.contains(&("toolkit_slugs", "gmail".to_string())));
+    }
+
+    #[test]
+    fn disconnect_calls_the_revocation_endpoint() {
+        assert_eq!(
+            ComposioTool::build_disconnect_url(" ca_gmail "),
+            "https://backend.composio.dev/api/v3/connected_accounts/ca_gmail"
+        );
+    }
+
+    #[test]
+    fn disconnect_target_is_resolved_among_own_accounts() {
+        let accounts = connected_accounts();
+
+        let by_id = select_disconnect_target(&accounts, Some("ca_notion"), None).unwrap();
+        assert_eq!(by_id.id, "ca_notion");
+        let by_app = select_disconnect_target(&accounts, None, Some("Gmail")).unwrap();
+        assert_eq!(by_app.id, "ca_gmail");
+
+        let foreign = select_disconnect_target(&accounts, Some("ca_other_user"), None);
+        assert!(foreign.unwrap_err().contains("No connected account"));
+        let ambiguous = select_disconnect_target(&accounts, None, Some("github")).unwrap_err();
+        assert!(ambiguous.contains("ca_gh_1, ca_gh_2"));
+        assert!(select_disconnect_target(&accounts, None, Some("slack")).is_err());
+    }
 }
@@ -1,7 +1,7 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
 use crate::cron::{self, DeliveryConfig, JobType, Schedule, SessionTarget};
-use crate::security::SecurityPolicy;
+use crate::security::{PolicyViolation, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
@@ -54,6 +54,7 @@ impl Tool for CronAddTool {
                 success: false,
                 output: String::new(),
                 error: Some("cron is disabled by config (cron.enabled=false)".to_string()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -65,6 +66,7 @@ impl Tool for CronAddTool {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Invalid schedule: {e}")),
+                        artifacts: Vec::new(),
                     });
                 }
             },
@@ -73,6 +75,7 @@ impl Tool for CronAddTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'schedule' parameter".to_string()),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -90,6 +93,7 @@ impl Tool for CronAddTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Invalid job_type: {other}")),
+                    artifacts: Vec::new(),
                 });
             }
             None => {
@@ -116,16 +120,13 @@ impl Tool for CronAddTool {
                             success: false,
                             output: String::new(),
                             error: Some("Missing 'command' for shell job".to_string()),
+                            artifacts: Vec::new(),
                         });
                     }
                 };
 
                 if !self.security.is_command_allowed(command) {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some(format!("Command blocked by security policy: {command}")),
-                    });
+                    return Err(PolicyViolation::CommandNotAllowed(command.to_string()).into());
                 }
 
                 cron::add_shell_job(&self.config, name, schedule, command)
@@ -138,6 +139,7 @@ impl Tool for CronAddTool {
                             success: false,
                             output: String::new(),
                             error: Some("Missing 'prompt' for agent job".to_string()),
+                            artifacts: Vec::new(),
                         });
                     }
                 };
@@ -150,6 +152,7 @@ impl Tool for CronAddTool {
                                 success: false,
                                 output: String::new(),
                                 error: Some(format!("Invalid session_target: {e}")),
+                                artifacts: Vec::new(),
                             });
                         }
                     },
@@ -169,6 +172,7 @@ impl Tool for CronAddTool {
                                 success: false,
                                 output: String::new(),
                                 error: Some(format!("Invalid delivery config: {e}")),
+                                artifacts: Vec::new(),
                             });
                         }
                     },
@@ -200,11 +204,13 @@ impl Tool for CronAddTool {
                     "enabled": job.enabled
                 }))?,
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(e.to_string()),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -266,20 +272,19 @@ mod tests {
         let cfg = Arc::new(config);
         let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
 
-        let result = tool
+        let err = tool
             .execute(json!({
                 "schedule": { "kind": "cron", "expr": "*/5 * * * *" },
                 "job_type": "shell",
                 "command": "curl https://example.com"
             }))
             .await
-            .unwrap();
+            .unwrap_err();
 
-        assert!(!result.success);
-        assert!(result
-            .error
-            .unwrap_or_default()
-            .contains("blocked by security policy"));
+        assert!(matches!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::CommandNotAllowed(_))
+        ));
     }
 
     #[tokio::test]
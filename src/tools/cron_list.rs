@@ -33,12 +33,17 @@ impl Tool for CronListTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
         if !self.config.cron.enabled {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some("cron is disabled by config (cron.enabled=false)".to_string()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -47,11 +52,13 @@ impl Tool for CronListTool {
                 success: true,
                 output: serde_json::to_string_pretty(&jobs)?,
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(e.to_string()),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -1,7 +1,7 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
 use crate::cron::{self, CronJobPatch};
-use crate::security::SecurityPolicy;
+use crate::security::{PolicyViolation, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
@@ -44,6 +44,7 @@ impl Tool for CronUpdateTool {
                 success: false,
                 output: String::new(),
                 error: Some("cron is disabled by config (cron.enabled=false)".to_string()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -54,6 +55,7 @@ impl Tool for CronUpdateTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'job_id' parameter".to_string()),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -65,6 +67,7 @@ impl Tool for CronUpdateTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'patch' parameter".to_string()),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -76,17 +79,14 @@ impl Tool for CronUpdateTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Invalid patch payload: {e}")),
+                    artifacts: Vec::new(),
                 });
             }
         };
 
         if let Some(command) = &patch.command {
             if !self.security.is_command_allowed(command) {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(format!("Command blocked by security policy: {command}")),
-                });
+                return Err(PolicyViolation::CommandNotAllowed(command.to_string()).into());
             }
         }
 
@@ -95,11 +95,13 @@ impl Tool for CronUpdateTool {
                 success: true,
                 output: serde_json::to_string_pretty(&job)?,
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(e.to_string()),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -161,17 +163,16 @@ mod tests {
         let job = cron::add_job(&cfg, "*/5 * * * *", "echo ok").unwrap();
         let tool = CronUpdateTool::new(cfg.clone(), test_security(&cfg));
 
-        let result = tool
+        let err = tool
             .execute(json!({
                 "job_id": job.id,
                 "patch": { "command": "curl https://example.com" }
             }))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result
-            .error
-            .unwrap_or_default()
-            .contains("blocked by security policy"));
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::CommandNotAllowed(_))
+        ));
     }
 }
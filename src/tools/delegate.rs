@@ -113,6 +113,7 @@ impl Tool for DelegateTool {
                 success: false,
                 output: String::new(),
                 error: Some("'agent' parameter must not be empty".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -127,6 +128,7 @@ impl Tool for DelegateTool {
                 success: false,
                 output: String::new(),
                 error: Some("'prompt' parameter must not be empty".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -153,6 +155,7 @@ impl Tool for DelegateTool {
                             available.join(", ")
                         }
                     )),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -168,19 +171,12 @@ impl Tool for DelegateTool {
                     depth = self.depth,
                     max = agent_config.max_depth
                 )),
+                artifacts: Vec::new(),
             });
         }
 
-        if let Err(error) = self
-            .security
-            .enforce_tool_operation(ToolOperation::Act, "delegate")
-        {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(error),
-            });
-        }
+        self.security
+            .enforce_tool_operation(ToolOperation::Act, "delegate")?;
 
         // Create provider for this agent
         let provider_credential_owned = agent_config
@@ -201,6 +197,7 @@ impl Tool for DelegateTool {
                             "Failed to create provider '{}' for agent '{agent_name}': {e}",
                             agent_config.provider
                         )),
+                        artifacts: Vec::new(),
                     });
                 }
             };
@@ -235,6 +232,7 @@ impl Tool for DelegateTool {
                     error: Some(format!(
                         "Agent '{agent_name}' timed out after {DELEGATE_TIMEOUT_SECS}s"
                     )),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -254,12 +252,14 @@ impl Tool for DelegateTool {
                         model = agent_config.model
                     ),
                     error: None,
+                    artifacts: Vec::new(),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Agent '{agent_name}' failed: {e}",)),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -268,7 +268,7 @@ impl Tool for DelegateTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use crate::security::{AutonomyLevel, PolicyViolation, SecurityPolicy};
 
     fn test_security() -> Arc<SecurityPolicy> {
         Arc::new(SecurityPolicy::default())
@@ -463,16 +463,14 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = DelegateTool::new(sample_agents(), None, readonly);
-        let result = tool
+        let err = tool
             .execute(json!({"agent": "researcher", "prompt": "test"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("read-only mode"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
     }
 
     #[tokio::test]
@@ -482,16 +480,14 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = DelegateTool::new(sample_agents(), None, limited);
-        let result = tool
+        let err = tool
             .execute(json!({"agent": "researcher", "prompt": "test"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("Rate limit exceeded"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::RateLimited)
+        );
     }
 
     #[tokio::test]
@@ -1,19 +1,114 @@
+use super::scratch::expand_scratch_path;
 use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use crate::security::{PolicyViolation, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
 const MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
 
+/// Which part of the file to return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadRange {
+    Whole,
+    /// 1-based, inclusive line numbers
+    Lines {
+        start: u64,
+        end: Option<u64>,
+    },
+    Bytes {
+        offset: u64,
+        length: Option<u64>,
+    },
+}
+
+fn optional_u64(args: &serde_json::Value, key: &str) -> Result<Option<u64>, String> {
+    match args.get(key) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| format!("'{key}' must be a non-negative integer")),
+    }
+}
+
+impl ReadRange {
+    fn from_args(args: &serde_json::Value) -> Result<Self, String> {
+        let start_line = optional_u64(args, "start_line")?;
+        let end_line = optional_u64(args, "end_line")?;
+        let offset = optional_u64(args, "offset")?;
+        let length = optional_u64(args, "length")?;
+
+        let by_lines = start_line.is_some() || end_line.is_some();
+        let by_bytes = offset.is_some() || length.is_some();
+        match (by_lines, by_bytes) {
+            (true, true) => Err("Use either start_line/end_line or offset/length, not both".into()),
+            (true, false) => {
+                let start = start_line.unwrap_or(1);
+                if start == 0 {
+                    return Err("'start_line' is 1-based and must be at least 1".into());
+                }
+                if let Some(end) = end_line {
+                    if end < start {
+                        return Err(format!(
+                            "'end_line' ({end}) must not be before 'start_line' ({start})"
+                        ));
+                    }
+                }
+                Ok(Self::Lines {
+                    start,
+                    end: end_line,
+                })
+            }
+            (false, true) => Ok(Self::Bytes {
+                offset: offset.unwrap_or(0),
+                length,
+            }),
+            (false, false) => Ok(Self::Whole),
+        }
+    }
+}
+
+/// Lines `start..=end` of `contents` (clamped to the last line), with a
+/// header giving the returned range and the file's size.
+fn slice_lines(contents: &str, start: u64, end: Option<u64>) -> Result<String, String> {
+    let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+    let total = lines.len() as u64;
+    if start > total {
+        return Err(format!(
+            "start_line {start} is past the end of the file ({total} lines)"
+        ));
+    }
+    let end = end.map_or(total, |end| end.min(total));
+    // Both bounds are at most `lines.len()` here.
+    let index = |line: u64| usize::try_from(line).unwrap_or(lines.len());
+    let slice: String = lines[index(start - 1)..index(end)].concat();
+    Ok(format!(
+        "[lines {start}-{end} of {total}, {} bytes total]\n{slice}",
+        contents.len()
+    ))
+}
+
 /// Read file contents with path sandboxing
 pub struct FileReadTool {
     security: Arc<SecurityPolicy>,
+    scratch_dir: Option<PathBuf>,
 }
 
 impl FileReadTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+        Self {
+            security,
+            scratch_dir: None,
+        }
+    }
+
+    /// Expand a leading `$TMPDIR` in paths to `scratch_dir` (workspace-relative).
+    pub fn with_scratch_dir(mut self, scratch_dir: PathBuf) -> Self {
+        self.scratch_dir = Some(scratch_dir);
+        self
     }
 }
 
@@ -24,7 +119,7 @@ impl Tool for FileReadTool {
     }
 
     fn description(&self) -> &str {
-        "Read the contents of a file in the workspace"
+        "Read the contents of a file in the workspace. Pass start_line/end_line or offset/length to read only part of a large file. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -33,34 +128,67 @@ impl Tool for FileReadTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Relative path to the file within the workspace"
+                    "description": "Relative path to the file within the workspace ($TMPDIR/... for scratch files)"
+                },
+                "start_line": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "First line to return (1-based). Defaults to 1 when end_line is set."
+                },
+                "end_line": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Last line to return (inclusive). Defaults to the end of the file."
+                },
+                "offset": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Byte offset to start reading at. Cannot be combined with line ranges."
+                },
+                "length": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Number of bytes to read from offset. Defaults to the end of the file."
                 }
             },
             "required": ["path"]
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let path = args
             .get("path")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        let path = &expand_scratch_path(path, self.scratch_dir.as_deref());
+        let range = match ReadRange::from_args(&args) {
+            Ok(range) => range,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Invalid range: {e}")),
+                    artifacts: Vec::new(),
+                });
+            }
+        };
 
         if self.security.is_rate_limited() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                artifacts: Vec::new(),
             });
         }
 
         // Security check: validate path is within workspace
         if !self.security.is_path_allowed(path) {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!("Path not allowed by security policy: {path}")),
-            });
+            return Err(PolicyViolation::PathNotAllowed(path.to_string()).into());
         }
 
         // Record action BEFORE canonicalization so that every non-trivially-rejected
@@ -71,6 +199,7 @@ impl Tool for FileReadTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -84,6 +213,7 @@ impl Tool for FileReadTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to resolve file path: {e}")),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -96,47 +226,129 @@ impl Tool for FileReadTool {
                     "Resolved path escapes workspace: {}",
                     resolved_path.display()
                 )),
+                artifacts: Vec::new(),
             });
         }
 
         // Check file size AFTER canonicalization to prevent TOCTOU symlink bypass
-        match tokio::fs::metadata(&resolved_path).await {
+        let file_size = match tokio::fs::metadata(&resolved_path).await {
             Ok(meta) => {
-                if meta.len() > MAX_FILE_SIZE_BYTES {
+                // Byte ranges seek instead of loading the file, so only the
+                // requested length is limited.
+                let read_size = match range {
+                    ReadRange::Bytes { offset, length } => {
+                        let rest = meta.len().saturating_sub(offset);
+                        length.map_or(rest, |length| length.min(rest))
+                    }
+                    _ => meta.len(),
+                };
+                if read_size > MAX_FILE_SIZE_BYTES {
                     return Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!(
-                            "File too large: {} bytes (limit: {MAX_FILE_SIZE_BYTES} bytes)",
-                            meta.len()
+                            "File too large: {read_size} bytes (limit: {MAX_FILE_SIZE_BYTES} bytes)"
                         )),
+                        artifacts: Vec::new(),
                     });
                 }
+                meta.len()
             }
             Err(e) => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to read file metadata: {e}")),
+                    artifacts: Vec::new(),
                 });
             }
+        };
+
+        if let ReadRange::Bytes { offset, length } = range {
+            return Ok(
+                match read_bytes(&resolved_path, file_size, offset, length).await {
+                    Ok(output) => ToolResult {
+                        success: true,
+                        output,
+                        error: None,
+                        artifacts: Vec::new(),
+                    },
+                    Err(e) => ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(e),
+                        artifacts: Vec::new(),
+                    },
+                },
+            );
         }
 
         match tokio::fs::read_to_string(&resolved_path).await {
-            Ok(contents) => Ok(ToolResult {
-                success: true,
-                output: contents,
-                error: None,
-            }),
+            Ok(contents) => {
+                let sliced = match range {
+                    ReadRange::Lines { start, end } => slice_lines(&contents, start, end),
+                    _ => Ok(contents),
+                };
+                Ok(match sliced {
+                    Ok(output) => ToolResult {
+                        success: true,
+                        output,
+                        error: None,
+                        artifacts: Vec::new(),
+                    },
+                    Err(e) => ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(e),
+                        artifacts: Vec::new(),
+                    },
+                })
+            }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to read file: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
 }
 
+/// `length` bytes from `offset` (clamped to the end of the file), with a
+/// header giving the returned range and the file's size. Invalid UTF-8 at the
+/// slice edges is replaced rather than rejected.
+async fn read_bytes(
+    path: &std::path::Path,
+    file_size: u64,
+    offset: u64,
+    length: Option<u64>,
+) -> Result<String, String> {
+    if offset > file_size {
+        return Err(format!(
+            "offset {offset} is past the end of the file ({file_size} bytes)"
+        ));
+    }
+    let length = length.map_or(file_size - offset, |length| length.min(file_size - offset));
+
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to read file: {e}"))?;
+    file.seek(std::io::SeekFrom::Start(offset))
+        .await
+        .map_err(|e| format!("Failed to read file: {e}"))?;
+    let mut buf = Vec::new();
+    file.take(length)
+        .read_to_end(&mut buf)
+        .await
+        .map_err(|e| format!("Failed to read file: {e}"))?;
+
+    let end = offset + buf.len() as u64;
+    Ok(format!(
+        "[bytes {offset}-{end} of {file_size}]\n{}",
+        String::from_utf8_lossy(&buf)
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,12 +431,14 @@ mod tests {
         tokio::fs::create_dir_all(&dir).await.unwrap();
 
         let tool = FileReadTool::new(test_security(dir.clone()));
-        let result = tool
+        let err = tool
             .execute(json!({"path": "../../../etc/passwd"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::PathNotAllowed(_))
+        ));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -232,9 +446,14 @@ mod tests {
     #[tokio::test]
     async fn file_read_blocks_absolute_path() {
         let tool = FileReadTool::new(test_security(std::env::temp_dir()));
-        let result = tool.execute(json!({"path": "/etc/passwd"})).await.unwrap();
-        assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+        let err = tool
+            .execute(json!({"path": "/etc/passwd"}))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::PathNotAllowed(_))
+        ));
     }
 
     #[tokio::test]
@@ -391,6 +610,105 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
 
+    #[tokio::test]
+    async fn file_read_line_range() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_line_range");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        tokio::fs::write(dir.join("lines.txt"), "one\ntwo\nthree\nfour\n")
+            .await
+            .unwrap();
+
+        let tool = FileReadTool::new(test_security(dir.clone()));
+        let result = tool
+            .execute(json!({"path": "lines.txt", "start_line": 2, "end_line": 3}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            result.output,
+            "[lines 2-3 of 4, 19 bytes total]\ntwo\nthree\n"
+        );
+
+        // end_line past the last line is clamped
+        let result = tool
+            .execute(json!({"path": "lines.txt", "start_line": 4, "end_line": 99}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "[lines 4-4 of 4, 19 bytes total]\nfour\n");
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn file_read_byte_range() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_byte_range");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        tokio::fs::write(dir.join("bytes.txt"), "hello world")
+            .await
+            .unwrap();
+
+        let tool = FileReadTool::new(test_security(dir.clone()));
+        let result = tool
+            .execute(json!({"path": "bytes.txt", "offset": 6, "length": 5}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "[bytes 6-11 of 11]\nworld");
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn file_read_out_of_bounds_range_errors() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_out_of_bounds");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        tokio::fs::write(dir.join("short.txt"), "a\nb\n")
+            .await
+            .unwrap();
+
+        let tool = FileReadTool::new(test_security(dir.clone()));
+        let past_end = tool
+            .execute(json!({"path": "short.txt", "start_line": 5}))
+            .await
+            .unwrap();
+        assert!(!past_end.success);
+        assert!(past_end
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("past the end of the file (2 lines)"));
+
+        let past_size = tool
+            .execute(json!({"path": "short.txt", "offset": 100}))
+            .await
+            .unwrap();
+        assert!(!past_size.success);
+        assert!(past_size.error.as_deref().unwrap().contains("(4 bytes)"));
+
+        let inverted = tool
+            .execute(json!({"path": "short.txt", "start_line": 2, "end_line": 1}))
+            .await
+            .unwrap();
+        assert!(!inverted.success);
+        assert!(inverted
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Invalid range"));
+
+        let mixed = tool
+            .execute(json!({"path": "short.txt", "start_line": 1, "offset": 0}))
+            .await
+            .unwrap();
+        assert!(!mixed.success);
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
     #[tokio::test]
     async fn file_read_rejects_oversized_file() {
         let dir = std::env::temp_dir().join("zeroclaw_test_file_read_large");
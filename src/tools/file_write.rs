@@ -1,17 +1,30 @@
+use super::scratch::expand_scratch_path;
 use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use crate::security::{PolicyViolation, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Write file contents with path sandboxing
 pub struct FileWriteTool {
     security: Arc<SecurityPolicy>,
+    scratch_dir: Option<PathBuf>,
 }
 
 impl FileWriteTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+        Self {
+            security,
+            scratch_dir: None,
+        }
+    }
+
+    /// Expand a leading `$TMPDIR` in paths to `scratch_dir` (workspace-relative).
+    pub fn with_scratch_dir(mut self, scratch_dir: PathBuf) -> Self {
+        self.scratch_dir = Some(scratch_dir);
+        self
     }
 }
 
@@ -22,7 +35,7 @@ impl Tool for FileWriteTool {
     }
 
     fn description(&self) -> &str {
-        "Write contents to a file in the workspace"
+        "Write contents to a file in the workspace. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -31,11 +44,15 @@ impl Tool for FileWriteTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Relative path to the file within the workspace"
+                    "description": "Relative path to the file within the workspace ($TMPDIR/... for scratch files)"
                 },
                 "content": {
                     "type": "string",
                     "description": "Content to write to the file"
+                },
+                "backup": {
+                    "type": "boolean",
+                    "description": "Keep the previous contents as <path>.bak (default false)"
                 }
             },
             "required": ["path", "content"]
@@ -47,18 +64,19 @@ impl Tool for FileWriteTool {
             .get("path")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        let path = &expand_scratch_path(path, self.scratch_dir.as_deref());
 
         let content = args
             .get("content")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'content' parameter"))?;
+        let backup = args
+            .get("backup")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
 
         if !self.security.can_act() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
-            });
+            return Err(PolicyViolation::ReadOnly.into());
         }
 
         if self.security.is_rate_limited() {
@@ -66,16 +84,13 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                artifacts: Vec::new(),
             });
         }
 
         // Security check: validate path is within workspace
         if !self.security.is_path_allowed(path) {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!("Path not allowed by security policy: {path}")),
-            });
+            return Err(PolicyViolation::PathNotAllowed(path.to_string()).into());
         }
 
         let full_path = self.security.workspace_dir.join(path);
@@ -85,6 +100,7 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some("Invalid path: missing parent directory".into()),
+                artifacts: Vec::new(),
             });
         };
 
@@ -99,6 +115,7 @@ impl Tool for FileWriteTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to resolve file path: {e}")),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -111,6 +128,7 @@ impl Tool for FileWriteTool {
                     "Resolved path escapes workspace: {}",
                     resolved_parent.display()
                 )),
+                artifacts: Vec::new(),
             });
         }
 
@@ -119,6 +137,7 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some("Invalid path: missing file name".into()),
+                artifacts: Vec::new(),
             });
         };
 
@@ -134,6 +153,7 @@ impl Tool for FileWriteTool {
                         "Refusing to write through symlink: {}",
                         resolved_target.display()
                     )),
+                    artifacts: Vec::new(),
                 });
             }
         }
@@ -143,24 +163,113 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".into()),
+                artifacts: Vec::new(),
+            });
+        }
+
+        let existing = tokio::fs::read(&resolved_target).await.ok();
+        let content = match &existing {
+            Some(existing) => match_line_endings(existing, content),
+            None => Cow::Borrowed(content),
+        };
+        if existing.as_deref() == Some(content.as_bytes()) {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("{path} already has this content ({} bytes)", content.len()),
+                error: None,
+                artifacts: Vec::new(),
             });
         }
 
-        match tokio::fs::write(&resolved_target, content).await {
+        match write_atomically(&resolved_target, content.as_bytes(), backup).await {
             Ok(()) => Ok(ToolResult {
                 success: true,
                 output: format!("Written {} bytes to {path}", content.len()),
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to write file: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
 }
 
+/// Keep CRLF line endings when rewriting a file that uses them: content sent
+/// with bare `\n` is converted to `\r\n`. Content that already contains CRLF
+/// is written as given.
+fn match_line_endings<'a>(existing: &[u8], content: &'a str) -> Cow<'a, str> {
+    let uses_crlf = existing.windows(2).any(|pair| pair == b"\r\n");
+    if uses_crlf && content.contains('\n') && !content.contains("\r\n") {
+        Cow::Owned(content.replace('\n', "\r\n"))
+    } else {
+        Cow::Borrowed(content)
+    }
+}
+
+/// Replace `target` with `content` so readers see either the old or the new
+/// file, never a partial one: the bytes go to a temporary sibling first, which
+/// is then renamed over the target. An existing target's permissions carry
+/// over, and with `backup` its contents are kept as `<target>.bak`.
+async fn write_atomically(target: &Path, content: &[u8], backup: bool) -> std::io::Result<()> {
+    let previous = tokio::fs::metadata(target).await.ok();
+    let temp = write_temp_sibling(target, content).await?;
+
+    let result = async {
+        if let Some(meta) = &previous {
+            tokio::fs::set_permissions(&temp, meta.permissions()).await?;
+            if backup {
+                tokio::fs::copy(target, backup_path(target)).await?;
+            }
+        }
+        tokio::fs::rename(&temp, target).await
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&temp).await;
+    }
+    result
+}
+
+/// Write and sync `content` to a hidden temporary file next to `target`, so
+/// the final rename stays on one filesystem.
+async fn write_temp_sibling(target: &Path, content: &[u8]) -> std::io::Result<PathBuf> {
+    use tokio::io::AsyncWriteExt;
+
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = target.with_file_name(format!(
+        ".{file_name}.{}.{}.tmp",
+        std::process::id(),
+        uuid::Uuid::new_v4().simple()
+    ));
+
+    let mut file = tokio::fs::File::create(&temp).await?;
+    let written = async {
+        file.write_all(content).await?;
+        file.sync_all().await
+    }
+    .await;
+    if let Err(e) = written {
+        drop(file);
+        let _ = tokio::fs::remove_file(&temp).await;
+        return Err(e);
+    }
+    Ok(temp)
+}
+
+fn backup_path(target: &Path) -> PathBuf {
+    let mut name = target.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    target.with_file_name(name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,6 +335,25 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
 
+    #[tokio::test]
+    async fn file_write_expands_tmpdir_to_scratch_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let scratch = crate::tools::ScratchDir::create(dir.path(), false).unwrap();
+
+        let tool = FileWriteTool::new(test_security(dir.path().to_path_buf()))
+            .with_scratch_dir(scratch.relative_path().to_path_buf());
+        let result = tool
+            .execute(json!({"path": "$TMPDIR/notes.txt", "content": "scratch"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let content = tokio::fs::read_to_string(scratch.path().join("notes.txt"))
+            .await
+            .unwrap();
+        assert_eq!(content, "scratch");
+    }
+
     #[tokio::test]
     async fn file_write_creates_parent_dirs() {
         let dir = std::env::temp_dir().join("zeroclaw_test_file_write_nested");
@@ -278,12 +406,14 @@ mod tests {
         tokio::fs::create_dir_all(&dir).await.unwrap();
 
         let tool = FileWriteTool::new(test_security(dir.clone()));
-        let result = tool
+        let err = tool
             .execute(json!({"path": "../../etc/evil", "content": "bad"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::PathNotAllowed(_))
+        ));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -291,12 +421,14 @@ mod tests {
     #[tokio::test]
     async fn file_write_blocks_absolute_path() {
         let tool = FileWriteTool::new(test_security(std::env::temp_dir()));
-        let result = tool
+        let err = tool
             .execute(json!({"path": "/etc/evil", "content": "bad"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::PathNotAllowed(_))
+        ));
     }
 
     #[tokio::test]
@@ -369,13 +501,15 @@ mod tests {
         tokio::fs::create_dir_all(&dir).await.unwrap();
 
         let tool = FileWriteTool::new(test_security_with(dir.clone(), AutonomyLevel::ReadOnly, 20));
-        let result = tool
+        let err = tool
             .execute(json!({"path": "out.txt", "content": "should-block"}))
             .await
-            .unwrap();
+            .unwrap_err();
 
-        assert!(!result.success);
-        assert!(result.error.as_deref().unwrap_or("").contains("read-only"));
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
         assert!(!dir.join("out.txt").exists());
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
@@ -465,4 +599,132 @@ mod tests {
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
+
+    #[tokio::test]
+    async fn interrupted_write_leaves_original_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("data.txt");
+        tokio::fs::write(&target, "original").await.unwrap();
+
+        // A crash after staging but before the rename leaves only the temp file.
+        let temp = write_temp_sibling(&target, b"new contents").await.unwrap();
+
+        assert_eq!(
+            tokio::fs::read_to_string(&target).await.unwrap(),
+            "original"
+        );
+        assert_ne!(temp, target);
+        assert_eq!(temp.parent(), target.parent());
+        assert_eq!(
+            tokio::fs::read_to_string(&temp).await.unwrap(),
+            "new contents"
+        );
+    }
+
+    #[tokio::test]
+    async fn file_write_leaves_no_temp_files_and_keeps_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("notes.md"), "v1")
+            .await
+            .unwrap();
+
+        let tool = FileWriteTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"path": "notes.md", "content": "v2", "backup": true}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        assert_eq!(
+            tokio::fs::read_to_string(dir.path().join("notes.md"))
+                .await
+                .unwrap(),
+            "v2"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(dir.path().join("notes.md.bak"))
+                .await
+                .unwrap(),
+            "v1"
+        );
+        let mut names = Vec::new();
+        let mut entries = tokio::fs::read_dir(dir.path()).await.unwrap();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        names.sort();
+        assert_eq!(names, vec!["notes.md", "notes.md.bak"]);
+    }
+
+    #[tokio::test]
+    async fn file_write_with_same_content_is_a_no_op() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("same.txt"), "stable")
+            .await
+            .unwrap();
+
+        let tool = FileWriteTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"path": "same.txt", "content": "stable", "backup": true}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("already has this content"));
+        assert!(!dir.path().join("same.txt.bak").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn file_write_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("script.sh");
+        tokio::fs::write(&target, "echo old").await.unwrap();
+        tokio::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o750))
+            .await
+            .unwrap();
+
+        let tool = FileWriteTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"path": "script.sh", "content": "echo new"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let mode = tokio::fs::metadata(&target)
+            .await
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o750);
+        assert_eq!(
+            tokio::fs::read_to_string(&target).await.unwrap(),
+            "echo new"
+        );
+    }
+
+    #[tokio::test]
+    async fn file_write_preserves_crlf_line_endings() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("win.txt"), "one\r\ntwo\r\n")
+            .await
+            .unwrap();
+
+        let tool = FileWriteTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"path": "win.txt", "content": "one\ntwo\nthree\n"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            tokio::fs::read_to_string(dir.path().join("win.txt"))
+                .await
+                .unwrap(),
+            "one\r\ntwo\r\nthree\r\n"
+        );
+
+        // LF files stay LF
+        assert_eq!(match_line_endings(b"a\nb\n", "c\n"), "c\n");
+    }
 }
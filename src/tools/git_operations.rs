@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::security::{AutonomyLevel, SecurityPolicy};
+use crate::security::{PolicyViolation, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
@@ -128,6 +128,7 @@ impl GitOperationsTool {
             success: true,
             output: serde_json::to_string_pretty(&result).unwrap_or_default(),
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -207,6 +208,7 @@ impl GitOperationsTool {
             success: true,
             output: serde_json::to_string_pretty(&result).unwrap_or_default(),
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -244,6 +246,7 @@ impl GitOperationsTool {
             output: serde_json::to_string_pretty(&json!({ "commits": commits }))
                 .unwrap_or_default(),
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -276,6 +279,7 @@ impl GitOperationsTool {
             }))
             .unwrap_or_default(),
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -315,11 +319,13 @@ impl GitOperationsTool {
                 success: true,
                 output: format!("Committed: {message}"),
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Commit failed: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -340,11 +346,13 @@ impl GitOperationsTool {
                 success: true,
                 output: format!("Staged: {paths}"),
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Add failed: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -376,11 +384,13 @@ impl GitOperationsTool {
                 success: true,
                 output: format!("Switched to branch: {branch_name}"),
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Checkout failed: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -413,11 +423,13 @@ impl GitOperationsTool {
                 success: true,
                 output: out,
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Stash {action} failed: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -488,6 +500,7 @@ impl Tool for GitOperationsTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'operation' parameter".into()),
+                    artifacts: Vec::new(),
                 });
             }
         };
@@ -510,6 +523,7 @@ impl Tool for GitOperationsTool {
                     success: false,
                     output: String::new(),
                     error: Some("Not in a git repository".into()),
+                    artifacts: Vec::new(),
                 });
             }
         }
@@ -517,24 +531,7 @@ impl Tool for GitOperationsTool {
         // Check autonomy level for write operations
         if self.requires_write_access(operation) {
             if !self.security.can_act() {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(
-                        "Action blocked: git write operations require higher autonomy level".into(),
-                    ),
-                });
-            }
-
-            match self.security.autonomy {
-                AutonomyLevel::ReadOnly => {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some("Action blocked: read-only mode".into()),
-                    });
-                }
-                AutonomyLevel::Supervised | AutonomyLevel::Full => {}
+                return Err(PolicyViolation::ReadOnly.into());
             }
         }
 
@@ -544,6 +541,7 @@ impl Tool for GitOperationsTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -561,6 +559,7 @@ impl Tool for GitOperationsTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Unknown operation: {operation}")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -569,7 +568,7 @@ impl Tool for GitOperationsTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::security::SecurityPolicy;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
     use tempfile::TempDir;
 
     fn test_tool(dir: &std::path::Path) -> GitOperationsTool {
@@ -706,17 +705,14 @@ mod tests {
         });
         let tool = GitOperationsTool::new(security, tmp.path().to_path_buf());
 
-        let result = tool
+        let err = tool
             .execute(json!({"operation": "commit", "message": "test"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        // can_act() returns false for ReadOnly, so we get the "higher autonomy level" message
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("higher autonomy"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
     }
 
     #[tokio::test]
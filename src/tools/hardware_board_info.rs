@@ -86,6 +86,10 @@ impl Tool for HardwareBoardInfoTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let board = args
             .get("board")
@@ -103,6 +107,7 @@ impl Tool for HardwareBoardInfoTool {
                     "No peripherals configured. Add boards to config.toml [peripherals.boards]."
                         .into(),
                 ),
+                artifacts: Vec::new(),
             });
         }
 
@@ -121,6 +126,7 @@ impl Tool for HardwareBoardInfoTool {
                         success: true,
                         output: info,
                         error: None,
+                        artifacts: Vec::new(),
                     });
                 }
                 Err(e) => {
@@ -151,6 +157,7 @@ impl Tool for HardwareBoardInfoTool {
             success: true,
             output,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 }
@@ -72,6 +72,10 @@ impl Tool for HardwareMemoryMapTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let board = args
             .get("board")
@@ -89,6 +93,7 @@ impl Tool for HardwareMemoryMapTool {
                     "No peripherals configured. Add boards to config.toml [peripherals.boards]."
                         .into(),
                 ),
+                artifacts: Vec::new(),
             });
         }
 
@@ -139,6 +144,7 @@ impl Tool for HardwareMemoryMapTool {
             success: true,
             output,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 }
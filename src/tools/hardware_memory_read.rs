@@ -59,6 +59,10 @@ impl Tool for HardwareMemoryReadTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         if self.boards.is_empty() {
             return Ok(ToolResult {
@@ -68,6 +72,7 @@ impl Tool for HardwareMemoryReadTool {
                     "No peripherals configured. Add nucleo-f401re to config.toml [peripherals.boards]."
                         .into(),
                 ),
+                artifacts: Vec::new(),
             });
         }
 
@@ -87,6 +92,7 @@ impl Tool for HardwareMemoryReadTool {
                     "Memory read only supports nucleo-f401re, nucleo-f411re. Got: {}",
                     board
                 )),
+                artifacts: Vec::new(),
             });
         }
 
@@ -109,6 +115,7 @@ impl Tool for HardwareMemoryReadTool {
                         success: true,
                         output,
                         error: None,
+                        artifacts: Vec::new(),
                     });
                 }
                 Err(e) => {
@@ -119,6 +126,7 @@ impl Tool for HardwareMemoryReadTool {
                             "probe-rs read failed: {}. Ensure Nucleo is connected via USB and built with --features probe.",
                             e
                         )),
+                        artifacts: Vec::new(),
                     });
                 }
             }
@@ -133,6 +141,7 @@ impl Tool for HardwareMemoryReadTool {
                     "Memory read requires probe feature. Build with: cargo build --features hardware,probe"
                         .into(),
                 ),
+                artifacts: Vec::new(),
             })
         }
     }
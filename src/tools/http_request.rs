@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use crate::security::{PolicyViolation, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
@@ -186,6 +186,10 @@ impl Tool for HttpRequestTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let url = args
             .get("url")
@@ -197,11 +201,7 @@ impl Tool for HttpRequestTool {
         let body = args.get("body").and_then(|v| v.as_str());
 
         if !self.security.can_act() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
-            });
+            return Err(PolicyViolation::ReadOnly.into());
         }
 
         if !self.security.record_action() {
@@ -209,6 +209,7 @@ impl Tool for HttpRequestTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -219,6 +220,7 @@ impl Tool for HttpRequestTool {
                     success: false,
                     output: String::new(),
                     error: Some(e.to_string()),
+                    artifacts: Vec::new(),
                 })
             }
         };
@@ -230,6 +232,7 @@ impl Tool for HttpRequestTool {
                     success: false,
                     output: String::new(),
                     error: Some(e.to_string()),
+                    artifacts: Vec::new(),
                 })
             }
         };
@@ -280,15 +283,33 @@ impl Tool for HttpRequestTool {
                     } else {
                         None
                     },
+                    artifacts: Vec::new(),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("HTTP request failed: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
+
+    fn is_retryable(&self, args: &serde_json::Value, error: &str) -> bool {
+        // Only idempotent methods (GET, HEAD, OPTIONS, PUT, DELETE): a POST or
+        // PATCH that timed out may already have been applied by the server.
+        let method = args.get("method").and_then(|v| v.as_str()).unwrap_or("GET");
+        if !self
+            .validate_method(method)
+            .is_ok_and(|method| method.is_idempotent())
+        {
+            return false;
+        }
+        // Connection errors/timeouts and throttling or gateway statuses; other
+        // 4xx/5xx and policy blocks would fail the same way again.
+        error.starts_with("HTTP request failed:")
+            || matches!(error, "HTTP 429" | "HTTP 502" | "HTTP 503" | "HTTP 504")
+    }
 }
 
 // Helper functions similar to browser_open.rs
@@ -657,12 +678,14 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = HttpRequestTool::new(security, vec!["example.com".into()], 1_000_000, 30);
-        let result = tool
+        let err = tool
             .execute(json!({"url": "https://example.com"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result.error.unwrap().contains("read-only"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
     }
 
     #[tokio::test]
@@ -879,4 +902,31 @@ mod tests {
             .to_string();
         assert!(err.contains("IPv6"));
     }
+
+    #[test]
+    fn retries_only_transient_failures() {
+        let tool = test_tool(vec!["example.com"]);
+        let get = json!({"url": "https://example.com"});
+        assert!(tool.is_retryable(&get, "HTTP request failed: operation timed out"));
+        assert!(tool.is_retryable(&get, "HTTP 503"));
+        assert!(!tool.is_retryable(&get, "HTTP 404"));
+        assert!(!tool.is_retryable(&get, "Action blocked: rate limit exceeded"));
+    }
+
+    #[test]
+    fn retries_only_idempotent_methods() {
+        let tool = test_tool(vec!["example.com"]);
+        for method in ["GET", "head", "OPTIONS", "PUT", "DELETE"] {
+            let args = json!({"url": "https://example.com", "method": method});
+            assert!(tool.is_retryable(&args, "HTTP 503"), "{method}");
+        }
+        for method in ["POST", "PATCH", "INVALID"] {
+            let args = json!({"url": "https://example.com", "method": method});
+            assert!(!tool.is_retryable(&args, "HTTP 503"), "{method}");
+            assert!(
+                !tool.is_retryable(&args, "HTTP request failed: operation timed out"),
+                "{method}"
+            );
+        }
+    }
 }
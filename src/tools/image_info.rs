@@ -125,7 +125,7 @@ impl Tool for ImageInfoTool {
     }
 
     fn description(&self) -> &str {
-        "Read image file metadata (format, dimensions, size) and optionally return base64-encoded data."
+        "Read image file metadata (format, dimensions, size) and optionally return base64-encoded data. Use when: inspecting images, preparing visual data for analysis."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -145,6 +145,10 @@ impl Tool for ImageInfoTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let path_str = args
             .get("path")
@@ -166,6 +170,7 @@ impl Tool for ImageInfoTool {
                 error: Some(format!(
                     "Path not allowed: {path_str} (must be within workspace)"
                 )),
+                artifacts: Vec::new(),
             });
         }
 
@@ -174,6 +179,7 @@ impl Tool for ImageInfoTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("File not found: {path_str}")),
+                artifacts: Vec::new(),
             });
         }
 
@@ -190,6 +196,7 @@ impl Tool for ImageInfoTool {
                 error: Some(format!(
                     "Image too large: {file_size} bytes (max {MAX_IMAGE_BYTES} bytes)"
                 )),
+                artifacts: Vec::new(),
             });
         }
 
@@ -224,6 +231,7 @@ impl Tool for ImageInfoTool {
             success: true,
             output,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 }
@@ -0,0 +1,326 @@
+use super::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Only the tail of the log is read; older events are not useful for
+/// "what just happened" questions.
+const MAX_LOG_BYTES: usize = 1_048_576;
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 100;
+
+/// Query zeroclaw's own event log (written by the `jsonl` observability
+/// backend) for recent errors or a summary of the last run.
+///
+/// The tool only ever reads the configured event log; it takes no path.
+pub struct LogQueryTool {
+    log_path: PathBuf,
+}
+
+impl LogQueryTool {
+    pub fn new(log_path: PathBuf) -> Self {
+        Self { log_path }
+    }
+
+    async fn read_events(&self) -> Result<Vec<Value>, String> {
+        let bytes = tokio::fs::read(&self.log_path).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                format!(
+                    "No event log at {}. Set [observability] backend = \"jsonl\" to record one.",
+                    self.log_path.display()
+                )
+            } else {
+                format!("Failed to read {}: {e}", self.log_path.display())
+            }
+        })?;
+
+        let tail = if bytes.len() > MAX_LOG_BYTES {
+            let start = bytes.len() - MAX_LOG_BYTES;
+            // Skip the partial line at the cut.
+            let newline = bytes[start..].iter().position(|b| *b == b'\n');
+            &bytes[newline.map_or(bytes.len(), |n| start + n + 1)..]
+        } else {
+            &bytes[..]
+        };
+
+        Ok(String::from_utf8_lossy(tail)
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+fn is_error(event: &Value) -> bool {
+    event["event"] == "error" || event["success"] == false
+}
+
+fn field<'a>(event: &'a Value, key: &str) -> &'a str {
+    event[key].as_str().unwrap_or("")
+}
+
+/// One human-readable line per error event.
+fn describe_error(event: &Value) -> String {
+    let ts = field(event, "ts");
+    match field(event, "event") {
+        "error" => format!(
+            "{ts} error [{}]: {}",
+            field(event, "component"),
+            field(event, "error")
+        ),
+        "llm.response" => format!(
+            "{ts} provider call failed ({} / {}): {}",
+            field(event, "provider"),
+            field(event, "model"),
+            event["error"].as_str().unwrap_or("no error message")
+        ),
+        "tool.call" => format!("{ts} tool '{}' failed", field(event, "tool")),
+        other => format!("{ts} {other} failed"),
+    }
+}
+
+fn recent_errors(events: &[Value], limit: usize) -> String {
+    let errors: Vec<&Value> = events.iter().filter(|e| is_error(e)).collect();
+    if errors.is_empty() {
+        return "No errors in the event log.".into();
+    }
+    let shown = &errors[errors.len().saturating_sub(limit)..];
+    let mut out = format!("{} most recent error(s):\n", shown.len());
+    for event in shown {
+        let _ = writeln!(out, "- {}", describe_error(event));
+    }
+    out
+}
+
+/// Summary of the events since the last `agent.start`.
+fn last_run_summary(events: &[Value], limit: usize) -> String {
+    let start = events
+        .iter()
+        .rposition(|e| e["event"] == "agent.start")
+        .unwrap_or(0);
+    let run = &events[start..];
+    if run.is_empty() {
+        return "The event log is empty.".into();
+    }
+
+    let mut out = String::new();
+    if let Some(first) = run.first().filter(|e| e["event"] == "agent.start") {
+        let _ = writeln!(
+            out,
+            "Last run started {} ({} / {})",
+            field(first, "ts"),
+            field(first, "provider"),
+            field(first, "model")
+        );
+    }
+
+    let llm: Vec<&Value> = run
+        .iter()
+        .filter(|e| e["event"] == "llm.response")
+        .collect();
+    let llm_failed = llm.iter().filter(|e| e["success"] == false).count();
+    let _ = writeln!(out, "Provider calls: {} ({llm_failed} failed)", llm.len());
+
+    let mut tools: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for event in run.iter().filter(|e| e["event"] == "tool.call") {
+        let entry = tools.entry(field(event, "tool")).or_default();
+        entry.0 += 1;
+        if event["success"] == false {
+            entry.1 += 1;
+        }
+    }
+    if tools.is_empty() {
+        out.push_str("Tool calls: none\n");
+    } else {
+        let calls: Vec<String> = tools
+            .iter()
+            .map(|(tool, (calls, failed))| format!("{tool} x{calls} ({failed} failed)"))
+            .collect();
+        let _ = writeln!(out, "Tool calls: {}", calls.join(", "));
+    }
+
+    match run.iter().rev().find(|e| e["event"] == "agent.end") {
+        Some(end) => {
+            let _ = writeln!(
+                out,
+                "Finished {} after {} ms",
+                field(end, "ts"),
+                end["duration_ms"].as_u64().unwrap_or(0)
+            );
+        }
+        None => out.push_str("No agent.end event: the run is in progress or ended abnormally\n"),
+    }
+
+    let errors: Vec<&Value> = run.iter().filter(|e| is_error(e)).collect();
+    if errors.is_empty() {
+        out.push_str("Errors: none\n");
+    } else {
+        let _ = writeln!(out, "Errors: {}", errors.len());
+        for event in &errors[errors.len().saturating_sub(limit)..] {
+            let _ = writeln!(out, "- {}", describe_error(event));
+        }
+    }
+    out
+}
+
+#[async_trait]
+impl Tool for LogQueryTool {
+    fn name(&self) -> &str {
+        "log_query"
+    }
+
+    fn description(&self) -> &str {
+        "Read zeroclaw's own event log: recent errors, or a summary of the last run. Use to diagnose why a previous run or tool call failed."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "enum": ["errors", "summary"],
+                    "description": "'errors' lists recent failures; 'summary' describes the last run (default: errors)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of errors to list (default: 10, max: 100)"
+                }
+            }
+        })
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .unwrap_or("errors");
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_LIMIT, |n| {
+                usize::try_from(n).unwrap_or(MAX_LIMIT).clamp(1, MAX_LIMIT)
+            });
+
+        let events = match self.read_events().await {
+            Ok(events) => events,
+            Err(error) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error),
+                    artifacts: Vec::new(),
+                })
+            }
+        };
+
+        let output = match query {
+            "errors" => recent_errors(&events, limit),
+            "summary" => last_run_summary(&events, limit),
+            other => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!(
+                        "Unknown query '{other}'; use 'errors' or 'summary'"
+                    )),
+                    artifacts: Vec::new(),
+                })
+            }
+        };
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            artifacts: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::{JsonlObserver, Observer, ObserverEvent};
+    use std::time::Duration;
+
+    fn seed_log(path: &std::path::Path) {
+        let obs = JsonlObserver::new(path.to_path_buf());
+        obs.record_event(&ObserverEvent::AgentStart {
+            provider: "openrouter".into(),
+            model: "m".into(),
+        });
+        obs.record_event(&ObserverEvent::ToolCall {
+            tool: "shell".into(),
+            duration: Duration::from_millis(5),
+            success: true,
+        });
+        obs.record_event(&ObserverEvent::LlmResponse {
+            provider: "openrouter".into(),
+            model: "m".into(),
+            duration: Duration::from_millis(40),
+            success: false,
+            error_message: Some("HTTP 429: rate limited".into()),
+            input_tokens: None,
+            output_tokens: None,
+        });
+        obs.record_event(&ObserverEvent::Error {
+            component: "agent".into(),
+            message: "provider failed after retries".into(),
+        });
+        obs.flush();
+    }
+
+    #[tokio::test]
+    async fn errors_query_surfaces_seeded_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        seed_log(&path);
+
+        let tool = LogQueryTool::new(path);
+        let result = tool.execute(json!({"query": "errors"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("2 most recent error(s)"));
+        assert!(result.output.contains("HTTP 429: rate limited"));
+        assert!(result
+            .output
+            .contains("error [agent]: provider failed after retries"));
+    }
+
+    #[tokio::test]
+    async fn summary_describes_last_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        seed_log(&path);
+
+        let tool = LogQueryTool::new(path);
+        let result = tool.execute(json!({"query": "summary"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Provider calls: 1 (1 failed)"));
+        assert!(result.output.contains("shell x1 (0 failed)"));
+        assert!(result.output.contains("No agent.end event"));
+        assert!(result.output.contains("Errors: 2"));
+    }
+
+    #[tokio::test]
+    async fn missing_log_explains_how_to_enable_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool = LogQueryTool::new(tmp.path().join("events.jsonl"));
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("backend = \"jsonl\""));
+    }
+
+    #[test]
+    fn schema_takes_no_path() {
+        let tool = LogQueryTool::new(PathBuf::from("events.jsonl"));
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"].get("path").is_none());
+    }
+}
@@ -25,7 +25,7 @@ impl Tool for MemoryForgetTool {
     }
 
     fn description(&self) -> &str {
-        "Remove a memory by key. Use to delete outdated facts or sensitive data. Returns whether the memory was found and removed."
+        "Remove a memory by key. Returns whether the memory was found and removed. Use when: a memory is outdated, incorrect, or sensitive, or its removal was requested. Don't use when: the impact is uncertain."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -41,38 +41,37 @@ impl Tool for MemoryForgetTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let key = args
             .get("key")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'key' parameter"))?;
 
-        if let Err(error) = self
-            .security
-            .enforce_tool_operation(ToolOperation::Act, "memory_forget")
-        {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(error),
-            });
-        }
+        self.security
+            .enforce_tool_operation(ToolOperation::Act, "memory_forget")?;
 
         match self.memory.forget(key).await {
             Ok(true) => Ok(ToolResult {
                 success: true,
                 output: format!("Forgot memory: {key}"),
                 error: None,
+                artifacts: Vec::new(),
             }),
             Ok(false) => Ok(ToolResult {
                 success: true,
                 output: format!("No memory found with key: {key}"),
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to forget memory: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -82,7 +81,7 @@ impl Tool for MemoryForgetTool {
 mod tests {
     use super::*;
     use crate::memory::{MemoryCategory, SqliteMemory};
-    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use crate::security::{AutonomyLevel, PolicyViolation, SecurityPolicy};
     use tempfile::TempDir;
 
     fn test_security() -> Arc<SecurityPolicy> {
@@ -146,13 +145,11 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = MemoryForgetTool::new(mem.clone(), readonly);
-        let result = tool.execute(json!({"key": "temp"})).await.unwrap();
-        assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("read-only mode"));
+        let err = tool.execute(json!({"key": "temp"})).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
         assert!(mem.get("temp").await.unwrap().is_some());
     }
 
@@ -167,13 +164,11 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = MemoryForgetTool::new(mem.clone(), limited);
-        let result = tool.execute(json!({"key": "temp"})).await.unwrap();
-        assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("Rate limit exceeded"));
+        let err = tool.execute(json!({"key": "temp"})).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::RateLimited)
+        );
         assert!(mem.get("temp").await.unwrap().is_some());
     }
 }
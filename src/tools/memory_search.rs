@@ -0,0 +1,318 @@
+use super::traits::{Tool, ToolResult};
+use crate::memory::traits::parse_entry_timestamp;
+use crate::memory::{Memory, MemoryCategory, MemorySearchFilter};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Let the agent search its memory scoped by category and time range
+pub struct MemorySearchTool {
+    memory: Arc<dyn Memory>,
+}
+
+impl MemorySearchTool {
+    pub fn new(memory: Arc<dyn Memory>) -> Self {
+        Self { memory }
+    }
+}
+
+/// Parse a `since`/`until` bound. A bare `YYYY-MM-DD` `until` covers the whole day.
+fn parse_bound(raw: &str, end_of_day: bool) -> anyhow::Result<DateTime<Utc>> {
+    let timestamp = parse_entry_timestamp(raw)
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp '{raw}': use RFC 3339 or YYYY-MM-DD"))?;
+    if end_of_day && raw.trim().len() == "YYYY-MM-DD".len() {
+        return Ok(timestamp + Duration::days(1) - Duration::seconds(1));
+    }
+    Ok(timestamp)
+}
+
+#[async_trait]
+impl Tool for MemorySearchTool {
+    fn name(&self) -> &str {
+        "memory_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search memory with filters: restrict results to a category (e.g. only 'core' preferences) and/or a time range (since/until). Use instead of memory_recall when only recent or specific kinds of memories are relevant. Don't use when: a plain memory_recall is enough."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Keywords or phrase to search for; omit to list the most recent matching memories"
+                },
+                "category": {
+                    "type": "string",
+                    "description": "Only this category: 'core', 'daily', 'conversation', or a custom category name"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only memories stored at or after this time (RFC 3339 or YYYY-MM-DD)"
+                },
+                "until": {
+                    "type": "string",
+                    "description": "Only memories stored at or before this time (RFC 3339 or YYYY-MM-DD, inclusive)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max results to return (default: 5)"
+                }
+            }
+        })
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(5, |v| v as usize);
+
+        let category = args
+            .get("category")
+            .and_then(|v| v.as_str())
+            .map(MemoryCategory::from_name);
+
+        let since = args
+            .get("since")
+            .and_then(|v| v.as_str())
+            .map(|raw| parse_bound(raw, false))
+            .transpose()?;
+        let until = args
+            .get("until")
+            .and_then(|v| v.as_str())
+            .map(|raw| parse_bound(raw, true))
+            .transpose()?;
+
+        let filter = MemorySearchFilter {
+            query,
+            category,
+            since,
+            until,
+            session_id: None,
+            limit,
+        };
+
+        match self.memory.search(&filter).await {
+            Ok(entries) if entries.is_empty() => Ok(ToolResult {
+                success: true,
+                output: "No memories found matching those filters.".into(),
+                error: None,
+                artifacts: Vec::new(),
+            }),
+            Ok(entries) => {
+                let mut output = format!("Found {} memories:\n", entries.len());
+                for entry in &entries {
+                    let score = entry
+                        .score
+                        .map_or_else(String::new, |s| format!(" [{s:.0}%]"));
+                    let _ = writeln!(
+                        output,
+                        "- [{}] {} ({}): {}{score}",
+                        entry.category, entry.key, entry.timestamp, entry.content
+                    );
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                    artifacts: Vec::new(),
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Memory search failed: {e}")),
+                artifacts: Vec::new(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryEntry, SqliteMemory};
+    use tempfile::TempDir;
+
+    /// In-memory backend with fixed timestamps, exercising the default `search`.
+    struct FixedMemory {
+        entries: Vec<MemoryEntry>,
+    }
+
+    #[async_trait]
+    impl Memory for FixedMemory {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn store(
+            &self,
+            _key: &str,
+            _content: &str,
+            _category: MemoryCategory,
+            _session_id: Option<&str>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn recall(
+            &self,
+            query: &str,
+            limit: usize,
+            _session_id: Option<&str>,
+        ) -> anyhow::Result<Vec<MemoryEntry>> {
+            Ok(self
+                .entries
+                .iter()
+                .filter(|e| e.content.contains(query))
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        async fn get(&self, _key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+            Ok(None)
+        }
+
+        async fn list(
+            &self,
+            category: Option<&MemoryCategory>,
+            _session_id: Option<&str>,
+        ) -> anyhow::Result<Vec<MemoryEntry>> {
+            Ok(self
+                .entries
+                .iter()
+                .filter(|e| category.map_or(true, |c| *c == e.category))
+                .cloned()
+                .collect())
+        }
+
+        async fn forget(&self, _key: &str) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn count(&self) -> anyhow::Result<usize> {
+            Ok(self.entries.len())
+        }
+
+        async fn health_check(&self) -> bool {
+            true
+        }
+    }
+
+    fn entry(key: &str, content: &str, timestamp: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: content.into(),
+            category: MemoryCategory::Daily,
+            timestamp: timestamp.into(),
+            session_id: None,
+            score: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn category_filter_restricts_results() {
+        let tmp = TempDir::new().unwrap();
+        let mem: Arc<dyn Memory> = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+        mem.store("lang", "User prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store(
+            "log",
+            "Discussed Rust lifetimes",
+            MemoryCategory::Daily,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let tool = MemorySearchTool::new(mem);
+        let result = tool
+            .execute(json!({"query": "Rust", "category": "core"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Found 1"));
+        assert!(result.output.contains("User prefers Rust"));
+        assert!(!result.output.contains("lifetimes"));
+    }
+
+    #[tokio::test]
+    async fn date_range_excludes_older_entries() {
+        let mem: Arc<dyn Memory> = Arc::new(FixedMemory {
+            entries: vec![
+                entry("old", "deploy notes v1", "2026-01-02T09:00:00Z"),
+                entry("new", "deploy notes v2", "2026-02-12T09:00:00Z"),
+                entry("future", "deploy notes v3", "2026-03-01T09:00:00Z"),
+            ],
+        });
+
+        let tool = MemorySearchTool::new(mem);
+        let result = tool
+            .execute(json!({"query": "deploy", "since": "2026-02-09", "until": "2026-02-15"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Found 1"));
+        assert!(result.output.contains("v2"));
+        assert!(!result.output.contains("v1"));
+        assert!(!result.output.contains("v3"));
+    }
+
+    #[tokio::test]
+    async fn empty_query_lists_recent_entries_in_range() {
+        let mem: Arc<dyn Memory> = Arc::new(FixedMemory {
+            entries: vec![
+                entry("old", "one", "2026-01-02T09:00:00Z"),
+                entry("new", "two", "2026-02-12T09:00:00Z"),
+            ],
+        });
+
+        let tool = MemorySearchTool::new(mem);
+        let result = tool
+            .execute(json!({"since": "2026-02-01T00:00:00Z"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 1"));
+        assert!(result.output.contains("two"));
+    }
+
+    #[tokio::test]
+    async fn invalid_timestamp_is_rejected() {
+        let mem: Arc<dyn Memory> = Arc::new(FixedMemory {
+            entries: Vec::new(),
+        });
+        let tool = MemorySearchTool::new(mem);
+        let err = tool
+            .execute(json!({"query": "x", "since": "last week"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid timestamp"));
+    }
+
+    #[test]
+    fn until_date_covers_whole_day() {
+        let until = parse_bound("2026-02-15", true).unwrap();
+        assert_eq!(until.to_rfc3339(), "2026-02-15T23:59:59+00:00");
+        let since = parse_bound("2026-02-15", false).unwrap();
+        assert_eq!(since.to_rfc3339(), "2026-02-15T00:00:00+00:00");
+    }
+}
@@ -25,7 +25,7 @@ impl Tool for MemoryStoreTool {
     }
 
     fn description(&self) -> &str {
-        "Store a fact, preference, or note in long-term memory. Use category 'core' for permanent facts, 'daily' for session notes, 'conversation' for chat context, or a custom category name."
+        "Store a fact, preference, or note in long-term memory. Use category 'core' for permanent facts, 'daily' for session notes, 'conversation' for chat context, or a custom category name. Use when: preserving durable preferences, decisions, key context. Don't use when: the information is transient, noisy, or sensitive without need."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -49,6 +49,10 @@ impl Tool for MemoryStoreTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let key = args
             .get("key")
@@ -60,34 +64,26 @@ impl Tool for MemoryStoreTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'content' parameter"))?;
 
-        let category = match args.get("category").and_then(|v| v.as_str()) {
-            Some("core") | None => MemoryCategory::Core,
-            Some("daily") => MemoryCategory::Daily,
-            Some("conversation") => MemoryCategory::Conversation,
-            Some(other) => MemoryCategory::Custom(other.to_string()),
-        };
-
-        if let Err(error) = self
-            .security
-            .enforce_tool_operation(ToolOperation::Act, "memory_store")
-        {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(error),
-            });
-        }
+        let category = args
+            .get("category")
+            .and_then(|v| v.as_str())
+            .map_or(MemoryCategory::Core, MemoryCategory::from_name);
+
+        self.security
+            .enforce_tool_operation(ToolOperation::Act, "memory_store")?;
 
         match self.memory.store(key, content, category, None).await {
             Ok(()) => Ok(ToolResult {
                 success: true,
                 output: format!("Stored memory: {key}"),
                 error: None,
+                artifacts: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to store memory: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -97,7 +93,7 @@ impl Tool for MemoryStoreTool {
 mod tests {
     use super::*;
     use crate::memory::SqliteMemory;
-    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use crate::security::{AutonomyLevel, PolicyViolation, SecurityPolicy};
     use tempfile::TempDir;
 
     fn test_security() -> Arc<SecurityPolicy> {
@@ -188,16 +184,14 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = MemoryStoreTool::new(mem.clone(), readonly);
-        let result = tool
+        let err = tool
             .execute(json!({"key": "lang", "content": "Prefers Rust"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("read-only mode"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
         assert!(mem.get("lang").await.unwrap().is_none());
     }
 
@@ -209,16 +203,14 @@ mod tests {
             ..SecurityPolicy::default()
         });
         let tool = MemoryStoreTool::new(mem.clone(), limited);
-        let result = tool
+        let err = tool
             .execute(json!({"key": "lang", "content": "Prefers Rust"}))
             .await
-            .unwrap();
-        assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("Rate limit exceeded"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::RateLimited)
+        );
         assert!(mem.get("lang").await.unwrap().is_none());
     }
 }
@@ -1,5 +1,7 @@
 pub mod browser;
+pub mod browser_js;
 pub mod browser_open;
+pub mod browser_screenshot;
 pub mod composio;
 pub mod cron_add;
 pub mod cron_list;
@@ -16,20 +18,32 @@ pub mod hardware_memory_map;
 pub mod hardware_memory_read;
 pub mod http_request;
 pub mod image_info;
+pub mod injection_filter;
+pub mod log_query;
 pub mod memory_forget;
 pub mod memory_recall;
+pub mod memory_search;
 pub mod memory_store;
+pub mod multi_file_write;
+pub mod offload;
 pub mod proxy_config;
 pub mod pushover;
+pub mod retry;
 pub mod schedule;
 pub mod schema;
+pub mod scratch;
 pub mod screenshot;
 pub mod shell;
+#[cfg(feature = "templates")]
+pub mod template_expand;
+pub mod todo;
 pub mod traits;
 pub mod web_search_tool;
 
 pub use browser::{BrowserTool, ComputerUseConfig};
+pub use browser_js::BrowserJsTool;
 pub use browser_open::BrowserOpenTool;
+pub use browser_screenshot::BrowserScreenshotTool;
 pub use composio::ComposioTool;
 pub use cron_add::CronAddTool;
 pub use cron_list::CronListTool;
@@ -46,26 +60,36 @@ pub use hardware_memory_map::HardwareMemoryMapTool;
 pub use hardware_memory_read::HardwareMemoryReadTool;
 pub use http_request::HttpRequestTool;
 pub use image_info::ImageInfoTool;
+pub use injection_filter::PromptInjectionFilter;
+pub use log_query::LogQueryTool;
 pub use memory_forget::MemoryForgetTool;
 pub use memory_recall::MemoryRecallTool;
+pub use memory_search::MemorySearchTool;
 pub use memory_store::MemoryStoreTool;
+pub use multi_file_write::MultiFileWriteTool;
+pub use offload::OffloadLargeResults;
 pub use proxy_config::ProxyConfigTool;
 pub use pushover::PushoverTool;
+pub use retry::{execute_with_retry, ToolRetryPolicy};
 pub use schedule::ScheduleTool;
 #[allow(unused_imports)]
 pub use schema::{CleaningStrategy, SchemaCleanr};
+pub use scratch::ScratchDir;
 pub use screenshot::ScreenshotTool;
 pub use shell::ShellTool;
+#[cfg(feature = "templates")]
+pub use template_expand::TemplateExpandTool;
+pub use todo::{TodoList, TodoTool};
 pub use traits::Tool;
 #[allow(unused_imports)]
-pub use traits::{ToolResult, ToolSpec};
+pub use traits::{Artifact, ArtifactData, ToolResult, ToolSpec};
 pub use web_search_tool::WebSearchTool;
 
 use crate::config::{Config, DelegateAgentConfig};
 use crate::memory::Memory;
 use crate::runtime::{NativeRuntime, RuntimeAdapter};
-use crate::security::SecurityPolicy;
-use std::collections::HashMap;
+use crate::security::{SecurityPolicy, ToolClass};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Create the default tool registry
@@ -85,6 +109,41 @@ pub fn default_tools_with_runtime(
     ]
 }
 
+/// Tools backed by the memory backend, dropped when it is unavailable.
+pub const MEMORY_TOOL_NAMES: &[&str] = &[
+    "memory_store",
+    "memory_recall",
+    "memory_search",
+    "memory_forget",
+];
+
+/// Whether `name` is one of [`MEMORY_TOOL_NAMES`].
+pub fn is_memory_tool(name: &str) -> bool {
+    MEMORY_TOOL_NAMES.contains(&name)
+}
+
+/// `(name, description)` of each registered tool, for the system prompt's
+/// tool section. Fails when two tools share a name or a tool has no
+/// description, since the model could not tell which one it is calling.
+pub fn tool_descriptions(tools: &[Box<dyn Tool>]) -> anyhow::Result<Vec<(&str, &str)>> {
+    let mut seen = HashSet::new();
+    for tool in tools {
+        if !seen.insert(tool.name()) {
+            anyhow::bail!(
+                "Two tools are registered as '{}'; tool names must be unique",
+                tool.name()
+            );
+        }
+        if tool.description().trim().is_empty() {
+            anyhow::bail!("Tool '{}' has no description", tool.name());
+        }
+    }
+    Ok(tools
+        .iter()
+        .map(|tool| (tool.name(), tool.description()))
+        .collect())
+}
+
 /// Create full tool registry including memory tools and optional Composio
 #[allow(clippy::implicit_hasher, clippy::too_many_arguments)]
 pub fn all_tools(
@@ -136,6 +195,7 @@ pub fn all_tools_with_runtime(
         Box::new(ShellTool::new(security.clone(), runtime)),
         Box::new(FileReadTool::new(security.clone())),
         Box::new(FileWriteTool::new(security.clone())),
+        Box::new(MultiFileWriteTool::new(security.clone())),
         Box::new(CronAddTool::new(config.clone(), security.clone())),
         Box::new(CronListTool::new(config.clone())),
         Box::new(CronRemoveTool::new(config.clone())),
@@ -144,8 +204,12 @@ pub fn all_tools_with_runtime(
         Box::new(CronRunsTool::new(config.clone())),
         Box::new(MemoryStoreTool::new(memory.clone(), security.clone())),
         Box::new(MemoryRecallTool::new(memory.clone())),
+        Box::new(MemorySearchTool::new(memory.clone())),
         Box::new(MemoryForgetTool::new(memory, security.clone())),
         Box::new(ScheduleTool::new(security.clone(), root_config.clone())),
+        Box::new(LogQueryTool::new(crate::observability::jsonl::log_path(
+            &root_config.observability,
+        ))),
         Box::new(ProxyConfigTool::new(config.clone(), security.clone())),
         Box::new(GitOperationsTool::new(
             security.clone(),
@@ -157,6 +221,9 @@ pub fn all_tools_with_runtime(
         )),
     ];
 
+    #[cfg(feature = "templates")]
+    tools.push(Box::new(TemplateExpandTool::new(security.clone())));
+
     if browser_config.enabled {
         // Add legacy browser_open tool for simple URL opening
         tools.push(Box::new(BrowserOpenTool::new(
@@ -182,6 +249,21 @@ pub fn all_tools_with_runtime(
                 max_coordinate_y: browser_config.computer_use.max_coordinate_y,
             },
         )));
+        if browser_config.allow_screenshots {
+            tools.push(Box::new(BrowserScreenshotTool::new(
+                security.clone(),
+                browser_config.allowed_domains.clone(),
+                browser_config.native_chrome_path.clone(),
+            )));
+        }
+        if cfg!(feature = "browser-screenshot") && browser_config.allow_scripts {
+            tools.push(Box::new(BrowserJsTool::new(
+                security.clone(),
+                browser_config.allowed_domains.clone(),
+                browser_config.native_chrome_path.clone(),
+                browser_config.max_script_length,
+            )));
+        }
     }
 
     if http_config.enabled {
@@ -234,6 +316,17 @@ pub fn all_tools_with_runtime(
         )));
     }
 
+    // Full autonomy registers network tools only with `allow_network`.
+    if !security.allows_tool_class(ToolClass::Network) {
+        let before = tools.len();
+        tools.retain(|tool| security.allows_tool(tool.name()));
+        if tools.len() < before {
+            tracing::info!(
+                "Network tools disabled in full autonomy; set [autonomy] allow_network = true to enable them"
+            );
+        }
+    }
+
     tools
 }
 
@@ -258,6 +351,36 @@ mod tests {
         assert_eq!(tools.len(), 3);
     }
 
+    #[test]
+    fn tool_descriptions_come_from_the_tools() {
+        let security = Arc::new(SecurityPolicy::default());
+        let tools = default_tools(security);
+        let descs = tool_descriptions(&tools).unwrap();
+        assert_eq!(descs.len(), tools.len());
+        for ((name, description), tool) in descs.iter().zip(&tools) {
+            assert_eq!(*name, tool.name());
+            assert_eq!(*description, tool.description());
+            // The prompt has no separate guidance table; it comes from here.
+            assert!(description.contains("Use when:"), "{name}: {description}");
+            assert!(
+                description.contains("Don't use when:"),
+                "{name}: {description}"
+            );
+        }
+    }
+
+    #[test]
+    fn tool_descriptions_reject_duplicate_names() {
+        let security = Arc::new(SecurityPolicy::default());
+        let mut tools = default_tools(security.clone());
+        tools.push(Box::new(FileReadTool::new(security)));
+        let err = tool_descriptions(&tools).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Two tools are registered as 'file_read'; tool names must be unique"
+        );
+    }
+
     #[test]
     fn all_tools_excludes_browser_when_disabled() {
         let tmp = TempDir::new().unwrap();
@@ -333,10 +456,125 @@ mod tests {
         );
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"browser_open"));
+        assert!(!names.contains(&"browser_screenshot"));
+        assert!(!names.contains(&"browser_js"));
         assert!(names.contains(&"pushover"));
         assert!(names.contains(&"proxy_config"));
     }
 
+    #[test]
+    fn all_tools_includes_browser_screenshot_when_allowed() {
+        let tmp = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy::default());
+        let mem_cfg = MemoryConfig {
+            backend: "markdown".into(),
+            ..MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> =
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+
+        let browser = BrowserConfig {
+            enabled: true,
+            allowed_domains: vec!["example.com".into()],
+            allow_screenshots: true,
+            allow_scripts: true,
+            ..BrowserConfig::default()
+        };
+        let http = crate::config::HttpRequestConfig::default();
+        let cfg = test_config(&tmp);
+
+        let tools = all_tools(
+            Arc::new(Config::default()),
+            &security,
+            mem,
+            None,
+            None,
+            &browser,
+            &http,
+            tmp.path(),
+            &HashMap::new(),
+            None,
+            &cfg,
+        );
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert!(names.contains(&"browser_screenshot"));
+        assert_eq!(
+            names.contains(&"browser_js"),
+            cfg!(feature = "browser-screenshot")
+        );
+    }
+
+    fn full_autonomy_tool_names(allow_network: bool) -> Vec<String> {
+        let tmp = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: crate::security::AutonomyLevel::Full,
+            allow_network,
+            ..SecurityPolicy::default()
+        });
+        let mem_cfg = MemoryConfig {
+            backend: "markdown".into(),
+            ..MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> =
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+
+        let browser = BrowserConfig {
+            enabled: true,
+            allowed_domains: vec!["example.com".into()],
+            ..BrowserConfig::default()
+        };
+        let http = crate::config::HttpRequestConfig {
+            enabled: true,
+            ..crate::config::HttpRequestConfig::default()
+        };
+        let mut cfg = test_config(&tmp);
+        cfg.web_search.enabled = true;
+
+        let tools = all_tools(
+            Arc::new(Config::default()),
+            &security,
+            mem,
+            Some("composio-key"),
+            None,
+            &browser,
+            &http,
+            tmp.path(),
+            &HashMap::new(),
+            None,
+            &cfg,
+        );
+        tools.iter().map(|t| t.name().to_string()).collect()
+    }
+
+    #[test]
+    fn all_tools_excludes_network_tools_in_full_autonomy_without_opt_in() {
+        let names = full_autonomy_tool_names(false);
+        for network_tool in [
+            "browser_open",
+            "browser",
+            "http_request",
+            "web_search_tool",
+            "composio",
+        ] {
+            assert!(
+                !names.iter().any(|name| name == network_tool),
+                "{network_tool} registered without allow_network"
+            );
+        }
+        assert!(names.iter().any(|name| name == "shell"));
+        assert!(names.iter().any(|name| name == "file_read"));
+
+        let names = full_autonomy_tool_names(true);
+        for network_tool in [
+            "browser_open",
+            "http_request",
+            "web_search_tool",
+            "composio",
+        ] {
+            assert!(names.iter().any(|name| name == network_tool));
+        }
+    }
+
     #[test]
     fn default_tools_names() {
         let security = Arc::new(SecurityPolicy::default());
@@ -397,6 +635,7 @@ mod tests {
             success: true,
             output: "hello".into(),
             error: None,
+            artifacts: Vec::new(),
         };
         let json = serde_json::to_string(&result).unwrap();
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
@@ -411,6 +650,7 @@ mod tests {
             success: false,
             output: String::new(),
             error: Some("boom".into()),
+            artifacts: Vec::new(),
         };
         let json = serde_json::to_string(&result).unwrap();
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
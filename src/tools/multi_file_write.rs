@@ -0,0 +1,445 @@
+use super::scratch::expand_scratch_path;
+use super::traits::{Tool, ToolResult};
+use crate::security::{PolicyViolation, SecurityPolicy};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Upper bound on files per call, to keep one call from rewriting a tree.
+const MAX_FILES_PER_CALL: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct FileEntry {
+    path: String,
+    content: String,
+}
+
+/// A validated write: where it goes and the staged temp file beside it.
+struct PlannedWrite<'a> {
+    path: &'a str,
+    content: &'a str,
+    target: PathBuf,
+    staged: PathBuf,
+}
+
+/// Write several files in one call; either every file is written or none is.
+pub struct MultiFileWriteTool {
+    security: Arc<SecurityPolicy>,
+    scratch_dir: Option<PathBuf>,
+}
+
+impl MultiFileWriteTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            security,
+            scratch_dir: None,
+        }
+    }
+
+    /// Expand a leading `$TMPDIR` in paths to `scratch_dir` (workspace-relative).
+    pub fn with_scratch_dir(mut self, scratch_dir: PathBuf) -> Self {
+        self.scratch_dir = Some(scratch_dir);
+        self
+    }
+
+    fn failure(error: String) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+            artifacts: Vec::new(),
+        }
+    }
+
+    /// Resolve one allowed path against the workspace without touching disk.
+    /// Returns the absolute target path.
+    async fn validate(&self, path: &str) -> Result<PathBuf, String> {
+        let full_path = self.security.workspace_dir.join(path);
+        let (Some(parent), Some(file_name)) = (full_path.parent(), full_path.file_name()) else {
+            return Err(format!("Invalid path: {path}"));
+        };
+
+        // Parent directories may not exist yet; resolve the deepest one that
+        // does so symlinked ancestors cannot escape the workspace.
+        let mut existing = parent;
+        while !existing.exists() {
+            existing = existing
+                .parent()
+                .ok_or_else(|| format!("Invalid path: {path}"))?;
+        }
+        let resolved = tokio::fs::canonicalize(existing)
+            .await
+            .map_err(|e| format!("Failed to resolve file path {path}: {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(format!(
+                "Resolved path escapes workspace: {}",
+                resolved.display()
+            ));
+        }
+
+        let target = resolved
+            .join(parent.strip_prefix(existing).unwrap_or(Path::new("")))
+            .join(file_name);
+        if let Ok(meta) = tokio::fs::symlink_metadata(&target).await {
+            if meta.file_type().is_symlink() {
+                return Err(format!(
+                    "Refusing to write through symlink: {}",
+                    target.display()
+                ));
+            }
+            if meta.is_dir() {
+                return Err(format!("Path is a directory: {path}"));
+            }
+        }
+        Ok(target)
+    }
+}
+
+/// Temp file next to `target`, so the final rename stays on one filesystem.
+fn sibling(target: &Path, suffix: &str) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    target.with_file_name(format!(
+        ".{name}.{suffix}-{}",
+        uuid::Uuid::new_v4().simple()
+    ))
+}
+
+/// Move every staged file into place. On the first failure, put back what
+/// was already replaced and report the error.
+async fn commit(plan: &[PlannedWrite<'_>]) -> Result<(), String> {
+    // (target, backup of the previous file if there was one)
+    let mut committed: Vec<(&Path, Option<PathBuf>)> = Vec::new();
+    let mut result = Ok(());
+
+    for write in plan {
+        let backup = if write.target.exists() {
+            let backup = sibling(&write.target, "bak");
+            if let Err(e) = tokio::fs::rename(&write.target, &backup).await {
+                result = Err(format!("Failed to replace {}: {e}", write.path));
+                break;
+            }
+            Some(backup)
+        } else {
+            None
+        };
+        if let Err(e) = tokio::fs::rename(&write.staged, &write.target).await {
+            if let Some(backup) = &backup {
+                let _ = tokio::fs::rename(backup, &write.target).await;
+            }
+            result = Err(format!("Failed to write {}: {e}", write.path));
+            break;
+        }
+        committed.push((&write.target, backup));
+    }
+
+    if result.is_err() {
+        for (target, backup) in committed.iter().rev() {
+            match backup {
+                Some(backup) => {
+                    let _ = tokio::fs::rename(backup, target).await;
+                }
+                None => {
+                    let _ = tokio::fs::remove_file(target).await;
+                }
+            }
+        }
+    } else {
+        for backup in committed.iter().filter_map(|(_, b)| b.as_ref()) {
+            let _ = tokio::fs::remove_file(backup).await;
+        }
+    }
+    for write in plan {
+        let _ = tokio::fs::remove_file(&write.staged).await;
+    }
+    result
+}
+
+#[async_trait]
+impl Tool for MultiFileWriteTool {
+    fn name(&self) -> &str {
+        "multi_file_write"
+    }
+
+    fn description(&self) -> &str {
+        "Write several files in the workspace in one call; all are written or none are. Use when: a change spans files that must stay consistent. Don't use when: only one file changes."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "files": {
+                    "type": "array",
+                    "description": "Files to write",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "path": {
+                                "type": "string",
+                                "description": "Relative path to the file within the workspace ($TMPDIR/... for scratch files)"
+                            },
+                            "content": {
+                                "type": "string",
+                                "description": "Content to write to the file"
+                            }
+                        },
+                        "required": ["path", "content"]
+                    }
+                }
+            },
+            "required": ["files"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let files: Vec<FileEntry> = serde_json::from_value(
+            args.get("files")
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'files' parameter"))?,
+        )
+        .map_err(|e| anyhow::anyhow!("Invalid 'files' parameter: {e}"))?;
+
+        if files.is_empty() {
+            return Ok(Self::failure("No files to write".into()));
+        }
+        if files.len() > MAX_FILES_PER_CALL {
+            return Ok(Self::failure(format!(
+                "Too many files: {} (max {MAX_FILES_PER_CALL} per call)",
+                files.len()
+            )));
+        }
+
+        if !self.security.can_act() {
+            return Err(PolicyViolation::ReadOnly.into());
+        }
+
+        if self.security.is_rate_limited() {
+            return Ok(Self::failure(
+                "Rate limit exceeded: too many actions in the last hour".into(),
+            ));
+        }
+
+        // Validate every path before anything touches disk.
+        let paths: Vec<String> = files
+            .iter()
+            .map(|f| expand_scratch_path(&f.path, self.scratch_dir.as_deref()))
+            .collect();
+        let mut seen = HashSet::new();
+        let mut targets = Vec::with_capacity(files.len());
+        for path in &paths {
+            if !self.security.is_path_allowed(path) {
+                return Err(PolicyViolation::PathNotAllowed(path.clone()).into());
+            }
+            let target = match self.validate(path).await {
+                Ok(target) => target,
+                Err(e) => return Ok(Self::failure(format!("{e} (no files were written)"))),
+            };
+            if !seen.insert(target.clone()) {
+                return Ok(Self::failure(format!(
+                    "Duplicate path: {path} (no files were written)"
+                )));
+            }
+            targets.push(target);
+        }
+
+        if !self.security.record_action() {
+            return Ok(Self::failure(
+                "Rate limit exceeded: action budget exhausted".into(),
+            ));
+        }
+
+        // Stage every file beside its target, then rename them all into place.
+        let mut plan = Vec::with_capacity(files.len());
+        for ((file, path), target) in files.iter().zip(&paths).zip(targets) {
+            let staged = sibling(&target, "zeroclaw-tmp");
+            plan.push(PlannedWrite {
+                path,
+                content: &file.content,
+                target,
+                staged,
+            });
+        }
+        for write in &plan {
+            let staged = async {
+                if let Some(parent) = write.target.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&write.staged, write.content).await
+            }
+            .await;
+            if let Err(e) = staged {
+                for write in &plan {
+                    let _ = tokio::fs::remove_file(&write.staged).await;
+                }
+                return Ok(Self::failure(format!(
+                    "Failed to write {}: {e} (no files were written)",
+                    write.path
+                )));
+            }
+        }
+
+        if let Err(e) = commit(&plan).await {
+            return Ok(Self::failure(format!("{e} (no files were written)")));
+        }
+
+        let bytes: usize = plan.iter().map(|w| w.content.len()).sum();
+        let listing: Vec<&str> = plan.iter().map(|w| w.path).collect();
+        Ok(ToolResult {
+            success: true,
+            output: format!(
+                "Wrote {} file(s), {bytes} bytes: {}",
+                plan.len(),
+                listing.join(", ")
+            ),
+            error: None,
+            artifacts: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+
+    fn test_security(workspace: std::path::PathBuf) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    #[test]
+    fn multi_file_write_name_and_schema() {
+        let tool = MultiFileWriteTool::new(test_security(std::env::temp_dir()));
+        assert_eq!(tool.name(), "multi_file_write");
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["files"]["items"]["properties"]["path"].is_object());
+        assert!(schema["required"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("files")));
+    }
+
+    #[tokio::test]
+    async fn writes_all_files_and_reports_count() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("README.md"), "old").unwrap();
+
+        let tool = MultiFileWriteTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"files": [
+                {"path": "README.md", "content": "# App"},
+                {"path": "src/main.rs", "content": "fn main() {}"},
+                {"path": "src/lib/util.rs", "content": ""}
+            ]}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.starts_with("Wrote 3 file(s)"));
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("README.md")).unwrap(),
+            "# App"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert!(dir.path().join("src/lib/util.rs").is_file());
+
+        // No staged or backup files are left behind.
+        let leftovers: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_name().to_string_lossy().starts_with('.'))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn blocked_path_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let tool = MultiFileWriteTool::new(test_security(dir.path().to_path_buf()));
+        let err = tool
+            .execute(json!({"files": [
+                {"path": "ok.txt", "content": "fine"},
+                {"path": "../escape.txt", "content": "bad"}
+            ]}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::PathNotAllowed("../escape.txt".into()))
+        );
+        assert!(!dir.path().join("ok.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn duplicate_paths_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let tool = MultiFileWriteTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"files": [
+                {"path": "a.txt", "content": "1"},
+                {"path": "a.txt", "content": "2"}
+            ]}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Duplicate path"));
+        assert!(!dir.path().join("a.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn directory_target_leaves_other_files_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("keep.txt"), "original").unwrap();
+        // A directory where a file should go fails the whole batch.
+        std::fs::create_dir(dir.path().join("taken")).unwrap();
+
+        let tool = MultiFileWriteTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"files": [
+                {"path": "keep.txt", "content": "changed"},
+                {"path": "taken", "content": "x"}
+            ]}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("keep.txt")).unwrap(),
+            "original"
+        );
+    }
+
+    #[tokio::test]
+    async fn blocks_readonly_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            workspace_dir: dir.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+
+        let tool = MultiFileWriteTool::new(security);
+        let err = tool
+            .execute(json!({"files": [{"path": "a.txt", "content": "1"}]}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
+        assert!(!dir.path().join("a.txt").exists());
+    }
+}
@@ -0,0 +1,264 @@
+//! Offload large tool results to the run's scratch directory.
+//!
+//! Successful output above a size threshold is written to
+//! `$TMPDIR/tool-results/` and the model gets a reference plus a short
+//! summary (size, shape, first and last lines) instead of the full text. It
+//! can `file_read` the file with a line or byte range when it needs more.
+
+use super::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Subdirectory of the scratch directory holding offloaded results.
+const RESULTS_DIR: &str = "tool-results";
+/// Lines shown from the start and end of an offloaded result.
+const HEAD_LINES: usize = 20;
+const TAIL_LINES: usize = 5;
+/// Longest line shown in the summary before it is cut.
+const MAX_PREVIEW_LINE_CHARS: usize = 200;
+
+/// Wraps a tool so large successful results are offloaded to a file.
+pub struct OffloadLargeResults {
+    inner: Box<dyn Tool>,
+    scratch_dir: PathBuf,
+    threshold_bytes: usize,
+    counter: Arc<AtomicUsize>,
+}
+
+impl OffloadLargeResults {
+    /// `scratch_dir` is the run's absolute scratch directory; results go to
+    /// its `tool-results/` subdirectory and are referenced as `$TMPDIR/...`.
+    /// `counter` numbers the files and is shared by all wrapped tools.
+    pub fn new(
+        inner: Box<dyn Tool>,
+        scratch_dir: PathBuf,
+        threshold_bytes: usize,
+        counter: Arc<AtomicUsize>,
+    ) -> Self {
+        Self {
+            inner,
+            scratch_dir,
+            threshold_bytes,
+            counter,
+        }
+    }
+
+    /// Wrap every tool in `tools` except `file_read`, whose output is how the
+    /// model reads offloaded results back. A `threshold_bytes` of 0 disables
+    /// offloading and returns `tools` unchanged.
+    pub fn wrap_all(
+        tools: Vec<Box<dyn Tool>>,
+        scratch_dir: &std::path::Path,
+        threshold_bytes: usize,
+    ) -> Vec<Box<dyn Tool>> {
+        if threshold_bytes == 0 {
+            return tools;
+        }
+        let counter = Arc::new(AtomicUsize::new(0));
+        tools
+            .into_iter()
+            .map(|tool| -> Box<dyn Tool> {
+                if tool.name() == "file_read" {
+                    tool
+                } else {
+                    Box::new(Self::new(
+                        tool,
+                        scratch_dir.to_path_buf(),
+                        threshold_bytes,
+                        counter.clone(),
+                    ))
+                }
+            })
+            .collect()
+    }
+
+    async fn offload(&self, output: &str) -> std::io::Result<String> {
+        let dir = self.scratch_dir.join(RESULTS_DIR);
+        tokio::fs::create_dir_all(&dir).await?;
+        let n = self.counter.fetch_add(1, Ordering::Relaxed) + 1;
+        let file_name = format!("{}-{n}.txt", self.inner.name());
+        tokio::fs::write(dir.join(&file_name), output).await?;
+        let reference = format!("$TMPDIR/{RESULTS_DIR}/{file_name}");
+        Ok(summarize(output, &reference))
+    }
+}
+
+/// Reference and summary fed back to the model in place of `output`.
+fn summarize(output: &str, reference: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    let mut summary = format!(
+        "[Large result offloaded to {reference}: {} bytes, {} lines{}. \
+         Use file_read with start_line/end_line or offset/length to see more.]\n",
+        output.len(),
+        lines.len(),
+        json_shape(output)
+            .map(|shape| format!(", {shape}"))
+            .unwrap_or_default()
+    );
+
+    let preview = |line: &str| -> String {
+        if line.chars().count() > MAX_PREVIEW_LINE_CHARS {
+            let cut: String = line.chars().take(MAX_PREVIEW_LINE_CHARS).collect();
+            format!("{cut}…")
+        } else {
+            line.to_string()
+        }
+    };
+
+    summary.push_str("First lines:\n");
+    for line in lines.iter().take(HEAD_LINES) {
+        let _ = writeln!(summary, "{}", preview(line));
+    }
+    if lines.len() > HEAD_LINES + TAIL_LINES {
+        let _ = writeln!(
+            summary,
+            "… {} lines omitted …",
+            lines.len() - HEAD_LINES - TAIL_LINES
+        );
+    }
+    if lines.len() > HEAD_LINES {
+        summary.push_str("Last lines:\n");
+        let tail_start = lines.len().saturating_sub(TAIL_LINES).max(HEAD_LINES);
+        for line in &lines[tail_start..] {
+            let _ = writeln!(summary, "{}", preview(line));
+        }
+    }
+    summary
+}
+
+/// Top-level shape of JSON output: object keys or array length.
+fn json_shape(output: &str) -> Option<String> {
+    match serde_json::from_str::<serde_json::Value>(output).ok()? {
+        serde_json::Value::Object(map) => {
+            let keys: Vec<&str> = map.keys().map(String::as_str).take(20).collect();
+            Some(format!("JSON object with keys: {}", keys.join(", ")))
+        }
+        serde_json::Value::Array(items) => Some(format!("JSON array of {} items", items.len())),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl Tool for OffloadLargeResults {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.inner.parameters_schema()
+    }
+
+    fn is_retryable(&self, args: &serde_json::Value, error: &str) -> bool {
+        self.inner.is_retryable(args, error)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.inner.is_read_only()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let mut result = self.inner.execute(args).await?;
+        if result.success && result.output.len() > self.threshold_bytes {
+            let output = crate::agent::loop_::scrub_credentials(&result.output);
+            match self.offload(&output).await {
+                Ok(summary) => result.output = summary,
+                Err(e) => tracing::warn!(
+                    tool = self.inner.name(),
+                    "Failed to offload large tool result, keeping it inline: {e}"
+                ),
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct BigTool;
+
+    #[async_trait]
+    impl Tool for BigTool {
+        fn name(&self) -> &str {
+            "big"
+        }
+
+        fn description(&self) -> &str {
+            "Returns many lines"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({"type": "object"})
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            let lines = args["lines"].as_u64().unwrap_or(0);
+            Ok(ToolResult {
+                success: true,
+                output: (1..=lines)
+                    .map(|i| format!("row {i}"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                error: None,
+                artifacts: Vec::new(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn large_result_is_offloaded_with_reference_and_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool =
+            OffloadLargeResults::new(Box::new(BigTool), dir.path().into(), 1024, Arc::default());
+
+        let result = tool.execute(json!({"lines": 1000})).await.unwrap();
+
+        assert!(result.success);
+        assert!(result.output.contains("$TMPDIR/tool-results/big-1.txt"));
+        assert!(result.output.contains("1000 lines"));
+        assert!(result.output.contains("row 1\n"));
+        assert!(result.output.contains("row 1000"));
+        assert!(!result.output.contains("row 500\n"));
+        assert!(result.output.len() < 2048);
+
+        let saved = std::fs::read_to_string(dir.path().join("tool-results/big-1.txt")).unwrap();
+        assert_eq!(saved.lines().count(), 1000);
+        assert!(saved.contains("row 500\n"));
+    }
+
+    #[tokio::test]
+    async fn small_result_stays_inline() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool =
+            OffloadLargeResults::new(Box::new(BigTool), dir.path().into(), 1024, Arc::default());
+
+        let result = tool.execute(json!({"lines": 3})).await.unwrap();
+
+        assert_eq!(result.output, "row 1\nrow 2\nrow 3");
+        assert!(!dir.path().join("tool-results").exists());
+    }
+
+    #[test]
+    fn summary_describes_json_shape() {
+        let summary = summarize(r#"{"items": [1, 2], "total": 2}"#, "$TMPDIR/x.txt");
+        assert!(summary.contains("JSON object with keys: items, total"));
+        assert!(summarize("[1, 2, 3]", "$TMPDIR/x.txt").contains("JSON array of 3 items"));
+    }
+
+    #[test]
+    fn zero_threshold_disables_wrapping() {
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(BigTool)];
+        let wrapped = OffloadLargeResults::wrap_all(tools, std::path::Path::new("/tmp"), 0);
+        assert_eq!(wrapped.len(), 1);
+        assert_eq!(wrapped[0].name(), "big");
+    }
+}
@@ -2,6 +2,7 @@ use super::traits::{Tool, ToolResult};
 use crate::config::{
     runtime_proxy_config, set_runtime_proxy_config, Config, ProxyConfig, ProxyScope,
 };
+use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::{json, Value};
@@ -37,26 +38,6 @@ impl ProxyConfigTool {
         Ok(parsed)
     }
 
-    fn require_write_access(&self) -> Option<ToolResult> {
-        if !self.security.can_act() {
-            return Some(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
-            });
-        }
-
-        if !self.security.record_action() {
-            return Some(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
-            });
-        }
-
-        None
-    }
-
     fn parse_scope(raw: &str) -> Option<ProxyScope> {
         match raw.trim().to_ascii_lowercase().as_str() {
             "environment" | "env" => Some(ProxyScope::Environment),
@@ -145,6 +126,7 @@ impl ProxyConfigTool {
                 "environment": Self::env_snapshot(),
             }))?,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -161,6 +143,7 @@ impl ProxyConfigTool {
                 }
             }))?,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -234,6 +217,7 @@ impl ProxyConfigTool {
                 "environment": Self::env_snapshot(),
             }))?,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -261,6 +245,7 @@ impl ProxyConfigTool {
                 "environment": Self::env_snapshot(),
             }))?,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -291,6 +276,7 @@ impl ProxyConfigTool {
                 "environment": Self::env_snapshot(),
             }))?,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -303,6 +289,7 @@ impl ProxyConfigTool {
                 "environment": Self::env_snapshot(),
             }))?,
             error: None,
+            artifacts: Vec::new(),
         })
     }
 }
@@ -379,9 +366,8 @@ impl Tool for ProxyConfigTool {
             "get" => self.handle_get(),
             "list_services" => self.handle_list_services(),
             "set" | "disable" | "apply_env" | "clear_env" => {
-                if let Some(blocked) = self.require_write_access() {
-                    return Ok(blocked);
-                }
+                self.security
+                    .enforce_tool_operation(ToolOperation::Act, "proxy_config")?;
 
                 match action.as_str() {
                     "set" => self.handle_set(&args),
@@ -402,6 +388,7 @@ impl Tool for ProxyConfigTool {
                 success: false,
                 output: String::new(),
                 error: Some(error.to_string()),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use crate::security::{PolicyViolation, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::path::PathBuf;
@@ -113,11 +113,7 @@ impl Tool for PushoverTool {
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         if !self.security.can_act() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
-            });
+            return Err(PolicyViolation::ReadOnly.into());
         }
 
         if !self.security.record_action() {
@@ -125,6 +121,7 @@ impl Tool for PushoverTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -147,6 +144,7 @@ impl Tool for PushoverTool {
                     error: Some(format!(
                         "Invalid 'priority': {value}. Expected integer in range -2..=2"
                     )),
+                    artifacts: Vec::new(),
                 })
             }
             None => None,
@@ -188,6 +186,7 @@ impl Tool for PushoverTool {
                 success: false,
                 output: body,
                 error: Some(format!("Pushover API returned status {}", status)),
+                artifacts: Vec::new(),
             });
         }
 
@@ -203,12 +202,14 @@ impl Tool for PushoverTool {
                     body
                 ),
                 error: None,
+                artifacts: Vec::new(),
             })
         } else {
             Ok(ToolResult {
                 success: false,
                 output: body,
                 error: Some("Pushover API returned an application-level error".into()),
+                artifacts: Vec::new(),
             })
         }
     }
@@ -401,9 +402,11 @@ mod tests {
             PathBuf::from("/tmp"),
         );
 
-        let result = tool.execute(json!({"message": "hello"})).await.unwrap();
-        assert!(!result.success);
-        assert!(result.error.unwrap().contains("read-only"));
+        let err = tool.execute(json!({"message": "hello"})).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
     }
 
     #[tokio::test]
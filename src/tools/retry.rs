@@ -0,0 +1,93 @@
+//! Repeating tool calls that failed for a transient reason.
+//!
+//! Both the agent loop and [`Agent`](crate::agent::Agent) run tools through
+//! [`execute_with_retry`], so `[agent] tool_max_retries` and
+//! `tool_retry_backoff_ms` apply the same way everywhere. Only failures the
+//! tool itself classifies with [`Tool::is_retryable`] are repeated.
+
+use super::traits::{Tool, ToolResult};
+use crate::config::AgentConfig;
+use std::time::Duration;
+
+/// Upper bound for a single tool retry delay.
+const MAX_TOOL_RETRY_BACKOFF_MS: u64 = 10_000;
+
+/// How often a transient tool failure is repeated, and how long to wait in
+/// between. The default never retries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ToolRetryPolicy {
+    pub max_retries: u32,
+    pub backoff_ms: u64,
+}
+
+impl ToolRetryPolicy {
+    pub fn from_config(agent: &AgentConfig) -> Self {
+        Self {
+            max_retries: agent.tool_max_retries,
+            backoff_ms: agent.tool_retry_backoff_ms,
+        }
+    }
+
+    /// Backoff before retry number `attempt + 1`: `base`, `2×base`, `4×base`, ...
+    fn delay(self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.min(16);
+        Duration::from_millis(
+            self.backoff_ms
+                .saturating_mul(factor)
+                .min(MAX_TOOL_RETRY_BACKOFF_MS),
+        )
+    }
+}
+
+/// Run `tool`, repeating failures the tool classifies as transient with
+/// exponential backoff. Non-retryable failures return after one attempt.
+pub async fn execute_with_retry(
+    tool: &dyn Tool,
+    args: &serde_json::Value,
+    policy: ToolRetryPolicy,
+) -> anyhow::Result<ToolResult> {
+    let mut attempt: u32 = 0;
+    loop {
+        let outcome = tool.execute(args.clone()).await;
+        let error = match &outcome {
+            Ok(r) if r.success => None,
+            Ok(r) => Some(r.error.clone().unwrap_or_else(|| r.output.clone())),
+            Err(e) => Some(e.to_string()),
+        };
+        let Some(error) = error else {
+            return outcome;
+        };
+        if attempt >= policy.max_retries || !tool.is_retryable(args, &error) {
+            return outcome;
+        }
+
+        let delay = policy.delay(attempt);
+        attempt += 1;
+        tracing::warn!(
+            tool = tool.name(),
+            attempt,
+            delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+            "Transient tool failure, retrying: {error}"
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_and_caps() {
+        let policy = ToolRetryPolicy {
+            max_retries: 3,
+            backoff_ms: 500,
+        };
+        assert_eq!(policy.delay(0), Duration::from_millis(500));
+        assert_eq!(policy.delay(2), Duration::from_millis(2000));
+        assert_eq!(
+            policy.delay(10),
+            Duration::from_millis(MAX_TOOL_RETRY_BACKOFF_MS)
+        );
+    }
+}
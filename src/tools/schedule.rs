@@ -1,6 +1,7 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
 use crate::cron;
+use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -80,15 +81,13 @@ impl Tool for ScheduleTool {
                 self.handle_get(id)
             }
             "create" | "add" | "once" => {
-                if let Some(blocked) = self.enforce_mutation_allowed(action) {
-                    return Ok(blocked);
-                }
+                self.security
+                    .enforce_tool_operation(ToolOperation::Act, action)?;
                 self.handle_create_like(action, &args)
             }
             "cancel" | "remove" => {
-                if let Some(blocked) = self.enforce_mutation_allowed(action) {
-                    return Ok(blocked);
-                }
+                self.security
+                    .enforce_tool_operation(ToolOperation::Act, action)?;
                 let id = args
                     .get("id")
                     .and_then(|value| value.as_str())
@@ -96,9 +95,8 @@ impl Tool for ScheduleTool {
                 Ok(self.handle_cancel(id))
             }
             "pause" => {
-                if let Some(blocked) = self.enforce_mutation_allowed(action) {
-                    return Ok(blocked);
-                }
+                self.security
+                    .enforce_tool_operation(ToolOperation::Act, action)?;
                 let id = args
                     .get("id")
                     .and_then(|value| value.as_str())
@@ -106,9 +104,8 @@ impl Tool for ScheduleTool {
                 Ok(self.handle_pause_resume(id, true))
             }
             "resume" => {
-                if let Some(blocked) = self.enforce_mutation_allowed(action) {
-                    return Ok(blocked);
-                }
+                self.security
+                    .enforce_tool_operation(ToolOperation::Act, action)?;
                 let id = args
                     .get("id")
                     .and_then(|value| value.as_str())
@@ -121,34 +118,13 @@ impl Tool for ScheduleTool {
                 error: Some(format!(
                     "Unknown action '{other}'. Use create/add/once/list/get/cancel/remove/pause/resume."
                 )),
+                artifacts: Vec::new(),
             }),
         }
     }
 }
 
 impl ScheduleTool {
-    fn enforce_mutation_allowed(&self, action: &str) -> Option<ToolResult> {
-        if !self.security.can_act() {
-            return Some(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!(
-                    "Security policy: read-only mode, cannot perform '{action}'"
-                )),
-            });
-        }
-
-        if !self.security.record_action() {
-            return Some(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".to_string()),
-            });
-        }
-
-        None
-    }
-
     fn handle_list(&self) -> Result<ToolResult> {
         let jobs = cron::list_jobs(&self.config)?;
         if jobs.is_empty() {
@@ -156,6 +132,7 @@ impl ScheduleTool {
                 success: true,
                 output: "No scheduled jobs.".to_string(),
                 error: None,
+                artifacts: Vec::new(),
             });
         }
 
@@ -189,6 +166,7 @@ impl ScheduleTool {
             success: true,
             output: format!("Scheduled jobs ({}):\n{}", lines.len(), lines.join("\n")),
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -209,12 +187,14 @@ impl ScheduleTool {
                     success: true,
                     output: serde_json::to_string_pretty(&detail)?,
                     error: None,
+                    artifacts: Vec::new(),
                 })
             }
             Err(_) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Job '{id}' not found")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -237,6 +217,7 @@ impl ScheduleTool {
                         success: false,
                         output: String::new(),
                         error: Some("'add' requires 'expression' and forbids delay/run_at".into()),
+                        artifacts: Vec::new(),
                     });
                 }
             }
@@ -246,6 +227,7 @@ impl ScheduleTool {
                         success: false,
                         output: String::new(),
                         error: Some("'once' requires exactly one of 'delay' or 'run_at'".into()),
+                        artifacts: Vec::new(),
                     });
                 }
                 if delay.is_some() && run_at.is_some() {
@@ -253,6 +235,7 @@ impl ScheduleTool {
                         success: false,
                         output: String::new(),
                         error: Some("'once' supports either delay or run_at, not both".into()),
+                        artifacts: Vec::new(),
                     });
                 }
             }
@@ -269,6 +252,7 @@ impl ScheduleTool {
                             "Exactly one of 'expression', 'delay', or 'run_at' must be provided"
                                 .into(),
                         ),
+                        artifacts: Vec::new(),
                     });
                 }
             }
@@ -286,6 +270,7 @@ impl ScheduleTool {
                     job.command
                 ),
                 error: None,
+                artifacts: Vec::new(),
             });
         }
 
@@ -300,6 +285,7 @@ impl ScheduleTool {
                     job.command
                 ),
                 error: None,
+                artifacts: Vec::new(),
             });
         }
 
@@ -318,6 +304,7 @@ impl ScheduleTool {
                 job.command
             ),
             error: None,
+            artifacts: Vec::new(),
         })
     }
 
@@ -327,11 +314,13 @@ impl ScheduleTool {
                 success: true,
                 output: format!("Cancelled job {id}"),
                 error: None,
+                artifacts: Vec::new(),
             },
             Err(error) => ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(error.to_string()),
+                artifacts: Vec::new(),
             },
         }
     }
@@ -352,11 +341,13 @@ impl ScheduleTool {
                     format!("Resumed job {id}")
                 },
                 error: None,
+                artifacts: Vec::new(),
             },
             Err(error) => ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(error.to_string()),
+                artifacts: Vec::new(),
             },
         }
     }
@@ -365,7 +356,7 @@ impl ScheduleTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::security::AutonomyLevel;
+    use crate::security::{AutonomyLevel, PolicyViolation};
     use tempfile::TempDir;
 
     fn test_setup() -> (TempDir, Config, Arc<SecurityPolicy>) {
@@ -497,16 +488,18 @@ mod tests {
 
         let tool = ScheduleTool::new(security, config);
 
-        let blocked = tool
+        let err = tool
             .execute(json!({
                 "action": "create",
                 "expression": "* * * * *",
                 "command": "echo blocked"
             }))
             .await
-            .unwrap();
-        assert!(!blocked.success);
-        assert!(blocked.error.as_deref().unwrap().contains("read-only"));
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ReadOnly)
+        );
 
         let list = tool.execute(json!({"action": "list"})).await.unwrap();
         assert!(list.success);
@@ -0,0 +1,126 @@
+//! Per-run scratch directory for temporary tool files.
+//!
+//! Each agent run gets its own directory under `<workspace>/.zeroclaw-tmp/`.
+//! The shell tool points `TMPDIR` at it, and the file tools expand a leading
+//! `$TMPDIR` in paths to it, so scratch files stay inside the sandbox without
+//! littering the workspace. The directory is removed when the run ends unless
+//! it was created with `keep` (`zeroclaw agent --keep-temp`).
+
+use std::path::{Path, PathBuf};
+
+/// Workspace-relative parent of all run scratch directories.
+pub const SCRATCH_ROOT: &str = ".zeroclaw-tmp";
+
+/// Placeholder the file tools replace with the run's scratch directory.
+const SCRATCH_PLACEHOLDER: &str = "$TMPDIR";
+
+pub struct ScratchDir {
+    workspace_dir: PathBuf,
+    relative: PathBuf,
+    keep: bool,
+}
+
+impl ScratchDir {
+    /// Create a fresh scratch directory for one run.
+    pub fn create(workspace_dir: &Path, keep: bool) -> std::io::Result<Self> {
+        let relative =
+            Path::new(SCRATCH_ROOT).join(format!("run-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(workspace_dir.join(&relative))?;
+        Ok(Self {
+            workspace_dir: workspace_dir.to_path_buf(),
+            relative,
+            keep,
+        })
+    }
+
+    /// Absolute path of the directory.
+    pub fn path(&self) -> PathBuf {
+        self.workspace_dir.join(&self.relative)
+    }
+
+    /// Path relative to the workspace, as the file tools expect.
+    pub fn relative_path(&self) -> &Path {
+        &self.relative
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        if self.keep {
+            tracing::info!(path = %self.path().display(), "Keeping run scratch directory");
+            return;
+        }
+        if let Err(e) = std::fs::remove_dir_all(self.path()) {
+            tracing::warn!("Failed to remove scratch directory: {e}");
+        }
+        // Only succeeds once no other run is using the root.
+        let _ = std::fs::remove_dir(self.workspace_dir.join(SCRATCH_ROOT));
+    }
+}
+
+/// Replace a leading `$TMPDIR` in a tool path with the workspace-relative
+/// scratch directory. Other paths are returned unchanged.
+pub fn expand_scratch_path(path: &str, scratch: Option<&Path>) -> String {
+    let Some(scratch) = scratch else {
+        return path.to_string();
+    };
+    match path.strip_prefix(SCRATCH_PLACEHOLDER) {
+        Some("") => scratch.to_string_lossy().into_owned(),
+        Some(rest) if rest.starts_with('/') => {
+            scratch.join(&rest[1..]).to_string_lossy().into_owned()
+        }
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scratch_dir_is_created_and_removed_on_drop() {
+        let workspace = tempfile::tempdir().unwrap();
+        let scratch = ScratchDir::create(workspace.path(), false).unwrap();
+        let path = scratch.path();
+        assert!(path.is_dir());
+        assert!(path.starts_with(workspace.path().join(SCRATCH_ROOT)));
+        std::fs::write(path.join("notes.txt"), "scratch").unwrap();
+
+        drop(scratch);
+        assert!(!path.exists());
+        assert!(!workspace.path().join(SCRATCH_ROOT).exists());
+    }
+
+    #[test]
+    fn keep_preserves_scratch_dir() {
+        let workspace = tempfile::tempdir().unwrap();
+        let scratch = ScratchDir::create(workspace.path(), true).unwrap();
+        let path = scratch.path();
+        std::fs::write(path.join("notes.txt"), "scratch").unwrap();
+
+        drop(scratch);
+        assert!(path.join("notes.txt").is_file());
+    }
+
+    #[test]
+    fn expands_only_leading_tmpdir_placeholder() {
+        let scratch = Path::new(".zeroclaw-tmp/run-1");
+        assert_eq!(
+            expand_scratch_path("$TMPDIR/out.json", Some(scratch)),
+            ".zeroclaw-tmp/run-1/out.json"
+        );
+        assert_eq!(
+            expand_scratch_path("$TMPDIR", Some(scratch)),
+            ".zeroclaw-tmp/run-1"
+        );
+        assert_eq!(
+            expand_scratch_path("src/$TMPDIR/x", Some(scratch)),
+            "src/$TMPDIR/x"
+        );
+        assert_eq!(expand_scratch_path("$TMPDIRX", Some(scratch)), "$TMPDIRX");
+        assert_eq!(
+            expand_scratch_path("$TMPDIR/out.json", None),
+            "$TMPDIR/out.json"
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use crate::security::{PolicyViolation, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::fmt::Write;
@@ -77,6 +77,7 @@ impl ScreenshotTool {
                 success: false,
                 output: String::new(),
                 error: Some("Filename contains characters unsafe for shell execution".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -88,6 +89,7 @@ impl ScreenshotTool {
                 success: false,
                 output: String::new(),
                 error: Some("Screenshot not supported on this platform".into()),
+                artifacts: Vec::new(),
             });
         };
 
@@ -123,12 +125,14 @@ impl ScreenshotTool {
                                 "No screenshot tool found. Install gnome-screenshot, scrot, or ImageMagick."
                                     .into(),
                             ),
+                            artifacts: Vec::new(),
                         });
                     }
                     return Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Screenshot command failed: {stderr}")),
+                        artifacts: Vec::new(),
                     });
                 }
 
@@ -138,6 +142,7 @@ impl ScreenshotTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to execute screenshot command: {e}")),
+                artifacts: Vec::new(),
             }),
             Err(_) => Ok(ToolResult {
                 success: false,
@@ -145,6 +150,7 @@ impl ScreenshotTool {
                 error: Some(format!(
                     "Screenshot timed out after {SCREENSHOT_TIMEOUT_SECS}s"
                 )),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -163,6 +169,7 @@ impl ScreenshotTool {
                         meta.len(),
                     ),
                     error: None,
+                    artifacts: Vec::new(),
                 });
             }
         }
@@ -200,12 +207,14 @@ impl ScreenshotTool {
                     success: true,
                     output: output_msg,
                     error: None,
+                    artifacts: Vec::new(),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: format!("Screenshot saved to: {}", output_path.display()),
                 error: Some(format!("Failed to read screenshot file: {e}")),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -218,7 +227,7 @@ impl Tool for ScreenshotTool {
     }
 
     fn description(&self) -> &str {
-        "Capture a screenshot of the current screen. Returns the file path and base64-encoded PNG data."
+        "Capture a screenshot of the current screen. Returns the file path and base64-encoded PNG data. Use when: visual verification, UI inspection, debugging displays."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -239,11 +248,7 @@ impl Tool for ScreenshotTool {
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         if !self.security.can_act() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
-            });
+            return Err(PolicyViolation::ReadOnly.into());
         }
         self.capture(args).await
     }
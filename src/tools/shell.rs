@@ -1,10 +1,14 @@
 use super::traits::{Tool, ToolResult};
+use crate::agent::shutdown;
 use crate::runtime::RuntimeAdapter;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
+use std::path::PathBuf;
+use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 
 /// Maximum shell command execution time before kill.
 const SHELL_TIMEOUT_SECS: u64 = 60;
@@ -12,22 +16,126 @@ const SHELL_TIMEOUT_SECS: u64 = 60;
 const MAX_OUTPUT_BYTES: usize = 1_048_576;
 /// Environment variables safe to pass to shell commands.
 /// Only functional variables are included — never API keys or secrets.
-const SAFE_ENV_VARS: &[&str] = &[
+pub(crate) const SAFE_ENV_VARS: &[&str] = &[
     "PATH", "HOME", "TERM", "LANG", "LC_ALL", "LC_CTYPE", "USER", "SHELL", "TMPDIR",
 ];
+/// Additional variables Windows programs need to start at all (`cmd` and
+/// PowerShell fail without `SystemRoot`/`ComSpec`).
+pub(crate) const WINDOWS_ENV_VARS: &[&str] = &[
+    "SYSTEMROOT",
+    "SYSTEMDRIVE",
+    "WINDIR",
+    "COMSPEC",
+    "PATHEXT",
+    "USERPROFILE",
+    "USERNAME",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "PROGRAMDATA",
+    "PROGRAMFILES",
+    "TEMP",
+    "TMP",
+];
+
+/// Variables passed through to shell commands on this platform.
+pub(crate) fn safe_env_vars() -> impl Iterator<Item = &'static str> {
+    let windows: &[&str] = if cfg!(windows) { WINDOWS_ENV_VARS } else { &[] };
+    SAFE_ENV_VARS.iter().chain(windows).copied()
+}
+
+/// Receives each stdout/stderr line (without the trailing newline) while a
+/// command runs.
+pub type OutputSink = Arc<dyn Fn(&str) + Send + Sync>;
 
 /// Shell command execution tool with sandboxing
 pub struct ShellTool {
     security: Arc<SecurityPolicy>,
     runtime: Arc<dyn RuntimeAdapter>,
+    output_sink: Option<OutputSink>,
+    scratch_dir: Option<PathBuf>,
 }
 
 impl ShellTool {
     pub fn new(security: Arc<SecurityPolicy>, runtime: Arc<dyn RuntimeAdapter>) -> Self {
-        Self { security, runtime }
+        Self {
+            security,
+            runtime,
+            output_sink: None,
+            scratch_dir: None,
+        }
+    }
+
+    /// Stream output lines to `sink` as the command produces them. The tool
+    /// result still carries the complete output.
+    pub fn with_output_sink(mut self, sink: OutputSink) -> Self {
+        self.output_sink = Some(sink);
+        self
+    }
+
+    /// Point `TMPDIR` (and `TMP`/`TEMP`) at the run's scratch directory.
+    pub fn with_scratch_dir(mut self, scratch_dir: PathBuf) -> Self {
+        self.scratch_dir = Some(scratch_dir);
+        self
     }
 }
 
+/// Read `reader` line by line, passing each line to `sink` and collecting the
+/// raw bytes (up to just past the output cap).
+async fn read_streamed<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    sink: &OutputSink,
+) -> std::io::Result<Vec<u8>> {
+    let Some(reader) = reader else {
+        return Ok(Vec::new());
+    };
+    let mut reader = BufReader::new(reader);
+    let mut collected = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            break;
+        }
+        sink(String::from_utf8_lossy(&line).trim_end_matches(['\n', '\r']));
+        if collected.len() <= MAX_OUTPUT_BYTES {
+            collected.extend_from_slice(&line);
+        }
+    }
+    Ok(collected)
+}
+
+/// `Command::output`, with the child registered for shutdown cleanup.
+async fn output_registered(
+    mut cmd: tokio::process::Command,
+) -> std::io::Result<std::process::Output> {
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let child = cmd.spawn()?;
+    let _registration = shutdown::register_child(&child);
+    child.wait_with_output().await
+}
+
+/// Like `Command::output`, but hands every line to `sink` as it arrives.
+async fn output_streaming(
+    mut cmd: tokio::process::Command,
+    sink: &OutputSink,
+) -> std::io::Result<std::process::Output> {
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = cmd.spawn()?;
+    let _registration = shutdown::register_child(&child);
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (stdout, stderr, status) = tokio::join!(
+        read_streamed(stdout, sink),
+        read_streamed(stderr, sink),
+        child.wait()
+    );
+    Ok(std::process::Output {
+        status: status?,
+        stdout: stdout?,
+        stderr: stderr?,
+    })
+}
+
 #[async_trait]
 impl Tool for ShellTool {
     fn name(&self) -> &str {
@@ -35,7 +143,7 @@ impl Tool for ShellTool {
     }
 
     fn description(&self) -> &str {
-        "Execute a shell command in the workspace directory"
+        "Execute a shell command in the workspace directory. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or the command is destructive without approval."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -71,25 +179,19 @@ impl Tool for ShellTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                artifacts: Vec::new(),
             });
         }
 
-        match self.security.validate_command_execution(command, approved) {
-            Ok(_) => {}
-            Err(reason) => {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(reason),
-                });
-            }
-        }
+        self.security
+            .validate_command_execution(command, approved)?;
 
         if !self.security.record_action() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".into()),
+                artifacts: Vec::new(),
             });
         }
 
@@ -106,19 +208,38 @@ impl Tool for ShellTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to build runtime command: {e}")),
+                    artifacts: Vec::new(),
                 });
             }
         };
         cmd.env_clear();
-
-        for var in SAFE_ENV_VARS {
+        // Dropping the future (timeout or cancelled turn) must not leave the
+        // process running.
+        cmd.kill_on_drop(true);
+        cmd.stdin(Stdio::null());
+        // Its own process group, so shutdown can signal the command together
+        // with everything it started.
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        for var in safe_env_vars() {
             if let Ok(val) = std::env::var(var) {
                 cmd.env(var, val);
             }
         }
+        if let Some(scratch_dir) = &self.scratch_dir {
+            for var in ["TMPDIR", "TMP", "TEMP"] {
+                cmd.env(var, scratch_dir);
+            }
+        }
 
-        let result =
-            tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output()).await;
+        let result = tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), async {
+            match &self.output_sink {
+                Some(sink) => output_streaming(cmd, sink).await,
+                None => output_registered(cmd).await,
+            }
+        })
+        .await;
 
         match result {
             Ok(Ok(output)) => {
@@ -143,12 +264,14 @@ impl Tool for ShellTool {
                     } else {
                         Some(stderr)
                     },
+                    artifacts: Vec::new(),
                 })
             }
             Ok(Err(e)) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to execute command: {e}")),
+                artifacts: Vec::new(),
             }),
             Err(_) => Ok(ToolResult {
                 success: false,
@@ -156,6 +279,7 @@ impl Tool for ShellTool {
                 error: Some(format!(
                     "Command timed out after {SHELL_TIMEOUT_SECS}s and was killed"
                 )),
+                artifacts: Vec::new(),
             }),
         }
     }
@@ -165,7 +289,7 @@ impl Tool for ShellTool {
 mod tests {
     use super::*;
     use crate::runtime::{NativeRuntime, RuntimeAdapter};
-    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use crate::security::{AutonomyLevel, PolicyViolation, SecurityPolicy};
 
     fn test_security(autonomy: AutonomyLevel) -> Arc<SecurityPolicy> {
         Arc::new(SecurityPolicy {
@@ -218,18 +342,22 @@ mod tests {
     #[tokio::test]
     async fn shell_blocks_disallowed_command() {
         let tool = ShellTool::new(test_security(AutonomyLevel::Supervised), test_runtime());
-        let result = tool.execute(json!({"command": "rm -rf /"})).await.unwrap();
-        assert!(!result.success);
-        let error = result.error.as_deref().unwrap_or("");
-        assert!(error.contains("not allowed") || error.contains("high-risk"));
+        let err = tool
+            .execute(json!({"command": "rm -rf /"}))
+            .await
+            .unwrap_err();
+        let violation = err.downcast_ref::<PolicyViolation>().unwrap();
+        assert!(violation.is_denial(), "{violation}");
     }
 
     #[tokio::test]
     async fn shell_blocks_readonly() {
         let tool = ShellTool::new(test_security(AutonomyLevel::ReadOnly), test_runtime());
-        let result = tool.execute(json!({"command": "ls"})).await.unwrap();
-        assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+        let err = tool.execute(json!({"command": "ls"})).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::CommandNotAllowed(_))
+        ));
     }
 
     #[tokio::test]
@@ -257,6 +385,70 @@ mod tests {
         assert!(!result.success);
     }
 
+    #[tokio::test]
+    async fn streaming_mode_emits_lines_incrementally() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: std::env::temp_dir(),
+            allowed_commands: vec!["echo".into(), "sleep".into()],
+            ..SecurityPolicy::default()
+        });
+        let seen: Arc<std::sync::Mutex<Vec<(String, std::time::Instant)>>> = Arc::default();
+        let sink_seen = seen.clone();
+        let tool = ShellTool::new(security, test_runtime()).with_output_sink(Arc::new(
+            move |line: &str| {
+                sink_seen
+                    .lock()
+                    .unwrap()
+                    .push((line.to_string(), std::time::Instant::now()));
+            },
+        ));
+
+        let result = tool
+            .execute(json!({
+                "command": "echo one && sleep 0.4 && echo two && sleep 0.4 && echo three"
+            }))
+            .await
+            .unwrap();
+        let finished = std::time::Instant::now();
+
+        assert!(result.success);
+        assert_eq!(result.output, "one\ntwo\nthree\n");
+        let seen = seen.lock().unwrap();
+        let lines: Vec<&str> = seen.iter().map(|(line, _)| line.as_str()).collect();
+        assert_eq!(lines, vec!["one", "two", "three"]);
+        // The first line arrived well before the command finished.
+        assert!(finished.duration_since(seen[0].1) >= Duration::from_millis(600));
+        assert!(seen[1].1.duration_since(seen[0].1) >= Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn streaming_mode_captures_stderr_and_exit_status() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: std::env::temp_dir(),
+            allowed_commands: vec!["ls".into()],
+            ..SecurityPolicy::default()
+        });
+        let lines: Arc<std::sync::Mutex<Vec<String>>> = Arc::default();
+        let sink_lines = lines.clone();
+        let tool = ShellTool::new(security, test_runtime()).with_output_sink(Arc::new(
+            move |line: &str| sink_lines.lock().unwrap().push(line.to_string()),
+        ));
+
+        let result = tool
+            .execute(json!({"command": "ls zeroclaw_missing_file_for_stream_test"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        let stderr = result.error.unwrap();
+        assert!(lines
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|l| stderr.contains(l.as_str())));
+    }
+
     fn test_security_with_env_cmd() -> Arc<SecurityPolicy> {
         Arc::new(SecurityPolicy {
             autonomy: AutonomyLevel::Supervised,
@@ -308,6 +500,19 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn shell_points_tmpdir_at_scratch_dir() {
+        let tool = ShellTool::new(test_security_with_env_cmd(), test_runtime())
+            .with_scratch_dir(PathBuf::from("/tmp/zeroclaw-scratch-run"));
+
+        let result = tool
+            .execute(json!({"command": "echo $TMPDIR"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output.trim(), "/tmp/zeroclaw-scratch-run");
+    }
+
     #[tokio::test]
     async fn shell_preserves_path_and_home() {
         let tool = ShellTool::new(test_security_with_env_cmd(), test_runtime());
@@ -346,13 +551,11 @@ mod tests {
         let denied = tool
             .execute(json!({"command": "touch zeroclaw_shell_approval_test"}))
             .await
-            .unwrap();
-        assert!(!denied.success);
-        assert!(denied
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("explicit approval"));
+            .unwrap_err();
+        assert_eq!(
+            denied.downcast_ref::<PolicyViolation>(),
+            Some(&PolicyViolation::ApprovalRequired("medium"))
+        );
 
         let allowed = tool
             .execute(json!({
@@ -385,7 +588,7 @@ mod tests {
 
     #[test]
     fn shell_safe_env_vars_excludes_secrets() {
-        for var in SAFE_ENV_VARS {
+        for var in SAFE_ENV_VARS.iter().chain(WINDOWS_ENV_VARS) {
             let lower = var.to_lowercase();
             assert!(
                 !lower.contains("key") && !lower.contains("secret") && !lower.contains("token"),
@@ -0,0 +1,210 @@
+use super::file_write::FileWriteTool;
+use super::traits::{Tool, ToolResult};
+use crate::security::{PolicyViolation, SecurityPolicy};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Render a Tera template with variables, returning the text or writing it
+/// to a workspace file
+pub struct TemplateExpandTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl TemplateExpandTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+
+    fn failure(error: String) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+            artifacts: Vec::new(),
+        }
+    }
+}
+
+/// Tera's top-level message is generic ("Failed to render ..."); the line,
+/// column and offending variable are in the source chain.
+fn describe_tera_error(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        let _ = write!(message, ": {cause}");
+        source = cause.source();
+    }
+    message
+}
+
+fn render(template: &str, variables: &serde_json::Value) -> Result<String, String> {
+    let context = tera::Context::from_serialize(variables)
+        .map_err(|e| format!("Invalid 'variables': {}", describe_tera_error(&e)))?;
+    tera::Tera::one_off(template, &context, false)
+        .map_err(|e| format!("Template error: {}", describe_tera_error(&e)))
+}
+
+#[async_trait]
+impl Tool for TemplateExpandTool {
+    fn name(&self) -> &str {
+        "template_expand"
+    }
+
+    fn description(&self) -> &str {
+        "Render a Tera (Jinja2-style) template with variables; write it to a workspace file or return the text. Use when: instantiating scaffolding or boilerplate from a template. Don't use when: the content has no repeated structure."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "template": {
+                    "type": "string",
+                    "description": "Template source, e.g. \"Hello {{ name }}{% for x in items %} {{ x }}{% endfor %}\""
+                },
+                "variables": {
+                    "type": "object",
+                    "description": "Values available to the template"
+                },
+                "output_path": {
+                    "type": "string",
+                    "description": "Relative workspace path to write the result to; omit to return the rendered text"
+                }
+            },
+            "required": ["template"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let template = args
+            .get("template")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'template' parameter"))?;
+        let variables = match args.get("variables") {
+            None | Some(serde_json::Value::Null) => json!({}),
+            Some(value) if value.is_object() => value.clone(),
+            Some(_) => return Ok(Self::failure("'variables' must be an object".into())),
+        };
+        let output_path = args.get("output_path").and_then(|v| v.as_str());
+
+        // Reject a bad destination before doing any work.
+        if let Some(path) = output_path {
+            if !self.security.can_act() {
+                return Err(PolicyViolation::ReadOnly.into());
+            }
+            if !self.security.is_path_allowed(path) {
+                return Err(PolicyViolation::PathNotAllowed(path.to_string()).into());
+            }
+        }
+
+        let rendered = match render(template, &variables) {
+            Ok(rendered) => rendered,
+            Err(error) => return Ok(Self::failure(error)),
+        };
+
+        match output_path {
+            // The write goes through file_write for its symlink and rate checks.
+            Some(path) => {
+                FileWriteTool::new(self.security.clone())
+                    .execute(json!({"path": path, "content": rendered}))
+                    .await
+            }
+            None => Ok(ToolResult {
+                success: true,
+                output: rendered,
+                error: None,
+                artifacts: Vec::new(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+
+    fn test_security(workspace: std::path::PathBuf) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn renders_template_to_string() {
+        let tool = TemplateExpandTool::new(test_security(std::env::temp_dir()));
+        let result = tool
+            .execute(json!({
+                "template": "[package]\nname = \"{{ name }}\"\n{% for dep in deps %}{{ dep }} = \"*\"\n{% endfor %}",
+                "variables": {"name": "demo", "deps": ["serde", "tokio"]}
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            result.output,
+            "[package]\nname = \"demo\"\nserde = \"*\"\ntokio = \"*\"\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_rendered_template_to_output_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = TemplateExpandTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({
+                "template": "# {{ title | upper }}",
+                "variables": {"title": "readme"},
+                "output_path": "docs/README.md"
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("docs/README.md")).unwrap(),
+            "# README"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_output_path_outside_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = TemplateExpandTool::new(test_security(dir.path().to_path_buf()));
+        let err = tool
+            .execute(json!({"template": "x", "output_path": "../escape.txt"}))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PolicyViolation>(),
+            Some(PolicyViolation::PathNotAllowed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_undefined_variable_clearly() {
+        let tool = TemplateExpandTool::new(test_security(std::env::temp_dir()));
+        let result = tool
+            .execute(json!({"template": "Hello {{ missing }}", "variables": {}}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(error.starts_with("Template error:"));
+        assert!(error.contains("missing"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn reports_syntax_errors() {
+        let tool = TemplateExpandTool::new(test_security(std::env::temp_dir()));
+        let result = tool
+            .execute(json!({"template": "{% for x in %}"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("Template error:"));
+    }
+}
@@ -0,0 +1,339 @@
+use super::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+/// Called with the rendered list whenever the todo tool changes it.
+pub type TodoSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoStatus {
+    #[default]
+    Pending,
+    InProgress,
+    Done,
+}
+
+/// One step of the agent's task list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TodoItem {
+    pub text: String,
+    #[serde(default)]
+    pub status: TodoStatus,
+}
+
+/// The session's task list, shared between the `todo` tool and the session
+/// store that persists it.
+#[derive(Debug, Clone, Default)]
+pub struct TodoList(Arc<Mutex<Vec<TodoItem>>>);
+
+impl TodoList {
+    pub fn items(&self) -> Vec<TodoItem> {
+        self.0.lock().map(|items| items.clone()).unwrap_or_default()
+    }
+
+    /// Replace the list. The first pending step becomes in-progress when
+    /// nothing else is.
+    pub fn set(&self, mut items: Vec<TodoItem>) {
+        advance(&mut items);
+        if let Ok(mut current) = self.0.lock() {
+            *current = items;
+        }
+    }
+
+    /// Mark step `n` (1-based) done and start the next pending one.
+    pub fn check(&self, n: usize) -> Result<(), String> {
+        let mut items = self.0.lock().map_err(|_| "todo list unavailable")?;
+        let len = items.len();
+        let item = n
+            .checked_sub(1)
+            .and_then(|i| items.get_mut(i))
+            .ok_or_else(|| format!("No step {n}; the list has {len} step(s)"))?;
+        item.status = TodoStatus::Done;
+        advance(&mut items);
+        Ok(())
+    }
+
+    pub fn clear(&self) {
+        self.set(Vec::new());
+    }
+
+    /// Compact rendering: one line per step plus a progress count.
+    pub fn render(&self) -> String {
+        let items = self.items();
+        if items.is_empty() {
+            return "Todo list is empty.".into();
+        }
+        let mut out = String::new();
+        for (i, item) in items.iter().enumerate() {
+            let mark = match item.status {
+                TodoStatus::Pending => "[ ]",
+                TodoStatus::InProgress => "[>]",
+                TodoStatus::Done => "[x]",
+            };
+            out.push_str(&format!("{mark} {}. {}\n", i + 1, item.text));
+        }
+        let done = items
+            .iter()
+            .filter(|item| item.status == TodoStatus::Done)
+            .count();
+        out.push_str(&format!("({done}/{} done)", items.len()));
+        out
+    }
+}
+
+fn advance(items: &mut [TodoItem]) {
+    if items
+        .iter()
+        .any(|item| item.status == TodoStatus::InProgress)
+    {
+        return;
+    }
+    if let Some(next) = items
+        .iter_mut()
+        .find(|item| item.status == TodoStatus::Pending)
+    {
+        next.status = TodoStatus::InProgress;
+    }
+}
+
+/// Let the agent keep an ordered task list for multi-step work
+pub struct TodoTool {
+    list: TodoList,
+    on_change: Option<TodoSink>,
+}
+
+impl TodoTool {
+    pub fn new(list: TodoList) -> Self {
+        Self {
+            list,
+            on_change: None,
+        }
+    }
+
+    /// Show the list whenever `set` or `check` changes it.
+    pub fn with_on_change(mut self, sink: TodoSink) -> Self {
+        self.on_change = Some(sink);
+        self
+    }
+
+    fn parse_items(args: &serde_json::Value) -> Result<Vec<TodoItem>, String> {
+        let raw = args
+            .get("items")
+            .and_then(|v| v.as_array())
+            .ok_or("'set' needs an 'items' array")?;
+        raw.iter()
+            .map(|item| match item {
+                serde_json::Value::String(text) => Ok(TodoItem {
+                    text: text.clone(),
+                    status: TodoStatus::Pending,
+                }),
+                other => serde_json::from_value::<TodoItem>(other.clone())
+                    .map_err(|e| format!("Invalid todo item: {e}")),
+            })
+            .filter(|item| {
+                item.as_ref()
+                    .map_or(true, |item| !item.text.trim().is_empty())
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Tool for TodoTool {
+    fn name(&self) -> &str {
+        "todo"
+    }
+
+    fn description(&self) -> &str {
+        "Track the steps of a multi-step task. For any task with three or more steps, 'set' the list of steps before starting, 'check' each step as soon as it is finished, and 'get' to review progress. Every call returns the current list. Don't use when: the task is a single step or a quick question."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["set", "check", "get"],
+                    "description": "'set' replaces the list, 'check' marks step n done, 'get' shows the list"
+                },
+                "items": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Steps in order (for 'set')"
+                },
+                "n": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "1-based step number (for 'check')"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+
+        let changed = match action {
+            "set" => Self::parse_items(&args).map(|items| self.list.set(items)),
+            "check" => args
+                .get("n")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|n| usize::try_from(n).ok())
+                .ok_or_else(|| "'check' needs a step number 'n'".to_string())
+                .and_then(|n| self.list.check(n)),
+            "get" => {
+                return Ok(ToolResult {
+                    success: true,
+                    output: self.list.render(),
+                    error: None,
+                    artifacts: Vec::new(),
+                })
+            }
+            other => Err(format!("Unknown action '{other}'; use set, check or get")),
+        };
+
+        if let Err(error) = changed {
+            return Ok(ToolResult {
+                success: false,
+                output: self.list.render(),
+                error: Some(error),
+                artifacts: Vec::new(),
+            });
+        }
+        let rendered = self.list.render();
+        if let Some(sink) = &self.on_change {
+            sink(&rendered);
+        }
+        Ok(ToolResult {
+            success: true,
+            output: rendered,
+            error: None,
+            artifacts: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statuses(list: &TodoList) -> Vec<TodoStatus> {
+        list.items().iter().map(|item| item.status).collect()
+    }
+
+    #[tokio::test]
+    async fn set_starts_the_first_step() {
+        let list = TodoList::default();
+        let tool = TodoTool::new(list.clone());
+        let result = tool
+            .execute(json!({"action": "set", "items": ["read", "edit", "test"]}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(
+            statuses(&list),
+            [
+                TodoStatus::InProgress,
+                TodoStatus::Pending,
+                TodoStatus::Pending
+            ]
+        );
+        assert_eq!(
+            result.output,
+            "[>] 1. read\n[ ] 2. edit\n[ ] 3. test\n(0/3 done)"
+        );
+    }
+
+    #[tokio::test]
+    async fn check_marks_done_and_advances() {
+        let list = TodoList::default();
+        let tool = TodoTool::new(list.clone());
+        tool.execute(json!({"action": "set", "items": ["a", "b", "c"]}))
+            .await
+            .unwrap();
+
+        tool.execute(json!({"action": "check", "n": 1}))
+            .await
+            .unwrap();
+        assert_eq!(
+            statuses(&list),
+            [
+                TodoStatus::Done,
+                TodoStatus::InProgress,
+                TodoStatus::Pending
+            ]
+        );
+
+        // Checking ahead leaves the step in progress where it is.
+        tool.execute(json!({"action": "check", "n": 3}))
+            .await
+            .unwrap();
+        assert_eq!(
+            statuses(&list),
+            [TodoStatus::Done, TodoStatus::InProgress, TodoStatus::Done]
+        );
+
+        let result = tool
+            .execute(json!({"action": "check", "n": 2}))
+            .await
+            .unwrap();
+        assert!(result.output.ends_with("(3/3 done)"));
+    }
+
+    #[tokio::test]
+    async fn set_keeps_explicit_statuses() {
+        let list = TodoList::default();
+        let tool = TodoTool::new(list.clone());
+        tool.execute(json!({"action": "set", "items": [
+            {"text": "plan", "status": "done"},
+            "build",
+        ]}))
+        .await
+        .unwrap();
+        assert_eq!(statuses(&list), [TodoStatus::Done, TodoStatus::InProgress]);
+    }
+
+    #[tokio::test]
+    async fn invalid_step_is_an_error() {
+        let tool = TodoTool::new(TodoList::default());
+        tool.execute(json!({"action": "set", "items": ["only"]}))
+            .await
+            .unwrap();
+        let result = tool
+            .execute(json!({"action": "check", "n": 2}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("No step 2"));
+        let result = tool
+            .execute(json!({"action": "check", "n": 0}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn changes_are_reported_but_reads_are_not() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink_seen = seen.clone();
+        let tool = TodoTool::new(TodoList::default()).with_on_change(Arc::new(move |list| {
+            sink_seen.lock().unwrap().push(list.to_string());
+        }));
+        tool.execute(json!({"action": "set", "items": ["a"]}))
+            .await
+            .unwrap();
+        tool.execute(json!({"action": "get"})).await.unwrap();
+        tool.execute(json!({"action": "check", "n": 1}))
+            .await
+            .unwrap();
+        assert_eq!(seen.lock().unwrap().len(), 2);
+    }
+}
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Result of a tool execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +8,32 @@ pub struct ToolResult {
     pub success: bool,
     pub output: String,
     pub error: Option<String>,
+    /// Binary outputs (screenshots, generated images) that can't live in `output`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<Artifact>,
+}
+
+/// Binary payload produced by a tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub mime_type: String,
+    pub data: ArtifactData,
+}
+
+/// Where an artifact's bytes live
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactData {
+    /// Inline base64-encoded bytes
+    Base64(String),
+    /// File already written to disk
+    Path(PathBuf),
+}
+
+impl Artifact {
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/")
+    }
 }
 
 /// Description of a tool for the LLM
@@ -32,6 +59,21 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with given arguments
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
+    /// Whether a failure is transient and the same call may succeed if repeated
+    /// (network errors, timeouts, 429/5xx). `args` are the arguments of the
+    /// failed call and `error` is the execution error or the failed result's
+    /// error text. Defaults to `false`: only calls without side effects on
+    /// failure should opt in.
+    fn is_retryable(&self, _args: &serde_json::Value, _error: &str) -> bool {
+        false
+    }
+
+    /// Whether the tool never changes files in the workspace, so calling it
+    /// does not need a git checkpoint first. Defaults to `false`.
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
     /// Get the full spec for LLM registration
     fn spec(&self) -> ToolSpec {
         ToolSpec {
@@ -76,6 +118,7 @@ mod tests {
                     .unwrap_or_default()
                     .to_string(),
                 error: None,
+                artifacts: Vec::new(),
             })
         }
     }
@@ -110,6 +153,7 @@ mod tests {
             success: false,
             output: String::new(),
             error: Some("boom".into()),
+            artifacts: Vec::new(),
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -204,6 +204,10 @@ impl Tool for WebSearchTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let query = args
             .get("query")
@@ -226,8 +230,17 @@ impl Tool for WebSearchTool {
             success: true,
             output: result,
             error: None,
+            artifacts: Vec::new(),
         })
     }
+
+    fn is_retryable(&self, _args: &serde_json::Value, error: &str) -> bool {
+        // Searches are read-only, so network errors and throttling are safe to repeat.
+        error.contains("error sending request")
+            || error.contains("operation timed out")
+            || error.contains("failed with status: 429")
+            || error.contains("failed with status: 5")
+    }
 }
 
 #[cfg(test)]
@@ -2,6 +2,14 @@
 //!
 //! This module contains reusable helper functions used across the codebase.
 
+use chrono::{DateTime, FixedOffset, Local, Utc};
+
+/// Rough token count for `chars` characters of text (~4 characters per token),
+/// for when a provider does not report usage.
+pub fn estimate_tokens(chars: usize) -> u64 {
+    u64::try_from(chars.div_ceil(4)).unwrap_or(u64::MAX)
+}
+
 /// Truncate a string to at most `max_chars` characters, appending "..." if truncated.
 ///
 /// This function safely handles multi-byte UTF-8 characters (emoji, CJK, accented characters)
@@ -43,6 +51,82 @@ pub fn truncate_with_ellipsis(s: &str, max_chars: usize) -> String {
     }
 }
 
+/// Timezone for user-facing dates: the current date/time in the system
+/// prompt and the date that keys daily memory logs.
+///
+/// `[agent] timezone` selects an IANA zone; unset uses the system's zone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LocalTimezone {
+    #[default]
+    System,
+    Named(chrono_tz::Tz),
+}
+
+impl LocalTimezone {
+    /// Resolve `[agent] timezone`. An unknown name is logged and the system
+    /// zone is used instead.
+    pub fn from_config(name: Option<&str>) -> Self {
+        let Some(name) = name.map(str::trim).filter(|n| !n.is_empty()) else {
+            return Self::System;
+        };
+        name.parse().map(Self::Named).unwrap_or_else(|_| {
+            tracing::warn!(
+                "Unknown timezone '{name}' in [agent] timezone; using the system timezone"
+            );
+            Self::System
+        })
+    }
+
+    /// IANA name, e.g. `Europe/Berlin`.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Named(tz) => tz.name().to_string(),
+            Self::System => iana_time_zone::get_timezone().unwrap_or_else(|_| "local".into()),
+        }
+    }
+
+    /// Wall-clock time of `now` in this zone.
+    pub fn localize(&self, now: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            Self::Named(tz) => now.with_timezone(tz).fixed_offset(),
+            Self::System => now.with_timezone(&Local).fixed_offset(),
+        }
+    }
+
+    /// Calendar date of `now` in this zone (`YYYY-MM-DD`).
+    pub fn date_key(&self, now: DateTime<Utc>) -> String {
+        self.localize(now).format("%Y-%m-%d").to_string()
+    }
+}
+
+/// Heading of the system prompt section holding the current date and time.
+const DATETIME_HEADING: &str = "## Current Date & Time";
+
+/// System prompt section with the date, time and timezone at `now`.
+pub fn datetime_section(timezone: &LocalTimezone, now: DateTime<Utc>) -> String {
+    let local = timezone.localize(now);
+    format!(
+        "{DATETIME_HEADING}\n\n{}\nTimezone: {} (UTC{})",
+        local.format("%A, %Y-%m-%d %H:%M"),
+        timezone.name(),
+        local.format("%:z")
+    )
+}
+
+/// Rewrite the date/time section of a built system prompt for `now`, so a
+/// long-running session doesn't keep answering with its start date.
+/// Prompts without the section are left alone.
+pub fn refresh_datetime_section(prompt: &mut String, timezone: &LocalTimezone, now: DateTime<Utc>) {
+    let Some(start) = prompt.find(DATETIME_HEADING) else {
+        return;
+    };
+    let body = start + DATETIME_HEADING.len();
+    let end = prompt[body..]
+        .find("\n\n## ")
+        .map_or_else(|| prompt.trim_end().len(), |offset| body + offset);
+    prompt.replace_range(start..end, &datetime_section(timezone, now));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,4 +219,77 @@ mod tests {
         // Edge case: max_chars = 0
         assert_eq!(truncate_with_ellipsis("hello", 0), "...");
     }
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn date_key_rolls_over_at_local_midnight() {
+        let berlin = LocalTimezone::from_config(Some("Europe/Berlin"));
+        // 23:30 and 00:30 in Berlin (UTC+2 in summer) are different days,
+        // even though both instants fall on the same UTC date.
+        assert_eq!(berlin.date_key(at("2026-06-14T21:30:00Z")), "2026-06-14");
+        assert_eq!(berlin.date_key(at("2026-06-14T22:30:00Z")), "2026-06-15");
+        assert_eq!(
+            LocalTimezone::Named(chrono_tz::UTC).date_key(at("2026-06-14T22:30:00Z")),
+            "2026-06-14"
+        );
+    }
+
+    #[test]
+    fn evening_session_stays_on_one_day_west_of_utc() {
+        let new_york = LocalTimezone::from_config(Some("America/New_York"));
+        // 20:00 and 23:59 local are already the next day in UTC.
+        assert_eq!(new_york.date_key(at("2026-01-10T01:00:00Z")), "2026-01-09");
+        assert_eq!(new_york.date_key(at("2026-01-10T04:59:00Z")), "2026-01-09");
+        assert_eq!(new_york.date_key(at("2026-01-10T05:00:00Z")), "2026-01-10");
+    }
+
+    #[test]
+    fn unknown_timezone_falls_back_to_system() {
+        assert_eq!(
+            LocalTimezone::from_config(Some("Mars/Olympus")),
+            LocalTimezone::System
+        );
+        assert_eq!(
+            LocalTimezone::from_config(Some("  ")),
+            LocalTimezone::System
+        );
+        assert_eq!(LocalTimezone::from_config(None), LocalTimezone::System);
+    }
+
+    #[test]
+    fn datetime_section_shows_local_date_time_and_zone() {
+        let tokyo = LocalTimezone::from_config(Some("Asia/Tokyo"));
+        let section = datetime_section(&tokyo, at("2026-03-01T14:59:00Z"));
+        assert!(section.contains("Sunday, 2026-03-01 23:59"), "{section}");
+        assert!(
+            section.contains("Timezone: Asia/Tokyo (UTC+09:00)"),
+            "{section}"
+        );
+    }
+
+    #[test]
+    fn refresh_datetime_section_replaces_only_that_section() {
+        let tokyo = LocalTimezone::from_config(Some("Asia/Tokyo"));
+        let mut prompt = format!(
+            "## Workspace\n\nDir\n\n{}\n\n## Runtime\n\nHost: h\n",
+            datetime_section(&tokyo, at("2026-03-01T14:59:00Z"))
+        );
+        refresh_datetime_section(&mut prompt, &tokyo, at("2026-03-01T15:01:00Z"));
+        assert!(prompt.contains("Monday, 2026-03-02 00:01"), "{prompt}");
+        assert!(!prompt.contains("2026-03-01"), "{prompt}");
+        assert!(prompt.starts_with("## Workspace\n\nDir\n\n## Current Date & Time"));
+        assert!(
+            prompt.ends_with("(UTC+09:00)\n\n## Runtime\n\nHost: h\n"),
+            "{prompt}"
+        );
+
+        let mut unrelated = "## Runtime\n".to_string();
+        refresh_datetime_section(&mut unrelated, &tokyo, at("2026-03-01T15:01:00Z"));
+        assert_eq!(unrelated, "## Runtime\n");
+    }
 }
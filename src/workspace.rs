@@ -0,0 +1,256 @@
+//! Named workspaces.
+//!
+//! `[workspaces.<name>]` entries in `config.toml` map a name to a directory plus
+//! optional model/autonomy overrides, and are managed with
+//! `zeroclaw workspace list/add/remove`. `--workspace <name-or-path>` (or
+//! `/workspace <name>` in the interactive agent) re-roots the config at that
+//! directory through [`Config::apply_workspace`], so memory, sessions, skills,
+//! checkpoints and the security sandbox are all scoped to the workspace.
+
+use crate::config::{Config, WorkspaceProfile};
+use crate::security::AutonomyLevel;
+use anyhow::{Context, Result};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+fn validate_name(name: &str, config: &Config) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        anyhow::bail!("Invalid workspace name '{name}': use letters, digits, '-', '_' or '.'");
+    }
+    if config.workspaces.contains_key(name) {
+        anyhow::bail!("A workspace named '{name}' already exists");
+    }
+    Ok(())
+}
+
+/// Parse an autonomy level as written in `config.toml`.
+pub fn parse_autonomy(raw: &str) -> Result<AutonomyLevel> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "readonly" | "read-only" | "read_only" => Ok(AutonomyLevel::ReadOnly),
+        "supervised" => Ok(AutonomyLevel::Supervised),
+        "full" => Ok(AutonomyLevel::Full),
+        other => {
+            anyhow::bail!("Unknown autonomy level '{other}' (use readonly, supervised or full)")
+        }
+    }
+}
+
+/// Absolute form of a user-supplied workspace path.
+fn absolute_path(raw: &Path) -> Result<PathBuf> {
+    let expanded = PathBuf::from(shellexpand::tilde(&raw.to_string_lossy()).as_ref());
+    if expanded.is_absolute() {
+        return Ok(expanded);
+    }
+    Ok(std::env::current_dir()
+        .context("Failed to resolve current directory")?
+        .join(expanded))
+}
+
+/// `zeroclaw workspace add`: register and persist a named workspace.
+pub fn add_workspace(
+    config: &Config,
+    name: &str,
+    path: &Path,
+    model: Option<String>,
+    autonomy: Option<AutonomyLevel>,
+) -> Result<WorkspaceProfile> {
+    let name = name.trim();
+    validate_name(name, config)?;
+
+    let path = absolute_path(path)?;
+    std::fs::create_dir_all(&path)
+        .with_context(|| format!("Failed to create workspace directory {}", path.display()))?;
+
+    let profile = WorkspaceProfile {
+        path,
+        model: model.filter(|m| !m.trim().is_empty()),
+        autonomy,
+    };
+    let mut updated = config.clone();
+    updated.workspaces.insert(name.to_string(), profile.clone());
+    updated.save()?;
+    Ok(profile)
+}
+
+/// `zeroclaw workspace remove`: drop a registry entry. Files are left on disk.
+pub fn remove_workspace(config: &Config, name: &str) -> Result<WorkspaceProfile> {
+    let mut updated = config.clone();
+    let profile = updated
+        .workspaces
+        .remove(name.trim())
+        .ok_or_else(|| anyhow::anyhow!("No workspace named '{}'", name.trim()))?;
+    updated.save()?;
+    Ok(profile)
+}
+
+pub fn handle_command(command: crate::WorkspaceCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::WorkspaceCommands::List => {
+            if config.workspaces.is_empty() {
+                println!("No named workspaces registered.");
+                println!("Current workspace: {}", config.workspace_dir.display());
+                println!("\nAdd one with:");
+                println!("  zeroclaw workspace add <name> <path>");
+                return Ok(());
+            }
+
+            let active = config.active_workspace_name();
+            let mut names: Vec<&String> = config.workspaces.keys().collect();
+            names.sort();
+
+            println!("🗂️  Workspaces ({}):", names.len());
+            for name in names {
+                let profile = &config.workspaces[name];
+                let marker = if active == Some(name.as_str()) {
+                    "*"
+                } else {
+                    " "
+                };
+                let mut line = format!("{marker} {name} | {}", profile.path.display());
+                if let Some(model) = &profile.model {
+                    let _ = write!(line, " | model={model}");
+                }
+                if let Some(level) = profile.autonomy {
+                    let _ = write!(line, " | autonomy={level:?}");
+                }
+                println!("{line}");
+            }
+            Ok(())
+        }
+        crate::WorkspaceCommands::Add {
+            name,
+            path,
+            model,
+            autonomy,
+        } => {
+            let autonomy = autonomy.as_deref().map(parse_autonomy).transpose()?;
+            let profile = add_workspace(config, &name, Path::new(&path), model, autonomy)?;
+            println!(
+                "✅ Added workspace '{}' at {}",
+                name.trim(),
+                profile.path.display()
+            );
+            println!("  Use it with: zeroclaw --workspace {} agent", name.trim());
+            Ok(())
+        }
+        crate::WorkspaceCommands::Remove { name } => {
+            let profile = remove_workspace(config, &name)?;
+            println!(
+                "✅ Removed workspace '{}' (files kept at {})",
+                name.trim(),
+                profile.path.display()
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryCategory};
+    use tempfile::TempDir;
+
+    fn test_config(tmp: &TempDir) -> Config {
+        Config {
+            config_path: tmp.path().join("config.toml"),
+            workspace_dir: tmp.path().join("default"),
+            ..Config::default()
+        }
+    }
+
+    fn load_saved(tmp: &TempDir) -> Config {
+        toml::from_str(&std::fs::read_to_string(tmp.path().join("config.toml")).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn add_and_remove_roundtrip_through_config_file() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        let profile = add_workspace(
+            &config,
+            "client-a",
+            &tmp.path().join("a"),
+            Some("gpt-4o".into()),
+            Some(AutonomyLevel::ReadOnly),
+        )
+        .unwrap();
+        assert!(profile.path.is_dir());
+
+        let saved = load_saved(&tmp);
+        assert_eq!(saved.workspaces["client-a"], profile);
+
+        let mut saved = saved;
+        saved.config_path = tmp.path().join("config.toml");
+        assert!(add_workspace(&saved, "client-a", &tmp.path().join("b"), None, None).is_err());
+
+        remove_workspace(&saved, "client-a").unwrap();
+        assert!(load_saved(&tmp).workspaces.is_empty());
+        assert!(profile.path.is_dir());
+        assert!(remove_workspace(&saved, "client-a").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_names_and_autonomy() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        assert!(add_workspace(&config, "has space", tmp.path(), None, None).is_err());
+        assert!(add_workspace(&config, "", tmp.path(), None, None).is_err());
+        assert_eq!(
+            parse_autonomy("Read-Only").unwrap(),
+            AutonomyLevel::ReadOnly
+        );
+        assert!(parse_autonomy("yolo").is_err());
+    }
+
+    #[tokio::test]
+    async fn workspaces_keep_isolated_memory_stores() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        for name in ["alpha", "beta"] {
+            config.workspaces.insert(
+                name.into(),
+                WorkspaceProfile {
+                    path: tmp.path().join(name),
+                    model: None,
+                    autonomy: None,
+                },
+            );
+        }
+
+        let mut alpha = config.clone();
+        alpha.apply_workspace("alpha").unwrap();
+        let mut beta = config.clone();
+        beta.apply_workspace("beta").unwrap();
+
+        let alpha_mem = memory::create_memory(&alpha.memory, &alpha.workspace_dir, None).unwrap();
+        alpha_mem
+            .store(
+                "client",
+                "Alpha prefers weekly reports",
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let beta_mem = memory::create_memory(&beta.memory, &beta.workspace_dir, None).unwrap();
+        assert!(beta_mem.get("client").await.unwrap().is_none());
+        assert!(beta_mem
+            .recall("weekly reports", 10, None)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(beta_mem.count().await.unwrap(), 0);
+
+        // Re-opening alpha still sees its own entry.
+        drop(alpha_mem);
+        let alpha_mem = memory::create_memory(&alpha.memory, &alpha.workspace_dir, None).unwrap();
+        assert!(alpha_mem.get("client").await.unwrap().is_some());
+    }
+}